@@ -1,13 +1,105 @@
-from enum import Enum
+from enum import Enum, IntEnum
+from typing import List, NamedTuple
 from .akioi_2048 import init
+from .akioi_2048 import BLOCKER
+from .akioi_2048 import BOMB
+from .akioi_2048 import InvalidBoardError
+from .akioi_2048 import InvalidDirectionError
+from .akioi_2048 import GameFinishedError
+from .akioi_2048 import CancelledError
+from .akioi_2048 import CancelToken
 from .akioi_2048 import step
+from .akioi_2048 import Rules
+from .akioi_2048 import step_detailed
+from .akioi_2048 import MoveResult
+from .akioi_2048 import Merge
+from .akioi_2048 import Spawn
+from .akioi_2048 import step_with_animation
+from .akioi_2048 import TileMove
+from .akioi_2048 import Game
+from .akioi_2048 import StateToken
+from .akioi_2048 import record
+from .akioi_2048 import Recording
+from .akioi_2048 import MoveRecord
+from .akioi_2048 import verify_chain
+from .akioi_2048 import sign_replay
+from .akioi_2048 import verify_replay
+from .akioi_2048 import TrajectoryRecorder
+from .akioi_2048 import collect_dataset
+from .akioi_2048 import ReplayBuffer
+from .akioi_2048 import report_card
+from .akioi_2048 import tournament
+from .akioi_2048 import survival_probability
+from .akioi_2048 import GameStats
+from .akioi_2048 import run_cli
+from .akioi_2048 import benchmark
+from .akioi_2048 import render
+from .akioi_2048 import render_image
+from .akioi_2048 import parse_notation
+from .akioi_2048 import to_notation
+from .akioi_2048 import BoardBuilder
+from .akioi_2048 import Replay
+from .akioi_2048 import export_gif
+from .akioi_2048 import engine_stamp
+from .akioi_2048 import check_stamp
+from .akioi_2048 import Leaderboard
+from .akioi_2048 import LeaderboardEntry
+from .akioi_2048 import Arena
+from .akioi_2048 import step_batch
+from .akioi_2048 import evaluate_batch
+from .akioi_2048 import Arena2P
+from .akioi_2048 import VersusStep
+from .akioi_2048 import encode
+from .akioi_2048 import pack
+from .akioi_2048 import unpack
+from .akioi_2048 import board_key
+from .akioi_2048 import board_from_key
+from .akioi_2048 import Env
+from .akioi_2048 import Status
+from .akioi_2048 import status
+from .akioi_2048 import is_game_over
+from .akioi_2048 import is_won
+from .akioi_2048 import validate
+from .akioi_2048 import slide_row
+from .akioi_2048 import merge_preview
+from .akioi_2048 import rules_spec
+from .akioi_2048 import board_with
+from .akioi_2048 import random_board
+from .akioi_2048 import verify_step
+from .akioi_2048 import spawn_probabilities
+from .akioi_2048 import expected_spawn_value
+from .akioi_2048 import score_upper_bound
+from .akioi_2048 import check_consistency
+from .akioi_2048 import infer_move
+from .akioi_2048 import diff_web_log
+from .akioi_2048 import Puzzle
+from .akioi_2048 import PuzzleResult
+from .akioi_2048 import Tablebase
+from .akioi_2048 import TablebaseEntry
+from .akioi_2048 import tablebase_lookup
+from .akioi_2048 import enumerate_successors
+from .akioi_2048 import count_reachable_states
+from .akioi_2048 import hint
+from .akioi_2048 import OpeningBook
+from .akioi_2048 import evaluate_moves
+from .akioi_2048 import worst_case_moves
+from .akioi_2048 import tune_heuristics
+from .akioi_2048 import NTupleNetwork
+from .akioi_2048 import train
+from .akioi_2048 import OnnxPolicy
 
 
-class Direction(Enum):
-    Up = "Up"
-    Down = "Down"
-    Left = "Left"
-    Right = "Right"
+class Direction(IntEnum):
+    """A move, valued as its action id; the upper-case names are aliases."""
+
+    Down = 0
+    Right = 1
+    Up = 2
+    Left = 3
+    DOWN = 0
+    RIGHT = 1
+    UP = 2
+    LEFT = 3
 
 
 class State(Enum):
@@ -16,4 +108,104 @@ class State(Enum):
     Continue = "Continue"
 
 
-__all__ = ["init", "step", "Direction", "State"]
+class StepResult(NamedTuple):
+    """What ``step`` and ``Game.step`` return: still a
+    ``(board, delta, state)`` tuple, with the fields named."""
+
+    board: List[List[int]]
+    delta: int
+    state: State
+
+
+__all__ = [
+    "init",
+    "BLOCKER",
+    "BOMB",
+    "InvalidBoardError",
+    "InvalidDirectionError",
+    "GameFinishedError",
+    "CancelledError",
+    "CancelToken",
+    "step",
+    "Direction",
+    "State",
+    "StepResult",
+    "Rules",
+    "step_detailed",
+    "MoveResult",
+    "Merge",
+    "Spawn",
+    "step_with_animation",
+    "TileMove",
+    "Game",
+    "StateToken",
+    "record",
+    "Recording",
+    "MoveRecord",
+    "verify_chain",
+    "sign_replay",
+    "verify_replay",
+    "TrajectoryRecorder",
+    "collect_dataset",
+    "ReplayBuffer",
+    "report_card",
+    "tournament",
+    "survival_probability",
+    "GameStats",
+    "run_cli",
+    "benchmark",
+    "render",
+    "render_image",
+    "parse_notation",
+    "to_notation",
+    "BoardBuilder",
+    "Replay",
+    "export_gif",
+    "engine_stamp",
+    "check_stamp",
+    "Leaderboard",
+    "LeaderboardEntry",
+    "Arena",
+    "step_batch",
+    "evaluate_batch",
+    "Arena2P",
+    "VersusStep",
+    "encode",
+    "pack",
+    "unpack",
+    "board_key",
+    "board_from_key",
+    "Env",
+    "Status",
+    "status",
+    "is_game_over",
+    "is_won",
+    "validate",
+    "slide_row",
+    "merge_preview",
+    "rules_spec",
+    "board_with",
+    "random_board",
+    "verify_step",
+    "spawn_probabilities",
+    "expected_spawn_value",
+    "score_upper_bound",
+    "check_consistency",
+    "infer_move",
+    "diff_web_log",
+    "Puzzle",
+    "PuzzleResult",
+    "Tablebase",
+    "TablebaseEntry",
+    "tablebase_lookup",
+    "enumerate_successors",
+    "count_reachable_states",
+    "hint",
+    "OpeningBook",
+    "evaluate_moves",
+    "worst_case_moves",
+    "tune_heuristics",
+    "NTupleNetwork",
+    "train",
+    "OnnxPolicy",
+]
@@ -1,19 +1,194 @@
-from enum import Enum
+from enum import Enum, IntEnum
+from os import PathLike
+from pathlib import Path
+from typing import Any, Callable, NamedTuple
 
-class Direction(Enum):
-    Up: "Direction"
-    Down: "Direction"
-    Left: "Direction"
-    Right: "Direction"
+BLOCKER: int
+"""The immovable blocker tile, ``-3``, valid under ``Rules(blockers=True)``:
+it never slides or merges."""
+BOMB: int
+"""The bomb tile, ``-5``, valid under ``Rules(bombs=True)``."""
+
+class Direction(IntEnum):
+    """A move, valued as its action id; the upper-case names are aliases.
+
+    Wherever a direction is taken, its action id or its name in any case
+    (``"up"``) works too.
+    """
+
+    Down = 0
+    Right = 1
+    Up = 2
+    Left = 3
+    DOWN = 0
+    RIGHT = 1
+    UP = 2
+    LEFT = 3
 
 class State(Enum):
     Victory: "State"
     GameOver: "State"
     Continue: "State"
 
+class InvalidBoardError(ValueError):
+    """A board that is not 4x4 integers, or holds a tile the rules do not allow."""
+
+class InvalidDirectionError(ValueError):
+    """A move that is neither a ``Direction`` nor an action id from 0 to 3."""
+
+class GameFinishedError(ValueError):
+    """A move in a game, episode or match that is already over."""
+
+class CancelledError(Exception):
+    """A long native call stopped by its ``CancelToken``."""
+
+class CancelToken:
+    """Stops a long native call it is passed to as ``cancel=``.
+
+    ``cancel()`` may be called from any thread, or from a progress callback;
+    the call then raises ``CancelledError``. A token stays cancelled, so it
+    stops every later call it is passed to as well.
+    """
+
+    def __init__(self) -> None: ...
+    def cancel(self) -> None: ...
+    @property
+    def cancelled(self) -> bool: ...
+
+class StepResult(NamedTuple):
+    """What ``step`` and ``Game.step`` return: still a
+    ``(board, delta, state)`` tuple, with the fields named."""
+
+    board: list[list[int]]
+    delta: int
+    state: State
+
+class Rules:
+    """Multiplier merge mechanics and optional tiles used by ``step``.
+
+    Args:
+        max_multiplier: Largest multiplier magnitude; multipliers of this size
+            no longer merge with each other (standard: 4). Must be a power of
+            two >= 2.
+        multiplier_merge: Whether equal multipliers merge (``-1 + -1 -> -2``).
+        require_full_below: Whether numeric x multiplier merges need every
+            cell beyond the pair (in the move direction) occupied.
+        mercy: Never spawn a x1 multiplier into the last empty cell (the
+            remaining spawn weights are renormalized).
+        difficulty: Spawn weights: ``"easy"`` (fewer x1, more 4 and x2),
+            ``"standard"``, ``"cruel"`` (more x1, fewer 4 and x2) or
+            ``"adaptive"`` (easy up to a 128 tile, standard at 1024, cruel
+            from 8192).
+        bombs: Allow bomb tiles (``BOMB``): a tile sliding against one clears
+            the whole row or column of the move, blockers aside, and that line
+            scores nothing.
+        blockers: Allow blocker tiles (``BLOCKER``), which never slide or
+            merge and stop tiles sliding against them like a wall.
+        variant: Tile set, merges and spawns: ``"akioi"`` (multipliers, as set
+            up by the arguments above), ``"classic"`` (plain 2048: numbers
+            only, 2 and 4 spawning) or ``"fib"`` (consecutive Fibonacci
+            numbers merge, 1 and 2 spawning, 46368 wins).
+        base: Base ``b`` of the classic variant, from 2 to 16: tiles are a
+            digit below ``b`` times a power of ``b``, two tiles of the same
+            power merge when their digits add up to at most ``b``, ``b`` and
+            ``2b`` spawn and the largest power within ``max_tile`` wins.
+        max_tile: Cap of the number tiles, a power of two from 16 to 2**29:
+            no merge makes a larger tile, and the variant's largest tile
+            within it wins.
+        overflow: What two tiles at the cap merge into: ``"block"``
+            (nothing, they stay apart), ``"win"`` (a tile twice the cap,
+            which wins instead and merges no more) or ``"wrap"`` (the
+            smallest number tile, scoring twice the cap).
+        scoring: How merges add to the score, and so every ``delta_score``:
+            ``"standard"`` (every merge scores the tile it makes, so
+            ``-1 + -1 -> -2`` scores -2), ``"web"`` (merges making a
+            multiplier score nothing, so the score never goes down) or
+            ``"clamp"`` (merges score as standard, but a move that would
+            lose score scores 0).
+        spawns: Tiles spawned after a move, from 1 to 4, each placed like a
+            single spawn on the board the previous one left.
+        spawn_every: Moves from one spawn to the next, from 1 to 16: the
+            first move of a game spawns, then every ``spawn_every``-th.
+        placement: Where spawned tiles land, the starting ones included:
+            ``"uniform"`` (any empty cell), ``"edge"`` (empty cells on the
+            border while there are any) or ``"far"`` (the empty cells
+            farthest from the largest number tile).
+
+    Raises:
+        ValueError: If ``max_multiplier``, ``difficulty``, ``variant``,
+            ``base``, ``max_tile``, ``overflow``, ``scoring``, ``spawns``,
+            ``spawn_every`` or ``placement`` is invalid.
+    """
+
+    def __init__(
+        self,
+        max_multiplier: int = 4,
+        multiplier_merge: bool = True,
+        require_full_below: bool = True,
+        mercy: bool = False,
+        difficulty: str = "standard",
+        bombs: bool = False,
+        blockers: bool = False,
+        variant: str = "akioi",
+        base: int = 2,
+        max_tile: int = 65536,
+        overflow: str = "block",
+        scoring: str = "standard",
+        spawns: int = 1,
+        spawn_every: int = 1,
+        placement: str = "uniform",
+    ) -> None: ...
+    @staticmethod
+    def preset(name: str) -> Rules:
+        """Named preset: ``"akioi"`` (standard), ``"mercy"``, a variant or a
+        difficulty.
+
+        ``"classic"``, ``"fib"``, ``"easy"``, ``"standard"``, ``"cruel"`` and
+        ``"adaptive"`` give otherwise standard rules.
+        """
+    @property
+    def tag(self) -> str:
+        """Stable identifier such as ``"akioi-v2"`` or ``"akioi-v2+mercy"``.
+
+        Scores are only comparable between games with the same tag.
+        """
+    @property
+    def max_multiplier(self) -> int: ...
+    @property
+    def multiplier_merge(self) -> bool: ...
+    @property
+    def require_full_below(self) -> bool: ...
+    @property
+    def mercy(self) -> bool: ...
+    @property
+    def difficulty(self) -> str: ...
+    @property
+    def bombs(self) -> bool: ...
+    @property
+    def blockers(self) -> bool: ...
+    @property
+    def variant(self) -> str: ...
+    @property
+    def base(self) -> int: ...
+    @property
+    def max_tile(self) -> int: ...
+    @property
+    def overflow(self) -> str: ...
+    @property
+    def scoring(self) -> str: ...
+    @property
+    def spawns(self) -> int: ...
+    @property
+    def spawn_every(self) -> int: ...
+    @property
+    def placement(self) -> str: ...
+
 def step(
-    board: list[list[int]], direction: Direction
-) -> tuple[list[list[int]], int, State]:
+    board: list[list[int]],
+    direction: Direction | int | str,
+    rules: Rules | None = None,
+    strict: bool = True,
+) -> StepResult:
     """Apply one move.
 
     If the board changes, a new tile appears in a random empty cell.
@@ -21,19 +196,2041 @@ def step(
     Args:
         board: 4x4 game board. Positive numbers are normal tiles (2, 4, 8,
             ...). Negative numbers are multipliers: -1=x1, -2=x2, -4=x4
-            (absolute value is the multiplier).
-        direction: Move direction enum: ``Direction.{Up,Down,Left,Right}``.
+            (absolute value is the multiplier), except the immovable
+            blocker -3.
+        direction: ``Direction``, its action id or its name (``"up"``).
+        rules: Optional ``Rules`` overriding the standard multiplier mechanics.
+        strict: Reject tiles that are invalid under ``rules`` (see
+            ``validate``). ``strict=False`` applies the merge rules to any
+            values, for experiments with custom tile sets.
 
     Returns:
-        ``(new_board, delta_score, state)`` where ``state`` is ``State``.
+        A ``StepResult``, the tuple ``(board, delta, state)`` with named
+        fields, where ``state`` is ``State``.
+
+    Raises:
+        InvalidBoardError: For a malformed board or, when strict, an invalid
+            tile.
+        InvalidDirectionError: If ``direction`` names no direction.
 
     Note:
         If the board does not change, no tile is spawned and ``delta_score=0``.
     """
 
-def init() -> list[list[int]]:
+class Merge:
+    """Two tiles combined into one during a move."""
+
+    @property
+    def target(self) -> tuple[int, int]:
+        """``(row, col)`` holding the merged tile after the move."""
+    @property
+    def sources(self) -> list[tuple[int, int]]:
+        """Original cells: the leading tile (nearer the wall), then the one
+        that slid into it."""
+    @property
+    def tiles(self) -> list[int]:
+        """Values of the tiles at ``sources``."""
+    @property
+    def kind(self) -> str:
+        """``"numbers"`` (two equal number tiles added up), ``"multipliers"``
+        (two equal multipliers doubled) or ``"multiply"`` (a multiplier
+        applied to a number tile)."""
+    @property
+    def value(self) -> int:
+        """Resulting tile value."""
+    @property
+    def score(self) -> int:
+        """Score contributed by this merge."""
+
+class Spawn:
+    """Tile spawned after a valid move."""
+
+    @property
+    def cell(self) -> tuple[int, int]: ...
+    @property
+    def value(self) -> int: ...
+
+class MoveResult:
+    """Everything that happened during one move."""
+
+    @property
+    def board(self) -> list[list[int]]: ...
+    @property
+    def delta(self) -> int: ...
+    @property
+    def state(self) -> State: ...
+    @property
+    def moved(self) -> bool:
+        """Whether the move changed the board (only valid moves spawn)."""
+    @property
+    def merges(self) -> list[Merge]:
+        """Merges in row-major order of their target cell."""
+    @property
+    def spawn(self) -> Spawn | None:
+        """The spawned tile, or ``None`` for an invalid move."""
+    @property
+    def spawns(self) -> list[Spawn]:
+        """Every tile spawned, ``spawn`` first: more than one under
+        ``Rules(spawns=...)``, none on a move ``Rules(spawn_every=...)``
+        skips."""
+    @property
+    def score_breakdown(self) -> dict[str, int]:
+        """``delta`` split by merge kind: ``{"numbers": ..., "multipliers":
+        ..., "multiply": ...}``."""
+
+def step_detailed(
+    board: list[list[int]], direction: Direction, rules: Rules | None = None
+) -> MoveResult:
+    """Apply one move like ``step`` and describe it in detail.
+
+    Returns:
+        A ``MoveResult`` with the new board, delta, state, whether the move
+        was valid, every merge with its score and the spawned tile.
+    """
+
+class TileMove:
+    """Path of one tile during a move, ready for slide animations."""
+
+    @property
+    def source(self) -> tuple[int, int]:
+        """``(row, col)`` the tile starts in."""
+    @property
+    def target(self) -> tuple[int, int]:
+        """``(row, col)`` the tile slides to."""
+    @property
+    def value(self) -> int:
+        """Value of the tile before the move."""
+    @property
+    def merged(self) -> bool:
+        """Whether the tile merges with another one at ``target``."""
+
+def step_with_animation(
+    board: list[list[int]], direction: Direction, rules: Rules | None = None
+) -> tuple[MoveResult, list[TileMove]]:
+    """Apply one move and return the path of every tile for animation.
+
+    Returns:
+        The ``MoveResult`` (its ``spawn`` is the tile to fade in) and one
+        ``TileMove`` per tile of the original board, in row-major order of
+        ``source``.
+    """
+
+def infer_move(
+    before: list[list[int]], after: list[list[int]], rules: Rules | None = None
+) -> tuple[Direction, Spawn] | None:
+    """Find the move that turns ``before`` into ``after``.
+
+    Useful to check game logs and imported replays.
+
+    Returns:
+        The direction and the spawned tile, or ``None`` if no single valid
+        move does it (an unchanged board included). When several directions
+        fit, the first in action-id order (Down, Right, Up, Left) is returned.
+    """
+
+def diff_web_log(log: str, rules: Rules | None = None) -> dict[str, Any] | None:
+    """Replay a game the web version logged and find the first divergence.
+
+    The log is JSON lines: ``{"state": ...}`` with the starting
+    ``gameState``, then ``{"move": d, "state": ...}`` per move, ``d`` in the
+    web version's numbers (0 up, 1 right, 2 down, 3 left) or a name, with an
+    optional ``"spawn": {"x": column, "y": row, "value": tile}``.
+
+    Returns:
+        ``None`` when every move agrees; otherwise ``move`` (from 1),
+        ``kind`` (``"slide"``, ``"spawn"``, ``"score"`` or ``"over"``),
+        ``direction``, ``before``, ``web``, ``engine`` (the engine's slide,
+        without spawns), ``web_score``, ``engine_score``, ``message`` and
+        ``case``: for a slide, ``{"line", "engine", "web"}`` with the first
+        divergent line alone, read from the wall it slides toward.
+
+    Raises:
+        ValueError: For a malformed log or an invalid starting position.
+    """
+
+class MoveRecord:
+    """One accepted move in a hash-chained game record.
+
+    Args:
+        index: Position in the chain (0-based).
+        pre_hash: SHA-256 of the board before the move (32 bytes).
+        direction: Move direction.
+        post_hash: SHA-256 of the board after the move and spawn.
+        draw_index: Index of the spawn draw used after this move (the two
+            initial tiles are draws 0 and 1).
+        chain: SHA-256 over the previous ``chain`` and this record's fields.
+    """
+
+    def __init__(
+        self,
+        index: int,
+        pre_hash: bytes,
+        direction: Direction,
+        post_hash: bytes,
+        draw_index: int,
+        chain: bytes,
+    ) -> None: ...
+    @property
+    def index(self) -> int: ...
+    @property
+    def pre_hash(self) -> bytes: ...
+    @property
+    def direction(self) -> Direction: ...
+    @property
+    def post_hash(self) -> bytes: ...
+    @property
+    def draw_index(self) -> int: ...
+    @property
+    def chain(self) -> bytes: ...
+
+class Status:
+    """A ``State`` with the details behind it."""
+
+    @property
+    def state(self) -> State: ...
+    @property
+    def reason(self) -> str | None:
+        """``"victory_tile"`` (whether or not moves remain), ``"no_legal_moves"``,
+        ``"out_of_time"`` (a ``Game`` forfeited on time) or ``None`` while the
+        game continues."""
+    @property
+    def can_continue(self) -> bool:
+        """Whether some move still changes the board, e.g. after a victory."""
+
+def status(board: list[list[int]], rules: Rules | None = None) -> Status:
+    """Status of a board: its ``State``, why the game ended and whether any
+    move remains.
+
+    Raises:
+        ValueError: If the board holds invalid tiles.
+    """
+
+def is_game_over(board: list[list[int]], rules: Rules | None = None) -> bool:
+    """Whether no move in any of the four directions changes ``board``.
+
+    Evaluated on the board itself, so it also works for boards that did not
+    come from ``step``. A won board with moves left is not over.
+    """
+
+def is_won(board: list[list[int]], rules: Rules | None = None) -> bool:
+    """Whether ``board`` holds the winning tile of ``rules``: 65536 under the
+    standard ones."""
+
+def validate(
+    board: list[list[int]], strict: bool = True, rules: Rules | None = None
+) -> None:
+    """Check that ``board`` is a board the engine can play.
+
+    The 4x4 integer shape is checked in every mode; ``strict`` also requires
+    every tile to be 0, a power of two from 2 to 65536, a multiplier
+    allowed by ``rules``, or a blocker or bomb the rules allow.
+
+    Raises:
+        InvalidBoardError: Naming the first invalid tile, or for a malformed
+            board.
+    """
+
+def slide_row(row: list[int], rules: Rules | str | None = None) -> tuple[list[int], int]:
+    """Slide one row toward its first cell, exactly as a ``Left`` move does.
+
+    Reverse the row (and the result) for ``Right``; a column read from the
+    top slides the same way under ``Up``.
+
+    Returns:
+        The row after the slide, before any spawn, and the score it merges.
+
+    Raises:
+        InvalidBoardError: For a tile that is invalid under ``rules``.
+    """
+
+def merge_preview(
+    a: int,
+    b: int,
+    adjacent: bool = True,
+    below: list[int] = [],
+    rules: Rules | str | None = None,
+) -> tuple[int, int] | None:
+    """What two tiles meeting in a slide merge into, if anything.
+
+    ``a`` is the tile nearer the wall and ``b`` the next tile behind it. A
+    number and a multiplier only merge when ``adjacent`` (they touched
+    before the move) and, under ``require_full_below``, when every cell of
+    ``below`` (the tiles between ``a`` and the wall before the move) is
+    taken. Blockers and bombs never merge; a bomb clears the line instead.
+
+    Returns:
+        The merged tile and the score it adds, or ``None`` when the tiles
+        stay apart.
+
+    Raises:
+        InvalidBoardError: For an empty or invalid tile, or more than two
+            tiles below.
+    """
+
+def rules_spec(
+    rules: Rules | str | None = None, invalid_moves: str = "noop"
+) -> dict[str, Any]:
+    """The active ruleset as data, derived from the engine itself.
+
+    For tooling that must stay in sync with the engine: ``version``, ``tag``
+    and every ``Rules`` field; ``tiles`` (besides ``blocker`` and ``bomb``,
+    ``None`` unless the rules allow them);
+    ``merges``, one dict per merging pair with ``a`` (nearer the wall),
+    ``b``, ``tile``, ``score``, ``needs_adjacent`` and ``needs_full_below``;
+    ``spawns`` on an empty board; ``winning_tile``; and ``invalid_moves``.
+    """
+
+def verify_step(
+    board: list[list[int]],
+    direction: Direction,
+    expected: list[list[int]],
+    rules: Rules | None = None,
+) -> bool:
+    """Check a move against the reference model of the rules.
+
+    The reference is a slow, straightforward implementation kept apart from
+    the engine, for testing the engine or another implementation.
+
+    Returns:
+        Whether ``expected`` is the reference slide plus, if the move changed
+        the board, one tile that can spawn on a cell left empty.
+    """
+
+def spawn_probabilities(
+    board: list[list[int]] | None = None, rules: Rules | None = None
+) -> dict[int, float]:
+    """The exact spawn model: probability of each tile that can spawn.
+
+    Args:
+        board: Board the tile spawns on; only the mercy rule (no ×1 into the
+            last empty cell) and the adaptive difficulty (by its largest tile)
+            depend on it. Omitted: an empty board.
+        rules: Optional ``Rules``.
+
+    Returns:
+        ``{2: ..., 4: ..., -1: ..., -2: ...}`` summing to 1, or ``{}`` for a
+        full board.
+    """
+
+def expected_spawn_value(board: list[list[int]], rules: Rules | None = None) -> float:
+    """Expected face value of the next spawn on ``board``.
+
+    Number tiles are weighted by their probability; multipliers count 0, as
+    they add nothing until they merge. ``0.0`` for a full board.
+    """
+
+def score_upper_bound(board: list[list[int]], rules: Rules | None = None) -> int:
+    """Upper bound on the score the tiles of ``board`` can still merge.
+
+    Positions are ignored, so any two tiles may meet: no sequence of moves
+    merges more before the next spawn, which makes the bound safe for
+    pruning in solvers. Blockers and bombs never merge.
+
+    Raises:
+        InvalidBoardError: For an invalid board.
+    """
+
+def check_consistency(
+    board: list[list[int]], score: int, rules: Rules | None = None
+) -> dict[str, Any]:
+    """Check whether a claimed score fits the tiles on ``board``.
+
+    Every tile carries the score of the merges that built it, so a game's
+    score lies between the least and the most its tiles can carry. A
+    problem proves the claim false; a consistent score is not proof.
+
+    Returns:
+        ``consistent``, ``score``, ``min_score`` and ``max_score`` (``None``
+        where the rules set no bound), ``step`` (every score is a multiple)
+        and ``problems``.
+
+    Raises:
+        InvalidBoardError: For an invalid board.
+    """
+
+def board_with(
+    tiles: dict[tuple[int, int], int], rules: Rules | None = None
+) -> list[list[int]]:
+    """Build a board from ``{(row, col): tile}``; other cells are empty.
+
+    Raises:
+        ValueError: For a cell outside the board or an invalid tile.
+    """
+
+def random_board(
+    max_tile: int = 1024,
+    fill: float = 0.5,
+    include_multipliers: bool = True,
+    seed: int | None = None,
+    reachable: bool = False,
+    rules: Rules | None = None,
+) -> list[list[int]]:
+    """A random, plausible mid-game board, for curriculum learning and tests.
+
+    Tiles are scattered directly: ``max_tile`` in a corner and smaller powers
+    of two (and, one time in eight, a multiplier) in other random cells, drawn
+    again until some move is legal. With ``reachable=True`` the board is the
+    first fitting position of a mostly greedy heuristic game instead, so play
+    can reach it; it may fail for tiles above 2048.
+
+    Args:
+        max_tile: Largest tile, a power of two from 2 to 65536.
+        fill: Share of the 16 cells holding a tile, in ``(0, 1]``; with
+            ``reachable`` a minimum.
+        include_multipliers: Whether multipliers may appear.
+        seed: Seed for the draw; random when omitted.
+        reachable: Generate the board by playing instead.
+        rules: Optional ``Rules``.
+
+    Raises:
+        ValueError: For a ``max_tile`` or ``fill`` out of range, or when no
+            board fits.
+    """
+
+class Game:
+    """A seeded game in progress.
+
+    Args:
+        seed: Seed for all spawns; a random one is picked when omitted.
+            Equal seeds, rules and moves always give equal games.
+        rules: Optional ``Rules``.
+        proofs: Attach a hash-chained ``MoveRecord`` to every accepted move.
+        invalid_moves: What a move that changes nothing does: ``"noop"``,
+            ``"penalize"`` (``Env`` also lowers the reward), ``"mask"``
+            (raises ``ValueError``) or ``"auto-resolve"`` (plays a random
+            legal move instead).
+        keep_playing: Play on after a victory, like the web version's "keep
+            going" button: the winning move reports ``State.Victory`` once,
+            later moves ``State.Continue`` until no move is left.
+        spawns: ``[((row, col), tile), ...]`` placed in order after the next
+            valid moves instead of random spawns, so tests can predict every
+            board; random spawns resume once the list runs out. Scripted
+            spawns are not part of ``replay()``.
+        time_limit: Seconds on the game's clock, which starts when the game
+            is created; every ``step`` spends the time since the previous
+            one. No clock when omitted.
+        increment: Seconds added to the clock after every move made in time.
+        on_timeout: What a move after the clock ran out does: ``"reject"``
+            (raises ``ValueError``) or ``"forfeit"`` (the move is not played
+            and the game ends as lost, with ``status.reason ==
+            "out_of_time"``).
+        timings: Time every move for ``timings()``.
+    """
+
+    def __init__(
+        self,
+        seed: int | None = None,
+        rules: Rules | None = None,
+        proofs: bool = False,
+        invalid_moves: str = "noop",
+        keep_playing: bool = False,
+        spawns: list[tuple[tuple[int, int], int]] | None = None,
+        time_limit: float | None = None,
+        increment: float = 0.0,
+        on_timeout: str = "reject",
+        timings: bool = False,
+    ) -> None: ...
+    @staticmethod
+    def from_board(
+        board: list[list[int]],
+        score: int = 0,
+        seed: int | None = None,
+        rules: Rules | None = None,
+        proofs: bool = False,
+        invalid_moves: str = "noop",
+        keep_playing: bool = False,
+        spawns: list[tuple[tuple[int, int], int]] | None = None,
+        time_limit: float | None = None,
+        increment: float = 0.0,
+        on_timeout: str = "reject",
+        timings: bool = False,
+    ) -> Game:
+        """Start a game from a constructed position instead of two dealt tiles.
+
+        ``seed`` drives all later spawns. The position is kept in
+        ``replay()``, so constructed games replay exactly too.
+
+        Raises:
+            ValueError: For an invalid board.
+        """
+
+    @staticmethod
+    def from_web_save(
+        json: str,
+        seed: int | None = None,
+        rules: Rules | None = None,
+        proofs: bool = False,
+        invalid_moves: str = "noop",
+    ) -> Game:
+        """Resume a game from the web version's saved ``gameState`` JSON.
+
+        The board, score and "keep going" choice come from the save.
+
+        Raises:
+            ValueError: For malformed JSON or an invalid board.
+        """
+
+    def to_web_save(self) -> str:
+        """The position as the web version's ``gameState`` JSON."""
+
+    def step(self, direction: Direction | int | str) -> StepResult:
+        """Apply one move; returns a ``StepResult`` ``(board, delta, state)``.
+
+        Raises:
+            ValueError: For a move that changes nothing under ``"mask"``, or
+                a scripted spawn that lands on an occupied cell.
+            GameFinishedError: For any move once the game is over, under
+                ``"mask"``; other policies take it as a no-op.
+            InvalidDirectionError: If ``direction`` names no direction.
+        """
+
+    def step_in_place(self, direction: Direction | int | str) -> tuple[int, State]:
+        """Apply one move like ``step`` without building the board.
+
+        Returns only ``(delta, state)``, so tight loops that read ``board``
+        now and then allocate no nested lists per move. Raises as ``step``
+        does.
+        """
+
+    def step_detailed(self, direction: Direction | int | str) -> MoveResult:
+        """Apply one move and return the full ``MoveResult``."""
+
+    def snapshot(self) -> StateToken:
+        """Capture the whole game state, RNG included, to branch from later.
+
+        Tokens are cheap: the move history and proof records are shared
+        until the game moves on.
+        """
+
+    def restore(self, token: StateToken) -> None:
+        """Return to the state captured in ``token``.
+
+        Every later move replays exactly as after the snapshot. Callbacks
+        and the move log stay as they are.
+        """
+
+    def on_move(self, callback: Callable[[Direction, MoveResult], object]) -> None:
+        """Call ``callback(direction, result)`` after every move from now on.
+
+        Moves that were not played (rejected by ``"mask"``, or after the
+        clock ran out) do not call back. An exception raised by the callback
+        propagates from ``step`` after the move was played.
+
+        Raises:
+            ValueError: If ``callback`` is not callable.
+        """
+
+    def log_moves(
+        self,
+        path: str | PathLike[str] | None,
+        append: bool = False,
+        binary: bool = False,
+    ) -> None:
+        """Write every move from now on to ``path`` as JSON Lines, natively.
+
+        Each line holds ``move`` (index from 0), ``direction``, ``delta``,
+        ``score`` (total after the move), ``moved``, ``merges`` (count),
+        ``spawn`` (``{"row", "col", "value"}`` or ``null``) and ``state``.
+        ``None`` stops logging; ``append`` adds to an existing file.
+        ``binary`` writes a binary game log of the whole game instead, which
+        ``Replay.load`` reads; appending continues a log of this game.
+        """
+
+    def records(self) -> list[MoveRecord]:
+        """Proof records of accepted moves (empty unless ``proofs=True``)."""
+
+    def replay(self) -> Replay:
+        """Seed, rules and accepted moves, enough to reproduce this game."""
+
+    def timings(self) -> dict[str, Any]:
+        """Wall-clock time of every move so far, invalid ones included.
+
+        ``decision`` holds the seconds from the end of the previous move (or
+        the start of the game) to each move and ``engine`` the seconds spent
+        playing it, both as ``float64`` arrays, or lists of floats without
+        NumPy; ``stats`` maps both names to ``count``, ``total``, ``mean``,
+        ``median``, ``p95`` and ``max`` in seconds. Callbacks and the move log
+        count toward the next decision. Empty unless the game was created
+        with ``timings=True``.
+        """
+
+    @property
+    def board(self) -> list[list[int]]: ...
+    @property
+    def score(self) -> int: ...
+    @property
+    def state(self) -> State: ...
+    @property
+    def status(self) -> Status:
+        """``state`` with its reason and whether any move remains."""
+    @property
+    def time_left(self) -> float | None:
+        """Seconds left on the clock, ``None`` without ``time_limit``."""
+    @property
+    def won(self) -> bool:
+        """Whether a victory has been reached at some point."""
+    @property
+    def moves(self) -> int:
+        """Moves played so far, including invalid ones."""
+    @property
+    def seed(self) -> int: ...
+    @property
+    def rules(self) -> Rules: ...
+
+class StateToken:
+    """A saved ``Game`` state from ``Game.snapshot``."""
+
+    @property
+    def board(self) -> list[list[int]]: ...
+    @property
+    def score(self) -> int: ...
+    @property
+    def moves(self) -> int:
+        """Moves played before the snapshot."""
+
+def record(
+    path: str | PathLike[str],
+    seed: int | None = None,
+    rules: Rules | None = None,
+    invalid_moves: str = "noop",
+    keep_playing: bool = False,
+) -> Recording:
+    """Record a game to a binary game log: ``with record(path) as game:``.
+
+    Every move is written to ``path`` as it is made, so a crash keeps the
+    moves so far. Leaving the block stops logging and rewrites the file from
+    ``replay()``, complete and flushed, even after a ``restore``;
+    ``Replay.load(path)`` reads it back. The remaining arguments are those
+    of ``Game``.
+
+    Raises:
+        ValueError: For an unknown ``invalid_moves``.
+    """
+
+class Recording:
+    """Context manager returned by ``record``; entering it starts the log."""
+
+    def __enter__(self) -> Game: ...
+    def __exit__(self, *exc: object) -> bool: ...
+    @property
+    def game(self) -> Game:
+        """The game recorded."""
+    @property
+    def path(self) -> Path: ...
+
+class PuzzleResult:
+    """Verdict of ``Puzzle.check``."""
+
+    @property
+    def outcome(self) -> str:
+        """``"solved"``, ``"out_of_moves"``, ``"game_over"`` or ``"in_progress"``
+        when the moves ran out with budget left."""
+    @property
+    def solved(self) -> bool: ...
+    @property
+    def moves(self) -> int:
+        """Moves played up to the verdict."""
+    @property
+    def board(self) -> list[list[int]]: ...
+    @property
+    def score(self) -> int: ...
+
+class Puzzle:
+    """A fixed starting board to bring to a goal within a move budget.
+
+    Spawns derive from ``seed``, so everyone playing the same puzzle sees
+    the same tiles for the same moves.
+
+    Args:
+        board: 4x4 starting board.
+        max_moves: Move budget; invalid moves count too.
+        tile: Goal: some tile at least this large.
+        score: Goal: a score of at least this much.
+        clear_multipliers: Goal: no multiplier tile left on the board.
+        seed: Seed for the spawns.
+        rules: Optional ``Rules``.
+
+    Every goal that is given must hold at once, and at least one is needed.
+
+    Raises:
+        ValueError: For an invalid board or a puzzle without a goal.
+    """
+
+    def __init__(
+        self,
+        board: list[list[int]],
+        max_moves: int,
+        tile: int | None = None,
+        score: int | None = None,
+        clear_multipliers: bool = False,
+        seed: int = 0,
+        rules: Rules | None = None,
+    ) -> None: ...
+    def check(self, moves: list[Direction | int]) -> PuzzleResult:
+        """Play ``moves`` from the start until the goal is met, the budget is
+        used up or the game is over; later moves are ignored."""
+
+    def game(self) -> Game:
+        """A ``Game`` at the starting position, to play the puzzle move by move."""
+
+    @property
+    def board(self) -> list[list[int]]: ...
+    @property
+    def max_moves(self) -> int: ...
+    @property
+    def tile(self) -> int | None: ...
+    @property
+    def score(self) -> int | None: ...
+    @property
+    def clear_multipliers(self) -> bool: ...
+    @property
+    def seed(self) -> int: ...
+    @property
+    def rules(self) -> Rules: ...
+
+class TablebaseEntry:
+    """Value of one position in a ``Tablebase``."""
+
+    @property
+    def win_probability(self) -> float:
+        """Chance of reaching the goal tile within the moves left under
+        optimal play."""
+    @property
+    def expected_score(self) -> float:
+        """Expected score gained over the moves left under the same play."""
+    @property
+    def best_move(self) -> Direction | None:
+        """Move that maximizes the win probability, ties going to the higher
+        expected score; ``None`` once the game is decided."""
+
+class Tablebase:
+    """Exact values of every position reachable from an endgame root within
+    a move horizon, found by retrograde analysis."""
+
+    @staticmethod
+    def build(
+        board: list[list[int]],
+        horizon: int,
+        goal: int = 65536,
+        rules: Rules | None = None,
+        max_positions: int = 1_000_000,
+        progress: Callable[[int, int], object] | None = None,
+        cancel: CancelToken | None = None,
+    ) -> Tablebase:
+        """Solve every position reachable from ``board`` within ``horizon``
+        moves, each move followed by every spawn it can lead to.
+
+        A position is won once it holds a tile of at least ``goal``.
+        ``progress(done, total)`` is called with the layers expanded and then
+        solved, ``2 * horizon + 1`` in all; ``cancel`` or Ctrl-C stops the
+        build.
+
+        Raises:
+            ValueError: For an invalid board or more than ``max_positions``
+                positions.
+            CancelledError: If ``cancel`` was cancelled.
+        """
+
+    @staticmethod
+    def load(path: str | PathLike[str]) -> Tablebase:
+        """Load a table written by ``save``."""
+
+    @staticmethod
+    def open(path: str | PathLike[str], verify: bool = False) -> Tablebase:
+        """Use the table saved at ``path`` in place, memory-mapped.
+
+        Only the pages lookups touch are read, and processes that open the
+        same file share one copy of it. Nothing may write to or truncate the
+        file while the table is open: lookups read it in place, and reading
+        past the end of a truncated file kills the process. ``load`` reads
+        it into memory instead. ``verify`` checks the whole file against its
+        checksum first.
+
+        Raises:
+            OSError: If the file cannot be read.
+            ValueError: If it is not a tablebase, is in the version 1 format
+                of earlier releases (``load`` and ``save`` it again) or fails
+                verification.
+        """
+
+    @property
+    def mapped(self) -> bool:
+        """Whether the table is memory-mapped by ``open``."""
+
+    def save(self, path: str | PathLike[str]) -> None:
+        """Write the table to ``path`` in its binary format."""
+
+    def lookup(
+        self, board: list[list[int]], moves_left: int | None = None
+    ) -> TablebaseEntry | None:
+        """The value of ``board`` with ``moves_left`` moves to go, or with the
+        most moves the table holds for it when omitted; ``None`` for a
+        position the table never reached."""
+
+    @property
+    def goal(self) -> int: ...
+    @property
+    def horizon(self) -> int: ...
+    @property
+    def rules(self) -> Rules: ...
+    def __len__(self) -> int: ...
+
+def tablebase_lookup(
+    board: list[list[int]],
+    table: Tablebase | str | PathLike[str],
+    moves_left: int | None = None,
+) -> TablebaseEntry | None:
+    """Look ``board`` up in a ``Tablebase`` or the path of a saved one.
+
+    A path is mapped like ``Tablebase.open`` for the call, so the file must
+    not change during it.
+    """
+
+def enumerate_successors(
+    board: list[list[int]],
+    depth: int = 1,
+    rules: Rules | None = None,
+    numbers_only: bool = False,
+) -> list[list[list[int]]]:
+    """The distinct boards exactly ``depth`` moves from ``board``, each move
+    followed by every spawn it can give, in sorted order.
+
+    ``numbers_only=True`` spawns only 2 and 4, as in classic 2048.
+
+    Raises:
+        ValueError: For an invalid board.
+    """
+
+def count_reachable_states(
+    board: list[list[int]],
+    depth: int,
+    rules: Rules | None = None,
+    numbers_only: bool = False,
+) -> list[int]:
+    """Number of distinct boards reachable at ``0, 1, ..., depth`` moves, as
+    in ``enumerate_successors``.
+
+    Raises:
+        ValueError: For an invalid board.
+    """
+
+def hint(
+    board: list[list[int]],
+    depth: int = 3,
+    rules: Rules | None = None,
+    time_budget: float | None = None,
+    threads: int | None = None,
+    book: OpeningBook | None = None,
+    moves_played: int = 0,
+) -> tuple[Direction, dict[str, Any]] | None:
+    """Recommend a move, searching ``depth`` moves ahead with expectimax.
+
+    The search deepens one move at a time on ``threads`` threads (default:
+    one per core) sharing a transposition table; with a ``time_budget`` in
+    seconds it keeps the deepest search completed in time. Positions held
+    by ``book`` are looked up instead of searched. ``moves_played`` counts
+    the moves before ``board``, which decide the moves that spawn under
+    ``Rules(spawn_every=...)``.
+
+    The explanation holds ``"values"`` (expected evaluation of every legal
+    ``Direction``, best first; only the book's move for book positions),
+    ``"factors"`` (heuristic features of the board after the move),
+    ``"score"`` (what the move merges), ``"summary"`` (one sentence),
+    ``"depth"`` (the depth searched) and ``"source"`` (``"book"`` or
+    ``"search"``). ``None`` when no move is left.
+
+    Raises:
+        ValueError: For an invalid board, a negative time budget, no
+            threads or a book built under other rules.
+    """
+
+class OpeningBook:
+    """Precomputed best moves of the early game, consulted by ``hint``.
+
+    Holds the expectimax move of every position reachable from the start,
+    playing the book's own moves against every spawn, while the tiles sum
+    to at most ``max_sum`` (multipliers counting by their factor).
+    """
+
+    @staticmethod
+    def build(
+        max_sum: int = 16,
+        depth: int = 2,
+        rules: Rules | None = None,
+        max_positions: int = 1_000_000,
+        progress: Callable[[int, int], object] | None = None,
+        cancel: CancelToken | None = None,
+    ) -> OpeningBook:
+        """Search the best move of every opening position.
+
+        Raises:
+            ValueError: For too many positions.
+            CancelledError: If ``cancel`` was cancelled.
+        """
+    @staticmethod
+    def load(path: str | PathLike[str], force: bool = False) -> OpeningBook:
+        """Load a book written by ``save``.
+
+        ``force`` loads a book built by an engine that plays its rules
+        differently (see ``engine_stamp``), with a ``RuntimeWarning`` per
+        difference.
+
+        Raises:
+            OSError: If the file cannot be read.
+            ValueError: If it is not an opening book, fails its checksum, or
+                its stamp differs and ``force`` is not set.
+        """
+    def save(self, path: str | PathLike[str]) -> None:
+        """Write the book to ``path``, with a format version and checksum."""
+    def lookup(self, board: list[list[int]]) -> tuple[Direction, float] | None:
+        """The book's move for ``board`` and its expected evaluation."""
+    @property
+    def max_sum(self) -> int: ...
+    @property
+    def depth(self) -> int: ...
+    @property
+    def rules(self) -> Rules: ...
+    def __len__(self) -> int: ...
+    def __contains__(self, board: list[list[int]]) -> bool: ...
+
+def evaluate_moves(
+    board: list[list[int]], depth: int = 2, rules: Rules | None = None
+) -> dict[Direction, float]:
+    """The expectimax value of every legal move, in action-id order.
+
+    Each move is followed by an average over every spawn by its probability,
+    ``depth`` moves deep, with no pruning; boards with no move left count as
+    about ``-10000``. Empty when no move is left.
+
+    Raises:
+        ValueError: For an invalid board.
+    """
+
+def worst_case_moves(
+    board: list[list[int]], depth: int = 3, rules: Rules | None = None
+) -> dict[Direction, dict[str, Any]]:
+    """The worst case of every legal move, each spawn chosen by an adversary.
+
+    Minimax with alpha-beta pruning considers every tile the rules can spawn
+    on every empty cell. Each move maps to ``"value"``, the evaluation the
+    adversary can hold the player to (about ``-10000`` for a forced dead
+    board), and ``"guaranteed_moves"``, the moves the player is sure to make,
+    this one included, up to ``depth``.
+
+    Raises:
+        ValueError: For an invalid board.
+    """
+
+def tune_heuristics(
+    n_games: int,
+    generations: int,
+    method: str = "cma-es",
+    depth: int = 1,
+    seed: int = 0,
+    max_moves: int | None = None,
+    population: int | None = None,
+    sigma: float = 0.3,
+    rules: Rules | str | None = None,
+    progress: Callable[[int, int], object] | None = None,
+    cancel: CancelToken | None = None,
+    checkpoint: str | PathLike[str] | None = None,
+    checkpoint_every: int = 1,
+) -> dict[str, Any]:
+    """Tune the weights of the built-in evaluation heuristics by self-play.
+
+    ``method`` is ``"cma-es"`` or ``"annealing"``; every candidate plays the
+    same ``n_games`` seeds with an expectimax agent ``depth`` moves deep and
+    is scored by its mean score. Returns ``weights`` (per feature),
+    ``score``, ``history`` (best score after each generation) and
+    ``evaluations``. ``progress(done, total)`` is called with the self-play
+    games played so far; ``cancel`` or Ctrl-C stops the run.
+
+    With ``checkpoint`` the run saves its whole state to that file every
+    ``checkpoint_every`` generations and resumes from it if it exists,
+    ending exactly as an uninterrupted run would.
+
+    Raises:
+        ValueError: For an unknown method, no games, a population below 2, a
+            step size that is not positive, or a checkpoint that cannot be
+            used.
+        CancelledError: If ``cancel`` was cancelled.
+    """
+
+class NTupleNetwork:
+    """A 4-tuple network valuing boards, trained by ``train``.
+
+    Four 4-cell patterns (the outer and inner rows, the corner and edge 2x2
+    squares) are read under the 8 symmetries of the board; a board's value
+    is the sum of the 32 weights they select.
+    """
+
+    def __init__(self) -> None: ...
+    def value(self, board: list[list[int]], rules: Rules | None = None) -> float:
+        """Value of ``board``: the score the network expects from it on."""
+    def best_move(
+        self, board: list[list[int]], rules: Rules | None = None
+    ) -> Direction | None:
+        """The move worth the most score gained plus value of the board it
+        slides to, or ``None`` without legal moves."""
+    def save(self, path: str | PathLike[str]) -> None:
+        """Write the network to ``path``, with a format version and checksum."""
+    @staticmethod
+    def load(path: str | PathLike[str]) -> NTupleNetwork:
+        """Load a network written by ``save``.
+
+        Raises:
+            OSError: If the file cannot be read.
+            ValueError: If it is not a network or fails its checksum.
+        """
+    def __len__(self) -> int: ...
+
+def train(
+    n_games: int,
+    lr: float = 0.1,
+    lambda_: float = 0.5,
+    seed: int = 0,
+    rules: Rules | None = None,
+    eval_every: int = 0,
+    eval_games: int = 10,
+    max_moves: int | None = None,
+    network: NTupleNetwork | None = None,
+    callback: Callable[[dict[str, Any]], object] | None = None,
+    progress: Callable[[int, int], object] | None = None,
+    cancel: CancelToken | None = None,
+) -> dict[str, Any]:
+    """Train an ``NTupleNetwork`` by TD(lambda) on afterstates, natively.
+
+    Every game is played greedily by the network being trained; after each
+    move the previous afterstate's value moves toward the score gained plus
+    the next afterstate's value, the error reaching earlier afterstates too
+    with ``lambda_ > 0``. Evaluation matches after every ``eval_every``
+    games (and the last) play ``eval_games`` fixed seeds without learning;
+    ``callback`` gets each one's dict as it is played.
+
+    Returns ``network``, ``scores`` (every training game's score) and
+    ``evaluations`` (dicts of ``games``, ``mean_score``, ``max_tile`` and
+    ``win_rate``).
+
+    Raises:
+        ValueError: For a learning rate that is not positive or a
+            ``lambda_`` outside ``[0, 1]``.
+        CancelledError: If ``cancel`` was cancelled.
+    """
+
+class OnnxPolicy:
+    """A neural network policy loaded from an ONNX file, run natively.
+
+    The model takes one board as ``encode(board, scheme, rules)`` gives it and
+    its first four outputs score Down, Right, Up and Left; it plays the legal
+    move scored highest. As an agent of ``tournament``, ``report_card``,
+    ``collect_dataset`` or ``survival_probability`` every move is chosen in
+    Rust, without the GIL. Only feed-forward models are supported.
+
+    Raises:
+        ValueError: For an unreadable or unsupported model, an unknown scheme,
+            or a model whose input or output does not fit.
+    """
+
+    def __init__(
+        self,
+        path: str | PathLike[str],
+        scheme: str = "onehot",
+        rules: Rules | None = None,
+    ) -> None: ...
+    def scores(self, board: list[list[int]]) -> list[float]:
+        """The model's score of each direction, in action-id order."""
+    def choose(self, board: list[list[int]]) -> Direction | None:
+        """The legal move scored highest, or ``None`` without legal moves."""
+    def __call__(self, board: list[list[int]]) -> Direction | None: ...
+
+class Replay:
+    """A recorded game: seed, rules and the accepted moves.
+
+    Spawns derive from the seed, so a replay reproduces every board of the
+    original game. Get one from ``Game.replay()`` or ``Replay.parse(text)``.
+
+    Args:
+        seed: Seed the game was started with.
+        moves: Directions of the accepted moves, in order.
+        rules: Optional ``Rules`` the game was played under.
+    """
+
+    def __init__(
+        self, seed: int, moves: list[Direction], rules: Rules | None = None
+    ) -> None: ...
+    @staticmethod
+    def parse(text: str, force: bool = False) -> Replay:
+        """Parse the text produced by ``to_text()``.
+
+        Text stamped by an engine that plays its rules differently (see
+        ``engine_stamp``) is refused unless ``force`` is set, which loads it
+        with a ``RuntimeWarning`` per difference.
+        """
+
+    @staticmethod
+    def load(path: str | PathLike[str], force: bool = False) -> Replay:
+        """Read a binary game log written by ``save()`` or ``Game.log_moves``.
+
+        ``force`` loads a log stamped by an engine that plays its rules
+        differently, as for ``parse``.
+
+        Raises:
+            ValueError: If the file is not a valid log, is truncated, fails
+                a checksum, a spawn does not match the seed, or its stamp
+                differs and ``force`` is not set.
+        """
+
+    def save(self, path: str | PathLike[str]) -> None:
+        """Save as a compact binary game log, about one byte per move."""
+
+    def to_text(self) -> str:
+        """Plain-text form (also what the terminal client saves)."""
+
+    def digest(self) -> str:
+        """Hex SHA-256 of ``to_text()`` without its stamp line, identifying
+        the game whichever engine wrote it."""
+
+    def game(self) -> Game:
+        """Play the moves back and return the resulting ``Game``."""
+
+    def frames(self) -> list[list[list[int]]]:
+        """The starting board followed by the board after every move."""
+
+    @property
+    def seed(self) -> int: ...
+    @property
+    def start(self) -> tuple[list[list[int]], int] | None:
+        """``(board, score)`` of a ``Game.from_board`` game, else ``None``."""
+    @property
+    def moves(self) -> list[Direction]: ...
+    @property
+    def rules(self) -> Rules: ...
+    def __len__(self) -> int: ...
+
+def export_gif(
+    replay: Replay,
+    path: str | PathLike[str],
+    fps: int = 4,
+    theme: str = "light",
+) -> None:
+    """Save a replay as a looping animated GIF, one frame per board.
+
+    Args:
+        replay: The ``Replay`` to render.
+        path: Destination ``.gif`` file.
+        fps: Frames per second (1 to 100).
+        theme: ``"light"`` or ``"dark"``, as in ``render_image``.
+
+    Raises:
+        ValueError: For an invalid ``fps`` or theme, or a replay whose moves
+            do not play back.
+    """
+
+def engine_stamp(rules: Rules | None = None) -> str:
+    """Compatibility stamp of artifacts this engine makes under ``rules``:
+    ``"engine=<version> rules=<hash> spawns=<hash>"``.
+
+    Replays, binary game logs, opening books and datasets carry one, so a
+    file made by an engine that merges or spawns differently is noticed
+    when it is loaded.
+    """
+
+def check_stamp(
+    stamp: str, rules: Rules | None = None, force: bool = False
+) -> list[str]:
+    """Compare a stamp, as ``engine_stamp`` writes it, with this engine.
+
+    Args:
+        stamp: Stamp of an artifact, e.g. the ``stamp`` array of a dataset
+            decoded as text.
+        rules: Optional ``Rules`` the artifact was made under; defaults to
+            the akioi rules.
+        force: Accept a stamp this engine no longer matches, with a
+            ``RuntimeWarning`` instead of an error.
+
+    Returns:
+        How this engine plays the rules differently; empty when it plays
+        them the same.
+
+    Raises:
+        ValueError: If the stamp is malformed, or differs and ``force`` is
+            not set.
+    """
+
+class LeaderboardEntry:
+    """A finished game on a ``Leaderboard``."""
+
+    @property
+    def score(self) -> int: ...
+    @property
+    def max_tile(self) -> int: ...
+    @property
+    def moves(self) -> int:
+        """Accepted moves."""
+    @property
+    def seed(self) -> int: ...
+    @property
+    def rules(self) -> Rules: ...
+    @property
+    def replay_hash(self) -> str:
+        """``Replay.digest()`` of the game."""
+
+class Leaderboard:
+    """A local high-score table of finished games, kept in a text file.
+
+    Entries come from replays, so every score is one the engine played; the
+    file is only appended to. A missing file starts an empty board.
+
+    Raises:
+        OSError: If the file cannot be read.
+        ValueError: If it is not a leaderboard.
+    """
+
+    def __init__(self, path: str | PathLike[str]) -> None: ...
+    def record(self, replay: Replay) -> bool:
+        """Play ``replay`` back and add its final position.
+
+        Returns:
+            ``False`` if the same game is already on the board.
+
+        Raises:
+            ValueError: If the replay does not play back.
+        """
+    def top(
+        self,
+        rules: Rules | None = None,
+        sort: str = "score",
+        limit: int | None = None,
+    ) -> list[LeaderboardEntry]:
+        """Entries best first.
+
+        Args:
+            rules: Only games played under these ``Rules``.
+            sort: ``"score"``, ``"max_tile"`` (then score) or ``"moves"``
+                (fewest first, then score); ties keep the recording order.
+            limit: At most this many entries.
+        """
+    def rank(self, score: int, rules: Rules | None = None) -> int:
+        """1-based position a game with ``score`` would take under ``rules``."""
+    @property
+    def path(self) -> str: ...
+    def __len__(self) -> int: ...
+
+class Arena:
+    """Many independent seeded games stepped together, like a vectorized RL env.
+
+    Observations are NumPy ``int32`` arrays of shape ``(n_envs, 4, 4)``.
+
+    Args:
+        n_envs: Number of games.
+        seed: Arena seed; every game draws its own seed from it, the env
+            index and how many games the env has played, so equal seeds
+            and actions give equal runs. Random when omitted.
+        rules: Optional ``Rules`` for every game.
+        auto_reset: Replace a game with a fresh one as soon as it ends.
+        threads: Step large arenas on up to this many threads, with the GIL
+            released (default: one per core). Runs do not depend on it.
+        heatmaps: Also collect ``stats.heatmaps`` of where play happens.
+        branching: Also collect ``stats.branching``, how much choice and
+            chance every move had.
+
+    Every game that ends is added to ``stats``.
+    """
+
+    def __init__(
+        self,
+        n_envs: int,
+        seed: int | None = None,
+        rules: Rules | None = None,
+        auto_reset: bool = True,
+        threads: int | None = None,
+        heatmaps: bool = False,
+        branching: bool = False,
+    ) -> None: ...
+    def reset(self, indices: list[int] | None = None) -> Any:
+        """Start fresh games at ``indices`` (every env when omitted).
+
+        Returns the observations of all envs.
+        """
+
+    def step(self, actions: Any) -> tuple[Any, Any, Any]:
+        """Apply one ``Direction`` or action id (Down=0, Right=1, Up=2, Left=3) per env.
+
+        Returns ``(observations, rewards, dones)`` with ``int32`` rewards and
+        ``bool`` dones. With ``auto_reset`` the observation of a finished env
+        is already its new game; the board it ended on is in
+        ``final_observations``.
+
+        Raises:
+            ValueError: Unless there is exactly one valid action per env.
+        """
+
+    @property
+    def observations(self) -> Any:
+        """Current boards, shape ``(n_envs, 4, 4)``."""
+    @property
+    def final_observations(self) -> Any:
+        """Boards the last ``step`` ended on, before any automatic reset."""
+    @property
+    def scores(self) -> Any:
+        """Score of every current game, ``int64`` of shape ``(n_envs,)``."""
+    @property
+    def stats(self) -> GameStats:
+        """``GameStats`` of every game that ended in this arena."""
+    @property
+    def rules(self) -> Rules: ...
+    @property
+    def threads(self) -> int:
+        """Threads ``step`` may use."""
+    def __len__(self) -> int: ...
+
+def step_batch(
+    boards: list[list[list[int]]],
+    actions: Any,
+    seed: int | None = None,
+    rules: Rules | None = None,
+    threads: int | None = None,
+    step: int = 0,
+) -> list[StepResult]:
+    """Apply one move to each of many boards at once, natively.
+
+    Boards are stepped on up to ``threads`` threads (at most one per core)
+    with the GIL released. The spawn after board ``i`` comes from a
+    counter-based RNG stream keyed by ``seed``, ``i`` and ``step``, so equal
+    arguments give equal results whatever the thread count. Pass the step
+    number of the simulation as ``step`` to draw fresh spawns on every call.
+
+    Raises:
+        InvalidBoardError: For an invalid board.
+        InvalidDirectionError: For an invalid action.
+        ValueError: Unless there is exactly one action per board.
+    """
+
+def evaluate_batch(
+    boards: Any, rules: Rules | None = None, threads: int | None = None
+) -> Any:
+    """The heuristic features of many boards at once, natively.
+
+    ``boards`` are nested lists or a NumPy array of shape ``(n, 4, 4)``,
+    read on up to ``threads`` threads (at most one per core) with the GIL
+    released. Returns a ``float64`` array of shape ``(n, 6)`` whose columns
+    are ``empty_cells``, ``monotonicity``, ``smoothness``, ``corner``,
+    ``merges`` and ``multipliers``, as in the ``"factors"`` of ``hint``.
+
+    Raises:
+        InvalidBoardError: For an invalid board.
+    """
+
+class VersusStep:
+    """One player's part of an ``Arena2P.step``."""
+
+    @property
+    def board(self) -> list[list[int]]:
+        """Board after the move, its spawn and the attacks received."""
+    @property
+    def delta(self) -> int: ...
+    @property
+    def moved(self) -> bool:
+        """Whether the move changed the board; others count as a pass."""
+    @property
+    def sent(self) -> int:
+        """Attack tiles this move sent to the opponent."""
+    @property
+    def received(self) -> list[tuple[int, int]]:
+        """Cells the opponent's attacks landed on; attacks beyond the empty
+        cells are lost."""
+
+class Arena2P:
+    """Two-player versus match: both players move at once, and big merges
+    on one board drop attack tiles onto the other.
+
+    Args:
+        seed: Match seed; random when omitted. Both players start from the
+            same board.
+        rules: Optional ``Rules`` for both boards.
+        attack_tile: Tile an attack drops on the opponent's board, e.g.
+            ``-1`` (a x1 multiplier that only gets in the way) or ``2``.
+        attack_min_merge: Every merge into a tile of at least this value
+            sends one attack.
+        max_attacks: Most attack tiles one move can send.
+
+    A player with no move left loses; one reaching 65536 wins.
+
+    Raises:
+        ValueError: For an attack tile that is invalid under ``rules``.
+    """
+
+    def __init__(
+        self,
+        seed: int | None = None,
+        rules: Rules | None = None,
+        attack_tile: int = -1,
+        attack_min_merge: int = 16,
+        max_attacks: int = 2,
+    ) -> None: ...
+    def step(
+        self, moves: tuple[Direction | int, Direction | int]
+    ) -> tuple[VersusStep, VersusStep]:
+        """Apply one move per player.
+
+        Raises:
+            ValueError: Unless there are exactly two moves.
+            GameFinishedError: Once the match is over.
+        """
+
+    @property
+    def boards(self) -> list[list[list[int]]]:
+        """Both boards, player 0 first."""
+    @property
+    def scores(self) -> tuple[int, int]: ...
+    @property
+    def is_over(self) -> bool: ...
+    @property
+    def winner(self) -> int | None:
+        """``0`` or ``1`` once a player won, ``None`` while playing or after a
+        draw."""
+    @property
+    def seed(self) -> int: ...
+    @property
+    def rules(self) -> Rules: ...
+
+class Env:
+    """Gym-style environment around one seeded game.
+
+    Observations are NumPy ``int32`` boards of shape ``(4, 4)``.
+
+    Args:
+        seed: Environment seed; every ``reset`` draws a new game seed from it.
+        rules: Optional ``Rules``.
+        reward: ``"score"`` (score delta, the default), ``"log_score"``
+            (signed ``log2(1 + |delta|)``), ``"merges"`` (merge count) or a
+            callable ``reward(board, result) -> float`` given the board
+            before the move and its ``MoveResult``.
+        empty_bonus: Added per empty cell after the move.
+        game_over_penalty: Subtracted on the move that loses the game.
+        victory_bonus: Added on the move that wins the game.
+        max_moves: Truncate episodes after this many moves.
+        invalid_moves: Invalid-move policy, as for ``Game``.
+        invalid_penalty: Subtracted for a move that changes nothing under
+            ``invalid_moves="penalize"``.
+        keep_playing: Play on after a victory, as for ``Game``; episodes then
+            only terminate when no move is left.
+
+    Raises:
+        ValueError: For an unknown reward name, or bonuses combined with a
+            callable reward.
+    """
+
+    def __init__(
+        self,
+        seed: int | None = None,
+        rules: Rules | None = None,
+        reward: str | Callable[[list[list[int]], MoveResult], float] | None = None,
+        empty_bonus: float = 0.0,
+        game_over_penalty: float = 0.0,
+        victory_bonus: float = 0.0,
+        max_moves: int | None = None,
+        invalid_moves: str = "noop",
+        invalid_penalty: float = 1.0,
+        keep_playing: bool = False,
+    ) -> None: ...
+    def reset(self, seed: int | None = None) -> tuple[Any, dict[str, Any]]:
+        """Start a new episode; returns ``(observation, info)``.
+
+        ``seed`` restarts the sequence of episode seeds. ``info`` holds
+        ``score``, ``moves``, ``state`` and ``action_mask``, a ``bool`` array
+        of shape ``(4,)`` marking the legal action ids.
+        """
+
+    def step(self, action: Direction | int) -> tuple[Any, float, bool, bool, dict[str, Any]]:
+        """Apply one ``Direction`` or action id (Down=0, Right=1, Up=2, Left=3).
+
+        Returns ``(observation, reward, terminated, truncated, info)``; ``info``
+        holds the ``reset`` keys plus ``delta``, ``moved`` and ``action`` (the
+        action id actually played).
+
+        Raises:
+            ValueError: For a move that changes nothing under ``"mask"``.
+            GameFinishedError: For a step after the episode ended, until
+                ``reset``.
+            InvalidDirectionError: For an invalid action.
+        """
+
+    @property
+    def observation(self) -> Any:
+        """Current board, shape ``(4, 4)``."""
+    @property
+    def score(self) -> int: ...
+    @property
+    def rules(self) -> Rules: ...
+
+def encode(board: Any, scheme: str = "onehot", rules: Rules | None = None) -> Any:
+    """Encode boards as ``float32`` planes for neural networks.
+
+    Args:
+        board: One 4x4 board or a batch of them (nested lists or a NumPy
+            array such as ``Arena.observations``).
+        scheme: ``"onehot"`` (empty plane, one plane per number tile 2 to
+            65536, then one per multiplier x1, x2, ...), ``"log2"`` (log2 of
+            number tiles and, on a second plane, log2(m) + 1 of x-m
+            multipliers) or ``"raw"`` (the tile values).
+        rules: Optional ``Rules``; the largest multiplier sets the number of
+            one-hot multiplier planes.
+
+    Returns:
+        Array of shape ``(channels, 4, 4)`` for one board or
+        ``(n, channels, 4, 4)`` for a batch.
+
+    Raises:
+        ValueError: For an unknown scheme or invalid tiles.
+    """
+
+def pack(board: list[list[int]], size: int = 16) -> bytes:
+    """Pack a board into compact bytes, one byte or nibble per cell.
+
+    ``size=16`` holds every power-of-two tile and multiplier; ``size=8``
+    holds numbers up to 4096 and the multipliers ``-1``, ``-2`` and ``-4``.
+
+    Raises:
+        ValueError: For another size or a tile the size cannot hold.
+    """
+
+def unpack(data: bytes) -> list[list[int]]:
+    """Unpack a board from the bytes ``pack`` made.
+
+    Raises:
+        ValueError: For a length other than 8 or 16, or a byte no tile
+            packs to.
+    """
+
+def board_key(board: list[list[int]]) -> int:
+    """The 8-byte ``pack`` of a board as one 64-bit integer.
+
+    For Python-side caches and databases keyed on a single number; the
+    first cell is the top nibble and ``board_from_key`` inverts it.
+    Only boards of numbers up to 4096 and multipliers up to ``-4`` have a
+    key; games past a 4096 tile need the 16-byte ``pack``.
+
+    Raises:
+        ValueError: Naming the first tile 8 bytes cannot hold: numbers
+            above 4096, multipliers beyond ``-4``, blockers and bombs.
+    """
+
+def board_from_key(key: int) -> list[list[int]]:
+    """Inverse of ``board_key``: the board of a 64-bit key."""
+
+def verify_chain(
+    records: list[MoveRecord], seed: int | None = None, rules: Rules | None = None
+) -> bool:
+    """Verify a chain of ``MoveRecord`` objects.
+
+    Checks that indices are consecutive, each move starts where the previous
+    ended, spawns use consecutive draws and every chain hash is correct. With
+    ``seed`` the moves are also replayed and must reproduce the records
+    exactly, proving every move and spawn was legal engine play.
+    """
+
+def sign_replay(replay: Replay, key: bytes) -> bytes:
+    """Sign a finished game for later verification.
+
+    The 32-byte signature is HMAC-SHA256 under ``key`` over
+    ``replay.to_text()`` followed by a ``score <n>`` line with the final
+    score, so only a holder of ``key`` can produce it.
+
+    Raises:
+        ValueError: If the replay does not play back.
+    """
+
+def verify_replay(replay: Replay, signature: bytes, key: bytes) -> bool:
+    """Check a signature made by ``sign_replay``.
+
+    The moves are played back from the seed, so a replay that is not legal
+    engine play, or whose score differs from the signed one, never verifies.
+    """
+
+def init(blockers: int = 0, rules: Rules | str | None = None) -> list[list[int]]:
     """Create a new board with two starting tiles.
 
+    Args:
+        blockers: Immovable blocker tiles (``-3``) placed on random cells
+            first; they never slide or merge, and need rules with
+            ``blockers=True``.
+        rules: ``Rules``, a preset name such as ``"classic"`` or a rules tag,
+            deciding what the starting tiles can be.
+
     Returns:
         Fresh board ready for play.
+
+    Raises:
+        ValueError: For blockers the rules do not allow, more than 14 of
+            them or unknown rules.
+    """
+
+Transition = tuple[list[list[int]], Direction, float, list[list[int]], bool, int | None]
+
+class TrajectoryRecorder:
+    """Record ``(state, action, reward, next_state, done, goal)`` transitions.
+
+    Args:
+        goal: Optional target tile. When set, transitions are goal-conditioned:
+            reward is ``1.0`` on the move that first reaches the tile, else
+            ``0.0``, and the episode ends there. When unset, reward is the
+            score delta and ``goal`` is ``None``.
+        hindsight: Also emit every finished episode relabelled with the max
+            tile it actually achieved as the goal.
+    """
+
+    def __init__(self, goal: int | None = None, hindsight: bool = False) -> None: ...
+    def record(
+        self,
+        board: list[list[int]],
+        direction: Direction,
+        delta: int,
+        next_board: list[list[int]],
+        state: State,
+    ) -> None:
+        """Record one move as returned by ``step``.
+
+        The episode is finished automatically once ``state`` is not
+        ``State.Continue``.
+        """
+
+    def finish_episode(self) -> None:
+        """Finish the current episode early (e.g. when truncated by a move limit)."""
+
+    def transitions(self) -> list[Transition]:
+        """Return all transitions emitted by finished episodes."""
+
+    def clear(self) -> None:
+        """Drop all emitted transitions and the current episode."""
+
+    def __len__(self) -> int: ...
+
+def collect_dataset(
+    policy: Callable[[list[list[int]]], Direction] | str,
+    n_games: int,
+    out_path: str | PathLike[str],
+    format: str = "npz",
+    goal: int | None = None,
+    hindsight: bool = False,
+    max_moves: int | None = None,
+) -> int:
+    """Play games natively and write the recorded transitions to disk.
+
+    Args:
+        policy: Callable ``policy(board) -> Direction`` or the name of a
+            built-in policy (``"random"``, ``"greedy-score"``,
+            ``"down-left-corner"`` or ``"snake"``).
+        n_games: Number of games to play from fresh ``init()`` boards.
+        out_path: Destination file.
+        format: Output format. ``"npz"`` writes a NumPy archive with arrays
+            ``states`` (N, 4, 4) int32, ``actions`` (N,) uint8 (Down=0,
+            Right=1, Up=2, Left=3), ``rewards`` (N,) float32,
+            ``next_states`` (N, 4, 4) int32, ``dones`` (N,) bool and
+            ``goals`` (N,) int32 (0 = no goal), and ``stamp``: the bytes
+            of the ``engine_stamp`` of the rules the games were played by.
+        goal: Optional goal tile, see ``TrajectoryRecorder``.
+        hindsight: Also write hindsight-relabelled transitions.
+        max_moves: Truncate games after this many moves.
+
+    Returns:
+        Number of transitions written.
+    """
+
+class ReplayBuffer:
+    """Fixed-capacity replay buffer of packed transitions, for off-policy RL.
+
+    Boards are packed to 16 bytes, so a transition takes 42 bytes. Once
+    full, every ``push`` evicts the oldest transition.
+
+    Args:
+        capacity: Most transitions kept.
+        prioritized: Sample in proportion to ``priority ** alpha`` instead of
+            uniformly (prioritized experience replay).
+        alpha: How strongly priorities skew sampling; ``0`` is uniform.
+        seed: Sampling seed; random when omitted.
+
+    Raises:
+        ValueError: For a zero capacity or a negative ``alpha``.
+    """
+
+    def __init__(
+        self,
+        capacity: int,
+        prioritized: bool = False,
+        alpha: float = 0.6,
+        seed: int | None = None,
+    ) -> None: ...
+    @property
+    def capacity(self) -> int: ...
+    @property
+    def prioritized(self) -> bool: ...
+    def push(
+        self,
+        state: list[list[int]],
+        action: Direction | int,
+        reward: float,
+        next_state: list[list[int]],
+        done: bool,
+        priority: float | None = None,
+        goal: int | None = None,
+    ) -> None:
+        """Store one transition.
+
+        ``priority`` defaults to the largest so far, so new transitions are
+        sampled soon. Raises ``ValueError`` for a tile that is not a power of
+        two or a negative priority.
+        """
+
+    def sample(
+        self, batch_size: int, beta: float = 0.4, return_numpy: bool = True
+    ) -> dict[str, Any]:
+        """Draw ``batch_size`` transitions with replacement.
+
+        Returns a dict with ``states``, ``actions`` (Down=0, Right=1, Up=2,
+        Left=3), ``rewards``, ``next_states``, ``dones``, ``goals`` (0 = none),
+        ``indices`` and importance-sampling ``weights``
+        (``(len * P) ** -beta``, normalized to a maximum of 1; all 1 without
+        prioritization). NumPy arrays use the dtypes of ``collect_dataset``,
+        with ``indices`` int64 and ``weights`` float32.
+
+        Raises:
+            ValueError: If the buffer is empty.
+        """
+
+    def update_priorities(self, indices: list[int], priorities: list[float]) -> None:
+        """Set the priorities of sampled transitions, e.g. to new TD errors."""
+
+    def save(self, path: str | PathLike[str]) -> None:
+        """Write the buffer to ``path`` in its binary format."""
+
+    @staticmethod
+    def load(path: str | PathLike[str], seed: int | None = None) -> ReplayBuffer:
+        """Load a buffer written by ``save``; raises ``ValueError`` for other files."""
+
+    def __len__(self) -> int: ...
+
+def report_card(
+    agent: Callable[[list[list[int]]], Direction] | str,
+    seed: int = 0,
+    n_games: int = 8,
+    time_limit: float = 0.05,
+) -> dict[str, Any]:
+    """Run the standardized strength battery against an agent.
+
+    Batteries: ``seeded`` (fresh seeded games), ``positions`` (fixed mid-game
+    positions), ``adversarial`` (worst-case spawns) and ``timed`` (a move
+    slower than ``time_limit`` seconds forfeits the game). All spawns derive
+    from ``seed``, so cards of different agents are comparable.
+
+    Args:
+        agent: Callable ``agent(board) -> Direction`` or a built-in policy name.
+        seed: Master seed for the whole battery.
+        n_games: Games per battery (``positions`` plays each position once).
+        time_limit: Per-move limit in seconds for the ``timed`` battery.
+
+    Returns:
+        ``{"seed", "overall", "batteries"}`` where ``overall`` and each
+        ``batteries[name]`` hold ``games``, ``moves``, ``median_score``,
+        ``win_rate``, ``blunder_rate``, ``timeouts`` and ``moves_per_second``.
+        A blunder is a move that leaves the board unchanged, or one that some
+        spawn can punish with game over while another move is safe.
+    """
+
+def tournament(
+    agents: list[Callable[[list[list[int]]], Direction] | str],
+    games_per_agent: int = 100,
+    seed: int = 0,
+    rules: Rules | None = None,
+    games_path: str | PathLike[str] | None = None,
+    moves_path: str | PathLike[str] | None = None,
+    progress: Callable[[int, int], object] | None = None,
+    cancel: CancelToken | None = None,
+    objectives: list[str] | None = None,
+) -> dict[str, Any]:
+    """Compare agents over seed-paired games.
+
+    Game ``i`` of every agent starts from the same board and draws its
+    spawns from the same seeded stream, so score differences are paired
+    game by game. Built-in policies play in parallel with the GIL released;
+    once a callable is involved the agents play in turn.
+
+    Args:
+        agents: Callables ``agent(board) -> Direction`` or built-in policy
+            names: ``"random"``, ``"greedy-score"`` (the move gaining the
+            most score), ``"down-left-corner"`` (the first legal of Down,
+            Left, Right, Up) or ``"snake"`` (the move keeping the largest
+            tiles along a snake from the bottom-left corner).
+        games_per_agent: Games each agent plays.
+        seed: Master seed for every game.
+        rules: Optional ``Rules``.
+        games_path: Write one row per game to this Apache Arrow IPC
+            (Feather) file, or Parquet file if the name ends in
+            ``.parquet``: ``agent``, ``game``, ``seed``, ``score``,
+            ``moves``, ``max_tile`` and ``won``.
+        moves_path: Write one row per move to this Arrow or Parquet file,
+            by the same rule: ``agent``, ``game``, ``move``,
+            ``direction``, ``delta``, ``score`` (total after the move),
+            ``moved`` and the board it was played on as ``cell_0`` to
+            ``cell_15``, row-major.
+        objectives: Names of what to rank the agents on, higher being
+            better: ``"score"`` (mean final score), ``"max_tile"`` (mean
+            largest tile), ``"moves"`` (mean moves survived) and
+            ``"win_rate"`` (games reaching the winning tile). All four when
+            omitted.
+
+    Arrow files load with ``pandas.read_feather`` or ``polars.read_ipc``,
+    Parquet files with ``pandas.read_parquet`` or ``polars.read_parquet``.
+    ``progress(done, total)`` is called with the games played so far;
+    ``cancel`` or Ctrl-C stops the tournament between games.
+
+    Returns:
+        ``{"seed", "scores", "agents", "comparisons"}``. ``scores`` holds
+        each agent's per-game scores; ``agents[i]`` holds ``games``,
+        ``mean_score``, ``median_score``, ``std_score``, ``win_rate``,
+        ``mean_moves`` and ``max_tile``; every pair ``a < b`` gets a
+        comparison with ``a``, ``b``, ``mean_difference`` (``a`` minus
+        ``b``), ``wins``, ``losses``, ``ties``, the paired ``t_statistic``
+        and its two-sided ``p_value``, and ``sign_p_value`` of the exact
+        sign test. ``pareto`` holds the ``objectives`` ranked on, each
+        agent's ``values`` of them, its ``ranks`` (``0`` when no agent does
+        at least as well on every objective and better on one, ``1`` when
+        only rank-0 agents do, ...) and the rank-0 ``front``.
+
+    Raises:
+        ValueError: For an unknown policy name, a non-callable agent or an
+            unknown objective.
+        CancelledError: If ``cancel`` was cancelled.
+    """
+
+def survival_probability(
+    board: list[list[int]],
+    n_moves: int,
+    samples: int = 1000,
+    policy: Callable[[list[list[int]]], Direction] | str | None = None,
+    seed: int = 0,
+    rules: Rules | None = None,
+) -> dict[str, Any]:
+    """Estimate the chance that ``board`` survives ``n_moves`` more moves.
+
+    ``samples`` native rollouts play ``policy`` (default ``"random"``) with
+    spawns seeded from ``seed``, as in a ``tournament``. Moves that change
+    nothing are not counted; reaching the winning tile counts as surviving.
+
+    Returns:
+        ``{"probability", "std_error", "samples", "mean_moves"}``.
+
+    Raises:
+        InvalidBoardError: For an invalid board.
+        ValueError: For no samples, an unknown policy name or a non-callable
+            policy.
+    """
+
+class GameStats:
+    """Running statistics over finished games, computed natively.
+
+    Record games as they finish, then ``merge`` the stats of separate
+    workers; stats pickle, so they can be sent back from worker processes.
+
+    Args:
+        bin_width: Width of the ``score_histogram`` bins.
+        heatmaps: Also collect ``heatmaps`` of where play happens on the
+            board, replaying every recorded game.
+        branching: Also collect ``branching``, how much choice and chance
+            every move had, replaying every recorded game.
+
+    Raises:
+        ValueError: Unless ``bin_width`` is positive.
+    """
+
+    def __init__(
+        self, bin_width: int = 1024, heatmaps: bool = False, branching: bool = False
+    ) -> None: ...
+    def record(self, game: Game) -> None:
+        """Add a finished ``Game``, including the directions it was played in."""
+
+    def merge(self, other: GameStats) -> None:
+        """Add every game recorded in ``other``.
+
+        Raises:
+            ValueError: If the score bins differ in width, or only one of the
+                stats collects heatmaps or branching.
+        """
+
+    @property
+    def bin_width(self) -> int: ...
+    @property
+    def games(self) -> int: ...
+    @property
+    def wins(self) -> int:
+        """Games that reached the victory tile."""
+    @property
+    def win_rate(self) -> float: ...
+    @property
+    def mean_score(self) -> float: ...
+    @property
+    def moves(self) -> int:
+        """Moves over all games."""
+    @property
+    def mean_moves(self) -> float:
+        """Average game length in moves."""
+    @property
+    def shortest(self) -> int:
+        """Fewest moves in a game, ``0`` before any game."""
+    @property
+    def longest(self) -> int:
+        """Most moves in a game."""
+    @property
+    def max_tiles(self) -> dict[int, int]:
+        """``{max_tile: games}``."""
+    @property
+    def score_histogram(self) -> dict[int, int]:
+        """``{bin_start: games}`` for bins ``bin_width`` wide."""
+    @property
+    def direction_counts(self) -> dict[Direction, int]:
+        """``{Direction: moves}`` over all recorded games."""
+    @property
+    def heatmaps(self) -> dict[str, Any] | None:
+        """``{"max_tile", "merges", "spawns"}`` as NumPy ``uint64`` arrays of
+        shape ``(4, 4)``, indexed ``[row, col]``, or ``None`` unless collected:
+        positions with their largest tile in each cell (ties count in every
+        cell), merges by the cell of the merged tile and spawns by cell."""
+    @property
+    def branching(self) -> dict[str, Any] | None:
+        """By move number, the start at ``0``, or ``None`` unless collected.
+
+        ``positions`` (``uint64``) counts the games that played a move there;
+        ``legal_moves``, ``successors`` (distinct boards a move and a spawn
+        lead to) and ``spawn_entropy`` (bits, of the spawn after the move
+        played) are ``float64`` averages over them.
+        """
+    def to_dict(self) -> dict[str, Any]:
+        """Every field as plain Python values.
+
+        ``directions`` lists the move counts by action id (Down=0, Right=1,
+        Up=2, Left=3), ``heatmaps`` holds nested lists and ``branching`` the
+        totals by move number (the entropy in millionths of a bit), or
+        ``None``.
+        """
+
+    @staticmethod
+    def from_dict(d: dict[str, Any]) -> GameStats:
+        """Rebuild stats from ``to_dict`` output.
+
+        Raises:
+            ValueError: For a missing field or a non-positive ``bin_width``.
+        """
+
+def run_cli(args: list[str] | None = None) -> None:
+    """Run the interactive terminal client (what ``python -m akioi_2048`` does).
+
+    Arrow keys or WASD move, ``u`` undoes, ``v`` saves, ``o`` loads and ``q``
+    quits. Without a terminal, keys are read line by line from stdin.
+
+    Args:
+        args: Command-line arguments without the program name, e.g.
+            ``["--seed", "7", "--rules", "mercy"]``; ``["--help"]`` lists them.
+
+    Raises:
+        ValueError: For bad arguments or an unreadable save file.
+    """
+
+def benchmark(seconds: float = 0.5) -> list[dict[str, Any]]:
+    """Measure native engine throughput (what ``python -m akioi_2048.bench`` prints).
+
+    Cases: ``step``, ``step_detailed``, ``step_with_animation``, ``game``
+    (``Game.step``) and ``agent:random``, each playing random moves without
+    crossing into Python. The GIL is released while measuring.
+
+    Args:
+        seconds: Minimum time spent on each case.
+
+    Returns:
+        One ``{"name", "steps", "seconds", "steps_per_second"}`` per case.
+    """
+
+def render(board: list[list[int]], style: str = "unicode") -> str:
+    """Draw a board as an aligned multi-line string.
+
+    Args:
+        board: 4x4 board; tiles are drawn as-is, without validation.
+        style: ``"unicode"`` (box-drawing grid, multipliers as ``×2``),
+            ``"ascii"`` (``+-|`` grid, multipliers as ``x2``) or ``"ansi"``
+            (256-colour tiles for terminals).
+
+    Returns:
+        The board, one line per text row, without a trailing newline.
+
+    Raises:
+        ValueError: For an unknown style.
+    """
+
+def parse_notation(text: str) -> list[list[int]]:
+    """Read a board from its one-line notation.
+
+    Rows run top to bottom, split by ``/``, each with its four cells: ``.``
+    for an empty cell, a number for a number tile, ``x`` and a factor for a
+    multiplier (``x2``), ``#`` for a blocker and ``*`` for a bomb. A ``,``
+    or a space separates two numbers, so ``"2.4./..../.x2../8,8.#"`` is a
+    board whose bottom row is ``[8, 8, 0, BLOCKER]``.
+
+    Args:
+        text: The notation; tiles are read as written, without validation.
+
+    Returns:
+        The board.
+
+    Raises:
+        ValueError: For text that is not a board notation.
+    """
+
+def to_notation(board: list[list[int]]) -> str:
+    """Write a board in the notation ``parse_notation`` reads, e.g.
+    ``"2.4./..../.x2../8,8.#"``."""
+
+class BoardBuilder:
+    """A board built cell by cell, every tile checked under the rules as it
+    is placed, for test scenarios and puzzles.
+
+    ``set`` and ``clear`` return the builder, so calls chain.
+
+    Raises:
+        InvalidBoardError: If ``board`` holds a tile invalid under the rules.
+    """
+
+    def __init__(
+        self, board: list[list[int]] | None = None, rules: Rules | None = None
+    ) -> None: ...
+    def set(self, row: int, col: int, value: int) -> BoardBuilder:
+        """Put ``value`` at ``row``, ``col`` (from 0, top left); ``0`` empties it.
+
+        Raises:
+            IndexError: For a cell off the board.
+            InvalidBoardError: For a tile invalid under the rules.
+        """
+    def clear(self) -> BoardBuilder:
+        """Empty every cell."""
+    def validate(self) -> list[str]:
+        """Why no game under the rules reaches the board, such as two winning
+        tiles or a lone tile; empty when nothing rules it out."""
+    @property
+    def board(self) -> list[list[int]]: ...
+    @property
+    def rules(self) -> Rules: ...
+
+def render_image(
+    board: list[list[int]], path: str | PathLike[str], theme: str = "light"
+) -> None:
+    """Save a picture of a board as PNG or SVG.
+
+    Tile colours match the web version; multiplier tiles are purple and
+    labelled ``×1``/``×2``/``×4``.
+
+    Args:
+        board: 4x4 board.
+        path: Destination; the format follows its extension (``.png`` or ``.svg``).
+        theme: ``"light"`` or ``"dark"`` frame and empty cells.
+
+    Raises:
+        ValueError: For an unknown theme or file extension.
     """
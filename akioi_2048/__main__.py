@@ -0,0 +1,10 @@
+import sys
+
+from . import run_cli
+
+try:
+    run_cli(sys.argv[1:])
+except ValueError as e:
+    sys.exit(f"akioi-2048: {e}")
+except KeyboardInterrupt:
+    sys.exit(130)
@@ -0,0 +1,82 @@
+"""Throughput table: ``python -m akioi_2048.bench [--seconds S]``.
+
+Native cases come from ``benchmark``; the ``python:`` cases drive the same
+APIs from a Python loop, so the difference is the per-call binding overhead.
+"""
+
+import argparse
+import random
+import sys
+import time
+from typing import Any, Callable, Dict, List, Optional
+
+from . import Direction, Game, State, benchmark, init, step
+
+DIRECTIONS = list(Direction)
+
+
+def _measure(name: str, seconds: float, fn: Callable[[], None]) -> Dict[str, Any]:
+    steps = 0
+    started = time.perf_counter()
+    while True:
+        for _ in range(256):
+            fn()
+        steps += 256
+        elapsed = time.perf_counter() - started
+        if elapsed >= seconds:
+            return {
+                "name": name,
+                "steps": steps,
+                "seconds": elapsed,
+                "steps_per_second": steps / elapsed,
+            }
+
+
+def python_cases(seconds: float) -> List[Dict[str, Any]]:
+    rng = random.Random(0)
+    board = [init()]
+
+    def step_once() -> None:
+        next_board, _, state = step(board[0], rng.choice(DIRECTIONS))
+        board[0] = next_board if state is State.Continue else init()
+
+    game = [Game(seed=0)]
+
+    def game_once() -> None:
+        if game[0].step(rng.choice(DIRECTIONS))[2] is not State.Continue:
+            game[0] = Game(seed=0)
+
+    def in_place_once() -> None:
+        if game[0].step_in_place(rng.choice(DIRECTIONS))[1] is not State.Continue:
+            game[0] = Game(seed=0)
+
+    return [
+        _measure("python:step", seconds, step_once),
+        _measure("python:Game.step", seconds, game_once),
+        _measure("python:Game.step_in_place", seconds, in_place_once),
+    ]
+
+
+def format_table(results: List[Dict[str, Any]]) -> str:
+    width = max(len(r["name"]) for r in results)
+    lines = [f"{'case':<{width}}  {'steps':>12}  {'steps/s':>14}"]
+    for r in results:
+        lines.append(
+            f"{r['name']:<{width}}  {r['steps']:>12,}  {r['steps_per_second']:>14,.0f}"
+        )
+    return "\n".join(lines)
+
+
+def main(argv: Optional[List[str]] = None) -> int:
+    parser = argparse.ArgumentParser(prog="python -m akioi_2048.bench")
+    parser.add_argument(
+        "--seconds", type=float, default=0.5, help="minimum time per case"
+    )
+    args = parser.parse_args(argv)
+    results = benchmark(args.seconds) + python_cases(args.seconds)
+    print(format_table(results))
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,123 @@
+//! Microbenchmarks of the engine's hot paths, for tracking performance
+//! across optimizations: `cargo bench` from `core/bench`.
+//!
+//! Criterion keeps each run's results under `target/criterion` and reports
+//! the next run against them. Positions come from seeded games, so every
+//! run times the same boards: a mix of early, middle and late ones.
+
+use std::hint::black_box;
+
+use akioi_2048_core::arena::step_batch;
+use akioi_2048_core::search::{Weights, expectimax};
+use akioi_2048_core::{
+    ALL_DIRECTIONS, Board, Direction, Game, Rules, State, single_step, slide_column,
+};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+/// Positions [`single_step`] and [`slide_column`] run over per iteration
+const POSITIONS: usize = 1024;
+
+/// Every position of seeded games played to the end, `count` of them, each
+/// game moving in the first legal direction of a rotating order
+fn positions(rules: Rules, count: usize) -> Vec<Board> {
+    let mut boards = Vec::with_capacity(count);
+    for seed in 0.. {
+        let mut game = Game::with_rules(seed, rules).expect("benchmarked rules are valid");
+        for ply in 0.. {
+            if boards.len() == count {
+                return boards;
+            }
+            if game.state() != State::Continue {
+                break;
+            }
+            boards.push(game.board());
+            let direction = (0..4)
+                .map(|i| ALL_DIRECTIONS[(ply + i) % 4])
+                .find(|&d| game.is_legal(d))
+                .expect("a game that goes on has a move");
+            game.step(direction);
+        }
+    }
+    unreachable!("games go on until there are enough positions")
+}
+
+fn bench_single_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("single_step");
+    group.throughput(Throughput::Elements((POSITIONS * 4) as u64));
+    for (name, rules) in [("akioi", Rules::AKIOI), ("classic", Rules::CLASSIC)] {
+        let boards = positions(rules, POSITIONS);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for board in &boards {
+                    for direction in ALL_DIRECTIONS {
+                        black_box(single_step(black_box(board), direction, &rules));
+                    }
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_slide_column(c: &mut Criterion) {
+    let mut group = c.benchmark_group("slide_column");
+    group.throughput(Throughput::Elements((POSITIONS * 4) as u64));
+    for (name, rules) in [("akioi", Rules::AKIOI), ("classic", Rules::CLASSIC)] {
+        let columns: Vec<[i32; 4]> = positions(rules, POSITIONS)
+            .iter()
+            .flat_map(|board| (0..4).map(|c| board.map(|row| row[c])))
+            .collect();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for &column in &columns {
+                    black_box(slide_column(black_box(column), &rules));
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_step_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("step_batch");
+    let available = std::thread::available_parallelism().map_or(1, usize::from);
+    // one thread, and every core where there are more
+    let mut thread_counts = vec![1];
+    if available > 1 {
+        thread_counts.push(available);
+    }
+    for size in [256, 4096] {
+        let boards = positions(Rules::AKIOI, size);
+        let actions: Vec<Direction> = (0..size).map(|i| ALL_DIRECTIONS[i % 4]).collect();
+        group.throughput(Throughput::Elements(size as u64));
+        for &threads in &thread_counts {
+            let id = BenchmarkId::new(format!("{threads} threads"), size);
+            group.bench_with_input(id, &boards, |b, boards| {
+                b.iter(|| step_batch(boards, &actions, &Rules::AKIOI, 0, 0, threads));
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_expectimax(c: &mut Criterion) {
+    let mut group = c.benchmark_group("expectimax");
+    group.sample_size(10);
+    let board = positions(Rules::AKIOI, 200)[199];
+    let weights = Weights::default();
+    for depth in [1, 2, 3] {
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &depth, |b, &depth| {
+            b.iter(|| expectimax(black_box(&board), &Rules::AKIOI, depth, &weights));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_single_step,
+    bench_slide_column,
+    bench_step_batch,
+    bench_expectimax
+);
+criterion_main!(benches);
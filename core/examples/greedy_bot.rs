@@ -0,0 +1,29 @@
+//! A one-ply greedy bot driven through [`Game::play`].
+//!
+//! Run with `cargo run -p akioi-2048-core --example greedy_bot -- [seed]`.
+
+use akioi_2048_core::{Board, Engine, Game, Move};
+
+/// The legal move with the biggest immediate score
+fn greedy(board: &Board) -> Move {
+    let engine = Engine::AKIOI;
+    engine
+        .legal_moves(board)
+        .max_by_key(|&mv| engine.slide(board, mv).1)
+        .unwrap_or(Move::Down)
+}
+
+fn main() {
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(42);
+    let mut game = Game::new(seed);
+
+    let moves = game.play(greedy).count();
+    println!(
+        "seed {seed}: {:?} after {moves} moves, score {}",
+        game.state(),
+        game.score()
+    );
+}
@@ -0,0 +1,65 @@
+//! Property check of the engine against the reference model: random boards
+//! under every rule variant, each slid in all four directions.
+//!
+//! Run with `cargo run -p akioi-2048-core --example reference_check -- [cases] [seed]`.
+
+use akioi_2048_core::reference;
+use akioi_2048_core::{ALL_DIRECTIONS, Board, Engine, Rules};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Tiles weighted toward the edge cases: gaps, equal neighbours, the
+/// largest tiles and every multiplier
+const TILES: [i32; 16] = [
+    0, 0, 0, 0, 2, 2, 4, 8, 16, 32_768, 65_536, -1, -1, -2, -4, -8,
+];
+
+fn random_board(rng: &mut StdRng, rules: &Rules) -> Board {
+    let mut board = [[0; 4]; 4];
+    for tile in board.iter_mut().flatten() {
+        *tile = TILES[rng.random_range(..TILES.len())];
+        if *tile < 0 && !rules.is_multiplier(*tile) {
+            *tile = 0;
+        }
+    }
+    board
+}
+
+fn main() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let cases = args.next().and_then(|s| s.parse().ok()).unwrap_or(100_000);
+    let seed = args.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut variants = Vec::new();
+    for max_multiplier in [2, 4, 8] {
+        for multiplier_merge in [true, false] {
+            for require_full_below in [true, false] {
+                variants.push(Rules {
+                    max_multiplier,
+                    multiplier_merge,
+                    require_full_below,
+                    ..Rules::AKIOI
+                });
+            }
+        }
+    }
+
+    for i in 0..cases {
+        let rules = variants[i % variants.len()];
+        let engine = Engine::new(rules)?;
+        let board = random_board(&mut rng, &rules);
+        for dir in ALL_DIRECTIONS {
+            let expected = reference::slide(&board, dir, &rules);
+            let actual = engine.slide(&board, dir);
+            if actual != expected {
+                return Err(format!(
+                    "{} {dir:?} on {board:?}: engine {actual:?}, reference {expected:?}",
+                    rules.tag()
+                ));
+            }
+        }
+    }
+    println!("{cases} boards agree with the reference model");
+    Ok(())
+}
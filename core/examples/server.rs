@@ -0,0 +1,40 @@
+//! Embedding a game in a server: configure it with the builder, stream
+//! every move through a hook and verify the proof chain at the end.
+//!
+//! Run with `cargo run -p akioi-2048-core --example server`.
+
+use std::sync::mpsc;
+
+use akioi_2048_core::proof::verify_chain_with_seed;
+use akioi_2048_core::{ALL_DIRECTIONS, Game, Rules, State};
+
+fn main() -> Result<(), String> {
+    let seed = 2048;
+    let (events, inbox) = mpsc::channel();
+
+    let mut game = Game::builder()
+        .seed(seed)
+        .rules(Rules::MERCY)
+        .proofs(true)
+        .on_move(move |dir, result| {
+            // e.g. forward to connected clients
+            let _ = events.send(format!("{dir:?} moved={} +{}", result.moved, result.delta));
+        })
+        .build()?;
+
+    // Client input: cycle through the directions
+    for dir in ALL_DIRECTIONS.iter().cycle().take(200) {
+        if game.step(*dir).state != State::Continue {
+            break;
+        }
+    }
+
+    for event in inbox.try_iter().take(5) {
+        println!("{event}");
+    }
+    println!("score {} after {} moves", game.score(), game.moves());
+
+    verify_chain_with_seed(game.records(), seed, game.rules())?;
+    println!("{} proof records verified", game.records().len());
+    Ok(())
+}
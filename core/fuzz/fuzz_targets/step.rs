@@ -0,0 +1,174 @@
+//! Random boards and directions through `step`, checked against the
+//! invariants every ruleset keeps: `cargo +nightly fuzz run step` from
+//! `core/`.
+//!
+//! The input picks a ruleset, sixteen tiles from the ones it accepts, a
+//! direction and a spawn seed. A board the rules reject must be refused,
+//! never played; a valid one must step without panicking to a valid board
+//! the reference model accepts, with any blockers in place, no tile appearing
+//! from nowhere, and the slide the mirror and the transpose of the board
+//! give.
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use akioi_2048_core::reference::{slide, verify_step};
+use akioi_2048_core::session::InvalidMoves;
+use akioi_2048_core::spec::spec;
+use akioi_2048_core::stream::Stream;
+use akioi_2048_core::{
+    ALL_DIRECTIONS, BLOCKER, BOMB, Board, Direction, Engine, Overflow, Placement, Rules, Scoring,
+    Variant, validate_board_with,
+};
+use libfuzzer_sys::fuzz_target;
+
+/// Rulesets the first input byte picks from
+const RULES: [Rules; 7] = [
+    Rules::AKIOI,
+    Rules::MERCY,
+    Rules::CLASSIC,
+    Rules::FIBONACCI,
+    Rules {
+        bombs: true,
+        blockers: true,
+        ..Rules::AKIOI
+    },
+    Rules {
+        multiplier_merge: false,
+        require_full_below: false,
+        max_multiplier: 8,
+        scoring: Scoring::Clamp,
+        blockers: true,
+        ..Rules::AKIOI
+    },
+    Rules {
+        variant: Variant::Classic,
+        max_tile: 64,
+        overflow: Overflow::Wrap,
+        spawns: 2,
+        placement: Placement::Edge,
+        ..Rules::AKIOI
+    },
+];
+
+/// Tiles a cell byte picks from under each ruleset: every tile the rules
+/// accept, a blocker and a bomb where allowed and extra empty cells, so that
+/// boards have room to move
+fn palettes() -> &'static [Vec<i32>] {
+    static PALETTES: OnceLock<Vec<Vec<i32>>> = OnceLock::new();
+    PALETTES.get_or_init(|| {
+        RULES
+            .iter()
+            .map(|rules| {
+                let spec = spec(rules, InvalidMoves::Noop).expect("fuzzed rules are valid");
+                let mut palette = spec.tiles;
+                palette.extend([0; 8]);
+                if rules.blockers {
+                    palette.push(BLOCKER);
+                }
+                if rules.bombs {
+                    palette.push(BOMB);
+                }
+                palette
+            })
+            .collect()
+    })
+}
+
+fn mirror(board: &Board) -> Board {
+    board.map(|row| [row[3], row[2], row[1], row[0]])
+}
+
+fn mirror_direction(direction: Direction) -> Direction {
+    match direction {
+        Direction::Left => Direction::Right,
+        Direction::Right => Direction::Left,
+        d => d,
+    }
+}
+
+fn transpose(board: &Board) -> Board {
+    let mut out = [[0; 4]; 4];
+    for (r, row) in board.iter().enumerate() {
+        for (c, &tile) in row.iter().enumerate() {
+            out[c][r] = tile;
+        }
+    }
+    out
+}
+
+fn transpose_direction(direction: Direction) -> Direction {
+    match direction {
+        Direction::Left => Direction::Up,
+        Direction::Up => Direction::Left,
+        Direction::Right => Direction::Down,
+        Direction::Down => Direction::Right,
+    }
+}
+
+fn count(board: &Board, wanted: impl Fn(i32) -> bool) -> usize {
+    board.iter().flatten().filter(|&&t| wanted(t)).count()
+}
+
+fuzz_target!(|data: &[u8]| {
+    let byte = |i: usize| data.get(i).copied().unwrap_or(0);
+    let choice = usize::from(byte(0)) % RULES.len();
+    let (rules, palette) = (RULES[choice], &palettes()[choice]);
+    let mut board = [[0; 4]; 4];
+    for (i, cell) in board.iter_mut().flatten().enumerate() {
+        *cell = palette[usize::from(byte(1 + i)) % palette.len()];
+    }
+    let direction = ALL_DIRECTIONS[usize::from(byte(17)) % 4];
+    let seed = u64::from_le_bytes(std::array::from_fn(|i| byte(18 + i)));
+
+    let engine = Engine::new(rules).expect("fuzzed rules are valid");
+    let mut rng = Stream::new(seed, 0, 0);
+    if validate_board_with(&board, &rules).is_err() {
+        assert!(engine.step(board, direction, &mut rng).is_err());
+        return;
+    }
+    let (next, delta, _) = engine
+        .step(board, direction, &mut rng)
+        .expect("valid boards step");
+
+    // the engine's result is a board of the rules, and the reference
+    // model's move with a spawn it allows
+    validate_board_with(&next, &rules).expect("steps give valid boards");
+    let (slid, score) = engine.slide(&board, direction);
+    assert_eq!((slid, score), slide(&board, direction, &rules));
+    assert_eq!(delta, score);
+    verify_step(&board, direction, &next, &rules).expect("the reference model agrees");
+
+    // blockers keep their cells, a slide never adds tiles and a spawn adds
+    // at most the rules' tiles, only after a move that changed the board
+    for r in 0..4 {
+        for c in 0..4 {
+            assert_eq!(board[r][c] == BLOCKER, next[r][c] == BLOCKER);
+        }
+    }
+    let tiles = |b: &Board| count(b, |t| t != 0);
+    assert!(tiles(&slid) <= tiles(&board));
+    if slid == board {
+        assert_eq!((next, delta), (board, 0));
+    } else {
+        let spawned = tiles(&next) - tiles(&slid);
+        assert!(
+            (1..=usize::from(rules.spawns)).contains(&spawned) || count(&slid, |t| t == 0) == 0
+        );
+    }
+    // numbers only merge into their sum when nothing clears or wraps
+    if rules.variant == Variant::Classic && !rules.bombs && rules.overflow == Overflow::Block {
+        let sum = |b: &Board| b.iter().flatten().filter(|&&t| t > 0).sum::<i32>();
+        assert_eq!(sum(&slid), sum(&board));
+    }
+
+    // every direction is the same slide seen in a mirror or transposed
+    for d in ALL_DIRECTIONS {
+        let (slid, score) = engine.slide(&board, d);
+        let (m, m_score) = engine.slide(&mirror(&board), mirror_direction(d));
+        assert_eq!((mirror(&m), m_score), (slid, score));
+        let (t, t_score) = engine.slide(&transpose(&board), transpose_direction(d));
+        assert_eq!((transpose(&t), t_score), (slid, score));
+    }
+});
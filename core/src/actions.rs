@@ -0,0 +1,48 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A player's move: the direction the tiles slide in
+pub type Move = Direction;
+
+/// All directions in the engine’s canonical order.
+/// Order matters only for checking if any move is possible.
+pub const ALL_DIRECTIONS: [Direction; 4] = [
+    Direction::Down,
+    Direction::Right,
+    Direction::Up,
+    Direction::Left,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    Victory,
+    GameOver,
+    Continue,
+}
+
+/// Why a game left [`State::Continue`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndReason {
+    /// A 65536 tile is on the board, whether or not moves remain
+    VictoryTile,
+    /// No move changes the board
+    NoLegalMoves,
+    /// The game's clock ran out under [`Timeout::Forfeit`](crate::session::Timeout::Forfeit)
+    OutOfTime,
+}
+
+/// A [`State`] with the details behind it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Status {
+    pub state: State,
+    /// `None` while the state is [`State::Continue`]
+    pub reason: Option<EndReason>,
+    /// Some move still changes the board, so play could go on (after a
+    /// victory, say)
+    pub can_continue: bool,
+}
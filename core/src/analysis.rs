@@ -0,0 +1,306 @@
+//! Position analysis for the `akioi-2048 analyze` command: load a position,
+//! read its heuristic features, ask the search for every move's value,
+//! follow the principal variation and keep the line played, annotated.
+//!
+//! An [`Analysis`] executes one command per line and answers in text, so
+//! the command reads from a terminal or a script alike:
+//!
+//! ```
+//! use akioi_2048_core::analysis::Analysis;
+//! use akioi_2048_core::Rules;
+//!
+//! let mut analysis = Analysis::new(Rules::CLASSIC, 7).unwrap();
+//! analysis.execute("load 2 2 0 0/0 0 0 0/0 0 0 0/0 0 0 4").unwrap();
+//! analysis.execute("depth 1").unwrap();
+//! assert!(analysis.execute("suggest").unwrap().contains("Left"));
+//! analysis.execute("best").unwrap();
+//! assert!(analysis.export().contains("# move 1 "));
+//! ```
+
+use std::fmt::Write;
+use std::fs;
+
+use crate::hint::{Hint, hint_with};
+use crate::json::Json;
+use crate::notation::{parse_notation, to_notation};
+use crate::reach::spawn_outcomes_of;
+use crate::render::{Style, render};
+use crate::replay::Replay;
+use crate::search::{Features, SearchOptions, Weights, evaluate};
+use crate::session::Snapshot;
+use crate::{ALL_DIRECTIONS, Board, Direction, Engine, Game, Rules};
+
+/// Commands [`Analysis::execute`] understands
+pub const COMMANDS: &str = "\
+load POSITION  analyse a board: JSON rows or notation such as 2.4./..../.x2../8,8.#
+open FILE      analyse the end of a saved replay
+show           the board, its features and heuristic evaluation
+depth N        search N moves ahead (default 2)
+suggest        the expected value of every move, best first
+pv [N]         the principal variation N moves deep (default 4): the best move, then the likeliest spawn
+move DIR       play a move (left/right/up/down or L/R/U/D)
+best           play the suggested move
+back           take back the last move played
+export FILE    save the line played as a replay annotated with every move's value
+quit           leave";
+
+/// Moves [`Analysis`] searches ahead until told otherwise
+const DEFAULT_DEPTH: u32 = 2;
+
+/// Plies `pv` follows without a count
+const DEFAULT_PV: usize = 4;
+
+/// An analysis session: the position analysed, the search depth and the
+/// line played from the loaded position
+pub struct Analysis {
+    game: Game,
+    depth: u32,
+    seed: u64,
+    /// Game before every move played since the position was loaded, with
+    /// that move's annotation
+    line: Vec<(Snapshot, String)>,
+}
+
+impl Analysis {
+    /// A session on a fresh game under `rules`; `seed` drives the spawns
+    /// after moves played.
+    ///
+    /// # Errors
+    /// Returns an error for inconsistent rules.
+    pub fn new(rules: Rules, seed: u64) -> Result<Analysis, String> {
+        Ok(Analysis {
+            game: Game::with_rules(seed, rules)?,
+            depth: DEFAULT_DEPTH,
+            seed,
+            line: Vec::new(),
+        })
+    }
+
+    /// Analyse `board` from now on, forgetting the line played.
+    ///
+    /// # Errors
+    /// Returns an error for a board invalid under the session's rules.
+    pub fn load(&mut self, board: Board) -> Result<(), String> {
+        crate::validate_board_with(&board, &self.game.rules())?;
+        self.game = Game::builder()
+            .seed(self.seed)
+            .rules(self.game.rules())
+            .start(board, 0)
+            .build()?;
+        self.line.clear();
+        Ok(())
+    }
+
+    /// The current game, at the end of the line played
+    #[must_use]
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Run one command line (see [`COMMANDS`]) and describe the result.
+    ///
+    /// # Errors
+    /// Returns an error for an unknown command, bad arguments or a move that
+    /// does not change the board.
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let line = line.trim();
+        let (command, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let arg = arg.trim();
+        match command {
+            "help" => Ok(COMMANDS.to_string()),
+            "load" => {
+                self.load(parse_position(arg)?)?;
+                Ok(self.show())
+            }
+            "open" => {
+                let text = fs::read_to_string(arg).map_err(|e| format!("{arg}: {e}"))?;
+                let game = Replay::parse(&text)?.game()?;
+                self.seed = game.seed();
+                self.game = game;
+                self.line.clear();
+                Ok(self.show())
+            }
+            "show" => Ok(self.show()),
+            "depth" => {
+                self.depth = arg.parse().map_err(|_| format!("invalid depth: {arg}"))?;
+                Ok(format!("searching {} moves ahead", self.depth))
+            }
+            "suggest" => Ok(match self.hint()? {
+                Some(hint) => {
+                    let mut out = format!("depth {}\n", hint.depth);
+                    for &(direction, value) in &hint.values {
+                        let _ = writeln!(out, "  {:<6}{value:>10.1}", format!("{direction:?}"));
+                    }
+                    out + &hint.summary
+                }
+                None => "no move is left".to_string(),
+            }),
+            "pv" => {
+                let plies = if arg.is_empty() {
+                    DEFAULT_PV
+                } else {
+                    arg.parse().map_err(|_| format!("invalid length: {arg}"))?
+                };
+                self.principal_variation(plies)
+            }
+            "move" => {
+                let direction = parse_direction(arg)?;
+                self.play(direction)
+            }
+            "best" => match self.hint()? {
+                Some(hint) => self.play(hint.direction),
+                None => Err("no move is left".to_string()),
+            },
+            "back" => {
+                let (snapshot, _) = self.line.pop().ok_or("nothing to take back")?;
+                self.game.restore(&snapshot);
+                Ok(self.show())
+            }
+            "export" => {
+                fs::write(arg, self.export()).map_err(|e| format!("{arg}: {e}"))?;
+                Ok(format!("saved to {arg}"))
+            }
+            _ => Err(format!("unknown command: {command} (try `help`)")),
+        }
+    }
+
+    /// The line played as replay text, one `# move N DIR at depth D: …`
+    /// comment per move giving the value of every move of its position
+    #[must_use]
+    pub fn export(&self) -> String {
+        let mut text = self.game.replay().to_text();
+        for (_, note) in &self.line {
+            let _ = writeln!(text, "# {note}");
+        }
+        text
+    }
+
+    fn hint(&self) -> Result<Option<Hint>, String> {
+        self.hint_after(&self.game.board(), self.game.history().len() as u64)
+    }
+
+    /// The hint for `board` with `moves` moves played before it, which the
+    /// search needs under [`Rules::spawn_every`]
+    fn hint_after(&self, board: &Board, moves: u64) -> Result<Option<Hint>, String> {
+        let options = SearchOptions {
+            depth: self.depth,
+            moves_played: moves,
+            ..SearchOptions::default()
+        };
+        hint_with(board, &self.game.rules(), &options)
+    }
+
+    /// The board, drawn and in notation, with its score, features and
+    /// evaluation
+    fn show(&self) -> String {
+        let board = self.game.board();
+        let features: Vec<String> = Features::of(&board)
+            .named()
+            .iter()
+            .map(|(name, value)| format!("{name} {value}"))
+            .collect();
+        format!(
+            "{}  score {}  moves {}\n{}\n{}\nevaluation {:.1}: {}",
+            self.game.rules().tag(),
+            self.game.score(),
+            self.game.history().len(),
+            render(&board, Style::Ascii),
+            to_notation(&board),
+            evaluate(&board, &Weights::default()),
+            features.join(", ")
+        )
+    }
+
+    /// Play `direction`, noting the values the search gives every move
+    fn play(&mut self, direction: Direction) -> Result<String, String> {
+        if !self.game.is_legal(direction) {
+            return Err(format!("{direction:?} does not change the board"));
+        }
+        let values = self.hint()?.map(|hint| hint.values).unwrap_or_default();
+        let values: Vec<String> = values
+            .iter()
+            .map(|(d, v)| format!("{d:?} {v:.1}"))
+            .collect();
+        let note = format!(
+            "move {} {direction:?} at depth {}: {}",
+            self.game.history().len() + 1,
+            self.depth,
+            values.join(", ")
+        );
+        let before = self.game.snapshot();
+        self.game.step(direction);
+        self.line.push((before, note));
+        Ok(self.show())
+    }
+
+    /// Follow the best move `plies` times, each followed by its likeliest
+    /// spawn, without playing any of it
+    fn principal_variation(&self, plies: usize) -> Result<String, String> {
+        let rules = self.game.rules();
+        let engine = Engine::new(rules)?;
+        let mut board = self.game.board();
+        let mut out = String::new();
+        let played = self.game.history().len() as u64;
+        for (ply, moves) in (1..=plies).zip(played..) {
+            let Some(hint) = self.hint_after(&board, moves)? else {
+                out.push_str("no move is left");
+                return Ok(out);
+            };
+            let (slid, _) = engine.slide(&board, hint.direction);
+            let spawn = spawn_outcomes_of(&slid, &rules, rules.tiles_after(moves))
+                .into_iter()
+                .filter(|&(child, _)| child != slid)
+                .reduce(|best, s| if s.1 > best.1 { s } else { best });
+            let _ = write!(out, "{ply}. {:?} ({:.1})", hint.direction, hint.values[0].1);
+            board = match spawn {
+                Some((child, p)) => {
+                    let _ = write!(
+                        out,
+                        ", spawn {} ({:.0}%)",
+                        spawned(&slid, &child),
+                        p * 100.0
+                    );
+                    child
+                }
+                None => slid,
+            };
+            let _ = writeln!(out, "\n{}", render(&board, Style::Ascii));
+        }
+        out.pop();
+        Ok(out)
+    }
+}
+
+/// The tiles `child` has and `slid` lacks, as `T at row R, column C`
+/// joined by `and`
+fn spawned(slid: &Board, child: &Board) -> String {
+    let tiles: Vec<String> = (0..4)
+        .flat_map(|r| (0..4).map(move |c| (r, c)))
+        .filter(|&(r, c)| slid[r][c] != child[r][c])
+        .map(|(r, c)| format!("{} at row {}, column {}", child[r][c], r + 1, c + 1))
+        .collect();
+    tiles.join(" and ")
+}
+
+/// A board as JSON rows (`[[2, 0, 0, 0], …]`) or in the
+/// [notation](crate::notation)
+///
+/// # Errors
+/// Returns an error for text of neither form.
+pub fn parse_position(text: &str) -> Result<Board, String> {
+    if text.starts_with('[') {
+        return crate::server::board_param(&Json::parse(text, "board")?);
+    }
+    parse_notation(text)
+}
+
+/// A direction by name or initial, in any case
+fn parse_direction(name: &str) -> Result<Direction, String> {
+    ALL_DIRECTIONS
+        .into_iter()
+        .find(|d| {
+            let full = format!("{d:?}");
+            full.eq_ignore_ascii_case(name) || full[..1].eq_ignore_ascii_case(name)
+        })
+        .ok_or_else(|| format!("unknown direction: {name}"))
+}
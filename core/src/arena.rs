@@ -0,0 +1,301 @@
+//! Many independent games stepped together, in the style of vectorized
+//! reinforcement-learning environments.
+
+use rand::RngCore;
+
+use crate::pool::Pool;
+use crate::search::Features;
+use crate::session::{Game, random_seed};
+use crate::stats::GameStats;
+use crate::stream::Stream;
+use crate::{Board, Direction, Engine, Rules, State};
+
+/// Outcome of one game in an [`Arena::step`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArenaStep {
+    /// Board after the move, before any automatic reset
+    pub board: Board,
+    pub delta: i32,
+    pub state: State,
+    /// The game ended on this move
+    pub done: bool,
+}
+
+/// A fixed number of games played side by side.
+///
+/// Game `k` of env `i` is seeded from the [`Stream`] of the arena seed, `i`
+/// and `k`, so equal arena seeds and actions give equal runs, and an env's
+/// games do not depend on when the others reset. With `auto_reset` a game that ends is
+/// replaced by a fresh one right away. Every game that ends is added to the
+/// arena's [`stats`](Self::stats).
+///
+/// Large arenas step their games on up to [`threads`](Self::set_threads)
+/// threads, which the arena keeps from one step to the next. Every game has
+/// its own RNG, so runs do not depend on the thread count.
+///
+/// ```
+/// use akioi_2048_core::arena::Arena;
+/// use akioi_2048_core::{Direction, Rules};
+///
+/// let mut arena = Arena::new(4, Some(7), Rules::AKIOI, true).unwrap();
+/// let steps = arena.step(&[Direction::Down; 4]).unwrap();
+/// assert_eq!(steps.len(), 4);
+/// ```
+pub struct Arena {
+    rules: Rules,
+    seed: u64,
+    games: Vec<Game>,
+    /// Games started so far in each env
+    episodes: Vec<u64>,
+    auto_reset: bool,
+    stats: GameStats,
+    threads: usize,
+    pool: Pool,
+}
+
+impl Arena {
+    /// Start `n_envs` games; a random arena seed is picked when `seed` is
+    /// `None`.
+    ///
+    /// # Errors
+    /// Returns an error if the rules are inconsistent.
+    pub fn new(
+        n_envs: usize,
+        seed: Option<u64>,
+        rules: Rules,
+        auto_reset: bool,
+    ) -> Result<Self, String> {
+        rules.validate()?;
+        let seed = seed.unwrap_or_else(random_seed);
+        let games = (0..n_envs)
+            .map(|i| Game::with_rules(episode_seed(seed, i, 0), rules))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            rules,
+            seed,
+            games,
+            episodes: vec![1; n_envs],
+            auto_reset,
+            stats: GameStats::default(),
+            threads: 1,
+            pool: Pool::new(1),
+        })
+    }
+
+    /// Replace the games at `indices` with fresh ones.
+    ///
+    /// # Errors
+    /// Returns an error if an index is out of range; no game is reset then.
+    pub fn reset(&mut self, indices: &[usize]) -> Result<(), String> {
+        if let Some(&i) = indices.iter().find(|&&i| i >= self.games.len()) {
+            return Err(format!(
+                "env index {i} out of range for {} envs",
+                self.games.len()
+            ));
+        }
+        for &i in indices {
+            self.reset_one(i);
+        }
+        Ok(())
+    }
+
+    /// Replace every game with a fresh one
+    pub fn reset_all(&mut self) {
+        for i in 0..self.games.len() {
+            self.reset_one(i);
+        }
+    }
+
+    fn reset_one(&mut self, i: usize) {
+        let seed = episode_seed(self.seed, i, self.episodes[i]);
+        self.episodes[i] += 1;
+        self.games[i] = Game::with_rules(seed, self.rules).expect("rules were validated");
+    }
+
+    /// Apply `actions[i]` to game `i`.
+    ///
+    /// # Errors
+    /// Returns an error unless there is exactly one action per game.
+    pub fn step(&mut self, actions: &[Direction]) -> Result<Vec<ArenaStep>, String> {
+        if actions.len() != self.games.len() {
+            return Err(format!(
+                "expected {} actions, got {}",
+                self.games.len(),
+                actions.len()
+            ));
+        }
+        let (games, threads) = (&mut self.games[..], self.threads);
+        let results = self.pool.map(games, threads, |i, game| {
+            let was_over = game.is_over();
+            let result = game.step(actions[i]);
+            (was_over, result.board, result.delta, result.state)
+        });
+        let mut steps = Vec::with_capacity(actions.len());
+        for (i, (was_over, board, delta, state)) in results.into_iter().enumerate() {
+            let done = self.games[i].is_over();
+            if done && !was_over {
+                self.stats.record(&self.games[i]);
+            }
+            steps.push(ArenaStep {
+                board,
+                delta,
+                state,
+                done,
+            });
+            if done && self.auto_reset {
+                self.reset_one(i);
+            }
+        }
+        Ok(steps)
+    }
+
+    /// Collect [`Heatmaps`](crate::stats::Heatmaps) in the
+    /// [`stats`](Self::stats) of the games that end from now on, or stop
+    pub fn set_heatmaps(&mut self, enabled: bool) {
+        if enabled {
+            self.stats = std::mem::take(&mut self.stats).with_heatmaps();
+        } else {
+            self.stats.heatmaps = None;
+        }
+    }
+
+    /// Collect [`Branching`](crate::stats::Branching) in the
+    /// [`stats`](Self::stats) of the games that end from now on, or stop
+    pub fn set_branching(&mut self, enabled: bool) {
+        if enabled {
+            self.stats = std::mem::take(&mut self.stats).with_branching();
+        } else {
+            self.stats.branching = None;
+        }
+    }
+
+    /// Step games on up to `threads` threads (at least one, at most one per
+    /// core), started here and kept until the next call or the arena is
+    /// dropped
+    pub fn set_threads(&mut self, threads: usize) {
+        let threads = threads.max(1);
+        if threads != self.threads {
+            self.threads = threads;
+            self.pool = Pool::new(threads);
+        }
+    }
+
+    #[must_use]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// The games, in env order
+    #[must_use]
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
+    /// Statistics of every game that ended in this arena
+    #[must_use]
+    pub fn stats(&self) -> &GameStats {
+        &self.stats
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    #[must_use]
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+}
+
+/// Seed of game `episode` of env `env` in an arena seeded with `seed`
+fn episode_seed(seed: u64, env: usize, episode: u64) -> u64 {
+    Stream::new(seed, env as u64, episode).next_u64()
+}
+
+/// Apply `actions[i]` to `boards[i]` for every board, on up to `threads`
+/// threads of a pool the batch functions share and keep between calls.
+///
+/// The spawn after board `i` is drawn from the [`Stream`] of `seed`, `i`
+/// and `step`, so results depend on those alone, never on the thread count.
+/// Pass the step number of the simulation as `step` to draw fresh spawns on
+/// every call.
+///
+/// ```
+/// use akioi_2048_core::arena::step_batch;
+/// use akioi_2048_core::{Direction, Rules};
+///
+/// let boards = vec![[[2, 2, 0, 0], [0; 4], [0; 4], [0; 4]]; 600];
+/// let actions = vec![Direction::Left; 600];
+/// let one = step_batch(&boards, &actions, &Rules::AKIOI, 3, 0, 1).unwrap();
+/// let four = step_batch(&boards, &actions, &Rules::AKIOI, 3, 0, 4).unwrap();
+/// assert_eq!(one, four);
+/// assert!(one.iter().all(|&(_, delta, _)| delta == 4));
+/// let next = step_batch(&boards, &actions, &Rules::AKIOI, 3, 1, 4).unwrap();
+/// assert_ne!(one, next);
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules, an invalid board (naming its
+/// index) or unless there is exactly one action per board.
+pub fn step_batch(
+    boards: &[Board],
+    actions: &[Direction],
+    rules: &Rules,
+    seed: u64,
+    step: u64,
+    threads: usize,
+) -> Result<Vec<(Board, i32, State)>, String> {
+    if actions.len() != boards.len() {
+        return Err(format!(
+            "expected {} actions, got {}",
+            boards.len(),
+            actions.len()
+        ));
+    }
+    let engine = Engine::new(*rules)?;
+    for (i, board) in boards.iter().enumerate() {
+        crate::validate_board_with(board, rules).map_err(|e| format!("board {i}: {e}"))?;
+    }
+    let mut boards = boards.to_vec();
+    Ok(Pool::shared().map(&mut boards, threads, |i, board| {
+        let mut rng = Stream::new(seed, i as u64, step);
+        engine
+            .step(*board, actions[i], &mut rng)
+            .expect("boards were validated")
+    }))
+}
+
+/// The heuristic [`Features`] of every board, in order, on up to `threads`
+/// threads of the pool [`step_batch`] uses; for fitting value functions on
+/// features extracted in bulk.
+///
+/// ```
+/// use akioi_2048_core::arena::features_batch;
+/// use akioi_2048_core::search::Features;
+/// use akioi_2048_core::Rules;
+///
+/// let boards = vec![[[2, 2, 0, 0], [0; 4], [0; 4], [0, 0, 0, 4]]; 600];
+/// let features = features_batch(&boards, &Rules::AKIOI, 4).unwrap();
+/// assert_eq!(features.len(), 600);
+/// assert!(features.iter().all(|f| *f == Features::of(&boards[0])));
+/// ```
+///
+/// # Errors
+/// Returns an error for an invalid board, naming its index.
+pub fn features_batch(
+    boards: &[Board],
+    rules: &Rules,
+    threads: usize,
+) -> Result<Vec<Features>, String> {
+    for (i, board) in boards.iter().enumerate() {
+        crate::validate_board_with(board, rules).map_err(|e| format!("board {i}: {e}"))?;
+    }
+    let mut boards = boards.to_vec();
+    Ok(Pool::shared().map(&mut boards, threads, |_, board| Features::of(board)))
+}
@@ -0,0 +1,372 @@
+//! Apache Arrow IPC files (Feather v2) of flat tables, for loading results
+//! with `pandas.read_feather` or `polars.read_ipc` without Python loops.
+//!
+//! Every table is written as one record batch of non-nullable columns,
+//! uncompressed, with the flatbuffers metadata laid out by hand.
+
+use std::io::{self, Write};
+
+const MAGIC: &[u8; 6] = b"ARROW1";
+/// `MetadataVersion::V5`
+const VERSION: i16 = 4;
+
+/// One column: its values, all of one Arrow type
+#[derive(Clone, Debug, PartialEq)]
+pub enum Column {
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    UInt64(Vec<u64>),
+    Float64(Vec<f64>),
+    Bool(Vec<bool>),
+    Utf8(Vec<String>),
+}
+
+impl Column {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Int32(v) => v.len(),
+            Column::Int64(v) => v.len(),
+            Column::UInt64(v) => v.len(),
+            Column::Float64(v) => v.len(),
+            Column::Bool(v) => v.len(),
+            Column::Utf8(v) => v.len(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// `Type` union tag and table of the field
+    fn field_type(&self) -> (u8, Value) {
+        let int = |bits: i32, signed: bool| {
+            Value::Table(vec![Some(Value::I32(bits)), Some(Value::Bool(signed))])
+        };
+        match self {
+            Column::Int32(_) => (2, int(32, true)),
+            Column::Int64(_) => (2, int(64, true)),
+            Column::UInt64(_) => (2, int(64, false)),
+            // precision DOUBLE
+            Column::Float64(_) => (3, Value::Table(vec![Some(Value::I16(2))])),
+            Column::Utf8(_) => (5, Value::Table(Vec::new())),
+            Column::Bool(_) => (6, Value::Table(Vec::new())),
+        }
+    }
+
+    /// Data buffers after the (empty) validity bitmap
+    fn buffers(&self) -> Vec<Vec<u8>> {
+        fn bytes<const N: usize, T>(values: &[T], to: impl Fn(&T) -> [u8; N]) -> Vec<u8> {
+            values.iter().flat_map(to).collect()
+        }
+        match self {
+            Column::Int32(v) => vec![bytes(v, |x| x.to_le_bytes())],
+            Column::Int64(v) => vec![bytes(v, |x| x.to_le_bytes())],
+            Column::UInt64(v) => vec![bytes(v, |x| x.to_le_bytes())],
+            Column::Float64(v) => vec![bytes(v, |x| x.to_le_bytes())],
+            Column::Bool(v) => {
+                let mut bits = vec![0u8; v.len().div_ceil(8)];
+                for (i, _) in v.iter().enumerate().filter(|&(_, &b)| b) {
+                    bits[i / 8] |= 1 << (i % 8);
+                }
+                vec![bits]
+            }
+            Column::Utf8(v) => {
+                let mut offsets = vec![0i32];
+                let mut data = Vec::new();
+                for s in v {
+                    data.extend_from_slice(s.as_bytes());
+                    offsets.push(i32::try_from(data.len()).expect("strings under 2 GiB"));
+                }
+                vec![bytes(&offsets, |x| x.to_le_bytes()), data]
+            }
+        }
+    }
+}
+
+/// Named columns of equal length
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Table {
+    pub columns: Vec<(String, Column)>,
+}
+
+impl Table {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a column, builder style
+    #[must_use]
+    pub fn column(mut self, name: impl Into<String>, column: Column) -> Self {
+        self.columns.push((name.into(), column));
+        self
+    }
+
+    /// Rows, the length of the first column
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.columns.first().map_or(0, |(_, c)| c.len())
+    }
+}
+
+/// Write `table` as an Arrow IPC file.
+///
+/// ```
+/// use akioi_2048_core::arrow::{Column, Table, write};
+///
+/// let table = Table::new()
+///     .column("game", Column::Int32(vec![0, 1]))
+///     .column("won", Column::Bool(vec![false, true]));
+/// let mut file = Vec::new();
+/// write(&mut file, &table).unwrap();
+/// assert!(file.starts_with(b"ARROW1") && file.ends_with(b"ARROW1"));
+/// ```
+///
+/// # Errors
+/// Returns an error if the columns differ in length or writing fails.
+pub fn write<W: Write>(mut out: W, table: &Table) -> io::Result<()> {
+    let rows = table.rows();
+    if let Some((name, _)) = table.columns.iter().find(|(_, c)| c.len() != rows) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("column {name} has a different length than the others"),
+        ));
+    }
+    let mut file = MAGIC.to_vec();
+    file.extend_from_slice(&[0, 0]);
+
+    let schema = schema(table);
+    message(&mut file, 1, schema.clone(), &[]);
+
+    let mut body = Vec::new();
+    let mut buffers = Vec::new();
+    let mut push = |bytes: &[u8], buffers: &mut Vec<Value>| {
+        buffers.push(Value::Struct(pair(body.len() as i64, bytes.len() as i64)));
+        body.extend_from_slice(bytes);
+        body.resize(body.len().next_multiple_of(8), 0);
+    };
+    let mut nodes = Vec::new();
+    for (_, column) in &table.columns {
+        nodes.push(Value::Struct(pair(column.len() as i64, 0)));
+        // no nulls: an empty validity bitmap
+        push(&[], &mut buffers);
+        for buffer in column.buffers() {
+            push(&buffer, &mut buffers);
+        }
+    }
+    let batch = Value::Table(vec![
+        Some(Value::I64(rows as i64)),
+        Some(Value::Structs(nodes)),
+        Some(Value::Structs(buffers)),
+    ]);
+    let block = message(&mut file, 3, batch, &body);
+
+    // end of stream
+    file.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]);
+    let (offset, meta, body_len) = block;
+    let mut record = pair(offset as i64, 0);
+    record[8..12].copy_from_slice(&(meta as i32).to_le_bytes());
+    record.extend_from_slice(&(body_len as i64).to_le_bytes());
+    let footer = Value::Table(vec![
+        Some(Value::I16(VERSION)),
+        Some(schema),
+        Some(Value::Structs(Vec::new())),
+        Some(Value::Structs(vec![Value::Struct(record)])),
+    ]);
+    let footer = flatbuffer(&footer);
+    file.extend_from_slice(&footer);
+    file.extend_from_slice(&(footer.len() as i32).to_le_bytes());
+    file.extend_from_slice(MAGIC);
+    out.write_all(&file)
+}
+
+fn schema(table: &Table) -> Value {
+    let fields = table
+        .columns
+        .iter()
+        .map(|(name, column)| {
+            let (tag, ty) = column.field_type();
+            Value::Table(vec![
+                Some(Value::Str(name.clone())),
+                Some(Value::Bool(false)),
+                Some(Value::U8(tag)),
+                Some(ty),
+                None,
+                Some(Value::Tables(Vec::new())),
+            ])
+        })
+        .collect();
+    // little-endian
+    Value::Table(vec![Some(Value::I16(0)), Some(Value::Tables(fields))])
+}
+
+/// Append an encapsulated message with its body, 8-byte aligned, and
+/// return its offset, metadata length and body length
+fn message(
+    file: &mut Vec<u8>,
+    header_type: u8,
+    header: Value,
+    body: &[u8],
+) -> (usize, usize, usize) {
+    let offset = file.len();
+    let mut meta = flatbuffer(&Value::Table(vec![
+        Some(Value::I16(VERSION)),
+        Some(Value::U8(header_type)),
+        Some(header),
+        Some(Value::I64(body.len() as i64)),
+    ]));
+    meta.resize((meta.len() + 8).next_multiple_of(8) - 8, 0);
+    file.extend_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+    file.extend_from_slice(&(meta.len() as i32).to_le_bytes());
+    file.extend_from_slice(&meta);
+    file.extend_from_slice(body);
+    (offset, meta.len() + 8, body.len())
+}
+
+/// Two little-endian `i64`s, the layout of `FieldNode` and `Buffer`
+fn pair(a: i64, b: i64) -> Vec<u8> {
+    let mut bytes = a.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&b.to_le_bytes());
+    bytes
+}
+
+/// A flatbuffers value; table fields are indexed by field id
+#[derive(Clone)]
+enum Value {
+    U8(u8),
+    Bool(bool),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Str(String),
+    Table(Vec<Option<Value>>),
+    /// Vector of tables
+    Tables(Vec<Value>),
+    /// Vector of 8-byte aligned structs, each [`Value::Struct`]
+    Structs(Vec<Value>),
+    Struct(Vec<u8>),
+}
+
+impl Value {
+    /// Inline size in a table: scalars by value, the rest by offset
+    fn inline_size(&self) -> usize {
+        match self {
+            Value::U8(_) | Value::Bool(_) => 1,
+            Value::I16(_) => 2,
+            Value::I64(_) => 8,
+            _ => 4,
+        }
+    }
+}
+
+/// Serialize a root table. Objects are written front to back, every child
+/// after the field referring to it, so all offsets point forward.
+fn flatbuffer(root: &Value) -> Vec<u8> {
+    let mut buf = vec![0; 4];
+    let at = write_object(&mut buf, root);
+    patch(&mut buf, 0, at);
+    buf
+}
+
+fn patch(buf: &mut [u8], at: usize, target: usize) {
+    let offset = u32::try_from(target - at).expect("flatbuffers under 4 GiB");
+    buf[at..at + 4].copy_from_slice(&offset.to_le_bytes());
+}
+
+fn pad_to(buf: &mut Vec<u8>, align: usize, extra: usize) {
+    while !(buf.len() + extra).is_multiple_of(align) {
+        buf.push(0);
+    }
+}
+
+/// Write a table, string or vector and return its position
+fn write_object(buf: &mut Vec<u8>, value: &Value) -> usize {
+    match value {
+        Value::Table(fields) => write_table(buf, fields),
+        Value::Str(s) => {
+            pad_to(buf, 4, 0);
+            let at = buf.len();
+            buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            buf.extend_from_slice(s.as_bytes());
+            buf.push(0);
+            at
+        }
+        Value::Tables(tables) => {
+            pad_to(buf, 4, 0);
+            let at = buf.len();
+            buf.extend_from_slice(&(tables.len() as u32).to_le_bytes());
+            let slots = buf.len();
+            buf.resize(slots + 4 * tables.len(), 0);
+            for (i, table) in tables.iter().enumerate() {
+                let child = write_object(buf, table);
+                patch(buf, slots + 4 * i, child);
+            }
+            at
+        }
+        Value::Structs(structs) => {
+            pad_to(buf, 8, 4);
+            let at = buf.len();
+            buf.extend_from_slice(&(structs.len() as u32).to_le_bytes());
+            for s in structs {
+                if let Value::Struct(bytes) = s {
+                    buf.extend_from_slice(bytes);
+                }
+            }
+            at
+        }
+        _ => unreachable!("scalars are written inline"),
+    }
+}
+
+fn write_table(buf: &mut Vec<u8>, fields: &[Option<Value>]) -> usize {
+    // inline layout after the vtable offset, largest fields first
+    let mut order: Vec<usize> = (0..fields.len()).filter(|&i| fields[i].is_some()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(fields[i].as_ref().map_or(0, Value::inline_size)));
+    let mut slots = vec![0u16; fields.len()];
+    let mut size: usize = 4;
+    for &i in &order {
+        let n = fields[i].as_ref().map_or(0, Value::inline_size);
+        size = size.next_multiple_of(n);
+        slots[i] = size as u16;
+        size += n;
+    }
+    let size = size.next_multiple_of(4);
+
+    pad_to(buf, 2, 0);
+    let vtable = buf.len();
+    buf.extend_from_slice(&((4 + 2 * fields.len()) as u16).to_le_bytes());
+    buf.extend_from_slice(&(size as u16).to_le_bytes());
+    for slot in &slots {
+        buf.extend_from_slice(&slot.to_le_bytes());
+    }
+    pad_to(buf, 8, 0);
+    let table = buf.len();
+    buf.resize(table + size, 0);
+    let soffset = (table - vtable) as i32;
+    buf[table..table + 4].copy_from_slice(&soffset.to_le_bytes());
+
+    let mut children = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let Some(field) = field else { continue };
+        let at = table + usize::from(slots[i]);
+        let bytes: Vec<u8> = match field {
+            Value::U8(x) => vec![*x],
+            Value::Bool(x) => vec![u8::from(*x)],
+            Value::I16(x) => x.to_le_bytes().to_vec(),
+            Value::I32(x) => x.to_le_bytes().to_vec(),
+            Value::I64(x) => x.to_le_bytes().to_vec(),
+            _ => {
+                children.push((at, field));
+                continue;
+            }
+        };
+        buf[at..at + bytes.len()].copy_from_slice(&bytes);
+    }
+    for (at, child) in children {
+        let target = write_object(buf, child);
+        patch(buf, at, target);
+    }
+    table
+}
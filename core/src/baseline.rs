@@ -0,0 +1,109 @@
+//! Simple reference agents, the baselines benchmarks compare against.
+//!
+//! Each plays deterministically from the board alone, so its games depend
+//! only on the spawns. Ties go to the first move of [`PREFERENCE`], which
+//! keeps large tiles toward the bottom-left corner.
+
+use crate::{Board, Direction, Engine};
+
+/// Order ties are broken in, and the corner strategy's order outright
+pub const PREFERENCE: [Direction; 4] = [
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+    Direction::Up,
+];
+
+/// A built-in agent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Baseline {
+    /// The legal move that gains the most score right away
+    GreedyScore,
+    /// The first legal move of Down, Left, Right, Up
+    DownLeftCorner,
+    /// The legal move leaving the largest tiles along a snake from the
+    /// bottom-left corner: the bottom row left to right, the next right to
+    /// left, and so on, each cell weighted a quarter of the one before
+    Snake,
+}
+
+impl Baseline {
+    pub const ALL: [Baseline; 3] = [
+        Baseline::GreedyScore,
+        Baseline::DownLeftCorner,
+        Baseline::Snake,
+    ];
+
+    /// Look up an agent by its name: `"greedy-score"`,
+    /// `"down-left-corner"` or `"snake"`
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Baseline> {
+        Self::ALL.into_iter().find(|b| b.name() == name)
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Baseline::GreedyScore => "greedy-score",
+            Baseline::DownLeftCorner => "down-left-corner",
+            Baseline::Snake => "snake",
+        }
+    }
+
+    /// The move this agent plays on `board`, or `None` without legal moves
+    ///
+    /// ```
+    /// use akioi_2048_core::baseline::Baseline;
+    /// use akioi_2048_core::{Direction, Engine};
+    ///
+    /// let engine = Engine::default();
+    /// let board = [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [2, 0, 2, 4]];
+    /// assert_eq!(Baseline::GreedyScore.choose(&engine, &board), Some(Direction::Left));
+    /// assert_eq!(Baseline::DownLeftCorner.choose(&engine, &board), Some(Direction::Left));
+    /// let stuck = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]];
+    /// assert_eq!(Baseline::Snake.choose(&engine, &stuck), None);
+    /// ```
+    #[must_use]
+    pub fn choose(self, engine: &Engine, board: &Board) -> Option<Direction> {
+        let legal = || {
+            PREFERENCE
+                .into_iter()
+                .filter(|&dir| engine.legal_moves(board).any(|m| m == dir))
+        };
+        let best = |value: &dyn Fn(Board, i32) -> f64| {
+            legal()
+                .map(|dir| {
+                    let (after, gain) = engine.slide(board, dir);
+                    (dir, value(after, gain))
+                })
+                // the first of equal values, as `max_by` keeps the last
+                .reduce(|best, next| if next.1 > best.1 { next } else { best })
+                .map(|(dir, _)| dir)
+        };
+        match self {
+            Baseline::GreedyScore => best(&|_, gain| f64::from(gain)),
+            Baseline::DownLeftCorner => legal().next(),
+            Baseline::Snake => best(&|after, _| snake(&after)),
+        }
+    }
+}
+
+/// Number tiles summed along the snake, weighted `4^-k` at its `k`th cell
+fn snake(board: &Board) -> f64 {
+    let mut value = 0.0;
+    let mut weight = 1.0;
+    for (i, row) in board.iter().rev().enumerate() {
+        let cells: Vec<i32> = if i % 2 == 0 {
+            row.to_vec()
+        } else {
+            row.iter().rev().copied().collect()
+        };
+        for tile in cells {
+            if tile > 0 {
+                value += f64::from(tile) * weight;
+            }
+            weight /= 4.0;
+        }
+    }
+    value
+}
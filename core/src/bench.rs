@@ -0,0 +1,108 @@
+//! Engine throughput measurements behind `python -m akioi_2048.bench`.
+
+use std::time::{Duration, Instant};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+
+use crate::{ALL_DIRECTIONS, Board, Direction, Game, Rules, State};
+
+/// Steps per timing batch; the clock is only read between batches
+const BATCH: usize = 1024;
+
+/// Throughput of one benchmarked API
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchResult {
+    pub name: &'static str,
+    pub steps: u64,
+    pub seconds: f64,
+    pub steps_per_second: f64,
+}
+
+/// Measure every case for at least `min_time` each.
+///
+/// Cases play random moves from seeded games, restarting finished ones:
+/// * `step`: [`step_with_rng`](crate::step_with_rng)
+/// * `step_detailed`: [`step_detailed_with_rng`](crate::step_detailed_with_rng)
+/// * `step_with_animation`: [`step_with_animation_rng`](crate::detail::step_with_animation_rng)
+/// * `game`: [`Game::step`]
+/// * `agent:random`: [`Game::play`] with a uniformly random policy
+#[must_use]
+pub fn run(min_time: Duration) -> Vec<BenchResult> {
+    let rules = Rules::AKIOI;
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut board = crate::init_with_rng(&mut rng);
+    let mut game = Game::new(0);
+
+    vec![
+        measure("step", min_time, || {
+            let dir = random_direction(&mut rng);
+            let (next, _, state) = crate::step_with_rng(board, dir, &rules, &mut rng)
+                .expect("engine boards are always valid");
+            board = restart_if_over(next, state, &mut rng);
+        }),
+        measure("step_detailed", min_time, || {
+            let dir = random_direction(&mut rng);
+            let result = crate::step_detailed_with_rng(board, dir, &rules, &mut rng)
+                .expect("engine boards are always valid");
+            board = restart_if_over(result.board, result.state, &mut rng);
+        }),
+        measure("step_with_animation", min_time, || {
+            let dir = random_direction(&mut rng);
+            let (result, _) = crate::detail::step_with_animation_rng(board, dir, &rules, &mut rng)
+                .expect("engine boards are always valid");
+            board = restart_if_over(result.board, result.state, &mut rng);
+        }),
+        measure("game", min_time, || {
+            if game.step(random_direction(&mut rng)).state != State::Continue {
+                game = Game::new(0);
+            }
+        }),
+        {
+            let mut policy_rng = StdRng::seed_from_u64(1);
+            let mut game = Game::new(0);
+            measure("agent:random", min_time, || {
+                let policy = |_: &Board| random_direction(&mut policy_rng);
+                if game.play(policy).next().is_none() {
+                    game = Game::new(0);
+                }
+            })
+        },
+    ]
+}
+
+fn measure(name: &'static str, min_time: Duration, mut step: impl FnMut()) -> BenchResult {
+    let started = Instant::now();
+    let mut steps = 0u64;
+    loop {
+        for _ in 0..BATCH {
+            step();
+        }
+        steps += BATCH as u64;
+        if started.elapsed() >= min_time {
+            break;
+        }
+    }
+    let seconds = started.elapsed().as_secs_f64();
+    BenchResult {
+        name,
+        steps,
+        seconds,
+        steps_per_second: steps as f64 / seconds,
+    }
+}
+
+fn random_direction(rng: &mut StdRng) -> Direction {
+    *ALL_DIRECTIONS
+        .choose(rng)
+        .expect("ALL_DIRECTIONS is non-empty")
+}
+
+fn restart_if_over(board: Board, state: State, rng: &mut StdRng) -> Board {
+    if state == State::Continue {
+        board
+    } else {
+        crate::init_with_rng(rng)
+    }
+}
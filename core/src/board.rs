@@ -0,0 +1,44 @@
+use crate::rules::Rules;
+use crate::variant::MergeRule;
+
+/// 4×4 board grid type
+pub type Board = [[i32; 4]; 4];
+
+/// Immovable blocker tile: it never slides or merges, and tiles stop
+/// against it as against a wall. `-3` is free because multipliers are
+/// negated powers of two.
+pub const BLOCKER: i32 = -3;
+
+/// Bomb tile, valid under [`Rules::bombs`]: a tile sliding against it (or
+/// another bomb) clears the whole line of the move, blockers aside, and
+/// scores nothing for that line.
+pub const BOMB: i32 = -5;
+
+/// Ensure all tiles on the board are valid under the standard rules: empty,
+/// numbers up to 65536 and multipliers
+///
+/// # Errors
+/// Returns an error naming the first invalid tile.
+pub fn validate_board(board: &Board) -> Result<(), String> {
+    validate_board_with(board, &Rules::AKIOI)
+}
+
+/// Ensure all tiles on the board are valid under `rules`, which may also
+/// allow [`BLOCKER`]s and [`BOMB`]s
+///
+/// # Errors
+/// Returns an error naming the first invalid tile.
+pub fn validate_board_with(board: &Board, rules: &Rules) -> Result<(), String> {
+    for row in board {
+        for &tile in row {
+            let valid = tile == 0
+                || (rules.blockers && tile == BLOCKER)
+                || (rules.bombs && tile == BOMB)
+                || rules.variant.is_tile(tile, rules);
+            if !valid {
+                return Err(format!("invalid tile value: {tile}"));
+            }
+        }
+    }
+    Ok(())
+}
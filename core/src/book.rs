@@ -0,0 +1,349 @@
+//! Opening books: the searched best move of every early position that play
+//! by the book can reach, so common openings are looked up, not searched.
+//!
+//! [`OpeningBook::build`] starts from every board a game can start on and
+//! follows the book's own move from each position, with every spawn after
+//! it, until the tiles sum past a threshold. Positions play by the book
+//! never reaches are not stored, which keeps books small: each entry is a
+//! packed board, a move and its value. A book remembers the engine it was
+//! built by in a [`Stamp`]; one built by an engine that plays its rules
+//! differently is only read when forced.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::compat::Stamp;
+use crate::pack::{pack, unpack};
+use crate::progress::Progress;
+use crate::reach::{spawn_outcomes, spawn_outcomes_of};
+use crate::search::{SearchOptions, Weights, search};
+use crate::{ALL_DIRECTIONS, BLOCKER, BOMB, Board, Direction, Engine, Rules};
+
+/// First bytes of a saved book, before its version byte
+const MAGIC: &[u8; 7] = b"AKOB\0\0\0";
+
+/// Version [`OpeningBook::write`] writes; version 1 books have no stamp
+const VERSION: u8 = 2;
+
+/// Bytes of one entry on disk: the packed board, the move, its value
+const RECORD: usize = 16 + 1 + 8;
+
+/// The book's move for one position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BookMove {
+    pub direction: Direction,
+    /// Expected evaluation of the move, as [`search`] found it
+    pub value: f64,
+}
+
+/// Best moves of the opening positions; see the [module docs](self).
+///
+/// ```
+/// use akioi_2048_core::book::OpeningBook;
+/// use akioi_2048_core::progress::Progress;
+/// use akioi_2048_core::Rules;
+///
+/// let book = OpeningBook::build(Rules::CLASSIC, 6, 1, 100_000, &Progress::new()).unwrap();
+/// let start = [[0; 4], [0; 4], [0; 4], [2, 4, 0, 0]];
+/// assert!(book.lookup(&start).is_some());
+/// let late = [[0; 4], [0; 4], [0; 4], [4, 4, 0, 0]];
+/// assert_eq!(book.lookup(&late), None);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpeningBook {
+    rules: Rules,
+    max_sum: i32,
+    depth: u32,
+    moves: HashMap<Board, BookMove>,
+}
+
+impl OpeningBook {
+    /// Search the best move, `depth` moves deep, of every position book
+    /// play reaches while the tiles of the board sum to at most `max_sum`
+    /// (multipliers counting by their factor), counting positions searched
+    /// in `progress`.
+    ///
+    /// # Errors
+    /// Returns an error for inconsistent rules or rules that do not spawn
+    /// after every move (a book holds one move per board, whichever move
+    /// reaches it), if more than `max_positions` positions would be stored,
+    /// or [`CANCELLED`](crate::progress::CANCELLED) once cancelled.
+    pub fn build(
+        rules: Rules,
+        max_sum: i32,
+        depth: u32,
+        max_positions: usize,
+        progress: &Progress,
+    ) -> Result<OpeningBook, String> {
+        let engine = Engine::new(rules)?;
+        if rules.spawn_every != 1 {
+            return Err(format!(
+                "opening books need a spawn after every move, not every {}",
+                rules.spawn_every
+            ));
+        }
+        let options = SearchOptions {
+            depth,
+            ..SearchOptions::default()
+        };
+        let mut seen = HashSet::new();
+        // a game starts with two tiles, whatever the rules spawn later
+        let mut layer: Vec<Board> = spawn_outcomes_of(&[[0; 4]; 4], &rules, 2)
+            .into_iter()
+            .map(|(start, _)| start)
+            .filter(|start| tile_sum(start) <= max_sum && seen.insert(*start))
+            .collect();
+        let mut moves = HashMap::new();
+        while !layer.is_empty() {
+            if moves.len() + layer.len() > max_positions {
+                return Err(format!(
+                    "more than {max_positions} positions are reachable below a tile sum of {max_sum}"
+                ));
+            }
+            progress.set_total(moves.len() + layer.len());
+            let mut next = Vec::new();
+            for board in layer {
+                progress.check()?;
+                let values = search(&board, &rules, &Weights::default(), &options)?.values;
+                let best = values
+                    .into_iter()
+                    .reduce(|best, v| if v.1 > best.1 { v } else { best });
+                if let Some((direction, value)) = best {
+                    moves.insert(board, BookMove { direction, value });
+                    let (slid, _) = engine.slide(&board, direction);
+                    for (child, _) in spawn_outcomes(&slid, &rules) {
+                        if tile_sum(&child) <= max_sum && seen.insert(child) {
+                            next.push(child);
+                        }
+                    }
+                }
+                progress.advance(1);
+            }
+            layer = next;
+        }
+        Ok(OpeningBook {
+            rules,
+            max_sum,
+            depth,
+            moves,
+        })
+    }
+
+    /// The book's move for `board`, or `None` for a position it does not
+    /// hold
+    #[must_use]
+    pub fn lookup(&self, board: &Board) -> Option<BookMove> {
+        self.moves.get(board).copied()
+    }
+
+    #[must_use]
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Largest tile sum of the positions held
+    #[must_use]
+    pub fn max_sum(&self) -> i32 {
+        self.max_sum
+    }
+
+    /// Depth the moves were searched to
+    #[must_use]
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Number of positions held
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Write the book: a versioned header with the rules' [`Stamp`], the
+    /// entries sorted by packed board and a SHA-256 of everything before it.
+    ///
+    /// # Errors
+    /// Returns any error of `out`.
+    pub fn write<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let tag = self.rules.tag();
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend(u32::try_from(tag.len()).expect("short tag").to_le_bytes());
+        bytes.extend(tag.as_bytes());
+        let stamp = Stamp::of(&self.rules).to_text();
+        bytes.extend(
+            u32::try_from(stamp.len())
+                .expect("short stamp")
+                .to_le_bytes(),
+        );
+        bytes.extend(stamp.as_bytes());
+        bytes.extend(self.max_sum.to_le_bytes());
+        bytes.extend(self.depth.to_le_bytes());
+        bytes.extend((self.moves.len() as u64).to_le_bytes());
+        let mut records: Vec<[u8; RECORD]> = self
+            .moves
+            .iter()
+            .map(|(board, m)| encode_record(board, m))
+            .collect::<io::Result<_>>()?;
+        records.sort_unstable();
+        bytes.extend(records.iter().flatten());
+        bytes.extend(Sha256::digest(&bytes));
+        out.write_all(&bytes)?;
+        out.flush()
+    }
+
+    /// Read a book written by [`write`](Self::write).
+    ///
+    /// # Errors
+    /// Returns any error of `input`, or [`io::ErrorKind::InvalidData`] for
+    /// data that is not a book, fails its checksum or was built by an
+    /// engine that plays its rules differently.
+    pub fn read<R: Read>(input: R) -> io::Result<OpeningBook> {
+        Self::read_with(input, false).map(|(book, _)| book)
+    }
+
+    /// [`read`](Self::read) that with `force` also reads a book whose
+    /// [`Stamp`] no longer matches, returning the differences with it
+    ///
+    /// # Errors
+    /// As [`read`](Self::read), but a book with differences is only an
+    /// error when `force` is not set.
+    pub fn read_with<R: Read>(mut input: R, force: bool) -> io::Result<(OpeningBook, Vec<String>)> {
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes)?;
+        let mut at = 0;
+        let mut take = |n: usize| {
+            let field = bytes
+                .get(at..at + n)
+                .ok_or_else(|| invalid("truncated opening book"))?;
+            at += n;
+            Ok::<_, io::Error>(field)
+        };
+        if take(MAGIC.len())? != MAGIC {
+            return Err(invalid("not an opening book file"));
+        }
+        let version = take(1)?[0];
+        if version != 1 && version != VERSION {
+            return Err(invalid(&format!(
+                "unsupported opening book version {version}"
+            )));
+        }
+        let tag_len = u32::from_le_bytes(take(4)?.try_into().expect("4 bytes"));
+        let tag =
+            std::str::from_utf8(take(tag_len as usize)?).map_err(|e| invalid(&e.to_string()))?;
+        let rules = Rules::from_tag(tag).map_err(|e| invalid(&e))?;
+        let stamp = if version == VERSION {
+            let len = u32::from_le_bytes(take(4)?.try_into().expect("4 bytes"));
+            let text =
+                std::str::from_utf8(take(len as usize)?).map_err(|e| invalid(&e.to_string()))?;
+            Some(Stamp::parse(text).map_err(|e| invalid(&e))?)
+        } else {
+            None
+        };
+        let max_sum = i32::from_le_bytes(take(4)?.try_into().expect("4 bytes"));
+        let depth = u32::from_le_bytes(take(4)?.try_into().expect("4 bytes"));
+        let count = u64::from_le_bytes(take(8)?.try_into().expect("8 bytes"));
+        let size = usize::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_mul(RECORD))
+            .filter(|size| at.checked_add(size + 32) == Some(bytes.len()))
+            .ok_or_else(|| {
+                invalid(&format!(
+                    "opening book of {count} entries has the wrong size"
+                ))
+            })?;
+        let (body, digest) = bytes.split_at(at + size);
+        if Sha256::digest(body)[..] != *digest {
+            return Err(invalid("opening book checksum mismatch"));
+        }
+        let moves = body[at..]
+            .chunks_exact(RECORD)
+            .map(decode_record)
+            .collect::<io::Result<_>>()?;
+        let differences = match stamp {
+            Some(stamp) => stamp
+                .check("opening book", &rules, force)
+                .map_err(|e| invalid(&e))?,
+            None => Vec::new(),
+        };
+        let book = OpeningBook {
+            rules,
+            max_sum,
+            depth,
+            moves,
+        };
+        Ok((book, differences))
+    }
+
+    /// Save the book to `path`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    /// Load a book saved with [`save`](Self::save).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a book.
+    pub fn load(path: &Path) -> io::Result<OpeningBook> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// [`load`](Self::load) with [`read_with`](Self::read_with)
+    ///
+    /// # Errors
+    /// As [`read_with`](Self::read_with), or if the file cannot be read.
+    pub fn load_with(path: &Path, force: bool) -> io::Result<(OpeningBook, Vec<String>)> {
+        Self::read_with(BufReader::new(File::open(path)?), force)
+    }
+}
+
+/// Sum of the tiles of `board`, multipliers by their factor, blockers and
+/// bombs not at all
+fn tile_sum(board: &Board) -> i32 {
+    board
+        .iter()
+        .flatten()
+        .filter(|&&t| t != BLOCKER && t != BOMB)
+        .map(|t| t.abs())
+        .sum()
+}
+
+/// One entry on disk: the board packed in 16 bytes, the move's index in
+/// [`ALL_DIRECTIONS`] and its value
+fn encode_record(board: &Board, m: &BookMove) -> io::Result<[u8; RECORD]> {
+    let mut record = [0; RECORD];
+    let packed = pack(board, 16).map_err(|e| invalid(&e))?;
+    record[..16].copy_from_slice(&packed);
+    let index = ALL_DIRECTIONS
+        .iter()
+        .position(|&d| d == m.direction)
+        .expect("every direction is in ALL_DIRECTIONS");
+    record[16] = index as u8;
+    record[17..].copy_from_slice(&m.value.to_le_bytes());
+    Ok(record)
+}
+
+fn decode_record(record: &[u8]) -> io::Result<(Board, BookMove)> {
+    let board = unpack(&record[..16]).map_err(|e| invalid(&e))?;
+    let direction = *ALL_DIRECTIONS
+        .get(usize::from(record[16]))
+        .ok_or_else(|| invalid(&format!("invalid move index: {}", record[16])))?;
+    let value = f64::from_le_bytes(record[17..].try_into().expect("8 bytes"));
+    Ok((board, BookMove { direction, value }))
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
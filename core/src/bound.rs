@@ -0,0 +1,108 @@
+//! An upper bound on the score the tiles of a board can still merge, for
+//! pruning in solvers and "progress toward the maximum" displays.
+
+use std::collections::HashMap;
+
+use crate::variant::MergeRule;
+use crate::{BLOCKER, BOMB, Board, Overflow, Rules, Scoring};
+
+/// Tile sets searched exactly before the rest fall back to the coarse
+/// bound of one largest-tile merge per tile
+const STATE_LIMIT: usize = 200_000;
+
+/// The most score the tiles on `board` can merge from now on, before any
+/// further spawn.
+///
+/// Position is ignored: any two tiles may meet, as neighbours and with
+/// every cell below them taken, so every order of merges the rules allow
+/// anywhere is searched. No real sequence of moves merges more, which
+/// makes the bound admissible, and for a single line of equal tiles it is
+/// exact. Blockers and bombs are left out, as neither merges.
+///
+/// ```
+/// use akioi_2048_core::bound::score_upper_bound;
+/// use akioi_2048_core::Rules;
+///
+/// let twos = [[2, 2, 2, 2], [0; 4], [0; 4], [0; 4]];
+/// assert_eq!(score_upper_bound(&twos, &Rules::CLASSIC), Ok(4 + 4 + 8));
+///
+/// // 2 + 2 makes 4, and the x2 doubles it to 8
+/// let doubled = [[2, 2, -2, 0], [0; 4], [0; 4], [0; 4]];
+/// assert_eq!(score_upper_bound(&doubled, &Rules::AKIOI), Ok(4 + 8));
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules or an invalid board.
+pub fn score_upper_bound(board: &Board, rules: &Rules) -> Result<i64, String> {
+    rules.validate()?;
+    crate::validate_board_with(board, rules)?;
+    let mut tiles: Vec<i32> = board
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|&t| t != 0 && t != BLOCKER && t != BOMB)
+        .collect();
+    tiles.sort_unstable();
+    let mut bound = Bound {
+        rules,
+        memo: HashMap::new(),
+    };
+    Ok(bound.best(tiles))
+}
+
+struct Bound<'a> {
+    rules: &'a Rules,
+    /// Best score by sorted tile set
+    memo: HashMap<Vec<i32>, i64>,
+}
+
+impl Bound<'_> {
+    fn best(&mut self, tiles: Vec<i32>) -> i64 {
+        if let Some(&score) = self.memo.get(&tiles) {
+            return score;
+        }
+        if self.memo.len() >= STATE_LIMIT {
+            // no merge scores more than the cap, or two of it past the cap
+            let cap = i64::from(self.rules.max_tile);
+            let most = if self.rules.overflow == Overflow::Block {
+                cap
+            } else {
+                2 * cap
+            };
+            return tiles.len().saturating_sub(1) as i64 * most;
+        }
+        let variant = self.rules.variant;
+        let mut best = 0;
+        for i in 0..tiles.len() {
+            for j in i + 1..tiles.len() {
+                let (a, b) = (tiles[i], tiles[j]);
+                // equal tiles in a sorted set: only the first pair matters
+                if (i > 0 && tiles[i - 1] == a) || (j > i + 1 && tiles[j - 1] == b) {
+                    continue;
+                }
+                let merges = [
+                    variant.merge(a, b, true, &[], self.rules),
+                    variant.merge(b, a, true, &[], self.rules),
+                ];
+                for (tile, score) in merges.into_iter().flatten() {
+                    let mut rest: Vec<i32> = tiles
+                        .iter()
+                        .enumerate()
+                        .filter(|&(k, _)| k != i && k != j)
+                        .map(|(_, &t)| t)
+                        .collect();
+                    let at = rest.partition_point(|&t| t < tile);
+                    rest.insert(at, tile);
+                    let mut score = self.rules.scoring.merge_score(tile, score);
+                    if self.rules.scoring == Scoring::Clamp {
+                        // a clamped move scores at most its gaining merges
+                        score = score.max(0);
+                    }
+                    best = best.max(i64::from(score) + self.best(rest));
+                }
+            }
+        }
+        self.memo.insert(tiles, best);
+        best
+    }
+}
@@ -0,0 +1,427 @@
+//! Fixed-capacity replay buffer of packed transitions for reinforcement
+//! learning, with optional prioritized sampling.
+//!
+//! Boards are stored in the 16-byte [`pack`](crate::pack) form, so a
+//! transition takes 42 bytes, plus 24 for its priority when prioritized.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::dataset::action_index;
+use crate::pack::{pack, unpack};
+use crate::session::random_seed;
+use crate::trajectory::Transition;
+use crate::{ALL_DIRECTIONS, Board};
+
+/// Identifies replay buffer files, with a format version in the last byte
+const MAGIC: &[u8; 8] = b"AKRB\0\0\0\x01";
+
+/// Added to every priority so no transition becomes unsampleable
+const MIN_PRIORITY: f64 = 1.0e-6;
+
+/// Prioritized sampling settings (Schaul et al., 2015)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Priority {
+    /// How strongly priorities skew sampling: `0` is uniform, `1` fully
+    /// proportional
+    pub alpha: f64,
+}
+
+/// A sampled batch
+#[derive(Clone, Debug, PartialEq)]
+pub struct Batch {
+    /// Buffer slots of the transitions, for [`ReplayBuffer::update_priorities`]
+    pub indices: Vec<usize>,
+    pub transitions: Vec<Transition>,
+    /// Importance-sampling weights, normalized to a maximum of `1`; all `1`
+    /// without prioritization
+    pub weights: Vec<f64>,
+}
+
+/// Ring buffer of the last `capacity` transitions.
+///
+/// ```
+/// use akioi_2048_core::buffer::ReplayBuffer;
+/// use akioi_2048_core::trajectory::Transition;
+/// use akioi_2048_core::Direction;
+///
+/// let mut buffer = ReplayBuffer::new(2, None, Some(1)).unwrap();
+/// let board = [[2, 0, 0, 0], [0; 4], [0; 4], [0; 4]];
+/// for reward in [1.0, 2.0, 3.0] {
+///     let t = Transition {
+///         state: board,
+///         action: Direction::Left,
+///         reward,
+///         next_state: board,
+///         done: false,
+///         goal: None,
+///     };
+///     buffer.push(&t, None).unwrap();
+/// }
+/// assert_eq!(buffer.len(), 2);
+/// let batch = buffer.sample(8, 0.4).unwrap();
+/// assert!(batch.transitions.iter().all(|t| t.reward > 1.0));
+/// ```
+pub struct ReplayBuffer {
+    capacity: usize,
+    priority: Option<Priority>,
+    rng: StdRng,
+    /// Slot the next transition goes to
+    next: usize,
+    len: usize,
+    states: Vec<[u8; 16]>,
+    next_states: Vec<[u8; 16]>,
+    actions: Vec<u8>,
+    rewards: Vec<f32>,
+    dones: Vec<bool>,
+    /// `0` means no goal
+    goals: Vec<i32>,
+    /// Raw priority of every slot, empty without prioritization
+    priorities: Vec<f64>,
+    /// Sum tree over `priority^alpha`, leaves from `leaves` on
+    tree: Vec<f64>,
+    leaves: usize,
+    /// Largest raw priority so far; new transitions start with it
+    max_priority: f64,
+}
+
+impl ReplayBuffer {
+    /// An empty buffer; a sampling seed is picked when `seed` is `None`.
+    ///
+    /// # Errors
+    /// Returns an error for a zero capacity or a negative `alpha`.
+    pub fn new(
+        capacity: usize,
+        priority: Option<Priority>,
+        seed: Option<u64>,
+    ) -> Result<Self, String> {
+        if capacity == 0 {
+            return Err("capacity must be positive".to_string());
+        }
+        if priority.is_some_and(|p| p.alpha.is_nan() || p.alpha < 0.0) {
+            return Err("alpha must be non-negative".to_string());
+        }
+        let leaves = if priority.is_some() {
+            capacity.next_power_of_two()
+        } else {
+            0
+        };
+        Ok(Self {
+            capacity,
+            priority,
+            rng: StdRng::seed_from_u64(seed.unwrap_or_else(random_seed)),
+            next: 0,
+            len: 0,
+            states: Vec::new(),
+            next_states: Vec::new(),
+            actions: Vec::new(),
+            rewards: Vec::new(),
+            dones: Vec::new(),
+            goals: Vec::new(),
+            priorities: Vec::new(),
+            tree: vec![0.0; 2 * leaves],
+            leaves,
+            max_priority: 1.0,
+        })
+    }
+
+    /// Store `transition`, evicting the oldest once full. Prioritized
+    /// buffers give it `priority`, or the largest priority seen so far.
+    ///
+    /// # Errors
+    /// Returns an error for a board [`pack`] cannot hold or a negative
+    /// priority.
+    pub fn push(&mut self, transition: &Transition, priority: Option<f64>) -> Result<(), String> {
+        let state = packed(&transition.state)?;
+        let next_state = packed(&transition.next_state)?;
+        if priority.is_some_and(invalid_priority) {
+            return Err("priorities must be non-negative".to_string());
+        }
+        let slot = self.next;
+        put(&mut self.states, slot, state);
+        put(&mut self.next_states, slot, next_state);
+        put(&mut self.actions, slot, action_index(transition.action));
+        put(&mut self.rewards, slot, transition.reward);
+        put(&mut self.dones, slot, transition.done);
+        put(&mut self.goals, slot, transition.goal.unwrap_or(0));
+        if self.priority.is_some() {
+            self.set_priority(slot, priority.unwrap_or(self.max_priority));
+        }
+        self.next = (slot + 1) % self.capacity;
+        self.len = (self.len + 1).min(self.capacity);
+        Ok(())
+    }
+
+    /// Draw `batch` transitions with replacement: uniformly, or in
+    /// proportion to `priority^alpha` with importance weights
+    /// `(len * P)^-beta`.
+    ///
+    /// # Errors
+    /// Returns an error when the buffer is empty.
+    pub fn sample(&mut self, batch: usize, beta: f64) -> Result<Batch, String> {
+        if self.len == 0 {
+            return Err("cannot sample from an empty buffer".to_string());
+        }
+        let indices: Vec<usize> = if self.priority.is_some() {
+            let total = self.tree[1];
+            (0..batch)
+                .map(|_| {
+                    let mass = self.rng.random::<f64>() * total;
+                    self.find(mass)
+                })
+                .collect()
+        } else {
+            (0..batch)
+                .map(|_| self.rng.random_range(0..self.len))
+                .collect()
+        };
+        let weights = if self.priority.is_some() {
+            let total = self.tree[1];
+            let n = self.len as f64;
+            let weight = |i: usize| (n * self.tree[self.leaves + i] / total).powf(-beta);
+            let raw: Vec<f64> = indices.iter().map(|&i| weight(i)).collect();
+            let max = raw.iter().copied().fold(0.0, f64::max);
+            raw.into_iter().map(|w| w / max).collect()
+        } else {
+            vec![1.0; batch]
+        };
+        let transitions = indices.iter().map(|&i| self.get(i)).collect();
+        Ok(Batch {
+            indices,
+            transitions,
+            weights,
+        })
+    }
+
+    /// Set the priorities of sampled slots, e.g. to new TD errors.
+    ///
+    /// # Errors
+    /// Returns an error if the buffer is not prioritized, the lengths
+    /// differ, a slot is empty or a priority is negative; no priority is
+    /// changed then.
+    pub fn update_priorities(
+        &mut self,
+        indices: &[usize],
+        priorities: &[f64],
+    ) -> Result<(), String> {
+        if self.priority.is_none() {
+            return Err("the buffer is not prioritized".to_string());
+        }
+        if indices.len() != priorities.len() {
+            return Err(format!(
+                "got {} indices but {} priorities",
+                indices.len(),
+                priorities.len()
+            ));
+        }
+        if let Some(&i) = indices.iter().find(|&&i| i >= self.len) {
+            return Err(format!(
+                "index {i} out of range for {} transitions",
+                self.len
+            ));
+        }
+        if priorities.iter().copied().any(invalid_priority) {
+            return Err("priorities must be non-negative".to_string());
+        }
+        for (&i, &p) in indices.iter().zip(priorities) {
+            self.set_priority(i, p);
+        }
+        Ok(())
+    }
+
+    /// The transition in slot `index`
+    ///
+    /// # Panics
+    /// Panics if `index` is not below [`len`](Self::len).
+    #[must_use]
+    pub fn get(&self, index: usize) -> Transition {
+        assert!(index < self.len, "index {index} out of range");
+        Transition {
+            state: unpack(&self.states[index]).expect("stored boards unpack"),
+            action: ALL_DIRECTIONS[usize::from(self.actions[index])],
+            reward: self.rewards[index],
+            next_state: unpack(&self.next_states[index]).expect("stored boards unpack"),
+            done: self.dones[index],
+            goal: Some(self.goals[index]).filter(|&g| g != 0),
+        }
+    }
+
+    /// Raw priority of slot `index`, `None` without prioritization
+    #[must_use]
+    pub fn priority_of(&self, index: usize) -> Option<f64> {
+        self.priorities.get(index).copied()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[must_use]
+    pub fn priority(&self) -> Option<Priority> {
+        self.priority
+    }
+
+    fn set_priority(&mut self, slot: usize, priority: f64) {
+        let alpha = self.priority.expect("prioritized").alpha;
+        self.max_priority = self.max_priority.max(priority);
+        put(&mut self.priorities, slot, priority);
+        let mut node = self.leaves + slot;
+        self.tree[node] = (priority + MIN_PRIORITY).powf(alpha);
+        while node > 1 {
+            node /= 2;
+            self.tree[node] = self.tree[2 * node] + self.tree[2 * node + 1];
+        }
+    }
+
+    /// Slot whose cumulative priority range contains `mass`
+    fn find(&self, mut mass: f64) -> usize {
+        let mut node = 1;
+        while node < self.leaves {
+            let left = 2 * node;
+            if mass < self.tree[left] || self.tree[left + 1] == 0.0 {
+                node = left;
+            } else {
+                mass -= self.tree[left];
+                node = left + 1;
+            }
+        }
+        (node - self.leaves).min(self.len - 1)
+    }
+
+    /// Write the buffer in its binary format. The sampling RNG is not
+    /// saved; a loaded buffer is reseeded.
+    ///
+    /// # Errors
+    /// Returns the error of the underlying writer.
+    pub fn write<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&(self.capacity as u64).to_le_bytes())?;
+        out.write_all(&(self.len as u64).to_le_bytes())?;
+        out.write_all(&(self.next as u64).to_le_bytes())?;
+        let alpha = self.priority.map_or(-1.0, |p| p.alpha);
+        out.write_all(&alpha.to_le_bytes())?;
+        out.write_all(&self.max_priority.to_le_bytes())?;
+        for i in 0..self.len {
+            out.write_all(&self.states[i])?;
+            out.write_all(&self.next_states[i])?;
+            out.write_all(&[self.actions[i], u8::from(self.dones[i])])?;
+            out.write_all(&self.rewards[i].to_le_bytes())?;
+            out.write_all(&self.goals[i].to_le_bytes())?;
+            if self.priority.is_some() {
+                out.write_all(&self.priorities[i].to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a buffer written by [`write`](Self::write).
+    ///
+    /// # Errors
+    /// Returns [`io::ErrorKind::InvalidData`] for anything that is not a
+    /// replay buffer file, and the error of the underlying reader.
+    pub fn read<R: Read>(mut input: R, seed: Option<u64>) -> io::Result<ReplayBuffer> {
+        fn take<const N: usize>(input: &mut impl Read) -> io::Result<[u8; N]> {
+            let mut bytes = [0; N];
+            input.read_exact(&mut bytes)?;
+            Ok(bytes)
+        }
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let size = |bytes: [u8; 8]| {
+            usize::try_from(u64::from_le_bytes(bytes)).map_err(|e| invalid(e.to_string()))
+        };
+
+        if take::<8>(&mut input)? != *MAGIC {
+            return Err(invalid("not a replay buffer file".to_string()));
+        }
+        let capacity = size(take(&mut input)?)?;
+        let len = size(take(&mut input)?)?;
+        let next = size(take(&mut input)?)?;
+        let alpha = f64::from_le_bytes(take(&mut input)?);
+        let max_priority = f64::from_le_bytes(take(&mut input)?);
+        if len > capacity || next >= capacity.max(1) || (len < capacity && next != len) {
+            return Err(invalid("inconsistent replay buffer header".to_string()));
+        }
+        let priority = (alpha >= 0.0).then_some(Priority { alpha });
+        let mut buffer = ReplayBuffer::new(capacity, priority, seed).map_err(invalid)?;
+        for slot in 0..len {
+            let state: [u8; 16] = take(&mut input)?;
+            let next_state: [u8; 16] = take(&mut input)?;
+            let [action, done] = take(&mut input)?;
+            for board in [&state, &next_state] {
+                unpack(board).map_err(invalid)?;
+            }
+            if usize::from(action) >= ALL_DIRECTIONS.len() || done > 1 {
+                return Err(invalid("corrupt transition".to_string()));
+            }
+            buffer.states.push(state);
+            buffer.next_states.push(next_state);
+            buffer.actions.push(action);
+            buffer.dones.push(done == 1);
+            buffer.rewards.push(f32::from_le_bytes(take(&mut input)?));
+            buffer.goals.push(i32::from_le_bytes(take(&mut input)?));
+            if buffer.priority.is_some() {
+                let priority = f64::from_le_bytes(take(&mut input)?);
+                if invalid_priority(priority) {
+                    return Err(invalid("corrupt priority".to_string()));
+                }
+                buffer.set_priority(slot, priority);
+            }
+        }
+        buffer.len = len;
+        buffer.next = next;
+        buffer.max_priority = max_priority;
+        Ok(buffer)
+    }
+
+    /// Save to `path`, see [`write`](Self::write).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write(&mut out)?;
+        out.flush()
+    }
+
+    /// Load from `path`, see [`read`](Self::read).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a replay
+    /// buffer.
+    pub fn load(path: impl AsRef<Path>, seed: Option<u64>) -> io::Result<ReplayBuffer> {
+        Self::read(BufReader::new(File::open(path)?), seed)
+    }
+}
+
+/// Write `value` to `slot` of a column that is full up to `slot`
+fn put<T>(column: &mut Vec<T>, slot: usize, value: T) {
+    if slot == column.len() {
+        column.push(value);
+    } else {
+        column[slot] = value;
+    }
+}
+
+fn invalid_priority(priority: f64) -> bool {
+    priority.is_nan() || priority < 0.0
+}
+
+fn packed(board: &Board) -> Result<[u8; 16], String> {
+    let bytes = pack(board, 16)?;
+    Ok(bytes.try_into().expect("pack makes 16 bytes"))
+}
@@ -0,0 +1,419 @@
+//! Interactive terminal client.
+//!
+//! Shared by the `akioi-2048` binary and `python -m akioi_2048`. On a Unix
+//! terminal keys act immediately; otherwise input is read line by line, so
+//! the client can also be scripted through a pipe.
+
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::analysis::{self, Analysis};
+use crate::differential::{self, WebLog};
+use crate::leaderboard::{Entry, Leaderboard, SortKey};
+use crate::render::{Style, render};
+use crate::replay::Replay;
+use crate::server::Server;
+use crate::{Direction, Game, Rules, State};
+
+const USAGE: &str = "\
+usage: akioi-2048 [--seed N] [--rules TAG] [--load FILE] [--save FILE] [--leaderboard FILE]
+       akioi-2048 serve [--stdio | --tcp ADDR | --http ADDR]
+       akioi-2048 analyze [--seed N] [--rules TAG] [--depth N] [POSITION]
+       akioi-2048 webdiff [--rules TAG] LOG
+
+  --seed N      seed for all spawns (default: random)
+  --rules TAG   preset name (akioi, mercy, classic, fib, easy, ...) or rules tag, e.g. akioi-v2+max_multiplier=8
+  --load FILE   resume a saved game
+  --save FILE   where `v` saves a replay to (default: akioi-2048.save)
+  --leaderboard FILE
+                record finished games in this leaderboard
+
+  serve         run the JSON-RPC engine server on stdin/stdout (--stdio,
+                the default) or on a TCP address such as 127.0.0.1:2048;
+                --http ADDR serves it over HTTP (built with the `http` feature)
+
+  analyze       read analysis commands line by line (`help` lists them),
+                from POSITION in board notation (e.g. 2.../..../..../...2)
+                or a fresh game
+
+  webdiff       replay a game the web version logged (JSON lines of
+                `gameState`s and moves) through the engine; report the first
+                move they disagree on, shrunk to one line where it can be,
+                and fail if there is one
+
+keys: arrows or WASD move, u undo, v save, o load, q quit";
+
+/// Parse `args` (without the program name) and play until the user quits,
+/// run the [`server`](crate::server) for `serve`, an
+/// [`analysis`](crate::analysis) session for `analyze`, or a
+/// [`differential`](crate::differential) replay for `webdiff`.
+///
+/// # Errors
+/// Returns an error for bad arguments, an unreadable save file or a broken
+/// terminal.
+pub fn run(args: &[String]) -> Result<(), String> {
+    if args.first().is_some_and(|a| a == "serve") {
+        return serve(&args[1..]);
+    }
+    if args.first().is_some_and(|a| a == "analyze") {
+        return analyze(&args[1..]);
+    }
+    if args.first().is_some_and(|a| a == "webdiff") {
+        return webdiff(&args[1..]);
+    }
+    let mut seed = None;
+    let mut rules = Rules::AKIOI;
+    let mut load_path = None;
+    let mut save_path = PathBuf::from("akioi-2048.save");
+    let mut leaderboard = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("{arg} needs a value\n\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--seed" => seed = Some(parse_seed(value()?)?),
+            "--rules" => rules = parse_rules(value()?)?,
+            "--load" => load_path = Some(PathBuf::from(value()?)),
+            "--save" => save_path = PathBuf::from(value()?),
+            "--leaderboard" => {
+                let path = value()?;
+                leaderboard = Some(Leaderboard::open(path).map_err(|e| format!("{path}: {e}"))?);
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ => return Err(format!("unknown argument: {arg}\n\n{USAGE}")),
+        }
+    }
+
+    let mut game = match load_path {
+        Some(path) => load(&path)?,
+        None => Game::with_rules(seed.unwrap_or_else(crate::session::random_seed), rules)?,
+    };
+
+    let _raw = RawMode::enable();
+    let mut out = io::stdout().lock();
+    let mut keys = io::stdin().lock().bytes();
+    let mut message = String::new();
+    loop {
+        draw(&mut out, &game, &message).map_err(|e| e.to_string())?;
+        message.clear();
+        let Some(key) = read_key(&mut keys) else {
+            break;
+        };
+        match key {
+            Key::Move(dir) => {
+                if game.state() == State::Continue
+                    && game.step(dir).moved
+                    && let Some(board) = leaderboard.as_mut().filter(|_| game.is_over())
+                {
+                    message = record(board, &game);
+                }
+            }
+            Key::Undo => {
+                if !undo(&mut game) {
+                    message.push_str("nothing to undo");
+                }
+            }
+            Key::Save => {
+                message = match fs::write(&save_path, game.replay().to_text()) {
+                    Ok(()) => format!("saved to {}", save_path.display()),
+                    Err(e) => format!("save failed: {e}"),
+                }
+            }
+            Key::Load => {
+                message = match load(&save_path) {
+                    Ok(loaded) => {
+                        game = loaded;
+                        format!("loaded {}", save_path.display())
+                    }
+                    Err(e) => format!("load failed: {e}"),
+                }
+            }
+            Key::Quit => break,
+        }
+    }
+    write!(out, "\r\n").map_err(|e| e.to_string())
+}
+
+fn parse_seed(v: &str) -> Result<u64, String> {
+    v.parse().map_err(|_| format!("invalid seed: {v}"))
+}
+
+fn parse_rules(v: &str) -> Result<Rules, String> {
+    match Rules::preset(v) {
+        Some(preset) => Ok(preset),
+        None => Rules::from_tag(v),
+    }
+}
+
+/// Run an analysis session over stdin, answering each command on stdout;
+/// errors of a command are reported and the session goes on
+fn analyze(args: &[String]) -> Result<(), String> {
+    let mut seed = 0;
+    let mut rules = Rules::AKIOI;
+    let mut depth = None;
+    let mut position = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("{arg} needs a value\n\n{USAGE}"))
+        };
+        match arg.as_str() {
+            "--seed" => seed = parse_seed(value()?)?,
+            "--rules" => rules = parse_rules(value()?)?,
+            "--depth" => depth = Some(value()?),
+            "-h" | "--help" => {
+                println!("{USAGE}\n\nanalysis commands:\n{}", analysis::COMMANDS);
+                return Ok(());
+            }
+            _ if position.is_none() && !arg.starts_with("--") => position = Some(arg),
+            _ => return Err(format!("unknown argument: {arg}\n\n{USAGE}")),
+        }
+    }
+    let mut session = Analysis::new(rules, seed)?;
+    if let Some(depth) = depth {
+        session.execute(&format!("depth {depth}"))?;
+    }
+    if let Some(position) = position {
+        session.load(analysis::parse_position(position)?)?;
+    }
+
+    let prompt = if io::stdin().is_terminal() { "> " } else { "" };
+    let mut out = io::stdout().lock();
+    let mut answer = session.execute("show")?;
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        write!(out, "{answer}\n{prompt}").map_err(|e| e.to_string())?;
+        out.flush().map_err(|e| e.to_string())?;
+        let Some(line) = lines.next() else {
+            break;
+        };
+        answer = match line.map_err(|e| e.to_string())?.trim() {
+            "" => continue,
+            "quit" | "exit" | "q" => break,
+            command => session
+                .execute(command)
+                .unwrap_or_else(|e| format!("error: {e}")),
+        };
+    }
+    Ok(())
+}
+
+/// Replay the web version's log through the engine, describing the first
+/// divergence and failing if there is one
+fn webdiff(args: &[String]) -> Result<(), String> {
+    let mut rules = Rules::AKIOI;
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} needs a value\n\n{USAGE}"))?;
+                rules = parse_rules(value)?;
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => return Err(format!("unknown argument: {arg}\n\n{USAGE}")),
+        }
+    }
+    let path = path.ok_or_else(|| format!("webdiff needs a log\n\n{USAGE}"))?;
+    let text = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let log = WebLog::parse(&text).map_err(|e| format!("{path}: {e}"))?;
+    let Some(divergence) = differential::first_divergence(&log, &rules)? else {
+        println!("{} moves agree", log.moves.len());
+        return Ok(());
+    };
+    let mut report = format!(
+        "move {} ({:?}) diverges: {}\nbefore, score {}:\n{}\nweb version, score {}:\n{}",
+        divergence.move_number,
+        divergence.direction,
+        divergence.message,
+        divergence.before.score,
+        render(&divergence.before.board, Style::Ascii),
+        divergence.web.score,
+        render(&divergence.web.board, Style::Ascii),
+    );
+    if let Some(case) = divergence.minimize() {
+        report += &format!(
+            "\nminimal case: {:?} slides Left to {:?} in the engine, {:?} in the web version",
+            case.line, case.engine, case.web
+        );
+    }
+    Err(report)
+}
+
+/// Take back the last accepted move by replaying the rest from the seed
+fn undo(game: &mut Game) -> bool {
+    let mut replay = game.replay();
+    if replay.moves.pop().is_none() {
+        return false;
+    }
+    *game = replay.game().expect("a prefix of a valid game replays");
+    true
+}
+
+/// Record the finished `game` and describe its rank
+fn record(board: &mut Leaderboard, game: &Game) -> String {
+    let entry = match Entry::from_replay(&game.replay()) {
+        Ok(entry) => entry,
+        Err(e) => return format!("leaderboard failed: {e}"),
+    };
+    let rank = board.rank(entry.score, &entry.rules);
+    match board.record(entry) {
+        Ok(_) => {
+            let total = board.query(Some(&game.rules()), SortKey::Score, None).len();
+            format!("leaderboard: #{rank} of {total}")
+        }
+        Err(e) => format!("leaderboard failed: {e}"),
+    }
+}
+
+fn load(path: &Path) -> Result<Game, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Replay::parse(&text)?.game()
+}
+
+enum Key {
+    Move(Direction),
+    Undo,
+    Save,
+    Load,
+    Quit,
+}
+
+/// Next recognised key; `None` at end of input. Arrow keys arrive as
+/// `ESC [ A..D`, anything unknown is skipped.
+fn read_key(bytes: &mut impl Iterator<Item = io::Result<u8>>) -> Option<Key> {
+    let mut next = || bytes.next()?.ok();
+    loop {
+        let key = match next()? {
+            0x1b => {
+                if next()? != b'[' {
+                    continue;
+                }
+                match next()? {
+                    b'A' => Key::Move(Direction::Up),
+                    b'B' => Key::Move(Direction::Down),
+                    b'C' => Key::Move(Direction::Right),
+                    b'D' => Key::Move(Direction::Left),
+                    _ => continue,
+                }
+            }
+            b'w' | b'W' => Key::Move(Direction::Up),
+            b's' | b'S' => Key::Move(Direction::Down),
+            b'a' | b'A' => Key::Move(Direction::Left),
+            b'd' | b'D' => Key::Move(Direction::Right),
+            b'u' => Key::Undo,
+            b'v' => Key::Save,
+            b'o' => Key::Load,
+            // Ctrl-C and Ctrl-D do not raise signals in raw mode
+            b'q' | 0x03 | 0x04 => Key::Quit,
+            _ => continue,
+        };
+        return Some(key);
+    }
+}
+
+fn draw(out: &mut impl Write, game: &Game, message: &str) -> io::Result<()> {
+    let tty = io::stdout().is_terminal();
+    if tty {
+        write!(out, "\x1b[2J\x1b[H")?;
+    }
+    write!(
+        out,
+        "akioi-2048  {}  seed {}\r\nscore {}  moves {}\r\n\r\n",
+        game.rules().tag(),
+        game.seed(),
+        game.score(),
+        game.history().len()
+    )?;
+    let style = if tty { Style::Ansi } else { Style::Ascii };
+    write!(
+        out,
+        "{}\r\n",
+        render(&game.board(), style).replace('\n', "\r\n")
+    )?;
+    let status = match game.state() {
+        State::Victory => "You win! (u to undo, q to quit)",
+        State::GameOver => "Game over. (u to undo, q to quit)",
+        State::Continue => "arrows/WASD move  u undo  v save  o load  q quit",
+    };
+    write!(out, "\r\n{status}\r\n{message}\r\n")?;
+    out.flush()
+}
+
+/// Keys without Enter and without echo while alive (Unix terminals only)
+struct RawMode {
+    saved: Option<String>,
+}
+
+impl RawMode {
+    fn enable() -> Self {
+        let saved = (cfg!(unix) && io::stdin().is_terminal())
+            .then(|| stty(&["-g"]))
+            .flatten()
+            .filter(|_| stty(&["raw", "-echo"]).is_some());
+        Self { saved }
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if let Some(saved) = &self.saved {
+            stty(&[saved.trim()]);
+        }
+    }
+}
+
+/// Run `stty` on the controlling terminal, returning its output on success
+fn stty(args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("stty")
+        .args(args)
+        .stdin(std::process::Stdio::inherit())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn serve(args: &[String]) -> Result<(), String> {
+    match args {
+        [] => serve_stdio(),
+        [flag] if flag == "--stdio" => serve_stdio(),
+        [flag, addr] if flag == "--tcp" => {
+            crate::server::serve_tcp(addr.as_str()).map_err(|e| format!("{addr}: {e}"))
+        }
+        #[cfg(feature = "http")]
+        [flag, addr] if flag == "--http" => {
+            crate::http::serve_http(addr.as_str()).map_err(|e| format!("{addr}: {e}"))
+        }
+        [flag] if flag == "-h" || flag == "--help" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(format!(
+            "invalid serve arguments: {}\n\n{USAGE}",
+            args.join(" ")
+        )),
+    }
+}
+
+fn serve_stdio() -> Result<(), String> {
+    Server::new()
+        .serve(io::stdin().lock(), io::stdout().lock())
+        .map_err(|e| e.to_string())
+}
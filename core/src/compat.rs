@@ -0,0 +1,172 @@
+//! Compatibility stamps for saved artifacts: the engine version that wrote
+//! one, a hash of how its rules merge and a hash of how they spawn.
+//!
+//! A rules tag names a configuration, but the engine behind it can change:
+//! a fixed merge or a corrected spawn table plays the same tag differently,
+//! and a replay or dataset recorded before no longer means what it did.
+//! Replays, binary game logs, datasets and opening books carry a [`Stamp`]
+//! of the rules they were made under; loading one whose hashes this engine
+//! no longer produces is refused unless forced. A different engine version
+//! alone is no reason to refuse: only the hashes decide.
+
+use sha2::{Digest, Sha256};
+
+use crate::game::spawn_weights;
+use crate::rules::RULES_VERSION;
+use crate::session::InvalidMoves;
+use crate::spec::spec;
+use crate::{BLOCKER, Board, Rules};
+
+/// Version of this engine, as stamped on the artifacts it writes
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// What an artifact was made with; see the [module docs](self).
+///
+/// ```
+/// use akioi_2048_core::compat::Stamp;
+/// use akioi_2048_core::Rules;
+///
+/// let stamp = Stamp::of(&Rules::AKIOI);
+/// assert_eq!(Stamp::parse(&stamp.to_text()), Ok(stamp.clone()));
+/// assert!(stamp.differences(&Rules::AKIOI).is_empty());
+/// assert_ne!(stamp.spawn_hash, Stamp::of(&Rules::MERCY).spawn_hash);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stamp {
+    pub engine: String,
+    /// Hash of every merge the rules allow, with its tile and score
+    pub rules_hash: String,
+    /// Hash of the spawn weights on a set of probe boards
+    pub spawn_hash: String,
+}
+
+impl Stamp {
+    /// Stamp of artifacts this engine makes under `rules`
+    #[must_use]
+    pub fn of(rules: &Rules) -> Stamp {
+        Stamp {
+            engine: ENGINE_VERSION.to_string(),
+            rules_hash: rules_hash(rules),
+            spawn_hash: spawn_hash(rules),
+        }
+    }
+
+    /// `engine=0.5.1 rules=<16 hex digits> spawns=<16 hex digits>`
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        format!(
+            "engine={} rules={} spawns={}",
+            self.engine, self.rules_hash, self.spawn_hash
+        )
+    }
+
+    /// Inverse of [`to_text`](Self::to_text)
+    ///
+    /// # Errors
+    /// Returns an error for text of another form.
+    pub fn parse(text: &str) -> Result<Stamp, String> {
+        let invalid = || format!("invalid compatibility stamp: {text}");
+        let mut fields = text.split(' ');
+        let mut field = |name: &str| {
+            fields
+                .next()
+                .and_then(|f| f.strip_prefix(name)?.strip_prefix('='))
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+                .ok_or_else(invalid)
+        };
+        let stamp = Stamp {
+            engine: field("engine")?,
+            rules_hash: field("rules")?,
+            spawn_hash: field("spawns")?,
+        };
+        if fields.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(stamp)
+    }
+
+    /// How this engine plays `rules` differently from the engine that made
+    /// the stamp, one sentence each; empty when it plays them the same
+    #[must_use]
+    pub fn differences(&self, rules: &Rules) -> Vec<String> {
+        let now = Stamp::of(rules);
+        let tag = rules.tag();
+        let mut differences = Vec::new();
+        if self.rules_hash != now.rules_hash {
+            differences.push(format!(
+                "{tag} merges differently than in engine {} (rules hash {}, now {})",
+                self.engine, self.rules_hash, now.rules_hash
+            ));
+        }
+        if self.spawn_hash != now.spawn_hash {
+            differences.push(format!(
+                "{tag} spawns differently than in engine {} (spawn hash {}, now {})",
+                self.engine, self.spawn_hash, now.spawn_hash
+            ));
+        }
+        differences
+    }
+
+    /// Check an artifact of `what` stamped so against `rules`: the
+    /// differences, which are only allowed when `force` is set.
+    ///
+    /// # Errors
+    /// Returns an error naming the differences unless there are none or
+    /// `force` is set.
+    pub fn check(&self, what: &str, rules: &Rules, force: bool) -> Result<Vec<String>, String> {
+        let differences = self.differences(rules);
+        if differences.is_empty() || force {
+            return Ok(differences);
+        }
+        Err(format!(
+            "{what} was made by an incompatible engine: {}; load it with force=True to use it anyway",
+            differences.join("; ")
+        ))
+    }
+}
+
+/// Hash of every merge of the rules' tiles, and of their winning tile
+fn rules_hash(rules: &Rules) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(RULES_VERSION.to_le_bytes());
+    hasher.update(rules.tag());
+    if let Ok(spec) = spec(rules, InvalidMoves::Noop) {
+        hasher.update(spec.winning_tile.to_le_bytes());
+        for m in &spec.merges {
+            for value in [m.a, m.b, m.tile, m.score] {
+                hasher.update(value.to_le_bytes());
+            }
+            hasher.update([u8::from(m.needs_adjacent), u8::from(m.needs_full_below)]);
+        }
+    }
+    short_hex(&hasher.finalize())
+}
+
+/// Hash of the integer weights spawns are drawn from on boards that tell
+/// the spawn filters apart: an empty one, one with a single empty cell and
+/// one per stage of the adaptive difficulty
+fn spawn_hash(rules: &Rules) -> String {
+    let mut last_cell = [[BLOCKER; 4]; 4];
+    last_cell[0][0] = 0;
+    let mut probes: Vec<Board> = vec![[[0; 4]; 4], last_cell];
+    for top in [128, 1024, 4096, 8192] {
+        let mut board = [[0; 4]; 4];
+        board[3][3] = top;
+        probes.push(board);
+    }
+    let mut hasher = Sha256::new();
+    for board in &probes {
+        for &(tile, weight) in spawn_weights(rules, board).entries() {
+            hasher.update(tile.to_le_bytes());
+            hasher.update(weight.to_le_bytes());
+        }
+        hasher.update([0xff]);
+    }
+    short_hex(&hasher.finalize())
+}
+
+/// First 8 bytes of a digest as 16 hex digits
+fn short_hex(digest: &[u8]) -> String {
+    digest[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
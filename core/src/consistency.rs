@@ -0,0 +1,236 @@
+//! Whether a claimed score fits the tiles on a board, for vetting imported
+//! saves and leaderboard submissions that come without a replay.
+//!
+//! Every tile carries the score of the merges that built it: nothing for a
+//! spawned tile, `4` for a `4` made of two `2`s, `8 + 4 + 4` for an `8` of
+//! two such `4`s. Except where a bomb clears a line, tiles never leave the
+//! board without merging into another one, so the score of a game is the
+//! sum of what its tiles carry. [`check_consistency`] works out, for every
+//! tile the rules can build, the least and the most it can carry, counting
+//! spawned `4`s (which carry nothing) and multiplier merges (which can
+//! carry less than nothing), and compares the claim.
+//!
+//! Under [`Scoring::Clamp`](crate::Scoring::Clamp) a move losing score
+//! scores nothing instead, so a tile carries at most what its gaining
+//! merges scored and the game at least nothing.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::variant::MergeRule;
+use crate::{BLOCKER, BOMB, Board, Rules, Scoring};
+
+/// What [`check_consistency`] found
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Consistency {
+    /// The score claimed
+    pub score: i64,
+    /// Least score a game reaching the board has, or `None` when the rules
+    /// set no lower bound
+    pub min_score: Option<i64>,
+    /// Most score a game reaching the board has, or `None` when the rules
+    /// set no upper bound, as when a `×1` merge rebuilds a tile and scores
+    /// it again
+    pub max_score: Option<i64>,
+    /// Every merge scores a multiple of this, and so does every game
+    pub step: i64,
+    /// Why no game reaches the board with the score, one reason each;
+    /// empty when the score is consistent
+    pub problems: Vec<String>,
+}
+
+impl Consistency {
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Check whether a game under `rules` can stand on `board` with `score`.
+///
+/// The bounds are those of the tiles alone, so a consistent score is not
+/// proof the position was played; a problem is proof it was not.
+///
+/// ```
+/// use akioi_2048_core::consistency::check_consistency;
+/// use akioi_2048_core::Rules;
+///
+/// // an 8 built of 2s scored 16, of spawned 4s 8
+/// let board = [[8, 0, 0, 0], [0; 4], [0; 4], [0, 0, 0, 2]];
+/// let report = check_consistency(&board, 12, &Rules::CLASSIC).unwrap();
+/// assert!(report.is_consistent());
+/// assert_eq!((report.min_score, report.max_score), (Some(8), Some(16)));
+///
+/// let inflated = check_consistency(&board, 40, &Rules::CLASSIC).unwrap();
+/// assert!(!inflated.is_consistent());
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules or an invalid board.
+pub fn check_consistency(board: &Board, score: i64, rules: &Rules) -> Result<Consistency, String> {
+    rules.validate()?;
+    crate::validate_board_with(board, rules)?;
+    let carried = Carried::of(rules);
+
+    let mut problems = Vec::new();
+    let (mut min_score, mut max_score) = (Some(0), Some(0));
+    for &tile in board.iter().flatten() {
+        if tile == 0 || tile == BLOCKER || tile == BOMB {
+            continue;
+        }
+        let Some(&(lo, hi)) = carried.range.get(&tile) else {
+            problems.push(format!("no spawns and merges build a {tile}"));
+            continue;
+        };
+        min_score = min_score.zip(lo).map(|(a, b)| a + b);
+        max_score = max_score.zip(hi).map(|(a, b)| a + b);
+    }
+    if rules.scoring != Scoring::Standard {
+        // neither scores a move below nothing
+        min_score = Some(min_score.unwrap_or(0).max(0));
+    }
+    if rules.bombs {
+        // a cleared line takes what its tiles carried along
+        max_score = None;
+        if carried
+            .range
+            .values()
+            .any(|&(lo, _)| lo.is_none_or(|lo| lo < 0))
+        {
+            min_score = None;
+        }
+    }
+
+    if let Some(min) = min_score.filter(|&min| score < min) {
+        problems.push(format!(
+            "score {score} is below the {min} the tiles on the board took to build"
+        ));
+    }
+    if let Some(max) = max_score.filter(|&max| score > max) {
+        problems.push(format!(
+            "score {score} is above the {max} the tiles on the board can have scored"
+        ));
+    }
+    if score % carried.step != 0 {
+        problems.push(format!(
+            "score {score} is not a multiple of {}, as every merge's score is",
+            carried.step
+        ));
+    }
+    Ok(Consistency {
+        score,
+        min_score,
+        max_score,
+        step: carried.step,
+        problems,
+    })
+}
+
+/// The score every tile of a ruleset can carry
+struct Carried {
+    /// Least and most score by tile, `None` where unbounded; tiles no play
+    /// builds are missing
+    range: BTreeMap<i32, (Option<i64>, Option<i64>)>,
+    /// Greatest common divisor of every merge's score
+    step: i64,
+}
+
+impl Carried {
+    fn of(rules: &Rules) -> Carried {
+        let variant = rules.variant;
+        let spawns: BTreeSet<i32> = variant
+            .spawn_weights(&[[0; 4]; 4], rules)
+            .entries()
+            .iter()
+            .filter(|&&(_, weight)| weight > 0)
+            .map(|&(tile, _)| tile)
+            .collect();
+
+        // every tile play builds, and every merge between two of them
+        let mut tiles = spawns.clone();
+        let mut merges = Vec::new();
+        let mut seen = BTreeSet::new();
+        loop {
+            let before = tiles.len();
+            let known: Vec<i32> = tiles.iter().copied().collect();
+            for &a in &known {
+                for &b in &known {
+                    if !seen.insert((a, b)) {
+                        continue;
+                    }
+                    if let Some((tile, score)) = variant.merge(a, b, true, &[], rules) {
+                        let low = i64::from(rules.scoring.merge_score(tile, score));
+                        let high = if rules.scoring == Scoring::Clamp {
+                            low.max(0)
+                        } else {
+                            low
+                        };
+                        merges.push((a, b, tile, low, high));
+                        tiles.insert(tile);
+                    }
+                }
+            }
+            if tiles.len() == before {
+                break;
+            }
+        }
+
+        // Bellman-Ford from the spawns, which carry nothing: bounds still
+        // moving after one round per tile are on a cycle, so unbounded
+        let rounds = tiles.len();
+        let mut lo: BTreeMap<i32, i64> = spawns.iter().map(|&t| (t, 0)).collect();
+        let mut hi = lo.clone();
+        let (mut no_lo, mut no_hi) = (BTreeSet::new(), BTreeSet::new());
+        for round in 0..2 * rounds {
+            for &(a, b, tile, low, high) in &merges {
+                if let (Some(&x), Some(&y)) = (lo.get(&a), lo.get(&b)) {
+                    let v = low + x + y;
+                    if lo.get(&tile).is_none_or(|&old| v < old) {
+                        lo.insert(tile, v);
+                        if round >= rounds {
+                            no_lo.insert(tile);
+                        }
+                    }
+                }
+                if let (Some(&x), Some(&y)) = (hi.get(&a), hi.get(&b)) {
+                    let v = high + x + y;
+                    if hi.get(&tile).is_none_or(|&old| v > old) {
+                        hi.insert(tile, v);
+                        if round >= rounds {
+                            no_hi.insert(tile);
+                        }
+                    }
+                }
+            }
+        }
+        // a merge with an unbounded tile makes an unbounded one
+        for _ in 0..rounds {
+            for &(a, b, tile, ..) in &merges {
+                if no_lo.contains(&a) || no_lo.contains(&b) {
+                    no_lo.insert(tile);
+                }
+                if no_hi.contains(&a) || no_hi.contains(&b) {
+                    no_hi.insert(tile);
+                }
+            }
+        }
+
+        let range = tiles
+            .iter()
+            .map(|&t| {
+                let lo = lo.get(&t).copied().filter(|_| !no_lo.contains(&t));
+                let hi = hi.get(&t).copied().filter(|_| !no_hi.contains(&t));
+                (t, (lo, hi))
+            })
+            .collect();
+        let step = merges
+            .iter()
+            .map(|&(_, _, _, low, _)| low.abs())
+            .fold(0, gcd)
+            .max(1);
+        Carried { range, step }
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
@@ -0,0 +1,187 @@
+//! Random mid-game positions, for curriculum learning and tests.
+//!
+//! [`random_board`] scatters tiles directly, which is fast and covers
+//! positions play rarely reaches; [`played_board`] plays a game until the
+//! wanted tile appears, so the position is reachable by construction.
+
+use crate::search::{Weights, evaluate};
+use crate::{Board, Direction, Engine, Rng};
+
+/// What [`random_board`] and [`played_board`] generate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardSpec {
+    /// Largest tile on the board, a power of two from 2 to 65536
+    pub max_tile: i32,
+    /// Share of the 16 cells holding a tile, in `(0, 1]`
+    pub fill: f64,
+    /// Whether multipliers may appear
+    pub include_multipliers: bool,
+}
+
+impl Default for BoardSpec {
+    fn default() -> Self {
+        Self {
+            max_tile: 1024,
+            fill: 0.5,
+            include_multipliers: true,
+        }
+    }
+}
+
+impl BoardSpec {
+    /// # Errors
+    /// Returns an error for a `max_tile` or `fill` out of range.
+    pub fn validate(&self) -> Result<(), String> {
+        let tile_ok = (2..=0x0001_0000).contains(&self.max_tile)
+            && u32::try_from(self.max_tile).is_ok_and(u32::is_power_of_two);
+        if !tile_ok {
+            return Err(format!(
+                "max_tile must be a power of two from 2 to 65536, got {}",
+                self.max_tile
+            ));
+        }
+        if !(self.fill > 0.0 && self.fill <= 1.0) {
+            return Err(format!("fill must be in (0, 1], got {}", self.fill));
+        }
+        Ok(())
+    }
+
+    /// Cells holding a tile, at least one
+    fn tiles(&self) -> usize {
+        ((self.fill * 16.0).round() as usize).clamp(1, 16)
+    }
+}
+
+/// Boards drawn before [`random_board`] gives up on finding a live one
+const DRAWS: usize = 1000;
+/// Games [`played_board`] plays before giving up
+const GAMES: usize = 256;
+/// Odds of a [`played_board`] move being uniform rather than greedy
+const EXPLORE: f64 = 0.1;
+/// Odds of each tile but the largest being a multiplier
+const MULTIPLIER_ODDS: f64 = 0.125;
+
+/// A live board with `max_tile` in a corner and smaller tiles scattered
+/// over the other chosen cells.
+///
+/// Each other tile is `2^k` with `k` uniform below `max_tile`'s exponent
+/// or, when multipliers are included and the engine's variant has them,
+/// one time in eight a multiplier its rules allow. Boards without a legal move are drawn again.
+///
+/// ```
+/// use akioi_2048_core::curriculum::{BoardSpec, random_board};
+/// use akioi_2048_core::Engine;
+/// use rand::{SeedableRng, rngs::StdRng};
+///
+/// let spec = BoardSpec { max_tile: 256, fill: 0.75, include_multipliers: false };
+/// let board = random_board(&spec, &Engine::default(), &mut StdRng::seed_from_u64(1)).unwrap();
+/// let tiles: Vec<i32> = board.iter().flatten().copied().filter(|&t| t != 0).collect();
+/// assert_eq!(tiles.len(), 12);
+/// assert_eq!(tiles.iter().max(), Some(&256));
+/// assert!(tiles.iter().all(|&t| t > 0));
+/// ```
+///
+/// # Errors
+/// Returns an error for an invalid spec or when no live board turns up.
+pub fn random_board<R: Rng + ?Sized>(
+    spec: &BoardSpec,
+    engine: &Engine,
+    rng: &mut R,
+) -> Result<Board, String> {
+    spec.validate()?;
+    let top = spec.max_tile.trailing_zeros() as usize;
+    let multipliers = engine.rules().max_multiplier.trailing_zeros() as usize + 1;
+    let multipliers_allowed = spec.include_multipliers && engine.rules().is_multiplier(-1);
+    for _ in 0..DRAWS {
+        let mut cells: Vec<usize> = (0..16).collect();
+        let corner = [0, 3, 12, 15][rng.index(4)];
+        cells.swap(0, corner);
+        for i in 1..spec.tiles() {
+            let j = i + rng.index(16 - i);
+            cells.swap(i, j);
+        }
+        let mut board = [[0; 4]; 4];
+        for (i, &cell) in cells[..spec.tiles()].iter().enumerate() {
+            board[cell / 4][cell % 4] = if i == 0 {
+                spec.max_tile
+            } else if multipliers_allowed && rng.unit() < MULTIPLIER_ODDS {
+                -(1 << rng.index(multipliers))
+            } else {
+                1 << (1 + rng.index(top.saturating_sub(1).max(1)))
+            };
+        }
+        if !engine.is_over(&board) {
+            return Ok(board);
+        }
+    }
+    Err(format!("no live board found in {DRAWS} draws"))
+}
+
+/// The first position of a played game whose largest tile is `max_tile`
+/// with at least `fill` of the cells taken.
+///
+/// Moves are greedy on the default [`evaluate`] heuristic, one time in
+/// ten uniformly random for variety, which reaches large tiles far more
+/// often than uniform play. Without multipliers, only positions free of them
+/// count. Games that pass `max_tile` or end first are replayed.
+///
+/// ```
+/// use akioi_2048_core::curriculum::{BoardSpec, played_board};
+/// use akioi_2048_core::Engine;
+/// use rand::{SeedableRng, rngs::StdRng};
+///
+/// let spec = BoardSpec { max_tile: 64, ..BoardSpec::default() };
+/// let board = played_board(&spec, &Engine::default(), &mut StdRng::seed_from_u64(1)).unwrap();
+/// assert_eq!(board.iter().flatten().max(), Some(&64));
+/// ```
+///
+/// # Errors
+/// Returns an error for an invalid spec or when no game gets there.
+pub fn played_board<R: Rng + ?Sized>(
+    spec: &BoardSpec,
+    engine: &Engine,
+    rng: &mut R,
+) -> Result<Board, String> {
+    spec.validate()?;
+    let wanted = |board: &Board| {
+        let tiles = board.iter().flatten().filter(|&&t| t != 0).count();
+        tiles >= spec.tiles()
+            && board.iter().flatten().max() == Some(&spec.max_tile)
+            && (spec.include_multipliers || board.iter().flatten().all(|&t| t >= 0))
+    };
+    for _ in 0..GAMES {
+        let mut board = engine.init(rng);
+        for moves in 0.. {
+            if wanted(&board) {
+                return Ok(board);
+            }
+            let Some(dir) = greedy_move(&board, engine, rng) else {
+                break;
+            };
+            let (next, _, _) = engine.step_after(board, dir, rng, moves)?;
+            board = next;
+            if board.iter().flatten().any(|&t| t > spec.max_tile) {
+                break;
+            }
+        }
+    }
+    Err(format!(
+        "no game reached a {} tile in {GAMES} tries",
+        spec.max_tile
+    ))
+}
+
+fn greedy_move<R: Rng + ?Sized>(board: &Board, engine: &Engine, rng: &mut R) -> Option<Direction> {
+    let legal: Vec<Direction> = engine.legal_moves(board).collect();
+    if legal.is_empty() {
+        return None;
+    }
+    if rng.unit() < EXPLORE {
+        return Some(legal[rng.index(legal.len())]);
+    }
+    let weights = Weights::default();
+    let value = |d: Direction| evaluate(&engine.slide(board, d).0, &weights);
+    legal
+        .into_iter()
+        .max_by(|&a, &b| value(a).total_cmp(&value(b)))
+}
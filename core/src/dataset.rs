@@ -0,0 +1,89 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+use crate::compat::Stamp;
+use crate::npz::{self, Array};
+use crate::trajectory::{TrajectoryRecorder, Transition};
+use crate::{Board, Direction, Rules, State};
+
+/// Integer action id of `dir`: its index in [`ALL_DIRECTIONS`](crate::ALL_DIRECTIONS)
+/// (`Down=0, Right=1, Up=2, Left=3`)
+#[must_use]
+pub fn action_index(dir: Direction) -> u8 {
+    match dir {
+        Direction::Down => 0,
+        Direction::Right => 1,
+        Direction::Up => 2,
+        Direction::Left => 3,
+    }
+}
+
+/// Play `n_games` fresh games with `policy`, recording every move.
+///
+/// Games shorter than `max_moves` end naturally; longer ones are truncated.
+///
+/// # Errors
+/// Propagates the first error returned by `policy`.
+pub fn play_games<E>(
+    n_games: usize,
+    max_moves: Option<usize>,
+    recorder: &mut TrajectoryRecorder,
+    mut policy: impl FnMut(&Board) -> Result<Direction, E>,
+) -> Result<(), E> {
+    for _ in 0..n_games {
+        let mut board = crate::init();
+        let mut moves = 0;
+        loop {
+            let dir = policy(&board)?;
+            let (next, delta, state) =
+                crate::step(board, dir).expect("engine boards are always valid");
+            recorder.record(board, dir, delta, next, state);
+            board = next;
+            moves += 1;
+            if state != State::Continue {
+                break;
+            }
+            if max_moves.is_some_and(|limit| moves >= limit) {
+                recorder.finish_episode();
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write transitions to an `.npz` archive with arrays `states (N,4,4) int32`,
+/// `actions (N,) uint8`, `rewards (N,) float32`, `next_states (N,4,4) int32`,
+/// `dones (N,) bool` and `goals (N,) int32` (0 = no goal), and `stamp`, the
+/// UTF-8 bytes of the [`Stamp`] of the akioi rules [`play_games`] plays.
+///
+/// # Errors
+/// Returns an error if the file cannot be written.
+pub fn write_npz(path: &Path, transitions: &[Transition]) -> io::Result<()> {
+    let n = transitions.len();
+    let stamp = Stamp::of(&Rules::AKIOI).to_text();
+    let cells = |pick: fn(&Transition) -> &Board| {
+        transitions
+            .iter()
+            .flat_map(move |t| pick(t).iter().flatten().copied())
+    };
+    let arrays = [
+        Array::i32("states", vec![n, 4, 4], cells(|t| &t.state)),
+        Array::u8(
+            "actions",
+            vec![n],
+            transitions.iter().map(|t| action_index(t.action)),
+        ),
+        Array::f32("rewards", vec![n], transitions.iter().map(|t| t.reward)),
+        Array::i32("next_states", vec![n, 4, 4], cells(|t| &t.next_state)),
+        Array::bool("dones", vec![n], transitions.iter().map(|t| t.done)),
+        Array::i32(
+            "goals",
+            vec![n],
+            transitions.iter().map(|t| t.goal.unwrap_or(0)),
+        ),
+        Array::u8("stamp", vec![stamp.len()], stamp.into_bytes().into_iter()),
+    ];
+    npz::write(BufWriter::new(File::create(path)?), &arrays)
+}
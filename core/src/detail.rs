@@ -0,0 +1,386 @@
+use rand::rng;
+
+use crate::game::{Trace, single_step, single_step_traced, spawn_tiles, state_of};
+use crate::{ALL_DIRECTIONS, Board, Direction, Placement, Rng, Rules, State, validate_board_with};
+
+/// Board cell as `(row, col)`
+pub type Cell = (usize, usize);
+
+/// Which tiles a [`Merge`] combined
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeKind {
+    /// Two equal number tiles added up
+    Numbers,
+    /// Two equal multipliers doubled into a bigger multiplier
+    Multipliers,
+    /// A multiplier applied to a number tile
+    Multiply,
+}
+
+impl MergeKind {
+    /// Kind of the merge of tiles `a` and `b`
+    fn of(a: i32, b: i32) -> MergeKind {
+        match (a > 0, b > 0) {
+            (true, true) => MergeKind::Numbers,
+            (false, false) => MergeKind::Multipliers,
+            _ => MergeKind::Multiply,
+        }
+    }
+}
+
+/// Two tiles combined into one during a move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Merge {
+    /// Cell holding the merged tile after the move
+    pub target: Cell,
+    /// Original cells: the leading tile (nearer the wall), then the one that
+    /// slid into it
+    pub sources: [Cell; 2],
+    /// Values of the tiles at `sources`
+    pub tiles: [i32; 2],
+    pub kind: MergeKind,
+    /// Resulting tile value
+    pub value: i32,
+    /// Score contributed by this merge; under
+    /// [`Scoring::Clamp`](crate::Scoring::Clamp) the merges of a move can sum
+    /// below its `delta`
+    pub score: i32,
+}
+
+/// Tile spawned after a valid move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Spawn {
+    pub cell: Cell,
+    pub value: i32,
+}
+
+/// Path of one tile during a move, for slide animations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileMove {
+    /// Cell the tile starts in
+    pub source: Cell,
+    /// Cell the tile slides to
+    pub target: Cell,
+    /// Value of the tile before the move
+    pub value: i32,
+    /// Whether the tile merges with another one at `target`
+    pub merged: bool,
+}
+
+/// Everything that happened during one move
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoveResult {
+    pub board: Board,
+    pub delta: i32,
+    pub state: State,
+    /// Whether the move changed the board (only valid moves spawn a tile)
+    pub moved: bool,
+    /// Merges in row-major order of their target cell
+    pub merges: Vec<Merge>,
+    /// The tile spawned, the first of them when the rules spawn several;
+    /// `None` after an invalid move or one that spawns nothing under
+    /// [`Rules::spawn_every`]
+    pub spawn: Option<Spawn>,
+    /// Tiles spawned after `spawn` under [`Rules::spawns`], in order
+    pub extra_spawns: Vec<Spawn>,
+}
+
+impl MoveResult {
+    /// Part of `delta` scored by merges of `kind`
+    #[must_use]
+    pub fn score_of(&self, kind: MergeKind) -> i32 {
+        self.merges
+            .iter()
+            .filter(|m| m.kind == kind)
+            .map(|m| m.score)
+            .sum()
+    }
+}
+
+/// [`step_with_rules`](crate::step_with_rules) returning a full [`MoveResult`].
+///
+/// # Errors
+/// Returns an error if the rules are inconsistent or the board contains
+/// tiles that are invalid under them.
+pub fn step_detailed(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+) -> Result<MoveResult, String> {
+    step_detailed_with_rng(board, direction, rules, &mut rng())
+}
+
+/// [`step_with_rng`](crate::step_with_rng) returning a full [`MoveResult`].
+///
+/// Consumes the RNG exactly like `step_with_rng`, so both produce the same board.
+///
+/// # Errors
+/// Returns an error if the rules are inconsistent or the board contains
+/// tiles that are invalid under them.
+pub fn step_detailed_with_rng<R: Rng + ?Sized>(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    rng: &mut R,
+) -> Result<MoveResult, String> {
+    Ok(traced_step(board, direction, rules, rng, 0)?.0)
+}
+
+/// [`step_after`](crate::step_after) returning a full [`MoveResult`]
+///
+/// # Errors
+/// Returns an error if the rules are inconsistent or the board contains
+/// tiles that are invalid under them.
+pub fn step_detailed_after<R: Rng + ?Sized>(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    rng: &mut R,
+    moves: u64,
+) -> Result<MoveResult, String> {
+    Ok(traced_step(board, direction, rules, rng, moves)?.0)
+}
+
+/// [`step_detailed`] plus the path of every tile on the original board.
+///
+/// Tiles are listed in row-major order of their source cell; the spawned tile
+/// is reported in [`MoveResult::spawn`].
+///
+/// # Errors
+/// Returns an error if the rules are inconsistent or the board contains
+/// tiles that are invalid under them.
+pub fn step_with_animation(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+) -> Result<(MoveResult, Vec<TileMove>), String> {
+    step_with_animation_rng(board, direction, rules, &mut rng())
+}
+
+/// [`step_with_animation`] drawing the spawned tile from `rng`.
+///
+/// # Errors
+/// Returns an error if the rules are inconsistent or the board contains
+/// tiles that are invalid under them.
+pub fn step_with_animation_rng<R: Rng + ?Sized>(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    rng: &mut R,
+) -> Result<(MoveResult, Vec<TileMove>), String> {
+    let (result, traces) = traced_step(board, direction, rules, rng, 0)?;
+    let mut tiles = Vec::with_capacity(16);
+    for t in &traces {
+        let merged = t.merged_from.is_some();
+        tiles.push(TileMove {
+            source: t.from,
+            target: t.to,
+            value: board[t.from.0][t.from.1],
+            merged,
+        });
+        if let Some((r, c)) = t.merged_from {
+            tiles.push(TileMove {
+                source: (r, c),
+                target: t.to,
+                value: board[r][c],
+                merged,
+            });
+        }
+    }
+    tiles.sort_unstable_by_key(|m| m.source);
+    Ok((result, tiles))
+}
+
+/// The `tiles` spawns that turn the slid board `slid` into `after`, fewer
+/// if the board fills up, in row-major order; each tile is checked against
+/// the spawn odds on `slid`, and the tiles against the placement policy in
+/// some order of spawning
+///
+/// # Errors
+/// Returns an error describing why no such spawns can: a tile that
+/// changed, too few or too many new tiles, a tile that never spawns there
+/// or a cell the placement policy leaves alone.
+pub(crate) fn spawns_between(
+    slid: &Board,
+    after: &Board,
+    rules: &Rules,
+    tiles: usize,
+) -> Result<Vec<Spawn>, String> {
+    let empty = slid.iter().flatten().filter(|&&t| t == 0).count();
+    let mut spawns = Vec::new();
+    for r in 0..4 {
+        for c in 0..4 {
+            if slid[r][c] != after[r][c] {
+                if slid[r][c] != 0 {
+                    return Err(format!(
+                        "cell ({r}, {c}) should hold {} after the slide, got {}",
+                        slid[r][c], after[r][c]
+                    ));
+                }
+                spawns.push(Spawn {
+                    cell: (r, c),
+                    value: after[r][c],
+                });
+            }
+        }
+    }
+    let expected = tiles.min(empty);
+    match (expected, spawns.len()) {
+        (e, n) if e == n => {}
+        (0, n) => return Err(format!("{n} tiles spawned, expected none")),
+        (_, 0) => return Err("a valid move must spawn a tile".to_string()),
+        (1, n) => return Err(format!("{n} tiles spawned, expected one")),
+        (e, n) => return Err(format!("{n} tiles spawned, expected {e}")),
+    }
+    let odds = crate::spawn_probabilities(rules, slid);
+    for spawn in &spawns {
+        // mercy rule: no ×1 on the last empty cell
+        if spawn.value == -1 && rules.mercy && empty == 1 {
+            return Err("the mercy rule never spawns ×1 on the last empty cell".to_string());
+        }
+        if !odds
+            .iter()
+            .any(|&(tile, chance)| tile == spawn.value && chance > 0.0)
+        {
+            return Err(format!("{} never spawns", spawn.value));
+        }
+    }
+    if !placeable(slid, &spawns, rules.placement) {
+        return Err(format!(
+            "{} placement never spawns on {}",
+            rules.placement.name(),
+            spawns
+                .iter()
+                .map(|s| format!("({}, {})", s.cell.0, s.cell.1))
+                .collect::<Vec<_>>()
+                .join(" and ")
+        ));
+    }
+    Ok(spawns)
+}
+
+/// Whether `spawns` can land on `board` one after another in some order,
+/// each on a cell `placement` allows on the board the ones before it left
+fn placeable(board: &Board, spawns: &[Spawn], placement: Placement) -> bool {
+    if spawns.is_empty() {
+        return true;
+    }
+    let cells = placement.cells(board);
+    spawns.iter().enumerate().any(|(i, spawn)| {
+        if !cells.contains(&spawn.cell) {
+            return false;
+        }
+        let mut next = *board;
+        next[spawn.cell.0][spawn.cell.1] = spawn.value;
+        let mut rest = spawns.to_vec();
+        rest.remove(i);
+        placeable(&next, &rest, placement)
+    })
+}
+
+/// The move that turns `before` into `after`: its direction and the tile
+/// that spawned, or `None` if no single move can (an unchanged board
+/// included, since only valid moves are played). When several directions
+/// fit, the first in action-id order (`Down, Right, Up, Left`) is returned.
+///
+/// The move spawns as the first move of a game does; under
+/// [`Rules::spawns`] the first of its tiles in row-major order is returned.
+///
+/// ```
+/// use akioi_2048_core::{Direction, Rules, infer_move};
+///
+/// let before = [[2, 2, 0, 0], [0; 4], [0; 4], [0; 4]];
+/// let after = [[4, 0, 0, 0], [0; 4], [0; 4], [0, 0, 0, -1]];
+/// let (dir, spawn) = infer_move(&before, &after, &Rules::AKIOI).unwrap();
+/// assert_eq!(dir, Direction::Left);
+/// assert_eq!((spawn.cell, spawn.value), ((3, 3), -1));
+/// ```
+#[must_use]
+pub fn infer_move(before: &Board, after: &Board, rules: &Rules) -> Option<(Direction, Spawn)> {
+    validate_board_with(before, rules).ok()?;
+    validate_board_with(after, rules).ok()?;
+    ALL_DIRECTIONS.into_iter().find_map(|dir| {
+        let (slid, _, _) = single_step(before, dir, rules);
+        if slid == *before {
+            return None;
+        }
+        spawns_between(&slid, after, rules, rules.tiles_after(0))
+            .ok()
+            .and_then(|spawns| spawns.first().map(|&spawn| (dir, spawn)))
+    })
+}
+
+/// [`step_detailed_with_rng`] letting `place` add the spawned tiles after
+/// a valid move, in order
+///
+/// # Errors
+/// Returns an error if the rules or the board are invalid, or any error of
+/// `place`.
+pub(crate) fn step_detailed_with(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    place: impl FnOnce(&mut Board) -> Result<Vec<Spawn>, String>,
+) -> Result<MoveResult, String> {
+    Ok(traced_step_with(board, direction, rules, place)?.0)
+}
+
+fn traced_step<R: Rng + ?Sized>(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    rng: &mut R,
+    moves: u64,
+) -> Result<(MoveResult, Vec<Trace>), String> {
+    traced_step_with(board, direction, rules, |next| {
+        let tiles = spawn_tiles(next, rng, rules, rules.tiles_after(moves));
+        Ok(tiles
+            .into_iter()
+            .map(|(cell, value)| Spawn { cell, value })
+            .collect())
+    })
+}
+
+/// Slide `board` and, if that changed it, let `spawn` add the tiles
+fn traced_step_with(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    spawn: impl FnOnce(&mut Board) -> Result<Vec<Spawn>, String>,
+) -> Result<(MoveResult, Vec<Trace>), String> {
+    rules.validate()?;
+    validate_board_with(&board, rules)?;
+
+    let (mut next, delta, traces) = single_step_traced(&board, direction, rules);
+    let merges = traces
+        .iter()
+        .filter_map(|t| {
+            t.merged_from.map(|other| {
+                let tiles = [board[t.from.0][t.from.1], board[other.0][other.1]];
+                Merge {
+                    target: t.to,
+                    sources: [t.from, other],
+                    tiles,
+                    kind: MergeKind::of(tiles[0], tiles[1]),
+                    value: t.value,
+                    score: t.score,
+                }
+            })
+        })
+        .collect();
+
+    let moved = next != board;
+    let mut spawns = if moved { spawn(&mut next)? } else { Vec::new() };
+    let spawn = (!spawns.is_empty()).then(|| spawns.remove(0));
+
+    let result = MoveResult {
+        board: next,
+        delta,
+        state: state_of(&next, rules),
+        moved,
+        merges,
+        spawn,
+        extra_spawns: spawns,
+    };
+    Ok((result, traces))
+}
@@ -0,0 +1,355 @@
+//! Differential testing against the web version: replay a game it recorded
+//! through the engine and find the first move the two disagree on.
+//!
+//! A log is JSON lines. The first holds the starting position,
+//! `{"state": …}`, and every other one a move and the position after it,
+//! `{"move": 3, "state": …}`: states in the [`WebSave`] layout, moves in
+//! the web version's numbers (`0` up, `1` right, `2` down, `3` left) or by
+//! name. A move may also name the tile it spawned, as
+//! `"spawn": {"x": 1, "y": 0, "value": 2}` with `x` the column.
+//!
+//! Every move is replayed from the web version's position before it, so a
+//! divergence belongs to that one move: the position after it must be the
+//! engine's slide plus spawns the rules allow, its score the score before
+//! plus the engine's for the move, and it must be over exactly when the
+//! engine finds no move left. Blank lines and lines starting with `#` are
+//! skipped.
+//!
+//! Rows and columns slide independently of each other, so a slide that
+//! diverges shrinks to the one line that differs: [`Divergence::minimize`]
+//! gives it read from the wall it slides toward, as a row sliding left.
+//!
+//! ```
+//! use akioi_2048_core::differential::{Kind, WebLog, first_divergence};
+//! use akioi_2048_core::web::WebSave;
+//! use akioi_2048_core::Rules;
+//!
+//! let save = |board, score| {
+//!     let save = WebSave { board, score, over: false, won: false, keep_playing: false };
+//!     save.to_json()
+//! };
+//! let start = save([[0; 4], [2, 2, 0, 0], [0; 4], [0; 4]], 0);
+//! // a web version merging 2 + 2 into 8
+//! let after = save([[0; 4], [8, 0, 0, 0], [0; 4], [0, 0, 0, 2]], 4);
+//! let text = format!("{{\"state\": {start}}}\n{{\"move\": 3, \"state\": {after}}}");
+//! let log = WebLog::parse(&text).unwrap();
+//!
+//! let divergence = first_divergence(&log, &Rules::CLASSIC).unwrap().unwrap();
+//! assert_eq!((divergence.move_number, divergence.kind), (1, Kind::Slide));
+//! let case = divergence.minimize().unwrap();
+//! assert_eq!((case.engine, case.web), ([4, 0, 0, 0], [8, 0, 0, 0]));
+//! ```
+
+use crate::detail::{Cell, Spawn, spawns_between};
+use crate::json::Json;
+use crate::web::WebSave;
+use crate::{Board, Direction, Engine, Rules, State};
+
+/// What a [`Divergence`] disagrees on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A tile the slide leaves differs from the web version's
+    Slide,
+    /// The tiles added after the slide are not spawns the rules allow, or
+    /// not the one the log names
+    Spawn,
+    /// The score moved by another amount than the engine scores the move
+    Score,
+    /// The web version's game-over flag disagrees with the engine
+    Over,
+}
+
+impl Kind {
+    /// The kind in lowercase, as the Python API and the CLI give it
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Slide => "slide",
+            Kind::Spawn => "spawn",
+            Kind::Score => "score",
+            Kind::Over => "over",
+        }
+    }
+}
+
+/// One move of a [`WebLog`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebMove {
+    pub direction: Direction,
+    /// The tile the log says the move spawned, if it says
+    pub spawn: Option<Spawn>,
+    /// The position after the move
+    pub save: WebSave,
+}
+
+/// A game the web version recorded: its starting position and every move
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebLog {
+    pub start: WebSave,
+    pub moves: Vec<WebMove>,
+}
+
+impl WebLog {
+    /// Read a log in the format of the [module documentation](self).
+    ///
+    /// # Errors
+    /// Returns an error naming the line of malformed JSON, a bad state or
+    /// move, or a log without a starting position.
+    pub fn parse(text: &str) -> Result<WebLog, String> {
+        let mut start = None;
+        let mut moves = Vec::new();
+        for (number, line) in (1..).zip(text.lines()) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = (|| {
+                let root = Json::parse(line, "log")?;
+                let save = WebSave::from_json(root.field("state")?)?;
+                if start.is_none() {
+                    start = Some(save);
+                    return Ok(());
+                }
+                let spawn = root.get("spawn").map(spawn_param).transpose()?;
+                moves.push(WebMove {
+                    direction: direction_param(root.field("move")?)?,
+                    spawn,
+                    save,
+                });
+                Ok::<(), String>(())
+            })();
+            entry.map_err(|e| format!("line {number}: {e}"))?;
+        }
+        let start = start.ok_or("the log has no starting position")?;
+        Ok(WebLog { start, moves })
+    }
+}
+
+/// The first move of a [`WebLog`] the engine plays otherwise
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// The move, counting from 1
+    pub move_number: usize,
+    pub kind: Kind,
+    pub direction: Direction,
+    /// The web version's position before the move
+    pub before: WebSave,
+    /// The web version's position after it
+    pub web: WebSave,
+    /// The engine's slide of the position before, without spawns
+    pub slid: Board,
+    /// The score the engine gives the position after the move
+    pub score: i64,
+    /// What differs, in a sentence
+    pub message: String,
+}
+
+/// A divergent slide shrunk to a single line, read from the wall it
+/// slides toward: the row that slides left to the same disagreement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Case {
+    pub line: [i32; 4],
+    /// The line after the engine's slide
+    pub engine: [i32; 4],
+    /// The line after the web version's move, which may hold the tile it
+    /// spawned there
+    pub web: [i32; 4],
+}
+
+impl Divergence {
+    /// The smallest case of this divergence: the first line whose slide
+    /// differs, alone, or `None` for divergences other than
+    /// [`Kind::Slide`], which depend on the whole board
+    #[must_use]
+    pub fn minimize(&self) -> Option<Case> {
+        if self.kind != Kind::Slide {
+            return None;
+        }
+        (0..4).find_map(|i| {
+            let cells = from_wall(self.direction, i);
+            let read = |board: &Board| cells.map(|(r, c)| board[r][c]);
+            let (engine, web) = (read(&self.slid), read(&self.web.board));
+            engine
+                .iter()
+                .zip(web)
+                .any(|(&e, w)| e != 0 && e != w)
+                .then(|| Case {
+                    line: read(&self.before.board),
+                    engine,
+                    web,
+                })
+        })
+    }
+}
+
+/// Replay `log` through the engine under `rules` and find the first move
+/// the web version plays otherwise, or `None` when every move agrees.
+///
+/// # Errors
+/// Returns an error for invalid rules or a starting position invalid under
+/// them.
+pub fn first_divergence(log: &WebLog, rules: &Rules) -> Result<Option<Divergence>, String> {
+    let engine = Engine::new(*rules)?;
+    crate::validate_board_with(&log.start.board, rules)
+        .map_err(|e| format!("starting position: {e}"))?;
+    let mut before = log.start;
+    let mut played = 0;
+    for (number, web_move) in (1..).zip(&log.moves) {
+        let (direction, web) = (web_move.direction, web_move.save);
+        let (slid, delta) = engine.slide(&before.board, direction);
+        let score = before.score + i64::from(delta);
+        let diverge = |kind, message| Divergence {
+            move_number: number,
+            kind,
+            direction,
+            before,
+            web,
+            slid,
+            score,
+            message,
+        };
+        if let Some(message) = slide_difference(&slid, &web.board, direction) {
+            return Ok(Some(diverge(Kind::Slide, message)));
+        }
+        let tiles = if slid == before.board {
+            0
+        } else {
+            played += 1;
+            rules.tiles_after(played - 1)
+        };
+        if let Err(message) = check_spawns(&slid, web_move, rules, tiles, direction) {
+            return Ok(Some(diverge(Kind::Spawn, message)));
+        }
+        if web.score != score {
+            let message = format!(
+                "{direction:?} scores {delta} in the engine, the web version's score went from {} to {}",
+                before.score, web.score
+            );
+            return Ok(Some(diverge(Kind::Score, message)));
+        }
+        let over = engine.state(&web.board) == State::GameOver;
+        if web.over != over {
+            let message = if over {
+                "no move is left in the engine, the web version plays on"
+            } else {
+                "the web version ends the game, the engine still has a move"
+            };
+            return Ok(Some(diverge(Kind::Over, message.to_string())));
+        }
+        before = web;
+    }
+    Ok(None)
+}
+
+/// The first row or column holding a tile after the engine's slide that
+/// the web version's board lacks, described
+fn slide_difference(slid: &Board, web: &Board, direction: Direction) -> Option<String> {
+    (0..4).find_map(|i| {
+        let cells = in_order(direction, i);
+        let read = |board: &Board| cells.map(|(r, c)| board[r][c]);
+        let (engine, theirs) = (read(slid), read(web));
+        let differs = engine.iter().zip(theirs).any(|(&e, w)| e != 0 && e != w);
+        let line = if matches!(direction, Direction::Left | Direction::Right) {
+            "row"
+        } else {
+            "column"
+        };
+        differs.then(|| {
+            format!(
+                "{line} {} slides {direction:?} to {engine:?} in the engine, the web version has {theirs:?}",
+                i + 1
+            )
+        })
+    })
+}
+
+/// Whether the tiles `web_move` adds to `slid` are spawns the rules allow,
+/// `tiles` of them, and hold the one the log names
+fn check_spawns(
+    slid: &Board,
+    web_move: &WebMove,
+    rules: &Rules,
+    tiles: usize,
+    direction: Direction,
+) -> Result<(), String> {
+    if tiles == 0 && web_move.save.board != *slid {
+        return Err(if rules.spawn_every > 1 {
+            format!(
+                "no tile spawns after this move under spawn_every={}",
+                rules.spawn_every
+            )
+        } else {
+            format!("{direction:?} does not change the board, so no tile spawns")
+        });
+    }
+    let spawns = spawns_between(slid, &web_move.save.board, rules, tiles)?;
+    match web_move.spawn {
+        Some(named) if !spawns.contains(&named) => Err(format!(
+            "the log names a {} spawned at row {}, column {}, the board does not hold it",
+            named.value,
+            named.cell.0 + 1,
+            named.cell.1 + 1
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Cells of line `i` of a move in `direction`, left to right or top to
+/// bottom
+fn in_order(direction: Direction, i: usize) -> [Cell; 4] {
+    match direction {
+        Direction::Left | Direction::Right => [(i, 0), (i, 1), (i, 2), (i, 3)],
+        Direction::Up | Direction::Down => [(0, i), (1, i), (2, i), (3, i)],
+    }
+}
+
+/// Cells of line `i` of a move in `direction`, from the wall it slides
+/// toward
+fn from_wall(direction: Direction, i: usize) -> [Cell; 4] {
+    let mut cells = in_order(direction, i);
+    if matches!(direction, Direction::Right | Direction::Down) {
+        cells.reverse();
+    }
+    cells
+}
+
+/// A direction in the web version's numbers or by name
+fn direction_param(value: &Json) -> Result<Direction, String> {
+    if let Json::String(name) = value {
+        return ["up", "right", "down", "left"]
+            .iter()
+            .position(|d| d.eq_ignore_ascii_case(name))
+            .map(web_direction)
+            .ok_or_else(|| format!("unknown direction: {name}"));
+    }
+    let id: usize = value.integer()?;
+    if id > 3 {
+        return Err(format!("move must be between 0 and 3, got: {id}"));
+    }
+    Ok(web_direction(id))
+}
+
+/// The direction the web version numbers `id`
+fn web_direction(id: usize) -> Direction {
+    [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ][id]
+}
+
+/// A `{"x", "y", "value"}` spawn, `x` the column
+fn spawn_param(value: &Json) -> Result<Spawn, String> {
+    let coordinate = |name| {
+        let n: usize = value.field(name)?.integer()?;
+        if n > 3 {
+            return Err(format!("spawn `{name}` must be between 0 and 3, got: {n}"));
+        }
+        Ok(n)
+    };
+    Ok(Spawn {
+        cell: (coordinate("y")?, coordinate("x")?),
+        value: value.field("value")?.integer()?,
+    })
+}
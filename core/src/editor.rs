@@ -0,0 +1,125 @@
+//! Building boards cell by cell for test scenarios and puzzles, with every
+//! tile checked under the rules as it is placed.
+//!
+//! Valid tiles can still make a board no game reaches, such as two winning
+//! tiles; [`BoardBuilder::warnings`] names those, leaving it to the caller
+//! whether an unreachable scenario matters.
+
+use crate::notation::to_notation;
+use crate::variant::MergeRule;
+use crate::{BLOCKER, Board, Rules};
+
+/// Blockers a board can hold and still fit the two starting tiles
+const MAX_BLOCKERS: usize = 14;
+
+/// A board under construction; see the [module docs](self).
+///
+/// ```
+/// use akioi_2048_core::editor::BoardBuilder;
+/// use akioi_2048_core::Rules;
+///
+/// let mut builder = BoardBuilder::new(Rules::AKIOI);
+/// builder.set(3, 0, 65536).unwrap().set(3, 1, 65536).unwrap();
+/// assert!(builder.set(0, 0, 3).is_err());
+/// assert_eq!(
+///     builder.warnings(),
+///     ["2 tiles of 65536: a game is won at the first"]
+/// );
+/// assert_eq!(builder.board()[3], [65536, 65536, 0, 0]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoardBuilder {
+    board: Board,
+    rules: Rules,
+}
+
+impl BoardBuilder {
+    /// An empty board under `rules`
+    #[must_use]
+    pub fn new(rules: Rules) -> BoardBuilder {
+        BoardBuilder {
+            board: [[0; 4]; 4],
+            rules,
+        }
+    }
+
+    /// Start from `board` instead of an empty one.
+    ///
+    /// # Errors
+    /// Returns an error naming the first tile invalid under `rules`.
+    pub fn from_board(board: Board, rules: Rules) -> Result<BoardBuilder, String> {
+        crate::validate_board_with(&board, &rules)?;
+        Ok(BoardBuilder { board, rules })
+    }
+
+    /// Put `value` at row `row`, column `col` (both from 0, top left);
+    /// `0` empties the cell.
+    ///
+    /// # Errors
+    /// Returns an error for a cell off the board or a tile invalid under the
+    /// rules, leaving the board as it was.
+    pub fn set(&mut self, row: usize, col: usize, value: i32) -> Result<&mut Self, String> {
+        if row >= 4 || col >= 4 {
+            return Err(format!("cell ({row}, {col}) is off the 4x4 board"));
+        }
+        let mut board = self.board;
+        board[row][col] = value;
+        crate::validate_board_with(&board, &self.rules)?;
+        self.board = board;
+        Ok(self)
+    }
+
+    /// Empty every cell
+    pub fn clear(&mut self) -> &mut Self {
+        self.board = [[0; 4]; 4];
+        self
+    }
+
+    #[must_use]
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    #[must_use]
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Why no game under the rules reaches the board, one reason each;
+    /// empty when nothing rules it out
+    #[must_use]
+    pub fn warnings(&self) -> Vec<String> {
+        let cells = self.board.iter().flatten();
+        let blockers = cells.clone().filter(|&&t| t == BLOCKER).count();
+        let tiles = cells.clone().filter(|&&t| t != 0 && t != BLOCKER).count();
+        let goal = self.rules.variant.winning_tile(&self.rules);
+        let winners = cells.filter(|&&t| t == goal).count();
+
+        let mut warnings = Vec::new();
+        if winners > 1 {
+            warnings.push(format!(
+                "{winners} tiles of {goal}: a game is won at the first"
+            ));
+        }
+        if blockers > MAX_BLOCKERS {
+            warnings.push(format!(
+                "{blockers} blockers leave no room for the two starting tiles"
+            ));
+        }
+        // A slide keeps a tile and the spawn adds one; only a bomb clears
+        // a line on its own
+        if tiles == 0 {
+            warnings.push("no tiles: a spawn follows every move".to_string());
+        } else if tiles == 1 && !self.rules.bombs {
+            warnings
+                .push("only 1 tile: without bombs every position holds at least two".to_string());
+        }
+        warnings
+    }
+
+    /// The board in [notation](crate::notation)
+    #[must_use]
+    pub fn notation(&self) -> String {
+        to_notation(&self.board)
+    }
+}
@@ -0,0 +1,105 @@
+//! Boards as `f32` planes for neural networks, channel-first.
+
+use crate::variant::MergeRule;
+use crate::{BLOCKER, BOMB, Board, Rules, validate_board_with};
+
+/// How [`encode`] lays out a board
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scheme {
+    /// One plane holding the tile values as they are
+    Raw,
+    /// Plane 0: `log2` of number tiles; plane 1: `log2(m) + 1` of
+    /// multiplier ×m tiles, `-1` for blockers and `-2` for bombs; 0
+    /// elsewhere
+    Log2,
+    /// Plane 0: empty cells; planes 1–16: number tiles 2…65536, or up to
+    /// the rules' largest tile under another
+    /// [`max_tile`](crate::Rules::max_tile); then one plane per multiplier
+    /// ×1, ×2, … up to the rules' largest. Blockers and bombs are the cells
+    /// set in no plane.
+    OneHot,
+}
+
+impl Scheme {
+    /// Look up a scheme by its name (`"raw"`, `"log2"` or `"onehot"`)
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Scheme> {
+        match name {
+            "raw" => Some(Scheme::Raw),
+            "log2" => Some(Scheme::Log2),
+            "onehot" => Some(Scheme::OneHot),
+            _ => None,
+        }
+    }
+
+    /// Planes per board under `rules`
+    #[must_use]
+    pub fn channels(self, rules: &Rules) -> usize {
+        match self {
+            Scheme::Raw => 1,
+            Scheme::Log2 => 2,
+            Scheme::OneHot => 1 + number_planes(rules) + multiplier_planes(rules),
+        }
+    }
+}
+
+/// 2, 4, … the largest number tile
+fn number_planes(rules: &Rules) -> usize {
+    let largest = rules.variant.winning_tile(rules).max(rules.max_tile);
+    largest.ilog2() as usize
+}
+
+/// ×1, ×2, … ×`max_multiplier`
+fn multiplier_planes(rules: &Rules) -> usize {
+    rules.max_multiplier.ilog2() as usize + 1
+}
+
+/// Append the planes of `board` to `out`: `scheme.channels(rules)` planes of
+/// 16 cells each, in row-major order.
+///
+/// ```
+/// use akioi_2048_core::Rules;
+/// use akioi_2048_core::encode::{Scheme, encode};
+///
+/// let board = [[2, 0, 0, 0], [0, -2, 0, 0], [0; 4], [0; 4]];
+/// let mut planes = Vec::new();
+/// encode(&board, Scheme::Log2, &Rules::AKIOI, &mut planes).unwrap();
+/// assert_eq!(planes[0], 1.0); // log2(2) in the number plane
+/// assert_eq!(planes[16 + 5], 2.0); // ×2 in the multiplier plane
+/// ```
+///
+/// # Errors
+/// Returns an error if the board holds tiles invalid under `rules`.
+pub fn encode(
+    board: &Board,
+    scheme: Scheme,
+    rules: &Rules,
+    out: &mut Vec<f32>,
+) -> Result<(), String> {
+    validate_board_with(board, rules)?;
+    let start = out.len();
+    out.resize(start + scheme.channels(rules) * 16, 0.0);
+    let planes = &mut out[start..];
+    let numbers = number_planes(rules);
+    for (cell, &tile) in board.iter().flatten().enumerate() {
+        let exponent = tile.unsigned_abs().max(1).ilog2() as usize;
+        match scheme {
+            Scheme::Raw => planes[cell] = tile as f32,
+            Scheme::Log2 if tile == BLOCKER => planes[16 + cell] = -1.0,
+            Scheme::Log2 if tile == BOMB => planes[16 + cell] = -2.0,
+            _ if tile == BLOCKER || tile == BOMB => {}
+            Scheme::Log2 if tile > 0 => planes[cell] = exponent as f32,
+            Scheme::Log2 if tile < 0 => planes[16 + cell] = (exponent + 1) as f32,
+            Scheme::Log2 => {}
+            Scheme::OneHot => {
+                let plane = match tile {
+                    0 => 0,
+                    t if t > 0 => exponent,
+                    _ => 1 + numbers + exponent,
+                };
+                planes[plane * 16 + cell] = 1.0;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::detail::{MoveResult, step_detailed_with_rng};
+use crate::game::{is_dead, single_step, state_of, status_of};
+use crate::{ALL_DIRECTIONS, Board, Move, Rng, Rules, State, Status};
+
+/// The game mechanics under one validated ruleset.
+///
+/// A stateless, `Copy` handle for callers that manage boards and
+/// randomness themselves (search, servers, WebAssembly); see
+/// [`Game`](crate::Game) for a stateful seeded game.
+///
+/// ```
+/// use akioi_2048_core::{Engine, Move, Rng};
+///
+/// /// Always the first empty cell and the most common tile
+/// struct Fixed;
+///
+/// impl Rng for Fixed {
+///     fn index(&mut self, _len: usize) -> usize {
+///         0
+///     }
+///     fn unit(&mut self) -> f64 {
+///         0.0
+///     }
+/// }
+///
+/// let engine = Engine::AKIOI;
+/// let board = engine.init(&mut Fixed);
+/// assert_eq!(board[0], [2, 2, 0, 0]);
+/// let (next, delta, _) = engine.step(board, Move::Left, &mut Fixed).unwrap();
+/// assert_eq!((next[0], delta), ([4, 2, 0, 0], 4));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Engine {
+    rules: Rules,
+}
+
+impl Engine {
+    /// The standard akioi rules
+    pub const AKIOI: Engine = Engine {
+        rules: Rules::AKIOI,
+    };
+
+    /// # Errors
+    /// Returns an error if the rules are inconsistent.
+    pub fn new(rules: Rules) -> Result<Self, String> {
+        rules.validate()?;
+        Ok(Self { rules })
+    }
+
+    #[must_use]
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// A fresh board with two tiles drawn from `rng`
+    #[must_use]
+    pub fn init<R: Rng + ?Sized>(&self, rng: &mut R) -> Board {
+        crate::init_with_rules(rng, &self.rules)
+    }
+
+    /// Apply `mv` and spawn a tile from `rng` if the board changed.
+    ///
+    /// # Errors
+    /// Returns an error if the board contains tiles invalid under the rules.
+    pub fn step<R: Rng + ?Sized>(
+        &self,
+        board: Board,
+        mv: Move,
+        rng: &mut R,
+    ) -> Result<(Board, i32, State), String> {
+        crate::step_with_rng(board, mv, &self.rules, rng)
+    }
+
+    /// [`Engine::step`] for a move that `moves` others changing the board
+    /// came before; see [`step_after`](crate::step_after)
+    ///
+    /// # Errors
+    /// Returns an error if the board contains tiles invalid under the rules.
+    pub fn step_after<R: Rng + ?Sized>(
+        &self,
+        board: Board,
+        mv: Move,
+        rng: &mut R,
+        moves: u64,
+    ) -> Result<(Board, i32, State), String> {
+        crate::step_after(board, mv, &self.rules, rng, moves)
+    }
+
+    /// [`Engine::step`] returning a full [`MoveResult`]
+    ///
+    /// # Errors
+    /// Returns an error if the board contains tiles invalid under the rules.
+    pub fn step_detailed<R: Rng + ?Sized>(
+        &self,
+        board: Board,
+        mv: Move,
+        rng: &mut R,
+    ) -> Result<MoveResult, String> {
+        step_detailed_with_rng(board, mv, &self.rules, rng)
+    }
+
+    /// Slide without spawning: the afterstate and the score gained.
+    ///
+    /// The board is not validated, which keeps this cheap enough for search.
+    #[must_use]
+    pub fn slide(&self, board: &Board, mv: Move) -> (Board, i32) {
+        let (next, delta, _) = single_step(board, mv, &self.rules);
+        (next, delta)
+    }
+
+    /// Moves that change `board`, in [`ALL_DIRECTIONS`] order
+    pub fn legal_moves(&self, board: &Board) -> impl Iterator<Item = Move> + '_ {
+        let board = *board;
+        ALL_DIRECTIONS
+            .into_iter()
+            .filter(move |&mv| self.slide(&board, mv).0 != board)
+    }
+
+    /// Whether no move changes `board`
+    #[must_use]
+    pub fn is_over(&self, board: &Board) -> bool {
+        is_dead(board, &self.rules)
+    }
+
+    /// Whether `board` holds the winning tile of the rules' variant:
+    /// usually 65536
+    #[must_use]
+    pub fn is_won(&self, board: &Board) -> bool {
+        crate::game::is_won_with(board, &self.rules)
+    }
+
+    #[must_use]
+    pub fn state(&self, board: &Board) -> State {
+        state_of(board, &self.rules)
+    }
+
+    /// [`state`](Self::state) with its reason and whether any move remains
+    #[must_use]
+    pub fn status(&self, board: &Board) -> Status {
+        status_of(board, &self.rules)
+    }
+}
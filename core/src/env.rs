@@ -0,0 +1,190 @@
+//! Single-game reinforcement-learning environment with configurable rewards.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::detail::MoveResult;
+use crate::session::{Game, InvalidMoves, random_seed};
+use crate::{Board, Direction, Rules, State};
+
+/// Base term of a [`RewardSpec`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RewardKind {
+    /// The score delta of the move
+    #[default]
+    Score,
+    /// `log2(1 + |delta|)` with the sign of the delta
+    LogScore,
+    /// Number of merges in the move
+    Merges,
+}
+
+impl RewardKind {
+    /// Look up a kind by its name (`"score"`, `"log_score"` or `"merges"`)
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<RewardKind> {
+        match name {
+            "score" => Some(RewardKind::Score),
+            "log_score" => Some(RewardKind::LogScore),
+            "merges" => Some(RewardKind::Merges),
+            _ => None,
+        }
+    }
+}
+
+/// How [`Env::step`] turns a move into a reward: the base term, plus
+/// `empty_bonus` per empty cell after the move, plus `victory_bonus` on the
+/// winning move, minus `game_over_penalty` on the losing one. Under
+/// [`InvalidMoves::Penalize`] moves that change nothing also lose
+/// `invalid_penalty`.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct RewardSpec {
+    pub kind: RewardKind,
+    pub empty_bonus: f64,
+    pub game_over_penalty: f64,
+    pub victory_bonus: f64,
+    pub invalid_penalty: f64,
+}
+
+impl RewardSpec {
+    /// Reward for a move that produced `result`
+    #[must_use]
+    pub fn reward(&self, result: &MoveResult) -> f64 {
+        let delta = f64::from(result.delta);
+        let base = match self.kind {
+            RewardKind::Score => delta,
+            RewardKind::LogScore => delta.signum() * delta.abs().ln_1p() / std::f64::consts::LN_2,
+            RewardKind::Merges => result.merges.len() as f64,
+        };
+        let empty = result.board.iter().flatten().filter(|&&t| t == 0).count();
+        let terminal = match result.state {
+            State::Victory => self.victory_bonus,
+            State::GameOver => -self.game_over_penalty,
+            State::Continue => 0.0,
+        };
+        base + self.empty_bonus * empty as f64 + terminal
+    }
+}
+
+/// Outcome of one [`Env::step`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvStep {
+    pub result: MoveResult,
+    pub reward: f64,
+    /// The game was won or lost
+    pub terminated: bool,
+    /// The move limit was reached first
+    pub truncated: bool,
+}
+
+/// Settings of an [`Env`]; the defaults are standard rules, score rewards
+/// and no move limit
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct EnvOptions {
+    pub rules: Rules,
+    pub reward: RewardSpec,
+    /// Truncate episodes after this many moves
+    pub max_moves: Option<usize>,
+    pub invalid_moves: InvalidMoves,
+    /// See [`GameBuilder::keep_playing`](crate::GameBuilder::keep_playing)
+    pub keep_playing: bool,
+}
+
+/// One seeded game behind a `reset`/`step` interface.
+///
+/// Every reset starts a new game whose seed is drawn from the environment
+/// seed, so equal seeds and actions give equal episodes.
+///
+/// ```
+/// use akioi_2048_core::Direction;
+/// use akioi_2048_core::env::{Env, EnvOptions, RewardKind, RewardSpec};
+///
+/// let reward = RewardSpec { kind: RewardKind::Merges, ..RewardSpec::default() };
+/// let mut env = Env::new(Some(3), EnvOptions { reward, ..EnvOptions::default() }).unwrap();
+/// let step = env.step(Direction::Down).unwrap();
+/// assert_eq!(step.reward, step.result.merges.len() as f64);
+/// ```
+pub struct Env {
+    options: EnvOptions,
+    seeds: StdRng,
+    game: Game,
+}
+
+impl Env {
+    /// Start the first episode; a random seed is picked when `seed` is
+    /// `None`.
+    ///
+    /// # Errors
+    /// Returns an error if the rules are inconsistent.
+    pub fn new(seed: Option<u64>, options: EnvOptions) -> Result<Self, String> {
+        let mut seeds = StdRng::seed_from_u64(seed.unwrap_or_else(random_seed));
+        let game = new_game(&mut seeds, &options)?;
+        Ok(Self {
+            options,
+            seeds,
+            game,
+        })
+    }
+
+    /// Start a new episode and return its board; `seed` restarts the
+    /// sequence of episode seeds.
+    pub fn reset(&mut self, seed: Option<u64>) -> Board {
+        if let Some(seed) = seed {
+            self.seeds = StdRng::seed_from_u64(seed);
+        }
+        self.game = new_game(&mut self.seeds, &self.options).expect("rules were validated");
+        self.game.board()
+    }
+
+    /// Apply one move to the current episode
+    ///
+    /// # Errors
+    /// Returns an error for a move that changes nothing under
+    /// [`InvalidMoves::Mask`]; the episode is left untouched.
+    pub fn step(&mut self, direction: Direction) -> Result<EnvStep, String> {
+        let result = self.game.try_step(direction)?;
+        let terminated = self.game.is_over();
+        let truncated = !terminated
+            && self
+                .options
+                .max_moves
+                .is_some_and(|m| self.game.moves() >= m);
+        Ok(EnvStep {
+            reward: self.options.reward.reward(&result) - self.invalid_penalty(&result),
+            result,
+            terminated,
+            truncated,
+        })
+    }
+
+    /// What [`step`](Self::step) subtracts from the reward of `result` for
+    /// changing nothing under [`InvalidMoves::Penalize`]
+    #[must_use]
+    pub fn invalid_penalty(&self, result: &MoveResult) -> f64 {
+        if !result.moved && self.options.invalid_moves == InvalidMoves::Penalize {
+            self.options.reward.invalid_penalty
+        } else {
+            0.0
+        }
+    }
+
+    /// The current episode
+    #[must_use]
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    #[must_use]
+    pub fn options(&self) -> EnvOptions {
+        self.options
+    }
+}
+
+fn new_game(seeds: &mut StdRng, options: &EnvOptions) -> Result<Game, String> {
+    Game::builder()
+        .seed(seeds.random())
+        .rules(options.rules)
+        .invalid_moves(options.invalid_moves)
+        .keep_playing(options.keep_playing)
+        .build()
+}
@@ -0,0 +1,721 @@
+use rand::rng;
+
+use crate::actions::{ALL_DIRECTIONS, Direction, EndReason, State, Status};
+use crate::board::{BLOCKER, BOMB, Board, validate_board_with};
+use crate::rng::Rng;
+use crate::rules::{Difficulty, Rules};
+use crate::variant::{MergeRule, SpawnTable};
+
+/// Apply one move; if the board changes a new tile is spawned at random.
+///
+/// # Errors
+/// Returns an error if the board contains invalid tiles.
+pub fn step(board: Board, direction: Direction) -> Result<(Board, i32, State), String> {
+    step_with_rules(board, direction, &Rules::AKIOI)
+}
+
+/// Apply one move under `rules`; if the board changes a new tile is spawned at random.
+///
+/// # Errors
+/// Returns an error if the rules are inconsistent or the board contains
+/// tiles that are invalid under them.
+pub fn step_with_rules(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+) -> Result<(Board, i32, State), String> {
+    step_with_rng(board, direction, rules, &mut rng())
+}
+
+/// Apply one move under `rules`, drawing the spawned tile from `rng`.
+///
+/// The same board, direction and RNG state always produce the same result.
+/// The move spawns as the first of a game does, [`Rules::spawns`] tiles
+/// whatever [`Rules::spawn_every`]; [`step_after`] counts earlier moves.
+///
+/// # Errors
+/// Returns an error if the rules are inconsistent or the board contains
+/// tiles that are invalid under them.
+pub fn step_with_rng<R: Rng + ?Sized>(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    rng: &mut R,
+) -> Result<(Board, i32, State), String> {
+    validate_board_with(&board, rules)?;
+    step_unvalidated(board, direction, rules, rng)
+}
+
+/// [`step_with_rng`] without checking the tiles, for experiments with
+/// custom tile sets: the merge rules apply to whatever values are on the
+/// board (numbers merge only below the rules'
+/// [`max_tile`](Rules::max_tile)).
+///
+/// # Errors
+/// Returns an error if the rules are inconsistent.
+pub fn step_unvalidated<R: Rng + ?Sized>(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    rng: &mut R,
+) -> Result<(Board, i32, State), String> {
+    rules.validate()?;
+    Ok(slide_and_spawn(
+        board,
+        direction,
+        rules,
+        rng,
+        rules.tiles_after(0),
+    ))
+}
+
+/// [`step_with_rng`] for a move that `moves` others changing the board came
+/// before, spawning the tiles [`Rules::tiles_after`] gives it: none between
+/// the moves that spawn under [`Rules::spawn_every`].
+///
+/// ```
+/// use akioi_2048_core::{Direction, Rules, step_after};
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+///
+/// let rules = Rules { spawn_every: 2, ..Rules::AKIOI };
+/// let board = [[2, 0, 0, 0], [0; 4], [0; 4], [0; 4]];
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let (next, _, _) = step_after(board, Direction::Right, &rules, &mut rng, 1).unwrap();
+/// assert_eq!(next, [[0, 0, 0, 2], [0; 4], [0; 4], [0; 4]]);
+/// ```
+///
+/// # Errors
+/// Returns an error if the rules are inconsistent or the board contains
+/// tiles that are invalid under them.
+pub fn step_after<R: Rng + ?Sized>(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    rng: &mut R,
+    moves: u64,
+) -> Result<(Board, i32, State), String> {
+    validate_board_with(&board, rules)?;
+    rules.validate()?;
+    Ok(slide_and_spawn(
+        board,
+        direction,
+        rules,
+        rng,
+        rules.tiles_after(moves),
+    ))
+}
+
+/// Slide `board` and, if that changed it, spawn `tiles` tiles
+fn slide_and_spawn<R: Rng + ?Sized>(
+    board: Board,
+    direction: Direction,
+    rules: &Rules,
+    rng: &mut R,
+    tiles: usize,
+) -> (Board, i32, State) {
+    // ③ Perform one logical step
+    let (mut next, delta, _) = single_step(&board, direction, rules);
+
+    let moved = next != board;
+    if moved {
+        spawn_tiles(&mut next, rng, rules, tiles); // rule: spawn after a valid move
+    }
+
+    (next, delta, state_of(&next, rules))
+}
+
+/// Whether `board` holds a 65536 tile
+#[must_use]
+pub fn is_won(board: &Board) -> bool {
+    board.iter().flatten().any(|&v| v == 0x0001_0000)
+}
+
+/// Whether `board` holds the winning tile of the rules' variant
+pub(crate) fn is_won_with(board: &Board, rules: &Rules) -> bool {
+    let goal = rules.variant.winning_tile(rules);
+    board.iter().flatten().any(|&v| v == goal)
+}
+
+/// Whether no move in any of the four directions changes `board` under the
+/// standard rules; see [`Engine::is_over`](crate::Engine::is_over) for other
+/// rules
+#[must_use]
+pub fn is_game_over(board: &Board) -> bool {
+    is_dead(board, &Rules::AKIOI)
+}
+
+/// Classify a board: the winning tile (usually 65536) wins, no legal move
+/// loses
+pub(crate) fn state_of(board: &Board, rules: &Rules) -> State {
+    if is_won_with(board, rules) {
+        State::Victory
+    } else if is_dead(board, rules) {
+        State::GameOver
+    } else {
+        State::Continue
+    }
+}
+
+/// ④ Check failure (no moves in any direction)
+pub(crate) fn is_dead(board: &Board, rules: &Rules) -> bool {
+    ALL_DIRECTIONS
+        .iter()
+        .copied()
+        .all(|d| single_step(board, d, rules).0 == *board)
+}
+
+/// [`state_of`] with the reason and whether any move remains
+pub(crate) fn status_of(board: &Board, rules: &Rules) -> Status {
+    let state = state_of(board, rules);
+    let reason = match state {
+        State::Victory => Some(EndReason::VictoryTile),
+        State::GameOver => Some(EndReason::NoLegalMoves),
+        State::Continue => None,
+    };
+    Status {
+        state,
+        reason,
+        can_continue: match state {
+            State::Victory => !is_dead(board, rules),
+            State::GameOver => false,
+            State::Continue => true,
+        },
+    }
+}
+
+/// Initialize a new board with two tiles
+///
+/// :returns: A fresh 4×4 board
+#[must_use]
+pub fn init() -> Board {
+    init_with_rng(&mut rng())
+}
+
+/// Initialize a new board with two tiles drawn from `rng`
+#[must_use]
+pub fn init_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Board {
+    init_with_rules(rng, &Rules::AKIOI)
+}
+
+/// Initialize a new board with two tiles drawn from `rng` and spawned as
+/// under `rules`
+///
+/// ```
+/// use akioi_2048_core::{Rules, init_with_rules};
+/// use rand::{SeedableRng, rngs::StdRng};
+///
+/// let board = init_with_rules(&mut StdRng::seed_from_u64(3), &Rules::CLASSIC);
+/// assert!(board.iter().flatten().all(|&t| t == 0 || t == 2 || t == 4));
+/// ```
+#[must_use]
+pub fn init_with_rules<R: Rng + ?Sized>(rng: &mut R, rules: &Rules) -> Board {
+    let mut board: Board = [[0; 4]; 4];
+    spawn_tile(&mut board, rng, rules);
+    spawn_tile(&mut board, rng, rules);
+
+    board
+}
+
+/// Initialize a new board with `blockers` [`BLOCKER`]s on random cells,
+/// then two tiles spawned as under `rules`, all drawn from `rng`
+///
+/// ```
+/// use akioi_2048_core::{BLOCKER, Rules, init_with_blockers};
+/// use rand::{SeedableRng, rngs::StdRng};
+///
+/// let rules = Rules { blockers: true, ..Rules::AKIOI };
+/// let board = init_with_blockers(&mut StdRng::seed_from_u64(3), 2, &rules).unwrap();
+/// let tiles: Vec<i32> = board.iter().flatten().copied().filter(|&t| t != 0).collect();
+/// assert_eq!(tiles.iter().filter(|&&t| t == BLOCKER).count(), 2);
+/// assert_eq!(tiles.len(), 4);
+/// ```
+///
+/// # Errors
+/// Returns an error for blockers under rules without
+/// [`blockers`](Rules::blockers), or more than 14, which leave no room for
+/// the two tiles.
+pub fn init_with_blockers<R: Rng + ?Sized>(
+    rng: &mut R,
+    blockers: usize,
+    rules: &Rules,
+) -> Result<Board, String> {
+    if blockers > 0 && !rules.blockers {
+        return Err("the rules do not allow blockers".to_string());
+    }
+    if blockers > 14 {
+        return Err(format!("at most 14 blockers fit, got {blockers}"));
+    }
+    let mut board: Board = [[0; 4]; 4];
+    let mut cells: Vec<usize> = (0..16).collect();
+    for _ in 0..blockers {
+        let cell = cells.swap_remove(rng.index(cells.len()));
+        board[cell / 4][cell % 4] = BLOCKER;
+    }
+    spawn_tile(&mut board, rng, rules);
+    spawn_tile(&mut board, rng, rules);
+    Ok(board)
+}
+
+/// Return `(new_board, delta_score, victory?)` (no random tile spawn)
+pub fn single_step(board: &Board, direction: Direction, rules: &Rules) -> (Board, i32, bool) {
+    let rot = rotation(direction);
+    let mut work = rotate(*board, rot);
+
+    let mut delta = 0;
+    for c in 0..4 {
+        let (col, add) = slide_column([work[0][c], work[1][c], work[2][c], work[3][c]], rules);
+        delta += add;
+        for (row, v) in work.iter_mut().zip(col) {
+            row[c] = v;
+        }
+    }
+    let next = rotate(work, (4 - rot) % 4);
+    let victory = is_won_with(&next, rules);
+    (next, rules.scoring.move_score(delta), victory)
+}
+
+/// One tile of the board after a slide and the original cells it came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Trace {
+    /// Cell the tile ends up in
+    pub to: (usize, usize),
+    /// Cell of the leading tile (the one nearer the wall)
+    pub from: (usize, usize),
+    /// Cell of the tile that merged into the leading one, if any
+    pub merged_from: Option<(usize, usize)>,
+    pub value: i32,
+    pub score: i32,
+}
+
+/// Like [`single_step`], but also report where every resulting tile came from
+pub(crate) fn single_step_traced(
+    board: &Board,
+    direction: Direction,
+    rules: &Rules,
+) -> (Board, i32, Vec<Trace>) {
+    let rot = rotation(direction);
+    let back = (4 - rot) % 4;
+    let mut work = rotate(*board, rot);
+
+    let mut delta = 0;
+    let mut traces = Vec::new();
+    for c in 0..4 {
+        let (col, add, slots) = slide_column_traced(
+            [work[0][c], work[1][c], work[2][c], work[3][c]],
+            &rules.variant,
+            rules,
+        );
+        delta += add;
+        for (r, (row, v)) in work.iter_mut().zip(col).enumerate() {
+            row[c] = v;
+            if let Some(from) = slots[r].from {
+                traces.push(Trace {
+                    to: rotate_cell(r, c, back),
+                    from: rotate_cell(from, c, back),
+                    merged_from: slots[r].merged_from.map(|j| rotate_cell(j, c, back)),
+                    value: v,
+                    score: slots[r].score,
+                });
+            }
+        }
+    }
+    traces.sort_unstable_by_key(|t| t.to);
+    (rotate(work, back), rules.scoring.move_score(delta), traces)
+}
+
+/// Rotation that turns `direction` into a downward move
+fn rotation(direction: Direction) -> usize {
+    match direction {
+        Direction::Down => 0,  // ↓
+        Direction::Up => 2,    // ↑ rotate 180°
+        Direction::Left => 3,  // ← rotate -90°
+        Direction::Right => 1, // → rotate +90°
+    }
+}
+
+/// Rotate board 90°×k clockwise
+pub fn rotate(board: Board, rotations: usize) -> Board {
+    assert!(rotations < 4, "rotations must be 0..=3");
+    let mut rotated = [[0; 4]; 4];
+    for (src_row_idx, row) in board.iter().enumerate() {
+        for (src_col_idx, &val) in row.iter().enumerate() {
+            let (dest_row_idx, dest_col_idx) = rotate_cell(src_row_idx, src_col_idx, rotations);
+            rotated[dest_row_idx][dest_col_idx] = val;
+        }
+    }
+    rotated
+}
+
+/// Where cell `(row, col)` lands when the board is rotated 90°×k clockwise
+fn rotate_cell(row: usize, col: usize, rotations: usize) -> (usize, usize) {
+    match rotations {
+        0 => (row, col),
+        1 => (col, 3 - row),
+        2 => (3 - row, 3 - col),
+        3 => (3 - col, row),
+        _ => unreachable!("rotations must be 0..=3"),
+    }
+}
+
+/// Origin of one output cell of [`slide_column_traced`]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Slot {
+    /// Input index of the tile written here (`None` for an empty cell)
+    pub from: Option<usize>,
+    /// Input index of the tile merged into it
+    pub merged_from: Option<usize>,
+    pub score: i32,
+}
+
+/// Process one column: scan upward, merge, and drop tiles.
+/// Return `(new_column, score_delta)`
+pub fn slide_column(col: [i32; 4], rules: &Rules) -> ([i32; 4], i32) {
+    let (out, score, _) = slide_column_traced(col, &rules.variant, rules);
+    (out, score)
+}
+
+/// [`slide_column`] merging by `rule` instead of the rules' variant, which
+/// still supplies the settings `rule` reads.
+///
+/// ```
+/// use akioi_2048_core::variant::{Akioi, Classic};
+/// use akioi_2048_core::{Rules, slide_column_with};
+///
+/// let col = [0, 0, 2, -1];
+/// assert_eq!(slide_column_with(col, &Akioi, &Rules::AKIOI), ([0, 0, 0, 2], 2));
+/// assert_eq!(slide_column_with(col, &Classic, &Rules::AKIOI), (col, 0));
+/// ```
+pub fn slide_column_with<M: MergeRule + ?Sized>(
+    col: [i32; 4],
+    rule: &M,
+    rules: &Rules,
+) -> ([i32; 4], i32) {
+    let (out, score, _) = slide_column_traced(col, rule, rules);
+    (out, score)
+}
+
+/// Slide one row toward its first cell, as [`Direction::Left`] moves it.
+/// Return `(new_row, score_delta)`; reverse the row for a right move.
+///
+/// ```
+/// use akioi_2048_core::{Rules, slide_row};
+///
+/// assert_eq!(slide_row([2, 2, 4, 0], &Rules::AKIOI), ([4, 4, 0, 0], 4));
+/// assert_eq!(slide_row([-2, 8, 0, 0], &Rules::AKIOI), ([16, 0, 0, 0], 16));
+/// ```
+#[must_use]
+pub fn slide_row(row: [i32; 4], rules: &Rules) -> ([i32; 4], i32) {
+    let [a, b, c, d] = row;
+    let ([d, c, b, a], score) = slide_column([d, c, b, a], rules);
+    ([a, b, c, d], score)
+}
+
+/// [`slide_column`] that also records the origin of every output cell.
+///
+/// [`BLOCKER`]s stay put and split the column into segments that slide
+/// on their own, each toward the blocker (or wall) below it. A detonated
+/// [`BOMB`] leaves only the blockers and scores nothing.
+pub(crate) fn slide_column_traced<M: MergeRule + ?Sized>(
+    col: [i32; 4],
+    rule: &M,
+    rules: &Rules,
+) -> ([i32; 4], i32, [Slot; 4]) {
+    let mut out = [0i32; 4];
+    let mut slots = [Slot::default(); 4];
+    let mut score = 0;
+    let mut detonated = false;
+    let mut hi = 3;
+    loop {
+        let lo = (0..=hi).rev().find(|&i| col[i] == BLOCKER);
+        let start = lo.map_or(0, |b| b + 1);
+        if start <= hi {
+            match slide_segment(&col, start, hi, rule, rules, &mut out, &mut slots) {
+                Some(add) => score += add,
+                None => detonated = true,
+            }
+        }
+        let Some(b) = lo else { break };
+        out[b] = BLOCKER;
+        slots[b] = Slot {
+            from: Some(b),
+            ..Slot::default()
+        };
+        match b.checked_sub(1) {
+            Some(next) => hi = next,
+            None => break,
+        }
+    }
+    if detonated {
+        for (i, &tile) in col.iter().enumerate() {
+            if tile != BLOCKER {
+                out[i] = 0;
+                slots[i] = Slot::default();
+            }
+        }
+        score = 0;
+    }
+    (out, score, slots)
+}
+
+/// Slide `col[lo..=hi]` toward `hi`, writing into the same cells of `out`;
+/// the score, or `None` when a bomb goes off.
+///
+/// * Scan pointer `r` from `hi` down to `lo`.
+/// * Write pointer `w` from `hi` down to `lo` (always filling bottom up).
+fn slide_segment<M: MergeRule + ?Sized>(
+    col: &[i32; 4],
+    lo: usize,
+    hi: usize,
+    rule: &M,
+    rules: &Rules,
+    out: &mut [i32; 4],
+    slots: &mut [Slot; 4],
+) -> Option<i32> {
+    let above = |i: usize| (i > lo).then(|| i - 1);
+    let mut score = 0;
+    let mut w = hi; // write position (bottom to top)
+    let mut r = Some(hi); // read pointer (bottom to top)
+
+    while let Some(i) = r {
+        // skip empty cells
+        if col[i] == 0 {
+            r = above(i);
+            continue;
+        }
+
+        // find first non-zero above
+        let mut s = above(i);
+        while let Some(j) = s {
+            if col[j] != 0 {
+                break;
+            }
+            s = above(j);
+        }
+
+        // try merging i and s
+        if let Some(j) = s {
+            if rules.bombs && (col[i] == BOMB || col[j] == BOMB) {
+                return None;
+            }
+            let below_slice = &col[(i + 1)..=hi]; // slice is empty if i=hi
+            if let Some((tile, add)) = rule.merge(col[i], col[j], i == j + 1, below_slice, rules) {
+                let add = rules.scoring.merge_score(tile, add);
+                out[w] = tile;
+                slots[w] = Slot {
+                    from: Some(i),
+                    merged_from: Some(j),
+                    score: add,
+                };
+                score += add;
+                w = w.saturating_sub(1);
+                r = above(j); // skip the merged tile
+                continue;
+            }
+        }
+
+        out[w] = col[i];
+        slots[w] = Slot {
+            from: Some(i),
+            ..Slot::default()
+        };
+        w = w.saturating_sub(1);
+        r = above(i);
+    }
+    Some(score)
+}
+
+/// Spawn table of the web version: each tile with its probability in
+/// billionths, so every table keeps its odds to nine digits and its total
+/// fits a 32-bit index
+// TODO: The probabilities below do not match the documentation in
+// `rules/source.php`. Update once the documentation is corrected.
+const SPAWN_WEIGHTS: [(i32, u64); 4] = [
+    (2, 783_000_000),
+    (4, 78_000_000),
+    (-1, 111_800_000),
+    (-2, 27_200_000),
+];
+
+/// Spawn table of [`Difficulty::Easy`]
+const EASY_WEIGHTS: [(i32, u64); 4] = [
+    (2, 800_000_000),
+    (4, 100_000_000),
+    (-1, 50_000_000),
+    (-2, 50_000_000),
+];
+
+/// Spawn table of [`Difficulty::Cruel`]
+const CRUEL_WEIGHTS: [(i32, u64); 4] = [
+    (2, 770_000_000),
+    (4, 40_000_000),
+    (-1, 180_000_000),
+    (-2, 10_000_000),
+];
+
+/// `log2` of the largest tile at which [`Difficulty::Adaptive`] reaches the
+/// easy, standard and cruel tables
+const ADAPTIVE_LEVELS: [u32; 3] = [7, 10, 13];
+
+/// Probability of each tile that can spawn on `board` under `rules`: for
+/// the akioi variant `2`, `4`, `-1` for ×1 and `-2` for ×2.
+///
+/// The table comes from the rules' [`Variant`](crate::Variant). Akioi's
+/// follows the [`Difficulty`]; the adaptive one depends on the largest
+/// tile of `board`. The mercy rule never spawns ×1 into the last empty
+/// cell and scales the other tiles up to make up for it.
+///
+/// ```
+/// use akioi_2048_core::{Difficulty, Rules, spawn_probabilities};
+///
+/// let empty = [[0; 4]; 4];
+/// assert_eq!(spawn_probabilities(&Rules::AKIOI, &empty)[0], (2, 0.783));
+/// let last = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 0]];
+/// assert_eq!(spawn_probabilities(&Rules::MERCY, &last)[2], (-1, 0.0));
+///
+/// let adaptive = Rules { difficulty: Difficulty::Adaptive, ..Rules::AKIOI };
+/// let cruel = Rules { difficulty: Difficulty::Cruel, ..Rules::AKIOI };
+/// let late = [[8192, 0, 0, 0], [0; 4], [0; 4], [0; 4]];
+/// assert_eq!(spawn_probabilities(&adaptive, &late), spawn_probabilities(&cruel, &late));
+/// assert_eq!(spawn_probabilities(&Rules::CLASSIC, &empty), [(2, 0.9), (4, 0.1)]);
+/// ```
+#[must_use]
+pub fn spawn_probabilities(rules: &Rules, board: &Board) -> Vec<(i32, f64)> {
+    let weights = spawn_weights(rules, board);
+    #[allow(clippy::cast_precision_loss)]
+    let total = weights.total().max(1) as f64;
+    weights
+        .entries()
+        .iter()
+        .map(|&(tile, weight)| {
+            #[allow(clippy::cast_precision_loss)]
+            (tile, weight as f64 / total)
+        })
+        .collect()
+}
+
+/// The akioi variant's spawn table for `difficulty` on `board`
+pub(crate) fn akioi_spawn_weights(difficulty: Difficulty, board: &Board) -> [(i32, u64); 4] {
+    match difficulty {
+        Difficulty::Easy => EASY_WEIGHTS,
+        Difficulty::Standard => SPAWN_WEIGHTS,
+        Difficulty::Cruel => CRUEL_WEIGHTS,
+        Difficulty::Adaptive => adaptive_weights(board),
+    }
+}
+
+/// The adaptive table for `board`: linear in `log2` of its largest tile
+/// between the easy, standard and cruel tables, each weight rounded to the
+/// nearest integer
+fn adaptive_weights(board: &Board) -> [(i32, u64); 4] {
+    let max = board.iter().flatten().copied().max().unwrap_or(0);
+    let level = if max > 0 { max.ilog2() } else { 0 };
+    let [easy, standard, cruel] = ADAPTIVE_LEVELS;
+    let (from, to, step, steps) = if level <= standard {
+        let steps = standard - easy;
+        (
+            EASY_WEIGHTS,
+            SPAWN_WEIGHTS,
+            level.saturating_sub(easy),
+            steps,
+        )
+    } else {
+        let steps = cruel - standard;
+        (
+            SPAWN_WEIGHTS,
+            CRUEL_WEIGHTS,
+            (level - standard).min(steps),
+            steps,
+        )
+    };
+    let (step, steps) = (u64::from(step), u64::from(steps));
+    let mut weights = from;
+    for (slot, &(_, target)) in weights.iter_mut().zip(&to) {
+        // (from·(steps - step) + target·step) / steps, rounded half up
+        slot.1 = (2 * (slot.1 * (steps - step) + target * step) + steps) / (2 * steps);
+    }
+    weights
+}
+
+/// Expected face value of the next spawn on `board`: number tiles weighted
+/// by their probability, multipliers counting 0 (they add nothing until
+/// they merge). `0.0` for a full board.
+#[must_use]
+pub fn expected_spawn_value(board: &Board, rules: &Rules) -> f64 {
+    let empty = board.iter().flatten().filter(|&&t| t == 0).count();
+    if empty == 0 {
+        return 0.0;
+    }
+    spawn_probabilities(rules, board)
+        .iter()
+        .filter(|&&(tile, _)| tile > 0)
+        .map(|&(tile, chance)| f64::from(tile) * chance)
+        .sum()
+}
+
+/// Spawn `tiles` random tiles one after another, as [`spawn_tile`] does,
+/// until the board is full
+pub(crate) fn spawn_tiles<R: Rng + ?Sized>(
+    board: &mut Board,
+    rng: &mut R,
+    rules: &Rules,
+    tiles: usize,
+) -> Vec<((usize, usize), i32)> {
+    (0..tiles)
+        .map_while(|_| spawn_tile(board, rng, rules))
+        .collect()
+}
+
+/// Spawn a random tile on an empty cell (same probabilities as the web version)
+///
+/// The tile is drawn as an integer index into [`spawn_weights`], never by
+/// comparing floats, so a seed spawns the same tiles on every platform.
+///
+/// Returns the cell and value of the new tile, or `None` if the board is full.
+pub(crate) fn spawn_tile<R: Rng + ?Sized>(
+    board: &mut Board,
+    rng: &mut R,
+    rules: &Rules,
+) -> Option<((usize, usize), i32)> {
+    // ① Gather the empty cells the placement policy allows
+    let cells = rules.placement.cells(board);
+    // ② Pick a random position
+    if cells.is_empty() {
+        return None;
+    }
+    let (r, c) = cells[rng.index(cells.len())];
+
+    // ③ Generate a tile using the weighted spawn table
+    board[r][c] = pick(rng, spawn_weights(rules, board).entries());
+    Some(((r, c), board[r][c]))
+}
+
+/// A tile of `weights`, drawn as an index below their total and found in
+/// their running sums
+fn pick<R: Rng + ?Sized>(rng: &mut R, weights: &[(i32, u64)]) -> i32 {
+    let total: u64 = weights.iter().map(|&(_, weight)| weight).sum();
+    let total = usize::try_from(total).expect("spawn weights fit an index");
+    let mut draw = rng.index(total.max(1)) as u64;
+    for &(tile, weight) in weights {
+        if draw < weight {
+            return tile;
+        }
+        draw -= weight;
+    }
+    weights.last().map_or(2, |&(tile, _)| tile)
+}
+
+/// The spawn table [`spawn_tile`] draws from on `board`: the weights of
+/// the rules' [`Variant`](crate::Variant), with the mercy rule's ×1 at 0
+/// when one cell is left
+pub(crate) fn spawn_weights(rules: &Rules, board: &Board) -> SpawnTable {
+    let mut weights = rules.variant.spawn_weights(board, rules);
+    if rules.mercy && board.iter().flatten().filter(|&&t| t == 0).count() == 1 {
+        for (tile, weight) in weights.entries_mut() {
+            if *tile == -1 {
+                *weight = 0;
+            }
+        }
+    }
+    weights
+}
@@ -0,0 +1,417 @@
+//! Compact binary game logs: a header with the rules and seed, then one
+//! varint-encoded event per accepted move.
+//!
+//! A log starts with the magic `A2048LOG`, a version byte, the rules tag
+//! and its [`Stamp`] (each length-prefixed; version 1 logs have no stamp),
+//! the seed and the constructed start if there was one,
+//! followed by the first four bytes of the SHA-256 of the header. Each
+//! event is a single varint holding the direction and the tile it spawned,
+//! `1 + (direction | row << 2 | col << 4 | zigzag(value) << 6)`, usually one
+//! or two bytes. Every [`CHECKPOINT`] events, and when a log is finished, a
+//! `0` byte and the first four bytes of the SHA-256 of everything before it
+//! follow.
+//!
+//! Loading checks every checksum and plays the moves back, so a log whose
+//! spawns do not match its seed is rejected too; that also covers events
+//! after the last checkpoint of a log still being written. A log stamped by
+//! an engine that plays its rules differently is refused unless forced.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::compat::Stamp;
+use crate::detail::Spawn;
+use crate::replay::{Replay, Start};
+use crate::{Direction, Game, Rules};
+
+const MAGIC: &[u8; 8] = b"A2048LOG";
+const VERSION: u8 = 2;
+
+/// Events between two checksums
+pub const CHECKPOINT: usize = 4096;
+
+/// Writes a binary log event by event, each with a single `write_all`, so
+/// an unbuffered file is complete up to the last move.
+///
+/// ```
+/// use akioi_2048_core::gamelog::{GameLog, read};
+/// use akioi_2048_core::{Direction, Game};
+///
+/// let mut game = Game::new(7);
+/// let mut log = GameLog::create(Vec::new(), &game.replay()).unwrap();
+/// for dir in [Direction::Left, Direction::Up, Direction::Right] {
+///     let result = game.step(dir);
+///     if result.moved {
+///         log.push(dir, result.spawn).unwrap();
+///     }
+/// }
+/// let bytes = log.finish().unwrap();
+/// assert_eq!(read(&bytes), Ok(game.replay()));
+/// ```
+pub struct GameLog<W> {
+    writer: W,
+    /// Everything written so far
+    hasher: Sha256,
+    events: usize,
+}
+
+impl<W: Write> GameLog<W> {
+    /// Start a log of the game `replay` was recorded from, with its moves
+    /// so far.
+    ///
+    /// # Errors
+    /// Returns an error if the replay does not play back or writing fails.
+    pub fn create(writer: W, replay: &Replay) -> io::Result<Self> {
+        let mut log = Self {
+            writer,
+            hasher: Sha256::new(),
+            events: 0,
+        };
+        let mut header = MAGIC.to_vec();
+        header.push(VERSION);
+        let tag = replay.rules.tag();
+        put_varint(&mut header, tag.len() as u64);
+        header.extend_from_slice(tag.as_bytes());
+        let stamp = Stamp::of(&replay.rules).to_text();
+        put_varint(&mut header, stamp.len() as u64);
+        header.extend_from_slice(stamp.as_bytes());
+        put_varint(&mut header, replay.seed);
+        match replay.start {
+            Some(start) => {
+                header.push(1);
+                put_varint(&mut header, zigzag(start.score));
+                for &tile in start.board.iter().flatten() {
+                    put_varint(&mut header, zigzag(i64::from(tile)));
+                }
+            }
+            None => header.push(0),
+        }
+        header.extend_from_slice(&checksum(Sha256::new_with_prefix(&header)));
+        log.write(&header)?;
+        log.catch_up(replay, 0)?;
+        Ok(log)
+    }
+
+    /// Log an accepted move and the tile it spawned
+    ///
+    /// # Errors
+    /// Returns an error if writing fails.
+    pub fn push(&mut self, direction: Direction, spawn: Option<Spawn>) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(8);
+        put_varint(&mut bytes, encode_event(direction, spawn));
+        self.events += 1;
+        if self.events.is_multiple_of(CHECKPOINT) {
+            self.checkpoint(bytes)
+        } else {
+            self.write(&bytes)
+        }
+    }
+
+    /// Write a final checksum and return the writer
+    ///
+    /// # Errors
+    /// Returns an error if writing fails.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.events == 0 || !self.events.is_multiple_of(CHECKPOINT) {
+            self.checkpoint(Vec::new())?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Write `bytes` followed by a checksum of everything up to it
+    fn checkpoint(&mut self, mut bytes: Vec<u8>) -> io::Result<()> {
+        bytes.push(0);
+        self.hasher.update(&bytes);
+        let sum = checksum(self.hasher.clone());
+        self.hasher.update(sum);
+        bytes.extend_from_slice(&sum);
+        self.writer.write_all(&bytes)
+    }
+
+    /// Log the moves of `replay` from move `from` on
+    fn catch_up(&mut self, replay: &Replay, from: usize) -> io::Result<()> {
+        let mut game = replay_game(replay).map_err(invalid_data)?;
+        for (i, &dir) in replay.moves.iter().enumerate() {
+            let result = game.step(dir);
+            if !result.moved {
+                return Err(invalid_data(format!(
+                    "move {i} ({dir:?}) does not change the board"
+                )));
+            }
+            if i >= from {
+                self.push(dir, result.spawn)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.hasher.update(bytes);
+        self.writer.write_all(bytes)
+    }
+}
+
+impl GameLog<File> {
+    /// Continue the log at `path` with the moves of `replay` it is missing,
+    /// or start it if there is no such file.
+    ///
+    /// # Errors
+    /// Returns an error if the file is not a valid log, or is the log of
+    /// another game than `replay`.
+    pub fn append(path: &Path, replay: &Replay) -> io::Result<Self> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Self::create(File::create(path)?, replay);
+            }
+            Err(e) => return Err(e),
+        };
+        let logged = read(&bytes).map_err(invalid_data)?;
+        if logged.rules != replay.rules
+            || logged.seed != replay.seed
+            || logged.start != replay.start
+            || !replay.moves.starts_with(&logged.moves)
+        {
+            return Err(invalid_data("the log is of another game"));
+        }
+        let mut log = Self {
+            writer: OpenOptions::new().append(true).open(path)?,
+            hasher: Sha256::new_with_prefix(&bytes),
+            events: logged.moves.len(),
+        };
+        log.catch_up(replay, logged.moves.len())?;
+        Ok(log)
+    }
+}
+
+/// Save `replay` as a binary log at `path`
+///
+/// # Errors
+/// Returns an error if the replay does not play back or the file cannot be
+/// written.
+pub fn save(replay: &Replay, path: &Path) -> io::Result<()> {
+    let file = io::BufWriter::new(File::create(path)?);
+    GameLog::create(file, replay)?.finish()?;
+    Ok(())
+}
+
+/// Load the binary log at `path`
+///
+/// # Errors
+/// Returns an error if the file cannot be read or fails the checks of
+/// [`read`].
+pub fn load(path: &Path) -> io::Result<Replay> {
+    read(&fs::read(path)?).map_err(invalid_data)
+}
+
+/// [`load`] with [`read_with`]
+///
+/// # Errors
+/// Returns an error if the file cannot be read or fails the checks of
+/// [`read_with`].
+pub fn load_with(path: &Path, force: bool) -> io::Result<(Replay, Vec<String>)> {
+    read_with(&fs::read(path)?, force).map_err(invalid_data)
+}
+
+/// Decode a binary log, checking its checksums and that every spawn is the
+/// one its seed makes
+///
+/// # Errors
+/// Returns an error if `bytes` are not a log of this format, are truncated
+/// or corrupt, or were stamped by an engine that plays their rules
+/// differently.
+pub fn read(bytes: &[u8]) -> Result<Replay, String> {
+    read_with(bytes, false).map(|(replay, _)| replay)
+}
+
+/// [`read`] that with `force` also reads a log whose [`Stamp`] no longer
+/// matches, returning the differences with it
+///
+/// # Errors
+/// Returns an error if `bytes` are not a log of this format, are truncated
+/// or corrupt, or have differences and `force` is not set.
+pub fn read_with(bytes: &[u8], force: bool) -> Result<(Replay, Vec<String>), String> {
+    let mut reader = Reader { bytes, at: 0 };
+    if bytes.get(..MAGIC.len()) != Some(MAGIC) {
+        return Err("not an akioi-2048 binary log".to_string());
+    }
+    reader.at = MAGIC.len();
+    let version = reader.byte()?;
+    if version != 1 && version != VERSION {
+        return Err(format!("unsupported binary log version: {version}"));
+    }
+    let tag = reader.text("invalid rules tag")?;
+    let rules = Rules::from_tag(tag)?;
+    let stamp = if version == VERSION {
+        Some(Stamp::parse(reader.text("invalid stamp")?)?)
+    } else {
+        None
+    };
+    let seed = reader.varint()?;
+    let start = match reader.byte()? {
+        0 => None,
+        1 => {
+            let score = unzigzag(reader.varint()?);
+            let mut board = [[0; 4]; 4];
+            for cell in board.iter_mut().flatten() {
+                *cell =
+                    i32::try_from(unzigzag(reader.varint()?)).map_err(|_| "invalid start tile")?;
+            }
+            Some(Start { board, score })
+        }
+        _ => return Err("invalid start flag".to_string()),
+    };
+    reader.check(bytes)?;
+    let differences = match stamp {
+        Some(stamp) => stamp.check("binary log", &rules, force)?,
+        None => Vec::new(),
+    };
+    let mut events = Vec::new();
+    let mut since_checkpoint = 0;
+    while reader.at < bytes.len() {
+        match reader.varint()? {
+            0 => {
+                reader.check(bytes)?;
+                since_checkpoint = 0;
+            }
+            code => {
+                if since_checkpoint == CHECKPOINT {
+                    return Err(format!("missing checksum after event {}", events.len()));
+                }
+                events.push(decode_event(code - 1)?);
+                since_checkpoint += 1;
+            }
+        }
+    }
+    let replay = Replay {
+        rules,
+        seed,
+        start,
+        moves: events.iter().map(|&(dir, _)| dir).collect(),
+    };
+    let mut game = replay_game(&replay)?;
+    for (i, &(dir, spawn)) in events.iter().enumerate() {
+        let result = game.step(dir);
+        if !result.moved {
+            return Err(format!("move {i} ({dir:?}) does not change the board"));
+        }
+        if result.spawn != spawn {
+            return Err(format!("spawn of move {i} does not match the seed"));
+        }
+    }
+    Ok((replay, differences))
+}
+
+fn replay_game(replay: &Replay) -> Result<Game, String> {
+    let mut builder = Game::builder().seed(replay.seed).rules(replay.rules);
+    if let Some(start) = replay.start {
+        builder = builder.start(start.board, start.score);
+    }
+    builder.build()
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let taken = self
+            .bytes
+            .get(self.at..self.at.saturating_add(n))
+            .ok_or("binary log is truncated")?;
+        self.at += n;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("invalid varint in binary log".to_string())
+    }
+
+    /// Read length-prefixed UTF-8 text, failing with `invalid` otherwise
+    fn text(&mut self, invalid: &str) -> Result<&'a str, String> {
+        let len = usize::try_from(self.varint()?).map_err(|_| invalid.to_string())?;
+        std::str::from_utf8(self.take(len)?).map_err(|_| invalid.to_string())
+    }
+
+    /// Read a checksum and compare it with all of `bytes` before it
+    fn check(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let end = self.at;
+        let stored = self.take(4)?;
+        if stored != checksum(Sha256::new_with_prefix(&bytes[..end])) {
+            return Err(format!("checksum mismatch at byte {end}"));
+        }
+        Ok(())
+    }
+}
+
+fn checksum(hasher: Sha256) -> [u8; 4] {
+    let digest = hasher.finalize();
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+fn encode_event(direction: Direction, spawn: Option<Spawn>) -> u64 {
+    let dir = match direction {
+        Direction::Down => 0,
+        Direction::Right => 1,
+        Direction::Up => 2,
+        Direction::Left => 3,
+    };
+    let spawn = spawn.map_or(0, |s| {
+        (s.cell.0 as u64) << 2 | (s.cell.1 as u64) << 4 | zigzag(i64::from(s.value)) << 6
+    });
+    1 + (dir | spawn)
+}
+
+fn decode_event(code: u64) -> Result<(Direction, Option<Spawn>), String> {
+    let direction = match code & 3 {
+        0 => Direction::Down,
+        1 => Direction::Right,
+        2 => Direction::Up,
+        _ => Direction::Left,
+    };
+    let value = i32::try_from(unzigzag(code >> 6)).map_err(|_| "invalid spawn in binary log")?;
+    let cell = ((code >> 2 & 3) as usize, (code >> 4 & 3) as usize);
+    let spawn = (value != 0).then_some(Spawn { cell, value });
+    if spawn.is_none() && cell != (0, 0) {
+        return Err("invalid spawn in binary log".to_string());
+    }
+    Ok((direction, spawn))
+}
+
+fn invalid_data(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
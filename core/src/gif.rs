@@ -0,0 +1,154 @@
+//! Animated GIF export of replays.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::image::{Raster, Rgb, Theme, rasterize};
+use crate::replay::Replay;
+
+/// Render every frame of `replay` and save them as a looping GIF.
+///
+/// # Errors
+/// Returns an error if `fps` is not in `1..=100`, the replay does not play
+/// back or the file cannot be written.
+pub fn export_gif(replay: &Replay, path: &Path, fps: u32, theme: Theme) -> io::Result<()> {
+    if !(1..=100).contains(&fps) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("fps must be between 1 and 100, got: {fps}"),
+        ));
+    }
+    let boards = replay
+        .frames()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let frames: Vec<Raster> = boards.iter().map(|b| rasterize(b, theme)).collect();
+    // GIF delays are in hundredths of a second
+    let delay = (100 / fps) as u16;
+    fs::write(path, encode(&frames, delay))
+}
+
+/// Encode same-sized frames with at most 256 distinct colours overall
+#[must_use]
+pub fn encode(frames: &[Raster], delay: u16) -> Vec<u8> {
+    let (width, height) = frames.first().map_or((0, 0), |f| (f.width, f.height));
+    let mut palette: Vec<Rgb> = Vec::new();
+    let mut index: HashMap<Rgb, u8> = HashMap::new();
+    let mut indexed = |frame: &Raster| -> Vec<u8> {
+        frame
+            .pixels
+            .iter()
+            .map(|&c| {
+                *index.entry(c).or_insert_with(|| {
+                    palette.push(c);
+                    u8::try_from(palette.len() - 1).expect("board images use few colours")
+                })
+            })
+            .collect()
+    };
+    let frames: Vec<Vec<u8>> = frames.iter().map(&mut indexed).collect();
+
+    let mut out = b"GIF89a".to_vec();
+    out.extend_from_slice(&(width as u16).to_le_bytes());
+    out.extend_from_slice(&(height as u16).to_le_bytes());
+    out.extend_from_slice(&[0xf7, 0, 0]); // 256-entry global colour table
+    for i in 0..256 {
+        out.extend_from_slice(&palette.get(i).copied().unwrap_or_default());
+    }
+    // NETSCAPE2.0: loop forever
+    out.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for pixels in &frames {
+        out.extend_from_slice(&[0x21, 0xf9, 0x04, 0x00]);
+        out.extend_from_slice(&delay.to_le_bytes());
+        out.extend_from_slice(&[0, 0]);
+
+        out.push(0x2c);
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(&(width as u16).to_le_bytes());
+        out.extend_from_slice(&(height as u16).to_le_bytes());
+        out.push(0);
+
+        out.push(8); // LZW minimum code size
+        for block in lzw(pixels).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend_from_slice(block);
+        }
+        out.push(0);
+    }
+    out.push(0x3b);
+    out
+}
+
+const CLEAR: u16 = 256;
+const END: u16 = 257;
+const MAX_CODE: u16 = 4096;
+
+/// GIF-flavoured LZW with 8-bit symbols, LSB-first bit packing
+fn lzw(pixels: &[u8]) -> Vec<u8> {
+    struct Bits {
+        out: Vec<u8>,
+        acc: u32,
+        len: u32,
+        width: u32,
+    }
+    impl Bits {
+        fn put(&mut self, code: u16) {
+            self.acc |= u32::from(code) << self.len;
+            self.len += self.width;
+            while self.len >= 8 {
+                self.out.push(self.acc as u8);
+                self.acc >>= 8;
+                self.len -= 8;
+            }
+        }
+
+        /// The decoder widens codes once its table reaches the next power of
+        /// two, one code after the encoder added that entry
+        fn widen(&mut self, next: u16) {
+            if next == 1 << self.width && self.width < 12 {
+                self.width += 1;
+            }
+        }
+    }
+
+    let mut bits = Bits {
+        out: Vec::new(),
+        acc: 0,
+        len: 0,
+        width: 9,
+    };
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = END + 1;
+    bits.put(CLEAR);
+
+    if let Some((&first, rest)) = pixels.split_first() {
+        let mut prefix = u16::from(first);
+        for &k in rest {
+            if let Some(&code) = table.get(&(prefix, k)) {
+                prefix = code;
+                continue;
+            }
+            bits.put(prefix);
+            bits.widen(next);
+            if next < MAX_CODE {
+                table.insert((prefix, k), next);
+                next += 1;
+            } else {
+                bits.put(CLEAR);
+                table.clear();
+                next = END + 1;
+                bits.width = 9;
+            }
+            prefix = u16::from(k);
+        }
+        bits.put(prefix);
+        bits.widen(next);
+    }
+    bits.put(END);
+    if bits.len > 0 {
+        bits.out.push(bits.acc as u8);
+    }
+    bits.out
+}
@@ -0,0 +1,174 @@
+//! Move hints for front-ends: the best move by expectimax, with the numbers
+//! behind it.
+
+use crate::book::OpeningBook;
+use crate::search::{Features, SearchOptions, Weights, search};
+use crate::{Board, Direction, Engine, Rules};
+
+/// A recommended move and why
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hint {
+    pub direction: Direction,
+    /// Expected evaluation of every legal move, best first
+    pub values: Vec<(Direction, f64)>,
+    /// Features of the board after the recommended move, before its spawn
+    pub features: Features,
+    /// Score the recommended move merges
+    pub score: i32,
+    /// One sentence for the player, e.g. "Left scores 8, leaves 9 empty
+    /// cells and keeps the largest tile in a corner."
+    pub summary: String,
+    /// Depth the search completed, short of the one asked for when a time
+    /// budget ran out
+    pub depth: u32,
+    /// Whether the move came from an opening book instead of a search; the
+    /// values then hold the book's move alone
+    pub from_book: bool,
+}
+
+/// The best move from `board` after searching `depth` moves ahead, or
+/// `None` when no move is left.
+///
+/// ```
+/// use akioi_2048_core::hint::hint;
+/// use akioi_2048_core::{Direction, Rules};
+///
+/// let board = [[0; 4], [0; 4], [0; 4], [2, 2, 4, 8]];
+/// let hint = hint(&board, &Rules::AKIOI, 1).unwrap().unwrap();
+/// assert!(matches!(hint.direction, Direction::Left | Direction::Right));
+/// assert_eq!(hint.score, 4);
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules or an invalid board.
+pub fn hint(board: &Board, rules: &Rules, depth: u32) -> Result<Option<Hint>, String> {
+    let options = SearchOptions {
+        depth,
+        ..SearchOptions::default()
+    };
+    hint_with(board, rules, &options)
+}
+
+/// [`hint`] with every [`search`] setting, such as a time budget
+///
+/// # Errors
+/// Returns an error for inconsistent rules, an invalid board or invalid
+/// options.
+pub fn hint_with(
+    board: &Board,
+    rules: &Rules,
+    options: &SearchOptions,
+) -> Result<Option<Hint>, String> {
+    hint_with_book(board, rules, options, None)
+}
+
+/// [`hint_with`], looking `board` up in `book` first and searching only
+/// for positions it does not hold
+///
+/// ```
+/// use akioi_2048_core::book::OpeningBook;
+/// use akioi_2048_core::hint::hint_with_book;
+/// use akioi_2048_core::progress::Progress;
+/// use akioi_2048_core::search::SearchOptions;
+/// use akioi_2048_core::Rules;
+///
+/// let book = OpeningBook::build(Rules::CLASSIC, 4, 1, 100_000, &Progress::new()).unwrap();
+/// let start = [[0; 4], [0; 4], [0, 0, 2, 0], [0, 2, 0, 0]];
+/// let options = SearchOptions { depth: 1, ..SearchOptions::default() };
+/// let hint = hint_with_book(&start, &Rules::CLASSIC, &options, Some(&book)).unwrap().unwrap();
+/// assert!(hint.from_book);
+/// assert_eq!(Some(hint.direction), book.lookup(&start).map(|m| m.direction));
+/// ```
+///
+/// # Errors
+/// As [`hint_with`], and for a book built under other rules.
+pub fn hint_with_book(
+    board: &Board,
+    rules: &Rules,
+    options: &SearchOptions,
+    book: Option<&OpeningBook>,
+) -> Result<Option<Hint>, String> {
+    if let Some(book) = book {
+        if book.rules() != *rules {
+            return Err(format!(
+                "the opening book was built for the {} rules",
+                book.rules().tag()
+            ));
+        }
+        crate::validate_board_with(board, rules)?;
+        if let Some(m) = book.lookup(board) {
+            let values = vec![(m.direction, m.value)];
+            return explain(board, rules, values, book.depth(), true).map(Some);
+        }
+    }
+    let result = search(board, rules, &Weights::default(), options)?;
+    let mut values = result.values;
+    values.sort_by(|a, b| b.1.total_cmp(&a.1));
+    if values.is_empty() {
+        return Ok(None);
+    }
+    explain(board, rules, values, result.depth, false).map(Some)
+}
+
+/// The hint for the first of `values`, sorted best first and non-empty
+fn explain(
+    board: &Board,
+    rules: &Rules,
+    values: Vec<(Direction, f64)>,
+    depth: u32,
+    from_book: bool,
+) -> Result<Hint, String> {
+    let (direction, value) = values[0];
+    let (after, score) = Engine::new(*rules)?.slide(board, direction);
+    let features = Features::of(&after);
+    let lead = if from_book {
+        ", from the opening book".to_string()
+    } else {
+        lead(value, &values)
+    };
+    let summary = summarize(direction, value, &lead, &features, score);
+    Ok(Hint {
+        direction,
+        values,
+        features,
+        score,
+        summary,
+        depth,
+        from_book,
+    })
+}
+
+/// How far the best move, worth `value`, leads the next of `values`
+fn lead(value: f64, values: &[(Direction, f64)]) -> String {
+    match values.get(1) {
+        Some(&(next, v)) if value - v < 0.05 => format!(", tied with {next:?}"),
+        Some(&(next, v)) => format!(", {:.1} ahead of {next:?}", value - v),
+        None => ", the only move".to_string(),
+    }
+}
+
+fn summarize(
+    direction: Direction,
+    value: f64,
+    lead: &str,
+    features: &Features,
+    score: i32,
+) -> String {
+    let mut reasons = Vec::new();
+    if score > 0 {
+        reasons.push(format!("scores {score}"));
+    }
+    reasons.push(match features.empty_cells {
+        1.0 => "leaves 1 empty cell".to_string(),
+        n => format!("leaves {n} empty cells"),
+    });
+    if features.corner > 0.0 {
+        reasons.push("keeps the largest tile in a corner".to_string());
+    }
+    let reasons = match reasons.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+        None => unreachable!("always names the empty cells"),
+    };
+    format!("{direction:?} {reasons} (expected value {value:.1}{lead}).")
+}
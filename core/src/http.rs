@@ -0,0 +1,251 @@
+//! HTTP front of the [`server`](crate::server) on axum, for web backends
+//! and bot arenas; built only with the `http` feature, so the Python wheel
+//! carries none of it.
+//!
+//! Every endpoint takes a JSON object as the `POST` body and answers with
+//! the result of the server method of the same name; `/init` starts a game
+//! like `new_game`:
+//!
+//! | endpoint | method |
+//! |---|---|
+//! | `/init` | `new_game` |
+//! | `/step` | `step` |
+//! | `/suggest` | `suggest` |
+//! | `/simulate` | `simulate` |
+//! | `/state`, `/replay`, `/close` | the same |
+//!
+//! Games are shared by all connections; searches (`suggest`, `simulate`)
+//! run without holding them, so a deep search holds up no other request.
+//! Errors are `{"error": message}`, with status 400 for a bad body, 404
+//! for an unknown path, 405 for anything but `POST` to a known one and 413
+//! for a body over [`MAX_BODY`] bytes. Bodies nest at most 128 arrays and
+//! objects. At most [`MAX_CONNECTIONS`] connections are served at once,
+//! and one silent for [`READ_TIMEOUT`] is closed.
+
+use std::io;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::rejection::BytesRejection;
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::{Method, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::any;
+use axum::serve::Listener;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{Instant, Sleep};
+
+use crate::json::Json;
+use crate::server::{METHOD_NOT_FOUND, METHODS, Server, simulate, suggest};
+
+/// Largest request body accepted, in bytes
+pub const MAX_BODY: usize = 1 << 20;
+
+/// Most connections served at once; further ones wait in the backlog
+pub const MAX_CONNECTIONS: usize = 256;
+
+/// How long a connection may send nothing before it is closed
+pub const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The games every connection shares
+type Games = Arc<Mutex<Server>>;
+
+/// Serve HTTP on `addr`
+///
+/// # Errors
+/// Returns an error if `addr` cannot be bound.
+pub fn serve_http(addr: impl ToSocketAddrs) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    eprintln!("listening on http://{}", listener.local_addr()?);
+    serve_http_on(listener)
+}
+
+/// Serve HTTP on the bound `listener`
+///
+/// # Errors
+/// Returns an error if the async runtime cannot be started.
+pub fn serve_http_on(listener: TcpListener) -> io::Result<()> {
+    listener.set_nonblocking(true)?;
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(async {
+        let listener = Limited {
+            listener: tokio::net::TcpListener::from_std(listener)?,
+            slots: Arc::new(Semaphore::new(MAX_CONNECTIONS)),
+        };
+        axum::serve(listener, router()).await
+    })
+}
+
+fn router() -> Router {
+    Router::new()
+        .route("/{endpoint}", any(endpoint))
+        .fallback(|uri: axum::http::Uri| async move {
+            refusal(StatusCode::NOT_FOUND, &format!("no endpoint {uri}"))
+        })
+        .layer(DefaultBodyLimit::max(MAX_BODY))
+        .with_state(Arc::new(Mutex::new(Server::new())))
+}
+
+async fn endpoint(
+    State(games): State<Games>,
+    method: Method,
+    Path(path): Path<String>,
+    body: Result<Bytes, BytesRejection>,
+) -> Response {
+    let name = match path.as_str() {
+        "init" => "new_game",
+        name => name,
+    };
+    if !METHODS.contains(&name) {
+        return refusal(StatusCode::NOT_FOUND, &format!("no endpoint /{path}"));
+    }
+    if method != Method::POST {
+        return refusal(StatusCode::METHOD_NOT_ALLOWED, "only POST is supported");
+    }
+    let body = match body {
+        Ok(body) => body,
+        Err(rejection) => return refusal(rejection.status(), &rejection.body_text()),
+    };
+    let Ok(body) = std::str::from_utf8(&body) else {
+        return refusal(StatusCode::BAD_REQUEST, "the body is not UTF-8");
+    };
+    let params = if body.trim().is_empty() {
+        Json::Object(Vec::new())
+    } else {
+        match Json::parse(body, "request") {
+            Ok(params @ Json::Object(_)) => params,
+            Ok(_) => return refusal(StatusCode::BAD_REQUEST, "the body must be a JSON object"),
+            Err(e) => return refusal(StatusCode::BAD_REQUEST, &e),
+        }
+    };
+    let name = name.to_string();
+    // searches take long, so every call runs off the async workers
+    let result = tokio::task::spawn_blocking(move || call(&games, &name, &params))
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+    match result {
+        Ok(result) => json(StatusCode::OK, &result),
+        Err((METHOD_NOT_FOUND, _)) => {
+            refusal(StatusCode::NOT_FOUND, &format!("no endpoint /{path}"))
+        }
+        Err((_, message)) => refusal(StatusCode::BAD_REQUEST, &message),
+    }
+}
+
+/// Run the server method `name`, holding the games only as long as it
+/// reads or changes them
+fn call(games: &Mutex<Server>, name: &str, params: &Json) -> Result<Json, (i32, String)> {
+    match name {
+        "simulate" => simulate(params).map_err(|e| (0, e)),
+        "suggest" => {
+            let position = lock(games).suggest_position(params);
+            position
+                .and_then(|(board, rules)| suggest(&board, &rules, params))
+                .map_err(|e| (0, e))
+        }
+        name => lock(games).dispatch(name, params),
+    }
+}
+
+/// Lock `games`, which stay consistent even if a holder panicked
+fn lock(games: &Mutex<Server>) -> MutexGuard<'_, Server> {
+    games.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn json(status: StatusCode, body: &Json) -> Response {
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/json")],
+        body.to_string(),
+    )
+        .into_response()
+}
+
+fn refusal(status: StatusCode, message: &str) -> Response {
+    json(status, &Json::object([("error", message.into())]))
+}
+
+/// A listener taking a connection only while fewer than
+/// [`MAX_CONNECTIONS`] are open
+struct Limited {
+    listener: tokio::net::TcpListener,
+    slots: Arc<Semaphore>,
+}
+
+impl Listener for Limited {
+    type Io = Connection;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Connection, SocketAddr) {
+        let slot = Arc::clone(&self.slots)
+            .acquire_owned()
+            .await
+            .expect("the connection slots are never closed");
+        let (stream, addr) = Listener::accept(&mut self.listener).await;
+        let connection = Connection {
+            stream,
+            idle: Box::pin(tokio::time::sleep(READ_TIMEOUT)),
+            _slot: slot,
+        };
+        (connection, addr)
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+/// A connection that fails its read once the peer has sent nothing for
+/// [`READ_TIMEOUT`], and frees its slot when dropped
+struct Connection {
+    stream: TcpStream,
+    idle: Pin<Box<Sleep>>,
+    _slot: OwnedSemaphorePermit,
+}
+
+impl AsyncRead for Connection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        match Pin::new(&mut this.stream).poll_read(cx, buf) {
+            Poll::Ready(result) => {
+                this.idle.as_mut().reset(Instant::now() + READ_TIMEOUT);
+                Poll::Ready(result)
+            }
+            Poll::Pending => match this.idle.as_mut().poll(cx) {
+                Poll::Ready(()) => Poll::Ready(Err(io::ErrorKind::TimedOut.into())),
+                Poll::Pending => Poll::Pending,
+            },
+        }
+    }
+}
+
+impl AsyncWrite for Connection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
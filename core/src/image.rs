@@ -0,0 +1,311 @@
+//! Board pictures: SVG documents and RGB rasters encoded as PNG.
+//!
+//! Tile colours follow the web version; multipliers are purple so they read
+//! differently from numbers at a glance; blockers are plain dark tiles and
+//! bombs plain red ones.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::npz::crc32;
+use crate::{BLOCKER, BOMB, Board};
+
+/// Side of one tile in pixels
+pub const TILE: usize = 100;
+/// Space between tiles and around the board
+pub const GAP: usize = 12;
+/// Width and height of a rendered board
+pub const SIZE: usize = 4 * TILE + 5 * GAP;
+const RADIUS: usize = 6;
+
+pub type Rgb = [u8; 3];
+
+/// Colour scheme of the board frame and empty cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// Look up a theme by its name (`"light"` or `"dark"`)
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Theme> {
+        match name {
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            _ => None,
+        }
+    }
+
+    fn frame(self) -> Rgb {
+        match self {
+            Theme::Light => [0xbb, 0xad, 0xa0],
+            Theme::Dark => [0x2b, 0x2a, 0x33],
+        }
+    }
+
+    fn empty(self) -> Rgb {
+        match self {
+            Theme::Light => [0xcd, 0xc1, 0xb4],
+            Theme::Dark => [0x3d, 0x3b, 0x47],
+        }
+    }
+}
+
+/// Fill and text colour of a non-empty tile
+fn tile_colors(tile: i32) -> (Rgb, Rgb) {
+    const DARK_TEXT: Rgb = [0x77, 0x6e, 0x65];
+    const LIGHT_TEXT: Rgb = [0xf9, 0xf6, 0xf2];
+    const NUMBERS: [Rgb; 11] = [
+        [0xee, 0xe4, 0xda],
+        [0xed, 0xe0, 0xc8],
+        [0xf2, 0xb1, 0x79],
+        [0xf5, 0x95, 0x63],
+        [0xf6, 0x7c, 0x5f],
+        [0xf6, 0x5e, 0x3b],
+        [0xed, 0xcf, 0x72],
+        [0xed, 0xcc, 0x61],
+        [0xed, 0xc8, 0x50],
+        [0xed, 0xc5, 0x3f],
+        [0xed, 0xc2, 0x2e],
+    ];
+    match tile {
+        BLOCKER => ([0x5c, 0x55, 0x4d], LIGHT_TEXT),
+        BOMB => ([0xd6, 0x3a, 0x2f], LIGHT_TEXT),
+        -1 => ([0xc9, 0xb6, 0xe4], [0x4b, 0x3a, 0x66]),
+        -2 => ([0xa5, 0x8b, 0xd6], LIGHT_TEXT),
+        -4 => ([0x87, 0x63, 0xc7], LIGHT_TEXT),
+        t if t < 0 => ([0x6a, 0x45, 0xb3], LIGHT_TEXT),
+        2 | 4 => (NUMBERS[(tile.ilog2() - 1) as usize], DARK_TEXT),
+        t => {
+            let fill = NUMBERS
+                .get((t.ilog2() as usize).saturating_sub(1))
+                .copied()
+                .unwrap_or([0x3c, 0x3a, 0x32]);
+            (fill, LIGHT_TEXT)
+        }
+    }
+}
+
+fn label(tile: i32) -> String {
+    if tile == BLOCKER || tile == BOMB {
+        String::new()
+    } else if tile < 0 {
+        format!("×{}", -tile)
+    } else {
+        tile.to_string()
+    }
+}
+
+fn hex(c: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", c[0], c[1], c[2])
+}
+
+/// Top-left pixel of the tile at `(row, col)`
+fn origin(row: usize, col: usize) -> (usize, usize) {
+    (GAP + col * (TILE + GAP), GAP + row * (TILE + GAP))
+}
+
+/// The board as a standalone SVG document of [`SIZE`]×[`SIZE`] pixels
+#[must_use]
+pub fn svg(board: &Board, theme: Theme) -> String {
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SIZE}\" height=\"{SIZE}\" \
+         viewBox=\"0 0 {SIZE} {SIZE}\">\n\
+         <rect width=\"{SIZE}\" height=\"{SIZE}\" rx=\"{RADIUS}\" fill=\"{}\"/>\n",
+        hex(theme.frame())
+    );
+    for (r, row) in board.iter().enumerate() {
+        for (c, &tile) in row.iter().enumerate() {
+            let (x, y) = origin(r, c);
+            let (fill, text) = if tile == 0 {
+                (theme.empty(), theme.empty())
+            } else {
+                tile_colors(tile)
+            };
+            out.push_str(&format!(
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{TILE}\" height=\"{TILE}\" rx=\"{RADIUS}\" \
+                 fill=\"{}\"/>\n",
+                hex(fill)
+            ));
+            if tile != 0 {
+                let label = label(tile);
+                let size = match label.chars().count() {
+                    0..=2 => 48,
+                    3 => 40,
+                    4 => 32,
+                    _ => 26,
+                };
+                out.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" font-family=\"Clear Sans, Helvetica Neue, Arial, \
+                     sans-serif\" font-size=\"{size}\" font-weight=\"bold\" \
+                     text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{label}\
+                     </text>\n",
+                    x + TILE / 2,
+                    y + TILE / 2,
+                    hex(text)
+                ));
+            }
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Row-major RGB pixels
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Raster {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Raster {
+    fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+        }
+    }
+
+    /// Fill a `w`×`h` rectangle with corners rounded by [`RADIUS`]
+    fn rounded_rect(&mut self, x: usize, y: usize, w: usize, h: usize, color: Rgb) {
+        for dy in 0..h {
+            for dx in 0..w {
+                let cx = RADIUS.saturating_sub(dx.min(w - 1 - dx));
+                let cy = RADIUS.saturating_sub(dy.min(h - 1 - dy));
+                if cx * cx + cy * cy <= RADIUS * RADIUS {
+                    self.pixels[(y + dy) * self.width + x + dx] = color;
+                }
+            }
+        }
+    }
+
+    /// Encode as an 8-bit RGB PNG (stored, uncompressed deflate)
+    #[must_use]
+    pub fn png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width) {
+            raw.push(0); // filter: none
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut zlib = vec![0x78, 0x01];
+        let mut blocks = raw.chunks(0xffff).peekable();
+        while let Some(block) = blocks.next() {
+            zlib.push(u8::from(blocks.peek().is_none()));
+            let len = block.len() as u16;
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit RGB, no interlace
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        for (kind, data) in [(b"IHDR", &header), (b"IDAT", &zlib), (b"IEND", &Vec::new())] {
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            let start = out.len();
+            out.extend_from_slice(kind);
+            out.extend_from_slice(data);
+            let crc = crc32(&out[start..]);
+            out.extend_from_slice(&crc.to_be_bytes());
+        }
+        out
+    }
+}
+
+/// Draw the board into a [`SIZE`]×[`SIZE`] raster
+#[must_use]
+pub fn rasterize(board: &Board, theme: Theme) -> Raster {
+    let mut img = Raster::new(SIZE, SIZE, theme.frame());
+    for (r, row) in board.iter().enumerate() {
+        for (c, &tile) in row.iter().enumerate() {
+            let (x, y) = origin(r, c);
+            if tile == 0 {
+                img.rounded_rect(x, y, TILE, TILE, theme.empty());
+                continue;
+            }
+            let (fill, text) = tile_colors(tile);
+            img.rounded_rect(x, y, TILE, TILE, fill);
+
+            let glyphs: Vec<&[u8; 7]> = label(tile).chars().filter_map(glyph).collect();
+            let scale = ((TILE - 20) / (6 * glyphs.len())).clamp(1, 7);
+            let width = glyphs.len() * 6 * scale - scale;
+            let (mut gx, gy) = (x + (TILE - width) / 2, y + (TILE - 7 * scale) / 2);
+            for rows in glyphs {
+                for (dy, bits) in rows.iter().enumerate() {
+                    for dx in (0..5).filter(|dx| bits & (0x10 >> dx) != 0) {
+                        let (px, py) = (gx + dx * scale, gy + dy * scale);
+                        for sy in 0..scale {
+                            let start = (py + sy) * SIZE + px;
+                            img.pixels[start..start + scale].fill(text);
+                        }
+                    }
+                }
+                gx += 6 * scale;
+            }
+        }
+    }
+    img
+}
+
+/// Save a picture of `board`; the format follows the extension of `path`
+/// (`.png` or `.svg`).
+///
+/// # Errors
+/// Returns an error for other extensions or if the file cannot be written.
+pub fn save(board: &Board, path: &Path, theme: Theme) -> io::Result<()> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("png") => fs::write(path, rasterize(board, theme).png()),
+        Some("svg") => fs::write(path, svg(board, theme)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "unsupported image format: {} (use .png or .svg)",
+                path.display()
+            ),
+        )),
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+/// 5×7 bitmap of a label character, one byte per row (high bit = left)
+fn glyph(c: char) -> Option<&'static [u8; 7]> {
+    const DIGITS: [[u8; 7]; 10] = [
+        [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e],
+        [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+        [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e],
+        [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+        [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+        [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+        [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+        [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+    ];
+    const TIMES: [u8; 7] = [0x00, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x00];
+    match c {
+        '×' => Some(&TIMES),
+        _ => DIGITS.get(c.to_digit(10)? as usize),
+    }
+}
@@ -0,0 +1,216 @@
+//! Local high-score table of finished games.
+//!
+//! Entries are derived from [`Replay`]s, so every recorded score is one the
+//! engine actually played, and each keeps the replay's digest to find the
+//! game again. The file is plain text, one entry per line, and only ever
+//! appended to, so several front-ends can share it.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::Rules;
+use crate::replay::Replay;
+
+const HEADER: &str = "akioi-2048 leaderboard 1";
+
+/// One finished game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub score: i64,
+    pub max_tile: i32,
+    /// Accepted moves
+    pub moves: usize,
+    pub seed: u64,
+    pub rules: Rules,
+    /// [`Replay::digest`] of the game
+    pub replay_hash: String,
+}
+
+impl Entry {
+    /// Play `replay` back and describe its final position
+    ///
+    /// # Errors
+    /// Returns an error if the replay does not play back.
+    pub fn from_replay(replay: &Replay) -> Result<Entry, String> {
+        let game = replay.game()?;
+        Ok(Entry {
+            score: game.score(),
+            max_tile: game.board().iter().flatten().copied().max().unwrap_or(0),
+            moves: replay.moves.len(),
+            seed: replay.seed,
+            rules: replay.rules,
+            replay_hash: replay.digest(),
+        })
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{} {} {} {} {} {}",
+            self.score,
+            self.max_tile,
+            self.moves,
+            self.seed,
+            self.rules.tag(),
+            self.replay_hash
+        )
+    }
+
+    fn parse(line: &str) -> Result<Entry, String> {
+        let invalid = || format!("invalid leaderboard entry: {line}");
+        let fields: Vec<&str> = line.split(' ').collect();
+        let &[score, max_tile, moves, seed, tag, hash] = fields.as_slice() else {
+            return Err(invalid());
+        };
+        Ok(Entry {
+            score: score.parse().map_err(|_| invalid())?,
+            max_tile: max_tile.parse().map_err(|_| invalid())?,
+            moves: moves.parse().map_err(|_| invalid())?,
+            seed: seed.parse().map_err(|_| invalid())?,
+            rules: Rules::from_tag(tag)?,
+            replay_hash: hash.to_string(),
+        })
+    }
+}
+
+/// Order of [`Leaderboard::query`], best first
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// Highest score
+    #[default]
+    Score,
+    /// Largest tile, then highest score
+    MaxTile,
+    /// Fewest moves, then highest score
+    Moves,
+}
+
+impl SortKey {
+    /// `"score"`, `"max_tile"` or `"moves"`
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<SortKey> {
+        match name {
+            "score" => Some(SortKey::Score),
+            "max_tile" => Some(SortKey::MaxTile),
+            "moves" => Some(SortKey::Moves),
+            _ => None,
+        }
+    }
+}
+
+/// A leaderboard file and its entries
+///
+/// ```
+/// use akioi_2048_core::leaderboard::{Entry, Leaderboard, SortKey};
+/// use akioi_2048_core::{Direction, Game};
+///
+/// let path = std::env::temp_dir().join(format!("akioi-doc-{}.board", std::process::id()));
+/// let mut board = Leaderboard::open(&path).unwrap();
+/// let mut game = Game::new(7);
+/// game.step(Direction::Down);
+/// let entry = Entry::from_replay(&game.replay()).unwrap();
+/// assert!(board.record(entry.clone()).unwrap());
+/// assert!(!board.record(entry).unwrap());
+/// assert_eq!(Leaderboard::open(&path).unwrap().query(None, SortKey::Score, None).len(), 1);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Leaderboard {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+impl Leaderboard {
+    /// Read the leaderboard at `path`; a missing file is an empty board,
+    /// created on the first [`record`](Self::record)
+    ///
+    /// # Errors
+    /// Returns any error reading the file, or [`io::ErrorKind::InvalidData`]
+    /// if it is not a leaderboard.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Leaderboard> {
+        let path = path.as_ref().to_path_buf();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut lines = text.lines();
+        let entries = match lines.next() {
+            None => Vec::new(),
+            Some(HEADER) => lines
+                .filter(|line| !line.is_empty())
+                .map(Entry::parse)
+                .collect::<Result<_, _>>()
+                .map_err(invalid)?,
+            Some(_) => return Err(invalid("not an akioi-2048 leaderboard".to_string())),
+        };
+        Ok(Leaderboard { path, entries })
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Entries in the order they were recorded
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Append `entry` to the file, unless a game with the same replay hash
+    /// is already on the board. Returns whether it was added.
+    ///
+    /// # Errors
+    /// Returns any error writing the file.
+    pub fn record(&mut self, entry: Entry) -> io::Result<bool> {
+        if self
+            .entries
+            .iter()
+            .any(|e| e.replay_hash == entry.replay_hash)
+        {
+            return Ok(false);
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{HEADER}")?;
+        }
+        writeln!(file, "{}", entry.to_line())?;
+        self.entries.push(entry);
+        Ok(true)
+    }
+
+    /// Entries played under `rules` (all when `None`), best first by `sort`
+    /// and at most `limit` of them; ties keep the recording order
+    #[must_use]
+    pub fn query(&self, rules: Option<&Rules>, sort: SortKey, limit: Option<usize>) -> Vec<&Entry> {
+        let mut out: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|e| rules.is_none_or(|r| e.rules == *r))
+            .collect();
+        match sort {
+            SortKey::Score => out.sort_by_key(|e| std::cmp::Reverse(e.score)),
+            SortKey::MaxTile => {
+                out.sort_by_key(|e| std::cmp::Reverse((e.max_tile, e.score)));
+            }
+            SortKey::Moves => out.sort_by_key(|e| (e.moves, std::cmp::Reverse(e.score))),
+        }
+        out.truncate(limit.unwrap_or(out.len()));
+        out
+    }
+
+    /// 1-based position `score` would take among the entries under `rules`
+    #[must_use]
+    pub fn rank(&self, score: i64, rules: &Rules) -> usize {
+        1 + self
+            .entries
+            .iter()
+            .filter(|e| e.rules == *rules && e.score > score)
+            .count()
+    }
+}
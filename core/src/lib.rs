@@ -0,0 +1,96 @@
+//! Engine for akioi 2048, a 2048 variant with multiplier tiles.
+//!
+//! [`Engine`] applies moves to boards you own, [`Game`] is a seeded game in
+//! progress and [`Rng`] lets any randomness source drive the spawns. Nothing
+//! here depends on Python; the `akioi-2048` package wraps this crate.
+
+mod actions;
+mod board;
+mod engine;
+mod game;
+mod json;
+mod mmap;
+mod pool;
+mod rng;
+
+pub mod analysis;
+pub mod arena;
+pub mod arrow;
+pub mod baseline;
+pub mod bench;
+pub mod book;
+pub mod bound;
+pub mod buffer;
+pub mod cli;
+pub mod compat;
+pub mod consistency;
+pub mod curriculum;
+pub mod dataset;
+pub mod detail;
+pub mod differential;
+pub mod editor;
+pub mod encode;
+pub mod env;
+pub mod gamelog;
+pub mod gif;
+pub mod hint;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod image;
+pub mod leaderboard;
+pub mod movelog;
+pub mod notation;
+pub mod npz;
+pub mod ntuple;
+pub mod onnx;
+pub mod pack;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod progress;
+pub mod proof;
+pub mod puzzle;
+pub mod reach;
+pub mod reference;
+pub mod render;
+pub mod replay;
+pub mod report;
+pub mod rules;
+pub mod search;
+pub mod server;
+pub mod session;
+pub mod spec;
+pub mod stats;
+pub mod stream;
+pub mod survival;
+pub mod tablebase;
+pub mod tournament;
+pub mod train;
+pub mod trajectory;
+pub mod tune;
+pub mod variant;
+pub mod versus;
+pub mod web;
+
+pub use crate::actions::{ALL_DIRECTIONS, Direction, EndReason, Move, State, Status};
+pub use crate::board::{BLOCKER, BOMB, Board, validate_board, validate_board_with};
+pub use crate::detail::{
+    Merge, MergeKind, MoveResult, Spawn, TileMove, infer_move, step_detailed, step_detailed_after,
+    step_detailed_with_rng, step_with_animation,
+};
+pub use crate::engine::Engine;
+pub use crate::game::{
+    expected_spawn_value, init, init_with_blockers, init_with_rng, init_with_rules, is_game_over,
+    is_won, slide_column_with, slide_row, spawn_probabilities, step, step_after, step_unvalidated,
+    step_with_rng, step_with_rules,
+};
+pub use crate::rng::Rng;
+pub use crate::rules::{Difficulty, Overflow, Placement, Rules, Scoring};
+pub use crate::session::{
+    Game, GameBuilder, InvalidMoves, MoveHook, MoveTiming, Moves, Snapshot, TimeControl, Timeout,
+    TimingStats,
+};
+pub use crate::variant::{MergeRule, SpawnTable, Variant};
+
+/// Hot paths the criterion suite in `core/bench` times directly
+#[cfg(feature = "bench")]
+pub use crate::game::{single_step, slide_column};
@@ -0,0 +1,108 @@
+//! Read-only views of whole files, memory-mapped where the platform allows.
+//!
+//! A mapped file is paged in lazily as it is read, and every process that
+//! maps the same file shares its pages. Elsewhere the file is read into
+//! memory instead.
+//!
+//! The pages are the file's own, so a mapping is only sound while nothing
+//! changes the file: [`Mmap::new`] is `unsafe`, as in `memmap2`, and
+//! leaves that promise to its caller.
+
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+
+/// The bytes of a file, as of when it was opened
+pub(crate) struct Mmap {
+    #[cfg(unix)]
+    ptr: *const u8,
+    #[cfg(unix)]
+    len: usize,
+    #[cfg(not(unix))]
+    bytes: Vec<u8>,
+}
+
+// SAFETY: the mapping is read-only and owned by this value alone
+#[cfg(unix)]
+unsafe impl Send for Mmap {}
+#[cfg(unix)]
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    /// Map all of `file`, hinting that it will be read at random.
+    ///
+    /// # Safety
+    /// Nothing may write to or truncate the file while it is mapped: writes
+    /// change bytes already handed out as `&[u8]`, and reading a page past
+    /// the end of a shrunk file kills the process.
+    #[cfg(unix)]
+    pub(crate) unsafe fn new(file: &File) -> io::Result<Self> {
+        use std::os::fd::AsRawFd;
+
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::OutOfMemory, "file too large to map"))?;
+        if len == 0 {
+            return Ok(Self {
+                ptr: std::ptr::NonNull::dangling().as_ptr(),
+                len,
+            });
+        }
+        // SAFETY: a fresh read-only shared mapping of an open file
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the range was just mapped; the hint only affects paging
+        unsafe { libc::madvise(ptr, len, libc::MADV_RANDOM) };
+        Ok(Self {
+            ptr: ptr.cast(),
+            len,
+        })
+    }
+
+    /// Read all of `file`.
+    ///
+    /// # Safety
+    /// Always safe here; `unsafe` only to match the mapping platforms.
+    #[cfg(not(unix))]
+    pub(crate) unsafe fn new(mut file: &File) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        io::Read::read_to_end(&mut file, &mut bytes)?;
+        Ok(Self { bytes })
+    }
+}
+
+impl Deref for Mmap {
+    type Target = [u8];
+
+    #[cfg(unix)]
+    fn deref(&self) -> &[u8] {
+        // SAFETY: `ptr` is valid for `len` bytes until dropped (or dangling
+        // with `len` 0)
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    #[cfg(not(unix))]
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: unmaps exactly the range mapped in `new`
+            unsafe { libc::munmap(self.ptr.cast_mut().cast(), self.len) };
+        }
+    }
+}
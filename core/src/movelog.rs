@@ -0,0 +1,90 @@
+//! JSON Lines move logs, one object per move.
+//!
+//! Each line reads like
+//! `{"move":0,"direction":"Left","delta":4,"score":4,"moved":true,
+//! "merges":1,"spawn":{"row":3,"col":0,"value":2},"state":"Continue"}`;
+//! `spawn` is `null` when nothing spawned.
+
+use std::io::{self, Write};
+
+use crate::{Direction, MoveResult};
+
+/// Writes one JSON line per move to `W`.
+///
+/// Every line is written with a single `write_all`, so an unbuffered file
+/// is always complete up to the last move.
+///
+/// ```
+/// use akioi_2048_core::movelog::MoveLog;
+/// use akioi_2048_core::{Direction, Game};
+///
+/// let mut game = Game::new(1);
+/// let result = game.step(Direction::Left);
+/// let mut log = MoveLog::new(Vec::new());
+/// log.write(0, Direction::Left, &result, game.score()).unwrap();
+/// let line = String::from_utf8(log.into_inner()).unwrap();
+/// assert!(line.starts_with(r#"{"move":0,"direction":"Left""#));
+/// assert!(line.ends_with("}\n"));
+/// ```
+pub struct MoveLog<W> {
+    writer: W,
+}
+
+impl<W: Write> MoveLog<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Log move number `index` (counting from `0`); `score` is the total
+    /// after it.
+    ///
+    /// # Errors
+    /// Returns the error of the underlying writer.
+    pub fn write(
+        &mut self,
+        index: usize,
+        direction: Direction,
+        result: &MoveResult,
+        score: i64,
+    ) -> io::Result<()> {
+        self.writer
+            .write_all(line(index, direction, result, score).as_bytes())
+    }
+
+    /// # Errors
+    /// Returns the error of the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// The log line of one move, newline included
+#[must_use]
+pub fn line(index: usize, direction: Direction, result: &MoveResult, score: i64) -> String {
+    let spawn = match result.spawn {
+        Some(s) => format!(
+            r#"{{"row":{},"col":{},"value":{}}}"#,
+            s.cell.0, s.cell.1, s.value
+        ),
+        None => "null".to_string(),
+    };
+    format!(
+        concat!(
+            r#"{{"move":{},"direction":"{:?}","delta":{},"score":{},"#,
+            r#""moved":{},"merges":{},"spawn":{},"state":"{:?}"}}"#,
+            "\n"
+        ),
+        index,
+        direction,
+        result.delta,
+        score,
+        result.moved,
+        result.merges.len(),
+        spawn,
+        result.state
+    )
+}
@@ -0,0 +1,120 @@
+//! A one-line text notation for boards, short enough to paste into issues,
+//! chats and tests.
+//!
+//! Rows run top to bottom, split by `/`; each holds its four cells left to
+//! right:
+//!
+//! - `.` is an empty cell,
+//! - a number is a number tile, e.g. `2` or `1024`,
+//! - `x` and a factor is a multiplier, e.g. `x2`,
+//! - `#` is a blocker and `*` a bomb.
+//!
+//! A `,` or whitespace separates a number from a number after it, so
+//! `2,4..` is a row `2 4 _ _` while `24..` would be a tile `24`; separators
+//! anywhere else are allowed and ignored. The board
+//!
+//! ```text
+//! 2 _ 4 _
+//! _ _ _ _
+//! _ ×2 _ _
+//! 8 8 _ ■
+//! ```
+//!
+//! is `2.4./..../.x2../8,8.#`.
+
+use std::fmt::Write;
+
+use crate::{BLOCKER, BOMB, Board};
+
+/// Parse a board written in the [notation](self). Tiles are read as
+/// written, without checking them against any rules.
+///
+/// ```
+/// use akioi_2048_core::notation::{parse_notation, to_notation};
+/// use akioi_2048_core::BLOCKER;
+///
+/// let board = parse_notation("2.4./..../.x2../8,8.#").unwrap();
+/// assert_eq!(board, [[2, 0, 4, 0], [0; 4], [0, -2, 0, 0], [8, 8, 0, BLOCKER]]);
+/// assert_eq!(to_notation(&board), "2.4./..../.x2../8,8.#");
+/// ```
+///
+/// # Errors
+/// Returns an error naming the first character that does not fit, or a
+/// row of more or fewer than four cells.
+pub fn parse_notation(text: &str) -> Result<Board, String> {
+    let rows: Vec<&str> = text.trim().split('/').collect();
+    if rows.len() != 4 {
+        return Err(format!(
+            "a board notation has 4 rows split by `/`, got {}: {text}",
+            rows.len()
+        ));
+    }
+    let mut board = [[0; 4]; 4];
+    for (r, (out, row)) in board.iter_mut().zip(rows).enumerate() {
+        let cells = parse_row(row).map_err(|e| format!("row {}: {e}", r + 1))?;
+        *out = cells
+            .try_into()
+            .map_err(|cells: Vec<i32>| format!("row {} has {} cells, not 4", r + 1, cells.len()))?;
+    }
+    Ok(board)
+}
+
+fn parse_row(row: &str) -> Result<Vec<i32>, String> {
+    let mut cells = Vec::with_capacity(4);
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        let cell = match c {
+            '.' => 0,
+            '#' => BLOCKER,
+            '*' => BOMB,
+            c if c == ',' || c.is_whitespace() => continue,
+            'x' | 'X' | '0'..='9' => {
+                let multiplier = !c.is_ascii_digit();
+                let mut token = c.to_string();
+                while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                    token.push(d);
+                }
+                let digits = if multiplier { &token[1..] } else { &token };
+                let n: i32 = digits
+                    .parse()
+                    .map_err(|_| format!("invalid tile `{token}`"))?;
+                if multiplier { -n } else { n }
+            }
+            _ => return Err(format!("unexpected `{c}`")),
+        };
+        cells.push(cell);
+    }
+    Ok(cells)
+}
+
+/// Write `board` in the [notation](self), with a `,` only between two
+/// numbers that would otherwise run together; [`parse_notation`] reads it
+/// back.
+#[must_use]
+pub fn to_notation(board: &Board) -> String {
+    let mut out = String::new();
+    for (r, row) in board.iter().enumerate() {
+        if r > 0 {
+            out.push('/');
+        }
+        let mut after_digit = false;
+        for &tile in row {
+            match tile {
+                0 => out.push('.'),
+                BLOCKER => out.push('#'),
+                BOMB => out.push('*'),
+                t if t < 0 => {
+                    let _ = write!(out, "x{}", -i64::from(t));
+                }
+                t => {
+                    if after_digit {
+                        out.push(',');
+                    }
+                    let _ = write!(out, "{t}");
+                }
+            }
+            after_digit = out.ends_with(|c: char| c.is_ascii_digit());
+        }
+    }
+    out
+}
@@ -0,0 +1,185 @@
+//! Minimal writer for NumPy `.npz` archives (uncompressed zip of `.npy` files).
+
+use std::io::{self, Write};
+
+/// Element types understood by [`Array`]
+#[derive(Clone, Copy)]
+pub enum Dtype {
+    Bool,
+    U8,
+    I32,
+    F32,
+}
+
+impl Dtype {
+    fn descr(self) -> &'static str {
+        match self {
+            Dtype::Bool => "|b1",
+            Dtype::U8 => "|u1",
+            Dtype::I32 => "<i4",
+            Dtype::F32 => "<f4",
+        }
+    }
+}
+
+/// One named array: raw little-endian bytes plus dtype and shape
+pub struct Array {
+    pub name: &'static str,
+    pub dtype: Dtype,
+    pub shape: Vec<usize>,
+    pub data: Vec<u8>,
+}
+
+impl Array {
+    #[must_use]
+    pub fn i32(name: &'static str, shape: Vec<usize>, values: impl Iterator<Item = i32>) -> Self {
+        let data = values.flat_map(i32::to_le_bytes).collect();
+        Self {
+            name,
+            dtype: Dtype::I32,
+            shape,
+            data,
+        }
+    }
+
+    #[must_use]
+    pub fn u8(name: &'static str, shape: Vec<usize>, values: impl Iterator<Item = u8>) -> Self {
+        Self {
+            name,
+            dtype: Dtype::U8,
+            shape,
+            data: values.collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn bool(name: &'static str, shape: Vec<usize>, values: impl Iterator<Item = bool>) -> Self {
+        let data = values.map(u8::from).collect();
+        Self {
+            name,
+            dtype: Dtype::Bool,
+            shape,
+            data,
+        }
+    }
+
+    #[must_use]
+    pub fn f32(name: &'static str, shape: Vec<usize>, values: impl Iterator<Item = f32>) -> Self {
+        let data = values.flat_map(f32::to_le_bytes).collect();
+        Self {
+            name,
+            dtype: Dtype::F32,
+            shape,
+            data,
+        }
+    }
+
+    /// Serialize as a version 1.0 `.npy` file
+    fn to_npy(&self) -> Vec<u8> {
+        let shape = match self.shape.as_slice() {
+            [n] => format!("({n},)"),
+            dims => {
+                let parts: Vec<String> = dims.iter().map(ToString::to_string).collect();
+                format!("({})", parts.join(", "))
+            }
+        };
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': {shape}, }}",
+            self.dtype.descr()
+        );
+        // magic(6) + version(2) + header_len(2) + header, padded to 64 bytes
+        let unpadded = 10 + header.len() + 1;
+        header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+        header.push('\n');
+
+        let header_len = u16::try_from(header.len()).expect("npy header fits in u16");
+        let mut out = Vec::with_capacity(10 + header.len() + self.data.len());
+        out.extend_from_slice(b"\x93NUMPY\x01\x00");
+        out.extend_from_slice(&header_len.to_le_bytes());
+        out.extend_from_slice(header.as_bytes());
+        out.extend_from_slice(&self.data);
+        out
+    }
+}
+
+/// Write `arrays` as an `.npz` archive readable by `numpy.load`.
+///
+/// # Errors
+/// Returns an error if writing fails or the archive exceeds 4 GiB.
+pub fn write<W: Write>(mut out: W, arrays: &[Array]) -> io::Result<()> {
+    let too_large = || io::Error::other("npz archive larger than 4 GiB");
+    let mut offset: u32 = 0;
+    let mut central = Vec::new();
+    let mut count: u16 = 0;
+
+    for array in arrays {
+        let name = format!("{}.npy", array.name);
+        let body = array.to_npy();
+        let crc = crc32(&body);
+        let size = u32::try_from(body.len()).map_err(|_| too_large())?;
+        let name_len = u16::try_from(name.len()).map_err(|_| too_large())?;
+
+        // local file header
+        let mut local = Vec::with_capacity(30 + name.len());
+        local.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        local.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        local.extend_from_slice(&0u16.to_le_bytes()); // flags
+        local.extend_from_slice(&0u16.to_le_bytes()); // method: stored
+        local.extend_from_slice(&0u16.to_le_bytes()); // mod time
+        local.extend_from_slice(&0x0021u16.to_le_bytes()); // mod date: 1980-01-01
+        local.extend_from_slice(&crc.to_le_bytes());
+        local.extend_from_slice(&size.to_le_bytes());
+        local.extend_from_slice(&size.to_le_bytes());
+        local.extend_from_slice(&name_len.to_le_bytes());
+        local.extend_from_slice(&0u16.to_le_bytes()); // extra len
+        local.extend_from_slice(name.as_bytes());
+
+        // central directory entry
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        central.extend_from_slice(&local[4..30]);
+        central.extend_from_slice(&0u16.to_le_bytes()); // comment len
+        central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        central.extend_from_slice(&0u16.to_le_bytes()); // internal attrs
+        central.extend_from_slice(&0u32.to_le_bytes()); // external attrs
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+
+        out.write_all(&local)?;
+        out.write_all(&body)?;
+        let written = u32::try_from(local.len()).map_err(|_| too_large())?;
+        offset = offset
+            .checked_add(written)
+            .and_then(|o| o.checked_add(size))
+            .ok_or_else(too_large)?;
+        count += 1;
+    }
+
+    let central_len = u32::try_from(central.len()).map_err(|_| too_large())?;
+    out.write_all(&central)?;
+    // end of central directory
+    let mut end = Vec::with_capacity(22);
+    end.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    end.extend_from_slice(&0u16.to_le_bytes()); // this disk
+    end.extend_from_slice(&0u16.to_le_bytes()); // central dir disk
+    end.extend_from_slice(&count.to_le_bytes());
+    end.extend_from_slice(&count.to_le_bytes());
+    end.extend_from_slice(&central_len.to_le_bytes());
+    end.extend_from_slice(&offset.to_le_bytes());
+    end.extend_from_slice(&0u16.to_le_bytes()); // comment len
+    out.write_all(&end)?;
+    out.flush()
+}
+
+/// CRC-32 (IEEE) as required by the zip and PNG formats
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
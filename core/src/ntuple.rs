@@ -0,0 +1,223 @@
+//! N-tuple networks: board values as sums of weights looked up by the
+//! tiles under a few small patterns of cells.
+//!
+//! [`NTuple`] reads four 4-cell patterns, the outer and inner rows and the
+//! corner and edge 2×2 squares, each under the 8 symmetries of the board,
+//! so a board's value is the sum of 32 weights. Tiles are coded in 5 bits,
+//! which keeps the whole network at 4M `f32` weights. [`train`] fits one
+//! by temporal-difference learning.
+//!
+//! [`train`]: crate::train::train
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::{BLOCKER, BOMB, Board, Direction, Engine};
+
+/// Cells of each pattern, before the symmetries
+const PATTERNS: [[(usize, usize); 4]; 4] = [
+    [(0, 0), (0, 1), (0, 2), (0, 3)],
+    [(1, 0), (1, 1), (1, 2), (1, 3)],
+    [(0, 0), (0, 1), (1, 0), (1, 1)],
+    [(0, 1), (0, 2), (1, 1), (1, 2)],
+];
+
+/// Distinct tile codes, see [`code`]
+const CODES: usize = 32;
+
+/// Weights per pattern, one per combination of codes under its cells
+const TABLE: usize = CODES.pow(4);
+
+/// Weights a board's value sums
+pub const FEATURES: usize = PATTERNS.len() * 8;
+
+/// First bytes of a saved network, before its version byte
+const MAGIC: &[u8; 7] = b"AKNT\0\0\0";
+
+/// Version [`NTuple::write`] writes
+const VERSION: u8 = 1;
+
+/// A 4-tuple network; see the [module docs](self).
+///
+/// ```
+/// use akioi_2048_core::ntuple::{FEATURES, NTuple};
+///
+/// let mut net = NTuple::new();
+/// let board = [[2, 4, 8, 16], [32, 64, 128, 256], [512, 1024, 2048, 4096], [-1, -2, 0, 0]];
+/// assert_eq!(net.value(&board), 0.0);
+/// net.update(&board, 0.5);
+/// assert_eq!(net.value(&board), 0.5 * FEATURES as f64);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct NTuple {
+    weights: Vec<f32>,
+}
+
+impl Default for NTuple {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NTuple {
+    /// A network valuing every board at 0
+    #[must_use]
+    pub fn new() -> Self {
+        NTuple {
+            weights: vec![0.0; PATTERNS.len() * TABLE],
+        }
+    }
+
+    /// Number of weights
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// The sum of the [`FEATURES`] weights `board` reads
+    #[must_use]
+    pub fn value(&self, board: &Board) -> f64 {
+        features(board).map(|i| f64::from(self.weights[i])).sum()
+    }
+
+    /// Add `step` to every weight `board` reads, moving its value by
+    /// [`FEATURES`] times `step` (less if a weight is read twice)
+    pub fn update(&mut self, board: &Board, step: f32) {
+        for i in features(board) {
+            self.weights[i] += step;
+        }
+    }
+
+    /// The legal move with the highest score gained plus value of the board
+    /// it slides to, with that board and the gain; `None` without legal
+    /// moves
+    #[must_use]
+    pub fn best_move(&self, engine: &Engine, board: &Board) -> Option<(Direction, Board, i32)> {
+        let mut best: Option<(f64, (Direction, Board, i32))> = None;
+        for dir in engine.legal_moves(board) {
+            let (after, gain) = engine.slide(board, dir);
+            let value = f64::from(gain) + self.value(&after);
+            if best.as_ref().is_none_or(|(v, _)| value > *v) {
+                best = Some((value, (dir, after, gain)));
+            }
+        }
+        best.map(|(_, choice)| choice)
+    }
+
+    /// Write the network: a versioned header, the weights as little-endian
+    /// `f32` and a SHA-256 of everything before it.
+    ///
+    /// # Errors
+    /// Returns any error of `out`.
+    pub fn write<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend((PATTERNS.len() as u32).to_le_bytes());
+        bytes.extend((CODES as u32).to_le_bytes());
+        bytes.reserve(4 * self.weights.len() + 32);
+        for weight in &self.weights {
+            bytes.extend(weight.to_le_bytes());
+        }
+        bytes.extend(Sha256::digest(&bytes));
+        out.write_all(&bytes)?;
+        out.flush()
+    }
+
+    /// Read a network written by [`write`](Self::write).
+    ///
+    /// # Errors
+    /// Returns any error of `input`, or [`io::ErrorKind::InvalidData`] for
+    /// data that is not a network of this shape or fails its checksum.
+    pub fn read<R: Read>(mut input: R) -> io::Result<NTuple> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        let mut bytes = Vec::new();
+        input.read_to_end(&mut bytes)?;
+        if bytes.get(..MAGIC.len()) != Some(MAGIC) {
+            return Err(invalid("not an n-tuple network file"));
+        }
+        let header = MAGIC.len() + 9;
+        if bytes.len() != header + 4 * PATTERNS.len() * TABLE + 32 {
+            return Err(invalid("n-tuple network has the wrong size"));
+        }
+        let (body, digest) = bytes.split_at(bytes.len() - 32);
+        if Sha256::digest(body)[..] != *digest {
+            return Err(invalid("n-tuple network checksum mismatch"));
+        }
+        let word = |at: usize| u32::from_le_bytes(body[at..at + 4].try_into().expect("4 bytes"));
+        let version = body[MAGIC.len()];
+        if version != VERSION {
+            return Err(invalid(&format!(
+                "unsupported n-tuple network version {version}"
+            )));
+        }
+        if (word(MAGIC.len() + 1), word(MAGIC.len() + 5)) != (PATTERNS.len() as u32, CODES as u32) {
+            return Err(invalid("n-tuple network has other patterns"));
+        }
+        let weights = body[header..]
+            .chunks_exact(4)
+            .map(|w| f32::from_le_bytes(w.try_into().expect("4 bytes")))
+            .collect();
+        Ok(NTuple { weights })
+    }
+
+    /// Save the network to `path`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    /// Load a network saved with [`save`](Self::save).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a network.
+    pub fn load(path: &Path) -> io::Result<NTuple> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+/// 5-bit code of a tile: 0 when empty, `1 + log2` for numbers (1 to 17),
+/// `18 + log2` of the factor for multipliers, then blockers and bombs
+fn code(tile: i32) -> usize {
+    match tile {
+        0 => 0,
+        BLOCKER => 30,
+        BOMB => 31,
+        t if t > 0 => 1 + t.ilog2().min(16) as usize,
+        t => 18 + t.unsigned_abs().ilog2().min(11) as usize,
+    }
+}
+
+/// `(row, col)` under symmetry `s` of the eight: four rotations, each
+/// optionally mirrored
+fn symmetry(s: usize, (r, c): (usize, usize)) -> (usize, usize) {
+    let (r, c) = if s >= 4 { (r, 3 - c) } else { (r, c) };
+    match s % 4 {
+        0 => (r, c),
+        1 => (c, 3 - r),
+        2 => (3 - r, 3 - c),
+        _ => (3 - c, r),
+    }
+}
+
+/// Indices of the weights `board` reads
+fn features(board: &Board) -> impl Iterator<Item = usize> + '_ {
+    PATTERNS.iter().enumerate().flat_map(move |(p, cells)| {
+        (0..8).map(move |s| {
+            let index = cells.iter().fold(0, |index, &cell| {
+                let (r, c) = symmetry(s, cell);
+                index * CODES + code(board[r][c])
+            });
+            p * TABLE + index
+        })
+    })
+}
@@ -0,0 +1,774 @@
+//! Neural policies in the ONNX format, run natively.
+//!
+//! [`Model`] reads the subset of ONNX that feed-forward networks exported
+//! from PyTorch or Keras use: dense layers (`Gemm`, `MatMul`), elementwise
+//! arithmetic with broadcasting, the usual activations, `Softmax`,
+//! `Flatten` and `Reshape`. Models with other operators (convolutions,
+//! recurrence, control flow) are rejected when loading. Weights are `f32`
+//! and models run one board at a time on the calling thread.
+//!
+//! The interpreter is built in rather than bound to ONNX Runtime or tract,
+//! so the wheels link no native runtime and carry no large dependency tree
+//! for networks of a few dense layers. Larger models (convolutional or
+//! recurrent) are out of scope: run them with `onnxruntime` in Python and
+//! pass the policy as a callable.
+//!
+//! [`NeuralPolicy`] feeds a model boards [`encode`]d under a [`Scheme`]
+//! and plays the legal move with the highest output.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::encode::{Scheme, encode};
+use crate::{ALL_DIRECTIONS, Board, Direction, Engine, Rules};
+
+/// A dense `f32` tensor, row-major
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tensor {
+    pub dims: Vec<usize>,
+    pub data: Vec<f32>,
+}
+
+impl Tensor {
+    fn scalar_at(&self, i: usize) -> f32 {
+        self.data[i]
+    }
+}
+
+/// Operators [`Model`] runs, with their attributes
+#[derive(Clone, Debug, PartialEq)]
+enum Op {
+    Gemm {
+        alpha: f32,
+        beta: f32,
+        trans_a: bool,
+        trans_b: bool,
+    },
+    MatMul,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Relu,
+    LeakyRelu(f32),
+    Tanh,
+    Sigmoid,
+    Softmax(i64),
+    LogSoftmax(i64),
+    Flatten(i64),
+    Reshape,
+    Identity,
+    Constant(Tensor),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Node {
+    op: Op,
+    inputs: Vec<String>,
+    output: String,
+}
+
+/// A feed-forward ONNX model; see the [module docs](self)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Model {
+    nodes: Vec<Node>,
+    initializers: HashMap<String, Tensor>,
+    input: String,
+    /// Declared shape of the input, symbolic dimensions as 1
+    input_dims: Vec<usize>,
+    output: String,
+}
+
+impl Model {
+    /// Read a model from the bytes of an `.onnx` file.
+    ///
+    /// # Errors
+    /// Returns an error for malformed protobuf, a graph without input or
+    /// output, or an unsupported operator or attribute.
+    pub fn parse(bytes: &[u8]) -> Result<Model, String> {
+        let mut graph = None;
+        for field in fields(bytes) {
+            if let (7, Value::Bytes(g)) = field? {
+                graph = Some(g);
+            }
+        }
+        let graph = graph.ok_or("not an ONNX model: no graph")?;
+        let (mut nodes, mut initializers, mut inputs, mut outputs) =
+            (Vec::new(), HashMap::new(), Vec::new(), Vec::new());
+        for field in fields(graph) {
+            match field? {
+                (1, Value::Bytes(node)) => nodes.push(parse_node(node)?),
+                (5, Value::Bytes(tensor)) => {
+                    let (name, tensor) = parse_tensor(tensor)?;
+                    initializers.insert(name, tensor);
+                }
+                (11, Value::Bytes(info)) => inputs.push(parse_value_info(info)?),
+                (12, Value::Bytes(info)) => outputs.push(parse_value_info(info)?),
+                _ => {}
+            }
+        }
+        // older exporters list the initializers among the inputs
+        let (input, input_dims) = inputs
+            .into_iter()
+            .find(|(name, _)| !initializers.contains_key(name))
+            .ok_or("the ONNX graph has no input")?;
+        let (output, _) = outputs
+            .into_iter()
+            .next()
+            .ok_or("the ONNX graph has no output")?;
+        elements(&input_dims)?;
+        Ok(Model {
+            nodes,
+            initializers,
+            input,
+            input_dims,
+            output,
+        })
+    }
+
+    /// Read the model saved at `path`
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a supported
+    /// model.
+    pub fn load(path: &Path) -> Result<Model, String> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        Self::parse(&bytes)
+    }
+
+    /// Number of values the model's input takes
+    #[must_use]
+    pub fn input_len(&self) -> usize {
+        self.input_dims.iter().product()
+    }
+
+    /// Run the model on `input`, reshaped to the declared input shape.
+    ///
+    /// ```
+    /// use akioi_2048_core::onnx::Model;
+    ///
+    /// // y = relu(x W + b): 2 inputs, 2 outputs, written field by field
+    /// fn field(n: u8, bytes: &[u8]) -> Vec<u8> {
+    ///     [&[n << 3 | 2, bytes.len() as u8][..], bytes].concat()
+    /// }
+    /// fn tensor(name: &str, dims: &[u8], values: &[f32]) -> Vec<u8> {
+    ///     let raw: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    ///     let dims: Vec<u8> = dims.iter().flat_map(|&d| [1 << 3, d]).collect();
+    ///     [dims, vec![2 << 3, 1], field(8, name.as_bytes()), field(9, &raw)].concat()
+    /// }
+    /// let node = |op: &str, inputs: &[&str], output: &str| {
+    ///     let inputs: Vec<u8> = inputs.iter().flat_map(|i| field(1, i.as_bytes())).collect();
+    ///     [inputs, field(2, output.as_bytes()), field(4, op.as_bytes())].concat()
+    /// };
+    /// let graph = [
+    ///     field(1, &node("MatMul", &["x", "w"], "h")),
+    ///     field(1, &node("Add", &["h", "b"], "z")),
+    ///     field(1, &node("Relu", &["z"], "y")),
+    ///     field(5, &tensor("w", &[2, 2], &[1.0, -1.0, 2.0, 0.5])),
+    ///     field(5, &tensor("b", &[2], &[0.5, -4.0])),
+    ///     field(11, &field(1, b"x")),
+    ///     field(12, &field(1, b"y")),
+    /// ]
+    /// .concat();
+    /// let model = Model::parse(&field(7, &graph)).unwrap();
+    /// let y = model.run(&[1.0, 2.0]).unwrap();
+    /// assert_eq!(y.data, [5.5, 0.0]);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error for input of the wrong length, a missing tensor or
+    /// shapes an operator cannot combine.
+    pub fn run(&self, input: &[f32]) -> Result<Tensor, String> {
+        let dims = if self.input_dims.is_empty() {
+            vec![1, input.len()]
+        } else {
+            self.input_dims.clone()
+        };
+        if elements(&dims)? != input.len() {
+            return Err(format!(
+                "the model takes {} inputs, got {}",
+                self.input_len(),
+                input.len()
+            ));
+        }
+        let mut values: HashMap<&str, Tensor> = HashMap::new();
+        values.insert(
+            &self.input,
+            Tensor {
+                dims,
+                data: input.to_vec(),
+            },
+        );
+        for node in &self.nodes {
+            let args = node
+                .inputs
+                .iter()
+                .map(|name| {
+                    values
+                        .get(name.as_str())
+                        .or_else(|| self.initializers.get(name))
+                        .ok_or_else(|| format!("tensor {name:?} is used before it is computed"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let result = apply(&node.op, &args)?;
+            values.insert(&node.output, result);
+        }
+        values
+            .remove(self.output.as_str())
+            .ok_or_else(|| format!("the model never computes its output {:?}", self.output))
+    }
+}
+
+/// A model playing moves: boards go in [`encode`]d under a scheme, and the
+/// first four outputs score Down, Right, Up and Left
+#[derive(Clone, Debug)]
+pub struct NeuralPolicy {
+    model: Model,
+    scheme: Scheme,
+    engine: Engine,
+}
+
+impl NeuralPolicy {
+    /// # Errors
+    /// Returns an error for inconsistent rules or a model whose input does
+    /// not take one board encoded under `scheme`.
+    pub fn new(model: Model, scheme: Scheme, rules: Rules) -> Result<Self, String> {
+        let engine = Engine::new(rules)?;
+        let wanted = scheme.channels(&rules) * 16;
+        if !model.input_dims.is_empty() && model.input_len() != wanted {
+            return Err(format!(
+                "the model takes {} inputs, but a board encoded this way has {wanted}",
+                model.input_len()
+            ));
+        }
+        Ok(NeuralPolicy {
+            model,
+            scheme,
+            engine,
+        })
+    }
+
+    /// The model's score of each direction, in action-id order
+    ///
+    /// # Errors
+    /// Returns an error for an invalid board or a model that fails to run
+    /// or has fewer than four outputs.
+    pub fn scores(&self, board: &Board) -> Result<[f32; 4], String> {
+        let mut input = Vec::new();
+        encode(board, self.scheme, &self.engine.rules(), &mut input)?;
+        let output = self.model.run(&input)?;
+        if output.data.len() < 4 {
+            return Err(format!(
+                "the model has {} outputs; a policy needs 4",
+                output.data.len()
+            ));
+        }
+        Ok(std::array::from_fn(|i| output.scalar_at(i)))
+    }
+
+    /// The legal move the model scores highest, or `None` without legal
+    /// moves
+    ///
+    /// # Errors
+    /// As [`NeuralPolicy::scores`].
+    pub fn choose(&self, board: &Board) -> Result<Option<Direction>, String> {
+        let scores = self.scores(board)?;
+        let legal: Vec<_> = self.engine.legal_moves(board).collect();
+        Ok(ALL_DIRECTIONS
+            .iter()
+            .zip(scores)
+            .filter(|(dir, _)| legal.contains(dir))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(&dir, _)| dir))
+    }
+}
+
+/// Run `op` on its arguments
+fn apply(op: &Op, args: &[&Tensor]) -> Result<Tensor, String> {
+    let arg = |i: usize| {
+        args.get(i)
+            .copied()
+            .ok_or_else(|| format!("{op:?} expects at least {} inputs", i + 1))
+    };
+    let map = |f: &dyn Fn(f32) -> f32| -> Result<Tensor, String> {
+        let x = arg(0)?;
+        Ok(Tensor {
+            dims: x.dims.clone(),
+            data: x.data.iter().map(|&v| f(v)).collect(),
+        })
+    };
+    match op {
+        Op::Gemm {
+            alpha,
+            beta,
+            trans_a,
+            trans_b,
+        } => {
+            let a = transpose_if(arg(0)?, *trans_a)?;
+            let b = transpose_if(arg(1)?, *trans_b)?;
+            let mut y = matmul(&a, &b)?;
+            y.data.iter_mut().for_each(|v| *v *= alpha);
+            match args.get(2) {
+                Some(c) => {
+                    let c = Tensor {
+                        dims: c.dims.clone(),
+                        data: c.data.iter().map(|v| v * beta).collect(),
+                    };
+                    broadcast(&y, &c, |a, b| a + b)
+                }
+                None => Ok(y),
+            }
+        }
+        Op::MatMul => matmul(arg(0)?, arg(1)?),
+        Op::Add => broadcast(arg(0)?, arg(1)?, |a, b| a + b),
+        Op::Sub => broadcast(arg(0)?, arg(1)?, |a, b| a - b),
+        Op::Mul => broadcast(arg(0)?, arg(1)?, |a, b| a * b),
+        Op::Div => broadcast(arg(0)?, arg(1)?, |a, b| a / b),
+        Op::Relu => map(&|v| v.max(0.0)),
+        Op::LeakyRelu(alpha) => map(&|v| if v < 0.0 { alpha * v } else { v }),
+        Op::Tanh => map(&f32::tanh),
+        Op::Sigmoid => map(&|v| 1.0 / (1.0 + (-v).exp())),
+        Op::Softmax(axis) => softmax(arg(0)?, *axis, false),
+        Op::LogSoftmax(axis) => softmax(arg(0)?, *axis, true),
+        Op::Flatten(axis) => {
+            let x = arg(0)?;
+            let axis = resolve_axis(*axis, x.dims.len() + 1)?;
+            let outer = x.dims[..axis].iter().product();
+            Ok(Tensor {
+                dims: vec![outer, x.data.len() / outer.max(1)],
+                data: x.data.clone(),
+            })
+        }
+        Op::Reshape => {
+            let (x, shape) = (arg(0)?, arg(1)?);
+            let mut dims = Vec::with_capacity(shape.data.len());
+            let mut unknown = None;
+            for (i, &d) in shape.data.iter().enumerate() {
+                match d as i64 {
+                    -1 => unknown = Some(i),
+                    0 => dims.push(*x.dims.get(i).ok_or("Reshape copies a missing dimension")?),
+                    d if d > 0 => dims.push(d as usize),
+                    d => return Err(format!("invalid Reshape dimension {d}")),
+                }
+                if unknown == Some(i) {
+                    dims.push(1);
+                }
+            }
+            if let Some(i) = unknown {
+                let known = elements(&dims)?;
+                dims[i] = x.data.len() / known.max(1);
+            }
+            if elements(&dims)? != x.data.len() {
+                return Err(format!("cannot reshape {:?} to {dims:?}", x.dims));
+            }
+            Ok(Tensor {
+                dims,
+                data: x.data.clone(),
+            })
+        }
+        Op::Identity => Ok(arg(0)?.clone()),
+        Op::Constant(value) => Ok(value.clone()),
+    }
+}
+
+/// Number of values a tensor of shape `dims` holds
+fn elements(dims: &[usize]) -> Result<usize, String> {
+    dims.iter()
+        .try_fold(1_usize, |n, &d| n.checked_mul(d))
+        .ok_or_else(|| format!("tensor shape {dims:?} holds too many values"))
+}
+
+/// `axis` counted from the end when negative, checked against `rank`
+fn resolve_axis(axis: i64, rank: usize) -> Result<usize, String> {
+    let resolved = if axis < 0 { axis + rank as i64 } else { axis };
+    usize::try_from(resolved)
+        .ok()
+        .filter(|&a| a < rank.max(1))
+        .ok_or_else(|| format!("axis {axis} out of range for rank {rank}"))
+}
+
+fn transpose_if(x: &Tensor, transpose: bool) -> Result<Tensor, String> {
+    let [rows, cols] = x.dims[..] else {
+        return Err(format!("Gemm takes matrices, got shape {:?}", x.dims));
+    };
+    if !transpose {
+        return Ok(x.clone());
+    }
+    Ok(Tensor {
+        dims: vec![cols, rows],
+        data: (0..cols * rows)
+            .map(|i| x.data[(i % rows) * cols + i / rows])
+            .collect(),
+    })
+}
+
+/// Matrix product of 1- or 2-dimensional tensors
+fn matmul(a: &Tensor, b: &Tensor) -> Result<Tensor, String> {
+    let as_matrix = |t: &Tensor, row: bool| match t.dims[..] {
+        [n] if row => Ok((1, n)),
+        [n] => Ok((n, 1)),
+        [r, c] => Ok((r, c)),
+        _ => Err(format!("MatMul takes up to 2 dimensions, got {:?}", t.dims)),
+    };
+    let ((m, k), (k2, n)) = (as_matrix(a, true)?, as_matrix(b, false)?);
+    if k != k2 {
+        return Err(format!("cannot multiply {:?} by {:?}", a.dims, b.dims));
+    }
+    let mut data = vec![0.0; m * n];
+    for i in 0..m {
+        for p in 0..k {
+            let x = a.data[i * k + p];
+            for j in 0..n {
+                data[i * n + j] += x * b.data[p * n + j];
+            }
+        }
+    }
+    let dims = match (a.dims.len(), b.dims.len()) {
+        (1, 1) => vec![],
+        (1, _) => vec![n],
+        (_, 1) => vec![m],
+        _ => vec![m, n],
+    };
+    Ok(Tensor { dims, data })
+}
+
+/// `f` elementwise under NumPy broadcasting
+fn broadcast(a: &Tensor, b: &Tensor, f: impl Fn(f32, f32) -> f32) -> Result<Tensor, String> {
+    let rank = a.dims.len().max(b.dims.len());
+    let padded = |t: &Tensor| {
+        let mut dims = vec![1; rank - t.dims.len()];
+        dims.extend(&t.dims);
+        dims
+    };
+    let (da, db) = (padded(a), padded(b));
+    let dims = da
+        .iter()
+        .zip(&db)
+        .map(|(&x, &y)| match (x, y) {
+            _ if x == y => Ok(x),
+            (1, _) => Ok(y),
+            (_, 1) => Ok(x),
+            _ => Err(format!("cannot broadcast {:?} with {:?}", a.dims, b.dims)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let strides = |d: &[usize]| {
+        let mut strides = vec![0; rank];
+        let mut step = 1;
+        for i in (0..rank).rev() {
+            strides[i] = if d[i] == 1 { 0 } else { step };
+            step *= d[i];
+        }
+        strides
+    };
+    let (sa, sb) = (strides(&da), strides(&db));
+    let len = dims.iter().product();
+    let data = (0..len)
+        .map(|mut flat| {
+            let (mut ia, mut ib) = (0, 0);
+            for axis in (0..rank).rev() {
+                let index = flat % dims[axis];
+                flat /= dims[axis];
+                ia += index * sa[axis];
+                ib += index * sb[axis];
+            }
+            f(a.data[ia], b.data[ib])
+        })
+        .collect();
+    Ok(Tensor { dims, data })
+}
+
+fn softmax(x: &Tensor, axis: i64, log: bool) -> Result<Tensor, String> {
+    if x.dims.is_empty() || x.data.is_empty() {
+        return Err(format!(
+            "Softmax takes a non-empty tensor of rank 1 or more, got shape {:?}",
+            x.dims
+        ));
+    }
+    let axis = resolve_axis(axis, x.dims.len())?;
+    let len = x.dims[axis];
+    let inner: usize = x.dims[axis + 1..].iter().product();
+    let mut data = x.data.clone();
+    for block in data.chunks_mut(len * inner) {
+        for offset in 0..inner {
+            let at = |i: usize| i * inner + offset;
+            let max = (0..len)
+                .map(|i| block[at(i)])
+                .fold(f32::NEG_INFINITY, f32::max);
+            let sum: f32 = (0..len).map(|i| (block[at(i)] - max).exp()).sum();
+            for i in 0..len {
+                let v = block[at(i)] - max;
+                block[at(i)] = if log { v - sum.ln() } else { v.exp() / sum };
+            }
+        }
+    }
+    Ok(Tensor {
+        dims: x.dims.clone(),
+        data,
+    })
+}
+
+/// A protobuf field's payload
+enum Value<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+fn varint(bytes: &[u8], at: &mut usize) -> Result<u64, String> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*at).ok_or("truncated protobuf varint")?;
+        *at += 1;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("overlong protobuf varint".to_string())
+}
+
+/// The next `n` bytes at `at`
+fn take<'a>(bytes: &'a [u8], at: &mut usize, n: usize) -> Result<&'a [u8], String> {
+    let slice = bytes
+        .get(*at..at.saturating_add(n))
+        .ok_or("truncated protobuf field")?;
+    *at += n;
+    Ok(slice)
+}
+
+/// The field at `at`: its number and payload
+fn field<'a>(bytes: &'a [u8], at: &mut usize) -> Result<(u64, Value<'a>), String> {
+    let key = varint(bytes, at)?;
+    let value = match key & 7 {
+        0 => Value::Varint(varint(bytes, at)?),
+        1 => Value::Fixed64(u64::from_le_bytes(
+            take(bytes, at, 8)?.try_into().expect("8 bytes"),
+        )),
+        2 => {
+            let len = usize::try_from(varint(bytes, at)?).map_err(|e| e.to_string())?;
+            Value::Bytes(take(bytes, at, len)?)
+        }
+        5 => Value::Fixed32(u32::from_le_bytes(
+            take(bytes, at, 4)?.try_into().expect("4 bytes"),
+        )),
+        wire => return Err(format!("unsupported protobuf wire type {wire}")),
+    };
+    Ok((key >> 3, value))
+}
+
+/// The `(field number, payload)` pairs of a protobuf message, stopping
+/// after the first malformed one
+fn fields(bytes: &[u8]) -> impl Iterator<Item = Result<(u64, Value<'_>), String>> {
+    let mut at = 0;
+    std::iter::from_fn(move || {
+        if at >= bytes.len() {
+            return None;
+        }
+        let result = field(bytes, &mut at);
+        if result.is_err() {
+            at = bytes.len();
+        }
+        Some(result)
+    })
+}
+
+/// The payload of the first length-delimited field `number` of a message
+fn nested(bytes: &[u8], number: u64) -> Result<Option<&[u8]>, String> {
+    for field in fields(bytes) {
+        if let (n, Value::Bytes(inner)) = field?
+            && n == number
+        {
+            return Ok(Some(inner));
+        }
+    }
+    Ok(None)
+}
+
+fn string(bytes: &[u8]) -> Result<String, String> {
+    String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string())
+}
+
+/// Repeated integers, packed or not, appended to `out`
+fn push_ints(value: Value<'_>, out: &mut Vec<i64>) -> Result<(), String> {
+    match value {
+        Value::Varint(v) => out.push(v as i64),
+        Value::Bytes(packed) => {
+            let mut at = 0;
+            while at < packed.len() {
+                out.push(varint(packed, &mut at)? as i64);
+            }
+        }
+        _ => return Err("malformed ONNX integer list".to_string()),
+    }
+    Ok(())
+}
+
+/// A `TensorProto`: its name and values as `f32`
+fn parse_tensor(bytes: &[u8]) -> Result<(String, Tensor), String> {
+    const FLOAT: u64 = 1;
+    const INT32: u64 = 6;
+    const INT64: u64 = 7;
+    const DOUBLE: u64 = 11;
+    let (mut dims, mut data_type, mut name) = (Vec::new(), FLOAT, String::new());
+    let (mut raw, mut floats, mut ints, mut doubles) = (None, Vec::new(), Vec::new(), Vec::new());
+    for field in fields(bytes) {
+        match field? {
+            (1, value) => push_ints(value, &mut dims)?,
+            (2, Value::Varint(t)) => data_type = t,
+            (4, Value::Fixed32(f)) => floats.push(f32::from_bits(f)),
+            (4, Value::Bytes(packed)) => floats.extend(
+                packed
+                    .chunks_exact(4)
+                    .map(|f| f32::from_le_bytes(f.try_into().expect("4 bytes"))),
+            ),
+            (5 | 7, value) => push_ints(value, &mut ints)?,
+            (8, Value::Bytes(n)) => name = string(n)?,
+            (9, Value::Bytes(r)) => raw = Some(r),
+            (10, Value::Fixed64(d)) => doubles.push(f64::from_bits(d)),
+            (10, Value::Bytes(packed)) => doubles.extend(
+                packed
+                    .chunks_exact(8)
+                    .map(|d| f64::from_le_bytes(d.try_into().expect("8 bytes"))),
+            ),
+            _ => {}
+        }
+    }
+    let data: Vec<f32> = match (data_type, raw) {
+        (FLOAT, Some(raw)) => raw
+            .chunks_exact(4)
+            .map(|f| f32::from_le_bytes(f.try_into().expect("4 bytes")))
+            .collect(),
+        (INT32, Some(raw)) => raw
+            .chunks_exact(4)
+            .map(|i| i32::from_le_bytes(i.try_into().expect("4 bytes")) as f32)
+            .collect(),
+        (INT64, Some(raw)) => raw
+            .chunks_exact(8)
+            .map(|i| i64::from_le_bytes(i.try_into().expect("8 bytes")) as f32)
+            .collect(),
+        (DOUBLE, Some(raw)) => raw
+            .chunks_exact(8)
+            .map(|d| f64::from_le_bytes(d.try_into().expect("8 bytes")) as f32)
+            .collect(),
+        (FLOAT, None) => floats,
+        (INT32 | INT64, None) => ints.iter().map(|&i| i as f32).collect(),
+        (DOUBLE, None) => doubles.iter().map(|&d| d as f32).collect(),
+        (t, _) => return Err(format!("unsupported ONNX tensor type {t} in {name:?}")),
+    };
+    let dims: Vec<usize> = dims.iter().map(|&d| d.max(0) as usize).collect();
+    if elements(&dims)? != data.len() {
+        return Err(format!(
+            "ONNX tensor {name:?} of shape {dims:?} holds {} values",
+            data.len()
+        ));
+    }
+    Ok((name, Tensor { dims, data }))
+}
+
+/// A `ValueInfoProto`: its name and tensor shape, symbolic dimensions as 1
+fn parse_value_info(bytes: &[u8]) -> Result<(String, Vec<usize>), String> {
+    let (mut name, mut dims) = (String::new(), Vec::new());
+    for field in fields(bytes) {
+        match field? {
+            (1, Value::Bytes(n)) => name = string(n)?,
+            (2, Value::Bytes(ty)) => {
+                // TypeProto.tensor_type -> .shape -> .dim
+                let shape = nested(ty, 1)?.map(|t| nested(t, 2)).transpose()?.flatten();
+                for field in fields(shape.unwrap_or_default()) {
+                    if let (1, Value::Bytes(dim)) = field? {
+                        let mut value = 1;
+                        for field in fields(dim) {
+                            if let (1, Value::Varint(v)) = field? {
+                                value = (v as usize).max(1);
+                            }
+                        }
+                        dims.push(value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok((name, dims))
+}
+
+/// A `NodeProto` as the operator it runs
+fn parse_node(bytes: &[u8]) -> Result<Node, String> {
+    let (mut inputs, mut outputs, mut op_type) = (Vec::new(), Vec::new(), String::new());
+    let mut attributes = HashMap::new();
+    for field in fields(bytes) {
+        match field? {
+            (1, Value::Bytes(i)) => inputs.push(string(i)?),
+            (2, Value::Bytes(o)) => outputs.push(string(o)?),
+            (4, Value::Bytes(t)) => op_type = string(t)?,
+            (5, Value::Bytes(a)) => {
+                let (name, attribute) = parse_attribute(a)?;
+                attributes.insert(name, attribute);
+            }
+            _ => {}
+        }
+    }
+    let float = |name: &str, default: f32| match attributes.get(name) {
+        Some(Attribute::Float(f)) => *f,
+        _ => default,
+    };
+    let int = |name: &str, default: i64| match attributes.get(name) {
+        Some(Attribute::Int(i)) => *i,
+        _ => default,
+    };
+    let op = match op_type.as_str() {
+        "Gemm" => Op::Gemm {
+            alpha: float("alpha", 1.0),
+            beta: float("beta", 1.0),
+            trans_a: int("transA", 0) != 0,
+            trans_b: int("transB", 0) != 0,
+        },
+        "MatMul" => Op::MatMul,
+        "Add" => Op::Add,
+        "Sub" => Op::Sub,
+        "Mul" => Op::Mul,
+        "Div" => Op::Div,
+        "Relu" => Op::Relu,
+        "LeakyRelu" => Op::LeakyRelu(float("alpha", 0.01)),
+        "Tanh" => Op::Tanh,
+        "Sigmoid" => Op::Sigmoid,
+        "Softmax" => Op::Softmax(int("axis", -1)),
+        "LogSoftmax" => Op::LogSoftmax(int("axis", -1)),
+        "Flatten" => Op::Flatten(int("axis", 1)),
+        "Reshape" => Op::Reshape,
+        "Identity" | "Dropout" => Op::Identity,
+        "Constant" => match attributes.remove("value") {
+            Some(Attribute::Tensor(t)) => Op::Constant(t),
+            _ => return Err("ONNX Constant without a tensor value".to_string()),
+        },
+        other => return Err(format!("unsupported ONNX operator {other}")),
+    };
+    let output = outputs
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("ONNX {op_type} node without output"))?;
+    Ok(Node { op, inputs, output })
+}
+
+enum Attribute {
+    Float(f32),
+    Int(i64),
+    Tensor(Tensor),
+    Other,
+}
+
+fn parse_attribute(bytes: &[u8]) -> Result<(String, Attribute), String> {
+    let (mut name, mut attribute) = (String::new(), Attribute::Other);
+    for field in fields(bytes) {
+        match field? {
+            (1, Value::Bytes(n)) => name = string(n)?,
+            (2, Value::Fixed32(f)) => attribute = Attribute::Float(f32::from_bits(f)),
+            (3, Value::Varint(i)) => attribute = Attribute::Int(i as i64),
+            (5, Value::Bytes(t)) => attribute = Attribute::Tensor(parse_tensor(t)?.1),
+            _ => {}
+        }
+    }
+    Ok((name, attribute))
+}
@@ -0,0 +1,142 @@
+//! Compact byte form of boards, for storing many states.
+//!
+//! Cells are stored in row-major order, one byte or one nibble each:
+//! * 16 bytes: `0` empty, `k` for the number `2^k` (`1..=30`),
+//!   `0x80 | k` for the multiplier `-2^k`, `0xFF` for a blocker and `0xFE`
+//!   for a bomb, so every tile fits
+//! * 8 bytes, high nibble first: `0` empty, `1..=12` for `2` to `4096` and
+//!   `13`, `14`, `15` for the multipliers `-1`, `-2`, `-4`; no blockers
+//!   or bombs
+//!
+//! [`board_key`] reads the 8-byte form as one integer.
+
+use crate::{BLOCKER, BOMB, Board};
+
+/// Pack `board` into `size` bytes (`8` or `16`).
+///
+/// ```
+/// use akioi_2048_core::pack::{pack, unpack};
+///
+/// let board = [[0, 2, 4, 8], [-1, -2, -4, 0], [0; 4], [4096, 0, 0, 2]];
+/// for size in [8, 16] {
+///     let bytes = pack(&board, size).unwrap();
+///     assert_eq!(bytes.len(), size);
+///     assert_eq!(unpack(&bytes).unwrap(), board);
+/// }
+/// assert!(pack(&[[8192, 0, 0, 0], [0; 4], [0; 4], [0; 4]], 8).is_err());
+/// ```
+///
+/// # Errors
+/// Returns an error for another size, or a tile the size cannot hold:
+/// anything but a power of two, its negation, a blocker or a bomb, and in 8 bytes
+/// anything above 4096 or below -4.
+pub fn pack(board: &Board, size: usize) -> Result<Vec<u8>, String> {
+    let tiles = board.iter().flatten().copied();
+    match size {
+        16 => tiles.map(byte_code).collect(),
+        8 => {
+            let nibbles = tiles
+                .map(nibble_code)
+                .collect::<Result<Vec<u8>, String>>()?;
+            Ok(nibbles
+                .chunks(2)
+                .map(|pair| (pair[0] << 4) | pair[1])
+                .collect())
+        }
+        _ => Err(format!("packed size must be 8 or 16 bytes, got {size}")),
+    }
+}
+
+/// Unpack a board from the 8 or 16 bytes [`pack`] made.
+///
+/// # Errors
+/// Returns an error for another length or a byte no tile packs to.
+pub fn unpack(bytes: &[u8]) -> Result<Board, String> {
+    let codes: Vec<u8> = match bytes.len() {
+        16 => bytes.to_vec(),
+        8 => bytes
+            .iter()
+            .flat_map(|&b| [b >> 4, b & 0xF])
+            .map(|n| match n {
+                13..=15 => 0x80 | (n - 13),
+                n => n,
+            })
+            .collect(),
+        n => return Err(format!("packed boards are 8 or 16 bytes, got {n}")),
+    };
+    let mut board = [[0; 4]; 4];
+    for (i, &code) in codes.iter().enumerate() {
+        board[i / 4][i % 4] = match code {
+            0 => 0,
+            1..=30 => 1 << code,
+            0x80..=0x9E => -(1 << (code & 0x7F)),
+            0xFE => BOMB,
+            0xFF => BLOCKER,
+            _ => return Err(format!("no tile packs to byte {code:#04x}")),
+        };
+    }
+    Ok(board)
+}
+
+/// The 8-byte packing of `board` as one big-endian integer, so the first
+/// cell is the top nibble: a single `u64` to key caches and databases on.
+///
+/// The key only covers boards of numbers up to 4096 and the multipliers
+/// `-1`, `-2` and `-4`: games past a 4096 tile, with larger multipliers,
+/// blockers, bombs or another variant's tiles need the 16-byte [`pack`].
+///
+/// ```
+/// use akioi_2048_core::pack::{board_from_key, board_key};
+///
+/// let board = [[2, 0, 0, 0], [0; 4], [0; 4], [0, 0, 0, -1]];
+/// assert_eq!(board_key(&board), Ok(0x1000_0000_0000_000d));
+/// assert_eq!(board_from_key(0x1000_0000_0000_000d), board);
+/// ```
+///
+/// # Errors
+/// Returns an error naming the first tile the key cannot hold.
+pub fn board_key(board: &Board) -> Result<u64, String> {
+    let bytes = pack(board, 8).map_err(|_| {
+        let tile = board.iter().flatten().find(|&&t| nibble_code(t).is_err());
+        format!(
+            "board_key holds numbers up to 4096 and the multipliers -1, -2 and -4, not tile {}",
+            tile.expect("packing fails on some tile")
+        )
+    })?;
+    Ok(u64::from_be_bytes(
+        bytes.try_into().expect("8-byte packing is 8 bytes"),
+    ))
+}
+
+/// Inverse of [`board_key`]; every key is some board
+#[must_use]
+pub fn board_from_key(key: u64) -> Board {
+    unpack(&key.to_be_bytes()).expect("every nibble packs a tile")
+}
+
+fn byte_code(tile: i32) -> Result<u8, String> {
+    match tile {
+        0 => return Ok(0),
+        BLOCKER => return Ok(0xFF),
+        BOMB => return Ok(0xFE),
+        _ => {}
+    }
+    let magnitude = tile.unsigned_abs();
+    if !magnitude.is_power_of_two() || tile == 1 || magnitude > 1 << 30 {
+        return Err(format!(
+            "cannot pack tile {tile}: not a power of two up to 2^30"
+        ));
+    }
+    let k = magnitude.trailing_zeros() as u8;
+    Ok(if tile > 0 { k } else { 0x80 | k })
+}
+
+fn nibble_code(tile: i32) -> Result<u8, String> {
+    match tile {
+        0..=4096 => byte_code(tile),
+        -1 => Ok(13),
+        -2 => Ok(14),
+        -4 => Ok(15),
+        _ => Err(format!("cannot pack tile {tile} into 8 bytes")),
+    }
+}
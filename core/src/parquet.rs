@@ -0,0 +1,115 @@
+//! Parquet files of the [`arrow`](crate::arrow) tables, for
+//! `pandas.read_parquet`, `polars.read_parquet` or any other Parquet
+//! reader; built only with the `parquet` feature.
+//!
+//! Every table is written as one uncompressed row group of required
+//! columns. `UInt64` columns are 64-bit integers annotated as unsigned.
+
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use ::parquet::basic::{IntType, LogicalType, Repetition, Type as Physical};
+use ::parquet::column::writer::ColumnWriter;
+use ::parquet::data_type::ByteArray;
+use ::parquet::errors::ParquetError;
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::writer::SerializedFileWriter;
+use ::parquet::schema::types::Type;
+
+use crate::arrow::{Column, Table};
+
+/// Write `table` as a Parquet file.
+///
+/// ```
+/// use akioi_2048_core::arrow::{Column, Table};
+/// use akioi_2048_core::parquet::write;
+///
+/// let table = Table::new()
+///     .column("game", Column::Int32(vec![0, 1]))
+///     .column("won", Column::Bool(vec![false, true]));
+/// let mut file = Vec::new();
+/// write(&mut file, &table).unwrap();
+/// assert!(file.starts_with(b"PAR1") && file.ends_with(b"PAR1"));
+/// ```
+///
+/// # Errors
+/// Returns an error if the columns differ in length or writing fails.
+pub fn write<W: Write + Send>(out: W, table: &Table) -> io::Result<()> {
+    let rows = table.rows();
+    if let Some((name, _)) = table.columns.iter().find(|(_, c)| c.len() != rows) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("column {name} has a different length than the others"),
+        ));
+    }
+    write_columns(out, table).map_err(io::Error::other)
+}
+
+fn write_columns<W: Write + Send>(out: W, table: &Table) -> Result<(), ParquetError> {
+    let fields = table
+        .columns
+        .iter()
+        .map(|(name, column)| field(name, column).map(Arc::new))
+        .collect::<Result<Vec<_>, _>>()?;
+    let schema = Type::group_type_builder("schema")
+        .with_fields(fields)
+        .build()?;
+    let properties = WriterProperties::builder().build();
+    let mut file = SerializedFileWriter::new(out, Arc::new(schema), Arc::new(properties))?;
+    let mut group = file.next_row_group()?;
+    for (_, column) in &table.columns {
+        let Some(mut writer) = group.next_column()? else {
+            unreachable!("the schema has a field per column");
+        };
+        match (writer.untyped(), column) {
+            (ColumnWriter::Int32ColumnWriter(w), Column::Int32(v)) => {
+                w.write_batch(v, None, None)?;
+            }
+            (ColumnWriter::Int64ColumnWriter(w), Column::Int64(v)) => {
+                w.write_batch(v, None, None)?;
+            }
+            (ColumnWriter::Int64ColumnWriter(w), Column::UInt64(v)) => {
+                // stored as the same 64 bits, read back as unsigned
+                let bits: Vec<i64> = v.iter().map(|&x| x.cast_signed()).collect();
+                w.write_batch(&bits, None, None)?;
+            }
+            (ColumnWriter::DoubleColumnWriter(w), Column::Float64(v)) => {
+                w.write_batch(v, None, None)?;
+            }
+            (ColumnWriter::BoolColumnWriter(w), Column::Bool(v)) => {
+                w.write_batch(v, None, None)?;
+            }
+            (ColumnWriter::ByteArrayColumnWriter(w), Column::Utf8(v)) => {
+                let strings: Vec<ByteArray> = v.iter().map(|s| s.as_str().into()).collect();
+                w.write_batch(&strings, None, None)?;
+            }
+            _ => unreachable!("every field has the physical type of its column"),
+        }
+        writer.close()?;
+    }
+    group.close()?;
+    file.close()?;
+    Ok(())
+}
+
+/// The schema field of a required column
+fn field(name: &str, column: &Column) -> Result<Type, ParquetError> {
+    let (physical, logical) = match column {
+        Column::Int32(_) => (Physical::INT32, None),
+        Column::Int64(_) => (Physical::INT64, None),
+        Column::UInt64(_) => (
+            Physical::INT64,
+            Some(LogicalType::Integer(IntType {
+                bit_width: 64,
+                is_signed: false,
+            })),
+        ),
+        Column::Float64(_) => (Physical::DOUBLE, None),
+        Column::Bool(_) => (Physical::BOOLEAN, None),
+        Column::Utf8(_) => (Physical::BYTE_ARRAY, Some(LogicalType::String)),
+    };
+    Type::primitive_type_builder(name, physical)
+        .with_repetition(Repetition::REQUIRED)
+        .with_logical_type(logical)
+        .build()
+}
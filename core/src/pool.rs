@@ -0,0 +1,94 @@
+//! Worker threads kept alive between batches, on rayon, so stepping many
+//! games in small batches does not pay for spawning threads on every call.
+//!
+//! [`Pool::map`] splits a slice into contiguous chunks and runs them on the
+//! pool's threads, at most one per core, waiting for all of them before it
+//! returns, so the work may borrow from the caller.
+
+use std::sync::OnceLock;
+
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+/// Items a thread takes at least; smaller chunks cost more to hand out
+/// than they save
+const MIN_CHUNK: usize = 256;
+
+/// Worker threads running the chunks of a batch
+pub(crate) struct Pool {
+    /// `None` for a single thread, or where workers cannot be started:
+    /// every chunk then runs on the calling thread
+    workers: Option<ThreadPool>,
+}
+
+impl Pool {
+    /// A pool of `threads` workers, capped at one per core
+    pub(crate) fn new(threads: usize) -> Pool {
+        let threads = threads.min(cores());
+        let workers = (threads > 1)
+            .then(|| {
+                ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .thread_name(|_| "akioi-2048-worker".to_string())
+                    .build()
+                    .ok()
+            })
+            .flatten();
+        Pool { workers }
+    }
+
+    /// The pool the free batch functions share, one worker per core
+    pub(crate) fn shared() -> &'static Pool {
+        static SHARED: OnceLock<Pool> = OnceLock::new();
+        SHARED.get_or_init(|| Pool::new(cores()))
+    }
+
+    /// Threads this pool runs chunks on
+    pub(crate) fn threads(&self) -> usize {
+        self.workers
+            .as_ref()
+            .map_or(1, ThreadPool::current_num_threads)
+    }
+
+    /// `f(i, &mut items[i])` for every item, in order, split into contiguous
+    /// chunks over up to `threads` of the pool's threads.
+    ///
+    /// A panic in `f` is raised again here once every chunk has finished.
+    pub(crate) fn map<T: Send, U: Send>(
+        &self,
+        items: &mut [T],
+        threads: usize,
+        f: impl Fn(usize, &mut T) -> U + Sync,
+    ) -> Vec<U> {
+        let threads = threads
+            .min(self.threads())
+            .clamp(1, items.len().div_ceil(MIN_CHUNK).max(1));
+        let size = items.len().div_ceil(threads).max(1);
+        let run = |c: usize, chunk: &mut [T]| -> Vec<U> {
+            let first = c * size;
+            chunk
+                .iter_mut()
+                .enumerate()
+                .map(|(i, item)| f(first + i, item))
+                .collect()
+        };
+        match &self.workers {
+            Some(workers) if threads > 1 => {
+                let chunks: Vec<Vec<U>> = workers.install(|| {
+                    items
+                        .par_chunks_mut(size)
+                        .enumerate()
+                        .map(|(c, chunk)| run(c, chunk))
+                        .collect()
+                });
+                chunks.into_iter().flatten().collect()
+            }
+            _ => run(0, items),
+        }
+    }
+}
+
+/// Threads the machine runs at once
+fn cores() -> usize {
+    std::thread::available_parallelism().map_or(1, usize::from)
+}
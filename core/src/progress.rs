@@ -0,0 +1,78 @@
+//! Progress reporting and cancellation for long native loops.
+//!
+//! Long operations take a [`Progress`] they advance as they go and check
+//! between units of work; another thread reads it to report progress and
+//! cancels it to stop the operation early.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Error message of an operation stopped by [`Progress::cancel`]
+pub const CANCELLED: &str = "cancelled";
+
+/// Work done out of the total, and whether to stop
+///
+/// ```
+/// use akioi_2048_core::progress::{CANCELLED, Progress};
+///
+/// let progress = Progress::new();
+/// progress.set_total(10);
+/// progress.advance(3);
+/// assert_eq!((progress.done(), progress.total()), (3, 10));
+/// assert_eq!(progress.check(), Ok(()));
+/// progress.cancel();
+/// assert_eq!(progress.check(), Err(CANCELLED.to_string()));
+/// ```
+#[derive(Debug, Default)]
+pub struct Progress {
+    done: AtomicUsize,
+    total: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl Progress {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Units of work the operation expects to do
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, units: usize) {
+        self.done.fetch_add(units, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn done(&self) -> usize {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    /// Ask the operation to stop at its next check
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// `Err(CANCELLED)` once cancelled
+    ///
+    /// # Errors
+    /// Returns [`CANCELLED`] if [`Progress::cancel`] was called.
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Hash-chained move records and signed replays for verifiable server play.
+//!
+//! Every accepted move yields a [`MoveRecord`] whose `chain` hash commits to
+//! the previous record, so a game's records form a hash chain: altering,
+//! dropping or reordering any move breaks every later link. A finished game
+//! can also be signed as a whole with [`sign_replay`].
+
+use sha2::{Digest, Sha256};
+
+use crate::dataset::action_index;
+use crate::replay::Replay;
+use crate::session::Game;
+use crate::{Board, Direction, Rules};
+
+/// SHA-256 digest
+pub type Hash = [u8; 32];
+
+/// One accepted move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveRecord {
+    /// Position of the record in the chain (0-based)
+    pub index: u64,
+    pub pre_hash: Hash,
+    pub direction: Direction,
+    pub post_hash: Hash,
+    /// Index of the spawn draw used after this move (the initial tiles are 0 and 1)
+    pub draw_index: u64,
+    /// Hash over the previous record's `chain` and this record's fields
+    pub chain: Hash,
+}
+
+/// Records of one game
+#[derive(Clone, Default)]
+pub struct ProofChain {
+    records: Vec<MoveRecord>,
+}
+
+impl ProofChain {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a record for a move from `pre` to `post`
+    pub fn push(&mut self, pre: &Board, direction: Direction, post: &Board, draw_index: u64) {
+        let prev = self.records.last().map_or([0; 32], |r| r.chain);
+        let index = self.records.len() as u64;
+        let pre_hash = board_hash(pre);
+        let post_hash = board_hash(post);
+        self.records.push(MoveRecord {
+            index,
+            pre_hash,
+            direction,
+            post_hash,
+            draw_index,
+            chain: link(&prev, index, &pre_hash, direction, &post_hash, draw_index),
+        });
+    }
+
+    #[must_use]
+    pub fn records(&self) -> &[MoveRecord] {
+        &self.records
+    }
+}
+
+/// SHA-256 over the 16 cells as little-endian `i32` in row-major order
+#[must_use]
+pub fn board_hash(board: &Board) -> Hash {
+    let mut hasher = Sha256::new();
+    for &cell in board.iter().flatten() {
+        hasher.update(cell.to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+fn link(
+    prev: &Hash,
+    index: u64,
+    pre_hash: &Hash,
+    direction: Direction,
+    post_hash: &Hash,
+    draw_index: u64,
+) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(prev);
+    hasher.update(index.to_le_bytes());
+    hasher.update(pre_hash);
+    hasher.update([action_index(direction)]);
+    hasher.update(post_hash);
+    hasher.update(draw_index.to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Check the internal consistency of a chain: consecutive indices, each
+/// move starting where the previous one ended, one spawn draw per move and
+/// every `chain` hash matching its record.
+///
+/// # Errors
+/// Returns a description of the first broken record.
+pub fn verify_chain(records: &[MoveRecord]) -> Result<(), String> {
+    let mut prev: Option<&MoveRecord> = None;
+    for (i, r) in records.iter().enumerate() {
+        let fail = |why: &str| Err(format!("record {i}: {why}"));
+        if r.index != i as u64 {
+            return fail("index out of sequence");
+        }
+        if let Some(p) = prev {
+            if r.pre_hash != p.post_hash {
+                return fail("pre-state does not match previous post-state");
+            }
+            if r.draw_index != p.draw_index + 1 {
+                return fail("spawn draw index out of sequence");
+            }
+        }
+        let expected = link(
+            &prev.map_or([0; 32], |p| p.chain),
+            r.index,
+            &r.pre_hash,
+            r.direction,
+            &r.post_hash,
+            r.draw_index,
+        );
+        if r.chain != expected {
+            return fail("chain hash mismatch");
+        }
+        prev = Some(r);
+    }
+    Ok(())
+}
+
+/// [`verify_chain`], then replay the moves from `seed` under `rules` and
+/// check that the engine produces exactly these records.
+///
+/// # Errors
+/// Returns a description of the first broken or illegal record.
+pub fn verify_chain_with_seed(
+    records: &[MoveRecord],
+    seed: u64,
+    rules: Rules,
+) -> Result<(), String> {
+    verify_chain(records)?;
+    let mut game = Game::with_rules(seed, rules)?.with_proofs();
+    for (i, r) in records.iter().enumerate() {
+        game.step(r.direction);
+        if game.records().get(i) != Some(r) {
+            return Err(format!("record {i}: not reproduced by the engine"));
+        }
+    }
+    Ok(())
+}
+
+/// HMAC-SHA256 under `key` over the replay's
+/// [canonical text](Replay::canonical_text) followed by a `score <n>` line
+/// with its final score, so a server holding `key` can vouch for a game and
+/// the score it reached.
+///
+/// ```
+/// use akioi_2048_core::proof::{sign_replay, verify_replay};
+/// use akioi_2048_core::{Direction, Game};
+///
+/// let mut game = Game::new(3);
+/// game.step(Direction::Down);
+/// let replay = game.replay();
+/// let signature = sign_replay(&replay, b"server key").unwrap();
+/// assert!(verify_replay(&replay, &signature, b"server key"));
+/// assert!(!verify_replay(&replay, &signature, b"another key"));
+/// ```
+///
+/// # Errors
+/// Returns an error if the replay does not play back.
+pub fn sign_replay(replay: &Replay, key: &[u8]) -> Result<Hash, String> {
+    let score = replay.game()?.score();
+    Ok(hmac(
+        key,
+        format!("{}score {score}\n", replay.canonical_text()).as_bytes(),
+    ))
+}
+
+/// Whether `signature` is [`sign_replay`] of `replay` under `key`; the
+/// moves are played back, so a replay with an illegal move never verifies.
+#[must_use]
+pub fn verify_replay(replay: &Replay, signature: &Hash, key: &[u8]) -> bool {
+    sign_replay(replay, key).is_ok_and(|expected| {
+        // compare every byte, so the time taken does not reveal the prefix
+        expected
+            .iter()
+            .zip(signature)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+    })
+}
+
+/// HMAC-SHA256 (RFC 2104)
+fn hmac(key: &[u8], message: &[u8]) -> Hash {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| block.map(|k| k ^ byte);
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
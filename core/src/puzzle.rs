@@ -0,0 +1,130 @@
+//! Puzzles: a fixed starting board, a goal and a move budget.
+
+use crate::{BLOCKER, BOMB, Board, Direction, Game, Rules};
+
+/// What a [`Puzzle`] asks for; every condition that is set must hold at
+/// once
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Goal {
+    /// Some tile at least this large (`2`, `4`, … `65536`)
+    pub tile: Option<i32>,
+    /// A score of at least this much, counted from the starting position
+    pub score: Option<i64>,
+    /// No multiplier tile left on the board
+    pub clear_multipliers: bool,
+}
+
+impl Goal {
+    /// Whether a game at `board` with `score` meets the goal
+    #[must_use]
+    pub fn is_met(&self, board: &Board, score: i64) -> bool {
+        let tiles = || board.iter().flatten().copied();
+        self.tile.is_none_or(|goal| tiles().any(|t| t >= goal))
+            && self.score.is_none_or(|goal| score >= goal)
+            && (!self.clear_multipliers || tiles().all(|t| t >= 0 || t == BLOCKER || t == BOMB))
+    }
+}
+
+/// How a [`Puzzle::check`] ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The goal was met within the budget
+    Solved,
+    /// The budget was used up first
+    OutOfMoves,
+    /// No move was left first
+    GameOver,
+    /// The moves ran out with budget to spare and the goal not met
+    InProgress,
+}
+
+/// Verdict of a [`Puzzle::check`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PuzzleResult {
+    pub outcome: Outcome,
+    /// Moves played up to the verdict, invalid ones included
+    pub moves: usize,
+    /// Position at the verdict
+    pub board: Board,
+    pub score: i64,
+}
+
+/// A constructed position to solve in at most `max_moves` moves.
+///
+/// Spawns derive from `seed`, so everyone playing the same puzzle sees the
+/// same tiles for the same moves.
+///
+/// ```
+/// use akioi_2048_core::puzzle::{Goal, Outcome, Puzzle};
+/// use akioi_2048_core::{Direction, Rules};
+///
+/// let puzzle = Puzzle {
+///     board: [[0; 4], [0; 4], [0; 4], [0, 0, 2, 2]],
+///     goal: Goal { tile: Some(4), ..Goal::default() },
+///     max_moves: 1,
+///     seed: 0,
+///     rules: Rules::AKIOI,
+/// };
+/// let result = puzzle.check(&[Direction::Left]).unwrap();
+/// assert_eq!(result.outcome, Outcome::Solved);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Puzzle {
+    pub board: Board,
+    pub goal: Goal,
+    pub max_moves: usize,
+    pub seed: u64,
+    pub rules: Rules,
+}
+
+impl Puzzle {
+    /// A game at the starting position
+    ///
+    /// # Errors
+    /// Returns an error if the rules are inconsistent or the board holds
+    /// tiles invalid under them.
+    pub fn game(&self) -> Result<Game, String> {
+        Game::builder()
+            .seed(self.seed)
+            .rules(self.rules)
+            .start(self.board, 0)
+            .build()
+    }
+
+    /// Play `moves` from the starting position until the goal is met, the
+    /// budget is used up or the game is over; later moves are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the puzzle has no goal or [`game`](Self::game)
+    /// fails.
+    pub fn check(&self, moves: &[Direction]) -> Result<PuzzleResult, String> {
+        if self.goal == Goal::default() {
+            return Err("puzzle has no goal".to_string());
+        }
+        let mut game = self.game()?;
+        let mut moves = moves.iter();
+        let outcome = loop {
+            if self.goal.is_met(&game.board(), game.score()) {
+                break Outcome::Solved;
+            }
+            if !game.status().can_continue {
+                break Outcome::GameOver;
+            }
+            if game.moves() >= self.max_moves {
+                break Outcome::OutOfMoves;
+            }
+            match moves.next() {
+                Some(&dir) => {
+                    game.step(dir);
+                }
+                None => break Outcome::InProgress,
+            }
+        };
+        Ok(PuzzleResult {
+            outcome,
+            moves: game.moves(),
+            board: game.board(),
+            score: game.score(),
+        })
+    }
+}
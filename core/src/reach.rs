@@ -0,0 +1,193 @@
+//! Reachable positions: every board a number of moves can lead to, each move
+//! followed by every spawn it can give, with duplicates merged.
+//!
+//! Useful to measure how much the multipliers widen the game tree compared
+//! with classic 2048, where only 2 and 4 spawn.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use crate::{Board, Engine, Rules, spawn_probabilities};
+
+/// Every board the spawns after a move on `slid` can give, with its
+/// probability: [`Rules::spawns`] tiles, as after the first move of a game
+pub(crate) fn spawn_outcomes(slid: &Board, rules: &Rules) -> Vec<(Board, f64)> {
+    spawn_outcomes_of(slid, rules, usize::from(rules.spawns))
+}
+
+/// Every board `tiles` spawns one after another on `slid` can give, with
+/// its probability, boards reached in several orders merged; `slid` itself
+/// for no tiles or a full board
+pub(crate) fn spawn_outcomes_of(slid: &Board, rules: &Rules, tiles: usize) -> Vec<(Board, f64)> {
+    if tiles == 1 {
+        // one spawn never reaches a board twice
+        let spawned = single_spawn_outcomes(slid, rules);
+        if !spawned.is_empty() {
+            return spawned;
+        }
+    }
+    let mut outcomes = vec![(*slid, 1.0)];
+    for _ in 0..tiles {
+        let mut next: Vec<(Board, f64)> = Vec::new();
+        let mut index: HashMap<Board, usize> = HashMap::new();
+        for (board, p) in outcomes {
+            let spawned = single_spawn_outcomes(&board, rules);
+            if spawned.is_empty() {
+                next.push((board, p));
+                continue;
+            }
+            for (child, chance) in spawned {
+                match index.entry(child) {
+                    Entry::Occupied(i) => next[*i.get()].1 += p * chance,
+                    Entry::Vacant(v) => {
+                        v.insert(next.len());
+                        next.push((child, p * chance));
+                    }
+                }
+            }
+        }
+        outcomes = next;
+    }
+    outcomes
+}
+
+/// Every board one spawn on `slid` can give, with its probability; none on
+/// a full board
+fn single_spawn_outcomes(slid: &Board, rules: &Rules) -> Vec<(Board, f64)> {
+    let empty = rules.placement.cells(slid);
+    let odds = spawn_probabilities(rules, slid);
+    let per_cell = 1.0 / empty.len() as f64;
+    let mut out = Vec::with_capacity(empty.len() * odds.len());
+    for &(r, c) in &empty {
+        for &(tile, chance) in &odds {
+            if chance > 0.0 {
+                let mut child = *slid;
+                child[r][c] = tile;
+                out.push((child, chance * per_cell));
+            }
+        }
+    }
+    out
+}
+
+/// Distinct boards one move and its spawns away from some board of
+/// `layer`, `moves` moves after the root
+fn next_layer(
+    layer: &HashSet<Board>,
+    engine: &Engine,
+    numbers_only: bool,
+    moves: u64,
+) -> HashSet<Board> {
+    let rules = engine.rules();
+    let tiles = rules.tiles_after(moves);
+    let mut next = HashSet::new();
+    for board in layer {
+        for dir in engine.legal_moves(board) {
+            let (slid, _) = engine.slide(board, dir);
+            if numbers_only {
+                let mut spawned = HashSet::from([slid]);
+                for _ in 0..tiles {
+                    spawned = spawned
+                        .iter()
+                        .flat_map(|board| {
+                            let cells = rules.placement.cells(board);
+                            let full = cells.is_empty().then_some(*board);
+                            cells
+                                .into_iter()
+                                .flat_map(move |(r, c)| {
+                                    [2, 4].map(|tile| {
+                                        let mut child = *board;
+                                        child[r][c] = tile;
+                                        child
+                                    })
+                                })
+                                .chain(full)
+                        })
+                        .collect();
+                }
+                next.extend(spawned);
+            } else {
+                next.extend(
+                    spawn_outcomes_of(&slid, &rules, tiles)
+                        .into_iter()
+                        .map(|(b, _)| b),
+                );
+            }
+        }
+    }
+    next
+}
+
+fn layers(
+    board: &Board,
+    rules: &Rules,
+    depth: usize,
+    numbers_only: bool,
+) -> Result<impl Iterator<Item = HashSet<Board>>, String> {
+    let engine = Engine::new(*rules)?;
+    crate::validate_board_with(board, rules)?;
+    let root = HashSet::from([*board]);
+    let mut moves = 0;
+    Ok(std::iter::successors(Some(root), move |layer| {
+        let next = next_layer(layer, &engine, numbers_only, moves);
+        moves += 1;
+        Some(next)
+    })
+    .take(depth + 1))
+}
+
+/// The distinct boards exactly `depth` moves from `board`, in sorted order.
+///
+/// `numbers_only` spawns only 2 and 4, as in classic 2048. Under
+/// [`Rules::spawn_every`] the first move spawns, as in a new game.
+///
+/// ```
+/// use akioi_2048_core::reach::enumerate_successors;
+/// use akioi_2048_core::Rules;
+///
+/// let board = [[0; 4], [0; 4], [0; 4], [2, 0, 0, 0]];
+/// let next = enumerate_successors(&board, &Rules::AKIOI, 1, false).unwrap();
+/// // Right and Up, 15 empty cells and four tiles that spawn; a 2 in both
+/// // corners is reached either way
+/// assert_eq!(next.len(), 2 * 15 * 4 - 1);
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules or an invalid board.
+pub fn enumerate_successors(
+    board: &Board,
+    rules: &Rules,
+    depth: usize,
+    numbers_only: bool,
+) -> Result<Vec<Board>, String> {
+    let last = layers(board, rules, depth, numbers_only)?
+        .last()
+        .expect("holds the root");
+    let mut boards: Vec<Board> = last.into_iter().collect();
+    boards.sort_unstable();
+    Ok(boards)
+}
+
+/// Number of distinct boards exactly `0, 1, …, depth` moves from `board`.
+///
+/// ```
+/// use akioi_2048_core::reach::count_reachable_states;
+/// use akioi_2048_core::Rules;
+///
+/// let board = [[0; 4], [0; 4], [0; 4], [2, 0, 0, 0]];
+/// let counts = count_reachable_states(&board, &Rules::AKIOI, 1, true).unwrap();
+/// assert_eq!(counts, [1, 2 * 15 * 2 - 1]);
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules or an invalid board.
+pub fn count_reachable_states(
+    board: &Board,
+    rules: &Rules,
+    depth: usize,
+    numbers_only: bool,
+) -> Result<Vec<usize>, String> {
+    Ok(layers(board, rules, depth, numbers_only)?
+        .map(|layer| layer.len())
+        .collect())
+}
@@ -0,0 +1,280 @@
+//! A slow, straightforward model of one move, kept apart from the engine.
+//!
+//! It walks each line from the wall outwards and spells the merge rules
+//! out one by one, so it can check the optimized engine (and any other
+//! implementation) against the rules rather than against itself.
+
+use crate::detail::spawns_between;
+use crate::{
+    BLOCKER, BOMB, Board, Direction, Overflow, Rules, Scoring, Variant, validate_board_with,
+};
+
+/// Cells of line `k` for `direction`, starting at the wall the tiles move to
+fn line_cells(direction: Direction, k: usize) -> [(usize, usize); 4] {
+    match direction {
+        Direction::Left => [(k, 0), (k, 1), (k, 2), (k, 3)],
+        Direction::Right => [(k, 3), (k, 2), (k, 1), (k, 0)],
+        Direction::Up => [(0, k), (1, k), (2, k), (3, k)],
+        Direction::Down => [(3, k), (2, k), (1, k), (0, k)],
+    }
+}
+
+/// Slide `board` toward `direction` without spawning, returning the new
+/// board and the score of the merges.
+///
+/// ```
+/// use akioi_2048_core::reference::slide;
+/// use akioi_2048_core::{Direction, Rules};
+///
+/// let board = [[2, 2, -2, 4], [0; 4], [0; 4], [0; 4]];
+/// let (next, score) = slide(&board, Direction::Left, &Rules::AKIOI);
+/// assert_eq!(next[0], [4, 8, 0, 0]);
+/// assert_eq!(score, 12);
+///
+/// let walled = [[0, 2, -3, 2], [0; 4], [0; 4], [0; 4]];
+/// assert_eq!(slide(&walled, Direction::Left, &Rules::AKIOI).0[0], [2, 0, -3, 2]);
+/// ```
+#[must_use]
+pub fn slide(board: &Board, direction: Direction, rules: &Rules) -> (Board, i32) {
+    let mut next = [[0; 4]; 4];
+    let mut score = 0;
+    for k in 0..4 {
+        let cells = line_cells(direction, k);
+        let (line, add) = slide_line(cells.map(|(r, c)| board[r][c]), rules);
+        for ((r, c), tile) in cells.into_iter().zip(line) {
+            next[r][c] = tile;
+        }
+        score += add;
+    }
+    if rules.scoring == Scoring::Clamp {
+        score = score.max(0);
+    }
+    (next, score)
+}
+
+/// Slide one line toward index 0.
+///
+/// Tiles are taken from the wall outwards; each one merges with the next
+/// tile behind it if [`merge`] allows, and a merged tile never merges
+/// again in the same move. A [`BLOCKER`] keeps its cell and acts as the
+/// wall of the tiles behind it. Under [`Rules::bombs`], a pair with a
+/// [`BOMB`] clears every tile of the line but the blockers, for no score.
+#[must_use]
+pub fn slide_line(line: [i32; 4], rules: &Rules) -> ([i32; 4], i32) {
+    let mut out = [0; 4];
+    let mut score = 0;
+    let mut wall = 0;
+    for end in (0..4).filter(|&i| line[i] == BLOCKER).chain([4]) {
+        match slide_run(&line[wall..end], rules, &mut out[wall..end]) {
+            Some(add) => score += add,
+            None => return (line.map(|t| if t == BLOCKER { t } else { 0 }), 0),
+        }
+        if end < 4 {
+            out[end] = BLOCKER;
+        }
+        wall = end + 1;
+    }
+    (out, score)
+}
+
+/// Slide the blocker-free `run` toward its index 0 into `out`, returning
+/// the score, or `None` if a bomb goes off.
+fn slide_run(run: &[i32], rules: &Rules, out: &mut [i32]) -> Option<i32> {
+    let tiles: Vec<(usize, i32)> = (0..run.len())
+        .filter(|&i| run[i] != 0)
+        .map(|i| (i, run[i]))
+        .collect();
+    let mut slid = Vec::with_capacity(run.len());
+    let mut score = 0;
+    let mut k = 0;
+    while k < tiles.len() {
+        let (i, a) = tiles[k];
+        let bomb = |b: i32| rules.bombs && (a == BOMB || b == BOMB);
+        if tiles.get(k + 1).is_some_and(|&(_, b)| bomb(b)) {
+            return None;
+        }
+        let merged = tiles
+            .get(k + 1)
+            .and_then(|&(j, b)| merge(a, b, j == i + 1, &run[..i], rules));
+        match merged {
+            Some(tile) => {
+                let b = tiles[k + 1].1;
+                slid.push(tile);
+                // two numbers score their sum, even where it wraps
+                score += match tile {
+                    _ if a > 0 && b > 0 => a + b,
+                    t if t < 0 && rules.scoring == Scoring::Web => 0,
+                    t => t,
+                };
+                k += 2;
+            }
+            None => {
+                slid.push(a);
+                k += 1;
+            }
+        }
+    }
+    out[..slid.len()].copy_from_slice(&slid);
+    Some(score)
+}
+
+/// The tile that `a` (nearer the wall) and `b` (behind it) merge into.
+///
+/// * Blockers never merge, nor does a tile above the largest tile within
+///   [`Rules::max_tile`] (65536 by default), which only a won overflow
+///   makes.
+/// * Two equal number tiles add up.
+/// * In the classic variant nothing else merges; with a base `b` other
+///   than 2, two tiles `m·p` and `n·p` for a power `p` of `b` and digits
+///   `m`, `n` below `b` merge instead if `m + n <= b`.
+/// * In the Fibonacci variant instead two ones, or two numbers next to each
+///   other in the Fibonacci sequence, add up.
+/// * Two numbers adding up past the largest tile merge only under
+///   [`Overflow::Win`], into their sum, or [`Overflow::Wrap`], into the
+///   smallest number tile: the base, or 1 in the Fibonacci variant.
+/// * Two equal multipliers double when the rules allow multiplier merges
+///   and the result stays within `max_multiplier`.
+/// * A number and a multiplier multiply, capped at `max_tile`, if they
+///   touched before the move and, under `require_full_below`, every cell
+///   between them and the wall (`toward_wall`) holds a tile.
+#[must_use]
+pub fn merge(a: i32, b: i32, adjacent: bool, toward_wall: &[i32], rules: &Rules) -> Option<i32> {
+    let top = top_tile(rules);
+    if a == BLOCKER || b == BLOCKER || a > top || b > top {
+        return None;
+    }
+    if a > 0 && b > 0 {
+        let pair = match rules.variant {
+            Variant::Classic if rules.base != 2 => classic_pair(a, b, rules.base),
+            Variant::Fibonacci => fibonacci_pair(a, b),
+            _ => a == b,
+        };
+        if !pair {
+            return None;
+        }
+        return match rules.overflow {
+            _ if a + b <= top => Some(a + b),
+            Overflow::Block => None,
+            Overflow::Win => Some(a + b),
+            Overflow::Wrap if rules.variant == Variant::Fibonacci => Some(1),
+            Overflow::Wrap => Some(rules.base),
+        };
+    }
+    if rules.variant != Variant::Akioi {
+        return None;
+    }
+    if a < 0 && b < 0 {
+        return (rules.multiplier_merge && a == b && -a < rules.max_multiplier).then_some(2 * a);
+    }
+    let supported = !rules.require_full_below || toward_wall.iter().all(|&t| t != 0);
+    if !(adjacent && supported) {
+        return None;
+    }
+    let product = (i64::from(a) * i64::from(b))
+        .abs()
+        .min(i64::from(rules.max_tile));
+    Some(i32::try_from(product).expect("capped at max_tile"))
+}
+
+/// The largest tile within [`Rules::max_tile`]: the cap itself, the
+/// largest power of a classic base, or the largest Fibonacci number
+fn top_tile(rules: &Rules) -> i32 {
+    let max = i64::from(rules.max_tile);
+    let top = match rules.variant {
+        Variant::Classic => {
+            let base = i64::from(rules.base);
+            let mut power = base;
+            while power * base <= max {
+                power *= base;
+            }
+            power
+        }
+        Variant::Fibonacci => {
+            let (mut x, mut y) = (1, 2);
+            while x + y <= max {
+                (x, y) = (y, x + y);
+            }
+            y
+        }
+        Variant::Akioi => max,
+    };
+    i32::try_from(top).expect("within max_tile")
+}
+
+/// Whether `a` and `b` are digits times the same power of `base` with a
+/// digit sum of at most `base`
+fn classic_pair(a: i32, b: i32, base: i32) -> bool {
+    let (a, b, base) = (i64::from(a), i64::from(b), i64::from(base));
+    let digit = |tile: i64, p: i64| {
+        (tile % p == 0)
+            .then(|| tile / p)
+            .filter(|d| (1..base).contains(d))
+    };
+    let mut p = base;
+    while p <= a.max(b) {
+        if let (Some(m), Some(n)) = (digit(a, p), digit(b, p)) {
+            return m + n <= base;
+        }
+        p *= base;
+    }
+    false
+}
+
+/// Whether `a` and `b` are two ones or consecutive Fibonacci numbers
+fn fibonacci_pair(a: i32, b: i32) -> bool {
+    let (lo, hi) = (a.min(b), a.max(b));
+    let (mut x, mut y) = (1, 1);
+    while y <= hi {
+        if (x, y) == (lo, hi) {
+            return true;
+        }
+        (x, y) = (y, x + y);
+    }
+    false
+}
+
+/// Check that `expected` is a possible result of moving `board` toward
+/// `direction`: the reference slide plus, if that changed the board, the
+/// [`Rules::spawns`] tiles (one by default) that can spawn on cells left
+/// empty, or none under [`Rules::spawn_every`].
+///
+/// ```
+/// use akioi_2048_core::reference::verify_step;
+/// use akioi_2048_core::{Direction, Rules};
+///
+/// let board = [[2, 2, 0, 0], [0; 4], [0; 4], [0; 4]];
+/// let after = [[4, 0, 0, 2], [0; 4], [0; 4], [0; 4]];
+/// assert!(verify_step(&board, Direction::Left, &after, &Rules::AKIOI).is_ok());
+/// ```
+///
+/// # Errors
+/// Returns an error describing the first difference, or if either board is
+/// invalid under `rules`.
+pub fn verify_step(
+    board: &Board,
+    direction: Direction,
+    expected: &Board,
+    rules: &Rules,
+) -> Result<(), String> {
+    rules.validate()?;
+    validate_board_with(board, rules)?;
+    validate_board_with(expected, rules)?;
+    let (slid, _) = slide(board, direction, rules);
+    if slid == *board {
+        return if expected == board {
+            Ok(())
+        } else {
+            Err(format!(
+                "{direction:?} does not change the board, so nothing may change"
+            ))
+        };
+    }
+    let spawning = spawns_between(&slid, expected, rules, rules.tiles_after(0));
+    if spawning.is_err()
+        && rules.spawn_every > 1
+        && spawns_between(&slid, expected, rules, 0).is_ok()
+    {
+        return Ok(());
+    }
+    spawning.map(|_| ())
+}
@@ -0,0 +1,110 @@
+//! Human-readable boards for logs, debuggers and terminals.
+
+use crate::{BLOCKER, BOMB, Board};
+
+/// Inner width of a cell; fits `65536` and `×4` with padding
+const CELL: usize = 7;
+
+/// How [`render`] draws a board
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Box-drawing grid, multipliers as `×1`/`×2`/`×4`, blockers as `■`
+    /// and bombs as `✱`
+    Unicode,
+    /// Plain `+-|` grid, multipliers as `x1`/`x2`/`x4`, blockers as `#`
+    /// and bombs as `*`
+    Ascii,
+    /// 256-colour tiles without a grid, for terminals
+    Ansi,
+}
+
+impl Style {
+    /// Look up a style by its name (`"unicode"`, `"ascii"` or `"ansi"`)
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Style> {
+        match name {
+            "unicode" => Some(Style::Unicode),
+            "ascii" => Some(Style::Ascii),
+            "ansi" => Some(Style::Ansi),
+            _ => None,
+        }
+    }
+}
+
+/// Draw `board` as lines joined by `\n` (no trailing newline); every cell
+/// has the same width, so columns line up for any tile values.
+#[must_use]
+pub fn render(board: &Board, style: Style) -> String {
+    let times = if style == Style::Ascii { "x" } else { "×" };
+    let (blocker, bomb) = if style == Style::Ascii {
+        ("#", "*")
+    } else {
+        ("■", "✱")
+    };
+    let label = |tile: i32| match tile {
+        0 => String::new(),
+        BLOCKER => blocker.to_string(),
+        BOMB => bomb.to_string(),
+        t if t < 0 => format!("{times}{}", -t),
+        t => t.to_string(),
+    };
+    let row_line = |row: &[i32; 4], sep: &str| {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|&t| format!("{:^CELL$}", label(t)))
+            .collect();
+        format!("{sep}{}{sep}", cells.join(sep))
+    };
+    let rule = |left: &str, fill: &str, mid: &str, right: &str| {
+        format!("{left}{}{right}", vec![fill.repeat(CELL); 4].join(mid))
+    };
+
+    let lines: Vec<String> = match style {
+        Style::Unicode => {
+            let mut lines = vec![rule("┌", "─", "┬", "┐")];
+            for (i, row) in board.iter().enumerate() {
+                if i > 0 {
+                    lines.push(rule("├", "─", "┼", "┤"));
+                }
+                lines.push(row_line(row, "│"));
+            }
+            lines.push(rule("└", "─", "┴", "┘"));
+            lines
+        }
+        Style::Ascii => {
+            let mut lines = vec![rule("+", "-", "+", "+")];
+            for row in board {
+                lines.push(row_line(row, "|"));
+                lines.push(rule("+", "-", "+", "+"));
+            }
+            lines
+        }
+        Style::Ansi => board
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&t| format!("\x1b[30;48;5;{}m{:^CELL$}\x1b[0m", tile_color(t), label(t)))
+                    .collect()
+            })
+            .collect(),
+    };
+    lines.join("\n")
+}
+
+/// 256-colour background for a tile
+fn tile_color(tile: i32) -> u8 {
+    const NUMBERS: [u8; 16] = [
+        230, 223, 216, 209, 202, 196, 227, 226, 220, 214, 208, 154, 118, 82, 46, 40,
+    ];
+    match tile {
+        0 => 250,
+        BLOCKER => 240,
+        BOMB => 160,
+        t if t < 0 => 177,
+        t => {
+            NUMBERS[(t.ilog2() as usize)
+                .saturating_sub(1)
+                .min(NUMBERS.len() - 1)]
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Recorded games: seed, rules and the accepted moves.
+//!
+//! Spawns are derived from the seed, so a replay reproduces every board of
+//! the original game exactly.
+
+use std::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+use crate::compat::Stamp;
+use crate::{Board, Direction, Game, Rules};
+
+const HEADER: &str = "akioi-2048 replay 2";
+
+/// Header of replays from before the [`Stamp`], the form
+/// [`Replay::canonical_text`] still writes
+const HEADER_V1: &str = "akioi-2048 replay 1";
+
+/// A game that can be played back move for move
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replay {
+    pub rules: Rules,
+    pub seed: u64,
+    /// Position the game began from, when it was not dealt from the seed
+    pub start: Option<Start>,
+    /// Directions of accepted moves (moves that changed the board)
+    pub moves: Vec<Direction>,
+}
+
+/// A constructed starting position (see [`GameBuilder::start`](crate::GameBuilder::start))
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Start {
+    pub board: Board,
+    pub score: i64,
+}
+
+impl Replay {
+    /// Play the moves back, returning the game in its final position
+    ///
+    /// # Errors
+    /// Returns an error if the rules are inconsistent or a move does not
+    /// change the board it is played on.
+    pub fn game(&self) -> Result<Game, String> {
+        self.play(|_| {})
+    }
+
+    /// The starting board followed by the board after every move
+    ///
+    /// # Errors
+    /// Same as [`Replay::game`].
+    pub fn frames(&self) -> Result<Vec<Board>, String> {
+        let mut frames = Vec::with_capacity(self.moves.len() + 1);
+        self.play(|board| frames.push(*board))?;
+        Ok(frames)
+    }
+
+    fn play(&self, mut on_board: impl FnMut(&Board)) -> Result<Game, String> {
+        let mut builder = Game::builder().seed(self.seed).rules(self.rules);
+        if let Some(start) = self.start {
+            builder = builder.start(start.board, start.score);
+        }
+        let mut game = builder.build()?;
+        on_board(&game.board());
+        for (i, &dir) in self.moves.iter().enumerate() {
+            if !game.step(dir).moved {
+                return Err(format!("move {i} ({dir:?}) does not change the board"));
+            }
+            on_board(&game.board());
+        }
+        Ok(game)
+    }
+
+    /// Plain-text form: a header line, then `rules <tag>`, the
+    /// [`Stamp`] of the rules as `stamp <stamp>`, `seed <n>`, for a
+    /// constructed start `start <score> <16 tiles>` in row-major order, and
+    /// `moves <letters>` with one of `DRUL` per move
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let stamp = Stamp::of(&self.rules).to_text();
+        self.text(HEADER, &format!("stamp {stamp}\n"))
+    }
+
+    /// [`to_text`](Self::to_text) without the stamp, as version 1 replays
+    /// were written: what [`digest`](Self::digest) and replay signatures
+    /// cover, so they stay the same from one engine version to the next
+    #[must_use]
+    pub fn canonical_text(&self) -> String {
+        self.text(HEADER_V1, "")
+    }
+
+    fn text(&self, header: &str, stamp: &str) -> String {
+        let moves: String = self.moves.iter().map(|&d| letter(d)).collect();
+        let start = self.start.map_or_else(String::new, |start| {
+            let tiles: Vec<String> = start.board.iter().flatten().map(i32::to_string).collect();
+            format!("start {} {}\n", start.score, tiles.join(" "))
+        });
+        format!(
+            "{header}\nrules {}\n{stamp}seed {}\n{start}moves {moves}\n",
+            self.rules.tag(),
+            self.seed
+        )
+    }
+
+    /// Hex SHA-256 of [`Replay::canonical_text`], identifying the game
+    #[must_use]
+    pub fn digest(&self) -> String {
+        Sha256::digest(self.canonical_text()).iter().fold(
+            String::with_capacity(64),
+            |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            },
+        )
+    }
+
+    /// Inverse of [`Replay::to_text`] and [`Replay::canonical_text`],
+    /// skipping comment lines that begin with `#` after the header
+    ///
+    /// # Errors
+    /// Returns an error if `text` is not a replay of this format, or was
+    /// stamped by an engine that plays its rules differently.
+    pub fn parse(text: &str) -> Result<Replay, String> {
+        Self::parse_with(text, false).map(|(replay, _)| replay)
+    }
+
+    /// [`parse`](Self::parse) that with `force` also reads a replay whose
+    /// [`Stamp`] no longer matches, returning the differences with it
+    ///
+    /// # Errors
+    /// Returns an error if `text` is not a replay of this format, or has
+    /// differences and `force` is not set.
+    pub fn parse_with(text: &str, force: bool) -> Result<(Replay, Vec<String>), String> {
+        let mut lines = text
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .peekable();
+        let stamped = match lines.next() {
+            Some(HEADER) => true,
+            Some(HEADER_V1) => false,
+            _ => return Err("not an akioi-2048 replay".to_string()),
+        };
+        let rules = Rules::from_tag(field(&mut lines, "rules")?)?;
+        let differences = if stamped {
+            Stamp::parse(field(&mut lines, "stamp")?)?.check("replay", &rules, force)?
+        } else {
+            Vec::new()
+        };
+        let seed = field(&mut lines, "seed")?;
+        let seed = seed
+            .parse()
+            .map_err(|_| format!("invalid seed in replay: {seed}"))?;
+        let start = lines
+            .next_if(|line| line.starts_with("start "))
+            .map(|line| parse_start(&line["start ".len()..]))
+            .transpose()?;
+        let moves = field(&mut lines, "moves")?
+            .chars()
+            .map(|c| direction(c).ok_or_else(|| format!("invalid move in replay: {c}")))
+            .collect::<Result<_, _>>()?;
+        let replay = Replay {
+            rules,
+            seed,
+            start,
+            moves,
+        };
+        Ok((replay, differences))
+    }
+}
+
+fn field<'a>(lines: &mut impl Iterator<Item = &'a str>, name: &str) -> Result<&'a str, String> {
+    lines
+        .next()
+        .and_then(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .ok_or_else(|| format!("replay is missing `{name}`"))
+}
+
+fn parse_start(line: &str) -> Result<Start, String> {
+    let invalid = || format!("invalid start in replay: {line}");
+    let mut fields = line.split(' ');
+    let score = fields
+        .next()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(invalid)?;
+    let tiles: Vec<i32> = fields
+        .map(|t| t.parse().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    if tiles.len() != 16 {
+        return Err(invalid());
+    }
+    let mut board = [[0; 4]; 4];
+    for (cell, tile) in board.iter_mut().flatten().zip(tiles) {
+        *cell = tile;
+    }
+    Ok(Start { board, score })
+}
+
+fn letter(dir: Direction) -> char {
+    match dir {
+        Direction::Down => 'D',
+        Direction::Right => 'R',
+        Direction::Up => 'U',
+        Direction::Left => 'L',
+    }
+}
+
+fn direction(c: char) -> Option<Direction> {
+    match c {
+        'D' => Some(Direction::Down),
+        'R' => Some(Direction::Right),
+        'U' => Some(Direction::Up),
+        'L' => Some(Direction::Left),
+        _ => None,
+    }
+}
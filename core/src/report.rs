@@ -0,0 +1,303 @@
+//! Standardized strength battery for agents.
+//!
+//! Every battery is fully determined by the seed, so report cards of
+//! different agents are directly comparable.
+
+use std::time::{Duration, Instant};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::game::{is_dead, single_step, state_of};
+use crate::variant::MergeRule;
+use crate::{ALL_DIRECTIONS, Board, Direction, Rules, State};
+
+/// Mid-game starting positions used by the `positions` battery
+pub const POSITIONS: [Board; 4] = [
+    [
+        [0, 0, 0, 2],
+        [0, 0, 4, 8],
+        [2, 16, 32, 64],
+        [128, 256, 512, 1024],
+    ],
+    [
+        [0, 2, 0, 0],
+        [0, -1, 4, 0],
+        [8, 16, 2, 4],
+        [64, 32, 128, 256],
+    ],
+    [
+        [2, 0, 0, 0],
+        [4, 2, 0, 0],
+        [8, 64, -2, 0],
+        [2048, 512, 16, 4],
+    ],
+    [
+        [0, 0, -1, 0],
+        [0, 4, 8, 0],
+        [2, 32, 16, 2],
+        [4096, 1024, 256, 64],
+    ],
+];
+
+/// Games end after this many consecutive moves that leave the board unchanged
+const STALL_LIMIT: usize = 64;
+
+/// How spawns are chosen during a battery
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Spawner {
+    /// Regular weighted random spawns
+    Random,
+    /// The spawn that leaves the agent with the fewest legal moves
+    Adversarial,
+}
+
+/// Aggregate result of one battery (or of the whole card)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatteryResult {
+    pub games: usize,
+    pub moves: usize,
+    pub median_score: f64,
+    /// Fraction of games that reached a 65536 tile
+    pub win_rate: f64,
+    /// Fraction of moves that were blunders (see [`is_blunder`])
+    pub blunder_rate: f64,
+    /// Games forfeited by exceeding the per-move time limit
+    pub timeouts: usize,
+    /// Agent + engine throughput
+    pub moves_per_second: f64,
+}
+
+/// Full report: one entry per battery plus the overall aggregate
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportCard {
+    pub seed: u64,
+    pub overall: BatteryResult,
+    pub batteries: Vec<(&'static str, BatteryResult)>,
+}
+
+/// Raw per-game numbers before aggregation
+struct GameLog {
+    score: i64,
+    won: bool,
+    moves: usize,
+    blunders: usize,
+    timed_out: bool,
+    elapsed: Duration,
+}
+
+/// Run the standard battery against `agent`.
+///
+/// Batteries (each `n_games` long, `positions` once per entry of [`POSITIONS`]):
+/// * `seeded`: fresh games with seeded random spawns
+/// * `positions`: fixed mid-game positions with seeded random spawns
+/// * `adversarial`: fresh games where every spawn is chosen adversarially
+/// * `timed`: like `seeded`, but a move slower than `time_limit` forfeits the game
+///
+/// # Errors
+/// Propagates the first error returned by `agent`.
+pub fn report_card<E>(
+    seed: u64,
+    n_games: usize,
+    time_limit: Duration,
+    mut agent: impl FnMut(&Board) -> Result<Direction, E>,
+) -> Result<ReportCard, E> {
+    let rules = Rules::AKIOI;
+    let mut batteries = Vec::new();
+    let mut every_game = Vec::new();
+
+    let specs: [(&'static str, u64, Spawner, Option<Duration>); 4] = [
+        ("seeded", 0, Spawner::Random, None),
+        ("positions", 1, Spawner::Random, None),
+        ("adversarial", 2, Spawner::Adversarial, None),
+        ("timed", 3, Spawner::Random, Some(time_limit)),
+    ];
+    for (name, stream, spawner, limit) in specs {
+        let mut logs = Vec::new();
+        let starts: Vec<Option<Board>> = if name == "positions" {
+            POSITIONS.iter().copied().map(Some).collect()
+        } else {
+            vec![None; n_games]
+        };
+        for (i, start) in starts.into_iter().enumerate() {
+            let game_seed = seed
+                .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+                .wrapping_add((stream << 32) | i as u64);
+            let mut rng = StdRng::seed_from_u64(game_seed);
+            let board = start.unwrap_or_else(|| crate::init_with_rules(&mut rng, &rules));
+            logs.push(play(board, &rules, spawner, limit, &mut rng, &mut agent)?);
+        }
+        batteries.push((name, summarize(&logs)));
+        every_game.extend(logs);
+    }
+
+    Ok(ReportCard {
+        seed,
+        overall: summarize(&every_game),
+        batteries,
+    })
+}
+
+fn play<E>(
+    mut board: Board,
+    rules: &Rules,
+    spawner: Spawner,
+    limit: Option<Duration>,
+    rng: &mut StdRng,
+    agent: &mut impl FnMut(&Board) -> Result<Direction, E>,
+) -> Result<GameLog, E> {
+    let mut log = GameLog {
+        score: 0,
+        won: false,
+        moves: 0,
+        blunders: 0,
+        timed_out: false,
+        elapsed: Duration::ZERO,
+    };
+    let (mut stalled, mut moved) = (0, 0);
+    while state_of(&board, rules) != State::GameOver && stalled < STALL_LIMIT {
+        let started = Instant::now();
+        let dir = agent(&board)?;
+        let thinking = started.elapsed();
+
+        if is_blunder(&board, dir, rules) {
+            log.blunders += 1;
+        }
+        let next = match spawner {
+            Spawner::Random => {
+                let (next, delta, _) = crate::step_after(board, dir, rules, rng, moved)
+                    .expect("engine boards are always valid");
+                log.score += i64::from(delta);
+                next
+            }
+            Spawner::Adversarial => {
+                let (mut next, delta, _) = single_step(&board, dir, rules);
+                if next != board {
+                    spawn_adversarial(&mut next, rules);
+                }
+                log.score += i64::from(delta);
+                next
+            }
+        };
+        log.elapsed += started.elapsed();
+        log.moves += 1;
+        if next == board {
+            stalled += 1;
+        } else {
+            (stalled, moved) = (0, moved + 1);
+        }
+        board = next;
+
+        if limit.is_some_and(|limit| thinking > limit) {
+            log.timed_out = true;
+            break;
+        }
+        if state_of(&board, rules) == State::Victory {
+            log.won = true;
+            break;
+        }
+    }
+    Ok(log)
+}
+
+/// A move is a blunder if it does not change the board, or if some spawn
+/// after it can end the game while another direction is safe against every
+/// spawn.
+#[must_use]
+pub fn is_blunder(board: &Board, dir: Direction, rules: &Rules) -> bool {
+    let after = single_step(board, dir, rules).0;
+    if after == *board {
+        return !is_dead(board, rules);
+    }
+    !is_safe(&after, rules)
+        && ALL_DIRECTIONS.iter().any(|&d| {
+            let alt = single_step(board, d, rules).0;
+            alt != *board && is_safe(&alt, rules)
+        })
+}
+
+/// No single spawn on the afterstate `after` produces a dead board
+fn is_safe(after: &Board, rules: &Rules) -> bool {
+    spawns(after, rules).all(|next| !is_dead(&next, rules))
+}
+
+/// Every board reachable from `after` by one spawn
+fn spawns<'a>(after: &'a Board, rules: &Rules) -> impl Iterator<Item = Board> + 'a {
+    let tiles: Vec<i32> = rules
+        .variant
+        .spawn_weights(after, rules)
+        .entries()
+        .iter()
+        .map(|&(tile, _)| tile)
+        .collect();
+    rules
+        .placement
+        .cells(after)
+        .into_iter()
+        .flat_map(move |(r, c)| {
+            tiles.clone().into_iter().map(move |tile| {
+                let mut next = *after;
+                next[r][c] = tile;
+                next
+            })
+        })
+}
+
+/// Place the spawn that leaves the fewest legal moves (ties: fewest empty
+/// cells after the best reply, then first in row-major order)
+fn spawn_adversarial(after: &mut Board, rules: &Rules) {
+    let legal_moves = |b: &Board| {
+        ALL_DIRECTIONS
+            .iter()
+            .filter(|&&d| single_step(b, d, rules).0 != *b)
+            .count()
+    };
+    let best_empties = |b: &Board| {
+        ALL_DIRECTIONS
+            .iter()
+            .map(|&d| single_step(b, d, rules).0)
+            .filter(|n| n != b)
+            .map(|n| n.iter().flatten().filter(|&&v| v == 0).count())
+            .max()
+            .unwrap_or(0)
+    };
+    if let Some(worst) = spawns(after, rules).min_by_key(|b| (legal_moves(b), best_empties(b))) {
+        *after = worst;
+    }
+}
+
+fn summarize(logs: &[GameLog]) -> BatteryResult {
+    if logs.is_empty() {
+        return BatteryResult::default();
+    }
+    let mut scores: Vec<i64> = logs.iter().map(|g| g.score).collect();
+    scores.sort_unstable();
+    let mid = scores.len() / 2;
+    let median_score = if scores.len().is_multiple_of(2) {
+        (scores[mid - 1] + scores[mid]) as f64 / 2.0
+    } else {
+        scores[mid] as f64
+    };
+    let moves: usize = logs.iter().map(|g| g.moves).sum();
+    let blunders: usize = logs.iter().map(|g| g.blunders).sum();
+    let elapsed: Duration = logs.iter().map(|g| g.elapsed).sum();
+    let wins = logs.iter().filter(|g| g.won).count();
+
+    BatteryResult {
+        games: logs.len(),
+        moves,
+        median_score,
+        win_rate: wins as f64 / logs.len() as f64,
+        blunder_rate: if moves == 0 {
+            0.0
+        } else {
+            blunders as f64 / moves as f64
+        },
+        timeouts: logs.iter().filter(|g| g.timed_out).count(),
+        moves_per_second: if elapsed.is_zero() {
+            0.0
+        } else {
+            moves as f64 / elapsed.as_secs_f64()
+        },
+    }
+}
@@ -0,0 +1,26 @@
+/// Source of randomness for spawns.
+///
+/// Every [`rand::RngCore`] implements it, so any `rand` generator works;
+/// implement it directly to drive the engine from another source (a
+/// WebAssembly host, a recorded stream, a test fixture).
+///
+/// Spawns take only [`index`](Rng::index): the cell, then the tile as an
+/// index into integer weights, so an implementation giving the same indices
+/// spawns the same tiles everywhere.
+pub trait Rng {
+    /// Uniform index in `0..len`; `len` is never 0
+    fn index(&mut self, len: usize) -> usize;
+
+    /// Uniform float in `[0, 1)`
+    fn unit(&mut self) -> f64;
+}
+
+impl<R: rand::RngCore + ?Sized> Rng for R {
+    fn index(&mut self, len: usize) -> usize {
+        rand::Rng::random_range(self, ..len)
+    }
+
+    fn unit(&mut self) -> f64 {
+        rand::Rng::random(self)
+    }
+}
@@ -0,0 +1,569 @@
+use crate::Board;
+use crate::variant::Variant;
+
+/// Version of the engine's rule semantics, bumped whenever an existing
+/// configuration would play differently
+pub const RULES_VERSION: u32 = 2;
+
+/// How generous the spawns are; see [`spawn_probabilities`](crate::spawn_probabilities)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    /// Fewer ×1 multipliers, more 4s and ×2s
+    Easy,
+    /// The web version's spawn table
+    #[default]
+    Standard,
+    /// More ×1 multipliers, fewer 4s and ×2s
+    Cruel,
+    /// Easy while the largest tile is at most 128, standard at 1024 and
+    /// cruel from 8192, shifting gradually in between
+    Adaptive,
+}
+
+impl Difficulty {
+    /// Every difficulty, in declaration order
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Standard,
+        Difficulty::Cruel,
+        Difficulty::Adaptive,
+    ];
+
+    /// Lowercase name, as in rules tags
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Standard => "standard",
+            Difficulty::Cruel => "cruel",
+            Difficulty::Adaptive => "adaptive",
+        }
+    }
+
+    /// Inverse of [`Difficulty::name`]
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        Difficulty::ALL.into_iter().find(|d| d.name() == name)
+    }
+}
+
+/// What happens when two tiles at the [`Rules::max_tile`] cap meet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// They stay apart; the cap is the winning tile
+    #[default]
+    Block,
+    /// They merge into a tile twice the cap, which wins and merges no more
+    Win,
+    /// They merge back into the smallest number tile, scoring twice the
+    /// cap; the cap is the winning tile
+    Wrap,
+}
+
+impl Overflow {
+    /// Every overflow behavior, in declaration order
+    pub const ALL: [Overflow; 3] = [Overflow::Block, Overflow::Win, Overflow::Wrap];
+
+    /// Lowercase name, as in rules tags
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Overflow::Block => "block",
+            Overflow::Win => "win",
+            Overflow::Wrap => "wrap",
+        }
+    }
+
+    /// Inverse of [`Overflow::name`]
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Overflow> {
+        Overflow::ALL.into_iter().find(|o| o.name() == name)
+    }
+}
+
+/// How merges add to the score
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Scoring {
+    /// Every merge scores the tile it makes, so `-1 + -1 -> -2` scores −2
+    #[default]
+    Standard,
+    /// Merges making a multiplier score nothing, so only number tiles count
+    /// and the score never goes down, as a web score counter shows it
+    Web,
+    /// Merges score as standard, but a move that would lose score scores 0
+    Clamp,
+}
+
+impl Scoring {
+    /// Every scoring rule, in declaration order
+    pub const ALL: [Scoring; 3] = [Scoring::Standard, Scoring::Web, Scoring::Clamp];
+
+    /// Lowercase name, as in rules tags
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Scoring::Standard => "standard",
+            Scoring::Web => "web",
+            Scoring::Clamp => "clamp",
+        }
+    }
+
+    /// Inverse of [`Scoring::name`]
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Scoring> {
+        Scoring::ALL.into_iter().find(|s| s.name() == name)
+    }
+
+    /// Score of a merge making `tile` that the variant scores `score`
+    #[must_use]
+    pub fn merge_score(self, tile: i32, score: i32) -> i32 {
+        match self {
+            Scoring::Web if tile < 0 => 0,
+            _ => score,
+        }
+    }
+
+    /// Score of a move whose merges score `delta` in all
+    #[must_use]
+    pub fn move_score(self, delta: i32) -> i32 {
+        match self {
+            Scoring::Clamp => delta.max(0),
+            _ => delta,
+        }
+    }
+}
+
+/// Where spawned tiles land
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Placement {
+    /// Any empty cell, as the web version spawns
+    #[default]
+    Uniform,
+    /// Empty cells on the border of the board while there are any
+    Edge,
+    /// The empty cells farthest from the largest number tile
+    Far,
+}
+
+impl Placement {
+    /// Every placement policy, in declaration order
+    pub const ALL: [Placement; 3] = [Placement::Uniform, Placement::Edge, Placement::Far];
+
+    /// Lowercase name, as in rules tags
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Placement::Uniform => "uniform",
+            Placement::Edge => "edge",
+            Placement::Far => "far",
+        }
+    }
+
+    /// Inverse of [`Placement::name`]
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Placement> {
+        Placement::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    /// The empty cells of `board` a spawn may land on, each as likely, in
+    /// row-major order; empty only on a full board.
+    ///
+    /// [`Edge`](Placement::Edge) falls back to every empty cell once the
+    /// border is full. [`Far`](Placement::Far) measures distance in moves
+    /// (rows plus columns) to the nearest copy of the largest number tile,
+    /// and takes every empty cell on a board without number tiles.
+    ///
+    /// ```
+    /// use akioi_2048_core::Placement;
+    ///
+    /// let board = [[0; 4], [0, 8, 2, 0], [0; 4], [0; 4]];
+    /// assert_eq!(Placement::Far.cells(&board), [(3, 3)]);
+    /// assert_eq!(Placement::Edge.cells(&board).len(), 12);
+    /// assert_eq!(Placement::Uniform.cells(&board).len(), 14);
+    /// ```
+    #[must_use]
+    pub fn cells(self, board: &Board) -> Vec<(usize, usize)> {
+        let empty: Vec<(usize, usize)> = (0..16)
+            .map(|i| (i / 4, i % 4))
+            .filter(|&(r, c)| board[r][c] == 0)
+            .collect();
+        match self {
+            Placement::Uniform => empty,
+            Placement::Edge => {
+                let edge: Vec<(usize, usize)> = empty
+                    .iter()
+                    .copied()
+                    .filter(|&(r, c)| r == 0 || r == 3 || c == 0 || c == 3)
+                    .collect();
+                if edge.is_empty() { empty } else { edge }
+            }
+            Placement::Far => {
+                let top = board.iter().flatten().copied().max().unwrap_or(0);
+                if top <= 0 {
+                    return empty;
+                }
+                let tops: Vec<(usize, usize)> = (0..16)
+                    .map(|i| (i / 4, i % 4))
+                    .filter(|&(r, c)| board[r][c] == top)
+                    .collect();
+                let distance = |&(r, c): &(usize, usize)| {
+                    tops.iter()
+                        .map(|&(tr, tc)| r.abs_diff(tr) + c.abs_diff(tc))
+                        .min()
+                        .unwrap_or(0)
+                };
+                let farthest = empty.iter().map(distance).max().unwrap_or(0);
+                empty
+                    .into_iter()
+                    .filter(|cell| distance(cell) == farthest)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Largest [`Rules::max_tile`], so twice it still fits a tile
+const MAX_TILE_LIMIT: i32 = 1 << 29;
+
+/// Most tiles [`Rules::spawns`] spawns after a move
+const MAX_SPAWNS: u8 = 4;
+
+/// Largest [`Rules::spawn_every`]
+const MAX_SPAWN_EVERY: u8 = 16;
+
+/// Tunable game mechanics: multiplier merges, spawn filters and optional
+/// tile types.
+///
+/// The default is the standard akioi ruleset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// Tile set, merges and spawns; the settings below refine the akioi
+    /// variant's multipliers and spawns
+    pub variant: Variant,
+    /// Largest multiplier magnitude; multipliers of this size no longer merge
+    /// with each other (standard: 4, i.e. `-4`). Must be a power of two ≥ 2.
+    pub max_multiplier: i32,
+    /// Whether two equal multipliers merge into a bigger one (`-1 + -1 -> -2`)
+    pub multiplier_merge: bool,
+    /// Whether numeric × multiplier merges require every cell beyond the pair
+    /// (in the move direction) to be occupied
+    pub require_full_below: bool,
+    /// Mercy rule: never spawn a ×1 multiplier (which cannot help) into the
+    /// last empty cell
+    pub mercy: bool,
+    /// Spawn weights
+    pub difficulty: Difficulty,
+    /// Whether [`BOMB`](crate::BOMB) tiles are valid and detonate
+    pub bombs: bool,
+    /// Whether immovable [`BLOCKER`](crate::BLOCKER) tiles are valid
+    pub blockers: bool,
+    /// Base of the classic variant's progression, from 2 to 16; see
+    /// [`Classic`](crate::variant::Classic)
+    pub base: i32,
+    /// Cap of the number tiles, a power of two from 16 to 2²⁹ (standard:
+    /// 65536): no merge makes a larger tile, except as set by `overflow`,
+    /// and each variant's largest tile within it wins
+    pub max_tile: i32,
+    /// What two tiles at the cap merge into
+    pub overflow: Overflow,
+    /// How merges add to the score (standard: every merge scores the tile
+    /// it makes)
+    pub scoring: Scoring,
+    /// Tiles spawned one after another after a move that spawns, from 1 to
+    /// 4 (standard: 1); fewer when the board fills up
+    pub spawns: u8,
+    /// Only every this many moves spawns: the first move that changes the
+    /// board and every `spawn_every`-th after it, from 1 to 16 (standard: 1,
+    /// every move); see [`Rules::tiles_after`]
+    pub spawn_every: u8,
+    /// Where spawned tiles land, the starting tiles included (standard: any
+    /// empty cell)
+    pub placement: Placement,
+}
+
+impl Rules {
+    /// The standard akioi ruleset
+    pub const AKIOI: Rules = Rules {
+        variant: Variant::Akioi,
+        max_multiplier: 4,
+        multiplier_merge: true,
+        require_full_below: true,
+        mercy: false,
+        difficulty: Difficulty::Standard,
+        bombs: false,
+        blockers: false,
+        base: 2,
+        max_tile: 0x0001_0000,
+        overflow: Overflow::Block,
+        scoring: Scoring::Standard,
+        spawns: 1,
+        spawn_every: 1,
+        placement: Placement::Uniform,
+    };
+
+    /// Standard rules plus the mercy spawn filter
+    pub const MERCY: Rules = Rules {
+        mercy: true,
+        ..Rules::AKIOI
+    };
+
+    /// Classic 2048: no multipliers, 2 and 4 spawning
+    pub const CLASSIC: Rules = Rules {
+        variant: Variant::Classic,
+        ..Rules::AKIOI
+    };
+
+    /// Fibonacci 2048: consecutive Fibonacci numbers merge, 1 and 2 spawning
+    pub const FIBONACCI: Rules = Rules {
+        variant: Variant::Fibonacci,
+        ..Rules::AKIOI
+    };
+
+    /// Look up a named preset: `"akioi"` (standard), `"mercy"`, another
+    /// variant (`"classic"`, `"fib"`) or a difficulty (`"easy"`, `"standard"`,
+    /// `"cruel"`, `"adaptive"`) with otherwise standard rules
+    #[must_use]
+    pub fn preset(name: &str) -> Option<Rules> {
+        match name {
+            "akioi" => Some(Rules::AKIOI),
+            "mercy" => Some(Rules::MERCY),
+            _ => Variant::from_name(name)
+                .map(|variant| Rules {
+                    variant,
+                    ..Rules::AKIOI
+                })
+                .or_else(|| {
+                    Difficulty::from_name(name).map(|difficulty| Rules {
+                        difficulty,
+                        ..Rules::AKIOI
+                    })
+                }),
+        }
+    }
+
+    /// Stable identifier of these rules, e.g. `akioi-v2` or
+    /// `akioi-v2+mercy+max_multiplier=8` or
+    /// `akioi-v2+variant=classic+max_tile=1048576+overflow=win` or
+    /// `akioi-v2+spawns=2+spawn_every=3+placement=edge`.
+    ///
+    /// Scores are only comparable between games with the same tag.
+    #[must_use]
+    pub fn tag(&self) -> String {
+        let std = Rules::AKIOI;
+        let mut tag = format!("akioi-v{RULES_VERSION}");
+        if self.variant != std.variant {
+            tag.push_str(&format!("+variant={}", self.variant.name()));
+        }
+        if self.mercy {
+            tag.push_str("+mercy");
+        }
+        if self.max_multiplier != std.max_multiplier {
+            tag.push_str(&format!("+max_multiplier={}", self.max_multiplier));
+        }
+        if !self.multiplier_merge {
+            tag.push_str("+no_multiplier_merge");
+        }
+        if !self.require_full_below {
+            tag.push_str("+no_full_below");
+        }
+        if self.bombs {
+            tag.push_str("+bombs");
+        }
+        if self.blockers {
+            tag.push_str("+blockers");
+        }
+        if self.difficulty != std.difficulty {
+            tag.push_str(&format!("+difficulty={}", self.difficulty.name()));
+        }
+        if self.base != std.base {
+            tag.push_str(&format!("+base={}", self.base));
+        }
+        if self.max_tile != std.max_tile {
+            tag.push_str(&format!("+max_tile={}", self.max_tile));
+        }
+        if self.overflow != std.overflow {
+            tag.push_str(&format!("+overflow={}", self.overflow.name()));
+        }
+        if self.scoring != std.scoring {
+            tag.push_str(&format!("+scoring={}", self.scoring.name()));
+        }
+        if self.spawns != std.spawns {
+            tag.push_str(&format!("+spawns={}", self.spawns));
+        }
+        if self.spawn_every != std.spawn_every {
+            tag.push_str(&format!("+spawn_every={}", self.spawn_every));
+        }
+        if self.placement != std.placement {
+            tag.push_str(&format!("+placement={}", self.placement.name()));
+        }
+        tag
+    }
+
+    /// Inverse of [`Rules::tag`]
+    ///
+    /// # Errors
+    /// Returns an error for tags of another rules version, naming an older
+    /// one, unknown suffixes or inconsistent rules.
+    pub fn from_tag(tag: &str) -> Result<Rules, String> {
+        let mut parts = tag.split('+');
+        let version = parts.next().and_then(|head| head.strip_prefix("akioi-v"));
+        match version.map(str::parse::<u32>) {
+            Some(Ok(RULES_VERSION)) => {}
+            Some(Ok(old)) if old < RULES_VERSION => {
+                return Err(format!(
+                    "rules tag {tag} is of rules version {old}, which this engine no longer \
+                     plays: seeded games spawn differently since version {RULES_VERSION}"
+                ));
+            }
+            _ => return Err(format!("unsupported rules tag: {tag}")),
+        }
+        let mut rules = Rules::AKIOI;
+        for part in parts {
+            match part {
+                "mercy" => rules.mercy = true,
+                "no_multiplier_merge" => rules.multiplier_merge = false,
+                "no_full_below" => rules.require_full_below = false,
+                "bombs" => rules.bombs = true,
+                "blockers" => rules.blockers = true,
+                _ if part.starts_with("variant=") => {
+                    rules.variant = Variant::from_name(&part["variant=".len()..])
+                        .ok_or_else(|| format!("unknown variant in rules tag: {part}"))?;
+                }
+                _ if part.starts_with("difficulty=") => {
+                    rules.difficulty = Difficulty::from_name(&part["difficulty=".len()..])
+                        .ok_or_else(|| format!("unknown difficulty in rules tag: {part}"))?;
+                }
+                _ if part.starts_with("base=") => {
+                    rules.base = part["base=".len()..]
+                        .parse()
+                        .map_err(|_| format!("invalid base in rules tag: {part}"))?;
+                }
+                _ if part.starts_with("max_tile=") => {
+                    rules.max_tile = part["max_tile=".len()..]
+                        .parse()
+                        .map_err(|_| format!("invalid max_tile in rules tag: {part}"))?;
+                }
+                _ if part.starts_with("overflow=") => {
+                    rules.overflow = Overflow::from_name(&part["overflow=".len()..])
+                        .ok_or_else(|| format!("unknown overflow in rules tag: {part}"))?;
+                }
+                _ if part.starts_with("scoring=") => {
+                    rules.scoring = Scoring::from_name(&part["scoring=".len()..])
+                        .ok_or_else(|| format!("unknown scoring in rules tag: {part}"))?;
+                }
+                _ if part.starts_with("spawns=") => {
+                    rules.spawns = part["spawns=".len()..]
+                        .parse()
+                        .map_err(|_| format!("invalid spawns in rules tag: {part}"))?;
+                }
+                _ if part.starts_with("spawn_every=") => {
+                    rules.spawn_every = part["spawn_every=".len()..]
+                        .parse()
+                        .map_err(|_| format!("invalid spawn_every in rules tag: {part}"))?;
+                }
+                _ if part.starts_with("placement=") => {
+                    rules.placement = Placement::from_name(&part["placement=".len()..])
+                        .ok_or_else(|| format!("unknown placement in rules tag: {part}"))?;
+                }
+                _ => {
+                    rules.max_multiplier = part
+                        .strip_prefix("max_multiplier=")
+                        .and_then(|n| n.parse().ok())
+                        .ok_or_else(|| format!("unknown rules tag suffix: {part}"))?;
+                }
+            }
+        }
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// Check that the configuration is usable
+    ///
+    /// # Errors
+    /// Returns an error if `max_multiplier` is not a power of two ≥ 2,
+    /// `base` is out of range or set outside the classic variant,
+    /// `max_tile` is out of range or below `base²`, or `spawns` or
+    /// `spawn_every` is out of range.
+    pub fn validate(&self) -> Result<(), String> {
+        let ok = self.max_multiplier >= 2
+            && u32::try_from(self.max_multiplier).is_ok_and(u32::is_power_of_two);
+        if !ok {
+            return Err(format!(
+                "max_multiplier must be a power of two >= 2, got: {}",
+                self.max_multiplier
+            ));
+        }
+        if !(2..=16).contains(&self.base) {
+            return Err(format!("base must be from 2 to 16, got: {}", self.base));
+        }
+        if self.base != 2 && self.variant != Variant::Classic {
+            return Err("base only applies to the classic variant".to_string());
+        }
+        let power = u32::try_from(self.max_tile).is_ok_and(u32::is_power_of_two);
+        if !(power && (16..=MAX_TILE_LIMIT).contains(&self.max_tile)) {
+            return Err(format!(
+                "max_tile must be a power of two from 16 to {MAX_TILE_LIMIT}, got: {}",
+                self.max_tile
+            ));
+        }
+        if self.max_tile < self.base * self.base {
+            return Err(format!(
+                "max_tile must be at least base * base = {}, got: {}",
+                self.base * self.base,
+                self.max_tile
+            ));
+        }
+        if !(1..=MAX_SPAWNS).contains(&self.spawns) {
+            return Err(format!(
+                "spawns must be from 1 to {MAX_SPAWNS}, got: {}",
+                self.spawns
+            ));
+        }
+        if !(1..=MAX_SPAWN_EVERY).contains(&self.spawn_every) {
+            return Err(format!(
+                "spawn_every must be from 1 to {MAX_SPAWN_EVERY}, got: {}",
+                self.spawn_every
+            ));
+        }
+        Ok(())
+    }
+
+    /// Tiles spawned after a move that changes the board when `moves` such
+    /// moves came before it: [`spawns`](Self::spawns) when `moves` is a
+    /// multiple of [`spawn_every`](Self::spawn_every), otherwise none.
+    ///
+    /// Steps that do not know the moves before them, such as
+    /// [`step`](crate::step), and solvers by default count as move 0, so
+    /// their first move spawns.
+    ///
+    /// ```
+    /// use akioi_2048_core::Rules;
+    ///
+    /// let rules = Rules { spawns: 2, spawn_every: 3, ..Rules::AKIOI };
+    /// let tiles: Vec<usize> = (0..7).map(|moves| rules.tiles_after(moves)).collect();
+    /// assert_eq!(tiles, [2, 0, 0, 2, 0, 0, 2]);
+    /// ```
+    #[must_use]
+    pub fn tiles_after(&self, moves: u64) -> usize {
+        if moves.is_multiple_of(u64::from(self.spawn_every)) {
+            usize::from(self.spawns)
+        } else {
+            0
+        }
+    }
+
+    /// Whether `tile` (< 0) is a multiplier allowed under these rules
+    #[must_use]
+    pub fn is_multiplier(&self, tile: i32) -> bool {
+        self.variant == Variant::Akioi
+            && tile < 0
+            && tile >= -self.max_multiplier
+            && u32::try_from(-tile).is_ok_and(u32::is_power_of_two)
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::AKIOI
+    }
+}
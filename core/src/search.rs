@@ -0,0 +1,691 @@
+//! Heuristic evaluation and expectimax search over moves and spawns.
+//!
+//! [`evaluate`] scores a board by weighted [`Features`]; [`expectimax`]
+//! looks a few moves ahead, averaging over every spawn by its probability,
+//! and reports the expected evaluation of each legal move. [`search`] is
+//! the same search deepened iteratively on every core, sharing one
+//! transposition table, until a depth or a time budget is reached.
+//! [`worst_case`] instead lets an adversary choose every spawn, by minimax
+//! with alpha-beta pruning.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::reach::spawn_outcomes_of;
+use crate::{BLOCKER, BOMB, Board, Direction, Engine, Rules};
+
+/// Value of a position no move changes, far below any evaluation
+pub const LOSS: f64 = -1.0e4;
+
+/// Spawn sequences less likely than this are not searched further; their
+/// board is evaluated as it stands
+const MIN_PROBABILITY: f64 = 1.0e-3;
+
+/// Shards of the transposition table of [`search`], each behind its own lock
+const TABLE_SHARDS: usize = 64;
+
+/// Positions a [`search`] worker visits between looks at the clock
+const CLOCK_INTERVAL: u32 = 256;
+
+/// Position features the evaluation weighs
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Features {
+    /// Empty cells
+    pub empty_cells: f64,
+    /// How far rows and columns are from running monotonically, in steps
+    /// of `log2`, negated: `0` for perfectly ordered lines
+    pub monotonicity: f64,
+    /// Differences in `log2` between neighbouring number tiles, negated
+    pub smoothness: f64,
+    /// `log2` of the largest tile if it sits in a corner, else `0`
+    pub corner: f64,
+    /// Neighbouring pairs that could merge: equal numbers, or a number
+    /// beside a multiplier
+    pub merges: f64,
+    /// Multiplier tiles on the board
+    pub multipliers: f64,
+}
+
+impl Features {
+    /// The features of `board`
+    #[must_use]
+    pub fn of(board: &Board) -> Features {
+        let level = |t: i32| if t > 0 { f64::from(t.ilog2()) } else { 0.0 };
+        let mut f = Features::default();
+
+        let lines = (0..4)
+            .map(|r| board[r])
+            .chain((0..4).map(|c| [board[0][c], board[1][c], board[2][c], board[3][c]]));
+        for line in lines {
+            let (mut up, mut down) = (0.0, 0.0);
+            for pair in line.windows(2) {
+                let (a, b) = (level(pair[0]), level(pair[1]));
+                if a > b {
+                    down += a - b;
+                } else {
+                    up += b - a;
+                }
+                if pair[0] > 0 && pair[1] > 0 {
+                    f.smoothness -= (a - b).abs();
+                }
+                let (a, b) = (pair[0], pair[1]);
+                let blocked = [a, b].iter().any(|&t| t == BLOCKER || t == BOMB);
+                if (a > 0 && a == b) || (!blocked && a.signum() * b.signum() < 0) {
+                    f.merges += 1.0;
+                }
+            }
+            f.monotonicity -= f64::min(up, down);
+        }
+
+        let tiles = || board.iter().flatten().copied();
+        f.empty_cells = tiles().filter(|&t| t == 0).count() as f64;
+        f.multipliers = tiles()
+            .filter(|&t| t < 0 && t != BLOCKER && t != BOMB)
+            .count() as f64;
+        let max = tiles().max().unwrap_or(0);
+        if max > 0 && [board[0][0], board[0][3], board[3][0], board[3][3]].contains(&max) {
+            f.corner = level(max);
+        }
+        f
+    }
+
+    /// The features as `(name, value)` pairs, in field order
+    #[must_use]
+    pub fn named(&self) -> [(&'static str, f64); 6] {
+        [
+            ("empty_cells", self.empty_cells),
+            ("monotonicity", self.monotonicity),
+            ("smoothness", self.smoothness),
+            ("corner", self.corner),
+            ("merges", self.merges),
+            ("multipliers", self.multipliers),
+        ]
+    }
+}
+
+/// Weight of each feature in [`evaluate`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weights {
+    pub empty_cells: f64,
+    pub monotonicity: f64,
+    pub smoothness: f64,
+    pub corner: f64,
+    pub merges: f64,
+    pub multipliers: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights {
+            empty_cells: 2.7,
+            monotonicity: 1.0,
+            smoothness: 0.1,
+            corner: 1.0,
+            merges: 0.7,
+            multipliers: -0.5,
+        }
+    }
+}
+
+impl Weights {
+    /// The weights as `(name, weight)` pairs, in the order of
+    /// [`Features::named`]
+    #[must_use]
+    pub fn named(&self) -> [(&'static str, f64); 6] {
+        [
+            ("empty_cells", self.empty_cells),
+            ("monotonicity", self.monotonicity),
+            ("smoothness", self.smoothness),
+            ("corner", self.corner),
+            ("merges", self.merges),
+            ("multipliers", self.multipliers),
+        ]
+    }
+
+    /// The weights in field order
+    #[must_use]
+    pub fn to_array(&self) -> [f64; 6] {
+        [
+            self.empty_cells,
+            self.monotonicity,
+            self.smoothness,
+            self.corner,
+            self.merges,
+            self.multipliers,
+        ]
+    }
+
+    /// Inverse of [`to_array`](Self::to_array)
+    #[must_use]
+    pub fn from_array(
+        [
+            empty_cells,
+            monotonicity,
+            smoothness,
+            corner,
+            merges,
+            multipliers,
+        ]: [f64; 6],
+    ) -> Self {
+        Weights {
+            empty_cells,
+            monotonicity,
+            smoothness,
+            corner,
+            merges,
+            multipliers,
+        }
+    }
+}
+
+/// Weighted sum of the [`Features`] of `board`
+#[must_use]
+pub fn evaluate(board: &Board, weights: &Weights) -> f64 {
+    let f = Features::of(board);
+    f.empty_cells * weights.empty_cells
+        + f.monotonicity * weights.monotonicity
+        + f.smoothness * weights.smoothness
+        + f.corner * weights.corner
+        + f.merges * weights.merges
+        + f.multipliers * weights.multipliers
+}
+
+/// Expected evaluation of each legal move from `board`, looking `depth`
+/// moves ahead, in [`ALL_DIRECTIONS`](crate::ALL_DIRECTIONS) order; empty
+/// when no move is left.
+///
+/// Every move is followed by an average over all spawns, weighted by their
+/// probability; positions with no move left count as [`LOSS`]. Under
+/// [`Rules::spawn_every`] the first move spawns.
+///
+/// ```
+/// use akioi_2048_core::search::{Weights, expectimax};
+/// use akioi_2048_core::{Direction, Rules};
+///
+/// let board = [[0; 4], [0; 4], [0; 4], [0, 0, 2, 2]];
+/// let values = expectimax(&board, &Rules::AKIOI, 2, &Weights::default()).unwrap();
+/// assert_eq!(values.len(), 3);
+/// assert!(values.iter().all(|&(dir, _)| dir != Direction::Down));
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules or an invalid board.
+pub fn expectimax(
+    board: &Board,
+    rules: &Rules,
+    depth: u32,
+    weights: &Weights,
+) -> Result<Vec<(Direction, f64)>, String> {
+    crate::validate_board_with(board, rules)?;
+    let mut search = Search {
+        engine: Engine::new(*rules)?,
+        weights: *weights,
+        depth: depth.max(1),
+        cache: HashMap::new(),
+    };
+    Ok(search.moves(board, depth.max(1), 1.0))
+}
+
+struct Search {
+    engine: Engine,
+    weights: Weights,
+    /// Depth of the root, so a position's remaining depth tells the moves
+    /// before it
+    depth: u32,
+    /// Values of searched positions by remaining depth
+    cache: HashMap<(Board, u32), f64>,
+}
+
+impl Search {
+    fn moves(&mut self, board: &Board, depth: u32, probability: f64) -> Vec<(Direction, f64)> {
+        let legal: Vec<Direction> = self.engine.legal_moves(board).collect();
+        legal
+            .into_iter()
+            .map(|dir| {
+                let (slid, _) = self.engine.slide(board, dir);
+                (dir, self.chance(&slid, depth, probability))
+            })
+            .collect()
+    }
+
+    /// Expected value of the afterstate `slid` over its spawns
+    fn chance(&mut self, slid: &Board, depth: u32, probability: f64) -> f64 {
+        let rules = self.engine.rules();
+        let tiles = rules.tiles_after(u64::from(self.depth - depth));
+        spawn_outcomes_of(slid, &rules, tiles)
+            .into_iter()
+            .map(|(child, chance)| chance * self.max(&child, depth - 1, probability * chance))
+            .sum()
+    }
+
+    /// Value of `board` with the player to move
+    fn max(&mut self, board: &Board, depth: u32, probability: f64) -> f64 {
+        if depth == 0 || probability < MIN_PROBABILITY {
+            return if self.engine.is_over(board) {
+                LOSS
+            } else {
+                evaluate(board, &self.weights)
+            };
+        }
+        if let Some(&value) = self.cache.get(&(*board, depth)) {
+            return value;
+        }
+        let value = self
+            .moves(board, depth, probability)
+            .into_iter()
+            .map(|(_, v)| v)
+            .reduce(f64::max)
+            .unwrap_or(LOSS);
+        self.cache.insert((*board, depth), value);
+        value
+    }
+}
+
+/// Settings of [`search`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SearchOptions {
+    /// Deepest iteration, in moves
+    pub depth: u32,
+    /// Stop deepening once this much time has passed, keeping the deepest
+    /// completed iteration; depth 1 always completes
+    pub time_budget: Option<Duration>,
+    /// Spawn sequences less likely than this are evaluated as they stand
+    pub min_probability: f64,
+    /// Worker threads (default: one per core)
+    pub threads: Option<usize>,
+    /// Moves played before the position, which tells the moves that
+    /// spawn under [`Rules::spawn_every`]; 0 lets the first move spawn
+    pub moves_played: u64,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        SearchOptions {
+            depth: 3,
+            time_budget: None,
+            min_probability: MIN_PROBABILITY,
+            threads: None,
+            moves_played: 0,
+        }
+    }
+}
+
+/// Outcome of [`search`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    /// Expected evaluation of each legal move, as [`expectimax`] orders them
+    pub values: Vec<(Direction, f64)>,
+    /// Deepest iteration completed
+    pub depth: u32,
+    /// Positions in the transposition table at the end
+    pub positions: usize,
+}
+
+/// Iterative-deepening [`expectimax`] over every core, up to
+/// `options.depth` moves or until `options.time_budget` runs out.
+///
+/// The boards after each first move and spawn are shared out between the
+/// threads, which fill one transposition table. Spawn probabilities are
+/// tracked in whole powers of two, rounded to the nearest, so the value of
+/// a position depends only on the position, the depth left and that level:
+/// the table holds the same values whichever thread fills it, and results
+/// do not depend on the number of threads. Once pruning ends every line
+/// before the depth of an iteration, deeper ones cannot change the values
+/// and the search stops there, reporting `options.depth`.
+///
+/// ```
+/// use akioi_2048_core::search::{SearchOptions, Weights, search};
+/// use akioi_2048_core::Rules;
+///
+/// let board = [[0; 4], [0; 4], [0, 2, 0, 0], [0, 0, 2, 4]];
+/// let options = SearchOptions { depth: 3, ..SearchOptions::default() };
+/// let one = SearchOptions { threads: Some(1), ..options };
+/// let result = search(&board, &Rules::AKIOI, &Weights::default(), &options).unwrap();
+/// assert_eq!(result.depth, 3);
+/// assert_eq!(result, search(&board, &Rules::AKIOI, &Weights::default(), &one).unwrap());
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules, an invalid board, no threads
+/// or a `min_probability` outside `0..=1`.
+pub fn search(
+    board: &Board,
+    rules: &Rules,
+    weights: &Weights,
+    options: &SearchOptions,
+) -> Result<SearchResult, String> {
+    crate::validate_board_with(board, rules)?;
+    let engine = Engine::new(*rules)?;
+    if !(0.0..=1.0).contains(&options.min_probability) {
+        return Err(format!(
+            "min_probability must be between 0 and 1, got {}",
+            options.min_probability
+        ));
+    }
+    let threads = match options.threads {
+        Some(0) => return Err("threads must be positive".to_string()),
+        Some(n) => n,
+        None => std::thread::available_parallelism().map_or(1, usize::from),
+    };
+    let max_level = if options.min_probability > 0.0 {
+        (-options.min_probability.log2()).floor() as u32
+    } else {
+        u32::MAX
+    };
+    let start = Instant::now();
+    let deadline = options.time_budget.map(|budget| start + budget);
+
+    // Every first move, with every spawn after it
+    let mut roots = Vec::new();
+    let legal: Vec<Direction> = engine.legal_moves(board).collect();
+    for (i, &dir) in legal.iter().enumerate() {
+        let (slid, _) = engine.slide(board, dir);
+        let tiles = rules.tiles_after(options.moves_played);
+        for (child, chance) in spawn_outcomes_of(&slid, rules, tiles) {
+            roots.push((i, child, chance));
+        }
+    }
+    let table = Table::new();
+    let mut result = SearchResult {
+        values: Vec::new(),
+        depth: 0,
+        positions: 0,
+    };
+    for depth in 1..=options.depth.max(1) {
+        if depth > 1 && deadline.is_some_and(|d| Instant::now() >= d) {
+            break;
+        }
+        let stop = AtomicBool::new(false);
+        let next = AtomicUsize::new(0);
+        // summed in root order once all are in, so the threads' timing
+        // cannot change the rounding
+        let parts = Mutex::new(vec![0.0; roots.len()]);
+        let bottomed = AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            for _ in 0..threads.min(roots.len()) {
+                scope.spawn(|| {
+                    let mut worker = Worker {
+                        engine: &engine,
+                        weights,
+                        table: &table,
+                        max_level,
+                        moves_played: options.moves_played,
+                        depth,
+                        deadline: deadline.filter(|_| depth > 1),
+                        stop: &stop,
+                        visited: 0,
+                    };
+                    loop {
+                        let n = next.fetch_add(1, Ordering::Relaxed);
+                        let Some((_, child, chance)) = roots.get(n) else {
+                            break;
+                        };
+                        let (value, full) =
+                            worker.max(child, depth - 1, probability_level(*chance));
+                        if full {
+                            bottomed.store(true, Ordering::Relaxed);
+                        }
+                        parts.lock().expect("search threads do not panic")[n] = chance * value;
+                    }
+                });
+            }
+        });
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        let mut values = vec![0.0; legal.len()];
+        let parts = parts.into_inner().expect("search threads do not panic");
+        for (&(i, _, _), part) in roots.iter().zip(parts) {
+            values[i] += part;
+        }
+        result.values = legal.iter().copied().zip(values).collect();
+        result.depth = depth;
+        if !bottomed.into_inner() {
+            result.depth = options.depth.max(1);
+            break;
+        }
+    }
+    result.positions = table.len();
+    Ok(result)
+}
+
+/// `-log2(probability)` rounded to the nearest integer
+fn probability_level(probability: f64) -> u32 {
+    (-probability.log2()).round().max(0.0) as u32
+}
+
+/// A position, its remaining depth, its probability level and the moves
+/// before it modulo [`Rules::spawn_every`]
+type Key = (Board, u32, u32, u64);
+
+/// A value, and whether some line below it reached the full depth
+type Entry = (f64, bool);
+
+/// Entries by [`Key`]
+struct Table {
+    shards: Vec<Mutex<HashMap<Key, Entry>>>,
+    hasher: RandomState,
+}
+
+impl Table {
+    fn new() -> Self {
+        Table {
+            shards: (0..TABLE_SHARDS).map(|_| Mutex::default()).collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    fn shard(&self, key: &Key) -> &Mutex<HashMap<Key, Entry>> {
+        &self.shards[self.hasher.hash_one(key) as usize % TABLE_SHARDS]
+    }
+
+    fn get(&self, key: &Key) -> Option<Entry> {
+        let shard = self.shard(key).lock().expect("search threads do not panic");
+        shard.get(key).copied()
+    }
+
+    fn insert(&self, key: Key, entry: Entry) {
+        let mut shard = self
+            .shard(&key)
+            .lock()
+            .expect("search threads do not panic");
+        shard.insert(key, entry);
+    }
+
+    fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|s| s.lock().expect("search threads do not panic").len())
+            .sum()
+    }
+}
+
+struct Worker<'a> {
+    engine: &'a Engine,
+    weights: &'a Weights,
+    table: &'a Table,
+    max_level: u32,
+    /// Moves before the root, and the depth of this iteration: a position
+    /// with `d` moves left has `moves_played + depth - d` before it
+    moves_played: u64,
+    depth: u32,
+    deadline: Option<Instant>,
+    /// Set once the deadline passes; values computed after it are dropped
+    stop: &'a AtomicBool,
+    visited: u32,
+}
+
+impl Worker<'_> {
+    fn stopped(&mut self) -> bool {
+        if self.stop.load(Ordering::Relaxed) {
+            return true;
+        }
+        self.visited += 1;
+        if self.visited.is_multiple_of(CLOCK_INTERVAL)
+            && self.deadline.is_some_and(|d| Instant::now() >= d)
+        {
+            self.stop.store(true, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+
+    /// Value of `board` with the player to move, `level` being the
+    /// probability level of the spawns that led to it, and whether some
+    /// line below it reached `depth`
+    fn max(&mut self, board: &Board, depth: u32, level: u32) -> Entry {
+        if depth == 0 || level > self.max_level {
+            let value = if self.engine.is_over(board) {
+                LOSS
+            } else {
+                evaluate(board, self.weights)
+            };
+            return (value, depth == 0);
+        }
+        let rules = self.engine.rules();
+        let moves = self.moves_played + u64::from(self.depth - depth);
+        let key = (*board, depth, level, moves % u64::from(rules.spawn_every));
+        if let Some(entry) = self.table.get(&key) {
+            return entry;
+        }
+        if self.stopped() {
+            return (0.0, true);
+        }
+        let legal: Vec<Direction> = self.engine.legal_moves(board).collect();
+        let mut full = false;
+        let value = legal
+            .into_iter()
+            .map(|dir| {
+                let (slid, _) = self.engine.slide(board, dir);
+                let mut value = 0.0;
+                let tiles = rules.tiles_after(moves);
+                for (child, chance) in spawn_outcomes_of(&slid, &rules, tiles) {
+                    let (v, f) = self.max(&child, depth - 1, level + probability_level(chance));
+                    value += chance * v;
+                    full |= f;
+                }
+                value
+            })
+            .reduce(f64::max)
+            .unwrap_or(LOSS);
+        // the stop flag never clears, so a value finished before it is set
+        // was searched in full
+        if !self.stop.load(Ordering::Relaxed) {
+            self.table.insert(key, (value, full));
+        }
+        (value, full)
+    }
+}
+
+/// Worst-case outcome of one move, against spawns chosen by an adversary
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorstCase {
+    pub direction: Direction,
+    /// Evaluation of the position the adversary can hold the player to;
+    /// below `LOSS / 2` when it can force a dead board
+    pub value: f64,
+    /// Moves the player is sure to make, this one included, whatever
+    /// spawns; the search depth when no dead board can be forced
+    pub guaranteed_moves: u32,
+}
+
+/// The worst case of each legal move from `board`, looking `depth` moves
+/// ahead with every spawn chosen to hurt the player most, in
+/// [`ALL_DIRECTIONS`](crate::ALL_DIRECTIONS) order.
+///
+/// Any spawn the rules allow on any empty cell is considered, however
+/// unlikely, so `guaranteed_moves` is a bound on survival that no luck can
+/// break. A dead board counts as [`LOSS`] plus the moves made before it, so
+/// the player prefers to lose later. Under [`Rules::spawn_every`] the first
+/// move spawns.
+///
+/// ```
+/// use akioi_2048_core::search::{Weights, worst_case};
+/// use akioi_2048_core::Rules;
+///
+/// let board = [[0; 4], [0; 4], [0; 4], [0, 0, 2, 2]];
+/// let moves = worst_case(&board, &Rules::CLASSIC, 2, &Weights::default()).unwrap();
+/// assert_eq!(moves.len(), 3);
+/// assert!(moves.iter().all(|m| m.guaranteed_moves == 2));
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules or an invalid board.
+pub fn worst_case(
+    board: &Board,
+    rules: &Rules,
+    depth: u32,
+    weights: &Weights,
+) -> Result<Vec<WorstCase>, String> {
+    crate::validate_board_with(board, rules)?;
+    let depth = depth.max(1);
+    let adversary = Adversary {
+        engine: Engine::new(*rules)?,
+        weights: *weights,
+        depth,
+    };
+    let legal: Vec<Direction> = adversary.engine.legal_moves(board).collect();
+    Ok(legal
+        .into_iter()
+        .map(|direction| {
+            let (slid, _) = adversary.engine.slide(board, direction);
+            let value = adversary.min(&slid, depth, f64::NEG_INFINITY, f64::INFINITY);
+            let guaranteed_moves = if value < LOSS / 2.0 {
+                (value - LOSS).round() as u32
+            } else {
+                depth
+            };
+            WorstCase {
+                direction,
+                value,
+                guaranteed_moves,
+            }
+        })
+        .collect())
+}
+
+struct Adversary {
+    engine: Engine,
+    weights: Weights,
+    depth: u32,
+}
+
+impl Adversary {
+    /// Value of `board` with the player to move and `depth` moves left
+    fn max(&self, board: &Board, depth: u32, mut alpha: f64, beta: f64) -> f64 {
+        if self.engine.is_over(board) {
+            return LOSS + f64::from(self.depth - depth);
+        }
+        if depth == 0 {
+            return evaluate(board, &self.weights);
+        }
+        let mut best = f64::NEG_INFINITY;
+        for dir in self.engine.legal_moves(board) {
+            let (slid, _) = self.engine.slide(board, dir);
+            best = best.max(self.min(&slid, depth, alpha, beta));
+            alpha = alpha.max(best);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Value of the afterstate `slid` under the spawn worst for the player
+    fn min(&self, slid: &Board, depth: u32, alpha: f64, mut beta: f64) -> f64 {
+        let rules = self.engine.rules();
+        let tiles = rules.tiles_after(u64::from(self.depth - depth));
+        let mut worst = f64::INFINITY;
+        for (child, _) in spawn_outcomes_of(slid, &rules, tiles) {
+            worst = worst.min(self.max(&child, depth - 1, alpha, beta));
+            beta = beta.min(worst);
+            if alpha >= beta {
+                break;
+            }
+        }
+        worst
+    }
+}
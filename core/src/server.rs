@@ -0,0 +1,536 @@
+//! Long-running engine server speaking JSON-RPC 2.0, one message per line.
+//!
+//! Started with `akioi-2048 serve`, over stdin/stdout or TCP, so programs
+//! in any language can drive games without linking the crate. Each
+//! connection owns its games, addressed by the id `new_game` returns.
+//!
+//! | method | params | result |
+//! |---|---|---|
+//! | `new_game` | `seed?`, `rules?` | `game`, `seed`, `rules`, `board`, `score`, `state` |
+//! | `step` | `game`, `direction` | `board`, `delta`, `moved`, `score`, `state`, `spawn` |
+//! | `suggest` | `game` or `board` + `rules?`, `depth?`, `time_budget?` | `direction`, `values`, `summary`, `depth` |
+//! | `state` | `game` | `board`, `score`, `state`, `moves` |
+//! | `replay` | `game` | `replay` (the text of [`Replay::to_text`](crate::replay::Replay::to_text)) |
+//! | `close` | `game` | `true` |
+//! | `rules_spec` | `rules?`, `invalid_moves?` | the ruleset as data, see [`spec`](crate::spec::spec) |
+//! | `simulate` | `seed?`, `rules?`, `depth?`, `max_moves?` | `seed`, `rules`, `max_tile`, `board`, `score`, `state`, `moves`, `replay` |
+//!
+//! Directions are names (`"up"`, any case) or action ids 0–3 in the order
+//! of [`ALL_DIRECTIONS`]; states are `"Continue"`, `"Victory"` or
+//! `"GameOver"`. An array of requests is a batch and gets an array of
+//! responses; requests without an `id` are notifications and get none.
+//! `suggest` without a `time_budget` and `simulate` search at most 6
+//! plies deep.
+//! A request line holds at most [`MAX_LINE`] bytes and nests at most 128
+//! arrays and objects; past either it is answered with an error and the
+//! connection goes on.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, ToSocketAddrs};
+use std::thread;
+use std::time::Duration;
+
+use crate::hint::hint_with;
+use crate::json::Json;
+use crate::search::{SearchOptions, Weights, expectimax};
+use crate::session::InvalidMoves;
+use crate::spec::spec;
+use crate::{ALL_DIRECTIONS, Board, Direction, Game, Rules, validate_board_with};
+
+/// Search depth of `suggest` when none is given
+const DEFAULT_DEPTH: u32 = 2;
+/// Search depth of `simulate` when none is given, cheap enough for whole games
+const SIMULATE_DEPTH: u32 = 1;
+/// Moves `simulate` plays at most when not told otherwise
+const MAX_SIMULATED_MOVES: usize = 20_000;
+/// Deepest search `suggest` without a time budget and `simulate` run;
+/// each ply costs the previous depth times the spawns and moves of a board
+const MAX_DEPTH: u32 = 6;
+
+/// Longest request line [`Server::serve`] reads, in bytes
+pub const MAX_LINE: usize = 1 << 20;
+
+/// Every method [`Server`] answers, for the HTTP front to tell unknown
+/// paths apart
+#[cfg(feature = "http")]
+pub(crate) const METHODS: [&str; 8] = [
+    "new_game",
+    "step",
+    "suggest",
+    "simulate",
+    "rules_spec",
+    "state",
+    "replay",
+    "close",
+];
+
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+/// The games of one connection
+///
+/// ```
+/// use akioi_2048_core::server::Server;
+///
+/// let mut server = Server::new();
+/// let reply = server
+///     .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"new_game","params":{"seed":7}}"#)
+///     .unwrap();
+/// assert!(reply.starts_with(r#"{"jsonrpc":"2.0","id":1,"result":{"game":1,"seed":7,"#));
+/// let reply = server
+///     .handle_line(r#"{"jsonrpc":"2.0","id":2,"method":"step","params":{"game":9,"direction":"up"}}"#)
+///     .unwrap();
+/// assert!(reply.contains(r#""error":{"code":-32602,"message":"unknown game: 9"}"#));
+/// ```
+#[derive(Default)]
+pub struct Server {
+    games: HashMap<u64, Game>,
+    next_id: u64,
+}
+
+impl Server {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The response to one line of input, or `None` for a blank line or
+    /// notifications only
+    pub fn handle_line(&mut self, line: &str) -> Option<String> {
+        if line.trim().is_empty() {
+            return None;
+        }
+        let response = match Json::parse(line, "request") {
+            Err(e) => Some(error(Json::Null, PARSE_ERROR, e)),
+            Ok(Json::Array(requests)) if requests.is_empty() => Some(error(
+                Json::Null,
+                INVALID_REQUEST,
+                "empty batch".to_string(),
+            )),
+            Ok(Json::Array(requests)) => {
+                let responses: Vec<Json> = requests
+                    .iter()
+                    .filter_map(|request| self.handle(request))
+                    .collect();
+                (!responses.is_empty()).then_some(Json::Array(responses))
+            }
+            Ok(request) => self.handle(&request),
+        };
+        response.map(|r| r.to_string())
+    }
+
+    /// Answer every line of `reader` on `writer` until end of input; a line
+    /// longer than [`MAX_LINE`] bytes is answered with an error and skipped
+    /// without being buffered whole
+    ///
+    /// # Errors
+    /// Returns any error reading or writing.
+    pub fn serve(&mut self, mut reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let limit = MAX_LINE as u64 + 1;
+            if (&mut reader).take(limit).read_until(b'\n', &mut line)? == 0 {
+                return Ok(());
+            }
+            let response = if line.len() > MAX_LINE && line.last() != Some(&b'\n') {
+                skip_line(&mut reader)?;
+                let message = format!("a request line holds at most {MAX_LINE} bytes");
+                Some(error(Json::Null, INVALID_REQUEST, message).to_string())
+            } else {
+                match std::str::from_utf8(&line) {
+                    Ok(text) => self.handle_line(text.trim_end_matches(['\n', '\r'])),
+                    Err(_) => {
+                        let message = "a request line must be UTF-8".to_string();
+                        Some(error(Json::Null, PARSE_ERROR, message).to_string())
+                    }
+                }
+            };
+            if let Some(response) = response {
+                writeln!(writer, "{response}")?;
+                writer.flush()?;
+            }
+        }
+    }
+
+    fn handle(&mut self, request: &Json) -> Option<Json> {
+        let Some(id) = request.get("id").cloned() else {
+            // a notification: run it, but answer nothing
+            let _ = self.call(request);
+            return None;
+        };
+        Some(match self.call(request) {
+            Ok(result) => Json::object([("jsonrpc", "2.0".into()), ("id", id), ("result", result)]),
+            Err((code, message)) => error(id, code, message),
+        })
+    }
+
+    fn call(&mut self, request: &Json) -> Result<Json, (i32, String)> {
+        let invalid = |message: &str| (INVALID_REQUEST, message.to_string());
+        if !matches!(request, Json::Object(_)) {
+            return Err(invalid("a request must be an object"));
+        }
+        let method = request
+            .get("method")
+            .and_then(|m| m.str().ok())
+            .ok_or_else(|| invalid("a request needs a `method` string"))?;
+        let empty = Json::Object(Vec::new());
+        let params = match request.get("params") {
+            None => &empty,
+            Some(params @ Json::Object(_)) => params,
+            Some(_) => return Err(invalid("`params` must be an object")),
+        };
+        self.dispatch(method, params)
+    }
+
+    /// Run `method` with the object `params`
+    pub(crate) fn dispatch(&mut self, method: &str, params: &Json) -> Result<Json, (i32, String)> {
+        let params_error = |message: String| (INVALID_PARAMS, message);
+        match method {
+            "new_game" => self.new_game(params),
+            "step" => self.step(params),
+            "suggest" => self.suggest(params),
+            "simulate" => simulate(params),
+            "rules_spec" => rules_spec(params),
+            "state" => self.game(params).map(|(_, game)| position(game)),
+            "replay" => self
+                .game(params)
+                .map(|(_, game)| Json::object([("replay", game.replay().to_text().into())])),
+            "close" => self.game(params).map(|(id, _)| id).map(|id| {
+                self.games.remove(&id);
+                Json::Bool(true)
+            }),
+            _ => return Err((METHOD_NOT_FOUND, format!("unknown method: {method}"))),
+        }
+        .map_err(params_error)
+    }
+
+    fn new_game(&mut self, params: &Json) -> Result<Json, String> {
+        let seed = seed_param(params)?;
+        let rules = rules_param(params)?;
+        let game = Game::with_rules(seed, rules)?;
+        self.next_id += 1;
+        let mut result = vec![
+            ("game".to_string(), self.next_id.into()),
+            ("seed".to_string(), seed.into()),
+            ("rules".to_string(), rules.tag().into()),
+        ];
+        if let Json::Object(fields) = position(&game) {
+            result.extend(fields);
+        }
+        self.games.insert(self.next_id, game);
+        Ok(Json::Object(result))
+    }
+
+    fn step(&mut self, params: &Json) -> Result<Json, String> {
+        let direction = direction_param(params.field("direction")?)?;
+        let id = game_id(params)?;
+        let game = self
+            .games
+            .get_mut(&id)
+            .ok_or_else(|| format!("unknown game: {id}"))?;
+        let result = game.try_step(direction)?;
+        let spawn = result.spawn.map_or(Json::Null, |s| {
+            Json::object([
+                ("row", s.cell.0.into()),
+                ("col", s.cell.1.into()),
+                ("value", s.value.into()),
+            ])
+        });
+        Ok(Json::object([
+            ("board", board_json(&result.board)),
+            ("delta", result.delta.into()),
+            ("moved", result.moved.into()),
+            ("score", game.score().into()),
+            ("state", format!("{:?}", result.state).into()),
+            ("spawn", spawn),
+        ]))
+    }
+
+    fn suggest(&mut self, params: &Json) -> Result<Json, String> {
+        let (board, rules) = self.suggest_position(params)?;
+        suggest(&board, &rules, params)
+    }
+
+    /// The board `suggest` searches and its rules: `board` and `rules`
+    /// when given, or else those of the game `game`
+    pub(crate) fn suggest_position(&self, params: &Json) -> Result<(Board, Rules), String> {
+        match params.get("board") {
+            Some(board) => {
+                let rules = rules_param(params)?;
+                let board = board_param(board)?;
+                validate_board_with(&board, &rules)?;
+                Ok((board, rules))
+            }
+            None => {
+                let (_, game) = self.game(params)?;
+                Ok((game.board(), game.rules()))
+            }
+        }
+    }
+
+    fn game(&self, params: &Json) -> Result<(u64, &Game), String> {
+        let id = game_id(params)?;
+        self.games
+            .get(&id)
+            .map(|game| (id, game))
+            .ok_or_else(|| format!("unknown game: {id}"))
+    }
+}
+
+/// The best move on `board` under `rules` by the search `params` ask for
+pub(crate) fn suggest(board: &Board, rules: &Rules, params: &Json) -> Result<Json, String> {
+    let depth = match params.get("depth") {
+        Some(depth) => depth.integer().map_err(|e| format!("`depth`: {e}"))?,
+        None => DEFAULT_DEPTH,
+    };
+    let time_budget = match params.get("time_budget") {
+        Some(seconds) => {
+            let seconds = seconds
+                .number()
+                .map_err(|e| format!("`time_budget`: {e}"))?;
+            Some(Duration::try_from_secs_f64(seconds).map_err(|_| {
+                format!("`time_budget` must be a duration in seconds, got {seconds}")
+            })?)
+        }
+        None => None,
+    };
+    if time_budget.is_none() {
+        check_depth(depth)?;
+    }
+    let options = SearchOptions {
+        depth,
+        time_budget,
+        ..SearchOptions::default()
+    };
+    Ok(match hint_with(board, rules, &options)? {
+        None => Json::object([
+            ("direction", Json::Null),
+            ("values", Json::Object(Vec::new())),
+            ("summary", "No move is left.".into()),
+            ("depth", 0.into()),
+        ]),
+        Some(hint) => Json::object([
+            ("direction", direction_name(hint.direction).into()),
+            (
+                "values",
+                Json::Object(
+                    hint.values
+                        .iter()
+                        .map(|&(d, v)| (direction_name(d).to_string(), v.into()))
+                        .collect(),
+                ),
+            ),
+            ("summary", hint.summary.into()),
+            ("depth", hint.depth.into()),
+        ]),
+    })
+}
+
+/// Serve connections on `addr`, each on its own thread with its own games
+///
+/// # Errors
+/// Returns an error if `addr` cannot be bound.
+pub fn serve_tcp(addr: impl ToSocketAddrs) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    eprintln!("listening on {}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("connection failed: {e}");
+                continue;
+            }
+        };
+        thread::spawn(move || {
+            let reader = match stream.try_clone() {
+                Ok(reader) => BufReader::new(reader),
+                Err(e) => return eprintln!("connection failed: {e}"),
+            };
+            if let Err(e) = Server::new().serve(reader, stream) {
+                eprintln!("connection closed: {e}");
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Consume `reader` up to and including the next newline, a buffer at a
+/// time
+fn skip_line(reader: &mut impl BufRead) -> io::Result<()> {
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(());
+        }
+        if let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            reader.consume(end + 1);
+            return Ok(());
+        }
+        let read = buffer.len();
+        reader.consume(read);
+    }
+}
+
+fn error(id: Json, code: i32, message: String) -> Json {
+    Json::object([
+        ("jsonrpc", "2.0".into()),
+        ("id", id),
+        (
+            "error",
+            Json::object([("code", code.into()), ("message", message.into())]),
+        ),
+    ])
+}
+
+fn position(game: &Game) -> Json {
+    Json::object([
+        ("board", board_json(&game.board())),
+        ("score", game.score().into()),
+        ("state", format!("{:?}", game.state()).into()),
+        ("moves", game.moves().into()),
+    ])
+}
+
+fn board_json(board: &Board) -> Json {
+    Json::Array(board.iter().map(|row| row.to_vec().into()).collect())
+}
+
+/// Play a whole game on the server, each move the best of an expectimax
+/// search `depth` deep, for at most `max_moves` moves
+pub(crate) fn simulate(params: &Json) -> Result<Json, String> {
+    let seed = seed_param(params)?;
+    let rules = rules_param(params)?;
+    let depth = match params.get("depth") {
+        Some(depth) => depth.integer().map_err(|e| format!("`depth`: {e}"))?,
+        None => SIMULATE_DEPTH,
+    };
+    check_depth(depth)?;
+    let max_moves: usize = match params.get("max_moves") {
+        Some(n) => n.integer().map_err(|e| format!("`max_moves`: {e}"))?,
+        None => MAX_SIMULATED_MOVES,
+    };
+    let mut game = Game::with_rules(seed, rules)?;
+    let weights = Weights::default();
+    while !game.is_over() && game.moves() < max_moves {
+        let values = expectimax(&game.board(), &rules, depth, &weights)?;
+        let Some(&(direction, _)) = values.iter().max_by(|a, b| a.1.total_cmp(&b.1)) else {
+            break;
+        };
+        game.try_step(direction)?;
+    }
+    let max_tile = game.board().iter().flatten().copied().max().unwrap_or(0);
+    let mut result = vec![
+        ("seed".to_string(), seed.into()),
+        ("rules".to_string(), rules.tag().into()),
+        ("max_tile".to_string(), max_tile.into()),
+    ];
+    if let Json::Object(fields) = position(&game) {
+        result.extend(fields);
+    }
+    result.push(("replay".to_string(), game.replay().to_text().into()));
+    Ok(Json::Object(result))
+}
+
+/// Refuse a search deeper than [`MAX_DEPTH`] that no time budget bounds
+fn check_depth(depth: u32) -> Result<(), String> {
+    if depth > MAX_DEPTH {
+        return Err(format!(
+            "`depth` is at most {MAX_DEPTH} without a `time_budget`, got {depth}"
+        ));
+    }
+    Ok(())
+}
+
+/// Describe the `rules` and `invalid_moves` policy, by default `"noop"`
+fn rules_spec(params: &Json) -> Result<Json, String> {
+    let rules = rules_param(params)?;
+    let invalid_moves = match params.get("invalid_moves") {
+        Some(name) => {
+            let name = name.str().map_err(|e| format!("`invalid_moves`: {e}"))?;
+            InvalidMoves::from_name(name)
+                .ok_or_else(|| format!("unknown invalid_moves policy: {name}"))?
+        }
+        None => InvalidMoves::Noop,
+    };
+    Ok(spec(&rules, invalid_moves)?.to_json())
+}
+
+/// `seed`, or a random one within the integers a JSON number holds exactly
+fn seed_param(params: &Json) -> Result<u64, String> {
+    match params.get("seed") {
+        Some(seed) => seed.integer().map_err(|e| format!("`seed`: {e}")),
+        None => Ok(crate::session::random_seed() >> 11),
+    }
+}
+
+fn game_id(params: &Json) -> Result<u64, String> {
+    params
+        .field("game")?
+        .integer()
+        .map_err(|e| format!("`game`: {e}"))
+}
+
+/// `rules` as a preset name or rules tag, the default rules if absent
+fn rules_param(params: &Json) -> Result<Rules, String> {
+    let Some(rules) = params.get("rules") else {
+        return Ok(Rules::default());
+    };
+    let name = rules.str().map_err(|e| format!("`rules`: {e}"))?;
+    match Rules::preset(name) {
+        Some(preset) => Ok(preset),
+        None => Rules::from_tag(name),
+    }
+}
+
+pub(crate) fn board_param(board: &Json) -> Result<Board, String> {
+    let invalid = || "`board` must be 4 rows of 4 integers".to_string();
+    let Json::Array(rows) = board else {
+        return Err(invalid());
+    };
+    if rows.len() != 4 {
+        return Err(invalid());
+    }
+    let mut out = [[0; 4]; 4];
+    for (r, row) in rows.iter().enumerate() {
+        let Json::Array(cells) = row else {
+            return Err(invalid());
+        };
+        if cells.len() != 4 {
+            return Err(invalid());
+        }
+        for (c, cell) in cells.iter().enumerate() {
+            out[r][c] = cell.integer().map_err(|_| invalid())?;
+        }
+    }
+    Ok(out)
+}
+
+fn direction_param(direction: &Json) -> Result<Direction, String> {
+    if let Json::Number(_) = direction {
+        let id: usize = direction
+            .integer()
+            .map_err(|e| format!("`direction`: {e}"))?;
+        return ALL_DIRECTIONS
+            .get(id)
+            .copied()
+            .ok_or_else(|| format!("action id must be between 0 and 3, got: {id}"));
+    }
+    let name = direction.str().map_err(|e| format!("`direction`: {e}"))?;
+    ALL_DIRECTIONS
+        .into_iter()
+        .find(|&d| direction_name(d).eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("unknown direction: {name}"))
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
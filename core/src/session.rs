@@ -0,0 +1,857 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::detail::{MoveResult, Spawn, step_detailed_after, step_detailed_with};
+use crate::game::{spawn_tile, state_of};
+use crate::proof::{MoveRecord, ProofChain};
+use crate::replay::{Replay, Start};
+use crate::{
+    ALL_DIRECTIONS, Board, Direction, EndReason, Engine, Rules, State, Status, validate_board_with,
+};
+
+/// What a [`Game`] does with a move that would not change the board
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum InvalidMoves {
+    /// Leave the game untouched
+    #[default]
+    Noop,
+    /// Leave the game untouched; an [`Env`](crate::env::Env) also
+    /// subtracts its `invalid_penalty` from the reward
+    Penalize,
+    /// Reject the move: [`Game::try_step`] returns an error
+    Mask,
+    /// Play a random legal move instead
+    AutoResolve,
+}
+
+impl InvalidMoves {
+    /// Look up a policy by its name (`"noop"`, `"penalize"`, `"mask"` or
+    /// `"auto-resolve"`)
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<InvalidMoves> {
+        match name {
+            "noop" => Some(InvalidMoves::Noop),
+            "penalize" => Some(InvalidMoves::Penalize),
+            "mask" => Some(InvalidMoves::Mask),
+            "auto-resolve" => Some(InvalidMoves::AutoResolve),
+            _ => None,
+        }
+    }
+
+    /// Inverse of [`InvalidMoves::from_name`]
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            InvalidMoves::Noop => "noop",
+            InvalidMoves::Penalize => "penalize",
+            InvalidMoves::Mask => "mask",
+            InvalidMoves::AutoResolve => "auto-resolve",
+        }
+    }
+}
+
+/// Time controls of a [`Game`]: a clock that runs between moves, like a
+/// chess clock with one side
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeControl {
+    /// Time on the clock when the game is built
+    pub total: Duration,
+    /// Added to the clock after every move played in time
+    pub increment: Duration,
+    /// What a move made after the clock ran out does
+    pub on_timeout: Timeout,
+}
+
+/// What a [`Game`] does with a move made after its clock ran out
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Timeout {
+    /// Refuse the move: [`Game::try_step`] returns an error
+    #[default]
+    Reject,
+    /// End the game: the move is not played and the game is lost with
+    /// [`EndReason::OutOfTime`]
+    Forfeit,
+}
+
+impl Timeout {
+    /// Look up a policy by its name (`"reject"` or `"forfeit"`)
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Timeout> {
+        match name {
+            "reject" => Some(Timeout::Reject),
+            "forfeit" => Some(Timeout::Forfeit),
+            _ => None,
+        }
+    }
+}
+
+/// A running [`TimeControl`]
+#[derive(Clone, Copy, Debug)]
+struct Clock {
+    control: TimeControl,
+    /// Time left when the clock was last wound, at `since`
+    left: Duration,
+    since: Instant,
+    /// The game was forfeited on time
+    forfeited: bool,
+}
+
+impl Clock {
+    fn time_left(&self) -> Duration {
+        self.left.saturating_sub(self.since.elapsed())
+    }
+}
+
+/// Wall-clock time of one move of a [`Game`] with
+/// [`GameBuilder::timings`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveTiming {
+    /// From the end of the previous move (or the start of the game) to this
+    /// one: the time the player took to decide
+    pub decision: Duration,
+    /// Spent playing the move, hooks excluded
+    pub engine: Duration,
+}
+
+/// Aggregates of a series of durations, such as one field of every
+/// [`MoveTiming`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TimingStats {
+    pub count: usize,
+    pub total: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    /// 95th percentile, the nearest-rank way
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl TimingStats {
+    /// Aggregate `times`; all zero for none
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use akioi_2048_core::TimingStats;
+    ///
+    /// let stats = TimingStats::of((1..=20).map(Duration::from_millis));
+    /// assert_eq!(stats.total, Duration::from_millis(210));
+    /// assert_eq!(stats.median, Duration::from_millis(10));
+    /// assert_eq!(stats.p95, Duration::from_millis(19));
+    /// assert_eq!(stats.max, Duration::from_millis(20));
+    /// ```
+    #[must_use]
+    pub fn of(times: impl IntoIterator<Item = Duration>) -> TimingStats {
+        let mut times: Vec<Duration> = times.into_iter().collect();
+        if times.is_empty() {
+            return TimingStats::default();
+        }
+        times.sort_unstable();
+        let count = times.len();
+        let rank = |q: usize| times[(count * q).div_ceil(100).max(1) - 1];
+        let total: Duration = times.iter().sum();
+        TimingStats {
+            count,
+            total,
+            mean: total / u32::try_from(count).unwrap_or(u32::MAX),
+            median: rank(50),
+            p95: rank(95),
+            max: times[count - 1],
+        }
+    }
+}
+
+/// Times the moves of a [`Game`]
+#[derive(Clone, Debug)]
+struct Timer {
+    /// End of the last move, or the start of the game
+    since: Instant,
+    /// Shared with clones and snapshots until the next move
+    moves: Arc<Vec<MoveTiming>>,
+}
+
+/// Callback run after every move with the direction played and its result
+pub type MoveHook = Box<dyn FnMut(Direction, &MoveResult) + Send + Sync>;
+
+/// A seeded game in progress: board, score and the RNG that drives spawns.
+///
+/// Two games created with the same seed and rules and fed the same moves
+/// are identical. Clones share the game state but not the hooks, so
+/// lookahead on a clone never fires them.
+pub struct Game {
+    board: Board,
+    score: i64,
+    state: State,
+    moves: usize,
+    rules: Rules,
+    seed: u64,
+    /// Constructed starting position, if the board was not dealt from `seed`
+    start: Option<Start>,
+    rng: StdRng,
+    /// Spawn draws so far: the two initial tiles, then one per moving step,
+    /// scripted or drawn from `rng`, so proof records stay in sequence
+    draws: u64,
+    proofs: Option<Arc<ProofChain>>,
+    /// Directions of accepted moves, enough to replay the game; shared
+    /// with clones and snapshots until the next move
+    history: Arc<Vec<Direction>>,
+    invalid_moves: InvalidMoves,
+    /// Play on after a victory instead of ending the game
+    keep_playing: bool,
+    /// A victory has been reported
+    won: bool,
+    /// Picks replacements for invalid moves; kept apart from `rng` so
+    /// auto-resolved games still replay from their history
+    resolver: StdRng,
+    /// Spawns still to place instead of drawing from `rng`
+    script: VecDeque<Spawn>,
+    clock: Option<Clock>,
+    timer: Option<Timer>,
+    hooks: Vec<MoveHook>,
+}
+
+impl Clone for Game {
+    fn clone(&self) -> Self {
+        Self {
+            board: self.board,
+            score: self.score,
+            state: self.state,
+            moves: self.moves,
+            rules: self.rules,
+            seed: self.seed,
+            start: self.start,
+            rng: self.rng.clone(),
+            draws: self.draws,
+            proofs: self.proofs.clone(),
+            history: self.history.clone(),
+            invalid_moves: self.invalid_moves,
+            keep_playing: self.keep_playing,
+            won: self.won,
+            resolver: self.resolver.clone(),
+            script: self.script.clone(),
+            clock: self.clock,
+            timer: self.timer.clone(),
+            hooks: Vec::new(),
+        }
+    }
+}
+
+impl Game {
+    /// Configure a new game; see [`GameBuilder`]
+    #[must_use]
+    pub fn builder() -> GameBuilder {
+        GameBuilder::default()
+    }
+
+    /// Start a standard-rules game whose spawns derive from `seed`
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self::with_rules(seed, Rules::AKIOI).expect("standard rules are valid")
+    }
+
+    /// Start a game under `rules`
+    ///
+    /// # Errors
+    /// Returns an error if the rules are inconsistent.
+    pub fn with_rules(seed: u64, rules: Rules) -> Result<Self, String> {
+        Self::builder().seed(seed).rules(rules).build()
+    }
+
+    /// Chain a [`MoveRecord`] to every accepted move from now on
+    #[must_use]
+    pub fn with_proofs(mut self) -> Self {
+        self.proofs = Some(Arc::new(ProofChain::new()));
+        self
+    }
+
+    /// Apply one move; invalid moves are handled by the game's
+    /// [`InvalidMoves`] policy (under [`InvalidMoves::Mask`] they leave the
+    /// game untouched, see [`try_step`](Self::try_step)).
+    ///
+    /// # Panics
+    /// Panics if the next [scripted spawn](GameBuilder::spawns) lands on an
+    /// occupied cell, or the clock ran out under [`Timeout::Reject`];
+    /// [`try_step`](Self::try_step) reports these as errors.
+    pub fn step(&mut self, direction: Direction) -> MoveResult {
+        if let Some(result) = self.check_clock().unwrap_or_else(|e| panic!("{e}")) {
+            return result;
+        }
+        let direction = self.resolve(direction);
+        self.apply(direction).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// [`step`](Self::step) that refuses invalid moves under
+    /// [`InvalidMoves::Mask`] instead of counting them
+    ///
+    /// # Errors
+    /// Returns an error, leaving the game untouched, if the policy is
+    /// [`InvalidMoves::Mask`] and `direction` does not change the board,
+    /// the next scripted spawn lands on an occupied cell, or the clock ran
+    /// out under [`Timeout::Reject`].
+    pub fn try_step(&mut self, direction: Direction) -> Result<MoveResult, String> {
+        if let Some(result) = self.check_clock()? {
+            return Ok(result);
+        }
+        if self.invalid_moves == InvalidMoves::Mask && !self.is_legal(direction) {
+            return Err(format!(
+                "invalid move: {direction:?} does not change the board"
+            ));
+        }
+        let direction = self.resolve(direction);
+        self.apply(direction)
+    }
+
+    /// Charge the time since the last move to the clock. A game forfeited
+    /// on time returns its final result instead of playing the move.
+    fn check_clock(&mut self) -> Result<Option<MoveResult>, String> {
+        let Some(clock) = &mut self.clock else {
+            return Ok(None);
+        };
+        if !clock.forfeited && clock.time_left().is_zero() {
+            match clock.control.on_timeout {
+                Timeout::Reject => return Err("out of time: the clock has run out".to_string()),
+                Timeout::Forfeit => {
+                    clock.forfeited = true;
+                    self.state = State::GameOver;
+                }
+            }
+        }
+        if clock.forfeited {
+            return Ok(Some(MoveResult {
+                board: self.board,
+                delta: 0,
+                state: State::GameOver,
+                moved: false,
+                merges: Vec::new(),
+                spawn: None,
+                extra_spawns: Vec::new(),
+            }));
+        }
+        let now = Instant::now();
+        clock.left = clock.left.saturating_sub(now - clock.since) + clock.control.increment;
+        clock.since = now;
+        Ok(None)
+    }
+
+    fn apply(&mut self, direction: Direction) -> Result<MoveResult, String> {
+        let started = self.timer.is_some().then(Instant::now);
+        let draw_index = self.draws;
+        let moves = self.history.len() as u64;
+        let scripted = !self.script.is_empty();
+        let mut result = if scripted {
+            self.scripted_step(direction, moves)?
+        } else {
+            step_detailed_after(self.board, direction, &self.rules, &mut self.rng, moves)
+                .expect("game boards are always valid")
+        };
+        if result.state == State::Victory && self.keep_playing && self.won {
+            result.state = self.status_after_win(&result.board).state;
+        }
+        self.won |= result.state == State::Victory;
+        if result.moved {
+            self.draws += 1;
+            Arc::make_mut(&mut self.history).push(direction);
+            if let Some(chain) = &mut self.proofs {
+                Arc::make_mut(chain).push(&self.board, direction, &result.board, draw_index);
+            }
+        }
+        self.board = result.board;
+        self.score += i64::from(result.delta);
+        self.state = result.state;
+        self.moves += 1;
+        if let (Some(timer), Some(started)) = (&mut self.timer, started) {
+            Arc::make_mut(&mut timer.moves).push(MoveTiming {
+                decision: started - timer.since,
+                engine: started.elapsed(),
+            });
+        }
+        for hook in &mut self.hooks {
+            hook(direction, &result);
+        }
+        if let Some(timer) = &mut self.timer {
+            timer.since = Instant::now();
+        }
+        Ok(result)
+    }
+
+    /// Slide toward `direction`, placing scripted spawns before drawing
+    /// from the RNG; a spawn landing on an occupied cell leaves the game
+    /// untouched
+    fn scripted_step(&mut self, direction: Direction, moves: u64) -> Result<MoveResult, String> {
+        let rules = self.rules;
+        let mut script = self.script.clone();
+        let mut rng = self.rng.clone();
+        let result = step_detailed_with(self.board, direction, &rules, |next| {
+            let mut spawns = Vec::new();
+            for _ in 0..rules.tiles_after(moves) {
+                let spawn = match script.pop_front() {
+                    Some(spawn) => {
+                        let (row, col) = spawn.cell;
+                        if next[row][col] != 0 {
+                            return Err(format!(
+                                "scripted spawn at ({row}, {col}) lands on an occupied cell"
+                            ));
+                        }
+                        next[row][col] = spawn.value;
+                        spawn
+                    }
+                    None => match spawn_tile(next, &mut rng, &rules) {
+                        Some((cell, value)) => Spawn { cell, value },
+                        None => break,
+                    },
+                };
+                spawns.push(spawn);
+            }
+            Ok(spawns)
+        })?;
+        self.script = script;
+        self.rng = rng;
+        Ok(result)
+    }
+
+    /// Whether `direction` changes the board
+    #[must_use]
+    pub fn is_legal(&self, direction: Direction) -> bool {
+        self.engine().slide(&self.board, direction).0 != self.board
+    }
+
+    /// Legal moves in action-id order (`Down, Right, Up, Left`), for
+    /// action-masked agents
+    #[must_use]
+    pub fn action_mask(&self) -> [bool; 4] {
+        ALL_DIRECTIONS.map(|dir| self.is_legal(dir))
+    }
+
+    fn engine(&self) -> Engine {
+        Engine::new(self.rules).expect("rules were validated")
+    }
+
+    fn resolve(&mut self, direction: Direction) -> Direction {
+        if self.invalid_moves != InvalidMoves::AutoResolve || self.is_legal(direction) {
+            return direction;
+        }
+        let legal: Vec<Direction> = self.engine().legal_moves(&self.board).collect();
+        if legal.is_empty() {
+            direction
+        } else {
+            legal[self.resolver.random_range(..legal.len())]
+        }
+    }
+
+    /// Run after every move from now on (see [`GameBuilder::on_move`])
+    pub fn on_move(&mut self, hook: impl FnMut(Direction, &MoveResult) + Send + Sync + 'static) {
+        self.hooks.push(Box::new(hook));
+    }
+
+    /// Let `policy` play: yields each direction it picks with the result,
+    /// until the game is over (see [`is_over`](Self::is_over)).
+    ///
+    /// A policy that keeps picking invalid moves never ends the game, so
+    /// bound the iterator (e.g. with [`Iterator::take`]) if that can happen.
+    pub fn play<P>(&mut self, policy: P) -> Moves<'_, P>
+    where
+        P: FnMut(&Board) -> Direction,
+    {
+        Moves { game: self, policy }
+    }
+
+    #[must_use]
+    pub fn board(&self) -> Board {
+        self.board
+    }
+
+    #[must_use]
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    #[must_use]
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// [`state`](Self::state) with its reason and whether any move remains
+    #[must_use]
+    pub fn status(&self) -> Status {
+        if self.clock.is_some_and(|c| c.forfeited) {
+            return Status {
+                state: State::GameOver,
+                reason: Some(EndReason::OutOfTime),
+                can_continue: false,
+            };
+        }
+        if self.state == State::Victory || !self.won {
+            self.engine().status(&self.board)
+        } else {
+            self.status_after_win(&self.board)
+        }
+    }
+
+    /// Status of `board` in a game that already reported its victory and
+    /// plays on: only running out of moves ends it
+    fn status_after_win(&self, board: &Board) -> Status {
+        if self.engine().is_over(board) {
+            Status {
+                state: State::GameOver,
+                reason: Some(EndReason::NoLegalMoves),
+                can_continue: false,
+            }
+        } else {
+            Status {
+                state: State::Continue,
+                reason: None,
+                can_continue: true,
+            }
+        }
+    }
+
+    /// Whether play has ended: the game is lost, or won without
+    /// [`GameBuilder::keep_playing`]
+    #[must_use]
+    pub fn is_over(&self) -> bool {
+        match self.state {
+            State::Continue => false,
+            State::Victory => !self.keep_playing,
+            State::GameOver => true,
+        }
+    }
+
+    /// Time left on the clock, `None` without [`GameBuilder::time_control`]
+    #[must_use]
+    pub fn time_left(&self) -> Option<Duration> {
+        self.clock.map(|c| {
+            if c.forfeited {
+                Duration::ZERO
+            } else {
+                c.time_left()
+            }
+        })
+    }
+
+    /// Time of every move so far, invalid ones included (empty without
+    /// [`GameBuilder::timings`])
+    #[must_use]
+    pub fn timings(&self) -> &[MoveTiming] {
+        self.timer.as_ref().map_or(&[], |t| &t.moves)
+    }
+
+    /// Whether a victory has been reached at some point
+    #[must_use]
+    pub fn won(&self) -> bool {
+        self.won
+    }
+
+    /// Whether play goes on after a victory
+    #[must_use]
+    pub fn keep_playing(&self) -> bool {
+        self.keep_playing
+    }
+
+    /// Moves played so far, including invalid ones
+    #[must_use]
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    #[must_use]
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    #[must_use]
+    pub fn invalid_moves(&self) -> InvalidMoves {
+        self.invalid_moves
+    }
+
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Directions of accepted moves so far
+    #[must_use]
+    pub fn history(&self) -> &[Direction] {
+        &self.history
+    }
+
+    /// Everything needed to replay this game: seed, rules, the starting
+    /// position if it was constructed, and accepted moves
+    #[must_use]
+    pub fn replay(&self) -> Replay {
+        Replay {
+            rules: self.rules,
+            seed: self.seed,
+            start: self.start,
+            moves: self.history.to_vec(),
+        }
+    }
+
+    /// Proof records of accepted moves (empty unless enabled with [`Game::with_proofs`])
+    #[must_use]
+    pub fn records(&self) -> &[MoveRecord] {
+        self.proofs.as_deref().map_or(&[], ProofChain::records)
+    }
+
+    /// Capture the whole game state, RNG included, to branch from later.
+    ///
+    /// Snapshots are cheap: the move history and proof records are shared
+    /// with the game until one of them moves on.
+    ///
+    /// ```
+    /// use akioi_2048_core::{Direction, Game};
+    ///
+    /// let mut game = Game::new(5);
+    /// let fork = game.snapshot();
+    /// let first = game.step(Direction::Left);
+    /// game.restore(&fork);
+    /// assert_eq!(game.moves(), 0);
+    /// assert_eq!(game.step(Direction::Left), first);
+    /// ```
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot(Box::new(self.clone()))
+    }
+
+    /// Return to the state captured by `snapshot`; hooks stay as they are.
+    ///
+    /// Any game's snapshot can be restored, so a game can also jump to the
+    /// position, rules and seed of another. A clock comes back with the
+    /// time it had, still charged from the snapshot's last move, and so do
+    /// the [timings](Self::timings).
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        let hooks = std::mem::take(&mut self.hooks);
+        *self = (*snapshot.0).clone();
+        self.hooks = hooks;
+    }
+}
+
+/// A saved [`Game`] state, see [`Game::snapshot`]
+#[derive(Clone)]
+pub struct Snapshot(Box<Game>);
+
+impl Snapshot {
+    #[must_use]
+    pub fn board(&self) -> Board {
+        self.0.board
+    }
+
+    #[must_use]
+    pub fn score(&self) -> i64 {
+        self.0.score
+    }
+
+    #[must_use]
+    pub fn moves(&self) -> usize {
+        self.0.moves
+    }
+}
+
+/// Mixed into the seed of the invalid-move resolver
+const RESOLVER_SALT: u64 = 0x5bd1_e995_7f4a_7c15;
+
+/// Step-by-step configuration of a [`Game`].
+///
+/// ```
+/// use akioi_2048_core::{Game, Rules};
+///
+/// let game = Game::builder()
+///     .seed(7)
+///     .rules(Rules::MERCY)
+///     .proofs(true)
+///     .on_move(|dir, result| println!("{dir:?}: +{}", result.delta))
+///     .build()
+///     .unwrap();
+/// assert_eq!(game.seed(), 7);
+/// ```
+#[derive(Default)]
+pub struct GameBuilder {
+    seed: Option<u64>,
+    rules: Rules,
+    proofs: bool,
+    invalid_moves: InvalidMoves,
+    keep_playing: bool,
+    start: Option<Start>,
+    script: Vec<Spawn>,
+    time_control: Option<TimeControl>,
+    timings: bool,
+    hooks: Vec<MoveHook>,
+}
+
+impl GameBuilder {
+    /// Seed for all spawns (default: [`random_seed`])
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Rules to play under (default: [`Rules::AKIOI`])
+    #[must_use]
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Chain a [`MoveRecord`] to every accepted move
+    #[must_use]
+    pub fn proofs(mut self, enabled: bool) -> Self {
+        self.proofs = enabled;
+        self
+    }
+
+    /// How to handle moves that would not change the board (default:
+    /// [`InvalidMoves::Noop`])
+    #[must_use]
+    pub fn invalid_moves(mut self, policy: InvalidMoves) -> Self {
+        self.invalid_moves = policy;
+        self
+    }
+
+    /// Keep going after a victory, like the web version's "keep going"
+    /// button: the winning move reports [`State::Victory`] once, later moves
+    /// report [`State::Continue`] until no move is left (default: `false`)
+    #[must_use]
+    pub fn keep_playing(mut self, enabled: bool) -> Self {
+        self.keep_playing = enabled;
+        self
+    }
+
+    /// Begin from `board` with `score` already scored instead of dealing two
+    /// tiles, for puzzles and constructed test positions; the seed still
+    /// drives every later spawn
+    #[must_use]
+    pub fn start(mut self, board: Board, score: i64) -> Self {
+        self.start = Some(Start { board, score });
+        self
+    }
+
+    /// Place these tiles, in order, after the next valid moves instead of
+    /// random spawns, so tests can predict every board; random spawns
+    /// resume once the script runs out. Scripted spawns are not part of
+    /// [`Game::replay`].
+    #[must_use]
+    pub fn spawns(mut self, script: impl IntoIterator<Item = Spawn>) -> Self {
+        self.script.extend(script);
+        self
+    }
+
+    /// Put the game on a clock that starts when it is built; every move
+    /// spends the time since the previous one (default: no clock)
+    #[must_use]
+    pub fn time_control(mut self, control: TimeControl) -> Self {
+        self.time_control = Some(control);
+        self
+    }
+
+    /// Time every move, the player's decision and the engine's work apart,
+    /// for [`Game::timings`] (default: `false`)
+    #[must_use]
+    pub fn timings(mut self, enabled: bool) -> Self {
+        self.timings = enabled;
+        self
+    }
+
+    /// Run `hook` after every move, valid or not
+    #[must_use]
+    pub fn on_move(
+        mut self,
+        hook: impl FnMut(Direction, &MoveResult) + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Deal the two starting tiles, or set up the [`start`](Self::start)
+    /// position
+    ///
+    /// # Errors
+    /// Returns an error if the rules are inconsistent, or the starting board
+    /// or a scripted spawn holds tiles invalid under them.
+    pub fn build(self) -> Result<Game, String> {
+        self.rules.validate()?;
+        for spawn in &self.script {
+            let (row, col) = spawn.cell;
+            if row >= 4 || col >= 4 {
+                return Err(format!(
+                    "scripted spawn at ({row}, {col}) is outside the board"
+                ));
+            }
+            if spawn.value == 0 {
+                return Err("scripted spawn of an empty tile".to_string());
+            }
+            validate_board_with(
+                &[[spawn.value, 0, 0, 0], [0; 4], [0; 4], [0; 4]],
+                &self.rules,
+            )?;
+        }
+        let seed = self.seed.unwrap_or_else(random_seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (board, score, draws) = match self.start {
+            Some(start) => {
+                validate_board_with(&start.board, &self.rules)?;
+                (start.board, start.score, 0)
+            }
+            None => (crate::init_with_rules(&mut rng, &self.rules), 0, 2),
+        };
+        let state = state_of(&board, &self.rules);
+        Ok(Game {
+            board,
+            score,
+            state,
+            moves: 0,
+            rules: self.rules,
+            seed,
+            start: self.start,
+            rng,
+            draws,
+            proofs: self.proofs.then(|| Arc::new(ProofChain::new())),
+            history: Arc::default(),
+            invalid_moves: self.invalid_moves,
+            keep_playing: self.keep_playing,
+            won: state == State::Victory,
+            resolver: StdRng::seed_from_u64(seed ^ RESOLVER_SALT),
+            script: self.script.into(),
+            clock: self.time_control.map(|control| Clock {
+                control,
+                left: control.total,
+                since: Instant::now(),
+                forfeited: false,
+            }),
+            timer: self.timings.then(|| Timer {
+                since: Instant::now(),
+                moves: Arc::default(),
+            }),
+            hooks: self.hooks,
+        })
+    }
+}
+
+/// Iterator returned by [`Game::play`]
+pub struct Moves<'a, P> {
+    game: &'a mut Game,
+    policy: P,
+}
+
+impl<P> Iterator for Moves<'_, P>
+where
+    P: FnMut(&Board) -> Direction,
+{
+    type Item = (Direction, MoveResult);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.game.is_over() {
+            return None;
+        }
+        let direction = (self.policy)(&self.game.board);
+        Some((direction, self.game.step(direction)))
+    }
+}
+
+/// Fresh random seed for games created without one
+#[must_use]
+pub fn random_seed() -> u64 {
+    rand::rng().random()
+}
@@ -0,0 +1,167 @@
+//! A ruleset described as data, so documentation generators and clients
+//! in other languages can follow the engine instead of restating it.
+//!
+//! Everything is derived by asking the engine itself — which tiles its
+//! variant accepts, what each pair merges into and under which conditions,
+//! what spawns — so the description cannot drift from the rules it
+//! describes.
+
+use std::collections::BTreeSet;
+
+use crate::game::spawn_probabilities;
+use crate::json::Json;
+use crate::rules::RULES_VERSION;
+use crate::session::InvalidMoves;
+use crate::{BLOCKER, BOMB, MergeRule, Rules};
+
+/// Tiles of at most this magnitude are found by asking the variant about
+/// each; larger ones, under a larger [`Rules::max_tile`], by merging
+const TILE_RANGE: i32 = 0x0001_0000;
+
+/// One pair of tiles that merges
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MergeSpec {
+    /// The tile nearer the wall
+    pub a: i32,
+    /// The tile behind it
+    pub b: i32,
+    pub tile: i32,
+    pub score: i32,
+    /// The pair only merges if the tiles touched before the move
+    pub needs_adjacent: bool,
+    /// The pair only merges if every cell between `a` and the wall is taken
+    pub needs_full_below: bool,
+}
+
+/// A ruleset and game policy, as data
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spec {
+    pub rules: Rules,
+    /// [`RULES_VERSION`] of the engine
+    pub version: u32,
+    /// Every tile the variant accepts besides blockers and bombs, ascending
+    pub tiles: Vec<i32>,
+    /// Every pair of [`tiles`](Self::tiles) that merges, by `a` then `b`
+    pub merges: Vec<MergeSpec>,
+    /// Spawn probabilities on an empty board; adaptive difficulty shifts
+    /// them with the largest tile and mercy with the last empty cell
+    pub spawns: Vec<(i32, f64)>,
+    pub winning_tile: i32,
+    /// The blocker tile, when the rules allow blockers
+    pub blocker: Option<i32>,
+    /// The bomb tile, when the rules allow bombs
+    pub bomb: Option<i32>,
+    pub invalid_moves: InvalidMoves,
+}
+
+/// Describe `rules`, played with the `invalid_moves` policy
+///
+/// ```
+/// use akioi_2048_core::session::InvalidMoves;
+/// use akioi_2048_core::{Rules, spec::spec};
+///
+/// let spec = spec(&Rules::CLASSIC, InvalidMoves::Noop).unwrap();
+/// assert_eq!(spec.tiles.len(), 16);
+/// assert_eq!(spec.merges.len(), 15);
+/// assert_eq!(spec.winning_tile, 65536);
+/// assert!(spec.merges.iter().all(|m| m.a == m.b && !m.needs_adjacent));
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules.
+pub fn spec(rules: &Rules, invalid_moves: InvalidMoves) -> Result<Spec, String> {
+    rules.validate()?;
+    let variant = rules.variant;
+    let mut tiles: BTreeSet<i32> = (-TILE_RANGE..=TILE_RANGE)
+        .filter(|&t| t != 0 && t != BLOCKER && t != BOMB && variant.is_tile(t, rules))
+        .collect();
+    loop {
+        let larger: Vec<i32> = tiles
+            .iter()
+            .flat_map(|&a| tiles.iter().map(move |&b| (a, b)))
+            .filter_map(|(a, b)| variant.merge(a, b, true, &[], rules))
+            .map(|(tile, _)| tile)
+            .filter(|&t| t > TILE_RANGE && !tiles.contains(&t))
+            .collect();
+        if larger.is_empty() {
+            break;
+        }
+        tiles.extend(larger);
+    }
+    let tiles: Vec<i32> = tiles.into_iter().collect();
+    let mut merges = Vec::new();
+    for &a in &tiles {
+        for &b in &tiles {
+            let Some((tile, score)) = variant.merge(a, b, true, &[], rules) else {
+                continue;
+            };
+            merges.push(MergeSpec {
+                a,
+                b,
+                tile,
+                score: rules.scoring.merge_score(tile, score),
+                needs_adjacent: variant.merge(a, b, false, &[], rules).is_none(),
+                needs_full_below: variant.merge(a, b, true, &[0], rules).is_none(),
+            });
+        }
+    }
+    Ok(Spec {
+        rules: *rules,
+        version: RULES_VERSION,
+        tiles,
+        merges,
+        spawns: spawn_probabilities(rules, &[[0; 4]; 4]),
+        winning_tile: variant.winning_tile(rules),
+        blocker: rules.blockers.then_some(BLOCKER),
+        bomb: rules.bombs.then_some(BOMB),
+        invalid_moves,
+    })
+}
+
+impl Spec {
+    /// The spec as the `rules_spec` server method returns it; spawn odds
+    /// are keyed by the tile written as a string
+    pub(crate) fn to_json(&self) -> Json {
+        let rules = &self.rules;
+        let merges = self.merges.iter().map(|m| {
+            Json::object([
+                ("a", m.a.into()),
+                ("b", m.b.into()),
+                ("tile", m.tile.into()),
+                ("score", m.score.into()),
+                ("needs_adjacent", m.needs_adjacent.into()),
+                ("needs_full_below", m.needs_full_below.into()),
+            ])
+        });
+        let spawns = self
+            .spawns
+            .iter()
+            .map(|&(tile, chance)| (tile.to_string(), chance.into()));
+        Json::object([
+            ("version", self.version.into()),
+            ("tag", rules.tag().into()),
+            ("variant", rules.variant.name().into()),
+            ("base", rules.base.into()),
+            ("max_multiplier", rules.max_multiplier.into()),
+            ("multiplier_merge", rules.multiplier_merge.into()),
+            ("require_full_below", rules.require_full_below.into()),
+            ("difficulty", rules.difficulty.name().into()),
+            ("mercy", rules.mercy.into()),
+            ("bombs", rules.bombs.into()),
+            ("blockers", rules.blockers.into()),
+            ("max_tile", rules.max_tile.into()),
+            ("overflow", rules.overflow.name().into()),
+            ("scoring", rules.scoring.name().into()),
+            ("spawns_per_move", u32::from(rules.spawns).into()),
+            ("spawn_every", u32::from(rules.spawn_every).into()),
+            ("placement", rules.placement.name().into()),
+            ("tiles", self.tiles.clone().into()),
+            ("blocker", self.blocker.into()),
+            ("bomb", self.bomb.into()),
+            ("merges", Json::Array(merges.collect())),
+            ("spawns", Json::Object(spawns.collect())),
+            ("winning_tile", self.winning_tile.into()),
+            ("invalid_moves", self.invalid_moves.name().into()),
+        ])
+    }
+}
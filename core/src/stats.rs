@@ -0,0 +1,410 @@
+//! Running statistics over finished games, mergeable across workers.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::game::single_step_traced;
+use crate::reach::spawn_outcomes_of;
+use crate::tournament::Outcome;
+use crate::{ALL_DIRECTIONS, Board, Engine, Game};
+
+/// Default width of a [`GameStats::scores`] bin
+pub const DEFAULT_BIN_WIDTH: i64 = 1024;
+
+/// Aggregate numbers of many games.
+///
+/// Record games as they finish, then [`merge`](Self::merge) the stats of
+/// separate workers; merging gives the same result as recording every game
+/// in one place.
+///
+/// ```
+/// use akioi_2048_core::stats::GameStats;
+/// use akioi_2048_core::tournament::Outcome;
+///
+/// let mut a = GameStats::default();
+/// a.record_outcome(&Outcome { score: 1500, moves: 180, max_tile: 128, won: false });
+/// let mut b = GameStats::default();
+/// b.record_outcome(&Outcome { score: 3000, moves: 300, max_tile: 256, won: false });
+/// a.merge(&b).unwrap();
+/// assert_eq!(a.games, 2);
+/// assert_eq!(a.mean_moves(), 240.0);
+/// assert_eq!(a.scores[&1024], 1);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameStats {
+    /// Width of the score histogram bins
+    pub bin_width: i64,
+    pub games: u64,
+    /// Games that reached the victory tile
+    pub wins: u64,
+    /// Moves over all games
+    pub moves: u64,
+    pub total_score: i64,
+    /// Fewest moves in a game, `0` before any game
+    pub shortest: u64,
+    /// Most moves in a game
+    pub longest: u64,
+    /// Games by their largest tile
+    pub max_tiles: BTreeMap<i32, u64>,
+    /// Games by score, keyed by the lower edge of their bin
+    pub scores: BTreeMap<i64, u64>,
+    /// Moves played in each direction, in
+    /// [`ALL_DIRECTIONS`](crate::ALL_DIRECTIONS) order; only games recorded
+    /// with [`record`](Self::record) contribute
+    pub directions: [u64; 4],
+    /// Where play happened on the board, when collected (see
+    /// [`with_heatmaps`](Self::with_heatmaps))
+    pub heatmaps: Option<Heatmaps>,
+    /// How wide the game tree was move by move, when collected (see
+    /// [`with_branching`](Self::with_branching))
+    pub branching: Option<Branching>,
+}
+
+/// Counts by board cell, `[row][col]`, over every recorded game: whether
+/// play keeps its largest tile and its merges in a corner, and where tiles
+/// spawn
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Heatmaps {
+    /// Positions, the start and after every move, whose largest number
+    /// tile is in the cell; a tie counts in every cell holding it
+    pub max_tile: [[u64; 4]; 4],
+    /// Merges by the cell of the merged tile
+    pub merges: [[u64; 4]; 4],
+    /// Tiles spawned after a move, by cell
+    pub spawns: [[u64; 4]; 4],
+}
+
+impl Heatmaps {
+    /// Add every position of `game`, as its [`replay`](Game::replay) plays
+    /// it back; a game whose replay does not play back, as with scripted
+    /// spawns, is left out.
+    pub fn record(&mut self, game: &Game) {
+        let replay = game.replay();
+        let Ok(frames) = replay.frames() else {
+            return;
+        };
+        let rules = game.rules();
+        self.count_max_tile(&frames[0]);
+        for (pair, &direction) in frames.windows(2).zip(&replay.moves) {
+            let (slid, _, traces) = single_step_traced(&pair[0], direction, &rules);
+            for trace in traces.iter().filter(|t| t.merged_from.is_some()) {
+                self.merges[trace.to.0][trace.to.1] += 1;
+            }
+            for (r, c) in cells() {
+                if slid[r][c] == 0 && pair[1][r][c] != 0 {
+                    self.spawns[r][c] += 1;
+                }
+            }
+            self.count_max_tile(&pair[1]);
+        }
+    }
+
+    fn count_max_tile(&mut self, board: &Board) {
+        let max = board.iter().flatten().copied().max().unwrap_or(0);
+        if max <= 0 {
+            return;
+        }
+        for (r, c) in cells() {
+            if board[r][c] == max {
+                self.max_tile[r][c] += 1;
+            }
+        }
+    }
+
+    /// Add the counts of `other`
+    pub fn merge(&mut self, other: &Heatmaps) {
+        let pairs = [
+            (&mut self.max_tile, &other.max_tile),
+            (&mut self.merges, &other.merges),
+            (&mut self.spawns, &other.spawns),
+        ];
+        for (mine, theirs) in pairs {
+            for (a, b) in mine.iter_mut().flatten().zip(theirs.iter().flatten()) {
+                *a += b;
+            }
+        }
+    }
+}
+
+/// Millionths of a bit [`Branching::spawn_entropy`] counts in
+const ENTROPY_SCALE: f64 = 1e6;
+
+/// Totals by move number over every recorded game, to compare how much
+/// choice and chance each ruleset gives as games go on: index `t` holds the
+/// positions that `t` moves led to and a move was played from, the start
+/// at `0`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Branching {
+    /// Games that played a move from a position `t` moves in
+    pub positions: Vec<u64>,
+    /// Moves that change the board
+    pub legal_moves: Vec<u64>,
+    /// Distinct boards a legal move and its spawns lead to
+    pub successors: Vec<u64>,
+    /// Entropy of the spawns after the move played, in millionths of a bit
+    /// so that merging stays exact
+    pub spawn_entropy: Vec<u64>,
+}
+
+impl Branching {
+    /// Add every position of `game` a move was played from, as its
+    /// [`replay`](Game::replay) plays it back; a game whose replay does not
+    /// play back, as with scripted spawns, is left out.
+    pub fn record(&mut self, game: &Game) {
+        let replay = game.replay();
+        let Ok(frames) = replay.frames() else {
+            return;
+        };
+        let rules = game.rules();
+        let engine = Engine::new(rules).expect("a game's rules are valid");
+        for (t, (board, &direction)) in frames.iter().zip(&replay.moves).enumerate() {
+            let tiles = rules.tiles_after(t as u64);
+            let mut legal = 0;
+            let mut successors = HashSet::new();
+            for mv in engine.legal_moves(board) {
+                legal += 1;
+                let (slid, _) = engine.slide(board, mv);
+                let outcomes = spawn_outcomes_of(&slid, &rules, tiles);
+                successors.extend(outcomes.into_iter().map(|(b, _)| b));
+            }
+            let (slid, _) = engine.slide(board, direction);
+            let entropy: f64 = spawn_outcomes_of(&slid, &rules, tiles)
+                .iter()
+                .map(|&(_, p)| -p * p.log2())
+                .sum();
+            self.grow(t + 1);
+            self.positions[t] += 1;
+            self.legal_moves[t] += legal;
+            self.successors[t] += successors.len() as u64;
+            self.spawn_entropy[t] += (entropy * ENTROPY_SCALE).round() as u64;
+        }
+    }
+
+    /// Add the totals of `other`
+    pub fn merge(&mut self, other: &Branching) {
+        self.grow(other.positions.len());
+        let pairs = [
+            (&mut self.positions, &other.positions),
+            (&mut self.legal_moves, &other.legal_moves),
+            (&mut self.successors, &other.successors),
+            (&mut self.spawn_entropy, &other.spawn_entropy),
+        ];
+        for (mine, theirs) in pairs {
+            for (a, b) in mine.iter_mut().zip(theirs) {
+                *a += b;
+            }
+        }
+    }
+
+    fn grow(&mut self, len: usize) {
+        if self.positions.len() < len {
+            for totals in [
+                &mut self.positions,
+                &mut self.legal_moves,
+                &mut self.successors,
+                &mut self.spawn_entropy,
+            ] {
+                totals.resize(len, 0);
+            }
+        }
+    }
+
+    /// Average legal moves by move number
+    #[must_use]
+    pub fn mean_legal_moves(&self) -> Vec<f64> {
+        self.per_position(&self.legal_moves, 1.0)
+    }
+
+    /// Average distinct successor boards by move number
+    #[must_use]
+    pub fn mean_successors(&self) -> Vec<f64> {
+        self.per_position(&self.successors, 1.0)
+    }
+
+    /// Average spawn entropy in bits by move number
+    #[must_use]
+    pub fn mean_spawn_entropy(&self) -> Vec<f64> {
+        self.per_position(&self.spawn_entropy, ENTROPY_SCALE)
+    }
+
+    fn per_position(&self, totals: &[u64], scale: f64) -> Vec<f64> {
+        totals
+            .iter()
+            .zip(&self.positions)
+            .map(|(&total, &n)| total as f64 / scale / n as f64)
+            .collect()
+    }
+}
+
+/// Every `(row, col)` of a board, in row-major order
+fn cells() -> impl Iterator<Item = (usize, usize)> {
+    (0..4).flat_map(|r| (0..4).map(move |c| (r, c)))
+}
+
+impl Default for GameStats {
+    fn default() -> Self {
+        GameStats {
+            bin_width: DEFAULT_BIN_WIDTH,
+            games: 0,
+            wins: 0,
+            moves: 0,
+            total_score: 0,
+            shortest: 0,
+            longest: 0,
+            max_tiles: BTreeMap::new(),
+            scores: BTreeMap::new(),
+            directions: [0; 4],
+            heatmaps: None,
+            branching: None,
+        }
+    }
+}
+
+impl GameStats {
+    /// Empty stats with score bins `bin_width` wide
+    ///
+    /// # Errors
+    /// Returns an error unless `bin_width` is positive.
+    pub fn new(bin_width: i64) -> Result<Self, String> {
+        if bin_width <= 0 {
+            return Err("bin width must be positive".to_string());
+        }
+        Ok(GameStats {
+            bin_width,
+            ..GameStats::default()
+        })
+    }
+
+    /// Also collect [`Heatmaps`] of the games recorded from now on, which
+    /// replays each of them
+    #[must_use]
+    pub fn with_heatmaps(mut self) -> Self {
+        self.heatmaps.get_or_insert_with(Heatmaps::default);
+        self
+    }
+
+    /// Also collect [`Branching`] of the games recorded from now on, which
+    /// replays each of them and expands every position
+    #[must_use]
+    pub fn with_branching(mut self) -> Self {
+        self.branching.get_or_insert_with(Branching::default);
+        self
+    }
+
+    /// Add a finished game, including the directions it was played in and,
+    /// when collected, its heatmaps and branching
+    pub fn record(&mut self, game: &Game) {
+        if let Some(heatmaps) = &mut self.heatmaps {
+            heatmaps.record(game);
+        }
+        if let Some(branching) = &mut self.branching {
+            branching.record(game);
+        }
+        let board = game.board();
+        self.record_outcome(&Outcome {
+            score: game.score(),
+            moves: game.moves(),
+            max_tile: board.iter().flatten().copied().max().unwrap_or(0),
+            won: game.won(),
+        });
+        for dir in game.history() {
+            let i = ALL_DIRECTIONS
+                .iter()
+                .position(|d| d == dir)
+                .expect("ALL_DIRECTIONS holds every direction");
+            self.directions[i] += 1;
+        }
+    }
+
+    /// Add the outcome of a game
+    pub fn record_outcome(&mut self, outcome: &Outcome) {
+        let moves = outcome.moves as u64;
+        self.shortest = if self.games == 0 {
+            moves
+        } else {
+            self.shortest.min(moves)
+        };
+        self.longest = self.longest.max(moves);
+        self.games += 1;
+        self.wins += u64::from(outcome.won);
+        self.moves += moves;
+        self.total_score += outcome.score;
+        *self.max_tiles.entry(outcome.max_tile).or_default() += 1;
+        let bin = outcome.score.div_euclid(self.bin_width) * self.bin_width;
+        *self.scores.entry(bin).or_default() += 1;
+    }
+
+    /// Add every game recorded in `other`
+    ///
+    /// # Errors
+    /// Returns an error if the score bins differ in width, or only one of
+    /// the stats collects heatmaps or branching; `self` is left unchanged
+    /// then.
+    pub fn merge(&mut self, other: &GameStats) -> Result<(), String> {
+        if other.bin_width != self.bin_width {
+            return Err(format!(
+                "cannot merge stats with bin width {} into bin width {}",
+                other.bin_width, self.bin_width
+            ));
+        }
+        if other.heatmaps.is_some() != self.heatmaps.is_some() {
+            return Err("cannot merge stats with heatmaps and stats without".to_string());
+        }
+        if other.branching.is_some() != self.branching.is_some() {
+            return Err("cannot merge stats with branching and stats without".to_string());
+        }
+        if let (Some(mine), Some(theirs)) = (&mut self.heatmaps, &other.heatmaps) {
+            mine.merge(theirs);
+        }
+        if let (Some(mine), Some(theirs)) = (&mut self.branching, &other.branching) {
+            mine.merge(theirs);
+        }
+        if other.games == 0 {
+            return Ok(());
+        }
+        self.shortest = if self.games == 0 {
+            other.shortest
+        } else {
+            self.shortest.min(other.shortest)
+        };
+        self.longest = self.longest.max(other.longest);
+        self.games += other.games;
+        self.wins += other.wins;
+        self.moves += other.moves;
+        self.total_score += other.total_score;
+        for (&tile, &n) in &other.max_tiles {
+            *self.max_tiles.entry(tile).or_default() += n;
+        }
+        for (&bin, &n) in &other.scores {
+            *self.scores.entry(bin).or_default() += n;
+        }
+        for (mine, theirs) in self.directions.iter_mut().zip(other.directions) {
+            *mine += theirs;
+        }
+        Ok(())
+    }
+
+    /// Fraction of games that reached the victory tile, `0` before any game
+    #[must_use]
+    pub fn win_rate(&self) -> f64 {
+        self.per_game(self.wins as f64)
+    }
+
+    #[must_use]
+    pub fn mean_score(&self) -> f64 {
+        self.per_game(self.total_score as f64)
+    }
+
+    /// Average game length in moves
+    #[must_use]
+    pub fn mean_moves(&self) -> f64 {
+        self.per_game(self.moves as f64)
+    }
+
+    fn per_game(&self, total: f64) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            total / self.games as f64
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Monte Carlo estimates of how likely a position is to survive, for
+//! "danger meters" in front-ends.
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::tournament::game_seed;
+use crate::{Board, Direction, Engine, State};
+
+/// Rollouts end after this many consecutive moves that leave the board
+/// unchanged, counting as lost: the policy cannot go on
+const STALL_LIMIT: usize = 64;
+
+/// Outcome of [`survival_probability`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Survival {
+    /// Share of the rollouts still alive after the moves asked for
+    pub probability: f64,
+    /// Standard error of [`probability`](Self::probability)
+    pub std_error: f64,
+    pub samples: usize,
+    /// Mean moves made before the rollout ended or reached its length
+    pub mean_moves: f64,
+}
+
+/// Estimate the chance that `board` survives `n_moves` more moves played
+/// by `policy` under the rules of `engine`, over `samples` rollouts with
+/// spawns seeded from `seed`.
+///
+/// Moves that change nothing are not counted; a rollout that reaches the
+/// winning tile survives. Rollout `i` uses the spawns of game `i` of a
+/// [`tournament`](crate::tournament) seeded with `seed`, so estimates for
+/// different policies are paired.
+///
+/// ```
+/// use akioi_2048_core::survival::survival_probability;
+/// use akioi_2048_core::{ALL_DIRECTIONS, Engine};
+///
+/// let engine = Engine::default();
+/// let dead = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]];
+/// let mut turn = 0;
+/// let mut cycle = |_: &_| {
+///     turn += 1;
+///     Ok::<_, String>(ALL_DIRECTIONS[turn % 4])
+/// };
+/// let open = [[0; 4], [0; 4], [0; 4], [0, 0, 2, 2]];
+/// let dead_odds = survival_probability(&engine, &dead, 1, 8, 0, &mut cycle).unwrap();
+/// let open_odds = survival_probability(&engine, &open, 3, 8, 0, &mut cycle).unwrap();
+/// assert_eq!((dead_odds.probability, open_odds.probability), (0.0, 1.0));
+/// ```
+///
+/// # Errors
+/// Propagates the first error returned by `policy`.
+///
+/// # Panics
+/// If `board` is invalid under the engine's rules; check it with
+/// [`validate_board_with`](crate::validate_board_with) first.
+pub fn survival_probability<E>(
+    engine: &Engine,
+    board: &Board,
+    n_moves: usize,
+    samples: usize,
+    seed: u64,
+    policy: &mut impl FnMut(&Board) -> Result<Direction, E>,
+) -> Result<Survival, E> {
+    let (mut survived, mut moves) = (0, 0);
+    for sample in 0..samples {
+        let mut rng = StdRng::seed_from_u64(game_seed(seed, sample));
+        let mut board = *board;
+        let (mut made, mut stalled) = (0, 0);
+        let alive = loop {
+            match engine.state(&board) {
+                State::GameOver => break false,
+                State::Victory => break true,
+                State::Continue if made == n_moves => break true,
+                State::Continue if stalled == STALL_LIMIT => break false,
+                State::Continue => {}
+            }
+            let (next, _, _) = engine
+                .step_after(board, policy(&board)?, &mut rng, made as u64)
+                .expect("the board was valid, and so is every board after it");
+            if next == board {
+                stalled += 1;
+            } else {
+                made += 1;
+                stalled = 0;
+            }
+            board = next;
+        };
+        survived += usize::from(alive);
+        moves += made;
+    }
+    let n = samples.max(1) as f64;
+    let probability = survived as f64 / n;
+    Ok(Survival {
+        probability,
+        std_error: (probability * (1.0 - probability) / n).sqrt(),
+        samples,
+        mean_moves: moves as f64 / n,
+    })
+}
@@ -0,0 +1,596 @@
+//! Endgame tablebases: exact values of every position reachable from a
+//! root within a move horizon, found by retrograde analysis.
+//!
+//! All positions are enumerated layer by layer, each move followed by every
+//! spawn it can lead to; values are then computed from the last layer back
+//! to the root. This is only feasible for endgames, boards with few empty
+//! cells and short horizons, so [`Tablebase::build`] takes a position limit.
+//! Under [`Rules::spawn_every`] the first move from the root spawns.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::mmap::Mmap;
+use crate::progress::Progress;
+use crate::reach::spawn_outcomes_of;
+use crate::{ALL_DIRECTIONS, Board, Direction, Engine, Rules};
+
+/// First bytes of a saved tablebase, before its version byte
+const MAGIC: &[u8; 7] = b"AKTB\0\0\0";
+
+/// Version [`Tablebase::write`] writes
+const VERSION: u8 = 2;
+
+/// Bytes of one entry on disk
+const RECORD: usize = 85;
+
+/// Value of one position with some moves left
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Entry {
+    /// Chance of reaching the goal tile in time under optimal play
+    pub win_probability: f64,
+    /// Expected score gained over the moves left, under the same play
+    pub expected_score: f64,
+    /// Move that maximizes the win probability, ties going to the higher
+    /// expected score; `None` once the game is decided
+    pub best_move: Option<Direction>,
+}
+
+impl Entry {
+    const DECIDED: Entry = Entry {
+        win_probability: 0.0,
+        expected_score: 0.0,
+        best_move: None,
+    };
+}
+
+/// Exact values of the positions reachable from a root.
+///
+/// ```
+/// use akioi_2048_core::tablebase::Tablebase;
+/// use akioi_2048_core::{Direction, Rules};
+///
+/// let board = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 1024, 1024]];
+/// let table = Tablebase::build(board, Rules::AKIOI, 2048, 1, 1_000).unwrap();
+/// let entry = table.lookup(&board, None).unwrap();
+/// assert_eq!(entry.win_probability, 1.0);
+/// assert_eq!(entry.best_move, Some(Direction::Right));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tablebase {
+    rules: Rules,
+    goal: i32,
+    horizon: u32,
+    entries: HashMap<(Board, u32), Entry>,
+}
+
+impl Tablebase {
+    /// Solve every position reachable from `root` within `horizon` moves.
+    ///
+    /// A position is won once it holds a tile of at least `goal`; it is
+    /// lost when no move is left or the horizon runs out first.
+    ///
+    /// # Errors
+    /// Returns an error for inconsistent rules, an invalid root or if more
+    /// than `max_positions` positions are reachable.
+    pub fn build(
+        root: Board,
+        rules: Rules,
+        goal: i32,
+        horizon: u32,
+        max_positions: usize,
+    ) -> Result<Tablebase, String> {
+        Self::build_with_progress(root, rules, goal, horizon, max_positions, &Progress::new())
+    }
+
+    /// [`Tablebase::build`], counting the layers expanded and then solved
+    /// in `progress` and stopping between positions once it is cancelled
+    ///
+    /// # Errors
+    /// As [`Tablebase::build`], and
+    /// [`CANCELLED`](crate::progress::CANCELLED) once cancelled.
+    pub fn build_with_progress(
+        root: Board,
+        rules: Rules,
+        goal: i32,
+        horizon: u32,
+        max_positions: usize,
+        progress: &Progress,
+    ) -> Result<Tablebase, String> {
+        progress.set_total(2 * horizon as usize + 1);
+        let engine = Engine::new(rules)?;
+        crate::validate_board_with(&root, &rules)?;
+        let is_won = |board: &Board| board.iter().flatten().any(|&t| t >= goal);
+
+        let mut layers = vec![vec![root]];
+        let mut total = 1;
+        for moves in 0..u64::from(horizon) {
+            let tiles = rules.tiles_after(moves);
+            let mut next = HashSet::new();
+            for board in layers.last().expect("starts with the root") {
+                progress.check()?;
+                if is_won(board) {
+                    continue;
+                }
+                for dir in engine.legal_moves(board) {
+                    let (slid, _) = engine.slide(board, dir);
+                    for (child, _) in spawn_outcomes_of(&slid, &rules, tiles) {
+                        next.insert(child);
+                    }
+                }
+            }
+            total += next.len();
+            if total > max_positions {
+                return Err(format!(
+                    "more than {max_positions} positions are reachable within {horizon} moves"
+                ));
+            }
+            layers.push(next.into_iter().collect());
+            progress.advance(1);
+        }
+
+        let mut entries = HashMap::with_capacity(total);
+        for (depth, layer) in layers.iter().enumerate().rev() {
+            let left = horizon - u32::try_from(depth).expect("depth is at most the horizon");
+            for board in layer {
+                progress.check()?;
+                let entry = if is_won(board) {
+                    Entry {
+                        win_probability: 1.0,
+                        ..Entry::DECIDED
+                    }
+                } else if left == 0 {
+                    Entry::DECIDED
+                } else {
+                    let tiles = rules.tiles_after(depth as u64);
+                    best_entry(&engine, &rules, board, tiles, |child| {
+                        entries[&(*child, left - 1)]
+                    })
+                };
+                entries.insert((*board, left), entry);
+            }
+            progress.advance(1);
+        }
+        Ok(Tablebase {
+            rules,
+            goal,
+            horizon,
+            entries,
+        })
+    }
+
+    /// The value of `board` with `moves_left` moves to go, or with the most
+    /// moves the table holds for it when `None`; `None` for a position the
+    /// table never reached
+    #[must_use]
+    pub fn lookup(&self, board: &Board, moves_left: Option<u32>) -> Option<Entry> {
+        match moves_left {
+            Some(left) => self.entries.get(&(*board, left)).copied(),
+            None => (0..=self.horizon)
+                .rev()
+                .find_map(|left| self.entries.get(&(*board, left)).copied()),
+        }
+    }
+
+    #[must_use]
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    #[must_use]
+    pub fn goal(&self) -> i32 {
+        self.goal
+    }
+
+    #[must_use]
+    pub fn horizon(&self) -> u32 {
+        self.horizon
+    }
+
+    /// Number of (position, moves left) entries
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the table in its binary format, version 2: a header, the
+    /// entries sorted by position and a SHA-256 of everything before it.
+    ///
+    /// Sorted entries are what lets [`MappedTablebase`] look positions up
+    /// without reading the file.
+    ///
+    /// # Errors
+    /// Returns any error of `out`.
+    pub fn write<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let mut hasher = Sha256::new();
+        let mut emit = |bytes: &[u8]| {
+            hasher.update(bytes);
+            out.write_all(bytes)
+        };
+        let tag = self.rules.tag();
+        emit(MAGIC)?;
+        emit(&[VERSION])?;
+        emit(&u32::try_from(tag.len()).expect("short tag").to_le_bytes())?;
+        emit(tag.as_bytes())?;
+        emit(&self.goal.to_le_bytes())?;
+        emit(&self.horizon.to_le_bytes())?;
+        emit(&(self.entries.len() as u64).to_le_bytes())?;
+        let mut keys: Vec<_> = self.entries.keys().collect();
+        keys.sort_unstable();
+        for key in keys {
+            emit(&encode_record(key, &self.entries[key]))?;
+        }
+        out.write_all(&hasher.finalize())?;
+        out.flush()
+    }
+
+    /// Read a table written by [`write`](Self::write), or in the unsorted
+    /// version 1 format of earlier releases.
+    ///
+    /// # Errors
+    /// Returns any error of `input`, or [`io::ErrorKind::InvalidData`] for
+    /// data that is not a tablebase or fails its checksum.
+    pub fn read<R: Read>(mut input: R) -> io::Result<Tablebase> {
+        let mut prefix = [0; MAGIC.len() + 1];
+        input.read_exact(&mut prefix)?;
+        if prefix[..MAGIC.len()] != *MAGIC {
+            return Err(invalid("not a tablebase file"));
+        }
+        if prefix[MAGIC.len()] == 1 {
+            return read_v1(input);
+        }
+        let mut bytes = prefix.to_vec();
+        input.read_to_end(&mut bytes)?;
+        let header = Header::parse(&bytes)?;
+        header.verify(&bytes)?;
+        let entries = bytes[header.records.clone()]
+            .chunks_exact(RECORD)
+            .map(decode_record)
+            .collect::<io::Result<_>>()?;
+        Ok(Tablebase {
+            rules: header.rules,
+            goal: header.goal,
+            horizon: header.horizon,
+            entries,
+        })
+    }
+
+    /// Save the table to `path`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    /// Load a table saved with [`save`](Self::save).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a tablebase.
+    pub fn load(path: &Path) -> io::Result<Tablebase> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+/// A saved tablebase used in place: the file is memory-mapped and only the
+/// pages a lookup touches are read, so tables larger than memory work and
+/// processes opening the same file share one copy of it.
+///
+/// Opening checks the header and the file size; [`verify`](Self::verify)
+/// checks the whole file against its checksum.
+///
+/// ```
+/// use akioi_2048_core::tablebase::{MappedTablebase, Tablebase};
+/// use akioi_2048_core::Rules;
+///
+/// let board = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 1024, 1024]];
+/// let table = Tablebase::build(board, Rules::AKIOI, 2048, 1, 1_000).unwrap();
+/// let path = std::env::temp_dir().join("mapped-doctest.tb");
+/// table.save(&path).unwrap();
+///
+/// // SAFETY: nothing else touches the file while it is open
+/// let mapped = unsafe { MappedTablebase::open(&path) }.unwrap();
+/// mapped.verify().unwrap();
+/// assert_eq!(mapped.len(), table.len());
+/// assert_eq!(mapped.lookup(&board, None), table.lookup(&board, None));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct MappedTablebase {
+    map: Mmap,
+    header: Header,
+}
+
+impl MappedTablebase {
+    /// Map the table saved at `path`.
+    ///
+    /// # Safety
+    /// Nothing may write to or truncate the file while the table is open:
+    /// lookups read it in place, so a write changes entries under them and
+    /// a truncation kills the process when they next read past the end.
+    /// [`Tablebase::load`] reads the file into memory instead.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or mapped,
+    /// [`io::ErrorKind::InvalidData`] if it is not a tablebase and
+    /// [`io::ErrorKind::Unsupported`] for a version 1 file, which
+    /// [`Tablebase::load`] still reads.
+    pub unsafe fn open(path: &Path) -> io::Result<MappedTablebase> {
+        // SAFETY: the caller leaves the file alone while the table is open
+        let map = unsafe { Mmap::new(&File::open(path)?)? };
+        let header = Header::parse(&map)?;
+        Ok(MappedTablebase { map, header })
+    }
+
+    /// Check the whole file against its checksum, reading every page.
+    ///
+    /// # Errors
+    /// Returns [`io::ErrorKind::InvalidData`] if the file was damaged.
+    pub fn verify(&self) -> io::Result<()> {
+        self.header.verify(&self.map)
+    }
+
+    /// As [`Tablebase::lookup`]; a binary search of the mapped entries.
+    ///
+    /// Entries are only checked as they are read: a damaged entry reads as
+    /// an unknown position.
+    #[must_use]
+    pub fn lookup(&self, board: &Board, moves_left: Option<u32>) -> Option<Entry> {
+        let records = &self.map[self.header.records.clone()];
+        let count = records.len() / RECORD;
+        let record = |i: usize| &records[i * RECORD..(i + 1) * RECORD];
+        let bound = (*board, moves_left.unwrap_or(u32::MAX));
+        let after = partition_point(count, |i| decode_key(record(i)) <= bound);
+        let i = after.checked_sub(1)?;
+        let (key, entry) = decode_record(record(i)).ok()?;
+        let found = match moves_left {
+            Some(left) => key == (*board, left),
+            None => key.0 == *board,
+        };
+        found.then_some(entry)
+    }
+
+    /// Read every entry into a [`Tablebase`]
+    ///
+    /// # Errors
+    /// Returns [`io::ErrorKind::InvalidData`] for a damaged entry.
+    pub fn load(&self) -> io::Result<Tablebase> {
+        Tablebase::read(&self.map[..])
+    }
+
+    /// Copy the file to `out`
+    ///
+    /// # Errors
+    /// Returns any error of `out`.
+    pub fn write<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_all(&self.map)?;
+        out.flush()
+    }
+
+    #[must_use]
+    pub fn rules(&self) -> Rules {
+        self.header.rules
+    }
+
+    #[must_use]
+    pub fn goal(&self) -> i32 {
+        self.header.goal
+    }
+
+    #[must_use]
+    pub fn horizon(&self) -> u32 {
+        self.header.horizon
+    }
+
+    /// Number of (position, moves left) entries
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.header.records.len() / RECORD
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.header.records.is_empty()
+    }
+}
+
+/// First index in `0..len` where `before` is false, `before` being true
+/// for a prefix of the range
+fn partition_point(len: usize, before: impl Fn(usize) -> bool) -> usize {
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if before(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Fields of a version 2 header, and where its entries lie
+struct Header {
+    rules: Rules,
+    goal: i32,
+    horizon: u32,
+    records: Range<usize>,
+}
+
+impl Header {
+    fn parse(bytes: &[u8]) -> io::Result<Header> {
+        let mut at = 0;
+        let mut take = |n: usize| {
+            let field = bytes
+                .get(at..at + n)
+                .ok_or_else(|| invalid("truncated tablebase header"))?;
+            at += n;
+            Ok::<_, io::Error>(field)
+        };
+        if take(MAGIC.len())? != MAGIC {
+            return Err(invalid("not a tablebase file"));
+        }
+        match take(1)?[0] {
+            VERSION => {}
+            1 => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "version 1 tablebases cannot be mapped; load and save it again",
+                ));
+            }
+            v => return Err(invalid(&format!("unsupported tablebase version {v}"))),
+        }
+        let tag_len = u32::from_le_bytes(take(4)?.try_into().expect("4 bytes"));
+        let tag =
+            std::str::from_utf8(take(tag_len as usize)?).map_err(|e| invalid(&e.to_string()))?;
+        let rules = Rules::from_tag(tag).map_err(|e| invalid(&e))?;
+        let goal = i32::from_le_bytes(take(4)?.try_into().expect("4 bytes"));
+        let horizon = u32::from_le_bytes(take(4)?.try_into().expect("4 bytes"));
+        let count = u64::from_le_bytes(take(8)?.try_into().expect("8 bytes"));
+        let size = usize::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_mul(RECORD));
+        let records = size.map(|size| at..at + size);
+        match records {
+            Some(records) if records.end.checked_add(32) == Some(bytes.len()) => Ok(Header {
+                rules,
+                goal,
+                horizon,
+                records,
+            }),
+            _ => Err(invalid(&format!(
+                "tablebase of {count} entries has the wrong size"
+            ))),
+        }
+    }
+
+    /// Check `bytes`, the whole file, against its trailing checksum
+    fn verify(&self, bytes: &[u8]) -> io::Result<()> {
+        let (body, digest) = bytes.split_at(self.records.end);
+        if Sha256::digest(body)[..] != *digest {
+            return Err(invalid("tablebase checksum mismatch"));
+        }
+        Ok(())
+    }
+}
+
+/// Read the rest of a version 1 file, after its magic
+fn read_v1<R: Read>(mut input: R) -> io::Result<Tablebase> {
+    fn take<const N: usize>(input: &mut impl Read) -> io::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        input.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    let mut tag = vec![0; u32::from_le_bytes(take(&mut input)?) as usize];
+    input.read_exact(&mut tag)?;
+    let tag = String::from_utf8(tag).map_err(|e| invalid(&e.to_string()))?;
+    let rules = Rules::from_tag(&tag).map_err(|e| invalid(&e))?;
+    let goal = i32::from_le_bytes(take(&mut input)?);
+    let horizon = u32::from_le_bytes(take(&mut input)?);
+    let count = u64::from_le_bytes(take(&mut input)?);
+
+    let mut entries = HashMap::new();
+    for _ in 0..count {
+        let (key, entry) = decode_record(&take::<RECORD>(&mut input)?)?;
+        entries.insert(key, entry);
+    }
+    Ok(Tablebase {
+        rules,
+        goal,
+        horizon,
+        entries,
+    })
+}
+
+/// One entry on disk: 16 tiles and the moves left, the two values and the
+/// best move's index in [`ALL_DIRECTIONS`] (255 for none)
+fn encode_record(&(board, left): &(Board, u32), entry: &Entry) -> [u8; RECORD] {
+    let mut record = [0; RECORD];
+    let (mut tiles, rest) = record.split_at_mut(64);
+    for tile in board.iter().flatten() {
+        tiles[..4].copy_from_slice(&tile.to_le_bytes());
+        tiles = &mut tiles[4..];
+    }
+    rest[..4].copy_from_slice(&left.to_le_bytes());
+    rest[4..12].copy_from_slice(&entry.win_probability.to_le_bytes());
+    rest[12..20].copy_from_slice(&entry.expected_score.to_le_bytes());
+    let index = entry
+        .best_move
+        .and_then(|dir| ALL_DIRECTIONS.iter().position(|&d| d == dir));
+    rest[20] = index.map_or(u8::MAX, |i| i as u8);
+    record
+}
+
+fn decode_key(record: &[u8]) -> (Board, u32) {
+    let word = |i: usize| record[4 * i..4 * i + 4].try_into().expect("4 bytes");
+    let mut board = [[0; 4]; 4];
+    for (i, tile) in board.iter_mut().flatten().enumerate() {
+        *tile = i32::from_le_bytes(word(i));
+    }
+    (board, u32::from_le_bytes(word(16)))
+}
+
+fn decode_record(record: &[u8]) -> io::Result<((Board, u32), Entry)> {
+    let float = |at: usize| f64::from_le_bytes(record[at..at + 8].try_into().expect("8 bytes"));
+    let best_move = match record[84] {
+        u8::MAX => None,
+        i => Some(
+            *ALL_DIRECTIONS
+                .get(usize::from(i))
+                .ok_or_else(|| invalid(&format!("invalid move index: {i}")))?,
+        ),
+    };
+    let entry = Entry {
+        win_probability: float(68),
+        expected_score: float(76),
+        best_move,
+    };
+    Ok((decode_key(record), entry))
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Value of a playable `board` given the values of the next layer, each
+/// move spawning `tiles` tiles
+fn best_entry(
+    engine: &Engine,
+    rules: &Rules,
+    board: &Board,
+    tiles: usize,
+    value: impl Fn(&Board) -> Entry,
+) -> Entry {
+    let mut best = Entry::DECIDED;
+    for dir in engine.legal_moves(board) {
+        let (slid, gain) = engine.slide(board, dir);
+        let mut entry = Entry {
+            win_probability: 0.0,
+            expected_score: f64::from(gain),
+            best_move: Some(dir),
+        };
+        for (child, chance) in spawn_outcomes_of(&slid, rules, tiles) {
+            let next = value(&child);
+            entry.win_probability += chance * next.win_probability;
+            entry.expected_score += chance * next.expected_score;
+        }
+        let better = best.best_move.is_none()
+            || entry.win_probability > best.win_probability + 1e-12
+            || (entry.win_probability > best.win_probability - 1e-12
+                && entry.expected_score > best.expected_score);
+        if better {
+            best = entry;
+        }
+    }
+    best
+}
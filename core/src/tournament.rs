@@ -0,0 +1,648 @@
+//! Seed-paired tournaments between agents.
+//!
+//! Game `i` of every agent starts from the same board and draws its spawns
+//! from the same seeded stream, so score differences between agents are
+//! paired per game and can be tested for significance. Agents tuned for
+//! other targets than score are ranked on several [`Objective`]s at once
+//! by [`pareto`].
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::arrow::{Column, Table};
+use crate::progress::Progress;
+use crate::{Board, Direction, Engine, Rules, State};
+
+/// Games end after this many consecutive moves that leave the board unchanged
+const STALL_LIMIT: usize = 64;
+
+/// How one game of one agent went
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub score: i64,
+    pub moves: usize,
+    pub max_tile: i32,
+    /// Whether the game reached the victory tile
+    pub won: bool,
+}
+
+/// One move of a traced game
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    /// Board the move was played on
+    pub board: Board,
+    pub direction: Direction,
+    pub delta: i32,
+    /// Whether the move changed the board
+    pub moved: bool,
+}
+
+/// Moves of every game, one list of games per agent
+pub type Traces = Vec<Vec<Vec<Move>>>;
+
+/// Aggregate results of one agent
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AgentStats {
+    pub games: usize,
+    pub mean_score: f64,
+    pub median_score: f64,
+    /// Sample standard deviation of the scores
+    pub std_score: f64,
+    pub win_rate: f64,
+    pub mean_moves: f64,
+    pub max_tile: i32,
+}
+
+/// Paired comparison of agents `a` and `b` over the same games
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub a: usize,
+    pub b: usize,
+    /// Mean of `a`'s score minus `b`'s, game by game
+    pub mean_difference: f64,
+    /// Games where `a` scored more than `b`
+    pub wins: usize,
+    /// Games where `a` scored less than `b`
+    pub losses: usize,
+    pub ties: usize,
+    /// Paired t statistic of the score differences
+    pub t_statistic: f64,
+    /// Two-sided p-value of the paired t-test
+    pub p_value: f64,
+    /// Two-sided p-value of the exact sign test on wins and losses
+    pub sign_p_value: f64,
+}
+
+/// Full tournament result
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standings {
+    pub seed: u64,
+    /// Per-game outcomes, one list per agent in the order given
+    pub outcomes: Vec<Vec<Outcome>>,
+    /// One entry per agent in the order given
+    pub agents: Vec<AgentStats>,
+    /// One entry per pair of agents, `a < b`
+    pub comparisons: Vec<Comparison>,
+}
+
+/// A target agents are compared on, higher being better
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+    /// Mean final score
+    Score,
+    /// Mean largest tile reached
+    MaxTile,
+    /// Mean moves survived
+    Moves,
+    /// Fraction of games reaching the winning tile, 65536 in the default
+    /// rules
+    WinRate,
+}
+
+impl Objective {
+    pub const ALL: [Objective; 4] = [
+        Objective::Score,
+        Objective::MaxTile,
+        Objective::Moves,
+        Objective::WinRate,
+    ];
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Objective::Score => "score",
+            Objective::MaxTile => "max_tile",
+            Objective::Moves => "moves",
+            Objective::WinRate => "win_rate",
+        }
+    }
+
+    /// The objective called `name`, as [`name`](Self::name) gives it
+    ///
+    /// # Errors
+    /// Returns an error naming the objectives for any other name.
+    pub fn parse(name: &str) -> Result<Objective, String> {
+        Objective::ALL
+            .into_iter()
+            .find(|o| o.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Objective::ALL.iter().map(|o| o.name()).collect();
+                format!(
+                    "unknown objective: {name} (expected one of {})",
+                    names.join(", ")
+                )
+            })
+    }
+
+    /// The objective's value over `games`, `0` for none
+    #[must_use]
+    pub fn of(self, games: &[Outcome]) -> f64 {
+        if games.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = games
+            .iter()
+            .map(|g| match self {
+                Objective::Score => g.score as f64,
+                Objective::MaxTile => f64::from(g.max_tile),
+                Objective::Moves => g.moves as f64,
+                Objective::WinRate => f64::from(u8::from(g.won)),
+            })
+            .sum();
+        total / games.len() as f64
+    }
+}
+
+/// Agents ranked on several objectives at once: agent `a` dominates `b`
+/// when it does at least as well on every objective and better on one
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pareto {
+    pub objectives: Vec<Objective>,
+    /// Each agent's value of every objective, in order
+    pub values: Vec<Vec<f64>>,
+    /// Each agent's front: `0` when no agent dominates it, `1` when only
+    /// agents of front `0` do, and so on
+    pub ranks: Vec<usize>,
+}
+
+impl Pareto {
+    /// Agents no other agent dominates
+    #[must_use]
+    pub fn front(&self) -> Vec<usize> {
+        (0..self.ranks.len())
+            .filter(|&a| self.ranks[a] == 0)
+            .collect()
+    }
+}
+
+/// Rank agents by `objectives` over their per-game outcomes, one list per
+/// agent.
+///
+/// ```
+/// use akioi_2048_core::tournament::{Objective, Outcome, pareto};
+///
+/// let game = |score, moves| Outcome { score, moves, max_tile: 256, won: false };
+/// let outcomes = vec![vec![game(3000, 200)], vec![game(2000, 400)], vec![game(1000, 300)]];
+/// let ranked = pareto(&outcomes, &[Objective::Score, Objective::Moves]);
+/// // the last agent lasts shorter than the second and scores less
+/// assert_eq!(ranked.ranks, [0, 0, 1]);
+/// assert_eq!(ranked.front(), [0, 1]);
+/// ```
+#[must_use]
+pub fn pareto(outcomes: &[Vec<Outcome>], objectives: &[Objective]) -> Pareto {
+    let values: Vec<Vec<f64>> = outcomes
+        .iter()
+        .map(|games| objectives.iter().map(|o| o.of(games)).collect())
+        .collect();
+    let dominates = |a: &[f64], b: &[f64]| {
+        a.iter().zip(b).all(|(x, y)| x >= y) && a.iter().zip(b).any(|(x, y)| x > y)
+    };
+    // peel off the agents no unranked agent dominates, front by front
+    let mut ranks = vec![usize::MAX; values.len()];
+    let mut rank = 0;
+    while ranks.contains(&usize::MAX) {
+        let unranked: Vec<usize> = (0..values.len())
+            .filter(|&a| ranks[a] == usize::MAX)
+            .collect();
+        let front: Vec<usize> = unranked
+            .iter()
+            .copied()
+            .filter(|&a| !unranked.iter().any(|&b| dominates(&values[b], &values[a])))
+            .collect();
+        for a in front {
+            ranks[a] = rank;
+        }
+        rank += 1;
+    }
+    Pareto {
+        objectives: objectives.to_vec(),
+        values,
+        ranks,
+    }
+}
+
+/// Seed of game `game` in a tournament seeded with `seed`
+#[must_use]
+pub fn game_seed(seed: u64, game: usize) -> u64 {
+    seed.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        .wrapping_add(game as u64)
+}
+
+/// Play game `game` of the tournament seeded with `seed` with `agent`.
+///
+/// # Errors
+/// Propagates the first error returned by `agent`.
+pub fn play<E>(
+    seed: u64,
+    game: usize,
+    engine: &Engine,
+    agent: &mut impl FnMut(&Board) -> Result<Direction, E>,
+) -> Result<Outcome, E> {
+    play_traced(seed, game, engine, agent, &mut |_| {})
+}
+
+/// [`play`], passing every move to `on_move`
+///
+/// # Errors
+/// Propagates the first error returned by `agent`.
+pub fn play_traced<E>(
+    seed: u64,
+    game: usize,
+    engine: &Engine,
+    agent: &mut impl FnMut(&Board) -> Result<Direction, E>,
+    on_move: &mut impl FnMut(Move),
+) -> Result<Outcome, E> {
+    let mut rng = StdRng::seed_from_u64(game_seed(seed, game));
+    let mut board = engine.init(&mut rng);
+    let mut outcome = Outcome {
+        score: 0,
+        moves: 0,
+        max_tile: 0,
+        won: false,
+    };
+    let (mut stalled, mut moved) = (0, 0);
+    let mut state = engine.state(&board);
+    while state == State::Continue && stalled < STALL_LIMIT {
+        let dir = agent(&board)?;
+        let (next, delta, next_state) = engine
+            .step_after(board, dir, &mut rng, moved)
+            .expect("engine boards are always valid");
+        on_move(Move {
+            board,
+            direction: dir,
+            delta,
+            moved: next != board,
+        });
+        outcome.score += i64::from(delta);
+        outcome.moves += 1;
+        if next == board {
+            stalled += 1;
+        } else {
+            (stalled, moved) = (0, moved + 1);
+        }
+        board = next;
+        state = next_state;
+    }
+    outcome.won = state == State::Victory;
+    outcome.max_tile = board.iter().flatten().copied().max().unwrap_or(0);
+    Ok(outcome)
+}
+
+/// Play `games_per_agent` seed-paired games with every agent and compare
+/// them, running each agent on its own thread.
+///
+/// ```
+/// use akioi_2048_core::tournament::tournament;
+/// use akioi_2048_core::{ALL_DIRECTIONS, Board, Rules};
+///
+/// let agents = [0, 1].map(|offset| {
+///     let mut turn = offset;
+///     move |_: &Board| {
+///         turn += 1;
+///         Ok::<_, String>(ALL_DIRECTIONS[turn % 4])
+///     }
+/// });
+/// let standings = tournament(1, 4, &Rules::AKIOI, agents.into()).unwrap();
+/// assert_eq!(standings.agents[0].games, 4);
+/// assert_eq!(standings.comparisons.len(), 1);
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules, or the first error returned by
+/// an agent.
+pub fn tournament<A, E>(
+    seed: u64,
+    games_per_agent: usize,
+    rules: &Rules,
+    agents: Vec<A>,
+) -> Result<Standings, E>
+where
+    A: FnMut(&Board) -> Result<Direction, E> + Send,
+    E: From<String> + Send,
+{
+    let progress = Progress::new();
+    let (standings, _) =
+        tournament_with_progress(seed, games_per_agent, rules, agents, false, &progress)?;
+    Ok(standings)
+}
+
+/// [`tournament`] that also returns every move of every game when
+/// `traced`, by agent and game (see [`moves_table`]), counts finished
+/// games in `progress` and stops between games once it is cancelled
+///
+/// # Errors
+/// As [`tournament`], and [`CANCELLED`](crate::progress::CANCELLED) once
+/// cancelled.
+pub fn tournament_with_progress<A, E>(
+    seed: u64,
+    games_per_agent: usize,
+    rules: &Rules,
+    agents: Vec<A>,
+    traced: bool,
+    progress: &Progress,
+) -> Result<(Standings, Traces), E>
+where
+    A: FnMut(&Board) -> Result<Direction, E> + Send,
+    E: From<String> + Send,
+{
+    let engine = Engine::new(*rules)?;
+    progress.set_total(agents.len() * games_per_agent);
+    let played = std::thread::scope(|scope| {
+        let handles: Vec<_> = agents
+            .into_iter()
+            .map(|mut agent| {
+                let engine = &engine;
+                scope.spawn(move || {
+                    let mut outcomes = Vec::with_capacity(games_per_agent);
+                    let mut traces = Vec::new();
+                    for game in 0..games_per_agent {
+                        progress.check()?;
+                        let mut trace = Vec::new();
+                        let mut on_move = |m| {
+                            if traced {
+                                trace.push(m);
+                            }
+                        };
+                        outcomes.push(play_traced(seed, game, engine, &mut agent, &mut on_move)?);
+                        if traced {
+                            traces.push(trace);
+                        }
+                        progress.advance(1);
+                    }
+                    Ok((outcomes, traces))
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("agent threads do not panic"))
+            .collect::<Result<Vec<_>, E>>()
+    })?;
+    let (outcomes, traces) = played.into_iter().unzip();
+    Ok((standings(seed, outcomes), traces))
+}
+
+/// One row per game: `agent` and `game` (indices), the game's `seed`,
+/// `score`, `moves`, `max_tile` and `won`
+#[must_use]
+pub fn games_table(standings: &Standings) -> Table {
+    let rows = || {
+        standings
+            .outcomes
+            .iter()
+            .enumerate()
+            .flat_map(|(agent, games)| games.iter().enumerate().map(move |(g, o)| (agent, g, o)))
+    };
+    Table::new()
+        .column("agent", Column::Int32(rows().map(|r| r.0 as i32).collect()))
+        .column("game", Column::Int32(rows().map(|r| r.1 as i32).collect()))
+        .column(
+            "seed",
+            Column::UInt64(rows().map(|r| game_seed(standings.seed, r.1)).collect()),
+        )
+        .column("score", Column::Int64(rows().map(|r| r.2.score).collect()))
+        .column(
+            "moves",
+            Column::Int64(rows().map(|r| r.2.moves as i64).collect()),
+        )
+        .column(
+            "max_tile",
+            Column::Int32(rows().map(|r| r.2.max_tile).collect()),
+        )
+        .column("won", Column::Bool(rows().map(|r| r.2.won).collect()))
+}
+
+/// One row per move: `agent`, `game` and `move` (indices), `direction`
+/// (`"Down"`, `"Right"`, `"Up"` or `"Left"`), `delta`, `score` (the total
+/// after the move), `moved`, and the board it was played on as `cell_0` to
+/// `cell_15` in row-major order
+#[must_use]
+pub fn moves_table(traces: &[Vec<Vec<Move>>]) -> Table {
+    let mut agents = Vec::new();
+    let mut games = Vec::new();
+    let mut indices = Vec::new();
+    let mut directions = Vec::new();
+    let mut deltas = Vec::new();
+    let mut scores = Vec::new();
+    let mut moved = Vec::new();
+    let mut cells: Vec<Vec<i32>> = vec![Vec::new(); 16];
+    for (agent, traces) in traces.iter().enumerate() {
+        for (game, trace) in traces.iter().enumerate() {
+            let mut score = 0;
+            for (i, m) in trace.iter().enumerate() {
+                score += i64::from(m.delta);
+                agents.push(agent as i32);
+                games.push(game as i32);
+                indices.push(i as i32);
+                directions.push(format!("{:?}", m.direction));
+                deltas.push(m.delta);
+                scores.push(score);
+                moved.push(m.moved);
+                for (column, &tile) in cells.iter_mut().zip(m.board.iter().flatten()) {
+                    column.push(tile);
+                }
+            }
+        }
+    }
+    let mut table = Table::new()
+        .column("agent", Column::Int32(agents))
+        .column("game", Column::Int32(games))
+        .column("move", Column::Int32(indices))
+        .column("direction", Column::Utf8(directions))
+        .column("delta", Column::Int32(deltas))
+        .column("score", Column::Int64(scores))
+        .column("moved", Column::Bool(moved));
+    for (i, column) in cells.into_iter().enumerate() {
+        table = table.column(format!("cell_{i}"), Column::Int32(column));
+    }
+    table
+}
+
+/// Aggregate and compare per-game outcomes, one list per agent, where game
+/// `i` of every list was played from the same seed.
+#[must_use]
+pub fn standings(seed: u64, outcomes: Vec<Vec<Outcome>>) -> Standings {
+    let agents = outcomes.iter().map(|games| summarize(games)).collect();
+    let mut comparisons = Vec::new();
+    for a in 0..outcomes.len() {
+        for b in a + 1..outcomes.len() {
+            comparisons.push(compare(a, b, &outcomes[a], &outcomes[b]));
+        }
+    }
+    Standings {
+        seed,
+        outcomes,
+        agents,
+        comparisons,
+    }
+}
+
+fn summarize(games: &[Outcome]) -> AgentStats {
+    if games.is_empty() {
+        return AgentStats::default();
+    }
+    let n = games.len() as f64;
+    let scores: Vec<f64> = games.iter().map(|g| g.score as f64).collect();
+    let (mean_score, std_score) = mean_and_std(&scores);
+    let mut sorted = scores;
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    let median_score = if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    };
+    AgentStats {
+        games: games.len(),
+        mean_score,
+        median_score,
+        std_score,
+        win_rate: games.iter().filter(|g| g.won).count() as f64 / n,
+        mean_moves: games.iter().map(|g| g.moves as f64).sum::<f64>() / n,
+        max_tile: games.iter().map(|g| g.max_tile).max().unwrap_or(0),
+    }
+}
+
+fn compare(a: usize, b: usize, first: &[Outcome], second: &[Outcome]) -> Comparison {
+    let diffs: Vec<f64> = first
+        .iter()
+        .zip(second)
+        .map(|(x, y)| (x.score - y.score) as f64)
+        .collect();
+    let wins = diffs.iter().filter(|&&d| d > 0.0).count();
+    let losses = diffs.iter().filter(|&&d| d < 0.0).count();
+    let (mean_difference, std) = mean_and_std(&diffs);
+
+    let n = diffs.len() as f64;
+    let (t_statistic, p_value) = if diffs.len() < 2 {
+        (0.0, 1.0)
+    } else if std == 0.0 {
+        // every game differs by the same amount: certain unless that is zero
+        if mean_difference == 0.0 {
+            (0.0, 1.0)
+        } else {
+            (mean_difference.signum() * f64::INFINITY, 0.0)
+        }
+    } else {
+        let t = mean_difference / (std / n.sqrt());
+        (t, student_t_two_sided(t, n - 1.0))
+    };
+    Comparison {
+        a,
+        b,
+        mean_difference,
+        wins,
+        losses,
+        ties: diffs.len() - wins - losses,
+        t_statistic,
+        p_value,
+        sign_p_value: sign_test(wins, losses),
+    }
+}
+
+/// Mean and sample standard deviation; zeros when there are too few values
+fn mean_and_std(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, var.sqrt())
+}
+
+/// Two-sided p-value of Student's t distribution with `df` degrees of freedom
+fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    incomplete_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+/// Two-sided p-value of the exact binomial sign test
+fn sign_test(wins: usize, losses: usize) -> f64 {
+    let n = wins + losses;
+    if n == 0 {
+        return 1.0;
+    }
+    let k = wins.min(losses);
+    // P(X <= k) for X ~ Binomial(n, 1/2), summed in log space
+    let ln_half_n = n as f64 * 0.5f64.ln();
+    let mut ln_choose = 0.0;
+    let mut tail = 0.0;
+    for i in 0..=k {
+        if i > 0 {
+            ln_choose += ((n - i + 1) as f64).ln() - (i as f64).ln();
+        }
+        tail += (ln_choose + ln_half_n).exp();
+    }
+    (2.0 * tail).min(1.0)
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    // the continued fraction converges fast below the mean, so use symmetry
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - ln_front.exp() * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction of the incomplete beta function (modified Lentz)
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1.0e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..300 {
+        let m = f64::from(m);
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        for coeff in [even, odd] {
+            d = 1.0 + coeff * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + coeff / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1.0e-15 {
+            break;
+        }
+    }
+    h
+}
+
+/// Natural log of the gamma function (Lanczos approximation)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000_000_000_190_015;
+    for (i, c) in COEFFS.iter().enumerate() {
+        series += c / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
@@ -0,0 +1,241 @@
+//! Training an [`NTuple`] network by TD(λ) on afterstates.
+//!
+//! Each training game is played greedily by the network being trained: a
+//! move is worth the score it gains plus the value of the board it slides
+//! to, before the spawn (the afterstate). After every move the value of
+//! the previous afterstate is moved toward the gain plus the value of the
+//! next one (Szubert and Jaśkowski, 2014), and with `λ > 0` the error is
+//! passed back to earlier afterstates too, decaying by `λ` per move. The
+//! trace is cut once `λᵏ` falls below 1%, at 64 moves at most.
+//!
+//! Periodic evaluation matches play fixed seeds greedily without learning,
+//! so their mean scores form a learning curve comparable across runs.
+
+use std::collections::VecDeque;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::ntuple::{FEATURES, NTuple};
+use crate::progress::Progress;
+use crate::tournament::game_seed;
+use crate::variant::MergeRule;
+use crate::{Engine, Rules, State};
+
+/// Distinguishes the evaluation seeds from the training ones
+const EVAL_SALT: u64 = 0x6576_616C_5F73_6565;
+
+/// Longest trace of afterstates the error is passed back along
+const MAX_TRACE: usize = 64;
+
+/// Settings of [`train`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrainOptions {
+    pub rules: Rules,
+    /// How far one update moves an afterstate's value toward its target,
+    /// as a share of the error; split evenly over the weights it reads
+    pub learning_rate: f64,
+    /// Trace decay: 0 updates only the last afterstate, 1 every one in the
+    /// trace alike
+    pub lambda: f64,
+    /// Seed of the training games
+    pub seed: u64,
+    /// Evaluate after every this many training games, and after the last;
+    /// 0 evaluates after the last only
+    pub eval_every: usize,
+    /// Games per evaluation match; 0 skips evaluation
+    pub eval_games: usize,
+    /// Stop each game, training or evaluation, after this many moves
+    pub max_moves: Option<usize>,
+}
+
+impl Default for TrainOptions {
+    fn default() -> Self {
+        TrainOptions {
+            rules: Rules::default(),
+            learning_rate: 0.1,
+            lambda: 0.5,
+            seed: 0,
+            eval_every: 0,
+            eval_games: 10,
+            max_moves: None,
+        }
+    }
+}
+
+/// Result of one evaluation match
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Evaluation {
+    /// Training games played before the match
+    pub games: usize,
+    pub mean_score: f64,
+    /// Largest tile reached in any game of the match
+    pub max_tile: i32,
+    /// Share of the games that reached the winning tile
+    pub win_rate: f64,
+}
+
+/// Learning curves of [`train`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trained {
+    /// Final score of every training game
+    pub scores: Vec<i64>,
+    /// Every evaluation match, in order
+    pub evaluations: Vec<Evaluation>,
+}
+
+/// Train `net` over `n_games` games of self-play, counting them in
+/// `progress` and calling `on_eval` after each evaluation match.
+///
+/// ```
+/// use akioi_2048_core::ntuple::NTuple;
+/// use akioi_2048_core::progress::Progress;
+/// use akioi_2048_core::train::{TrainOptions, train};
+///
+/// let mut net = NTuple::new();
+/// let options = TrainOptions { eval_every: 5, eval_games: 2, ..TrainOptions::default() };
+/// let curves = train(&mut net, 10, &options, &Progress::new(), |_| Ok(())).unwrap();
+/// assert_eq!(curves.scores.len(), 10);
+/// let games: Vec<_> = curves.evaluations.iter().map(|e| e.games).collect();
+/// assert_eq!(games, [5, 10]);
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules, a learning rate that is not
+/// positive, a `lambda` outside `[0, 1]`, the first error of `on_eval`, or
+/// [`CANCELLED`](crate::progress::CANCELLED) once cancelled.
+pub fn train(
+    net: &mut NTuple,
+    n_games: usize,
+    options: &TrainOptions,
+    progress: &Progress,
+    mut on_eval: impl FnMut(&Evaluation) -> Result<(), String>,
+) -> Result<Trained, String> {
+    if !(options.learning_rate > 0.0 && options.learning_rate.is_finite()) {
+        return Err(format!(
+            "learning rate must be positive, got {}",
+            options.learning_rate
+        ));
+    }
+    if !(0.0..=1.0).contains(&options.lambda) {
+        return Err(format!(
+            "lambda must be between 0 and 1, got {}",
+            options.lambda
+        ));
+    }
+    let engine = Engine::new(options.rules)?;
+    progress.set_total(n_games);
+    let mut trained = Trained {
+        scores: Vec::with_capacity(n_games),
+        evaluations: Vec::new(),
+    };
+    for game in 0..n_games {
+        progress.check()?;
+        trained.scores.push(learn(net, &engine, options, game));
+        progress.advance(1);
+        let done = game + 1;
+        let periodic = options.eval_every > 0 && done.is_multiple_of(options.eval_every);
+        if options.eval_games > 0 && (periodic || done == n_games) {
+            let evaluation = evaluate(net, &engine, options, done);
+            on_eval(&evaluation)?;
+            trained.evaluations.push(evaluation);
+        }
+    }
+    Ok(trained)
+}
+
+/// Afterstates the error of a move is passed back to under `lambda`
+fn trace_len(lambda: f64) -> usize {
+    if lambda == 0.0 {
+        return 1;
+    }
+    let len = 0.01_f64.ln() / lambda.ln();
+    if len.is_finite() {
+        (len.ceil() as usize).clamp(1, MAX_TRACE)
+    } else {
+        MAX_TRACE
+    }
+}
+
+/// Play training game `game` with `net`, learning as it goes; its score
+fn learn(net: &mut NTuple, engine: &Engine, options: &TrainOptions, game: usize) -> i64 {
+    let mut rng = StdRng::seed_from_u64(game_seed(options.seed, game));
+    let step = options.learning_rate / FEATURES as f64;
+    let len = trace_len(options.lambda);
+    let mut trace = VecDeque::with_capacity(len);
+    let back = |net: &mut NTuple, trace: &VecDeque<_>, error: f64| {
+        let mut weight = step * error;
+        for after in trace.iter().rev() {
+            net.update(after, weight as f32);
+            weight *= options.lambda;
+        }
+    };
+
+    let mut board = engine.init(&mut rng);
+    let (mut score, mut moves) = (0, 0);
+    loop {
+        if options.max_moves.is_some_and(|limit| moves >= limit) {
+            return score;
+        }
+        let choice = (engine.state(&board) == State::Continue)
+            .then(|| net.best_move(engine, &board))
+            .flatten();
+        let Some((dir, after, gain)) = choice else {
+            break;
+        };
+        if let Some(previous) = trace.back() {
+            let error = f64::from(gain) + net.value(&after) - net.value(previous);
+            back(net, &trace, error);
+        }
+        if trace.len() == len {
+            trace.pop_front();
+        }
+        trace.push_back(after);
+        let (next, delta, _) = engine
+            .step_after(board, dir, &mut rng, moves as u64)
+            .expect("engine boards are always valid");
+        score += i64::from(delta);
+        moves += 1;
+        board = next;
+    }
+    // nothing follows the last afterstate
+    if let Some(last) = trace.back() {
+        let error = -net.value(last);
+        back(net, &trace, error);
+    }
+    score
+}
+
+/// Play the evaluation match after `games` training games
+fn evaluate(net: &NTuple, engine: &Engine, options: &TrainOptions, games: usize) -> Evaluation {
+    let winning = options.rules.variant.winning_tile(&options.rules);
+    let (mut total, mut max_tile, mut wins) = (0, 0, 0);
+    for game in 0..options.eval_games {
+        let mut rng = StdRng::seed_from_u64(game_seed(options.seed ^ EVAL_SALT, game));
+        let mut board = engine.init(&mut rng);
+        let mut moves = 0;
+        while options.max_moves.is_none_or(|limit| moves < limit) {
+            if engine.state(&board) != State::Continue {
+                break;
+            }
+            let Some((dir, _, _)) = net.best_move(engine, &board) else {
+                break;
+            };
+            let (next, delta, _) = engine
+                .step_after(board, dir, &mut rng, moves as u64)
+                .expect("engine boards are always valid");
+            total += i64::from(delta);
+            moves += 1;
+            board = next;
+        }
+        let top = board.iter().flatten().copied().max().unwrap_or(0);
+        max_tile = max_tile.max(top);
+        wins += usize::from(top >= winning);
+    }
+    Evaluation {
+        games,
+        mean_score: total as f64 / options.eval_games as f64,
+        max_tile,
+        win_rate: wins as f64 / options.eval_games as f64,
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{Board, Direction, State};
+
+/// One recorded `(state, action, reward, next_state, done)` tuple.
+///
+/// `goal` is `None` for plain transitions (reward = score delta) and
+/// `Some(tile)` for goal-conditioned ones (reward = 1.0 once `tile` is reached).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transition {
+    pub state: Board,
+    pub action: Direction,
+    pub reward: f32,
+    pub next_state: Board,
+    pub done: bool,
+    pub goal: Option<i32>,
+}
+
+/// Raw step as played, before any goal labelling
+#[derive(Clone, Copy)]
+struct Step {
+    state: Board,
+    action: Direction,
+    delta: i32,
+    next_state: Board,
+    done: bool,
+}
+
+/// Collects transitions episode by episode.
+///
+/// With `hindsight` enabled, every finished episode is additionally emitted
+/// relabelled with the max tile it actually achieved as the goal (HER "final"
+/// strategy), so failed episodes still yield successful goal-reaching data.
+pub struct TrajectoryRecorder {
+    goal: Option<i32>,
+    hindsight: bool,
+    episode: Vec<Step>,
+    transitions: Vec<Transition>,
+}
+
+impl TrajectoryRecorder {
+    #[must_use]
+    pub fn new(goal: Option<i32>, hindsight: bool) -> Self {
+        Self {
+            goal,
+            hindsight,
+            episode: Vec::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Record one move; the episode is finished automatically once `state`
+    /// is no longer `Continue`.
+    pub fn record(
+        &mut self,
+        state: Board,
+        action: Direction,
+        delta: i32,
+        next_state: Board,
+        outcome: State,
+    ) {
+        let done = outcome != State::Continue;
+        self.episode.push(Step {
+            state,
+            action,
+            delta,
+            next_state,
+            done,
+        });
+        if done {
+            self.finish_episode();
+        }
+    }
+
+    /// Flush the current episode (e.g. when truncated by a move limit).
+    pub fn finish_episode(&mut self) {
+        let episode = std::mem::take(&mut self.episode);
+        let Some(last) = episode.last() else {
+            return;
+        };
+        emit(&mut self.transitions, &episode, self.goal);
+
+        if self.hindsight {
+            let achieved = max_tile(&last.next_state);
+            if self.goal != Some(achieved) {
+                emit(&mut self.transitions, &episode, Some(achieved));
+            }
+        }
+    }
+
+    /// Transitions emitted so far (finished episodes only).
+    #[must_use]
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    /// Drain all emitted transitions, keeping the current episode.
+    pub fn take(&mut self) -> Vec<Transition> {
+        std::mem::take(&mut self.transitions)
+    }
+
+    /// Drop all emitted transitions and the current episode.
+    pub fn clear(&mut self) {
+        self.episode.clear();
+        self.transitions.clear();
+    }
+}
+
+/// Append `episode` labelled for `goal`; goal-conditioned episodes end at
+/// the first step that reaches the goal.
+fn emit(out: &mut Vec<Transition>, episode: &[Step], goal: Option<i32>) {
+    for step in episode {
+        let (reward, done) = match goal {
+            None => (step.delta as f32, step.done),
+            Some(tile) => {
+                let reached = max_tile(&step.next_state) >= tile;
+                (if reached { 1.0 } else { 0.0 }, reached || step.done)
+            }
+        };
+        out.push(Transition {
+            state: step.state,
+            action: step.action,
+            reward,
+            next_state: step.next_state,
+            done,
+            goal,
+        });
+        if done {
+            break;
+        }
+    }
+}
+
+/// Largest numeric tile on the board (0 if there is none)
+#[must_use]
+pub fn max_tile(board: &Board) -> i32 {
+    board.iter().flatten().copied().max().unwrap_or(0).max(0)
+}
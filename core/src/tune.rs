@@ -0,0 +1,734 @@
+//! Tuning the [`Weights`] of the built-in evaluation by self-play.
+//!
+//! Every candidate plays the same `n_games` seeds with a greedy
+//! [`expectimax`] agent, and its fitness is the mean score; the seeds being
+//! shared keeps the comparison between candidates fair, at the cost of
+//! tuning toward those games. The optimizer is CMA-ES or simulated
+//! annealing, started from [`Weights::default`]; [`tune_resumable`] saves
+//! its state to a checkpoint file between generations.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+
+use crate::progress::Progress;
+use crate::search::{Weights, expectimax};
+use crate::stream::Stream;
+use crate::tournament::game_seed;
+use crate::{Engine, Rules, State};
+
+/// Distinguishes the optimizer's random stream from the game seeds
+const OPTIMIZER_SALT: u64 = 0x7475_6E65_5F72_6E67;
+
+/// Dimension of the search space, one per feature
+const N: usize = 6;
+
+/// Search strategy of [`tune_heuristics`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Method {
+    /// Covariance matrix adaptation: a population per generation, whose
+    /// best half moves the mean and reshapes the sampling distribution
+    #[default]
+    CmaEs,
+    /// One neighbour per generation, accepted if better or, with a
+    /// probability that falls as the temperature cools, if worse
+    Annealing,
+}
+
+impl Method {
+    /// `"cma-es"` or `"annealing"`
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cma-es" => Some(Method::CmaEs),
+            "annealing" => Some(Method::Annealing),
+            _ => None,
+        }
+    }
+
+    /// Inverse of [`Method::from_name`]
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Method::CmaEs => "cma-es",
+            Method::Annealing => "annealing",
+        }
+    }
+}
+
+/// Settings of [`tune_heuristics`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TuneOptions {
+    pub method: Method,
+    pub rules: Rules,
+    /// Search depth of the self-play agent
+    pub depth: u32,
+    /// Seed of the games and of the optimizer
+    pub seed: u64,
+    /// Stop each game after this many moves
+    pub max_moves: Option<usize>,
+    /// Candidates per CMA-ES generation (default `4 + 3 ln 6`, i.e. 9)
+    pub population: Option<usize>,
+    /// Initial step size, in weight units
+    pub sigma: f64,
+}
+
+impl Default for TuneOptions {
+    fn default() -> Self {
+        TuneOptions {
+            method: Method::default(),
+            rules: Rules::default(),
+            depth: 1,
+            seed: 0,
+            max_moves: None,
+            population: None,
+            sigma: 0.3,
+        }
+    }
+}
+
+/// Outcome of [`tune_heuristics`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tuned {
+    /// The best weights evaluated, [`Weights::default`] if nothing beat it
+    pub weights: Weights,
+    /// Their mean score over the games
+    pub score: f64,
+    /// The best mean score after each generation
+    pub history: Vec<f64>,
+    /// Candidates evaluated, the starting weights included
+    pub evaluations: usize,
+}
+
+/// Tune the evaluation weights over `generations` generations of
+/// self-play, each candidate playing `n_games` games.
+///
+/// ```
+/// use akioi_2048_core::tune::{TuneOptions, tune_heuristics};
+///
+/// let options = TuneOptions { max_moves: Some(20), population: Some(4), ..TuneOptions::default() };
+/// let tuned = tune_heuristics(2, 2, &options).unwrap();
+/// assert_eq!(tuned.history.len(), 2);
+/// assert_eq!(tuned.evaluations, 1 + 2 * 4);
+/// assert!(tuned.history.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+///
+/// # Errors
+/// Returns an error for inconsistent rules, no games, a population below 2
+/// or a step size that is not positive.
+pub fn tune_heuristics(
+    n_games: usize,
+    generations: usize,
+    options: &TuneOptions,
+) -> Result<Tuned, String> {
+    tune_with_progress(n_games, generations, options, &Progress::new())
+}
+
+/// [`tune_heuristics`], counting self-play games in `progress` and
+/// stopping between games once it is cancelled
+///
+/// # Errors
+/// As [`tune_heuristics`], and [`CANCELLED`](crate::progress::CANCELLED)
+/// once cancelled.
+pub fn tune_with_progress(
+    n_games: usize,
+    generations: usize,
+    options: &TuneOptions,
+    progress: &Progress,
+) -> Result<Tuned, String> {
+    tune_resumable(n_games, generations, options, None, progress)
+}
+
+/// Where and how often [`tune_resumable`] saves its state
+#[derive(Clone, Copy, Debug)]
+pub struct Checkpoints<'a> {
+    pub path: &'a Path,
+    /// Save after every this many generations, and after the last one
+    pub every: usize,
+}
+
+/// [`tune_with_progress`], saving its whole state to a checkpoint file as
+/// it goes and resuming from that file if it exists.
+///
+/// A resumed run ends exactly as the uninterrupted run would have: the
+/// checkpoint holds the optimizer's state, the best weights so far and
+/// the generations done, and the optimizer draws generation `g` from its
+/// own counter-based [`Stream`]. Only whole generations are saved; a run
+/// stopped mid-generation repeats it.
+///
+/// ```
+/// use akioi_2048_core::tune::{Checkpoints, TuneOptions, tune_heuristics, tune_resumable};
+/// use akioi_2048_core::progress::Progress;
+///
+/// let options = TuneOptions { max_moves: Some(20), population: Some(3), ..TuneOptions::default() };
+/// let path = std::env::temp_dir().join("tune-doctest.ckpt");
+/// let checkpoints = Checkpoints { path: &path, every: 1 };
+/// let saved = tune_resumable(1, 2, &options, Some(&checkpoints), &Progress::new()).unwrap();
+/// // the checkpoint holds the finished run
+/// let resumed = tune_resumable(1, 2, &options, Some(&checkpoints), &Progress::new()).unwrap();
+/// assert_eq!(saved, resumed);
+/// assert_eq!(saved, tune_heuristics(1, 2, &options).unwrap());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+///
+/// # Errors
+/// As [`tune_with_progress`], and for a checkpoint that cannot be read or
+/// written, is damaged or was saved by a run with other settings.
+pub fn tune_resumable(
+    n_games: usize,
+    generations: usize,
+    options: &TuneOptions,
+    checkpoints: Option<&Checkpoints<'_>>,
+    progress: &Progress,
+) -> Result<Tuned, String> {
+    if n_games == 0 {
+        return Err("n_games must be positive".to_string());
+    }
+    if !(options.sigma > 0.0 && options.sigma.is_finite()) {
+        return Err(format!("sigma must be positive, got {}", options.sigma));
+    }
+    let population = options
+        .population
+        .unwrap_or(4 + (3.0 * (N as f64).ln()) as usize);
+    if population < 2 {
+        return Err(format!("population must be at least 2, got {population}"));
+    }
+    let per_generation = match options.method {
+        Method::CmaEs => population,
+        Method::Annealing => 1,
+    };
+    progress.set_total((1 + generations * per_generation) * n_games);
+    let mut tuner = Tuner {
+        engine: Engine::new(options.rules)?,
+        options,
+        progress,
+        n_games,
+        rng: Stream::new(options.seed ^ OPTIMIZER_SALT, 0, 0),
+        best: Weights::default().to_array(),
+        best_score: 0.0,
+        history: Vec::with_capacity(generations),
+        evaluations: 0,
+    };
+    let run = run_key(n_games, generations, options, population);
+    let saved = checkpoints
+        .filter(|c| c.path.exists())
+        .map(|c| read_checkpoint(c.path, &run))
+        .transpose()?;
+    // the optimizer's state as its `to_floats`
+    let optimizer = match saved {
+        Some(saved) => {
+            tuner.best = saved.best;
+            tuner.best_score = saved.best_score;
+            tuner.history = saved.history;
+            tuner.evaluations = saved.evaluations;
+            progress.advance(saved.evaluations * n_games);
+            Some(saved.optimizer)
+        }
+        None => {
+            let start = tuner.best;
+            tuner.best_score = tuner.fitness(&start);
+            None
+        }
+    };
+    let save = |tuner: &Tuner<'_>, optimizer: &[f64]| -> Result<(), String> {
+        let Some(c) = checkpoints else {
+            return Ok(());
+        };
+        let done = tuner.history.len();
+        if done == generations || done.is_multiple_of(c.every.max(1)) {
+            write_checkpoint(c.path, &run, tuner, optimizer)
+                .map_err(|e| format!("cannot save checkpoint {}: {e}", c.path.display()))?;
+        }
+        Ok(())
+    };
+    match options.method {
+        Method::CmaEs => {
+            let state = optimizer.map_or_else(
+                || CmaState::new(tuner.best, options.sigma),
+                |floats| CmaState::from_floats(&floats),
+            );
+            tuner.cma_es(state, generations, population, save)?;
+        }
+        Method::Annealing => {
+            let state = optimizer.map_or_else(
+                || AnnealState {
+                    current: tuner.best,
+                    current_score: tuner.best_score,
+                    start_temperature: 0.05 * tuner.best_score.max(1.0),
+                },
+                |floats| AnnealState::from_floats(&floats),
+            );
+            tuner.annealing(state, generations, save)?;
+        }
+    }
+    progress.check()?;
+    Ok(Tuned {
+        weights: Weights::from_array(tuner.best),
+        score: tuner.best_score,
+        history: tuner.history,
+        evaluations: tuner.evaluations,
+    })
+}
+
+struct Tuner<'a> {
+    engine: Engine,
+    options: &'a TuneOptions,
+    progress: &'a Progress,
+    n_games: usize,
+    rng: Stream,
+    best: [f64; N],
+    best_score: f64,
+    /// The best score after each generation done
+    history: Vec<f64>,
+    evaluations: usize,
+}
+
+impl Tuner<'_> {
+    /// Mean score of `x` over the games, played on all cores; games left
+    /// once cancelled score nothing
+    fn fitness(&mut self, x: &[f64; N]) -> f64 {
+        self.evaluations += 1;
+        let weights = Weights::from_array(*x);
+        let threads = std::thread::available_parallelism().map_or(1, usize::from);
+        let (engine, options, n_games) = (&self.engine, self.options, self.n_games);
+        let progress = self.progress;
+        let total: i64 = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads.min(n_games))
+                .map(|t| {
+                    scope.spawn(move || {
+                        (t..n_games)
+                            .step_by(threads)
+                            .map(|game| {
+                                if progress.is_cancelled() {
+                                    return 0;
+                                }
+                                let score = play(engine, options, &weights, game);
+                                progress.advance(1);
+                                score
+                            })
+                            .sum::<i64>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("self-play threads do not panic"))
+                .sum()
+        });
+        let score = total as f64 / n_games as f64;
+        if score > self.best_score {
+            self.best = *x;
+            self.best_score = score;
+        }
+        score
+    }
+
+    /// Start generation `generation`: check for cancellation and draw its
+    /// random numbers from its own stream
+    fn begin(&mut self, generation: usize) -> Result<(), String> {
+        self.progress.check()?;
+        self.rng = Stream::new(self.options.seed ^ OPTIMIZER_SALT, 0, generation as u64);
+        Ok(())
+    }
+
+    fn normal(&mut self) -> f64 {
+        let u: f64 = 1.0 - self.rng.random::<f64>();
+        let v: f64 = self.rng.random();
+        (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+    }
+
+    fn cma_es(
+        &mut self,
+        mut state: CmaState,
+        generations: usize,
+        lambda: usize,
+        save: impl Fn(&Self, &[f64]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let n = N as f64;
+        let mu = lambda / 2;
+        let raw: Vec<f64> = (1..=mu)
+            .map(|i| ((lambda as f64 + 1.0) / 2.0).ln() - (i as f64).ln())
+            .collect();
+        let total: f64 = raw.iter().sum();
+        let w: Vec<f64> = raw.iter().map(|r| r / total).collect();
+        let mu_eff = 1.0 / w.iter().map(|w| w * w).sum::<f64>();
+        let c_sigma = (mu_eff + 2.0) / (n + mu_eff + 5.0);
+        let d_sigma = 1.0 + 2.0 * (((mu_eff - 1.0) / (n + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+        let c_c = (4.0 + mu_eff / n) / (n + 4.0 + 2.0 * mu_eff / n);
+        let c1 = 2.0 / ((n + 1.3).powi(2) + mu_eff);
+        let c_mu =
+            (1.0 - c1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((n + 2.0).powi(2) + mu_eff));
+        let chi_n = n.sqrt() * (1.0 - 1.0 / (4.0 * n) + 1.0 / (21.0 * n * n));
+
+        for generation in self.history.len()..generations {
+            self.begin(generation)?;
+            let CmaState {
+                mean,
+                sigma,
+                c,
+                p_sigma,
+                p_c,
+            } = &mut state;
+            let (b, d) = eigen(c);
+            let mut samples: Vec<([f64; N], f64)> = (0..lambda)
+                .map(|_| {
+                    let z: [f64; N] = std::array::from_fn(|_| self.normal());
+                    let y: [f64; N] =
+                        std::array::from_fn(|i| (0..N).map(|j| b[i][j] * d[j] * z[j]).sum());
+                    let x = std::array::from_fn(|i| mean[i] + *sigma * y[i]);
+                    (y, self.fitness(&x))
+                })
+                .collect();
+            samples.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            let y_w: [f64; N] =
+                std::array::from_fn(|i| (0..mu).map(|k| w[k] * samples[k].0[i]).sum());
+            for i in 0..N {
+                mean[i] += *sigma * y_w[i];
+            }
+            // C^(-1/2) y_w = B D^-1 B^T y_w
+            let bt_y: [f64; N] = std::array::from_fn(|j| (0..N).map(|i| b[i][j] * y_w[i]).sum());
+            let whitened: [f64; N] =
+                std::array::from_fn(|i| (0..N).map(|j| b[i][j] * bt_y[j] / d[j]).sum());
+            let k_sigma = (c_sigma * (2.0 - c_sigma) * mu_eff).sqrt();
+            for i in 0..N {
+                p_sigma[i] = (1.0 - c_sigma) * p_sigma[i] + k_sigma * whitened[i];
+            }
+            let norm = p_sigma.iter().map(|p| p * p).sum::<f64>().sqrt();
+            let decay = (1.0 - (1.0 - c_sigma).powi(2 * (generation as i32 + 1))).sqrt();
+            let h_sigma = norm / decay < (1.4 + 2.0 / (n + 1.0)) * chi_n;
+            let k_c = if h_sigma {
+                (c_c * (2.0 - c_c) * mu_eff).sqrt()
+            } else {
+                0.0
+            };
+            for i in 0..N {
+                p_c[i] = (1.0 - c_c) * p_c[i] + k_c * y_w[i];
+            }
+            let stalled = if h_sigma { 0.0 } else { c_c * (2.0 - c_c) };
+            for i in 0..N {
+                for j in 0..N {
+                    let rank_mu: f64 = (0..mu)
+                        .map(|k| w[k] * samples[k].0[i] * samples[k].0[j])
+                        .sum();
+                    c[i][j] = (1.0 - c1 - c_mu) * c[i][j]
+                        + c1 * (p_c[i] * p_c[j] + stalled * c[i][j])
+                        + c_mu * rank_mu;
+                }
+            }
+            *sigma *= ((c_sigma / d_sigma) * (norm / chi_n - 1.0)).exp();
+            self.progress.check()?;
+            self.history.push(self.best_score);
+            save(self, &state.to_floats())?;
+        }
+        Ok(())
+    }
+
+    fn annealing(
+        &mut self,
+        mut state: AnnealState,
+        generations: usize,
+        save: impl Fn(&Self, &[f64]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        for generation in self.history.len()..generations {
+            self.begin(generation)?;
+            let cooling = 1.0 - generation as f64 / generations as f64;
+            let step = self.options.sigma * cooling;
+            let candidate: [f64; N] =
+                std::array::from_fn(|i| state.current[i] + step * self.normal());
+            let score = self.fitness(&candidate);
+            let temperature = state.start_temperature * cooling;
+            if score >= state.current_score
+                || self.rng.random::<f64>() < ((score - state.current_score) / temperature).exp()
+            {
+                state.current = candidate;
+                state.current_score = score;
+            }
+            self.progress.check()?;
+            self.history.push(self.best_score);
+            save(self, &state.to_floats())?;
+        }
+        Ok(())
+    }
+}
+
+/// State of CMA-ES between generations
+#[derive(Clone, Debug)]
+struct CmaState {
+    mean: [f64; N],
+    sigma: f64,
+    c: [[f64; N]; N],
+    p_sigma: [f64; N],
+    p_c: [f64; N],
+}
+
+impl CmaState {
+    /// Floats [`CmaState::to_floats`] returns
+    const LEN: usize = 1 + N * (N + 3);
+
+    fn new(mean: [f64; N], sigma: f64) -> Self {
+        CmaState {
+            mean,
+            sigma,
+            c: identity(),
+            p_sigma: [0.0; N],
+            p_c: [0.0; N],
+        }
+    }
+
+    fn to_floats(&self) -> Vec<f64> {
+        let mut floats = vec![self.sigma];
+        floats.extend(self.mean);
+        floats.extend(self.c.iter().flatten());
+        floats.extend(self.p_sigma);
+        floats.extend(self.p_c);
+        floats
+    }
+
+    /// Inverse of [`CmaState::to_floats`], given [`CmaState::LEN`] floats
+    fn from_floats(floats: &[f64]) -> Self {
+        let mut rest = floats[1..]
+            .chunks_exact(N)
+            .map(|chunk| <[f64; N]>::try_from(chunk).expect("chunks of N"));
+        let mut next = || rest.next().expect("LEN floats");
+        CmaState {
+            sigma: floats[0],
+            mean: next(),
+            c: std::array::from_fn(|_| next()),
+            p_sigma: next(),
+            p_c: next(),
+        }
+    }
+}
+
+/// State of simulated annealing between steps
+#[derive(Clone, Debug)]
+struct AnnealState {
+    current: [f64; N],
+    current_score: f64,
+    start_temperature: f64,
+}
+
+impl AnnealState {
+    /// Floats [`AnnealState::to_floats`] returns
+    const LEN: usize = N + 2;
+
+    fn to_floats(&self) -> Vec<f64> {
+        let mut floats = vec![self.current_score, self.start_temperature];
+        floats.extend(self.current);
+        floats
+    }
+
+    /// Inverse of [`AnnealState::to_floats`], given [`AnnealState::LEN`]
+    /// floats
+    fn from_floats(floats: &[f64]) -> Self {
+        AnnealState {
+            current_score: floats[0],
+            start_temperature: floats[1],
+            current: floats[2..].try_into().expect("LEN floats"),
+        }
+    }
+}
+
+/// First bytes of a tuning checkpoint, before its version byte
+const CHECKPOINT_MAGIC: &[u8; 7] = b"AKTUNE\0";
+
+/// Version of the checkpoint format
+const CHECKPOINT_VERSION: u8 = 1;
+
+/// Everything that must match for a checkpoint to resume a run
+fn run_key(
+    n_games: usize,
+    generations: usize,
+    options: &TuneOptions,
+    population: usize,
+) -> Vec<u8> {
+    let tag = options.rules.tag();
+    let mut key = vec![options.method as u8];
+    key.extend((tag.len() as u64).to_le_bytes());
+    key.extend(tag.as_bytes());
+    for word in [
+        n_games as u64,
+        generations as u64,
+        u64::from(options.depth),
+        options.seed,
+        options.max_moves.map_or(u64::MAX, |m| m as u64),
+        population as u64,
+    ] {
+        key.extend(word.to_le_bytes());
+    }
+    key.extend(options.sigma.to_le_bytes());
+    key
+}
+
+/// Save the state of `tuner` after its last generation, with `optimizer`
+/// from its optimizer's `to_floats`.
+///
+/// The file is written beside `path` and renamed over it, so a crash while
+/// saving leaves the previous checkpoint whole.
+fn write_checkpoint(
+    path: &Path,
+    run: &[u8],
+    tuner: &Tuner<'_>,
+    optimizer: &[f64],
+) -> io::Result<()> {
+    let mut bytes = CHECKPOINT_MAGIC.to_vec();
+    bytes.push(CHECKPOINT_VERSION);
+    bytes.extend((run.len() as u64).to_le_bytes());
+    bytes.extend(run);
+    bytes.extend((tuner.evaluations as u64).to_le_bytes());
+    bytes.extend((tuner.history.len() as u64).to_le_bytes());
+    let floats = std::iter::once(&tuner.best_score)
+        .chain(&tuner.best)
+        .chain(&tuner.history)
+        .chain(optimizer);
+    for float in floats {
+        bytes.extend(float.to_le_bytes());
+    }
+    bytes.extend(Sha256::digest(&bytes));
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, &bytes)?;
+    fs::rename(&temporary, path)
+}
+
+/// What a checkpoint holds
+struct Saved {
+    best: [f64; N],
+    best_score: f64,
+    history: Vec<f64>,
+    evaluations: usize,
+    /// The optimizer's `to_floats`
+    optimizer: Vec<f64>,
+}
+
+/// Read the checkpoint at `path`, which must have been saved by the run
+/// `run`
+fn read_checkpoint(path: &Path, run: &[u8]) -> Result<Saved, String> {
+    let bad = |why: &str| format!("invalid checkpoint {}: {why}", path.display());
+    let bytes =
+        fs::read(path).map_err(|e| format!("cannot read checkpoint {}: {e}", path.display()))?;
+    if bytes.get(..CHECKPOINT_MAGIC.len()) != Some(CHECKPOINT_MAGIC) {
+        return Err(bad("not a tuning checkpoint"));
+    }
+    let Some((body, digest)) = bytes.split_at_checked(bytes.len().saturating_sub(32)) else {
+        return Err(bad("truncated"));
+    };
+    if Sha256::digest(body)[..] != *digest {
+        return Err(bad("checksum mismatch"));
+    }
+    let mut rest = &body[CHECKPOINT_MAGIC.len()..];
+    let mut take = |n: usize| {
+        let (field, tail) = rest.split_at_checked(n).ok_or_else(|| bad("truncated"))?;
+        rest = tail;
+        Ok::<_, String>(field)
+    };
+    let version = take(1)?[0];
+    if version != CHECKPOINT_VERSION {
+        return Err(bad(&format!("unsupported version {version}")));
+    }
+    let word = |field: &[u8]| u64::from_le_bytes(field.try_into().expect("8 bytes")) as usize;
+    let run_len = word(take(8)?);
+    if take(run_len)? != run {
+        return Err(format!(
+            "checkpoint {} was saved by a run with other settings",
+            path.display()
+        ));
+    }
+    let evaluations = word(take(8)?);
+    let generations = word(take(8)?);
+    let floats: Vec<f64> = rest
+        .chunks_exact(8)
+        .map(|f| f64::from_le_bytes(f.try_into().expect("8 bytes")))
+        .collect();
+    let expected = if run[0] == Method::CmaEs as u8 {
+        CmaState::LEN
+    } else {
+        AnnealState::LEN
+    };
+    if rest.len() % 8 != 0 || floats.len() != 1 + N + generations + expected {
+        return Err(bad("wrong size"));
+    }
+    let (history, optimizer) = floats[1 + N..].split_at(generations);
+    Ok(Saved {
+        best_score: floats[0],
+        best: floats[1..=N].try_into().expect("N floats"),
+        history: history.to_vec(),
+        evaluations,
+        optimizer: optimizer.to_vec(),
+    })
+}
+
+/// Score of self-play game `game` with `weights`
+fn play(engine: &Engine, options: &TuneOptions, weights: &Weights, game: usize) -> i64 {
+    let rules = engine.rules();
+    let mut rng = StdRng::seed_from_u64(game_seed(options.seed, game));
+    let mut board = engine.init(&mut rng);
+    let (mut score, mut moves) = (0, 0);
+    while engine.state(&board) == State::Continue
+        && options.max_moves.is_none_or(|limit| moves < limit)
+    {
+        let values = expectimax(&board, &rules, options.depth, weights)
+            .expect("engine boards are always valid");
+        let Some(&(dir, _)) = values.iter().max_by(|a, b| a.1.total_cmp(&b.1)) else {
+            break;
+        };
+        let (next, delta, _) = engine
+            .step(board, dir, &mut rng)
+            .expect("engine boards are always valid");
+        score += i64::from(delta);
+        moves += 1;
+        board = next;
+    }
+    score
+}
+
+fn identity() -> [[f64; N]; N] {
+    std::array::from_fn(|i| std::array::from_fn(|j| if i == j { 1.0 } else { 0.0 }))
+}
+
+/// Eigenvectors (the columns of `B`) and the square roots of the
+/// eigenvalues of the symmetric matrix `c`, by Jacobi rotations
+fn eigen(c: &[[f64; N]; N]) -> ([[f64; N]; N], [f64; N]) {
+    let mut a = *c;
+    let mut b = identity();
+    for _ in 0..64 {
+        let off: f64 = (0..N)
+            .flat_map(|i| (0..N).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off < 1e-20 {
+            break;
+        }
+        for p in 0..N {
+            for q in p + 1..N {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let cos = 1.0 / (t * t + 1.0).sqrt();
+                let sin = t * cos;
+                for row in &mut a {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = cos * akp - sin * akq;
+                    row[q] = sin * akp + cos * akq;
+                }
+                let (row_p, row_q) = (a[p], a[q]);
+                a[p] = std::array::from_fn(|k| cos * row_p[k] - sin * row_q[k]);
+                a[q] = std::array::from_fn(|k| sin * row_p[k] + cos * row_q[k]);
+                for row in &mut b {
+                    let (bkp, bkq) = (row[p], row[q]);
+                    row[p] = cos * bkp - sin * bkq;
+                    row[q] = sin * bkp + cos * bkq;
+                }
+            }
+        }
+    }
+    let d = std::array::from_fn(|i| a[i][i].max(1e-20).sqrt());
+    (b, d)
+}
@@ -0,0 +1,399 @@
+//! Game variants: which tiles exist, how they merge and what spawns.
+//!
+//! [`MergeRule`] is the extension point; [`Variant`] names the built-in
+//! ones so [`Rules`] stays a small `Copy` value that tags and presets can
+//! describe. Blockers and bombs work the same in every variant.
+
+use crate::game::akioi_spawn_weights;
+use crate::rules::Overflow;
+use crate::{Board, Rules};
+
+/// The tile-level rules of a variant.
+///
+/// [`slide_column_with`](crate::slide_column_with) slides a column
+/// with any implementation, so a new variant can be tried out before it
+/// gets a [`Variant`].
+pub trait MergeRule {
+    /// Tile and score of `a` (nearer the wall) merging with `b`, the next
+    /// tile behind it, or `None` if they stay apart. `adjacent` is whether
+    /// they touched before the move and `below` the cells between `a` and
+    /// the wall.
+    fn merge(
+        &self,
+        a: i32,
+        b: i32,
+        adjacent: bool,
+        below: &[i32],
+        rules: &Rules,
+    ) -> Option<(i32, i32)>;
+
+    /// Whether the non-empty `tile` exists in the variant
+    fn is_tile(&self, tile: i32, rules: &Rules) -> bool;
+
+    /// Tiles that spawn on `board` with their weights, before the mercy
+    /// rule
+    fn spawn_weights(&self, board: &Board, rules: &Rules) -> SpawnTable;
+
+    /// The tile that wins the game
+    fn winning_tile(&self, rules: &Rules) -> i32 {
+        winning(rules.max_tile, 2 * rules.max_tile, rules)
+    }
+}
+
+/// Tiles that can spawn, each drawn with a chance of its integer weight
+/// out of their total; held inline, so drawing a spawn allocates nothing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpawnTable {
+    entries: [(i32, u64); 4],
+    len: usize,
+}
+
+impl SpawnTable {
+    /// A table of up to four tiles and their weights
+    #[must_use]
+    pub const fn new<const N: usize>(tiles: [(i32, u64); N]) -> SpawnTable {
+        assert!(N <= 4, "a spawn table holds at most four tiles");
+        let mut entries = [(0, 0); 4];
+        let mut i = 0;
+        while i < N {
+            entries[i] = tiles[i];
+            i += 1;
+        }
+        SpawnTable { entries, len: N }
+    }
+
+    /// The tiles with their weights, in order
+    #[must_use]
+    pub fn entries(&self) -> &[(i32, u64)] {
+        &self.entries[..self.len]
+    }
+
+    pub(crate) fn entries_mut(&mut self) -> &mut [(i32, u64)] {
+        &mut self.entries[..self.len]
+    }
+
+    /// Sum of the weights
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.entries().iter().map(|&(_, weight)| weight).sum()
+    }
+}
+
+/// Weights of the smaller and the larger spawn of the classic and
+/// Fibonacci variants: 90% and 10% in billionths
+const NINE_TO_ONE: [u64; 2] = [900_000_000, 100_000_000];
+
+/// The winning tile of a variant whose largest tile within the cap is
+/// `top`, two of which (or, for Fibonacci, the two largest) add up to `sum`
+fn winning(top: i32, sum: i32, rules: &Rules) -> i32 {
+    if rules.overflow == Overflow::Win {
+        sum
+    } else {
+        top
+    }
+}
+
+/// Tile and score of a merge past the cap, adding up to `sum`, in a variant
+/// whose smallest number tile is `lowest`
+fn overflow(sum: i32, lowest: i32, rules: &Rules) -> Option<(i32, i32)> {
+    match rules.overflow {
+        Overflow::Block => None,
+        Overflow::Win => Some((sum, sum)),
+        Overflow::Wrap => Some((lowest, sum)),
+    }
+}
+
+/// Akioi 2048: powers of two and the multipliers of [`Rules`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Akioi;
+
+impl MergeRule for Akioi {
+    fn merge(
+        &self,
+        a: i32,
+        b: i32,
+        adjacent: bool,
+        below: &[i32],
+        rules: &Rules,
+    ) -> Option<(i32, i32)> {
+        let top = rules.max_tile;
+        // the tile of a won overflow merges no more
+        if a > top || b > top {
+            return None;
+        }
+        // numeric + numeric
+        if a > 0 && b > 0 && a == b {
+            return if a < top {
+                Some((a + b, a + b))
+            } else {
+                overflow(a + b, 2, rules)
+            };
+        }
+        // multiplier + multiplier
+        if rules.multiplier_merge && a < 0 && b < 0 && a == b && a > -rules.max_multiplier {
+            return Some((a * 2, a * 2));
+        }
+        // numeric + multiplier
+        let supported = !rules.require_full_below || below.iter().all(|&v| v != 0);
+        if (a < 0) != (b < 0) && adjacent && supported {
+            let num = if a > 0 { a } else { b };
+            let mul = if a < 0 { a } else { b };
+            let mut v = num.saturating_mul(mul.saturating_abs());
+            v = v.min(top);
+            return Some((v, v));
+        }
+        None
+    }
+
+    fn is_tile(&self, tile: i32, rules: &Rules) -> bool {
+        is_number(tile, rules) || rules.is_multiplier(tile)
+    }
+
+    fn spawn_weights(&self, board: &Board, rules: &Rules) -> SpawnTable {
+        SpawnTable::new(akioi_spawn_weights(rules.difficulty, board))
+    }
+}
+
+/// Classic 2048: powers of two only, spawning 2 (90%) or 4 (10%).
+///
+/// With another [`Rules::base`] `b` the tiles are `m·bⁿ` for a digit
+/// `1 <= m < b` and `n >= 1`, up to the largest power of `b` within
+/// [`Rules::max_tile`], which wins. Two tiles of the same power merge when their digits
+/// add up to at most `b` (to `b`, they make the next power), and `b`
+/// (90%) or `2b` (10%) spawns. Base 2 is the usual game.
+///
+/// ```
+/// use akioi_2048_core::{Rules, slide_column_with, variant::Classic};
+///
+/// let threes = Rules { base: 3, ..Rules::CLASSIC };
+/// assert_eq!(slide_column_with([0, 0, 3, 3], &Classic, &threes), ([0, 0, 0, 6], 6));
+/// assert_eq!(slide_column_with([0, 0, 3, 6], &Classic, &threes), ([0, 0, 0, 9], 9));
+/// assert_eq!(slide_column_with([0, 0, 6, 6], &Classic, &threes), ([0, 0, 6, 6], 0));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Classic;
+
+impl MergeRule for Classic {
+    fn merge(
+        &self,
+        a: i32,
+        b: i32,
+        _adjacent: bool,
+        _below: &[i32],
+        rules: &Rules,
+    ) -> Option<(i32, i32)> {
+        let top = top_power(rules);
+        if a > top || b > top {
+            return None;
+        }
+        if rules.base == 2 {
+            return match a {
+                _ if a <= 0 || a != b => None,
+                _ if a < top => Some((a + b, a + b)),
+                _ => overflow(a + b, 2, rules),
+            };
+        }
+        let (m, p) = digit(a, rules.base)?;
+        let (n, q) = digit(b, rules.base)?;
+        let sum = a + b;
+        if p != q || m + n > rules.base {
+            return None;
+        }
+        // only two tiles of the top power add up past it
+        if sum <= top {
+            Some((sum, sum))
+        } else {
+            overflow(sum, rules.base, rules)
+        }
+    }
+
+    fn is_tile(&self, tile: i32, rules: &Rules) -> bool {
+        if rules.base == 2 {
+            return is_number(tile, rules);
+        }
+        let top = top_power(rules);
+        (digit(tile, rules.base).is_some() && tile <= top) || tile == winning(top, 2 * top, rules)
+    }
+
+    fn spawn_weights(&self, _board: &Board, rules: &Rules) -> SpawnTable {
+        let [small, large] = NINE_TO_ONE;
+        SpawnTable::new([(rules.base, small), (2 * rules.base, large)])
+    }
+
+    fn winning_tile(&self, rules: &Rules) -> i32 {
+        let top = top_power(rules);
+        winning(top, 2 * top, rules)
+    }
+}
+
+/// `tile` as a digit `m` in `1..base` times a power `bⁿ` with `n >= 1`
+fn digit(tile: i32, base: i32) -> Option<(i32, i32)> {
+    if tile < base {
+        return None;
+    }
+    let mut power = base;
+    while power <= tile / base && tile % (power * base) == 0 {
+        power *= base;
+    }
+    let m = tile / power;
+    (tile % power == 0 && m < base).then_some((m, power))
+}
+
+/// The largest power of the rules' base within [`Rules::max_tile`]
+fn top_power(rules: &Rules) -> i32 {
+    let mut power = rules.base;
+    while power <= rules.max_tile / rules.base {
+        power *= rules.base;
+    }
+    power
+}
+
+/// Fibonacci 2048: Fibonacci numbers from 1 to the largest within
+/// [`Rules::max_tile`], 46368 by default; two ones or two consecutive
+/// numbers merge into their sum, and 1 (90%) or 2 (10%) spawns.
+///
+/// ```
+/// use akioi_2048_core::{Rules, slide_column_with, variant::Fibonacci};
+///
+/// let rules = Rules::FIBONACCI;
+/// assert_eq!(slide_column_with([0, 0, 3, 5], &Fibonacci, &rules), ([0, 0, 0, 8], 8));
+/// assert_eq!(slide_column_with([0, 0, 1, 1], &Fibonacci, &rules), ([0, 0, 0, 2], 2));
+/// assert_eq!(slide_column_with([0, 0, 2, 2], &Fibonacci, &rules), ([0, 0, 2, 2], 0));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Fibonacci;
+
+/// The Fibonacci numbers up to the first past the largest cap
+const FIBONACCI: [i32; 43] = [
+    1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144, 233, 377, 610, 987, 1597, 2584, 4181, 6765, 10946,
+    17711, 28657, 46368, 75025, 121393, 196418, 317811, 514229, 832040, 1346269, 2178309, 3524578,
+    5702887, 9227465, 14930352, 24157817, 39088169, 63245986, 102334155, 165580141, 267914296,
+    433494437, 701408733,
+];
+
+/// Index in [`FIBONACCI`] of the largest tile within the cap
+fn top_fibonacci(rules: &Rules) -> usize {
+    FIBONACCI.partition_point(|&f| f <= rules.max_tile) - 1
+}
+
+impl MergeRule for Fibonacci {
+    fn merge(
+        &self,
+        a: i32,
+        b: i32,
+        _adjacent: bool,
+        _below: &[i32],
+        rules: &Rules,
+    ) -> Option<(i32, i32)> {
+        let top = top_fibonacci(rules);
+        let i = FIBONACCI.binary_search(&a).ok().filter(|&i| i <= top)?;
+        let j = FIBONACCI.binary_search(&b).ok().filter(|&j| j <= top)?;
+        if !(i.abs_diff(j) == 1 || (a == 1 && b == 1)) {
+            return None;
+        }
+        if i.max(j) < top {
+            Some((a + b, a + b))
+        } else {
+            overflow(a + b, 1, rules)
+        }
+    }
+
+    fn is_tile(&self, tile: i32, rules: &Rules) -> bool {
+        FIBONACCI
+            .binary_search(&tile)
+            .is_ok_and(|i| i <= top_fibonacci(rules))
+            || tile == self.winning_tile(rules)
+    }
+
+    fn spawn_weights(&self, _board: &Board, _rules: &Rules) -> SpawnTable {
+        let [small, large] = NINE_TO_ONE;
+        SpawnTable::new([(1, small), (2, large)])
+    }
+
+    fn winning_tile(&self, rules: &Rules) -> i32 {
+        let top = top_fibonacci(rules);
+        winning(FIBONACCI[top], FIBONACCI[top + 1], rules)
+    }
+}
+
+/// A power of two from 2 to the cap, or the tile of a won overflow
+fn is_number(tile: i32, rules: &Rules) -> bool {
+    let power = u32::try_from(tile).is_ok_and(u32::is_power_of_two);
+    power && tile >= 2 && (tile <= rules.max_tile || tile == Akioi.winning_tile(rules))
+}
+
+/// The built-in variants, selected by [`Rules::variant`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// [`Akioi`]
+    #[default]
+    Akioi,
+    /// [`Classic`]
+    Classic,
+    /// [`Fibonacci`]
+    Fibonacci,
+}
+
+impl Variant {
+    /// Every variant, in declaration order
+    pub const ALL: [Variant; 3] = [Variant::Akioi, Variant::Classic, Variant::Fibonacci];
+
+    /// Lowercase name, as in rules tags and presets
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Akioi => "akioi",
+            Variant::Classic => "classic",
+            Variant::Fibonacci => "fib",
+        }
+    }
+
+    /// Inverse of [`Variant::name`]
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Variant> {
+        Variant::ALL.into_iter().find(|v| v.name() == name)
+    }
+}
+
+/// Dispatches to the variant's rule without a virtual call, as the engine
+/// merges in its innermost loop.
+impl MergeRule for Variant {
+    fn merge(
+        &self,
+        a: i32,
+        b: i32,
+        adjacent: bool,
+        below: &[i32],
+        rules: &Rules,
+    ) -> Option<(i32, i32)> {
+        match self {
+            Variant::Akioi => Akioi.merge(a, b, adjacent, below, rules),
+            Variant::Classic => Classic.merge(a, b, adjacent, below, rules),
+            Variant::Fibonacci => Fibonacci.merge(a, b, adjacent, below, rules),
+        }
+    }
+
+    fn is_tile(&self, tile: i32, rules: &Rules) -> bool {
+        match self {
+            Variant::Akioi => Akioi.is_tile(tile, rules),
+            Variant::Classic => Classic.is_tile(tile, rules),
+            Variant::Fibonacci => Fibonacci.is_tile(tile, rules),
+        }
+    }
+
+    fn spawn_weights(&self, board: &Board, rules: &Rules) -> SpawnTable {
+        match self {
+            Variant::Akioi => Akioi.spawn_weights(board, rules),
+            Variant::Classic => Classic.spawn_weights(board, rules),
+            Variant::Fibonacci => Fibonacci.spawn_weights(board, rules),
+        }
+    }
+
+    fn winning_tile(&self, rules: &Rules) -> i32 {
+        match self {
+            Variant::Akioi => Akioi.winning_tile(rules),
+            Variant::Classic => Classic.winning_tile(rules),
+            Variant::Fibonacci => Fibonacci.winning_tile(rules),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Two-player versus mode: both players move at once, and big merges on one
+//! board drop "attack" tiles onto the other.
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::detail::{Cell, step_detailed_after};
+use crate::session::random_seed;
+use crate::{Board, Direction, Engine, Merge, Rng, Rules, validate_board_with};
+
+/// What a player's merges send to the opponent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attack {
+    /// Tile dropped on the opponent's board, e.g. `-1` (a ×1 multiplier
+    /// that only gets in the way) or `2` (junk)
+    pub tile: i32,
+    /// Every merge into a tile of at least this value sends one attack
+    pub min_merge: i32,
+    /// Most attack tiles one move can send
+    pub max_per_move: usize,
+}
+
+impl Default for Attack {
+    fn default() -> Self {
+        Attack {
+            tile: -1,
+            min_merge: 16,
+            max_per_move: 2,
+        }
+    }
+}
+
+impl Attack {
+    /// Attack tiles a move with these merges sends
+    fn sent_by(&self, merges: &[Merge]) -> usize {
+        merges
+            .iter()
+            .filter(|m| m.value >= self.min_merge)
+            .count()
+            .min(self.max_per_move)
+    }
+}
+
+/// One player's part of an [`Arena2P::step`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersusStep {
+    /// Board after the move, its spawn and the attack tiles received
+    pub board: Board,
+    pub delta: i32,
+    /// Whether the move changed the board; others count as a pass
+    pub moved: bool,
+    /// Attack tiles this move sent to the opponent
+    pub sent: usize,
+    /// Cells the opponent's attack tiles landed on; attacks beyond the
+    /// empty cells are lost
+    pub received: Vec<Cell>,
+}
+
+/// How a versus match stands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Standing {
+    Playing,
+    /// The player with this index (`0` or `1`) won
+    Won(usize),
+    /// Both players reached 65536, or both got stuck, on the same turn
+    Draw,
+}
+
+/// Two boards played against each other.
+///
+/// Each [`step`](Self::step) takes one move per player. Both moves are
+/// applied with their usual spawns, then the attack tiles each move earned
+/// land on random empty cells of the other board. A player with no move
+/// left loses, one reaching 65536 wins.
+///
+/// ```
+/// use akioi_2048_core::versus::{Arena2P, Attack, Standing};
+/// use akioi_2048_core::{Direction, Rules};
+///
+/// let mut arena = Arena2P::new(Some(3), Rules::AKIOI, Attack::default()).unwrap();
+/// let steps = arena.step([Direction::Left, Direction::Down]).unwrap();
+/// assert_eq!(steps[0].received.len(), steps[1].sent);
+/// assert_eq!(arena.standing(), Standing::Playing);
+/// ```
+pub struct Arena2P {
+    engine: Engine,
+    attack: Attack,
+    seed: u64,
+    boards: [Board; 2],
+    scores: [i64; 2],
+    /// Moves each player made that changed their board
+    moves: [u64; 2],
+    rngs: [StdRng; 2],
+    standing: Standing,
+}
+
+impl Arena2P {
+    /// Deal both boards; a random seed is picked when `seed` is `None`.
+    /// Both players get the same starting board.
+    ///
+    /// # Errors
+    /// Returns an error if the rules are inconsistent or the attack tile is
+    /// invalid under them.
+    pub fn new(seed: Option<u64>, rules: Rules, attack: Attack) -> Result<Self, String> {
+        let engine = Engine::new(rules)?;
+        if attack.tile == 0 {
+            return Err("attack tile must not be empty".to_string());
+        }
+        validate_board_with(&[[attack.tile, 0, 0, 0], [0; 4], [0; 4], [0; 4]], &rules)?;
+        let seed = seed.unwrap_or_else(random_seed);
+        let board = engine.init(&mut StdRng::seed_from_u64(seed));
+        Ok(Self {
+            engine,
+            attack,
+            seed,
+            boards: [board; 2],
+            scores: [0; 2],
+            moves: [0; 2],
+            rngs: [1, 2].map(|player| StdRng::seed_from_u64(seed ^ player)),
+            standing: Standing::Playing,
+        })
+    }
+
+    /// Apply one move per player.
+    ///
+    /// # Errors
+    /// Returns an error once the match is decided.
+    pub fn step(&mut self, moves: [Direction; 2]) -> Result<[VersusStep; 2], String> {
+        if self.standing != Standing::Playing {
+            return Err("the match is over".to_string());
+        }
+        let rules = self.engine.rules();
+        let mut steps = [0, 1].map(|player| {
+            let result = step_detailed_after(
+                self.boards[player],
+                moves[player],
+                &rules,
+                &mut self.rngs[player],
+                self.moves[player],
+            )
+            .expect("boards in play are always valid");
+            self.moves[player] += u64::from(result.moved);
+            VersusStep {
+                board: result.board,
+                delta: result.delta,
+                moved: result.moved,
+                sent: self.attack.sent_by(&result.merges),
+                received: Vec::new(),
+            }
+        });
+        for player in 0..2 {
+            let incoming = steps[1 - player].sent;
+            let step = &mut steps[player];
+            for _ in 0..incoming {
+                let empty: Vec<Cell> = (0..16)
+                    .map(|i| (i / 4, i % 4))
+                    .filter(|&(r, c)| step.board[r][c] == 0)
+                    .collect();
+                if empty.is_empty() {
+                    break;
+                }
+                let (r, c) = empty[self.rngs[player].index(empty.len())];
+                step.board[r][c] = self.attack.tile;
+                step.received.push((r, c));
+            }
+            self.boards[player] = step.board;
+            self.scores[player] += i64::from(step.delta);
+        }
+
+        let won = self.boards.map(|b| self.engine.is_won(&b));
+        let stuck = self.boards.map(|b| self.engine.is_over(&b));
+        self.standing = match (won, stuck) {
+            ([true, true], _) | ([false, false], [true, true]) => Standing::Draw,
+            ([true, false], _) | ([false, false], [false, true]) => Standing::Won(0),
+            ([false, true], _) | ([false, false], [true, false]) => Standing::Won(1),
+            ([false, false], [false, false]) => Standing::Playing,
+        };
+        Ok(steps)
+    }
+
+    #[must_use]
+    pub fn boards(&self) -> [Board; 2] {
+        self.boards
+    }
+
+    #[must_use]
+    pub fn scores(&self) -> [i64; 2] {
+        self.scores
+    }
+
+    #[must_use]
+    pub fn standing(&self) -> Standing {
+        self.standing
+    }
+
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[must_use]
+    pub fn rules(&self) -> Rules {
+        self.engine.rules()
+    }
+
+    #[must_use]
+    pub fn attack(&self) -> Attack {
+        self.attack
+    }
+}
@@ -0,0 +1,143 @@
+//! The web version's saved game: the `gameState` JSON it keeps in
+//! localStorage, in the layout of the original 2048 code.
+//!
+//! Cells are stored column by column (`cells[x][y]`, `x` the column), empty
+//! ones as `null`; tiles use this crate's values, multipliers negative.
+
+use crate::json::Json;
+use crate::{Board, Game, GameBuilder, State};
+
+/// A position as the web version saves it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WebSave {
+    pub board: Board,
+    pub score: i64,
+    /// The game was lost
+    pub over: bool,
+    /// A victory was reached
+    pub won: bool,
+    /// Play went on after the victory
+    pub keep_playing: bool,
+}
+
+impl WebSave {
+    /// Read a saved `gameState`.
+    ///
+    /// ```
+    /// use akioi_2048_core::web::WebSave;
+    ///
+    /// let save = WebSave {
+    ///     board: [[2, 0, 0, 0], [0; 4], [0; 4], [0, 0, 0, -2]],
+    ///     score: 12,
+    ///     over: false,
+    ///     won: false,
+    ///     keep_playing: false,
+    /// };
+    /// assert_eq!(WebSave::parse(&save.to_json()).unwrap(), save);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error for malformed JSON or a save of another layout.
+    pub fn parse(json: &str) -> Result<WebSave, String> {
+        WebSave::from_json(&Json::parse(json, "save")?)
+    }
+
+    /// Read a `gameState` already parsed, as when it is a field of another
+    /// document
+    pub(crate) fn from_json(root: &Json) -> Result<WebSave, String> {
+        let grid = field(root, "grid")?;
+        if field(grid, "size")?.number()? != 4.0 {
+            return Err("only 4x4 saves are supported".to_string());
+        }
+        let Json::Array(columns) = field(grid, "cells")? else {
+            return Err("`cells` is not an array".to_string());
+        };
+        if columns.len() != 4 {
+            return Err("`cells` must hold 4 columns".to_string());
+        }
+        let mut board = [[0; 4]; 4];
+        for (x, column) in columns.iter().enumerate() {
+            let Json::Array(cells) = column else {
+                return Err(format!("column {x} is not an array"));
+            };
+            if cells.len() != 4 {
+                return Err(format!("column {x} must hold 4 cells"));
+            }
+            for (y, cell) in cells.iter().enumerate() {
+                if *cell != Json::Null {
+                    board[y][x] = field(cell, "value")?.integer()?;
+                }
+            }
+        }
+        Ok(WebSave {
+            board,
+            score: field(root, "score")?.integer()?,
+            over: flag(root, "over")?,
+            won: flag(root, "won")?,
+            keep_playing: flag(root, "keepPlaying")?,
+        })
+    }
+
+    /// The position of `game`, as the web version would save it
+    #[must_use]
+    pub fn of(game: &Game) -> WebSave {
+        WebSave {
+            board: game.board(),
+            score: game.score(),
+            over: game.state() == State::GameOver,
+            won: game.won(),
+            keep_playing: game.keep_playing(),
+        }
+    }
+
+    /// A builder for a game resuming this position; seed and other options
+    /// can still be set on it
+    #[must_use]
+    pub fn builder(&self) -> GameBuilder {
+        Game::builder()
+            .start(self.board, self.score)
+            .keep_playing(self.keep_playing)
+    }
+
+    /// The `gameState` JSON the web version loads
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let columns: Vec<String> = (0..4)
+            .map(|x| {
+                let cells: Vec<String> = (0..4)
+                    .map(|y| match self.board[y][x] {
+                        0 => "null".to_string(),
+                        value => {
+                            format!(r#"{{"position":{{"x":{x},"y":{y}}},"value":{value}}}"#)
+                        }
+                    })
+                    .collect();
+                format!("[{}]", cells.join(","))
+            })
+            .collect();
+        format!(
+            r#"{{"grid":{{"size":4,"cells":[{}]}},"score":{},"over":{},"won":{},"keepPlaying":{}}}"#,
+            columns.join(","),
+            self.score,
+            self.over,
+            self.won,
+            self.keep_playing
+        )
+    }
+}
+
+/// The field `name` of `value`, which must be an object
+fn field<'a>(value: &'a Json, name: &str) -> Result<&'a Json, String> {
+    value
+        .field(name)
+        .map_err(|e| e.replace("missing", "save is missing"))
+}
+
+/// A boolean field; absent counts as `false`
+fn flag(value: &Json, name: &str) -> Result<bool, String> {
+    match value.field(name) {
+        Ok(Json::Bool(b)) => Ok(*b),
+        Ok(_) => Err(format!("`{name}` is not a boolean")),
+        Err(_) => Ok(false),
+    }
+}
@@ -0,0 +1,95 @@
+//! The HTTP front answering real requests over a socket
+#![cfg(feature = "http")]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::OnceLock;
+use std::thread;
+
+use akioi_2048_core::http::serve_http_on;
+
+/// Address of a server shared by the tests
+fn server() -> SocketAddr {
+    static ADDR: OnceLock<SocketAddr> = OnceLock::new();
+    *ADDR.get_or_init(|| {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("a local port is free");
+        let addr = listener.local_addr().expect("the listener is bound");
+        thread::spawn(move || serve_http_on(listener));
+        addr
+    })
+}
+
+/// Status code and body of the answer to `head`, then `body`
+fn request(head: &str, body: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(server()).expect("the server accepts");
+    write!(stream, "{head}\r\nConnection: close\r\n\r\n{body}").expect("the request is sent");
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .expect("the server answers");
+    let status = response[9..12].parse().expect("a status line");
+    let body = response.split_once("\r\n\r\n").map_or("", |(_, body)| body);
+    (status, body.to_string())
+}
+
+fn post(path: &str, body: &str) -> (u16, String) {
+    let head = format!(
+        "POST {path} HTTP/1.1\r\nHost: test\r\nContent-Length: {}",
+        body.len()
+    );
+    request(&head, body)
+}
+
+#[test]
+fn games_are_played_through_the_endpoints() {
+    let (status, body) = post("/init", r#"{"seed": 7}"#);
+    assert_eq!(status, 200, "{body}");
+    let game = body
+        .split_once(r#""game":"#)
+        .and_then(|(_, rest)| rest.split_once(','))
+        .map(|(id, _)| id.to_string())
+        .expect("a game id");
+    let (status, body) = post(
+        "/step",
+        &format!(r#"{{"game": {game}, "direction": "down"}}"#),
+    );
+    assert_eq!(status, 200, "{body}");
+    assert!(body.contains(r#""moved":"#), "{body}");
+    let (status, body) = post("/suggest", &format!(r#"{{"game": {game}, "depth": 1}}"#));
+    assert_eq!(status, 200, "{body}");
+    assert!(body.contains(r#""direction":"#), "{body}");
+}
+
+#[test]
+fn chunked_bodies_are_read() {
+    let body = r#"{"seed": 3}"#;
+    let chunked = format!("{:x}\r\n{body}\r\n0\r\n\r\n", body.len());
+    let head = "POST /init HTTP/1.1\r\nHost: test\r\nTransfer-Encoding: chunked";
+    let (status, body) = request(head, &chunked);
+    assert_eq!(status, 200, "{body}");
+    assert!(body.contains(r#""seed":3"#), "{body}");
+}
+
+#[test]
+fn bad_requests_are_refused() {
+    let (status, body) = post("/nope", "{}");
+    assert_eq!(
+        (status, body.as_str()),
+        (404, r#"{"error":"no endpoint /nope"}"#)
+    );
+    let (status, _) = request("GET /init HTTP/1.1\r\nHost: test", "");
+    assert_eq!(status, 405);
+    let (status, body) = post("/init", "[1]");
+    assert_eq!(
+        (status, body.as_str()),
+        (400, r#"{"error":"the body must be a JSON object"}"#)
+    );
+    let (status, body) = post(
+        "/suggest",
+        r#"{"board": [[0,0,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,2]], "depth": 7}"#,
+    );
+    assert_eq!(status, 400);
+    assert!(body.contains("`depth` is at most 6 without"), "{body}");
+    let (status, _) = post("/init", &" ".repeat((1 << 20) + 1));
+    assert_eq!(status, 413);
+}
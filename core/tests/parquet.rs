@@ -0,0 +1,76 @@
+//! Parquet tables read back by the `parquet` crate's own reader
+#![cfg(feature = "parquet")]
+
+use std::fs::File;
+
+use akioi_2048_core::arrow::{Column, Table};
+use akioi_2048_core::parquet::write;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+
+#[test]
+fn every_column_type_round_trips() {
+    let table = Table::new()
+        .column("agent", Column::Int32(vec![0, 1, -7]))
+        .column("score", Column::Int64(vec![0, 5394, i64::MIN]))
+        .column("seed", Column::UInt64(vec![0, 42, u64::MAX]))
+        .column("mean", Column::Float64(vec![0.5, -1.25, f64::MAX]))
+        .column("won", Column::Bool(vec![true, false, true]))
+        .column(
+            "direction",
+            Column::Utf8(vec!["Down".into(), String::new(), "×2".into()]),
+        );
+    let path = std::env::temp_dir().join(format!("akioi-2048-{}.parquet", std::process::id()));
+    write(File::create(&path).unwrap(), &table).unwrap();
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let names: Vec<&str> = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|c| c.name())
+        .collect();
+    assert_eq!(
+        names,
+        ["agent", "score", "seed", "mean", "won", "direction"]
+    );
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+    let rows: Vec<Vec<Field>> = reader
+        .get_row_iter(None)
+        .unwrap()
+        .map(|row| {
+            row.unwrap()
+                .get_column_iter()
+                .map(|(_, f)| f.clone())
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        rows[2],
+        [
+            Field::Int(-7),
+            Field::Long(i64::MIN),
+            Field::ULong(u64::MAX),
+            Field::Double(f64::MAX),
+            Field::Bool(true),
+            Field::Str("×2".into()),
+        ]
+    );
+    assert_eq!(rows[1][1], Field::Long(5394));
+    assert_eq!(rows[1][5], Field::Str(String::new()));
+}
+
+#[test]
+fn ragged_tables_are_refused() {
+    let table = Table::new()
+        .column("a", Column::Int32(vec![1]))
+        .column("b", Column::Bool(vec![]));
+    let error = write(Vec::new(), &table).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "column b has a different length than the others"
+    );
+}
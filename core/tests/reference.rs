@@ -0,0 +1,137 @@
+//! Property tests of the engine against the reference model: random boards
+//! and tile pairs under rulesets that cover multipliers, blockers, bombs
+//! and every variant must slide and merge as [`reference`] says.
+
+use akioi_2048_core::reference;
+use akioi_2048_core::session::InvalidMoves;
+use akioi_2048_core::spec::spec;
+use akioi_2048_core::variant::MergeRule;
+use akioi_2048_core::{
+    ALL_DIRECTIONS, BLOCKER, BOMB, Board, Engine, Overflow, Rules, Scoring, Variant,
+};
+use proptest::prelude::*;
+
+/// Rulesets the properties run under
+const RULES: [Rules; 9] = [
+    Rules::AKIOI,
+    Rules::MERCY,
+    Rules::CLASSIC,
+    Rules::FIBONACCI,
+    Rules {
+        blockers: true,
+        ..Rules::AKIOI
+    },
+    Rules {
+        bombs: true,
+        blockers: true,
+        ..Rules::AKIOI
+    },
+    Rules {
+        max_multiplier: 8,
+        multiplier_merge: false,
+        require_full_below: false,
+        scoring: Scoring::Clamp,
+        ..Rules::AKIOI
+    },
+    Rules {
+        max_multiplier: 2,
+        max_tile: 1024,
+        overflow: Overflow::Win,
+        bombs: true,
+        ..Rules::AKIOI
+    },
+    Rules {
+        variant: Variant::Classic,
+        max_tile: 64,
+        overflow: Overflow::Wrap,
+        blockers: true,
+        ..Rules::AKIOI
+    },
+];
+
+/// Every tile `rules` accept, with a blocker and a bomb where allowed and
+/// extra empty cells, so that boards have room to move
+fn palette(rules: &Rules) -> Vec<i32> {
+    let mut tiles = spec(rules, InvalidMoves::Noop)
+        .expect("tested rules are valid")
+        .tiles;
+    tiles.extend([0; 8]);
+    if rules.blockers {
+        tiles.extend([BLOCKER; 2]);
+    }
+    if rules.bombs {
+        tiles.extend([BOMB; 2]);
+    }
+    tiles
+}
+
+/// A ruleset with a board of its tiles
+fn rules_and_board() -> impl Strategy<Value = (Rules, Board)> {
+    (0..RULES.len()).prop_flat_map(|i| {
+        let rules = RULES[i];
+        let cells = prop::collection::vec(prop::sample::select(palette(&rules)), 16);
+        cells.prop_map(move |cells| {
+            let mut board = [[0; 4]; 4];
+            for (cell, tile) in board.iter_mut().flatten().zip(cells) {
+                *cell = tile;
+            }
+            (rules, board)
+        })
+    })
+}
+
+/// A ruleset with two tiles meeting in a slide and up to two below them.
+///
+/// Only the variant's own tiles merge: a slide splits its lines at blockers
+/// and clears them at bombs before any pair meets.
+fn rules_and_pair() -> impl Strategy<Value = (Rules, i32, i32, bool, Vec<i32>)> {
+    (0..RULES.len()).prop_flat_map(|i| {
+        let rules = RULES[i];
+        let tiles: Vec<i32> = palette(&rules)
+            .into_iter()
+            .filter(|&t| t != BLOCKER && t != BOMB)
+            .collect();
+        let tile = prop::sample::select(
+            tiles
+                .iter()
+                .copied()
+                .filter(|&t| t != 0)
+                .collect::<Vec<_>>(),
+        );
+        let below = prop::collection::vec(prop::sample::select(tiles), 0..=2);
+        (Just(rules), tile.clone(), tile, any::<bool>(), below)
+    })
+}
+
+proptest! {
+    #[test]
+    fn engine_slides_as_the_reference((rules, board) in rules_and_board()) {
+        let engine = Engine::new(rules).expect("tested rules are valid");
+        for direction in ALL_DIRECTIONS {
+            prop_assert_eq!(
+                engine.slide(&board, direction),
+                reference::slide(&board, direction, &rules),
+                "{} {:?} on {:?}",
+                rules.tag(),
+                direction,
+                board
+            );
+        }
+    }
+
+    #[test]
+    fn engine_merges_as_the_reference((rules, a, b, adjacent, below) in rules_and_pair()) {
+        let engine = rules.variant.merge(a, b, adjacent, &below, &rules);
+        let expected = reference::merge(a, b, adjacent, &below, &rules);
+        prop_assert_eq!(
+            engine.map(|(tile, _)| tile),
+            expected,
+            "{} {} + {} adjacent={} below={:?}",
+            rules.tag(),
+            a,
+            b,
+            adjacent,
+            below
+        );
+    }
+}
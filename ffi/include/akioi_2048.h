@@ -0,0 +1,70 @@
+/* C interface to the akioi 2048 engine (akioi-2048-ffi).
+ *
+ * Boards are 16 int32_t cells in row-major order. Positive values are
+ * number tiles, negative values multipliers (-1 = x1, -2 = x2, -4 = x4).
+ * Functions that can fail return a negative AKIOI_ERR_* code; otherwise
+ * they return the game state.
+ */
+#ifndef AKIOI_2048_H
+#define AKIOI_2048_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Directions (canonical action ids) */
+#define AKIOI_DOWN 0
+#define AKIOI_RIGHT 1
+#define AKIOI_UP 2
+#define AKIOI_LEFT 3
+
+/* States */
+#define AKIOI_CONTINUE 0
+#define AKIOI_VICTORY 1
+#define AKIOI_GAME_OVER 2
+
+/* Errors */
+#define AKIOI_ERR_NULL (-1)      /* a required pointer was null */
+#define AKIOI_ERR_DIRECTION (-2) /* direction not in 0..3 */
+#define AKIOI_ERR_BOARD (-3)     /* board holds invalid tiles */
+
+/* Opaque handle of a seeded game */
+typedef struct AkioiGame AkioiGame;
+
+/* Write a fresh board with two starting tiles to out[16]. */
+int akioi_init(int32_t *out);
+
+/* Apply one move under the standard rules, spawning a random tile if the
+ * board changed. board and out may be the same array; delta may be NULL. */
+int akioi_step(const int32_t *board, int direction, int32_t *out, int32_t *delta);
+
+/* Start a standard-rules game whose spawns derive from seed. */
+AkioiGame *akioi_game_new(uint64_t seed);
+
+/* Start a game under a rules preset ("akioi", "mercy") or rules tag
+ * ("akioi-v2+max_multiplier=8"); NULL rules means standard. Returns NULL
+ * for unknown rules. */
+AkioiGame *akioi_game_new_with_rules(uint64_t seed, const char *rules);
+
+/* Release a game; NULL is ignored. */
+void akioi_game_free(AkioiGame *game);
+
+/* Apply one move; invalid moves leave the game untouched. delta may be NULL. */
+int akioi_game_step(AkioiGame *game, int direction, int32_t *delta);
+
+/* Write the current board to out[16]; returns the state. */
+int akioi_game_board(const AkioiGame *game, int32_t *out);
+
+/* Total score (0 for NULL). */
+int64_t akioi_game_score(const AkioiGame *game);
+
+/* Moves played so far, including invalid ones (0 for NULL). */
+uint64_t akioi_game_moves(const AkioiGame *game);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AKIOI_2048_H */
@@ -0,0 +1,217 @@
+//! Stable C interface to the engine; declared in `include/akioi_2048.h`.
+//!
+//! Boards are 16 `int32_t` cells in row-major order. Directions use the
+//! canonical action ids (`Down=0, Right=1, Up=2, Left=3`). Functions that can
+//! fail return a negative `AKIOI_ERR_*` code; everything else is a state
+//! (`AKIOI_CONTINUE`, `AKIOI_VICTORY`, `AKIOI_GAME_OVER`).
+
+use std::ffi::{CStr, c_char, c_int};
+
+use akioi_2048_core::{ALL_DIRECTIONS, Board, Direction, Game, Rules, State};
+
+pub const AKIOI_CONTINUE: c_int = 0;
+pub const AKIOI_VICTORY: c_int = 1;
+pub const AKIOI_GAME_OVER: c_int = 2;
+
+/// A required pointer was null
+pub const AKIOI_ERR_NULL: c_int = -1;
+/// The direction is not in `0..=3`
+pub const AKIOI_ERR_DIRECTION: c_int = -2;
+/// The board holds tiles invalid under the rules
+pub const AKIOI_ERR_BOARD: c_int = -3;
+
+/// Opaque handle of a seeded game
+pub struct AkioiGame(Game);
+
+fn state_code(state: State) -> c_int {
+    match state {
+        State::Continue => AKIOI_CONTINUE,
+        State::Victory => AKIOI_VICTORY,
+        State::GameOver => AKIOI_GAME_OVER,
+    }
+}
+
+fn direction(id: c_int) -> Option<Direction> {
+    usize::try_from(id)
+        .ok()
+        .and_then(|i| ALL_DIRECTIONS.get(i).copied())
+}
+
+/// # Safety
+/// `cells` must point to 16 readable `int32_t`.
+unsafe fn read_board(cells: *const i32) -> Board {
+    // SAFETY: guaranteed by the caller
+    let cells = unsafe { std::slice::from_raw_parts(cells, 16) };
+    let mut board = [[0; 4]; 4];
+    for (i, &v) in cells.iter().enumerate() {
+        board[i / 4][i % 4] = v;
+    }
+    board
+}
+
+/// # Safety
+/// `out` must point to 16 writable `int32_t`.
+unsafe fn write_board(board: &Board, out: *mut i32) {
+    // SAFETY: guaranteed by the caller
+    let out = unsafe { std::slice::from_raw_parts_mut(out, 16) };
+    for (dst, &v) in out.iter_mut().zip(board.iter().flatten()) {
+        *dst = v;
+    }
+}
+
+/// Write a fresh board with two starting tiles to `out`.
+///
+/// # Safety
+/// `out` must be null or point to 16 writable `int32_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn akioi_init(out: *mut i32) -> c_int {
+    if out.is_null() {
+        return AKIOI_ERR_NULL;
+    }
+    // SAFETY: checked for null; the size is the caller's contract
+    unsafe { write_board(&akioi_2048_core::init(), out) };
+    AKIOI_CONTINUE
+}
+
+/// Apply one move under the standard rules, spawning a random tile if the
+/// board changed. Writes the new board to `out` and the score gained to
+/// `delta` (which may be null); returns the new state.
+///
+/// # Safety
+/// `board` and `out` must be null or point to 16 `int32_t` (they may be the
+/// same array); `delta` must be null or point to a writable `int32_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn akioi_step(
+    board: *const i32,
+    direction_id: c_int,
+    out: *mut i32,
+    delta: *mut i32,
+) -> c_int {
+    if board.is_null() || out.is_null() {
+        return AKIOI_ERR_NULL;
+    }
+    let Some(dir) = direction(direction_id) else {
+        return AKIOI_ERR_DIRECTION;
+    };
+    // SAFETY: checked for null; the size is the caller's contract
+    let board = unsafe { read_board(board) };
+    let Ok((next, gained, state)) = akioi_2048_core::step(board, dir) else {
+        return AKIOI_ERR_BOARD;
+    };
+    // SAFETY: checked for null; the sizes are the caller's contract
+    unsafe {
+        write_board(&next, out);
+        if !delta.is_null() {
+            *delta = gained;
+        }
+    }
+    state_code(state)
+}
+
+/// Start a standard-rules game whose spawns derive from `seed`.
+/// Free it with `akioi_game_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn akioi_game_new(seed: u64) -> *mut AkioiGame {
+    Box::into_raw(Box::new(AkioiGame(Game::new(seed))))
+}
+
+/// Start a game under the rules named by `rules` (a preset such as
+/// `"mercy"` or a rules tag); returns null for unknown rules.
+///
+/// # Safety
+/// `rules` must be null (standard rules) or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn akioi_game_new_with_rules(
+    seed: u64,
+    rules: *const c_char,
+) -> *mut AkioiGame {
+    let rules = if rules.is_null() {
+        Ok(Rules::AKIOI)
+    } else {
+        // SAFETY: the caller passes a NUL-terminated string
+        let name = unsafe { CStr::from_ptr(rules) }.to_string_lossy();
+        Rules::preset(&name).map_or_else(|| Rules::from_tag(&name), Ok)
+    };
+    match rules.and_then(|rules| Game::with_rules(seed, rules)) {
+        Ok(game) => Box::into_raw(Box::new(AkioiGame(game))),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Release a game; null is ignored.
+///
+/// # Safety
+/// `game` must be null or a handle from `akioi_game_new*` not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn akioi_game_free(game: *mut AkioiGame) {
+    if !game.is_null() {
+        // SAFETY: the handle came from Box::into_raw and is freed only once
+        drop(unsafe { Box::from_raw(game) });
+    }
+}
+
+/// Apply one move; invalid moves leave the game untouched. Writes the
+/// score gained to `delta` (which may be null) and returns the new state.
+///
+/// # Safety
+/// `game` must be null or a live handle; `delta` must be null or point to a
+/// writable `int32_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn akioi_game_step(
+    game: *mut AkioiGame,
+    direction_id: c_int,
+    delta: *mut i32,
+) -> c_int {
+    // SAFETY: live handle or null, per the contract
+    let Some(AkioiGame(game)) = (unsafe { game.as_mut() }) else {
+        return AKIOI_ERR_NULL;
+    };
+    let Some(dir) = direction(direction_id) else {
+        return AKIOI_ERR_DIRECTION;
+    };
+    let result = game.step(dir);
+    if !delta.is_null() {
+        // SAFETY: non-null and writable, per the contract
+        unsafe { *delta = result.delta };
+    }
+    state_code(result.state)
+}
+
+/// Write the current board of `game` to `out`.
+///
+/// # Safety
+/// `game` must be null or a live handle; `out` must be null or point to 16
+/// writable `int32_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn akioi_game_board(game: *const AkioiGame, out: *mut i32) -> c_int {
+    // SAFETY: live handle or null, per the contract
+    let Some(AkioiGame(game)) = (unsafe { game.as_ref() }) else {
+        return AKIOI_ERR_NULL;
+    };
+    if out.is_null() {
+        return AKIOI_ERR_NULL;
+    }
+    // SAFETY: checked for null; the size is the caller's contract
+    unsafe { write_board(&game.board(), out) };
+    state_code(game.state())
+}
+
+/// Total score of `game` (0 for a null handle).
+///
+/// # Safety
+/// `game` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn akioi_game_score(game: *const AkioiGame) -> i64 {
+    // SAFETY: live handle or null, per the contract
+    unsafe { game.as_ref() }.map_or(0, |AkioiGame(game)| game.score())
+}
+
+/// Moves played in `game` so far, including invalid ones (0 for null).
+///
+/// # Safety
+/// `game` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn akioi_game_moves(game: *const AkioiGame) -> u64 {
+    // SAFETY: live handle or null, per the contract
+    unsafe { game.as_ref() }.map_or(0, |AkioiGame(game)| game.moves() as u64)
+}
@@ -0,0 +1,74 @@
+/* Smoke test of the C interface: compiled against include/akioi_2048.h and
+ * linked with the static library, so a declared function the library does
+ * not export, or a constant with another value, fails the build.
+ *
+ *   cargo build -p akioi-2048-ffi
+ *   cc -std=c99 -Wall -Wextra -Werror -Iffi/include ffi/tests/smoke.c \
+ *       target/debug/libakioi_2048_ffi.a -lpthread -ldl -lm -o smoke
+ *   ./smoke
+ */
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "akioi_2048.h"
+
+#define CHECK(cond)                                                   \
+    do {                                                              \
+        if (!(cond)) {                                                \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #cond); \
+            exit(1);                                                  \
+        }                                                             \
+    } while (0)
+
+static int tiles(const int32_t *board) {
+    int n = 0;
+    for (int i = 0; i < 16; i++) {
+        n += board[i] != 0;
+    }
+    return n;
+}
+
+int main(void) {
+    int32_t board[16], delta = 0;
+
+    CHECK(akioi_init(board) == AKIOI_CONTINUE);
+    CHECK(tiles(board) == 2);
+    CHECK(akioi_init(NULL) == AKIOI_ERR_NULL);
+
+    int32_t line[16] = {2, 2};
+    CHECK(akioi_step(line, AKIOI_LEFT, line, &delta) == AKIOI_CONTINUE);
+    CHECK(line[0] == 4 && delta == 4);
+    CHECK(akioi_step(line, AKIOI_DOWN, line, NULL) == AKIOI_CONTINUE);
+    CHECK(akioi_step(line, 4, line, NULL) == AKIOI_ERR_DIRECTION);
+    int32_t invalid[16] = {3};
+    CHECK(akioi_step(invalid, AKIOI_UP, invalid, NULL) == AKIOI_ERR_BOARD);
+
+    AkioiGame *game = akioi_game_new(7);
+    CHECK(game != NULL);
+    CHECK(akioi_game_board(game, board) == AKIOI_CONTINUE);
+    CHECK(tiles(board) == 2);
+    int directions[] = {AKIOI_DOWN, AKIOI_RIGHT, AKIOI_UP, AKIOI_LEFT};
+    for (int i = 0; i < 8; i++) {
+        int state = akioi_game_step(game, directions[i % 4], &delta);
+        CHECK(state == AKIOI_CONTINUE || state == AKIOI_VICTORY || state == AKIOI_GAME_OVER);
+    }
+    CHECK(akioi_game_moves(game) == 8);
+    CHECK(akioi_game_score(game) >= 0);
+    CHECK(akioi_game_step(game, -1, NULL) == AKIOI_ERR_DIRECTION);
+    akioi_game_free(game);
+
+    AkioiGame *mercy = akioi_game_new_with_rules(7, "mercy");
+    CHECK(mercy != NULL);
+    CHECK(akioi_game_step(mercy, AKIOI_DOWN, NULL) >= 0);
+    akioi_game_free(mercy);
+    CHECK(akioi_game_new_with_rules(7, "nope") == NULL);
+
+    CHECK(akioi_game_step(NULL, AKIOI_DOWN, NULL) == AKIOI_ERR_NULL);
+    CHECK(akioi_game_board(NULL, board) == AKIOI_ERR_NULL);
+    CHECK(akioi_game_score(NULL) == 0);
+    CHECK(akioi_game_moves(NULL) == 0);
+    akioi_game_free(NULL);
+
+    puts("ok");
+    return 0;
+}
@@ -0,0 +1,305 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::errors::checked_board;
+use super::rules::{Rules, rules_or_default};
+use super::stats::GameStats;
+use super::{ndarray, parse_direction, step_result_to_py};
+use crate::Board;
+use crate::arena::{self, ArenaStep};
+use crate::session::random_seed;
+
+/// Threads to use when the caller does not say: one per core
+fn default_threads(threads: Option<usize>) -> usize {
+    threads.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, usize::from))
+}
+
+/// Many independent seeded games stepped together, like a vectorized RL env.
+///
+/// Observations are NumPy ``int32`` arrays of shape ``(n_envs, 4, 4)``.
+///
+/// Args:
+///     n_envs: Number of games.
+///     seed: Arena seed; every game draws its own seed from it, the env
+///           index and how many games the env has played, so equal seeds
+///           and actions give equal runs. Random when omitted.
+///     rules: Optional ``Rules`` for every game.
+///     auto_reset: Replace a game with a fresh one as soon as it ends.
+///     threads: Step large arenas on up to this many threads, with the GIL
+///              released (default and at most one per core). Runs do not
+///              depend on it.
+///     heatmaps: Also collect ``stats.heatmaps`` of where play happens.
+///     branching: Also collect ``stats.branching``, how much choice and
+///                chance every move had.
+///
+/// Every game that ends is added to ``stats``.
+#[pyclass(module = "akioi_2048")]
+pub struct Arena {
+    inner: arena::Arena,
+    /// Boards the last ``step`` ended on, before automatic resets
+    final_boards: Vec<Board>,
+}
+
+fn boards_bytes(boards: impl Iterator<Item = Board>) -> Vec<u8> {
+    boards
+        .flat_map(|b| b.into_iter().flatten())
+        .flat_map(i32::to_ne_bytes)
+        .collect()
+}
+
+impl Arena {
+    fn boards(&self) -> Vec<Board> {
+        self.inner.games().iter().map(crate::Game::board).collect()
+    }
+
+    fn observations_array<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let data = boards_bytes(self.boards().into_iter());
+        ndarray(py, &data, "int32", &[self.inner.len(), 4, 4])
+    }
+}
+
+#[pymethods]
+impl Arena {
+    #[new]
+    #[pyo3(signature = (
+        n_envs,
+        seed=None,
+        rules=None,
+        auto_reset=true,
+        threads=None,
+        heatmaps=false,
+        branching=false,
+    ))]
+    fn new(
+        n_envs: usize,
+        seed: Option<u64>,
+        rules: Option<&Rules>,
+        auto_reset: bool,
+        threads: Option<usize>,
+        heatmaps: bool,
+        branching: bool,
+    ) -> PyResult<Self> {
+        let mut inner = arena::Arena::new(n_envs, seed, rules_or_default(rules), auto_reset)
+            .map_err(PyValueError::new_err)?;
+        inner.set_threads(default_threads(threads));
+        inner.set_heatmaps(heatmaps);
+        inner.set_branching(branching);
+        let final_boards = inner.games().iter().map(crate::Game::board).collect();
+        Ok(Self {
+            inner,
+            final_boards,
+        })
+    }
+
+    /// Start fresh games at ``indices`` (every env when omitted).
+    ///
+    /// Returns:
+    ///     numpy.ndarray: Observations of all envs, shape ``(n_envs, 4, 4)``.
+    #[pyo3(signature = (indices=None))]
+    fn reset<'py>(
+        &mut self,
+        py: Python<'py>,
+        indices: Option<Vec<usize>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        match indices {
+            Some(indices) => self.inner.reset(&indices).map_err(PyValueError::new_err)?,
+            None => self.inner.reset_all(),
+        }
+        self.final_boards = self.boards();
+        self.observations_array(py)
+    }
+
+    /// Apply one action per env.
+    ///
+    /// Args:
+    ///     actions: One ``Direction`` or action id (Down=0, Right=1, Up=2,
+    ///              Left=3) per env, e.g. an integer NumPy array.
+    ///
+    /// Returns:
+    ///     tuple[numpy.ndarray, numpy.ndarray, numpy.ndarray]:
+    ///         ``(observations, rewards, dones)`` with ``int32`` rewards (score
+    ///         deltas) and ``bool`` dones. With ``auto_reset`` the observation
+    ///         of a finished env is already its new game; the board it ended
+    ///         on is in ``final_observations``.
+    fn step<'py>(
+        &mut self,
+        actions: &Bound<'py, PyAny>,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        let py = actions.py();
+        let actions = actions
+            .try_iter()?
+            .map(|a| parse_direction(&a?))
+            .collect::<PyResult<Vec<_>>>()?;
+        let inner = &mut self.inner;
+        let steps: Vec<ArenaStep> = py
+            .detach(|| inner.step(&actions))
+            .map_err(PyValueError::new_err)?;
+
+        self.final_boards = steps.iter().map(|s| s.board).collect();
+        let rewards: Vec<u8> = steps.iter().flat_map(|s| s.delta.to_ne_bytes()).collect();
+        let dones: Vec<u8> = steps.iter().map(|s| u8::from(s.done)).collect();
+        let n = steps.len();
+        Ok((
+            self.observations_array(py)?,
+            ndarray(py, &rewards, "int32", &[n])?,
+            ndarray(py, &dones, "bool", &[n])?,
+        ))
+    }
+
+    /// Current boards, shape ``(n_envs, 4, 4)``.
+    #[getter]
+    fn observations<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.observations_array(py)
+    }
+
+    /// Boards the last ``step`` ended on, before any automatic reset.
+    #[getter]
+    fn final_observations<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let data = boards_bytes(self.final_boards.iter().copied());
+        ndarray(py, &data, "int32", &[self.final_boards.len(), 4, 4])
+    }
+
+    /// Score of every current game, ``int64`` of shape ``(n_envs,)``.
+    #[getter]
+    fn scores<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let data: Vec<u8> = self
+            .inner
+            .games()
+            .iter()
+            .flat_map(|g| g.score().to_ne_bytes())
+            .collect();
+        ndarray(py, &data, "int64", &[self.inner.len()])
+    }
+
+    /// ``GameStats`` of every game that ended in this arena.
+    #[getter]
+    fn stats(&self) -> GameStats {
+        GameStats {
+            inner: self.inner.stats().clone(),
+        }
+    }
+
+    #[getter]
+    fn rules(&self) -> Rules {
+        Rules {
+            inner: self.inner.rules(),
+        }
+    }
+
+    /// Threads ``step`` may use.
+    #[getter]
+    fn threads(&self) -> usize {
+        self.inner.threads()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+/// Apply one move to each of many boards at once, natively.
+///
+/// Boards are stepped on up to ``threads`` threads with the GIL released.
+/// The spawn after board ``i`` comes from a counter-based RNG stream keyed
+/// by ``seed``, ``i`` and ``step``, so equal arguments give equal results
+/// whatever the thread count.
+///
+/// Args:
+///     boards: 4x4 boards.
+///     actions: One ``Direction``, action id or name per board.
+///     seed: Master seed of the spawns; random when omitted.
+///     rules: Optional ``Rules``.
+///     threads: Most threads to use (default and at most one per core).
+///              Batches below a few hundred boards stay on one thread.
+///     step: Step number of the simulation; pass a new one on every call
+///           with the same seed to draw fresh spawns.
+///
+/// Returns:
+///     list[StepResult]: ``(board, delta, state)`` for every board, in
+///         order.
+///
+/// Raises:
+///     InvalidBoardError: For an invalid board.
+///     InvalidDirectionError: For an invalid action.
+///     ValueError: Unless there is exactly one action per board.
+#[pyfunction]
+#[pyo3(signature = (boards, actions, seed=None, rules=None, threads=None, step=0))]
+pub fn step_batch(
+    py: Python<'_>,
+    boards: Vec<Bound<'_, PyAny>>,
+    actions: &Bound<'_, PyAny>,
+    seed: Option<u64>,
+    rules: Option<&Rules>,
+    threads: Option<usize>,
+    step: u64,
+) -> PyResult<Vec<Py<PyAny>>> {
+    let rules = rules_or_default(rules);
+    let boards = boards
+        .iter()
+        .map(|b| checked_board(b, &rules))
+        .collect::<PyResult<Vec<_>>>()?;
+    let actions = actions
+        .try_iter()?
+        .map(|a| parse_direction(&a?))
+        .collect::<PyResult<Vec<_>>>()?;
+    let seed = seed.unwrap_or_else(random_seed);
+    let threads = default_threads(threads);
+    let results = py
+        .detach(|| arena::step_batch(&boards, &actions, &rules, seed, step, threads))
+        .map_err(PyValueError::new_err)?;
+    results
+        .iter()
+        .map(|(board, delta, state)| step_result_to_py(py, board, *delta, *state))
+        .collect()
+}
+
+/// The heuristic features of many boards at once, natively, for fitting
+/// value functions on extracted features.
+///
+/// Boards are read on up to ``threads`` threads with the GIL released.
+///
+/// Args:
+///     boards: 4x4 boards, as nested lists or a NumPy array of shape
+///             ``(n, 4, 4)``.
+///     rules: Optional ``Rules`` the boards must be valid under.
+///     threads: Most threads to use (default and at most one per core).
+///              Batches below a few hundred boards stay on one thread.
+///
+/// Returns:
+///     numpy.ndarray: ``float64`` array of shape ``(n, 6)``, one row per
+///         board with the columns ``empty_cells``, ``monotonicity``,
+///         ``smoothness``, ``corner``, ``merges`` and ``multipliers``, as in
+///         the ``"factors"`` of ``hint``.
+///
+/// Raises:
+///     InvalidBoardError: For an invalid board.
+#[pyfunction]
+#[pyo3(signature = (boards, rules=None, threads=None))]
+pub fn evaluate_batch<'py>(
+    boards: &Bound<'py, PyAny>,
+    rules: Option<&Rules>,
+    threads: Option<usize>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = boards.py();
+    let rules = rules_or_default(rules);
+    let boards = if boards.hasattr("tolist")? {
+        boards.call_method0("tolist")?
+    } else {
+        boards.clone()
+    };
+    let boards = boards
+        .try_iter()?
+        .map(|b| checked_board(&b?, &rules))
+        .collect::<PyResult<Vec<Board>>>()?;
+    let threads = default_threads(threads);
+    let features = py
+        .detach(|| arena::features_batch(&boards, &rules, threads))
+        .map_err(PyValueError::new_err)?;
+    let data: Vec<u8> = features
+        .iter()
+        .flat_map(|f| f.named().map(|(_, value)| value))
+        .flat_map(f64::to_ne_bytes)
+        .collect();
+    ndarray(py, &data, "float64", &[features.len(), 6])
+}
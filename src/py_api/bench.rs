@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Measure native engine throughput (what ``python -m akioi_2048.bench`` prints).
+///
+/// Cases: ``step``, ``step_detailed``, ``step_with_animation``, ``game``
+/// (``Game.step``) and ``agent:random``, each playing random moves without
+/// crossing into Python. The GIL is released while measuring.
+///
+/// Args:
+///     seconds: Minimum time spent on each case.
+///
+/// Returns:
+///     list[dict]: One ``{"name", "steps", "seconds", "steps_per_second"}``
+///         per case.
+#[pyfunction]
+#[pyo3(signature = (seconds=0.5))]
+pub fn benchmark(py: Python<'_>, seconds: f64) -> PyResult<Vec<Bound<'_, PyDict>>> {
+    let min_time = Duration::try_from_secs_f64(seconds)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("seconds: {e}")))?;
+    let results = py.detach(|| crate::bench::run(min_time));
+    results
+        .iter()
+        .map(|r| {
+            let d = PyDict::new(py);
+            d.set_item("name", r.name)?;
+            d.set_item("steps", r.steps)?;
+            d.set_item("seconds", r.seconds)?;
+            d.set_item("steps_per_second", r.steps_per_second)?;
+            Ok(d)
+        })
+        .collect()
+}
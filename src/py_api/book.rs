@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::compat::warn_differences;
+use super::direction_to_py;
+use super::progress::{CancelToken, Watch};
+use super::replay::invalid_data;
+use super::rules::{Rules, rules_or_default};
+use crate::Board;
+use crate::book;
+
+/// Precomputed best moves of the early game, consulted by ``hint`` before
+/// it searches.
+///
+/// A book holds the expectimax move of every position reachable from the
+/// start while the tiles sum to at most ``max_sum`` (multipliers counting
+/// by their factor), playing the book's own moves against every spawn.
+/// Build one with ``OpeningBook.build`` and keep it with ``save`` /
+/// ``load``; each position takes 25 bytes.
+#[pyclass(module = "akioi_2048", frozen)]
+pub struct OpeningBook {
+    pub(crate) inner: book::OpeningBook,
+}
+
+#[pymethods]
+impl OpeningBook {
+    /// Search the best move of every opening position.
+    ///
+    /// Args:
+    ///     max_sum: Cover positions whose tiles sum to at most this.
+    ///     depth: Moves each position is searched ahead, as ``hint(depth=)``.
+    ///     rules: Optional ``Rules``.
+    ///     max_positions: Give up beyond this many positions.
+    ///     progress: Called as ``progress(done, total)`` with the positions
+    ///               searched; the total grows as play reaches new ones.
+    ///     cancel: A ``CancelToken`` that stops the build.
+    ///
+    /// Ctrl-C stops the build too, raising ``KeyboardInterrupt``.
+    ///
+    /// Raises:
+    ///     ValueError: For too many positions.
+    ///     CancelledError: If ``cancel`` was cancelled.
+    #[staticmethod]
+    #[pyo3(signature = (
+        max_sum=16,
+        depth=2,
+        rules=None,
+        max_positions=1_000_000,
+        progress=None,
+        cancel=None,
+    ))]
+    fn build(
+        py: Python<'_>,
+        max_sum: i32,
+        depth: u32,
+        rules: Option<&Rules>,
+        max_positions: usize,
+        progress: Option<&Bound<'_, PyAny>>,
+        cancel: Option<&CancelToken>,
+    ) -> PyResult<Self> {
+        let rules = rules_or_default(rules);
+        let inner = Watch::new(progress, cancel)?
+            .run(py, |progress| {
+                book::OpeningBook::build(rules, max_sum, depth, max_positions, progress)
+            })?
+            .map_err(PyValueError::new_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Load a book written by ``save``.
+    ///
+    /// ``force`` loads a book built by an engine that plays its rules
+    /// differently (see ``engine_stamp``), with a ``RuntimeWarning`` per
+    /// difference.
+    ///
+    /// Raises:
+    ///     OSError: If the file cannot be read.
+    ///     ValueError: If it is not an opening book, fails its checksum, or
+    ///         its stamp differs and ``force`` is not set.
+    #[staticmethod]
+    #[pyo3(signature = (path, force=false))]
+    fn load(py: Python<'_>, path: PathBuf, force: bool) -> PyResult<Self> {
+        let (inner, differences) = py
+            .detach(|| book::OpeningBook::load_with(&path, force))
+            .map_err(invalid_data)?;
+        warn_differences(py, &differences)?;
+        Ok(Self { inner })
+    }
+
+    /// Write the book to ``path``, with a format version and checksum.
+    fn save(&self, py: Python<'_>, path: PathBuf) -> PyResult<()> {
+        py.detach(|| self.inner.save(&path))?;
+        Ok(())
+    }
+
+    /// The book's move for ``board`` and its expected evaluation, or
+    /// ``None`` for a position the book does not hold.
+    fn lookup(&self, py: Python<'_>, board: Board) -> PyResult<Option<(Py<PyAny>, f64)>> {
+        self.inner
+            .lookup(&board)
+            .map(|m| Ok((direction_to_py(py, m.direction)?, m.value)))
+            .transpose()
+    }
+
+    #[getter]
+    fn max_sum(&self) -> i32 {
+        self.inner.max_sum()
+    }
+
+    #[getter]
+    fn depth(&self) -> u32 {
+        self.inner.depth()
+    }
+
+    #[getter]
+    fn rules(&self) -> Rules {
+        Rules {
+            inner: self.inner.rules(),
+        }
+    }
+
+    /// Number of positions held.
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __contains__(&self, board: Board) -> bool {
+        self.inner.lookup(&board).is_some()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "OpeningBook(max_sum={}, depth={}, positions={}, rules={:?})",
+            self.inner.max_sum(),
+            self.inner.depth(),
+            self.inner.len(),
+            self.inner.rules().tag()
+        )
+    }
+}
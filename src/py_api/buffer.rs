@@ -0,0 +1,240 @@
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::{board_to_py, ndarray, parse_direction};
+use crate::Board;
+use crate::buffer::{self, Batch, Priority};
+use crate::dataset::action_index;
+use crate::trajectory::Transition;
+
+/// Fixed-capacity replay buffer of packed transitions, for off-policy RL.
+///
+/// Boards are packed to 16 bytes, so a transition takes 42 bytes instead
+/// of the kilobytes of nested lists. Once full, every ``push`` evicts the
+/// oldest transition.
+///
+/// Args:
+///     capacity: Most transitions kept.
+///     prioritized: Sample in proportion to ``priority ** alpha`` instead
+///                  of uniformly (prioritized experience replay).
+///     alpha: How strongly priorities skew sampling; ``0`` is uniform.
+///     seed: Sampling seed; random when omitted.
+///
+/// Raises:
+///     ValueError: For a zero capacity or a negative ``alpha``.
+#[pyclass(module = "akioi_2048")]
+pub struct ReplayBuffer {
+    inner: buffer::ReplayBuffer,
+}
+
+#[pymethods]
+impl ReplayBuffer {
+    #[new]
+    #[pyo3(signature = (capacity, prioritized=false, alpha=0.6, seed=None))]
+    fn new(capacity: usize, prioritized: bool, alpha: f64, seed: Option<u64>) -> PyResult<Self> {
+        let priority = prioritized.then_some(Priority { alpha });
+        let inner =
+            buffer::ReplayBuffer::new(capacity, priority, seed).map_err(PyValueError::new_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Store one transition.
+    ///
+    /// Args:
+    ///     state: Board before the move.
+    ///     action: ``Direction`` or action id (Down=0, Right=1, Up=2, Left=3).
+    ///     reward: Reward of the move.
+    ///     next_state: Board after the move and its spawn.
+    ///     done: Whether the episode ended.
+    ///     priority: Priority in a prioritized buffer; the largest so far
+    ///               when omitted, so new transitions are seen soon.
+    ///     goal: Goal tile of a goal-conditioned transition.
+    ///
+    /// Raises:
+    ///     ValueError: For a board that does not pack (a tile that is not a
+    ///         power of two) or a negative priority.
+    #[pyo3(signature = (state, action, reward, next_state, done, priority=None, goal=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        state: Board,
+        action: &Bound<'_, PyAny>,
+        reward: f32,
+        next_state: Board,
+        done: bool,
+        priority: Option<f64>,
+        goal: Option<i32>,
+    ) -> PyResult<()> {
+        let transition = Transition {
+            state,
+            action: parse_direction(action)?,
+            reward,
+            next_state,
+            done,
+            goal,
+        };
+        self.inner
+            .push(&transition, priority)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Draw ``batch_size`` transitions with replacement.
+    ///
+    /// Args:
+    ///     batch_size: Transitions to draw.
+    ///     beta: Importance-sampling exponent of prioritized buffers; the
+    ///           weights are ``(len * P) ** -beta``, normalized to a maximum
+    ///           of 1.
+    ///     return_numpy: Return NumPy arrays (``states``/``next_states``
+    ///                   ``int32 (n, 4, 4)``, ``actions uint8``, ``rewards``
+    ///                   and ``weights float32``, ``dones bool``, ``goals``
+    ///                   ``int32`` with 0 for none, ``indices int64``)
+    ///                   instead of lists.
+    ///
+    /// Returns:
+    ///     dict: ``states``, ``actions``, ``rewards``, ``next_states``,
+    ///         ``dones``, ``goals``, ``indices`` (for ``update_priorities``)
+    ///         and ``weights`` (all 1 without prioritization).
+    ///
+    /// Raises:
+    ///     ValueError: If the buffer is empty.
+    #[pyo3(signature = (batch_size, beta=0.4, return_numpy=true))]
+    fn sample<'py>(
+        &mut self,
+        py: Python<'py>,
+        batch_size: usize,
+        beta: f64,
+        return_numpy: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let inner = &mut self.inner;
+        let batch = py
+            .detach(|| inner.sample(batch_size, beta))
+            .map_err(PyValueError::new_err)?;
+        if return_numpy {
+            batch_to_numpy(py, &batch)
+        } else {
+            batch_to_lists(py, &batch)
+        }
+    }
+
+    /// Set the priorities of sampled transitions, e.g. to new TD errors.
+    ///
+    /// Raises:
+    ///     ValueError: If the buffer is not prioritized, the lengths differ,
+    ///         an index is out of range or a priority is negative.
+    fn update_priorities(&mut self, indices: Vec<usize>, priorities: Vec<f64>) -> PyResult<()> {
+        self.inner
+            .update_priorities(&indices, &priorities)
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Write the buffer to ``path`` in its binary format.
+    fn save(&self, py: Python<'_>, path: PathBuf) -> PyResult<()> {
+        py.detach(|| self.inner.save(&path))?;
+        Ok(())
+    }
+
+    /// Load a buffer written by ``save``; sampling is reseeded with
+    /// ``seed``.
+    ///
+    /// Raises:
+    ///     OSError: If the file cannot be read.
+    ///     ValueError: If it is not a replay buffer.
+    #[staticmethod]
+    #[pyo3(signature = (path, seed=None))]
+    fn load(py: Python<'_>, path: PathBuf, seed: Option<u64>) -> PyResult<Self> {
+        py.detach(|| buffer::ReplayBuffer::load(&path, seed))
+            .map(|inner| Self { inner })
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::InvalidData | std::io::ErrorKind::UnexpectedEof => {
+                    PyValueError::new_err(e.to_string())
+                }
+                _ => e.into(),
+            })
+    }
+
+    #[getter]
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    #[getter]
+    fn prioritized(&self) -> bool {
+        self.inner.priority().is_some()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+fn batch_to_numpy<'py>(py: Python<'py>, batch: &Batch) -> PyResult<Bound<'py, PyDict>> {
+    let t = &batch.transitions;
+    let n = t.len();
+    let boards = |get: fn(&Transition) -> Board| -> Vec<u8> {
+        t.iter()
+            .flat_map(|t| get(t).into_iter().flatten())
+            .flat_map(i32::to_ne_bytes)
+            .collect()
+    };
+    let states = boards(|t| t.state);
+    let next_states = boards(|t| t.next_state);
+    let actions: Vec<u8> = t.iter().map(|t| action_index(t.action)).collect();
+    let rewards: Vec<u8> = t.iter().flat_map(|t| t.reward.to_ne_bytes()).collect();
+    let dones: Vec<u8> = t.iter().map(|t| u8::from(t.done)).collect();
+    let goals: Vec<u8> = t
+        .iter()
+        .flat_map(|t| t.goal.unwrap_or(0).to_ne_bytes())
+        .collect();
+    let indices: Vec<u8> = batch
+        .indices
+        .iter()
+        .flat_map(|&i| (i as i64).to_ne_bytes())
+        .collect();
+    let weights: Vec<u8> = batch
+        .weights
+        .iter()
+        .flat_map(|&w| (w as f32).to_ne_bytes())
+        .collect();
+
+    let d = PyDict::new(py);
+    d.set_item("states", ndarray(py, &states, "int32", &[n, 4, 4])?)?;
+    d.set_item("actions", ndarray(py, &actions, "uint8", &[n])?)?;
+    d.set_item("rewards", ndarray(py, &rewards, "float32", &[n])?)?;
+    d.set_item(
+        "next_states",
+        ndarray(py, &next_states, "int32", &[n, 4, 4])?,
+    )?;
+    d.set_item("dones", ndarray(py, &dones, "bool", &[n])?)?;
+    d.set_item("goals", ndarray(py, &goals, "int32", &[n])?)?;
+    d.set_item("indices", ndarray(py, &indices, "int64", &[n])?)?;
+    d.set_item("weights", ndarray(py, &weights, "float32", &[n])?)?;
+    Ok(d)
+}
+
+fn batch_to_lists<'py>(py: Python<'py>, batch: &Batch) -> PyResult<Bound<'py, PyDict>> {
+    let t = &batch.transitions;
+    let d = PyDict::new(py);
+    let states: Vec<_> = t.iter().map(|t| board_to_py(&t.state)).collect();
+    let next_states: Vec<_> = t.iter().map(|t| board_to_py(&t.next_state)).collect();
+    // a Vec<u8> would convert to bytes
+    let actions: Vec<usize> = t
+        .iter()
+        .map(|t| usize::from(action_index(t.action)))
+        .collect();
+    d.set_item("states", states)?;
+    d.set_item("actions", actions)?;
+    d.set_item("rewards", t.iter().map(|t| t.reward).collect::<Vec<_>>())?;
+    d.set_item("next_states", next_states)?;
+    d.set_item("dones", t.iter().map(|t| t.done).collect::<Vec<_>>())?;
+    d.set_item(
+        "goals",
+        t.iter().map(|t| t.goal.unwrap_or(0)).collect::<Vec<_>>(),
+    )?;
+    d.set_item("indices", batch.indices.clone())?;
+    d.set_item("weights", batch.weights.clone())?;
+    Ok(d)
+}
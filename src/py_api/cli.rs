@@ -0,0 +1,17 @@
+use pyo3::prelude::*;
+
+/// Run the interactive terminal client (what ``python -m akioi_2048`` does).
+///
+/// Args:
+///     args: Command-line arguments without the program name, e.g.
+///           ``["--seed", "7", "--rules", "mercy"]``; ``["--help"]`` lists them.
+///
+/// Raises:
+///     ValueError: For bad arguments or an unreadable save file.
+#[pyfunction]
+#[pyo3(signature = (args=None))]
+pub fn run_cli(py: Python<'_>, args: Option<Vec<String>>) -> PyResult<()> {
+    let args = args.unwrap_or_default();
+    py.detach(|| crate::cli::run(&args))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
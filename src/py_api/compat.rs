@@ -0,0 +1,68 @@
+use std::ffi::CString;
+
+use pyo3::exceptions::{PyRuntimeWarning, PyValueError};
+use pyo3::prelude::*;
+
+use super::rules::{Rules, rules_or_default};
+use crate::compat::Stamp;
+
+/// Compatibility stamp of artifacts this engine makes under ``rules``:
+/// ``"engine=<version> rules=<hash> spawns=<hash>"``.
+///
+/// Replays, binary game logs, opening books and datasets carry one, so a
+/// file made by an engine that merges or spawns differently is noticed
+/// when it is loaded.
+///
+/// Args:
+///     rules: Optional ``Rules``; defaults to the akioi rules.
+///
+/// Returns:
+///     str: The stamp.
+#[pyfunction]
+#[pyo3(signature = (rules=None))]
+pub fn engine_stamp(rules: Option<&Rules>) -> String {
+    Stamp::of(&rules_or_default(rules)).to_text()
+}
+
+/// Compare a stamp, as ``engine_stamp`` writes it, with this engine.
+///
+/// Args:
+///     stamp: Stamp of an artifact, e.g. the ``stamp`` array of a dataset
+///            decoded as text.
+///     rules: Optional ``Rules`` the artifact was made under; defaults to
+///            the akioi rules.
+///     force: Accept a stamp this engine no longer matches, with a
+///            ``RuntimeWarning`` instead of an error.
+///
+/// Returns:
+///     list[str]: How this engine plays the rules differently; empty when
+///     it plays them the same.
+///
+/// Raises:
+///     ValueError: If the stamp is malformed, or differs and ``force`` is
+///         not set.
+#[pyfunction]
+#[pyo3(signature = (stamp, rules=None, force=false))]
+pub fn check_stamp(
+    py: Python<'_>,
+    stamp: &str,
+    rules: Option<&Rules>,
+    force: bool,
+) -> PyResult<Vec<String>> {
+    let differences = Stamp::parse(stamp)
+        .and_then(|stamp| stamp.check("stamp", &rules_or_default(rules), force))
+        .map_err(PyValueError::new_err)?;
+    warn_differences(py, &differences)?;
+    Ok(differences)
+}
+
+/// Warn once per difference an artifact loaded with ``force=True`` has
+pub(crate) fn warn_differences(py: Python<'_>, differences: &[String]) -> PyResult<()> {
+    let category = py.get_type::<PyRuntimeWarning>();
+    for difference in differences {
+        let message = CString::new(format!("loaded anyway: {difference}"))
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        PyErr::warn(py, &category, &message, 1)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use super::board_to_py;
+use super::rules::{Rules, rules_or_default};
+use crate::Engine;
+use crate::curriculum::{self, BoardSpec};
+use crate::session::random_seed;
+
+/// A random, plausible mid-game board, for curriculum learning and tests.
+///
+/// By default tiles are scattered directly: ``max_tile`` in a corner and
+/// smaller powers of two (and, one time in eight, a multiplier) in other
+/// random cells, drawn again until some move is legal. With
+/// ``reachable=True`` the board is instead the first position of a
+/// mostly greedy heuristic game that fits, so actual play can reach it;
+/// it may fail for tiles above 2048.
+///
+/// Args:
+///     max_tile: Largest tile, a power of two from 2 to 65536.
+///     fill: Share of the 16 cells holding a tile (at least one), in
+///           ``(0, 1]``; with ``reachable`` a minimum.
+///     include_multipliers: Whether multipliers may appear.
+///     seed: Seed for the draw; random when omitted.
+///     reachable: Generate the board by playing instead.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     list[list[int]]: The board.
+///
+/// Raises:
+///     ValueError: For a ``max_tile`` or ``fill`` out of range, or when no
+///         board fits.
+#[pyfunction]
+#[pyo3(signature = (max_tile=1024, fill=0.5, include_multipliers=true, seed=None, reachable=false, rules=None))]
+pub fn random_board(
+    py: Python<'_>,
+    max_tile: i32,
+    fill: f64,
+    include_multipliers: bool,
+    seed: Option<u64>,
+    reachable: bool,
+    rules: Option<&Rules>,
+) -> PyResult<Vec<Vec<i32>>> {
+    let engine = Engine::new(rules_or_default(rules)).map_err(PyValueError::new_err)?;
+    let spec = BoardSpec {
+        max_tile,
+        fill,
+        include_multipliers,
+    };
+    let mut rng = StdRng::seed_from_u64(seed.unwrap_or_else(random_seed));
+    let board = py
+        .detach(|| {
+            if reachable {
+                curriculum::played_board(&spec, &engine, &mut rng)
+            } else {
+                curriculum::random_board(&spec, &engine, &mut rng)
+            }
+        })
+        .map_err(PyValueError::new_err)?;
+    Ok(board_to_py(&board))
+}
@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::policy::Policy;
+use crate::trajectory::TrajectoryRecorder;
+use crate::{Engine, dataset};
+
+/// Play games natively and write the recorded transitions to disk.
+///
+/// Args:
+///     policy: Callable ``policy(board) -> Direction`` or the name of a
+///             built-in policy (``"random"``, ``"greedy-score"``,
+///             ``"down-left-corner"`` or ``"snake"``).
+///     n_games: Number of games to play from fresh ``init()`` boards.
+///     out_path: Destination file.
+///     format: Output format; ``"npz"`` writes a NumPy archive with arrays
+///             ``states``, ``actions``, ``rewards``, ``next_states``,
+///             ``dones`` and ``goals``, and ``stamp``: the bytes of the
+///             :func:`engine_stamp` of the rules the games were played by.
+///     goal: Optional goal tile for goal-conditioned rewards.
+///     hindsight: Also write hindsight-relabelled transitions.
+///     max_moves: Truncate games after this many moves.
+///
+/// Returns:
+///     int: Number of transitions written.
+#[pyfunction]
+#[pyo3(signature = (policy, n_games, out_path, format="npz", goal=None, hindsight=false, max_moves=None))]
+pub fn collect_dataset(
+    policy: &Bound<'_, PyAny>,
+    n_games: usize,
+    out_path: PathBuf,
+    format: &str,
+    goal: Option<i32>,
+    hindsight: bool,
+    max_moves: Option<usize>,
+) -> PyResult<usize> {
+    if format != "npz" {
+        return Err(PyValueError::new_err(format!(
+            "unsupported dataset format: {format}"
+        )));
+    }
+
+    let py = policy.py();
+    let policy = Policy::from_py(policy)?;
+    let mut recorder = TrajectoryRecorder::new(goal, hindsight);
+    policy.run(py, Engine::AKIOI, |choose| {
+        dataset::play_games(n_games, max_moves, &mut recorder, choose)
+    })?;
+
+    let transitions = recorder.take();
+    py.detach(|| dataset::write_npz(&out_path, &transitions))?;
+    Ok(transitions.len())
+}
@@ -0,0 +1,268 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::rules::{Rules, rules_or_default};
+use super::{board_to_py, direction_to_py, parse_direction, state_to_py};
+use crate::detail;
+
+/// Two tiles combined into one during a move.
+///
+/// Attributes:
+///     target: ``(row, col)`` holding the merged tile after the move.
+///     sources: Original cells ``[(row, col), (row, col)]``: the leading tile
+///              (nearer the wall), then the one that slid into it.
+///     tiles: Values of the tiles at ``sources``.
+///     kind: ``"numbers"`` (two equal number tiles added up),
+///           ``"multipliers"`` (two equal multipliers doubled) or
+///           ``"multiply"`` (a multiplier applied to a number tile).
+///     value: Resulting tile value.
+///     score: Score contributed by this merge.
+#[pyclass(module = "akioi_2048", frozen, get_all, eq)]
+#[derive(Clone, PartialEq)]
+pub struct Merge {
+    target: (usize, usize),
+    sources: [(usize, usize); 2],
+    tiles: [i32; 2],
+    kind: &'static str,
+    value: i32,
+    score: i32,
+}
+
+#[pymethods]
+impl Merge {
+    fn __repr__(&self) -> String {
+        format!(
+            "Merge(target={:?}, sources={:?}, tiles={:?}, kind={:?}, value={}, score={})",
+            self.target, self.sources, self.tiles, self.kind, self.value, self.score
+        )
+    }
+}
+
+fn kind_name(kind: detail::MergeKind) -> &'static str {
+    match kind {
+        detail::MergeKind::Numbers => "numbers",
+        detail::MergeKind::Multipliers => "multipliers",
+        detail::MergeKind::Multiply => "multiply",
+    }
+}
+
+const MERGE_KINDS: [detail::MergeKind; 3] = [
+    detail::MergeKind::Numbers,
+    detail::MergeKind::Multipliers,
+    detail::MergeKind::Multiply,
+];
+
+/// Tile spawned after a valid move.
+///
+/// Attributes:
+///     cell: ``(row, col)`` of the new tile.
+///     value: Tile value (``2``, ``4``, ``-1`` or ``-2``).
+#[pyclass(module = "akioi_2048", frozen, get_all, eq)]
+#[derive(Clone, PartialEq)]
+pub struct Spawn {
+    cell: (usize, usize),
+    value: i32,
+}
+
+#[pymethods]
+impl Spawn {
+    fn __repr__(&self) -> String {
+        format!("Spawn(cell={:?}, value={})", self.cell, self.value)
+    }
+}
+
+/// Path of one tile during a move, ready for slide animations.
+///
+/// Attributes:
+///     source: ``(row, col)`` the tile starts in.
+///     target: ``(row, col)`` the tile slides to.
+///     value: Value of the tile before the move.
+///     merged: Whether the tile merges with another one at ``target``.
+#[pyclass(module = "akioi_2048", frozen, get_all, eq)]
+#[derive(Clone, PartialEq)]
+pub struct TileMove {
+    source: (usize, usize),
+    target: (usize, usize),
+    value: i32,
+    merged: bool,
+}
+
+#[pymethods]
+impl TileMove {
+    fn __repr__(&self) -> String {
+        format!(
+            "TileMove(source={:?}, target={:?}, value={}, merged={})",
+            self.source,
+            self.target,
+            self.value,
+            if self.merged { "True" } else { "False" }
+        )
+    }
+}
+
+/// Everything that happened during one move.
+///
+/// Attributes:
+///     board: Board after the move and spawn.
+///     delta: Score delta of the move.
+///     state: ``State`` after the move.
+///     moved: Whether the move changed the board.
+///     merges: ``list[Merge]`` in row-major order of their target cell.
+///     spawn: The spawned ``Spawn`` or ``None`` for an invalid move.
+///     spawns: ``list[Spawn]`` of every tile spawned, ``spawn`` first; more
+///             than one under ``Rules(spawns=...)``, none on a move
+///             ``Rules(spawn_every=...)`` skips.
+///     score_breakdown: ``delta`` split by merge kind:
+///                      ``{"numbers": ..., "multipliers": ..., "multiply": ...}``.
+#[pyclass(module = "akioi_2048", frozen, get_all)]
+pub struct MoveResult {
+    board: Vec<Vec<i32>>,
+    delta: i32,
+    state: Py<PyAny>,
+    moved: bool,
+    merges: Vec<Merge>,
+    spawn: Option<Spawn>,
+    spawns: Vec<Spawn>,
+    score_breakdown: Py<PyDict>,
+}
+
+#[pymethods]
+impl MoveResult {
+    fn __repr__(&self) -> String {
+        format!(
+            "MoveResult(delta={}, moved={}, merges={}, spawn={})",
+            self.delta,
+            if self.moved { "True" } else { "False" },
+            self.merges.len(),
+            self.spawn
+                .as_ref()
+                .map_or_else(|| "None".to_string(), Spawn::__repr__)
+        )
+    }
+}
+
+pub(crate) fn move_result_to_py(py: Python<'_>, r: &detail::MoveResult) -> PyResult<MoveResult> {
+    let score_breakdown = PyDict::new(py);
+    for kind in MERGE_KINDS {
+        score_breakdown.set_item(kind_name(kind), r.score_of(kind))?;
+    }
+    Ok(MoveResult {
+        board: board_to_py(&r.board),
+        delta: r.delta,
+        state: state_to_py(py, r.state)?,
+        moved: r.moved,
+        merges: r
+            .merges
+            .iter()
+            .map(|m| Merge {
+                target: m.target,
+                sources: m.sources,
+                tiles: m.tiles,
+                kind: kind_name(m.kind),
+                value: m.value,
+                score: m.score,
+            })
+            .collect(),
+        spawn: r.spawn.map(|s| Spawn {
+            cell: s.cell,
+            value: s.value,
+        }),
+        spawns: r
+            .spawn
+            .iter()
+            .chain(&r.extra_spawns)
+            .map(|s| Spawn {
+                cell: s.cell,
+                value: s.value,
+            })
+            .collect(),
+        score_breakdown: score_breakdown.unbind(),
+    })
+}
+
+/// Apply one move like ``step`` and describe it in detail.
+///
+/// Args:
+///     board: 4x4 board.
+///     direction: Move direction enum.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     MoveResult: New board, delta, state, validity, merges and spawn.
+#[pyfunction]
+#[pyo3(signature = (board, direction, rules=None))]
+pub fn step_detailed(
+    board: [[i32; 4]; 4],
+    direction: &Bound<'_, PyAny>,
+    rules: Option<&Rules>,
+) -> PyResult<MoveResult> {
+    let dir = parse_direction(direction)?;
+    let result = detail::step_detailed(board, dir, &rules_or_default(rules))
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    move_result_to_py(direction.py(), &result)
+}
+
+/// Apply one move and return the path of every tile for animation.
+///
+/// Args:
+///     board: 4x4 board.
+///     direction: Move direction enum.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     tuple[MoveResult, list[TileMove]]: The move result (its ``spawn`` is the
+///         tile to fade in) and one ``TileMove`` per tile of the original board,
+///         in row-major order of ``source``.
+#[pyfunction]
+#[pyo3(signature = (board, direction, rules=None))]
+pub fn step_with_animation(
+    board: [[i32; 4]; 4],
+    direction: &Bound<'_, PyAny>,
+    rules: Option<&Rules>,
+) -> PyResult<(MoveResult, Vec<TileMove>)> {
+    let dir = parse_direction(direction)?;
+    let (result, tiles) = detail::step_with_animation(board, dir, &rules_or_default(rules))
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let tiles = tiles
+        .into_iter()
+        .map(|t| TileMove {
+            source: t.source,
+            target: t.target,
+            value: t.value,
+            merged: t.merged,
+        })
+        .collect();
+    Ok((move_result_to_py(direction.py(), &result)?, tiles))
+}
+
+/// Find the move that turns ``before`` into ``after``, e.g. to check game
+/// logs and imported replays.
+///
+/// Args:
+///     before: 4x4 board before the move.
+///     after: 4x4 board after the move and its spawn.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     tuple[Direction, Spawn] | None: The direction and the spawned tile,
+///         or ``None`` if no single valid move does it (an unchanged board
+///         included). When several directions fit, the first in action-id
+///         order (Down, Right, Up, Left) is returned.
+#[pyfunction]
+#[pyo3(signature = (before, after, rules=None))]
+pub fn infer_move(
+    py: Python<'_>,
+    before: [[i32; 4]; 4],
+    after: [[i32; 4]; 4],
+    rules: Option<&Rules>,
+) -> PyResult<Option<(Py<PyAny>, Spawn)>> {
+    crate::infer_move(&before, &after, &rules_or_default(rules))
+        .map(|(dir, spawn)| {
+            let spawn = Spawn {
+                cell: spawn.cell,
+                value: spawn.value,
+            };
+            Ok((direction_to_py(py, dir)?, spawn))
+        })
+        .transpose()
+}
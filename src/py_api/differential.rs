@@ -0,0 +1,71 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use super::rules::{Rules, rules_or_default};
+use super::{board_to_py, direction_to_py};
+use crate::differential::{WebLog, first_divergence};
+
+/// Replay a game the web version logged through the engine and find the
+/// first move the two disagree on.
+///
+/// The log is JSON lines: ``{"state": ...}`` with the starting
+/// ``gameState`` (the layout ``Game.to_web_save`` writes), then one
+/// ``{"move": d, "state": ...}`` per move with the web version's direction
+/// numbers (0 up, 1 right, 2 down, 3 left) or a name, and optionally
+/// ``"spawn": {"x": column, "y": row, "value": tile}``. Every move replays
+/// from the web version's position before it, so the divergence found is
+/// one move's alone.
+///
+/// Args:
+///     log: The log's text.
+///     rules: Rules the web version plays (default ``Rules()``).
+///
+/// Returns:
+///     dict | None: ``None`` when every move agrees; otherwise ``move``
+///         (counting from 1), ``kind`` (``"slide"``, ``"spawn"``,
+///         ``"score"`` or ``"over"``), ``direction``, ``before`` and
+///         ``web`` (the web version's boards around the move), ``engine``
+///         (the engine's slide, without spawns), ``web_score`` and
+///         ``engine_score``, ``message``, and ``case``: for a slide, the
+///         first line that differs alone, read from the wall it slides
+///         toward, as ``{"line", "engine", "web"}``; ``None`` otherwise.
+///
+/// Raises:
+///     ValueError: For a malformed log, naming its line, or a starting
+///         position invalid under the rules.
+#[pyfunction]
+#[pyo3(signature = (log, rules=None))]
+pub fn diff_web_log<'py>(
+    py: Python<'py>,
+    log: &str,
+    rules: Option<&Rules>,
+) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let rules = rules_or_default(rules);
+    let log = WebLog::parse(log).map_err(PyValueError::new_err)?;
+    let Some(divergence) = first_divergence(&log, &rules).map_err(PyValueError::new_err)? else {
+        return Ok(None);
+    };
+    let out = PyDict::new(py);
+    out.set_item("move", divergence.move_number)?;
+    out.set_item("kind", divergence.kind.name())?;
+    out.set_item("direction", direction_to_py(py, divergence.direction)?)?;
+    out.set_item("before", board_to_py(&divergence.before.board))?;
+    out.set_item("web", board_to_py(&divergence.web.board))?;
+    out.set_item("engine", board_to_py(&divergence.slid))?;
+    out.set_item("web_score", divergence.web.score)?;
+    out.set_item("engine_score", divergence.score)?;
+    out.set_item("message", &divergence.message)?;
+    let case = divergence
+        .minimize()
+        .map(|case| {
+            let case_dict = PyDict::new(py);
+            case_dict.set_item("line", case.line.to_vec())?;
+            case_dict.set_item("engine", case.engine.to_vec())?;
+            case_dict.set_item("web", case.web.to_vec())?;
+            Ok::<_, PyErr>(case_dict)
+        })
+        .transpose()?;
+    out.set_item("case", case)?;
+    Ok(Some(out))
+}
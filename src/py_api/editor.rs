@@ -0,0 +1,93 @@
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::board_to_py;
+use super::errors::{checked_board, invalid_board};
+use super::rules::{Rules, rules_or_default};
+use crate::editor;
+
+/// A board built cell by cell, every tile checked under the rules as it is
+/// placed, for test scenarios and puzzles.
+///
+/// ``set`` and ``clear`` return the builder, so calls chain. Valid tiles can
+/// still make a board no game reaches; ``validate`` says why.
+///
+/// Args:
+///     board: Board to start from (default: empty).
+///     rules: Optional ``Rules`` the tiles must be valid under.
+///
+/// Raises:
+///     InvalidBoardError: If ``board`` holds a tile invalid under the rules.
+#[pyclass(module = "akioi_2048")]
+pub struct BoardBuilder {
+    inner: editor::BoardBuilder,
+}
+
+#[pymethods]
+impl BoardBuilder {
+    #[new]
+    #[pyo3(signature = (board=None, rules=None))]
+    fn new(board: Option<&Bound<'_, PyAny>>, rules: Option<&Rules>) -> PyResult<Self> {
+        let rules = rules_or_default(rules);
+        let inner = match board {
+            Some(board) => editor::BoardBuilder::from_board(checked_board(board, &rules)?, rules)
+                .map_err(invalid_board)?,
+            None => editor::BoardBuilder::new(rules),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Put ``value`` at ``row``, ``col`` (both from 0, top left); ``0``
+    /// empties the cell.
+    ///
+    /// Raises:
+    ///     IndexError: For a cell off the board.
+    ///     InvalidBoardError: For a tile invalid under the rules; the board
+    ///         is left as it was.
+    fn set(
+        mut slf: PyRefMut<'_, Self>,
+        row: usize,
+        col: usize,
+        value: i32,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        if row >= 4 || col >= 4 {
+            return Err(PyIndexError::new_err(format!(
+                "cell ({row}, {col}) is off the 4x4 board"
+            )));
+        }
+        slf.inner.set(row, col, value).map_err(invalid_board)?;
+        Ok(slf)
+    }
+
+    /// Empty every cell.
+    fn clear(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.inner.clear();
+        slf
+    }
+
+    /// Why no game under the rules reaches the board, such as two winning
+    /// tiles or a lone tile, one sentence each; empty when nothing rules
+    /// it out. Invalid tiles never get on the board, so these are only
+    /// warnings.
+    fn validate(&self) -> Vec<String> {
+        self.inner.warnings()
+    }
+
+    /// The board, as nested lists.
+    #[getter]
+    fn board(&self) -> Vec<Vec<i32>> {
+        board_to_py(&self.inner.board())
+    }
+
+    #[getter]
+    fn rules(&self) -> Rules {
+        Rules {
+            inner: self.inner.rules(),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BoardBuilder('{}')", self.inner.notation())
+    }
+}
@@ -0,0 +1,63 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::ndarray;
+use super::rules::{Rules, rules_or_default};
+use crate::Board;
+use crate::encode::Scheme;
+
+/// Encode boards as ``float32`` planes for neural networks.
+///
+/// Args:
+///     board: One 4x4 board or a batch of them (nested lists or a NumPy
+///            array such as ``Arena.observations``).
+///     scheme: ``"onehot"`` (empty plane, one plane per number tile 2 to
+///             65536, then one per multiplier x1, x2, ...), ``"log2"`` (log2 of
+///             number tiles and, on a second plane, log2(m) + 1 of x-m
+///             multipliers) or ``"raw"`` (the tile values).
+///     rules: Optional ``Rules``; the largest multiplier sets the number of
+///            one-hot multiplier planes.
+///
+/// Returns:
+///     numpy.ndarray: Shape ``(channels, 4, 4)`` for one board or
+///         ``(n, channels, 4, 4)`` for a batch.
+#[pyfunction]
+#[pyo3(signature = (board, scheme="onehot", rules=None))]
+pub fn encode<'py>(
+    board: &Bound<'py, PyAny>,
+    scheme: &str,
+    rules: Option<&Rules>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = board.py();
+    let scheme = Scheme::from_name(scheme)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown encoding scheme: {scheme}")))?;
+    let rules = rules_or_default(rules);
+    let board = if board.hasattr("tolist")? {
+        board.call_method0("tolist")?
+    } else {
+        board.clone()
+    };
+    let (boards, batch) = match board.extract::<Board>() {
+        Ok(one) => (vec![one], false),
+        Err(_) => (board.extract::<Vec<Board>>()?, true),
+    };
+
+    let planes = py.detach(|| {
+        let mut planes = Vec::with_capacity(boards.len() * scheme.channels(&rules) * 16);
+        for b in &boards {
+            crate::encode::encode(b, scheme, &rules, &mut planes)?;
+        }
+        Ok::<_, String>(planes)
+    });
+    let data: Vec<u8> = planes
+        .map_err(PyValueError::new_err)?
+        .into_iter()
+        .flat_map(f32::to_ne_bytes)
+        .collect();
+    let mut shape = vec![scheme.channels(&rules), 4, 4];
+    if batch {
+        shape.insert(0, boards.len());
+    }
+    ndarray(py, &data, "float32", &shape)
+}
@@ -0,0 +1,207 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::detail::move_result_to_py;
+use super::errors::game_finished;
+use super::rules::{Rules, rules_or_default};
+use super::session::parse_invalid_moves;
+use super::{board_to_py, ndarray, parse_direction, state_to_py};
+use crate::Board;
+use crate::dataset::action_index;
+use crate::env::{self, EnvOptions, RewardKind, RewardSpec};
+
+/// Gym-style environment around one seeded game.
+///
+/// Observations are NumPy ``int32`` boards of shape ``(4, 4)``.
+///
+/// Args:
+///     seed: Environment seed; every ``reset`` draws a new game seed from it.
+///     rules: Optional ``Rules``.
+///     reward: ``"score"`` (score delta), ``"log_score"`` (signed
+///             ``log2(1 + |delta|)``), ``"merges"`` (merge count) or a
+///             callable ``reward(board, result) -> float`` given the board
+///             before the move and its ``MoveResult``.
+///     empty_bonus: Added per empty cell after the move.
+///     game_over_penalty: Subtracted on the move that loses the game.
+///     victory_bonus: Added on the move that wins the game.
+///     max_moves: Truncate episodes after this many moves.
+///     invalid_moves: Invalid-move policy, as for ``Game``.
+///     invalid_penalty: Subtracted for a move that changes nothing under
+///                      ``invalid_moves="penalize"``.
+///     keep_playing: Play on after a victory, as for ``Game``; episodes then
+///                   only terminate when no move is left.
+#[pyclass(module = "akioi_2048")]
+pub struct Env {
+    inner: env::Env,
+    reward_fn: Option<Py<PyAny>>,
+}
+
+/// ``(observation, reward, terminated, truncated, info)``
+type StepOutput<'py> = (Bound<'py, PyAny>, f64, bool, bool, Bound<'py, PyDict>);
+
+fn info<'py>(py: Python<'py>, game: &crate::Game) -> PyResult<Bound<'py, PyDict>> {
+    let info = PyDict::new(py);
+    info.set_item("score", game.score())?;
+    info.set_item("moves", game.moves())?;
+    info.set_item("state", state_to_py(py, game.state())?)?;
+    let mask = game.action_mask().map(u8::from);
+    info.set_item("action_mask", ndarray(py, &mask, "bool", &[4])?)?;
+    Ok(info)
+}
+
+fn observation<'py>(py: Python<'py>, board: &Board) -> PyResult<Bound<'py, PyAny>> {
+    let data: Vec<u8> = board
+        .iter()
+        .flatten()
+        .flat_map(|v| v.to_ne_bytes())
+        .collect();
+    ndarray(py, &data, "int32", &[4, 4])
+}
+
+#[pymethods]
+impl Env {
+    #[new]
+    #[pyo3(signature = (
+        seed=None,
+        rules=None,
+        reward=None,
+        empty_bonus=0.0,
+        game_over_penalty=0.0,
+        victory_bonus=0.0,
+        max_moves=None,
+        invalid_moves="noop",
+        invalid_penalty=1.0,
+        keep_playing=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        seed: Option<u64>,
+        rules: Option<&Rules>,
+        reward: Option<&Bound<'_, PyAny>>,
+        empty_bonus: f64,
+        game_over_penalty: f64,
+        victory_bonus: f64,
+        max_moves: Option<usize>,
+        invalid_moves: &str,
+        invalid_penalty: f64,
+        keep_playing: bool,
+    ) -> PyResult<Self> {
+        let invalid_moves = parse_invalid_moves(invalid_moves)?;
+        let mut spec = RewardSpec {
+            kind: RewardKind::Score,
+            empty_bonus,
+            game_over_penalty,
+            victory_bonus,
+            invalid_penalty: 0.0,
+        };
+        let mut reward_fn = None;
+        if let Some(reward) = reward {
+            if let Ok(name) = reward.extract::<String>() {
+                spec.kind = RewardKind::from_name(&name)
+                    .ok_or_else(|| PyValueError::new_err(format!("unknown reward: {name}")))?;
+            } else if reward.is_callable() {
+                if spec != RewardSpec::default() {
+                    return Err(PyValueError::new_err(
+                        "reward bonuses and penalties cannot be combined with a callable reward",
+                    ));
+                }
+                reward_fn = Some(reward.clone().unbind());
+            } else {
+                return Err(PyValueError::new_err(
+                    "reward must be a callable or a reward name",
+                ));
+            }
+        }
+        spec.invalid_penalty = invalid_penalty;
+        let options = EnvOptions {
+            rules: rules_or_default(rules),
+            reward: spec,
+            max_moves,
+            invalid_moves,
+            keep_playing,
+        };
+        let inner = env::Env::new(seed, options).map_err(PyValueError::new_err)?;
+        Ok(Self { inner, reward_fn })
+    }
+
+    /// Start a new episode.
+    ///
+    /// Args:
+    ///     seed: Restart the sequence of episode seeds from this seed.
+    ///
+    /// Returns:
+    ///     tuple[numpy.ndarray, dict]: ``(observation, info)`` with ``score``,
+    ///         ``moves``, ``state`` and ``action_mask`` (a ``bool`` array of
+    ///         the legal action ids) in ``info``.
+    #[pyo3(signature = (seed=None))]
+    fn reset<'py>(
+        &mut self,
+        py: Python<'py>,
+        seed: Option<u64>,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyDict>)> {
+        let board = self.inner.reset(seed);
+        Ok((observation(py, &board)?, info(py, self.inner.game())?))
+    }
+
+    /// Apply one ``Direction`` or action id (Down=0, Right=1, Up=2, Left=3).
+    ///
+    /// Returns:
+    ///     tuple[numpy.ndarray, float, bool, bool, dict]: ``(observation,
+    ///         reward, terminated, truncated, info)``; ``info`` adds ``delta``,
+    ///         ``moved`` and ``action`` (the action id actually played, which
+    ///         differs under ``"auto-resolve"``) to the ``reset`` keys.
+    fn step<'py>(&mut self, action: &Bound<'py, PyAny>) -> PyResult<StepOutput<'py>> {
+        let py = action.py();
+        let direction = parse_direction(action)?;
+        if self.inner.game().is_over() {
+            return Err(game_finished("the episode is over; call reset()"));
+        }
+        let before = self.inner.game().board();
+        let step = self.inner.step(direction).map_err(PyValueError::new_err)?;
+        let game = self.inner.game();
+        let reward = match &self.reward_fn {
+            Some(f) => {
+                let reward: f64 = f
+                    .bind(py)
+                    .call1((board_to_py(&before), move_result_to_py(py, &step.result)?))?
+                    .extract()?;
+                reward - self.inner.invalid_penalty(&step.result)
+            }
+            None => step.reward,
+        };
+        let played = match game.history().last() {
+            Some(&last) if step.result.moved => last,
+            _ => direction,
+        };
+        let info = info(py, game)?;
+        info.set_item("delta", step.result.delta)?;
+        info.set_item("moved", step.result.moved)?;
+        info.set_item("action", action_index(played))?;
+        Ok((
+            observation(py, &step.result.board)?,
+            reward,
+            step.terminated,
+            step.truncated,
+            info,
+        ))
+    }
+
+    /// Current board, shape ``(4, 4)``.
+    #[getter]
+    fn observation<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        observation(py, &self.inner.game().board())
+    }
+
+    #[getter]
+    fn score(&self) -> i64 {
+        self.inner.game().score()
+    }
+
+    #[getter]
+    fn rules(&self) -> Rules {
+        Rules {
+            inner: self.inner.options().rules,
+        }
+    }
+}
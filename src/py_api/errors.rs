@@ -0,0 +1,57 @@
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+
+create_exception!(
+    akioi_2048,
+    InvalidBoardError,
+    PyValueError,
+    "A board that is not 4x4 integers, or holds a tile the rules do not allow."
+);
+create_exception!(
+    akioi_2048,
+    InvalidDirectionError,
+    PyValueError,
+    "A move that is neither a ``Direction`` nor an action id from 0 to 3."
+);
+create_exception!(
+    akioi_2048,
+    GameFinishedError,
+    PyValueError,
+    "A move in a game, episode or match that is already over."
+);
+create_exception!(
+    akioi_2048,
+    CancelledError,
+    PyException,
+    "A long native call stopped by its ``CancelToken``."
+);
+
+pub(crate) fn invalid_board(message: impl Into<String>) -> PyErr {
+    InvalidBoardError::new_err(message.into())
+}
+
+pub(crate) fn invalid_direction(message: impl Into<String>) -> PyErr {
+    InvalidDirectionError::new_err(message.into())
+}
+
+pub(crate) fn game_finished(message: impl Into<String>) -> PyErr {
+    GameFinishedError::new_err(message.into())
+}
+
+/// A 4x4 board of integers, or ``InvalidBoardError``
+pub(crate) fn extract_board(board: &Bound<'_, PyAny>) -> PyResult<crate::Board> {
+    board
+        .extract()
+        .map_err(|_| invalid_board("board must be 4x4 integers"))
+}
+
+/// ``extract_board`` that also checks every tile under ``rules``
+pub(crate) fn checked_board(
+    board: &Bound<'_, PyAny>,
+    rules: &crate::Rules,
+) -> PyResult<crate::Board> {
+    let board = extract_board(board)?;
+    crate::validate_board_with(&board, rules).map_err(invalid_board)?;
+    Ok(board)
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::book::OpeningBook;
+use super::direction_to_py;
+use super::rules::{Rules, rules_or_default};
+use crate::Board;
+use crate::search::{SearchOptions, Weights};
+
+/// Recommend a move, with the numbers behind it, for assist features.
+///
+/// Args:
+///     board: 4x4 board.
+///     depth: Moves to search ahead with expectimax, averaging over every
+///            spawn by its probability.
+///     rules: Optional ``Rules``.
+///     time_budget: Seconds to deepen the search for, one move at a time up
+///                  to ``depth``; the deepest completed search is used.
+///     threads: Search threads (default: one per core).
+///     book: An ``OpeningBook`` to look the board up in first; only
+///           positions it does not hold are searched.
+///     moves_played: Moves played before ``board``, which decide the moves
+///                   that spawn under ``Rules(spawn_every=...)``.
+///
+/// Returns:
+///     tuple[Direction, dict] | None: The best move and an explanation:
+///         ``"values"`` maps every legal ``Direction`` to its expected
+///         evaluation, best first (only the book's move when it comes from
+///         the book); ``"factors"`` holds the heuristic features of the
+///         board after the move (``empty_cells``, ``monotonicity``,
+///         ``smoothness``, ``corner``, ``merges``, ``multipliers``);
+///         ``"score"`` is what the move merges and ``"summary"`` says it in
+///         one sentence; ``"depth"`` is the depth searched and ``"source"``
+///         is ``"book"`` or ``"search"``. ``None`` when no move is left.
+///
+/// Raises:
+///     ValueError: For an invalid board, a negative time budget, no
+///         threads or a book built under other rules.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature = (board, depth=3, rules=None, time_budget=None, threads=None, book=None, moves_played=0))]
+pub fn hint(
+    py: Python<'_>,
+    board: Board,
+    depth: u32,
+    rules: Option<&Rules>,
+    time_budget: Option<f64>,
+    threads: Option<usize>,
+    book: Option<&OpeningBook>,
+    moves_played: u64,
+) -> PyResult<Option<(Py<PyAny>, Py<PyDict>)>> {
+    let rules = rules_or_default(rules);
+    let time_budget = time_budget
+        .map(|seconds| {
+            Duration::try_from_secs_f64(seconds).map_err(|_| {
+                PyValueError::new_err(format!("time_budget must be non-negative, got {seconds}"))
+            })
+        })
+        .transpose()?;
+    let options = SearchOptions {
+        depth,
+        time_budget,
+        threads,
+        moves_played,
+        ..SearchOptions::default()
+    };
+    let Some(hint) = py
+        .detach(|| crate::hint::hint_with_book(&board, &rules, &options, book.map(|b| &b.inner)))
+        .map_err(PyValueError::new_err)?
+    else {
+        return Ok(None);
+    };
+    let values = PyDict::new(py);
+    for &(dir, value) in &hint.values {
+        values.set_item(direction_to_py(py, dir)?, value)?;
+    }
+    let factors = PyDict::new(py);
+    for (name, value) in hint.features.named() {
+        factors.set_item(name, value)?;
+    }
+    let explanation = PyDict::new(py);
+    explanation.set_item("values", values)?;
+    explanation.set_item("factors", factors)?;
+    explanation.set_item("score", hint.score)?;
+    explanation.set_item("summary", hint.summary)?;
+    explanation.set_item("depth", hint.depth)?;
+    explanation.set_item("source", if hint.from_book { "book" } else { "search" })?;
+    Ok(Some((
+        direction_to_py(py, hint.direction)?,
+        explanation.unbind(),
+    )))
+}
+
+/// The expectimax value of every legal move, for move quality bars and
+/// policy statistics.
+///
+/// Each move is followed by an average over every spawn, weighted by its
+/// probability, down to ``depth`` moves; no spawn sequence is pruned, so
+/// the values are exact for the heuristic evaluation ``hint`` uses. Boards
+/// with no move left count as about ``-10000``.
+///
+/// Args:
+///     board: 4x4 board.
+///     depth: Moves to look ahead, this one included.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     dict[Direction, float]: The value of each legal move, in action-id
+///         order; empty when no move is left.
+///
+/// Raises:
+///     ValueError: For an invalid board.
+#[pyfunction]
+#[pyo3(signature = (board, depth=2, rules=None))]
+pub fn evaluate_moves(
+    py: Python<'_>,
+    board: Board,
+    depth: u32,
+    rules: Option<&Rules>,
+) -> PyResult<Py<PyDict>> {
+    let rules = rules_or_default(rules);
+    let values = py
+        .detach(|| crate::search::expectimax(&board, &rules, depth, &Weights::default()))
+        .map_err(PyValueError::new_err)?;
+    let out = PyDict::new(py);
+    for (dir, value) in values {
+        out.set_item(direction_to_py(py, dir)?, value)?;
+    }
+    Ok(out.unbind())
+}
+
+/// The worst case of every legal move, with each spawn chosen by an
+/// adversary instead of by chance.
+///
+/// Minimax with alpha-beta pruning replaces the average over spawns by the
+/// spawn worst for the player, considering every tile the rules can spawn
+/// on every empty cell, so the bounds hold whatever the luck: for proving
+/// survivability and for designing puzzles.
+///
+/// Args:
+///     board: 4x4 board.
+///     depth: Moves to look ahead.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     dict[Direction, dict]: For each legal move, ``"value"``, the
+///         evaluation the adversary can hold the player to (about
+///         ``-10000`` when it can force a dead board), and
+///         ``"guaranteed_moves"``, the moves the player is sure to make,
+///         this one included, up to ``depth``.
+///
+/// Raises:
+///     ValueError: For an invalid board.
+#[pyfunction]
+#[pyo3(signature = (board, depth=3, rules=None))]
+pub fn worst_case_moves(
+    py: Python<'_>,
+    board: Board,
+    depth: u32,
+    rules: Option<&Rules>,
+) -> PyResult<Py<PyDict>> {
+    let rules = rules_or_default(rules);
+    let moves = py
+        .detach(|| crate::search::worst_case(&board, &rules, depth, &Weights::default()))
+        .map_err(PyValueError::new_err)?;
+    let out = PyDict::new(py);
+    for m in moves {
+        let entry = PyDict::new(py);
+        entry.set_item("value", m.value)?;
+        entry.set_item("guaranteed_moves", m.guaranteed_moves)?;
+        out.set_item(direction_to_py(py, m.direction)?, entry)?;
+    }
+    Ok(out.unbind())
+}
@@ -0,0 +1,27 @@
+use std::io;
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::image::{self, Theme};
+
+/// Save a picture of a board as PNG or SVG.
+///
+/// Tile colours match the web version; multiplier tiles are purple and
+/// labelled ``×1``/``×2``/``×4``.
+///
+/// Args:
+///     board: 4x4 board.
+///     path: Destination; the format follows its extension (``.png`` or ``.svg``).
+///     theme: ``"light"`` or ``"dark"`` frame and empty cells.
+#[pyfunction]
+#[pyo3(signature = (board, path, theme="light"))]
+pub fn render_image(board: [[i32; 4]; 4], path: PathBuf, theme: &str) -> PyResult<()> {
+    let theme = Theme::from_name(theme)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown theme: {theme}")))?;
+    image::save(&board, &path, theme).map_err(|e| match e.kind() {
+        io::ErrorKind::InvalidInput => PyValueError::new_err(e.to_string()),
+        _ => e.into(),
+    })
+}
@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use super::replay::Replay;
+use super::rules::Rules;
+use crate::leaderboard::{self, Entry, SortKey};
+
+/// A finished game on a ``Leaderboard``.
+///
+/// Attributes:
+///     score: Final score.
+///     max_tile: Largest tile of the final board.
+///     moves: Accepted moves.
+///     seed: Seed the game was played with.
+///     rules: ``Rules`` it was played under.
+///     replay_hash: ``Replay.digest()`` of the game.
+#[pyclass(module = "akioi_2048", frozen, get_all)]
+pub struct LeaderboardEntry {
+    score: i64,
+    max_tile: i32,
+    moves: usize,
+    seed: u64,
+    rules: Rules,
+    replay_hash: String,
+}
+
+#[pymethods]
+impl LeaderboardEntry {
+    fn __repr__(&self) -> String {
+        format!(
+            "LeaderboardEntry(score={}, max_tile={}, moves={}, rules={:?})",
+            self.score,
+            self.max_tile,
+            self.moves,
+            self.rules.inner.tag()
+        )
+    }
+}
+
+impl From<&Entry> for LeaderboardEntry {
+    fn from(entry: &Entry) -> Self {
+        Self {
+            score: entry.score,
+            max_tile: entry.max_tile,
+            moves: entry.moves,
+            seed: entry.seed,
+            rules: Rules { inner: entry.rules },
+            replay_hash: entry.replay_hash.clone(),
+        }
+    }
+}
+
+/// A local high-score table of finished games, kept in a text file.
+///
+/// Entries come from replays, so every score is one the engine played;
+/// the file is only appended to. A missing file starts an empty board.
+///
+/// Args:
+///     path: Leaderboard file.
+///
+/// Raises:
+///     OSError: If the file cannot be read.
+///     ValueError: If it is not a leaderboard.
+#[pyclass(module = "akioi_2048")]
+pub struct Leaderboard {
+    inner: leaderboard::Leaderboard,
+}
+
+#[pymethods]
+impl Leaderboard {
+    #[new]
+    fn new(path: PathBuf) -> PyResult<Self> {
+        leaderboard::Leaderboard::open(&path)
+            .map(|inner| Self { inner })
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::InvalidData => PyValueError::new_err(e.to_string()),
+                _ => e.into(),
+            })
+    }
+
+    /// Play ``replay`` back and add its final position.
+    ///
+    /// Returns:
+    ///     bool: ``False`` if the same game is already on the board.
+    ///
+    /// Raises:
+    ///     ValueError: If the replay does not play back.
+    ///     OSError: If the file cannot be written.
+    fn record(&mut self, py: Python<'_>, replay: &Replay) -> PyResult<bool> {
+        let entry = py
+            .detach(|| Entry::from_replay(&replay.inner))
+            .map_err(PyValueError::new_err)?;
+        Ok(self.inner.record(entry)?)
+    }
+
+    /// Entries best first.
+    ///
+    /// Args:
+    ///     rules: Only games played under these ``Rules``.
+    ///     sort: ``"score"``, ``"max_tile"`` (then score) or ``"moves"``
+    ///           (fewest first, then score); ties keep the recording order.
+    ///     limit: At most this many entries.
+    ///
+    /// Raises:
+    ///     ValueError: For an unknown ``sort``.
+    #[pyo3(signature = (rules=None, sort="score", limit=None))]
+    fn top(
+        &self,
+        rules: Option<&Rules>,
+        sort: &str,
+        limit: Option<usize>,
+    ) -> PyResult<Vec<LeaderboardEntry>> {
+        let sort = SortKey::from_name(sort)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown sort key: {sort}")))?;
+        Ok(self
+            .inner
+            .query(rules.map(|r| &r.inner), sort, limit)
+            .into_iter()
+            .map(LeaderboardEntry::from)
+            .collect())
+    }
+
+    /// 1-based position a game with ``score`` would take under ``rules``.
+    #[pyo3(signature = (score, rules=None))]
+    fn rank(&self, score: i64, rules: Option<&Rules>) -> usize {
+        self.inner
+            .rank(score, &super::rules::rules_or_default(rules))
+    }
+
+    #[getter]
+    fn path(&self) -> String {
+        self.inner.path().display().to_string()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.entries().len()
+    }
+}
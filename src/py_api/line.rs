@@ -0,0 +1,92 @@
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::errors::invalid_board;
+use super::rules::rules_or_named;
+use crate::MergeRule;
+
+/// Check ``tiles`` as cells of a board under ``rules``.
+fn validate_tiles(tiles: &[i32], rules: &crate::Rules) -> PyResult<()> {
+    let mut board = [[0; 4]; 4];
+    for (i, &tile) in tiles.iter().enumerate() {
+        board[i / 4][i % 4] = tile;
+    }
+    crate::validate_board_with(&board, rules).map_err(invalid_board)
+}
+
+/// Slide one row toward its first cell, exactly as a ``Left`` move does.
+///
+/// Reverse the row (and the result) for ``Right``; a column read from the
+/// top slides the same way under ``Up``.
+///
+/// Args:
+///     row: Four tiles.
+///     rules: ``Rules``, a preset name or a rules tag.
+///
+/// Returns:
+///     tuple[list[int], int]: The row after the slide, before any spawn,
+///         and the score it merges.
+///
+/// Raises:
+///     InvalidBoardError: For a tile that is invalid under ``rules``.
+#[pyfunction]
+#[pyo3(signature = (row, rules=None))]
+pub fn slide_row(row: [i32; 4], rules: Option<&Bound<'_, PyAny>>) -> PyResult<(Vec<i32>, i32)> {
+    let rules = rules_or_named(rules)?;
+    validate_tiles(&row, &rules)?;
+    let (row, score) = crate::slide_row(row, &rules);
+    Ok((row.to_vec(), score))
+}
+
+/// What two tiles meeting in a slide merge into, if anything.
+///
+/// ``a`` is the tile nearer the wall and ``b`` the next tile behind it;
+/// the context decides the multiplier conditions: a number and a
+/// multiplier only merge when they touched before the move, and under
+/// ``require_full_below`` only when the cells between ``a`` and the wall
+/// are all taken. Blockers and bombs never merge; a bomb clears the line
+/// instead.
+///
+/// Args:
+///     a: Leading tile.
+///     b: Following tile.
+///     adjacent: Whether ``a`` and ``b`` touched before the move.
+///     below: Tiles between ``a`` and the wall before the move, ``0`` for
+///            an empty cell.
+///     rules: ``Rules``, a preset name or a rules tag.
+///
+/// Returns:
+///     tuple[int, int] | None: The merged tile and the score it adds, or
+///         ``None`` when the tiles stay apart.
+///
+/// Raises:
+///     InvalidBoardError: For an empty or invalid tile, or more than two
+///         tiles below.
+#[pyfunction]
+#[pyo3(signature = (a, b, adjacent=true, below=Vec::new(), rules=None))]
+pub fn merge_preview(
+    a: i32,
+    b: i32,
+    adjacent: bool,
+    below: Vec<i32>,
+    rules: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Option<(i32, i32)>> {
+    let rules = rules_or_named(rules)?;
+    if a == 0 || b == 0 {
+        return Err(invalid_board("merging tiles cannot be empty"));
+    }
+    if below.len() > 2 {
+        return Err(invalid_board(format!(
+            "at most 2 cells lie below two tiles of a row, got {}",
+            below.len()
+        )));
+    }
+    validate_tiles(&[&[a, b][..], &below].concat(), &rules)?;
+    if [a, b]
+        .iter()
+        .any(|&t| t == crate::BLOCKER || t == crate::BOMB)
+    {
+        return Ok(None);
+    }
+    Ok(rules.variant.merge(a, b, adjacent, &below, &rules))
+}
@@ -0,0 +1,564 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyByteArray, PyDict, PyModule};
+
+mod arena;
+mod bench;
+mod book;
+mod buffer;
+mod cli;
+mod compat;
+mod curriculum;
+mod dataset;
+mod detail;
+mod differential;
+mod editor;
+mod encode;
+mod env;
+mod errors;
+mod hint;
+mod image;
+mod leaderboard;
+mod line;
+mod notation;
+mod ntuple;
+mod onnx;
+mod pack;
+mod policy;
+mod progress;
+mod proof;
+mod puzzle;
+mod reach;
+mod render;
+mod replay;
+mod report;
+mod rules;
+mod session;
+mod spec;
+mod stats;
+mod status;
+mod survival;
+mod tablebase;
+mod tournament;
+mod trajectory;
+mod tune;
+mod versus;
+
+/// Create a new 4x4 board with two starting tiles.
+///
+/// Args:
+///     blockers: Immovable blocker tiles (``-3``) placed on random cells
+///               first; they never slide or merge, and need rules with
+///               ``blockers=True``.
+///     rules: ``Rules``, a preset name such as ``"classic"`` or a rules tag,
+///            deciding what the starting tiles can be.
+///
+/// Returns:
+///     list[list[int]]: Fresh board ready for play.
+///
+/// Raises:
+///     ValueError: For blockers the rules do not allow, more than 14 of
+///         them or unknown rules.
+#[pyfunction]
+#[pyo3(signature = (blockers=0, rules=None))]
+pub fn init(blockers: usize, rules: Option<&Bound<'_, PyAny>>) -> PyResult<Vec<Vec<i32>>> {
+    let rules = rules::rules_or_named(rules)?;
+    let board = crate::init_with_blockers(&mut rand::rng(), blockers, &rules)
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok(board_to_py(&board))
+}
+
+#[pyfunction]
+/// Apply one move. If the board changes, a new tile appears in a random empty cell.
+///
+/// Args:
+///     board: 4x4 board. Positive numbers are normal tiles (2, 4, 8, ...).
+///            Negative numbers are multipliers: -1=x1, -2=x2, -4=x4
+///            (absolute value is the multiplier), except the immovable
+///            blocker -3.
+///     direction: Direction, its action id or its name ("up").
+///     rules: Optional ``Rules`` overriding the standard multiplier mechanics.
+///     strict: Reject tiles that are invalid under ``rules`` (see
+///             ``validate``). ``strict=False`` applies the merge rules to any
+///             values, for experiments with custom tile sets.
+///
+/// Returns:
+///     StepResult: The tuple (board, delta, state) with named fields, where
+///         state is State.{Victory, GameOver, Continue}.
+///
+/// Raises:
+///     InvalidBoardError: For a malformed board or, when strict, an invalid
+///         tile.
+///     InvalidDirectionError: If direction names no direction.
+///
+/// Notes:
+///     If the board does not change, no tile is spawned and delta_score=0.
+#[pyo3(signature = (board, direction, rules=None, strict=true))]
+pub fn step(
+    board: &Bound<'_, PyAny>,
+    direction: &Bound<'_, PyAny>,
+    rules: Option<&rules::Rules>,
+    strict: bool,
+) -> PyResult<Py<PyAny>> {
+    let dir = parse_direction(direction)?;
+    let rules = rules::rules_or_default(rules);
+    let result = if strict {
+        let board4 = errors::checked_board(board, &rules)?;
+        crate::step_with_rules(board4, dir, &rules)
+    } else {
+        let board4 = errors::extract_board(board)?;
+        crate::step_unvalidated(board4, dir, &rules, &mut rand::rng())
+    };
+    match result {
+        Ok((next, delta, state)) => step_result_to_py(board.py(), &next, delta, state),
+        Err(msg) => Err(pyo3::exceptions::PyValueError::new_err(msg)),
+    }
+}
+
+/// Check that ``board`` is a board the engine can play.
+///
+/// Args:
+///     board: 4x4 board of integers, checked for shape in every mode.
+///     strict: Also require every tile to be valid under ``rules``: 0, a
+///             power of two from 2 to 65536, an allowed multiplier, or a
+///             blocker or bomb the rules allow.
+///     rules: Optional ``Rules``.
+///
+/// Raises:
+///     InvalidBoardError: Naming the first invalid tile, or for a malformed
+///         board.
+#[pyfunction]
+#[pyo3(signature = (board, strict=true, rules=None))]
+pub fn validate(
+    board: &Bound<'_, PyAny>,
+    strict: bool,
+    rules: Option<&rules::Rules>,
+) -> PyResult<()> {
+    if strict {
+        errors::checked_board(board, &rules::rules_or_default(rules))?;
+    } else {
+        errors::extract_board(board)?;
+    }
+    Ok(())
+}
+
+/// Build a board from the tiles at a few cells, for setting up scenarios.
+///
+/// Args:
+///     tiles: ``{(row, col): tile}``; every other cell is empty.
+///     rules: Optional ``Rules`` the tiles must be valid under.
+///
+/// Returns:
+///     list[list[int]]: The 4x4 board.
+///
+/// Raises:
+///     ValueError: For a cell outside the board or an invalid tile.
+#[pyfunction]
+#[pyo3(signature = (tiles, rules=None))]
+pub fn board_with(
+    tiles: HashMap<(usize, usize), i32>,
+    rules: Option<&rules::Rules>,
+) -> PyResult<Vec<Vec<i32>>> {
+    let mut board = [[0; 4]; 4];
+    for ((row, col), tile) in tiles {
+        let cell = board
+            .get_mut(row)
+            .and_then(|r| r.get_mut(col))
+            .ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "cell ({row}, {col}) is outside the board"
+                ))
+            })?;
+        *cell = tile;
+    }
+    crate::validate_board_with(&board, &rules::rules_or_default(rules))
+        .map_err(errors::invalid_board)?;
+    Ok(board_to_py(&board))
+}
+
+/// Check a move against the reference model of the rules.
+///
+/// The reference is a slow, straightforward implementation kept apart from
+/// the engine, for testing the engine or another implementation.
+///
+/// Args:
+///     board: 4x4 board before the move.
+///     direction: ``Direction`` of the move.
+///     expected: Board claimed to follow.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     bool: Whether ``expected`` is the reference slide plus, if the move
+///     changed the board, one tile that can spawn on a cell left empty.
+#[pyfunction]
+#[pyo3(signature = (board, direction, expected, rules=None))]
+pub fn verify_step(
+    board: crate::Board,
+    direction: &Bound<'_, PyAny>,
+    expected: crate::Board,
+    rules: Option<&rules::Rules>,
+) -> PyResult<bool> {
+    let dir = parse_direction(direction)?;
+    let rules = rules::rules_or_default(rules);
+    Ok(crate::reference::verify_step(&board, dir, &expected, &rules).is_ok())
+}
+
+/// The exact spawn model: probability of each tile that can spawn.
+///
+/// Args:
+///     board: Board the tile spawns on; only the mercy rule (no ×1 into
+///            the last empty cell) and the adaptive difficulty (by its
+///            largest tile) depend on it. Omitted: an empty board.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     dict[int, float]: ``{2: ..., 4: ..., -1: ..., -2: ...}`` summing to 1,
+///     or ``{}`` for a full board.
+#[pyfunction]
+#[pyo3(signature = (board=None, rules=None))]
+pub fn spawn_probabilities<'py>(
+    py: Python<'py>,
+    board: Option<crate::Board>,
+    rules: Option<&rules::Rules>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let rules = rules::rules_or_default(rules);
+    let board = board.unwrap_or_default();
+    crate::validate_board_with(&board, &rules).map_err(errors::invalid_board)?;
+    let odds = PyDict::new(py);
+    if board.iter().flatten().any(|&t| t == 0) {
+        for (tile, chance) in crate::spawn_probabilities(&rules, &board) {
+            odds.set_item(tile, chance)?;
+        }
+    }
+    Ok(odds)
+}
+
+/// Expected face value of the next spawn on ``board``.
+///
+/// Number tiles are weighted by their probability; multipliers count 0, as
+/// they add nothing until they merge. ``0.0`` for a full board.
+#[pyfunction]
+#[pyo3(signature = (board, rules=None))]
+pub fn expected_spawn_value(board: crate::Board, rules: Option<&rules::Rules>) -> PyResult<f64> {
+    let rules = rules::rules_or_default(rules);
+    crate::validate_board_with(&board, &rules).map_err(errors::invalid_board)?;
+    Ok(crate::expected_spawn_value(&board, &rules))
+}
+
+/// Upper bound on the score the tiles of ``board`` can still merge.
+///
+/// Positions are ignored, so any two tiles may meet: no sequence of moves
+/// merges more before the next spawn, which makes the bound safe for
+/// pruning in solvers. Blockers and bombs never merge.
+///
+/// Raises:
+///     InvalidBoardError: For an invalid board.
+#[pyfunction]
+#[pyo3(signature = (board, rules=None))]
+pub fn score_upper_bound(
+    py: Python<'_>,
+    board: &Bound<'_, PyAny>,
+    rules: Option<&rules::Rules>,
+) -> PyResult<i64> {
+    let rules = rules::rules_or_default(rules);
+    let board = errors::checked_board(board, &rules)?;
+    py.detach(|| crate::bound::score_upper_bound(&board, &rules))
+        .map_err(pyo3::exceptions::PyValueError::new_err)
+}
+
+/// Check whether a claimed score fits the tiles on ``board``, to vet saves
+/// and leaderboard submissions that come without a replay.
+///
+/// Every tile carries the score of the merges that built it (nothing when
+/// spawned, less than nothing after multiplier merges), and only bombs
+/// take tiles off the board unmerged, so a game's score lies between the
+/// least and the most its tiles can carry. A problem proves the claim
+/// false; a consistent score is not proof the position was played.
+///
+/// Returns:
+///     dict: ``consistent``, ``score``, ``min_score`` and ``max_score``
+///         (``None`` where the rules set no bound, as when ``×1`` merges let
+///         a tile score again), ``step`` (every score is a multiple of it)
+///         and ``problems``, one sentence each.
+///
+/// Raises:
+///     InvalidBoardError: For an invalid board.
+#[pyfunction]
+#[pyo3(signature = (board, score, rules=None))]
+pub fn check_consistency<'py>(
+    py: Python<'py>,
+    board: &Bound<'py, PyAny>,
+    score: i64,
+    rules: Option<&rules::Rules>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let rules = rules::rules_or_default(rules);
+    let board = errors::checked_board(board, &rules)?;
+    let report = crate::consistency::check_consistency(&board, score, &rules)
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let out = PyDict::new(py);
+    out.set_item("consistent", report.is_consistent())?;
+    out.set_item("score", report.score)?;
+    out.set_item("min_score", report.min_score)?;
+    out.set_item("max_score", report.max_score)?;
+    out.set_item("step", report.step)?;
+    out.set_item("problems", report.problems)?;
+    Ok(out)
+}
+
+/// A ``Direction``, its action id (Down=0, Right=1, Up=2, Left=3) or its
+/// name in any case (``"up"``).
+pub(crate) fn parse_direction(obj: &Bound<'_, PyAny>) -> PyResult<crate::Direction> {
+    if let Ok(id) = obj.extract::<i64>() {
+        return usize::try_from(id)
+            .ok()
+            .and_then(|i| crate::ALL_DIRECTIONS.get(i).copied())
+            .ok_or_else(|| {
+                errors::invalid_direction(format!("action id must be between 0 and 3, got: {id}"))
+            });
+    }
+    let name: String = obj
+        .extract()
+        .or_else(|_| obj.getattr("name").and_then(|name| name.extract()))
+        .map_err(|_| {
+            errors::invalid_direction(format!(
+                "direction must be a Direction, an action id or a name, got: {obj}"
+            ))
+        })?;
+    match name.to_ascii_lowercase().as_str() {
+        "down" => Ok(crate::Direction::Down),
+        "right" => Ok(crate::Direction::Right),
+        "up" => Ok(crate::Direction::Up),
+        "left" => Ok(crate::Direction::Left),
+        _ => Err(errors::invalid_direction(format!(
+            "unknown direction: {name}"
+        ))),
+    }
+}
+
+/// Wrap native-endian ``data`` as a writable NumPy array.
+pub(crate) fn ndarray<'py>(
+    py: Python<'py>,
+    data: &[u8],
+    dtype: &str,
+    shape: &[usize],
+) -> PyResult<Bound<'py, PyAny>> {
+    let numpy = PyModule::import(py, "numpy")?;
+    numpy
+        .call_method1("frombuffer", (PyByteArray::new(py, data), dtype))?
+        .call_method1("reshape", (shape.to_vec(),))
+}
+
+pub(crate) fn state_to_py(py: Python<'_>, state: crate::State) -> PyResult<Py<PyAny>> {
+    let pkg = PyModule::import(py, "akioi_2048")?;
+    let cls = pkg.getattr("State")?;
+    let variant = match state {
+        crate::State::Victory => "Victory",
+        crate::State::GameOver => "GameOver",
+        crate::State::Continue => "Continue",
+    };
+    Ok(cls.getattr(variant)?.unbind())
+}
+
+/// The ``StepResult`` named tuple of one move
+pub(crate) fn step_result_to_py(
+    py: Python<'_>,
+    board: &crate::Board,
+    delta: i32,
+    state: crate::State,
+) -> PyResult<Py<PyAny>> {
+    let pkg = PyModule::import(py, "akioi_2048")?;
+    let cls = pkg.getattr("StepResult")?;
+    Ok(cls
+        .call1((board_to_py(board), delta, state_to_py(py, state)?))?
+        .unbind())
+}
+
+pub(crate) fn direction_to_py(py: Python<'_>, dir: crate::Direction) -> PyResult<Py<PyAny>> {
+    let pkg = PyModule::import(py, "akioi_2048")?;
+    let cls = pkg.getattr("Direction")?;
+    let variant = match dir {
+        crate::Direction::Down => "Down",
+        crate::Direction::Right => "Right",
+        crate::Direction::Up => "Up",
+        crate::Direction::Left => "Left",
+    };
+    Ok(cls.getattr(variant)?.unbind())
+}
+
+pub(crate) fn parse_state(py_state: &Bound<'_, PyAny>) -> PyResult<crate::State> {
+    let name: String = py_state.getattr("name")?.extract()?;
+    match name.as_str() {
+        "Victory" => Ok(crate::State::Victory),
+        "GameOver" => Ok(crate::State::GameOver),
+        "Continue" => Ok(crate::State::Continue),
+        _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "state must be a State enum, got: {name}"
+        ))),
+    }
+}
+
+pub(crate) fn board_to_py(board: &crate::Board) -> Vec<Vec<i32>> {
+    board.iter().map(|r| r.to_vec()).collect()
+}
+
+/// Python module for the akioi 2048 engine.
+///
+/// Exposes:
+/// - init(blockers=0, rules=None) -> list[list[int]], BLOCKER = -3, BOMB = -5
+/// - step(board, direction, rules=None, strict=True) -> tuple[new_board, delta, State]
+/// - validate(board, strict=True, rules=None)
+/// - board_with(tiles, rules=None) -> list[list[int]]
+/// - random_board(max_tile=1024, fill=0.5, include_multipliers=True, ...) -> list[list[int]]
+/// - verify_step(board, direction, expected, rules=None) -> bool
+/// - spawn_probabilities(board=None, rules=None) -> dict, expected_spawn_value(board, rules=None)
+/// - score_upper_bound(board, rules=None) -> int
+/// - check_consistency(board, score, rules=None) -> dict
+/// - Rules
+/// - step_detailed(board, direction, rules=None) -> MoveResult
+/// - step_with_animation(board, direction, rules=None) -> (MoveResult, list[TileMove])
+/// - infer_move(before, after, rules=None) -> (Direction, Spawn) | None
+/// - diff_web_log(log, rules=None) -> dict | None
+/// - Game, StateToken, MoveRecord, verify_chain(records, seed=None, rules=None) -> bool
+/// - record(path, seed=None, rules=None, ...) -> Recording, for ``with record(path) as game:``
+/// - sign_replay(replay, key) -> bytes, verify_replay(replay, signature, key) -> bool
+/// - TrajectoryRecorder, collect_dataset(policy, n_games, out_path, ...)
+/// - ReplayBuffer(capacity, prioritized=False, alpha=0.6, seed=None)
+/// - report_card(agent, seed=0, ...) -> dict
+/// - tournament(agents, games_per_agent=100, seed=0, rules=None, ...) -> dict
+/// - survival_probability(board, n_moves, samples=1000, policy=None, ...) -> dict
+/// - GameStats(bin_width=1024, heatmaps=False, branching=False)
+/// - Replay, export_gif(replay, path, fps=4, theme="light")
+/// - engine_stamp(rules=None) -> str, check_stamp(stamp, rules=None, force=False) -> list[str]
+/// - Leaderboard(path), LeaderboardEntry
+/// - run_cli(args=None), benchmark(seconds=0.5) -> list[dict]
+/// - render(board, style="unicode") -> str, render_image(board, path, theme="light")
+/// - parse_notation(text) -> list[list[int]], to_notation(board) -> str
+/// - BoardBuilder(board=None, rules=None)
+/// - Arena(n_envs, seed=None, rules=None, auto_reset=True, threads=None, heatmaps=False, branching=False)
+/// - step_batch(boards, actions, seed=None, rules=None, threads=None, step=0) -> list[StepResult]
+/// - evaluate_batch(boards, rules=None, threads=None) -> numpy.ndarray
+/// - Arena2P(seed=None, rules=None, attack_tile=-1, ...), VersusStep
+/// - encode(board, scheme="onehot", rules=None) -> numpy.ndarray
+/// - pack(board, size=16) -> bytes, unpack(data) -> list[list[int]]
+/// - board_key(board) -> int, board_from_key(key) -> list[list[int]]
+/// - Env(seed=None, rules=None, reward=None, ...)
+/// - Status, status(board, rules=None) -> Status
+/// - is_game_over(board, rules=None) -> bool, is_won(board, rules=None) -> bool
+/// - Puzzle(board, max_moves, tile=None, ...), PuzzleResult
+/// - Tablebase, TablebaseEntry, tablebase_lookup(board, table, moves_left=None)
+/// - enumerate_successors(board, depth=1, ...), count_reachable_states(board, depth, ...)
+/// - hint(board, depth=3, rules=None, time_budget=None, threads=None, book=None) -> (Direction, dict) | None
+/// - OpeningBook.build(max_sum=16, depth=2, rules=None, ...)
+/// - evaluate_moves(board, depth=2, rules=None) -> dict
+/// - worst_case_moves(board, depth=3, rules=None) -> dict
+/// - tune_heuristics(n_games, generations, method="cma-es", ...) -> dict
+/// - NTupleNetwork, train(n_games, lr=0.1, lambda_=0.5, ...) -> dict
+/// - OnnxPolicy(path, scheme="onehot", rules=None)
+/// - slide_row(row, rules=None) -> (list[int], int)
+/// - merge_preview(a, b, adjacent=True, below=[], rules=None) -> (int, int) | None
+/// - rules_spec(rules=None, invalid_moves="noop") -> dict
+/// - CancelToken, for the ``cancel=`` of long calls
+/// - InvalidBoardError, InvalidDirectionError, GameFinishedError (ValueErrors),
+///   CancelledError
+#[pymodule]
+fn akioi_2048(_py: Python, module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(step, module)?)?;
+    module.add_function(wrap_pyfunction!(init, module)?)?;
+    module.add("BLOCKER", crate::BLOCKER)?;
+    module.add("BOMB", crate::BOMB)?;
+    let py = module.py();
+    module.add(
+        "InvalidBoardError",
+        py.get_type::<errors::InvalidBoardError>(),
+    )?;
+    module.add(
+        "InvalidDirectionError",
+        py.get_type::<errors::InvalidDirectionError>(),
+    )?;
+    module.add(
+        "GameFinishedError",
+        py.get_type::<errors::GameFinishedError>(),
+    )?;
+    module.add("CancelledError", py.get_type::<errors::CancelledError>())?;
+    module.add_class::<progress::CancelToken>()?;
+    module.add_function(wrap_pyfunction!(validate, module)?)?;
+    module.add_function(wrap_pyfunction!(line::slide_row, module)?)?;
+    module.add_function(wrap_pyfunction!(line::merge_preview, module)?)?;
+    module.add_function(wrap_pyfunction!(spec::rules_spec, module)?)?;
+    module.add_function(wrap_pyfunction!(board_with, module)?)?;
+    module.add_function(wrap_pyfunction!(curriculum::random_board, module)?)?;
+    module.add_function(wrap_pyfunction!(verify_step, module)?)?;
+    module.add_function(wrap_pyfunction!(spawn_probabilities, module)?)?;
+    module.add_function(wrap_pyfunction!(expected_spawn_value, module)?)?;
+    module.add_function(wrap_pyfunction!(score_upper_bound, module)?)?;
+    module.add_function(wrap_pyfunction!(check_consistency, module)?)?;
+    module.add_class::<rules::Rules>()?;
+    module.add_function(wrap_pyfunction!(detail::step_detailed, module)?)?;
+    module.add_class::<detail::MoveResult>()?;
+    module.add_class::<detail::Merge>()?;
+    module.add_class::<detail::Spawn>()?;
+    module.add_function(wrap_pyfunction!(detail::step_with_animation, module)?)?;
+    module.add_class::<detail::TileMove>()?;
+    module.add_function(wrap_pyfunction!(detail::infer_move, module)?)?;
+    module.add_class::<session::Game>()?;
+    module.add_class::<session::StateToken>()?;
+    module.add_function(wrap_pyfunction!(session::record, module)?)?;
+    module.add_class::<session::Recording>()?;
+    module.add_class::<proof::MoveRecord>()?;
+    module.add_function(wrap_pyfunction!(proof::verify_chain, module)?)?;
+    module.add_function(wrap_pyfunction!(proof::sign_replay, module)?)?;
+    module.add_function(wrap_pyfunction!(proof::verify_replay, module)?)?;
+    module.add_class::<trajectory::TrajectoryRecorder>()?;
+    module.add_function(wrap_pyfunction!(dataset::collect_dataset, module)?)?;
+    module.add_class::<buffer::ReplayBuffer>()?;
+    module.add_function(wrap_pyfunction!(report::report_card, module)?)?;
+    module.add_function(wrap_pyfunction!(tournament::tournament, module)?)?;
+    module.add_function(wrap_pyfunction!(survival::survival_probability, module)?)?;
+    module.add_class::<stats::GameStats>()?;
+    module.add_function(wrap_pyfunction!(cli::run_cli, module)?)?;
+    module.add_function(wrap_pyfunction!(bench::benchmark, module)?)?;
+    module.add_function(wrap_pyfunction!(render::render, module)?)?;
+    module.add_function(wrap_pyfunction!(image::render_image, module)?)?;
+    module.add_function(wrap_pyfunction!(notation::parse_notation, module)?)?;
+    module.add_function(wrap_pyfunction!(notation::to_notation, module)?)?;
+    module.add_function(wrap_pyfunction!(differential::diff_web_log, module)?)?;
+    module.add_class::<editor::BoardBuilder>()?;
+    module.add_class::<replay::Replay>()?;
+    module.add_function(wrap_pyfunction!(replay::export_gif, module)?)?;
+    module.add_function(wrap_pyfunction!(compat::engine_stamp, module)?)?;
+    module.add_function(wrap_pyfunction!(compat::check_stamp, module)?)?;
+    module.add_class::<leaderboard::Leaderboard>()?;
+    module.add_class::<leaderboard::LeaderboardEntry>()?;
+    module.add_class::<arena::Arena>()?;
+    module.add_function(wrap_pyfunction!(arena::step_batch, module)?)?;
+    module.add_function(wrap_pyfunction!(arena::evaluate_batch, module)?)?;
+    module.add_class::<versus::Arena2P>()?;
+    module.add_class::<versus::VersusStep>()?;
+    module.add_function(wrap_pyfunction!(encode::encode, module)?)?;
+    module.add_function(wrap_pyfunction!(pack::pack, module)?)?;
+    module.add_function(wrap_pyfunction!(pack::unpack, module)?)?;
+    module.add_function(wrap_pyfunction!(pack::board_key, module)?)?;
+    module.add_function(wrap_pyfunction!(pack::board_from_key, module)?)?;
+    module.add_class::<env::Env>()?;
+    module.add_class::<status::Status>()?;
+    module.add_function(wrap_pyfunction!(status::status, module)?)?;
+    module.add_function(wrap_pyfunction!(status::is_game_over, module)?)?;
+    module.add_function(wrap_pyfunction!(status::is_won, module)?)?;
+    module.add_class::<puzzle::Puzzle>()?;
+    module.add_class::<puzzle::PuzzleResult>()?;
+    module.add_class::<tablebase::Tablebase>()?;
+    module.add_class::<tablebase::TablebaseEntry>()?;
+    module.add_function(wrap_pyfunction!(tablebase::tablebase_lookup, module)?)?;
+    module.add_function(wrap_pyfunction!(reach::enumerate_successors, module)?)?;
+    module.add_function(wrap_pyfunction!(reach::count_reachable_states, module)?)?;
+    module.add_function(wrap_pyfunction!(hint::hint, module)?)?;
+    module.add_class::<book::OpeningBook>()?;
+    module.add_function(wrap_pyfunction!(hint::evaluate_moves, module)?)?;
+    module.add_function(wrap_pyfunction!(hint::worst_case_moves, module)?)?;
+    module.add_function(wrap_pyfunction!(tune::tune_heuristics, module)?)?;
+    module.add_class::<ntuple::NTupleNetwork>()?;
+    module.add_function(wrap_pyfunction!(ntuple::train, module)?)?;
+    module.add_class::<onnx::OnnxPolicy>()?;
+    Ok(())
+}
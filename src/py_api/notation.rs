@@ -0,0 +1,32 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::notation;
+
+/// Read a board from its one-line notation.
+///
+/// Rows run top to bottom, split by ``/``, each with its four cells: ``.``
+/// for an empty cell, a number for a number tile, ``x`` and a factor for a
+/// multiplier (``x2``), ``#`` for a blocker and ``*`` for a bomb. A ``,``
+/// or a space separates two numbers, so ``"2.4./..../.x2../8,8.#"`` is a
+/// board whose bottom row is ``[8, 8, 0, BLOCKER]``.
+///
+/// Args:
+///     text: The notation; tiles are read as written, without validation.
+///
+/// Returns:
+///     list[list[int]]: The board.
+///
+/// Raises:
+///     ValueError: For text that is not a board notation.
+#[pyfunction]
+pub fn parse_notation(text: &str) -> PyResult<[[i32; 4]; 4]> {
+    notation::parse_notation(text).map_err(PyValueError::new_err)
+}
+
+/// Write a board in the notation ``parse_notation`` reads, e.g.
+/// ``"2.4./..../.x2../8,8.#"``.
+#[pyfunction]
+pub fn to_notation(board: [[i32; 4]; 4]) -> String {
+    notation::to_notation(&board)
+}
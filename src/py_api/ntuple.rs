@@ -0,0 +1,231 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::errors::checked_board;
+use super::progress::{CancelToken, Watch};
+use super::rules::{Rules, rules_or_default};
+use super::{direction_to_py, replay::invalid_data};
+use crate::Engine;
+use crate::ntuple::NTuple;
+use crate::train::{Evaluation, TrainOptions};
+
+/// A 4-tuple network valuing boards, trained by ``train``.
+///
+/// Four 4-cell patterns (the outer and inner rows, the corner and edge 2x2
+/// squares) are read under the 8 symmetries of the board; a board's value
+/// is the sum of the 32 weights they select. A fresh network values every
+/// board at 0.
+#[pyclass(module = "akioi_2048", frozen)]
+#[derive(Clone)]
+pub struct NTupleNetwork {
+    inner: Arc<NTuple>,
+}
+
+#[pymethods]
+impl NTupleNetwork {
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: Arc::new(NTuple::new()),
+        }
+    }
+
+    /// Value of ``board``: the score the network expects from it on.
+    ///
+    /// Raises:
+    ///     InvalidBoardError: For an invalid board.
+    #[pyo3(signature = (board, rules=None))]
+    fn value(&self, board: &Bound<'_, PyAny>, rules: Option<&Rules>) -> PyResult<f64> {
+        let board = checked_board(board, &rules_or_default(rules))?;
+        Ok(self.inner.value(&board))
+    }
+
+    /// The move worth the most score gained plus value of the board it
+    /// slides to, or ``None`` without legal moves.
+    ///
+    /// ``network.best_move`` is an agent for ``tournament`` and the like.
+    ///
+    /// Raises:
+    ///     InvalidBoardError: For an invalid board.
+    #[pyo3(signature = (board, rules=None))]
+    fn best_move(
+        &self,
+        py: Python<'_>,
+        board: &Bound<'_, PyAny>,
+        rules: Option<&Rules>,
+    ) -> PyResult<Option<Py<PyAny>>> {
+        let rules = rules_or_default(rules);
+        let board = checked_board(board, &rules)?;
+        let engine = Engine::new(rules).map_err(PyValueError::new_err)?;
+        self.inner
+            .best_move(&engine, &board)
+            .map(|(dir, _, _)| direction_to_py(py, dir))
+            .transpose()
+    }
+
+    /// Write the network to ``path``, with a format version and checksum.
+    fn save(&self, py: Python<'_>, path: PathBuf) -> PyResult<()> {
+        py.detach(|| self.inner.save(&path))?;
+        Ok(())
+    }
+
+    /// Load a network written by ``save``.
+    ///
+    /// Raises:
+    ///     OSError: If the file cannot be read.
+    ///     ValueError: If it is not a network or fails its checksum.
+    #[staticmethod]
+    fn load(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        let inner = py.detach(|| NTuple::load(&path)).map_err(invalid_data)?;
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Number of weights.
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("NTupleNetwork(weights={})", self.inner.len())
+    }
+}
+
+fn evaluation_to_py<'py>(py: Python<'py>, e: &Evaluation) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new(py);
+    d.set_item("games", e.games)?;
+    d.set_item("mean_score", e.mean_score)?;
+    d.set_item("max_tile", e.max_tile)?;
+    d.set_item("win_rate", e.win_rate)?;
+    Ok(d)
+}
+
+/// Train an ``NTupleNetwork`` by TD(lambda) on afterstates, natively.
+///
+/// Every game is played greedily by the network being trained. After each
+/// move the value of the previous afterstate (the board a move slides to,
+/// before the spawn) moves toward the score gained plus the value of the
+/// next one; with ``lambda_ > 0`` the error also reaches earlier
+/// afterstates, decaying by ``lambda_`` per move. Evaluation matches play
+/// fixed seeds greedily without learning, so their scores make a learning
+/// curve comparable across runs. The loop runs without the GIL.
+///
+/// Args:
+///     n_games: Training games.
+///     lr: How far each update moves a value toward its target, as a share
+///         of the error.
+///     lambda_: Trace decay, from 0 (only the last afterstate) to 1.
+///     seed: Seed of the training games.
+///     rules: Optional ``Rules``.
+///     eval_every: Evaluate after every this many games, and after the
+///                 last; 0 evaluates after the last only.
+///     eval_games: Games per evaluation match; 0 skips evaluation.
+///     max_moves: Stop each game after this many moves.
+///     network: Keep training a copy of this network instead of a fresh one.
+///     callback: Called with each evaluation's dict as it is played.
+///     progress: Called as ``progress(done, total)`` with the training games
+///               played so far.
+///     cancel: A ``CancelToken`` that stops training.
+///
+/// Ctrl-C stops training too, raising ``KeyboardInterrupt``.
+///
+/// Returns:
+///     dict: ``network`` (the trained ``NTupleNetwork``), ``scores`` (the
+///     score of every training game) and ``evaluations`` (dicts of the
+///     training ``games`` played before each match, its ``mean_score``,
+///     ``max_tile`` and ``win_rate``).
+///
+/// Raises:
+///     ValueError: For a learning rate that is not positive or a
+///                 ``lambda_`` outside ``[0, 1]``.
+///     CancelledError: If ``cancel`` was cancelled.
+#[pyfunction]
+#[pyo3(signature = (
+    n_games,
+    lr=0.1,
+    lambda_=0.5,
+    seed=0,
+    rules=None,
+    eval_every=0,
+    eval_games=10,
+    max_moves=None,
+    network=None,
+    callback=None,
+    progress=None,
+    cancel=None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn train<'py>(
+    py: Python<'py>,
+    n_games: usize,
+    lr: f64,
+    lambda_: f64,
+    seed: u64,
+    rules: Option<&Rules>,
+    eval_every: usize,
+    eval_games: usize,
+    max_moves: Option<usize>,
+    network: Option<&NTupleNetwork>,
+    callback: Option<Bound<'py, PyAny>>,
+    progress: Option<&Bound<'py, PyAny>>,
+    cancel: Option<&CancelToken>,
+) -> PyResult<Bound<'py, PyDict>> {
+    if let Some(callback) = &callback
+        && !callback.is_callable()
+    {
+        return Err(PyValueError::new_err("callback must be callable"));
+    }
+    let options = TrainOptions {
+        rules: rules_or_default(rules),
+        learning_rate: lr,
+        lambda: lambda_,
+        seed,
+        eval_every,
+        eval_games,
+        max_moves,
+    };
+    let mut net = network.map_or_else(NTuple::new, |n| NTuple::clone(&n.inner));
+    let callback = callback.map(Bound::unbind);
+    let failed = Mutex::new(None);
+    let trained = Watch::new(progress, cancel)?.run(py, |progress| {
+        crate::train::train(&mut net, n_games, &options, progress, |evaluation| {
+            let Some(callback) = &callback else {
+                return Ok(());
+            };
+            Python::attach(|py| {
+                callback.call1(py, (evaluation_to_py(py, evaluation)?,))?;
+                Ok(())
+            })
+            .map_err(|e: PyErr| {
+                let message = e.to_string();
+                *failed.lock().unwrap_or_else(PoisonError::into_inner) = Some(e);
+                message
+            })
+        })
+    })?;
+    if let Some(e) = failed.into_inner().unwrap_or_else(PoisonError::into_inner) {
+        return Err(e);
+    }
+    let trained = trained.map_err(PyValueError::new_err)?;
+
+    let evaluations = trained
+        .evaluations
+        .iter()
+        .map(|e| evaluation_to_py(py, e))
+        .collect::<PyResult<Vec<_>>>()?;
+    let d = PyDict::new(py);
+    d.set_item(
+        "network",
+        NTupleNetwork {
+            inner: Arc::new(net),
+        },
+    )?;
+    d.set_item("scores", trained.scores)?;
+    d.set_item("evaluations", evaluations)?;
+    Ok(d)
+}
@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::direction_to_py;
+use super::errors::checked_board;
+use super::rules::{Rules, rules_or_default};
+use crate::encode::Scheme;
+use crate::onnx::{Model, NeuralPolicy};
+
+/// A neural network policy loaded from an ONNX file, run natively.
+///
+/// The model takes one board as ``encode(board, scheme, rules)`` gives it,
+/// in the shape its input declares (flat or ``(1, channels, 4, 4)``), and
+/// its first four outputs score Down, Right, Up and Left. It plays the
+/// legal move scored highest.
+///
+/// Pass it as an agent to ``tournament``, ``report_card``,
+/// ``collect_dataset`` or ``survival_probability`` and every move is chosen
+/// in Rust, without the GIL. It is also a callable ``policy(board)`` for
+/// any other code.
+///
+/// Only feed-forward models are supported: ``Gemm``, ``MatMul``, ``Add``,
+/// ``Sub``, ``Mul``, ``Div``, ``Relu``, ``LeakyRelu``, ``Tanh``,
+/// ``Sigmoid``, ``Softmax``, ``LogSoftmax``, ``Flatten``, ``Reshape``,
+/// ``Constant``, ``Identity`` and ``Dropout``.
+///
+/// Args:
+///     path: The ``.onnx`` file.
+///     scheme: The ``encode`` scheme the model was trained on.
+///     rules: Optional ``Rules``.
+///
+/// Raises:
+///     ValueError: For an unreadable or unsupported model, an unknown
+///                 scheme, or a model whose input or output does not fit.
+#[pyclass(module = "akioi_2048", frozen)]
+#[derive(Clone)]
+pub struct OnnxPolicy {
+    pub(crate) inner: Arc<NeuralPolicy>,
+    rules: crate::Rules,
+    path: PathBuf,
+}
+
+#[pymethods]
+impl OnnxPolicy {
+    #[new]
+    #[pyo3(signature = (path, scheme="onehot", rules=None))]
+    fn new(py: Python<'_>, path: PathBuf, scheme: &str, rules: Option<&Rules>) -> PyResult<Self> {
+        let scheme = Scheme::from_name(scheme)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown encoding scheme: {scheme}")))?;
+        let rules = rules_or_default(rules);
+        let inner = py
+            .detach(|| {
+                let policy = NeuralPolicy::new(Model::load(&path)?, scheme, rules)?;
+                // shapes are fixed, so one board checks them for every board
+                policy.scores(&[[0; 4]; 4])?;
+                Ok::<_, String>(policy)
+            })
+            .map_err(PyValueError::new_err)?;
+        Ok(Self {
+            inner: Arc::new(inner),
+            rules,
+            path,
+        })
+    }
+
+    /// The model's score of each direction, in action-id order.
+    ///
+    /// Raises:
+    ///     InvalidBoardError: For an invalid board.
+    fn scores(&self, board: &Bound<'_, PyAny>) -> PyResult<Vec<f32>> {
+        let board = checked_board(board, &self.rules)?;
+        let scores = self.inner.scores(&board).map_err(PyValueError::new_err)?;
+        Ok(scores.to_vec())
+    }
+
+    /// The legal move scored highest, or ``None`` without legal moves.
+    ///
+    /// Raises:
+    ///     InvalidBoardError: For an invalid board.
+    fn choose(&self, py: Python<'_>, board: &Bound<'_, PyAny>) -> PyResult<Option<Py<PyAny>>> {
+        let board = checked_board(board, &self.rules)?;
+        self.inner
+            .choose(&board)
+            .map_err(PyValueError::new_err)?
+            .map(|dir| direction_to_py(py, dir))
+            .transpose()
+    }
+
+    fn __call__(&self, py: Python<'_>, board: &Bound<'_, PyAny>) -> PyResult<Option<Py<PyAny>>> {
+        self.choose(py, board)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("OnnxPolicy({:?})", self.path.display().to_string())
+    }
+}
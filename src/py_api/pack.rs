@@ -0,0 +1,58 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use super::board_to_py;
+use crate::Board;
+
+/// Pack a board into compact bytes, one byte or nibble per cell.
+///
+/// ``size=16`` holds every power-of-two tile and multiplier; ``size=8``
+/// holds numbers up to 4096 and the multipliers ``-1``, ``-2`` and ``-4``.
+/// Packed boards of one size concatenate into fixed-width records.
+///
+/// Args:
+///     board: 4x4 board.
+///     size: Bytes per board, ``8`` or ``16``.
+///
+/// Returns:
+///     bytes: The packed board.
+///
+/// Raises:
+///     ValueError: For another size or a tile the size cannot hold.
+#[pyfunction]
+#[pyo3(signature = (board, size=16))]
+pub fn pack(board: Board, size: usize) -> PyResult<Vec<u8>> {
+    crate::pack::pack(&board, size).map_err(PyValueError::new_err)
+}
+
+/// The 8-byte ``pack`` of a board as one integer, to key caches and
+/// databases on a single 64-bit number; the first cell is the top nibble.
+/// Only boards of numbers up to 4096 and multipliers up to ``-4`` have a
+/// key; others need the 16-byte ``pack``.
+///
+/// Raises:
+///     ValueError: Naming the first tile 8 bytes cannot hold: numbers
+///         above 4096, multipliers beyond ``-4``, blockers and bombs.
+#[pyfunction]
+pub fn board_key(board: Board) -> PyResult<u64> {
+    crate::pack::board_key(&board).map_err(PyValueError::new_err)
+}
+
+/// Inverse of ``board_key``: the board of a 64-bit key.
+#[pyfunction]
+pub fn board_from_key(key: u64) -> Vec<Vec<i32>> {
+    board_to_py(&crate::pack::board_from_key(key))
+}
+
+/// Unpack a board from the bytes ``pack`` made; the size follows from the
+/// length.
+///
+/// Raises:
+///     ValueError: For a length other than 8 or 16, or a byte no tile
+///         packs to.
+#[pyfunction]
+pub fn unpack(data: &[u8]) -> PyResult<Vec<Vec<i32>>> {
+    crate::pack::unpack(data)
+        .map(|board| board_to_py(&board))
+        .map_err(PyValueError::new_err)
+}
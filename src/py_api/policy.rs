@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+
+use super::onnx::OnnxPolicy;
+use super::{board_to_py, parse_direction};
+use crate::baseline::Baseline;
+use crate::onnx::NeuralPolicy;
+use crate::{ALL_DIRECTIONS, Board, Direction, Engine};
+
+/// A built-in policy's move chooser, free to run on any thread
+pub(crate) type NativePolicy = Box<dyn FnMut(&Board) -> Result<Direction, String> + Send>;
+
+/// A policy argument: a Python callable ``policy(board) -> Direction``, the
+/// name of a built-in policy, or an ``OnnxPolicy``.
+pub(crate) enum Policy<'py> {
+    Random,
+    Baseline(Baseline),
+    Model(Arc<NeuralPolicy>),
+    Callable(Bound<'py, PyAny>),
+}
+
+/// The move `policy` chooses; it is only asked on boards with legal moves
+fn model_move(policy: &NeuralPolicy, board: &Board) -> Result<Direction, String> {
+    Ok(policy.choose(board)?.unwrap_or(Direction::Down))
+}
+
+/// The move `baseline` plays under `engine`, as [`model_move`]
+fn baseline_move(baseline: Baseline, engine: &Engine, board: &Board) -> Direction {
+    baseline.choose(engine, board).unwrap_or(Direction::Down)
+}
+
+impl<'py> Policy<'py> {
+    pub(crate) fn from_py(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(name) = obj.extract::<String>() {
+            if name == "random" {
+                return Ok(Policy::Random);
+            }
+            return Baseline::from_name(&name)
+                .map(Policy::Baseline)
+                .ok_or_else(|| PyValueError::new_err(format!("unknown policy: {name}")));
+        }
+        if let Ok(model) = obj.cast::<OnnxPolicy>() {
+            return Ok(Policy::Model(Arc::clone(&model.get().inner)));
+        }
+        if !obj.is_callable() {
+            return Err(PyValueError::new_err(
+                "policy must be a callable or a built-in policy name",
+            ));
+        }
+        Ok(Policy::Callable(obj.clone()))
+    }
+
+    /// A built-in policy as a move chooser that can run on any thread,
+    /// playing under `engine`, or `None` for a callable.
+    pub(crate) fn native(&self, engine: Engine) -> Option<NativePolicy> {
+        match self {
+            Policy::Random => {
+                let mut rng = StdRng::from_rng(&mut rand::rng());
+                Some(Box::new(move |_| {
+                    Ok(*ALL_DIRECTIONS
+                        .choose(&mut rng)
+                        .expect("ALL_DIRECTIONS is non-empty"))
+                }))
+            }
+            &Policy::Baseline(baseline) => Some(Box::new(move |board| {
+                Ok(baseline_move(baseline, &engine, board))
+            })),
+            Policy::Model(model) => {
+                let model = Arc::clone(model);
+                Some(Box::new(move |board| model_move(&model, board)))
+            }
+            Policy::Callable(_) => None,
+        }
+    }
+
+    /// Run `f` with this policy as a move chooser playing under `engine`.
+    ///
+    /// Built-in policies never touch Python objects, so the GIL is released
+    /// for the whole run; callables keep it, since every move calls back.
+    pub(crate) fn run<T: Send>(
+        &self,
+        py: Python<'_>,
+        engine: Engine,
+        f: impl FnOnce(&mut dyn FnMut(&Board) -> PyResult<Direction>) -> T + Send,
+    ) -> T {
+        match self {
+            Policy::Random => py.detach(|| {
+                let mut rng = rand::rng();
+                f(&mut |_| {
+                    Ok(*ALL_DIRECTIONS
+                        .choose(&mut rng)
+                        .expect("ALL_DIRECTIONS is non-empty"))
+                })
+            }),
+            &Policy::Baseline(baseline) => {
+                py.detach(|| f(&mut |board| Ok(baseline_move(baseline, &engine, board))))
+            }
+            Policy::Model(model) => py
+                .detach(|| f(&mut |board| model_move(model, board).map_err(PyValueError::new_err))),
+            Policy::Callable(obj) => {
+                f(&mut |board| parse_direction(&obj.call1((board_to_py(board),))?))
+            }
+        }
+    }
+}
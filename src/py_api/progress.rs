@@ -0,0 +1,172 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::errors::CancelledError;
+use crate::progress::Progress;
+
+/// How often a watched call reports progress and checks for Ctrl-C
+const POLL: Duration = Duration::from_millis(50);
+
+/// Stops a long native call it is passed to as ``cancel=``.
+///
+/// ``cancel()`` may be called from any thread, or from a progress
+/// callback; the call then raises ``CancelledError``. A token stays
+/// cancelled, so it stops every later call it is passed to as well.
+#[pyclass(module = "akioi_2048", frozen)]
+#[derive(Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl CancelToken {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[getter]
+    fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CancelToken(cancelled={})", self.cancelled())
+    }
+}
+
+/// Progress of one native call, reported to a Python callback, stopped by
+/// a ``CancelToken`` or Ctrl-C
+pub(crate) struct Watch {
+    pub(crate) progress: Progress,
+    callback: Option<Py<PyAny>>,
+    token: Option<Arc<AtomicBool>>,
+    /// Last `(done, total)` reported
+    reported: Mutex<Option<(usize, usize)>>,
+}
+
+impl Watch {
+    pub(crate) fn new(
+        callback: Option<&Bound<'_, PyAny>>,
+        cancel: Option<&CancelToken>,
+    ) -> PyResult<Self> {
+        if let Some(callback) = callback
+            && !callback.is_callable()
+        {
+            return Err(PyValueError::new_err("progress must be callable"));
+        }
+        let progress = Progress::new();
+        if cancel.is_some_and(CancelToken::cancelled) {
+            progress.cancel();
+        }
+        Ok(Self {
+            progress,
+            callback: callback.map(|c| c.clone().unbind()),
+            token: cancel.map(|t| Arc::clone(&t.cancelled)),
+            reported: Mutex::new(None),
+        })
+    }
+
+    /// Check for Ctrl-C and the token and report progress if it changed;
+    /// an error cancels the call
+    pub(crate) fn poll(&self, py: Python<'_>) -> PyResult<()> {
+        let result = self.check(py);
+        if result.is_err() {
+            self.progress.cancel();
+        }
+        result
+    }
+
+    fn check(&self, py: Python<'_>) -> PyResult<()> {
+        py.check_signals()?;
+        if self
+            .token
+            .as_ref()
+            .is_some_and(|t| t.load(Ordering::Relaxed))
+        {
+            self.progress.cancel();
+        }
+        let Some(callback) = &self.callback else {
+            return Ok(());
+        };
+        let now = (self.progress.done(), self.progress.total());
+        let mut reported = self.reported.lock().unwrap_or_else(PoisonError::into_inner);
+        if *reported != Some(now) {
+            *reported = Some(now);
+            drop(reported);
+            callback.call1(py, now)?;
+        }
+        Ok(())
+    }
+
+    /// Run `work` without the GIL on a worker thread, polling from this
+    /// one until it is done.
+    ///
+    /// Returns the error of a failed poll (``KeyboardInterrupt`` or a
+    /// raising callback), else ``CancelledError`` if the call was
+    /// cancelled, else what `work` returned.
+    pub(crate) fn run<T: Send>(
+        &self,
+        py: Python<'_>,
+        work: impl FnOnce(&Progress) -> T + Send,
+    ) -> PyResult<T> {
+        let progress = &self.progress;
+        let mut failed = None;
+        let result = py.detach(|| {
+            let watcher = thread::current();
+            thread::scope(|scope| {
+                let worker = scope.spawn(|| {
+                    let result = work(progress);
+                    watcher.unpark();
+                    result
+                });
+                while !worker.is_finished() {
+                    thread::park_timeout(POLL);
+                    if failed.is_none() {
+                        failed = Python::attach(|py| self.poll(py)).err();
+                    }
+                }
+                worker.join().expect("watched calls do not panic")
+            })
+        });
+        self.finish(py, failed)?;
+        Ok(result)
+    }
+
+    /// Count one unit of work done on a thread that may hold the GIL, and
+    /// poll; ``CancelledError`` once the call is cancelled
+    pub(crate) fn tick(&self) -> PyResult<()> {
+        self.progress.advance(1);
+        Python::attach(|py| self.poll(py))?;
+        if self.progress.is_cancelled() {
+            return Err(cancelled());
+        }
+        Ok(())
+    }
+
+    /// Report final progress, then fail as [`Watch::run`] describes
+    pub(crate) fn finish(&self, py: Python<'_>, failed: Option<PyErr>) -> PyResult<()> {
+        if let Some(e) = failed {
+            return Err(e);
+        }
+        self.poll(py)?;
+        if self.progress.is_cancelled() {
+            return Err(cancelled());
+        }
+        Ok(())
+    }
+}
+
+fn cancelled() -> PyErr {
+    CancelledError::new_err("the call was cancelled")
+}
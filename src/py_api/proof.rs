@@ -0,0 +1,170 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::replay::Replay;
+use super::rules::{Rules, rules_or_default};
+use super::{direction_to_py, parse_direction};
+use crate::proof::{self, Hash};
+
+/// One accepted move in a hash-chained game record.
+///
+/// Args:
+///     index: Position in the chain (0-based).
+///     pre_hash: SHA-256 of the board before the move (32 bytes).
+///     direction: Move direction enum.
+///     post_hash: SHA-256 of the board after the move and spawn.
+///     draw_index: Index of the spawn draw used after this move.
+///     chain: SHA-256 over the previous ``chain`` and this record's fields.
+#[pyclass(module = "akioi_2048", frozen, eq)]
+#[derive(Clone, PartialEq)]
+pub struct MoveRecord {
+    pub(crate) inner: proof::MoveRecord,
+}
+
+fn hash_from(bytes: &[u8], field: &str) -> PyResult<Hash> {
+    bytes
+        .try_into()
+        .map_err(|_| PyValueError::new_err(format!("{field} must be 32 bytes")))
+}
+
+#[pymethods]
+impl MoveRecord {
+    #[new]
+    fn new(
+        index: u64,
+        pre_hash: &[u8],
+        direction: &Bound<'_, PyAny>,
+        post_hash: &[u8],
+        draw_index: u64,
+        chain: &[u8],
+    ) -> PyResult<Self> {
+        Ok(Self {
+            inner: proof::MoveRecord {
+                index,
+                pre_hash: hash_from(pre_hash, "pre_hash")?,
+                direction: parse_direction(direction)?,
+                post_hash: hash_from(post_hash, "post_hash")?,
+                draw_index,
+                chain: hash_from(chain, "chain")?,
+            },
+        })
+    }
+
+    #[getter]
+    fn index(&self) -> u64 {
+        self.inner.index
+    }
+
+    #[getter]
+    fn pre_hash(&self) -> &[u8] {
+        &self.inner.pre_hash
+    }
+
+    #[getter]
+    fn direction(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        direction_to_py(py, self.inner.direction)
+    }
+
+    #[getter]
+    fn post_hash(&self) -> &[u8] {
+        &self.inner.post_hash
+    }
+
+    #[getter]
+    fn draw_index(&self) -> u64 {
+        self.inner.draw_index
+    }
+
+    #[getter]
+    fn chain(&self) -> &[u8] {
+        &self.inner.chain
+    }
+
+    fn __repr__(&self) -> String {
+        let hex: String = self.inner.chain[..8]
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        format!(
+            "MoveRecord(index={}, direction={:?}, draw_index={}, chain={hex}...)",
+            self.inner.index, self.inner.direction, self.inner.draw_index
+        )
+    }
+}
+
+/// Verify a chain of ``MoveRecord`` objects.
+///
+/// Checks that indices are consecutive, each move starts where the previous
+/// ended, spawns use consecutive draws and every chain hash is correct. With
+/// ``seed`` the moves are also replayed and must reproduce the records
+/// exactly, proving every move and spawn was legal engine play.
+///
+/// Args:
+///     records: Records in chain order, e.g. ``Game.records()``.
+///     seed: Optional game seed (revealed after the game) for full replay.
+///     rules: Rules the game was played under.
+///
+/// Returns:
+///     bool: Whether the chain is valid.
+#[pyfunction]
+#[pyo3(signature = (records, seed=None, rules=None))]
+pub fn verify_chain(
+    py: Python<'_>,
+    records: Vec<MoveRecord>,
+    seed: Option<u64>,
+    rules: Option<&Rules>,
+) -> bool {
+    let records: Vec<proof::MoveRecord> = records.into_iter().map(|r| r.inner).collect();
+    let rules = rules_or_default(rules);
+    py.detach(|| match seed {
+        Some(seed) => proof::verify_chain_with_seed(&records, seed, rules),
+        None => proof::verify_chain(&records),
+    })
+    .is_ok()
+}
+
+/// Sign a finished game for later verification.
+///
+/// The signature is HMAC-SHA256 under ``key`` over the replay's text
+/// without its stamp line, as version 1 replays were written, followed by
+/// a ``score <n>`` line with the final score, so only a holder
+/// of ``key`` (such as a tournament server) can produce it.
+///
+/// Args:
+///     replay: The game, e.g. ``Game.replay()``.
+///     key: Secret key.
+///
+/// Returns:
+///     bytes: The 32-byte signature.
+///
+/// Raises:
+///     ValueError: If the replay does not play back.
+#[pyfunction]
+pub fn sign_replay(py: Python<'_>, replay: &Replay, key: &[u8]) -> PyResult<Vec<u8>> {
+    let replay = &replay.inner;
+    py.detach(|| proof::sign_replay(replay, key))
+        .map(|signature| signature.to_vec())
+        .map_err(PyValueError::new_err)
+}
+
+/// Check a signature made by ``sign_replay``.
+///
+/// The moves are played back from the seed, so a replay that is not legal
+/// engine play, or whose score differs from the signed one, never verifies.
+///
+/// Args:
+///     replay: The submitted game.
+///     signature: Its claimed signature.
+///     key: Secret key it was signed with.
+///
+/// Returns:
+///     bool: Whether the signature is valid.
+#[pyfunction]
+pub fn verify_replay(py: Python<'_>, replay: &Replay, signature: &[u8], key: &[u8]) -> bool {
+    let Ok(signature) = Hash::try_from(signature) else {
+        return false;
+    };
+    let replay = &replay.inner;
+    py.detach(|| proof::verify_replay(replay, &signature, key))
+}
@@ -0,0 +1,165 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::errors::invalid_board;
+use super::rules::{Rules, rules_or_default};
+use super::session::Game;
+use super::{board_to_py, parse_direction};
+use crate::puzzle::{self, Goal, Outcome};
+use crate::{Board, validate_board_with};
+
+/// A fixed starting board to bring to a goal within a move budget.
+///
+/// Spawns derive from ``seed``, so everyone playing the same puzzle sees
+/// the same tiles for the same moves.
+///
+/// Args:
+///     board: 4x4 starting board.
+///     max_moves: Move budget; invalid moves count too.
+///     tile: Goal: some tile at least this large.
+///     score: Goal: a score of at least this much.
+///     clear_multipliers: Goal: no multiplier tile left on the board.
+///     seed: Seed for the spawns.
+///     rules: Optional ``Rules``.
+///
+/// Every goal that is given must hold at once, and at least one is needed.
+///
+/// Raises:
+///     ValueError: For an invalid board or a puzzle without a goal.
+#[pyclass(module = "akioi_2048", frozen)]
+pub struct Puzzle {
+    inner: puzzle::Puzzle,
+}
+
+/// Verdict of ``Puzzle.check``.
+///
+/// Attributes:
+///     outcome: ``"solved"``, ``"out_of_moves"``, ``"game_over"`` or
+///              ``"in_progress"`` when the moves ran out with budget left.
+///     moves: Moves played up to the verdict.
+///     board: Position at the verdict.
+///     score: Score at the verdict.
+#[pyclass(module = "akioi_2048", frozen, get_all)]
+pub struct PuzzleResult {
+    outcome: &'static str,
+    moves: usize,
+    board: Vec<Vec<i32>>,
+    score: i64,
+}
+
+#[pymethods]
+impl PuzzleResult {
+    /// Whether the goal was met within the budget.
+    #[getter]
+    fn solved(&self) -> bool {
+        self.outcome == "solved"
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PuzzleResult(outcome={:?}, moves={}, score={})",
+            self.outcome, self.moves, self.score
+        )
+    }
+}
+
+#[pymethods]
+impl Puzzle {
+    #[new]
+    #[pyo3(signature = (board, max_moves, tile=None, score=None, clear_multipliers=false, seed=0, rules=None))]
+    fn new(
+        board: Board,
+        max_moves: usize,
+        tile: Option<i32>,
+        score: Option<i64>,
+        clear_multipliers: bool,
+        seed: u64,
+        rules: Option<&Rules>,
+    ) -> PyResult<Self> {
+        let goal = Goal {
+            tile,
+            score,
+            clear_multipliers,
+        };
+        if goal == Goal::default() {
+            return Err(PyValueError::new_err("puzzle has no goal"));
+        }
+        let rules = rules_or_default(rules);
+        validate_board_with(&board, &rules).map_err(invalid_board)?;
+        Ok(Self {
+            inner: puzzle::Puzzle {
+                board,
+                goal,
+                max_moves,
+                seed,
+                rules,
+            },
+        })
+    }
+
+    /// Play ``moves`` (``Direction`` or action ids) from the start until
+    /// the goal is met, the budget is used up or the game is over; later
+    /// moves are ignored.
+    fn check(&self, moves: Vec<Bound<'_, PyAny>>) -> PyResult<PuzzleResult> {
+        let moves = moves
+            .iter()
+            .map(parse_direction)
+            .collect::<PyResult<Vec<_>>>()?;
+        let result = self.inner.check(&moves).map_err(PyValueError::new_err)?;
+        Ok(PuzzleResult {
+            outcome: match result.outcome {
+                Outcome::Solved => "solved",
+                Outcome::OutOfMoves => "out_of_moves",
+                Outcome::GameOver => "game_over",
+                Outcome::InProgress => "in_progress",
+            },
+            moves: result.moves,
+            board: board_to_py(&result.board),
+            score: result.score,
+        })
+    }
+
+    /// A ``Game`` at the starting position, to play the puzzle move by move.
+    fn game(&self) -> PyResult<Game> {
+        let inner = self.inner.game().map_err(PyValueError::new_err)?;
+        Ok(inner.into())
+    }
+
+    #[getter]
+    fn board(&self) -> Vec<Vec<i32>> {
+        board_to_py(&self.inner.board)
+    }
+
+    #[getter]
+    fn max_moves(&self) -> usize {
+        self.inner.max_moves
+    }
+
+    #[getter]
+    fn tile(&self) -> Option<i32> {
+        self.inner.goal.tile
+    }
+
+    #[getter]
+    fn score(&self) -> Option<i64> {
+        self.inner.goal.score
+    }
+
+    #[getter]
+    fn clear_multipliers(&self) -> bool {
+        self.inner.goal.clear_multipliers
+    }
+
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.inner.seed
+    }
+
+    #[getter]
+    fn rules(&self) -> Rules {
+        Rules {
+            inner: self.inner.rules,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use super::board_to_py;
+use super::rules::{Rules, rules_or_default};
+use crate::{Board, reach};
+
+/// The distinct boards exactly ``depth`` moves from ``board``, each move
+/// followed by every spawn it can give.
+///
+/// Args:
+///     board: 4x4 starting board.
+///     depth: Moves to look ahead; ``0`` gives ``[board]``.
+///     rules: Optional ``Rules``.
+///     numbers_only: Spawn only 2 and 4, as in classic 2048.
+///
+/// Returns:
+///     list[list[list[int]]]: The boards in sorted order.
+///
+/// Raises:
+///     ValueError: For an invalid board.
+#[pyfunction]
+#[pyo3(signature = (board, depth=1, rules=None, numbers_only=false))]
+pub fn enumerate_successors(
+    py: Python<'_>,
+    board: Board,
+    depth: usize,
+    rules: Option<&Rules>,
+    numbers_only: bool,
+) -> PyResult<Vec<Vec<Vec<i32>>>> {
+    let rules = rules_or_default(rules);
+    let boards = py
+        .detach(|| reach::enumerate_successors(&board, &rules, depth, numbers_only))
+        .map_err(PyValueError::new_err)?;
+    Ok(boards.iter().map(board_to_py).collect())
+}
+
+/// Count the distinct boards reachable from ``board`` at each depth, as in
+/// ``enumerate_successors`` but without building the boards in Python.
+///
+/// Returns:
+///     list[int]: Counts for ``0, 1, ..., depth`` moves; the first is 1.
+///
+/// Raises:
+///     ValueError: For an invalid board.
+#[pyfunction]
+#[pyo3(signature = (board, depth, rules=None, numbers_only=false))]
+pub fn count_reachable_states(
+    py: Python<'_>,
+    board: Board,
+    depth: usize,
+    rules: Option<&Rules>,
+    numbers_only: bool,
+) -> PyResult<Vec<usize>> {
+    let rules = rules_or_default(rules);
+    py.detach(|| reach::count_reachable_states(&board, &rules, depth, numbers_only))
+        .map_err(PyValueError::new_err)
+}
@@ -0,0 +1,22 @@
+use pyo3::prelude::*;
+
+use crate::render::Style;
+
+/// Draw a board as an aligned multi-line string.
+///
+/// Args:
+///     board: 4x4 board; tiles are drawn as-is, without validation.
+///     style: ``"unicode"`` (box-drawing grid, multipliers as ``×2``),
+///            ``"ascii"`` (``+-|`` grid, multipliers as ``x2``) or ``"ansi"``
+///            (256-colour tiles for terminals).
+///
+/// Returns:
+///     str: The board, one line per text row, without a trailing newline.
+#[pyfunction]
+#[pyo3(signature = (board, style="unicode"))]
+pub fn render(board: [[i32; 4]; 4], style: &str) -> PyResult<String> {
+    let style = Style::from_name(style).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!("unknown render style: {style}"))
+    })?;
+    Ok(crate::render::render(&board, style))
+}
@@ -0,0 +1,187 @@
+use std::io;
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::compat::warn_differences;
+use super::rules::{Rules, rules_or_default};
+use super::session::Game;
+use super::{board_to_py, direction_to_py, parse_direction};
+use crate::image::Theme;
+use crate::replay;
+
+/// A recorded game: seed, rules and the accepted moves.
+///
+/// Spawns derive from the seed, so a replay reproduces every board of the
+/// original game. Get one from ``Game.replay()`` or ``Replay.parse(text)``.
+///
+/// Args:
+///     seed: Seed the game was started with.
+///     moves: Directions of the accepted moves, in order.
+///     rules: Optional ``Rules`` the game was played under.
+#[pyclass(module = "akioi_2048", frozen, eq)]
+#[derive(Clone, PartialEq)]
+pub struct Replay {
+    pub(crate) inner: replay::Replay,
+}
+
+#[pymethods]
+impl Replay {
+    #[new]
+    #[pyo3(signature = (seed, moves, rules=None))]
+    fn new(seed: u64, moves: Vec<Bound<'_, PyAny>>, rules: Option<&Rules>) -> PyResult<Self> {
+        Ok(Self {
+            inner: replay::Replay {
+                rules: rules_or_default(rules),
+                seed,
+                start: None,
+                moves: moves.iter().map(parse_direction).collect::<PyResult<_>>()?,
+            },
+        })
+    }
+
+    /// Parse the text produced by ``to_text()``.
+    ///
+    /// Text stamped by an engine that plays its rules differently (see
+    /// ``engine_stamp``) is refused unless ``force`` is set, which loads it
+    /// with a ``RuntimeWarning`` per difference.
+    #[staticmethod]
+    #[pyo3(signature = (text, force=false))]
+    fn parse(py: Python<'_>, text: &str, force: bool) -> PyResult<Self> {
+        let (inner, differences) =
+            replay::Replay::parse_with(text, force).map_err(PyValueError::new_err)?;
+        warn_differences(py, &differences)?;
+        Ok(Self { inner })
+    }
+
+    /// Read a binary game log written by ``save()`` or ``Game.log_moves``.
+    ///
+    /// ``force`` loads a log stamped by an engine that plays its rules
+    /// differently, as for ``parse``.
+    ///
+    /// Raises:
+    ///     ValueError: If the file is not a valid log, is truncated, fails
+    ///         a checksum, a spawn does not match the seed, or its stamp
+    ///         differs and ``force`` is not set.
+    #[staticmethod]
+    #[pyo3(signature = (path, force=false))]
+    fn load(py: Python<'_>, path: PathBuf, force: bool) -> PyResult<Self> {
+        let (inner, differences) = py
+            .detach(|| crate::gamelog::load_with(&path, force))
+            .map_err(invalid_data)?;
+        warn_differences(py, &differences)?;
+        Ok(Self { inner })
+    }
+
+    /// Save as a compact binary game log: a header with the rules and
+    /// seed, and about one byte per move.
+    fn save(&self, py: Python<'_>, path: PathBuf) -> PyResult<()> {
+        py.detach(|| crate::gamelog::save(&self.inner, &path))
+            .map_err(invalid_data)
+    }
+
+    /// Plain-text form (also what the terminal client saves).
+    fn to_text(&self) -> String {
+        self.inner.to_text()
+    }
+
+    /// Hex SHA-256 of ``to_text()`` without its stamp line, identifying the
+    /// game whichever engine wrote it.
+    fn digest(&self) -> String {
+        self.inner.digest()
+    }
+
+    /// Play the moves back and return the resulting ``Game``.
+    fn game(&self, py: Python<'_>) -> PyResult<Game> {
+        let inner = py
+            .detach(|| self.inner.game())
+            .map_err(PyValueError::new_err)?;
+        Ok(inner.into())
+    }
+
+    /// The starting board followed by the board after every move.
+    fn frames(&self, py: Python<'_>) -> PyResult<Vec<Vec<Vec<i32>>>> {
+        let frames = py
+            .detach(|| self.inner.frames())
+            .map_err(PyValueError::new_err)?;
+        Ok(frames.iter().map(board_to_py).collect())
+    }
+
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.inner.seed
+    }
+
+    /// ``(board, score)`` the game began from if it was constructed with
+    /// ``Game.from_board``, else ``None``.
+    #[getter]
+    fn start(&self) -> Option<(Vec<Vec<i32>>, i64)> {
+        self.inner
+            .start
+            .map(|start| (board_to_py(&start.board), start.score))
+    }
+
+    #[getter]
+    fn moves(&self, py: Python<'_>) -> PyResult<Vec<Py<PyAny>>> {
+        self.inner
+            .moves
+            .iter()
+            .map(|&dir| direction_to_py(py, dir))
+            .collect()
+    }
+
+    #[getter]
+    fn rules(&self) -> Rules {
+        Rules {
+            inner: self.inner.rules,
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.moves.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Replay(seed={}, moves=<{} moves>, rules={:?})",
+            self.inner.seed,
+            self.inner.moves.len(),
+            self.inner.rules.tag()
+        )
+    }
+}
+
+pub(crate) fn invalid_data(e: io::Error) -> PyErr {
+    match e.kind() {
+        io::ErrorKind::InvalidData => PyValueError::new_err(e.to_string()),
+        _ => e.into(),
+    }
+}
+
+/// Save a replay as a looping animated GIF, one frame per board.
+///
+/// Args:
+///     replay: The ``Replay`` to render.
+///     path: Destination ``.gif`` file.
+///     fps: Frames per second (1 to 100).
+///     theme: ``"light"`` or ``"dark"``, as in ``render_image``.
+#[pyfunction]
+#[pyo3(signature = (replay, path, fps=4, theme="light"))]
+pub fn export_gif(
+    py: Python<'_>,
+    replay: &Replay,
+    path: PathBuf,
+    fps: u32,
+    theme: &str,
+) -> PyResult<()> {
+    let theme = Theme::from_name(theme)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown theme: {theme}")))?;
+    let replay = &replay.inner;
+    py.detach(|| crate::gif::export_gif(replay, &path, fps, theme))
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::InvalidInput => PyValueError::new_err(e.to_string()),
+            _ => e.into(),
+        })
+}
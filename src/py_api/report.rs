@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::policy::Policy;
+use crate::Engine;
+use crate::report::{self, BatteryResult};
+
+/// Run the standardized strength battery against an agent.
+///
+/// Batteries: ``seeded`` (fresh seeded games), ``positions`` (fixed mid-game
+/// positions), ``adversarial`` (worst-case spawns) and ``timed`` (a move
+/// slower than ``time_limit`` seconds forfeits the game). All spawns derive
+/// from ``seed``, so cards of different agents are comparable.
+///
+/// Args:
+///     agent: Callable ``agent(board) -> Direction`` or a built-in policy name.
+///     seed: Master seed for the whole battery.
+///     n_games: Games per battery (``positions`` plays each position once).
+///     time_limit: Per-move limit in seconds for the ``timed`` battery.
+///
+/// Returns:
+///     dict: ``{"seed", "overall", "batteries"}`` where ``overall`` and each
+///         ``batteries[name]`` hold ``games``, ``moves``, ``median_score``,
+///         ``win_rate``, ``blunder_rate``, ``timeouts`` and ``moves_per_second``.
+#[pyfunction]
+#[pyo3(signature = (agent, seed=0, n_games=8, time_limit=0.05))]
+pub fn report_card<'py>(
+    agent: &Bound<'py, PyAny>,
+    seed: u64,
+    n_games: usize,
+    time_limit: f64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let py = agent.py();
+    let limit = Duration::try_from_secs_f64(time_limit)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("time_limit: {e}")))?;
+    let agent = Policy::from_py(agent)?;
+    let card = agent.run(py, Engine::AKIOI, |choose| {
+        report::report_card(seed, n_games, limit, choose)
+    })?;
+
+    let batteries = PyDict::new(py);
+    for (name, result) in &card.batteries {
+        batteries.set_item(name, battery_to_py(py, result)?)?;
+    }
+    let out = PyDict::new(py);
+    out.set_item("seed", card.seed)?;
+    out.set_item("overall", battery_to_py(py, &card.overall)?)?;
+    out.set_item("batteries", batteries)?;
+    Ok(out)
+}
+
+fn battery_to_py<'py>(py: Python<'py>, r: &BatteryResult) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new(py);
+    d.set_item("games", r.games)?;
+    d.set_item("moves", r.moves)?;
+    d.set_item("median_score", r.median_score)?;
+    d.set_item("win_rate", r.win_rate)?;
+    d.set_item("blunder_rate", r.blunder_rate)?;
+    d.set_item("timeouts", r.timeouts)?;
+    d.set_item("moves_per_second", r.moves_per_second)?;
+    Ok(d)
+}
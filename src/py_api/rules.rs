@@ -0,0 +1,271 @@
+use pyo3::prelude::*;
+
+/// Multiplier merge mechanics and optional tiles used by ``step``.
+///
+/// Args:
+///     max_multiplier: Largest multiplier magnitude; multipliers of this size
+///                     no longer merge with each other (standard: 4).
+///     multiplier_merge: Whether equal multipliers merge (``-1 + -1 -> -2``).
+///     require_full_below: Whether numeric x multiplier merges need every cell
+///                         beyond the pair (in the move direction) occupied.
+///     mercy: Never spawn a x1 multiplier into the last empty cell.
+///     difficulty: Spawn weights: ``"easy"`` (fewer x1, more 4 and x2),
+///                 ``"standard"``, ``"cruel"`` (more x1, fewer 4 and x2) or
+///                 ``"adaptive"`` (easy up to a 128 tile, standard at 1024,
+///                 cruel from 8192).
+///     bombs: Allow bomb tiles (``-5``): a tile sliding against one clears
+///            the whole row or column of the move, blockers aside, and that
+///            line scores nothing.
+///     blockers: Allow blocker tiles (``-3``), which never slide or merge
+///               and stop tiles sliding against them like a wall.
+///     variant: Tile set, merges and spawns: ``"akioi"`` (multipliers, as
+///              set up by the arguments above), ``"classic"`` (plain
+///              2048: numbers only, 2 and 4 spawning) or ``"fib"``
+///              (consecutive Fibonacci numbers merge, 1 and 2 spawning,
+///              46368 wins).
+///     base: Base ``b`` of the classic variant, from 2 to 16: tiles are a
+///           digit below ``b`` times a power of ``b``, two tiles of the same
+///           power merge when their digits add up to at most ``b``, ``b``
+///           and ``2b`` spawn and the largest power within ``max_tile``
+///           wins.
+///     max_tile: Cap of the number tiles, a power of two from 16 to 2**29:
+///               no merge makes a larger tile, and the variant's largest
+///               tile within it wins.
+///     overflow: What two tiles at the cap merge into: ``"block"``
+///               (nothing, they stay apart), ``"win"`` (a tile twice the
+///               cap, which wins instead and merges no more) or ``"wrap"``
+///               (the smallest number tile, scoring twice the cap).
+///     scoring: How merges add to the score, and so every ``delta_score``:
+///              ``"standard"`` (every merge scores the tile it makes, so
+///              ``-1 + -1 -> -2`` scores -2), ``"web"`` (merges making a
+///              multiplier score nothing, so the score never goes down) or
+///              ``"clamp"`` (merges score as standard, but a move that would
+///              lose score scores 0).
+///     spawns: Tiles spawned after a move, from 1 to 4, each placed like
+///             a single spawn on the board the previous one left.
+///     spawn_every: Moves from one spawn to the next, from 1 to 16: the
+///                  first move of a game spawns, then every
+///                  ``spawn_every``-th after it.
+///     placement: Where spawned tiles land, the starting ones included:
+///                ``"uniform"`` (any empty cell), ``"edge"`` (empty cells
+///                on the border while there are any) or ``"far"`` (the
+///                empty cells farthest from the largest number tile).
+///
+/// Raises:
+///     ValueError: If ``max_multiplier`` is not a power of two >= 2, the
+///         difficulty, variant, overflow, scoring or placement is unknown, or ``base``,
+///         ``max_tile``, ``spawns`` or ``spawn_every`` does not fit.
+#[pyclass(module = "akioi_2048", frozen, eq)]
+#[derive(Clone, PartialEq)]
+pub struct Rules {
+    pub(crate) inner: crate::Rules,
+}
+
+#[pymethods]
+impl Rules {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (max_multiplier=4, multiplier_merge=true, require_full_below=true, mercy=false, difficulty="standard", bombs=false, blockers=false, variant="akioi", base=2, max_tile=0x0001_0000, overflow="block", scoring="standard", spawns=1, spawn_every=1, placement="uniform"))]
+    fn new(
+        max_multiplier: i32,
+        multiplier_merge: bool,
+        require_full_below: bool,
+        mercy: bool,
+        difficulty: &str,
+        bombs: bool,
+        blockers: bool,
+        variant: &str,
+        base: i32,
+        max_tile: i32,
+        overflow: &str,
+        scoring: &str,
+        spawns: u8,
+        spawn_every: u8,
+        placement: &str,
+    ) -> PyResult<Self> {
+        let difficulty = crate::Difficulty::from_name(difficulty).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("unknown difficulty: {difficulty}"))
+        })?;
+        let variant = crate::Variant::from_name(variant).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("unknown variant: {variant}"))
+        })?;
+        let overflow = crate::Overflow::from_name(overflow).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("unknown overflow: {overflow}"))
+        })?;
+        let scoring = crate::Scoring::from_name(scoring).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("unknown scoring: {scoring}"))
+        })?;
+        let placement = crate::Placement::from_name(placement).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("unknown placement: {placement}"))
+        })?;
+        let inner = crate::Rules {
+            variant,
+            max_multiplier,
+            multiplier_merge,
+            require_full_below,
+            mercy,
+            difficulty,
+            bombs,
+            blockers,
+            base,
+            max_tile,
+            overflow,
+            scoring,
+            spawns,
+            spawn_every,
+            placement,
+        };
+        inner
+            .validate()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Named preset: ``"akioi"`` (standard), ``"mercy"``, another variant
+    /// (``"classic"``, ``"fib"``) or a difficulty (``"easy"``, ``"standard"``,
+    /// ``"cruel"``, ``"adaptive"``) with otherwise standard rules.
+    #[staticmethod]
+    fn preset(name: &str) -> PyResult<Self> {
+        crate::Rules::preset(name)
+            .map(|inner| Self { inner })
+            .ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!("unknown rules preset: {name}"))
+            })
+    }
+
+    /// Stable identifier such as ``"akioi-v2"``; scores are only comparable
+    /// between games with the same tag.
+    #[getter]
+    fn tag(&self) -> String {
+        self.inner.tag()
+    }
+
+    #[getter]
+    fn max_multiplier(&self) -> i32 {
+        self.inner.max_multiplier
+    }
+
+    #[getter]
+    fn multiplier_merge(&self) -> bool {
+        self.inner.multiplier_merge
+    }
+
+    #[getter]
+    fn require_full_below(&self) -> bool {
+        self.inner.require_full_below
+    }
+
+    #[getter]
+    fn mercy(&self) -> bool {
+        self.inner.mercy
+    }
+
+    #[getter]
+    fn difficulty(&self) -> &'static str {
+        self.inner.difficulty.name()
+    }
+
+    #[getter]
+    fn bombs(&self) -> bool {
+        self.inner.bombs
+    }
+
+    #[getter]
+    fn blockers(&self) -> bool {
+        self.inner.blockers
+    }
+
+    #[getter]
+    fn variant(&self) -> &'static str {
+        self.inner.variant.name()
+    }
+
+    #[getter]
+    fn base(&self) -> i32 {
+        self.inner.base
+    }
+
+    #[getter]
+    fn max_tile(&self) -> i32 {
+        self.inner.max_tile
+    }
+
+    #[getter]
+    fn overflow(&self) -> &'static str {
+        self.inner.overflow.name()
+    }
+
+    #[getter]
+    fn scoring(&self) -> &'static str {
+        self.inner.scoring.name()
+    }
+
+    #[getter]
+    fn spawns(&self) -> u8 {
+        self.inner.spawns
+    }
+
+    #[getter]
+    fn spawn_every(&self) -> u8 {
+        self.inner.spawn_every
+    }
+
+    #[getter]
+    fn placement(&self) -> &'static str {
+        self.inner.placement.name()
+    }
+
+    fn __repr__(&self) -> String {
+        let py_bool = |b: bool| if b { "True" } else { "False" };
+        format!(
+            "Rules(max_multiplier={}, multiplier_merge={}, require_full_below={}, mercy={}, difficulty='{}', bombs={}, blockers={}, variant='{}', base={}, max_tile={}, overflow='{}', scoring='{}', spawns={}, spawn_every={}, placement='{}')",
+            self.inner.max_multiplier,
+            py_bool(self.inner.multiplier_merge),
+            py_bool(self.inner.require_full_below),
+            py_bool(self.inner.mercy),
+            self.inner.difficulty.name(),
+            py_bool(self.inner.bombs),
+            py_bool(self.inner.blockers),
+            self.inner.variant.name(),
+            self.inner.base,
+            self.inner.max_tile,
+            self.inner.overflow.name(),
+            self.inner.scoring.name(),
+            self.inner.spawns,
+            self.inner.spawn_every,
+            self.inner.placement.name()
+        )
+    }
+}
+
+/// Rules for an optional ``rules=`` argument (standard rules when omitted)
+pub(crate) fn rules_or_default(rules: Option<&Rules>) -> crate::Rules {
+    rules.map(|r| r.inner).unwrap_or_default()
+}
+
+/// Rules for a ``rules=`` argument that also takes a preset name or a
+/// rules tag (standard rules when omitted)
+pub(crate) fn rules_or_named(rules: Option<&Bound<'_, PyAny>>) -> PyResult<crate::Rules> {
+    let Some(obj) = rules else {
+        return Ok(crate::Rules::default());
+    };
+    if let Ok(rules) = obj.cast::<Rules>() {
+        return Ok(rules.get().inner);
+    }
+    let name: String = obj.extract().map_err(|_| {
+        pyo3::exceptions::PyValueError::new_err(
+            "rules must be a Rules, a preset name or a rules tag",
+        )
+    })?;
+    crate::Rules::preset(&name)
+        .map_or_else(|| crate::Rules::from_tag(&name), Ok)
+        .map_err(|e| {
+            // a tag's own error says what is wrong with it
+            let message = if name.starts_with("akioi-v") {
+                e
+            } else {
+                format!("unknown rules: {name}")
+            };
+            pyo3::exceptions::PyValueError::new_err(message)
+        })
+}
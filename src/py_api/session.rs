@@ -0,0 +1,641 @@
+use std::fs::{File, OpenOptions};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use pyo3::exceptions::PyImportError;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::detail::{MoveResult, move_result_to_py};
+use super::errors::game_finished;
+use super::proof::MoveRecord;
+use super::replay::{Replay, invalid_data};
+use super::rules::{Rules, rules_or_default};
+use super::status::{Status, status_to_py};
+use super::{
+    board_to_py, direction_to_py, ndarray, parse_direction, state_to_py, step_result_to_py,
+};
+use crate::Direction;
+use crate::detail::{self, Cell, Spawn};
+use crate::gamelog::GameLog;
+use crate::movelog::MoveLog;
+use crate::session::{self, InvalidMoves, Snapshot, TimeControl, Timeout, TimingStats};
+use crate::web::WebSave;
+
+pub(crate) fn parse_invalid_moves(name: &str) -> PyResult<InvalidMoves> {
+    InvalidMoves::from_name(name).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!("unknown invalid_moves policy: {name}"))
+    })
+}
+
+/// Clock settings shared by ``Game`` and ``Game.from_board``
+fn time_control(
+    time_limit: Option<f64>,
+    increment: f64,
+    on_timeout: &str,
+) -> PyResult<Option<TimeControl>> {
+    let on_timeout = Timeout::from_name(on_timeout).ok_or_else(|| {
+        pyo3::exceptions::PyValueError::new_err(format!("unknown on_timeout policy: {on_timeout}"))
+    })?;
+    let seconds = |name: &str, value: f64| {
+        Duration::try_from_secs_f64(value).map_err(|_| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "{name} must be a non-negative number of seconds, got {value}"
+            ))
+        })
+    };
+    let Some(total) = time_limit else {
+        return Ok(None);
+    };
+    Ok(Some(TimeControl {
+        total: seconds("time_limit", total)?,
+        increment: seconds("increment", increment)?,
+        on_timeout,
+    }))
+}
+
+/// A seeded game in progress.
+///
+/// Args:
+///     seed: Seed for all spawns; a random one is picked when omitted.
+///           Equal seeds, rules and moves always give equal games.
+///     rules: Optional ``Rules``.
+///     proofs: Attach a hash-chained ``MoveRecord`` to every accepted move
+///             (see ``verify_chain``).
+///     invalid_moves: What a move that changes nothing does: ``"noop"``
+///                    (counts as a move, nothing else), ``"penalize"`` (same;
+///                    ``Env`` also lowers the reward), ``"mask"`` (raises
+///                    ``ValueError``) or ``"auto-resolve"`` (plays a random
+///                    legal move instead).
+///     keep_playing: Play on after a victory, like the web version's "keep
+///                   going" button: the winning move reports
+///                   ``State.Victory`` once, later moves ``State.Continue``
+///                   until no move is left.
+///     spawns: ``[((row, col), tile), ...]`` placed in order after the next
+///             valid moves instead of random spawns, so tests can predict
+///             every board; random spawns resume once the list runs out.
+///             Scripted spawns are not part of ``replay()``.
+///     time_limit: Seconds on the game's clock, which starts when the game
+///                 is created; every ``step`` spends the time since the
+///                 previous one. No clock when omitted.
+///     increment: Seconds added to the clock after every move made in time.
+///     on_timeout: What a move after the clock ran out does: ``"reject"``
+///                 (raises ``ValueError``) or ``"forfeit"`` (the move is not
+///                 played and the game ends as lost, with
+///                 ``status.reason == "out_of_time"``).
+///     timings: Time every move for ``timings()``.
+///
+/// A ``Game`` may be shared between threads: every method call is atomic,
+/// so concurrent ``step`` calls are applied one after another and never
+/// interleave. Calls on one game are serialized; give each thread its own
+/// ``Game`` to play in parallel.
+#[pyclass(module = "akioi_2048", frozen)]
+pub struct Game {
+    /// Locked only around Rust code, never while calling into Python
+    inner: Mutex<session::Game>,
+    /// Callables run after every move, once `inner` is unlocked
+    callbacks: Mutex<Vec<Py<PyAny>>>,
+    /// Move log written after every move
+    log: Mutex<Option<Log>>,
+}
+
+enum Log {
+    Json(MoveLog<File>),
+    Binary(GameLog<File>),
+}
+
+impl From<session::Game> for Game {
+    fn from(inner: session::Game) -> Self {
+        Self {
+            inner: Mutex::new(inner),
+            callbacks: Mutex::new(Vec::new()),
+            log: Mutex::new(None),
+        }
+    }
+}
+
+impl Game {
+    pub(crate) fn game(&self) -> MutexGuard<'_, session::Game> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Play `direction`, then log it and run the callbacks if it was played
+    fn apply(&self, py: Python<'_>, direction: Direction) -> PyResult<detail::MoveResult> {
+        let (result, played) = {
+            let mut game = self.game();
+            // under the other policies a finished game takes moves as no-ops
+            if game.is_over() && game.invalid_moves() == InvalidMoves::Mask {
+                return Err(game_finished("the game is over"));
+            }
+            let before = game.moves();
+            let result = game
+                .try_step(direction)
+                .map_err(pyo3::exceptions::PyValueError::new_err)?;
+            let played = (game.moves() > before).then(|| {
+                // auto-resolve may have played another direction
+                let direction = match game.history().last() {
+                    Some(&last) if result.moved => last,
+                    _ => direction,
+                };
+                (before, direction, game.score())
+            });
+            (result, played)
+        };
+        let Some((index, direction, score)) = played else {
+            return Ok(result);
+        };
+        if let Some(log) = self
+            .log
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+        {
+            match log {
+                Log::Json(log) => log.write(index, direction, &result, score)?,
+                // the binary log holds accepted moves only
+                Log::Binary(log) if result.moved => log.push(direction, result.spawn)?,
+                Log::Binary(_) => {}
+            }
+        }
+        let callbacks: Vec<Py<PyAny>> = self
+            .callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|c| c.clone_ref(py))
+            .collect();
+        if !callbacks.is_empty() {
+            let direction = direction_to_py(py, direction)?;
+            let py_result = Py::new(py, move_result_to_py(py, &result)?)?;
+            for callback in callbacks {
+                callback.call1(py, (direction.clone_ref(py), py_result.clone_ref(py)))?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn build(
+    mut builder: session::GameBuilder,
+    seed: Option<u64>,
+    spawns: Option<Vec<(Cell, i32)>>,
+) -> PyResult<Game> {
+    if let Some(seed) = seed {
+        builder = builder.seed(seed);
+    }
+    if let Some(spawns) = spawns {
+        builder = builder.spawns(
+            spawns
+                .into_iter()
+                .map(|(cell, value)| Spawn { cell, value }),
+        );
+    }
+    let inner = builder
+        .build()
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok(inner.into())
+}
+
+/// A saved ``Game`` state from ``Game.snapshot``.
+///
+/// Attributes:
+///     board: Board at the snapshot.
+///     score: Score at the snapshot.
+///     moves: Moves played before the snapshot.
+#[pyclass(module = "akioi_2048", frozen)]
+pub struct StateToken {
+    inner: Snapshot,
+}
+
+#[pymethods]
+impl StateToken {
+    #[getter]
+    fn board(&self) -> Vec<Vec<i32>> {
+        board_to_py(&self.inner.board())
+    }
+
+    #[getter]
+    fn score(&self) -> i64 {
+        self.inner.score()
+    }
+
+    #[getter]
+    fn moves(&self) -> usize {
+        self.inner.moves()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "StateToken(moves={}, score={})",
+            self.inner.moves(),
+            self.inner.score()
+        )
+    }
+}
+
+#[pymethods]
+impl Game {
+    #[new]
+    #[pyo3(signature = (seed=None, rules=None, proofs=false, invalid_moves="noop", keep_playing=false, spawns=None, time_limit=None, increment=0.0, on_timeout="reject", timings=false))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        seed: Option<u64>,
+        rules: Option<&Rules>,
+        proofs: bool,
+        invalid_moves: &str,
+        keep_playing: bool,
+        spawns: Option<Vec<(Cell, i32)>>,
+        time_limit: Option<f64>,
+        increment: f64,
+        on_timeout: &str,
+        timings: bool,
+    ) -> PyResult<Self> {
+        let mut builder = session::Game::builder()
+            .rules(rules_or_default(rules))
+            .proofs(proofs)
+            .invalid_moves(parse_invalid_moves(invalid_moves)?)
+            .keep_playing(keep_playing)
+            .timings(timings);
+        if let Some(control) = time_control(time_limit, increment, on_timeout)? {
+            builder = builder.time_control(control);
+        }
+        build(builder, seed, spawns)
+    }
+
+    /// Start a game from a constructed position instead of two dealt tiles.
+    ///
+    /// Args:
+    ///     board: 4x4 starting board; every tile must be valid under
+    ///            ``rules``.
+    ///     score: Score the game starts with.
+    ///     seed: Seed for all later spawns; a random one is picked when
+    ///           omitted.
+    ///
+    /// The remaining arguments are those of ``Game``. The position is kept
+    /// in ``replay()``, so constructed games replay exactly too.
+    ///
+    /// Raises:
+    ///     ValueError: For an invalid board.
+    #[staticmethod]
+    #[pyo3(signature = (board, score=0, seed=None, rules=None, proofs=false, invalid_moves="noop", keep_playing=false, spawns=None, time_limit=None, increment=0.0, on_timeout="reject", timings=false))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_board(
+        board: &Bound<'_, PyAny>,
+        score: i64,
+        seed: Option<u64>,
+        rules: Option<&Rules>,
+        proofs: bool,
+        invalid_moves: &str,
+        keep_playing: bool,
+        spawns: Option<Vec<(Cell, i32)>>,
+        time_limit: Option<f64>,
+        increment: f64,
+        on_timeout: &str,
+        timings: bool,
+    ) -> PyResult<Self> {
+        let board: crate::Board = board.extract()?;
+        let mut builder = session::Game::builder()
+            .start(board, score)
+            .rules(rules_or_default(rules))
+            .proofs(proofs)
+            .invalid_moves(parse_invalid_moves(invalid_moves)?)
+            .keep_playing(keep_playing)
+            .timings(timings);
+        if let Some(control) = time_control(time_limit, increment, on_timeout)? {
+            builder = builder.time_control(control);
+        }
+        build(builder, seed, spawns)
+    }
+
+    /// Resume a game from the web version's saved ``gameState`` JSON.
+    ///
+    /// The board, score and "keep going" choice come from the save; the
+    /// remaining arguments are those of ``Game``.
+    ///
+    /// Raises:
+    ///     ValueError: For malformed JSON or a board invalid under ``rules``.
+    #[staticmethod]
+    #[pyo3(signature = (json, seed=None, rules=None, proofs=false, invalid_moves="noop"))]
+    fn from_web_save(
+        json: &str,
+        seed: Option<u64>,
+        rules: Option<&Rules>,
+        proofs: bool,
+        invalid_moves: &str,
+    ) -> PyResult<Self> {
+        let save = WebSave::parse(json).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let builder = save
+            .builder()
+            .rules(rules_or_default(rules))
+            .proofs(proofs)
+            .invalid_moves(parse_invalid_moves(invalid_moves)?);
+        build(builder, seed, None)
+    }
+
+    /// The position as the web version's ``gameState`` JSON, to load in a
+    /// browser.
+    fn to_web_save(&self) -> String {
+        WebSave::of(&self.game()).to_json()
+    }
+
+    /// Apply one move.
+    ///
+    /// Returns:
+    ///     StepResult: (board, delta, state) with named fields.
+    fn step(&self, direction: &Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
+        let result = self.apply(direction.py(), parse_direction(direction)?)?;
+        step_result_to_py(direction.py(), &result.board, result.delta, result.state)
+    }
+
+    /// Apply one move like ``step`` without building the board, for tight
+    /// loops that read ``board`` only now and then.
+    ///
+    /// Returns:
+    ///     tuple[int, State]: The move's score delta and the state after it.
+    fn step_in_place(&self, direction: &Bound<'_, PyAny>) -> PyResult<(i32, Py<PyAny>)> {
+        let result = self.apply(direction.py(), parse_direction(direction)?)?;
+        Ok((result.delta, state_to_py(direction.py(), result.state)?))
+    }
+
+    /// Apply one move and return the full ``MoveResult``.
+    fn step_detailed(&self, direction: &Bound<'_, PyAny>) -> PyResult<MoveResult> {
+        let result = self.apply(direction.py(), parse_direction(direction)?)?;
+        move_result_to_py(direction.py(), &result)
+    }
+
+    /// Capture the whole game state, RNG included, to branch from later.
+    ///
+    /// Tokens are cheap: the move history and proof records are shared
+    /// until the game moves on, so many branches can start from one
+    /// position.
+    fn snapshot(&self) -> StateToken {
+        StateToken {
+            inner: self.game().snapshot(),
+        }
+    }
+
+    /// Return to the state captured in ``token``.
+    ///
+    /// Every later move replays exactly as after the snapshot. Callbacks
+    /// and the move log stay as they are, and a token of another game
+    /// restores that game's position, rules and seed.
+    fn restore(&self, token: &StateToken) {
+        self.game().restore(&token.inner);
+    }
+
+    /// Call ``callback(direction, result)`` after every move from now on.
+    ///
+    /// ``result`` is the move's ``MoveResult``, with its score delta and
+    /// spawn. Moves that were not played (rejected by ``"mask"``, or after
+    /// the clock ran out) do not call back. An exception raised by the
+    /// callback propagates from ``step`` after the move was played.
+    ///
+    /// Raises:
+    ///     ValueError: If ``callback`` is not callable.
+    fn on_move(&self, callback: Bound<'_, PyAny>) -> PyResult<()> {
+        if !callback.is_callable() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "on_move needs a callable",
+            ));
+        }
+        self.callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(callback.unbind());
+        Ok(())
+    }
+
+    /// Write every move from now on to ``path`` as JSON Lines, natively.
+    ///
+    /// Each line holds ``move`` (index from 0), ``direction``, ``delta``,
+    /// ``score`` (total after the move), ``moved``, ``merges`` (count),
+    /// ``spawn`` (``{"row", "col", "value"}`` or ``null``) and ``state``.
+    /// Lines are written as moves are played, so the file is always up to
+    /// date.
+    ///
+    /// With ``binary=True`` the log is a compact binary game log instead,
+    /// which ``Replay.load`` reads back: it holds the whole game, so the
+    /// moves played so far are written first, and appending continues a
+    /// log of this game that stops at an earlier move.
+    ///
+    /// Args:
+    ///     path: Log file; ``None`` stops logging.
+    ///     append: Add to an existing file instead of replacing it.
+    ///     binary: Write a binary game log.
+    ///
+    /// Raises:
+    ///     ValueError: If ``append`` and ``binary`` are set and the file
+    ///         is not a valid log of this game.
+    #[pyo3(signature = (path, append=false, binary=false))]
+    fn log_moves(&self, path: Option<PathBuf>, append: bool, binary: bool) -> PyResult<()> {
+        let log = match path {
+            Some(path) if binary => {
+                let replay = self.game().replay();
+                let log = if append {
+                    GameLog::append(&path, &replay)
+                } else {
+                    File::create(&path).and_then(|file| GameLog::create(file, &replay))
+                };
+                Some(Log::Binary(log.map_err(invalid_data)?))
+            }
+            Some(path) => Some(Log::Json(MoveLog::new(
+                OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(append)
+                    .truncate(!append)
+                    .open(path)?,
+            ))),
+            None => None,
+        };
+        *self.log.lock().unwrap_or_else(PoisonError::into_inner) = log;
+        Ok(())
+    }
+
+    /// Seed, rules and accepted moves, enough to reproduce this game.
+    fn replay(&self) -> Replay {
+        Replay {
+            inner: self.game().replay(),
+        }
+    }
+
+    /// Proof records of accepted moves (empty unless ``proofs=True``).
+    fn records(&self) -> Vec<MoveRecord> {
+        self.game()
+            .records()
+            .iter()
+            .map(|&inner| MoveRecord { inner })
+            .collect()
+    }
+
+    #[getter]
+    fn board(&self) -> Vec<Vec<i32>> {
+        board_to_py(&self.game().board())
+    }
+
+    #[getter]
+    fn score(&self) -> i64 {
+        self.game().score()
+    }
+
+    #[getter]
+    fn state(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let state = self.game().state();
+        state_to_py(py, state)
+    }
+
+    /// ``state`` with its reason and whether any move remains.
+    #[getter]
+    fn status(&self, py: Python<'_>) -> PyResult<Status> {
+        let status = self.game().status();
+        status_to_py(py, status)
+    }
+
+    /// Seconds left on the clock, ``None`` without ``time_limit``.
+    #[getter]
+    fn time_left(&self) -> Option<f64> {
+        self.game().time_left().map(|left| left.as_secs_f64())
+    }
+
+    /// Wall-clock time of every move so far, invalid ones included, to
+    /// tell the player's decision latency from the engine's.
+    ///
+    /// Returns:
+    ///     dict: ``decision`` (seconds from the end of the previous move,
+    ///     or the start of the game, to each move) and ``engine`` (seconds
+    ///     spent playing each move), both ``float64`` arrays, or lists of
+    ///     floats without NumPy, and ``stats`` mapping both names to
+    ///     ``count``, ``total``, ``mean``, ``median``, ``p95`` and ``max``
+    ///     in seconds. Callbacks and the move log count toward the next
+    ///     decision. Empty unless the game was created with
+    ///     ``timings=True``.
+    fn timings<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let moves = self.game().timings().to_vec();
+        let d = PyDict::new(py);
+        let stats = PyDict::new(py);
+        let decision: Vec<Duration> = moves.iter().map(|t| t.decision).collect();
+        let engine: Vec<Duration> = moves.iter().map(|t| t.engine).collect();
+        let numpy = match py.import("numpy") {
+            Ok(_) => true,
+            Err(e) if e.is_instance_of::<PyImportError>(py) => false,
+            Err(e) => return Err(e),
+        };
+        for (name, times) in [("decision", decision), ("engine", engine)] {
+            let seconds: Vec<f64> = times.iter().map(Duration::as_secs_f64).collect();
+            if numpy {
+                let data: Vec<u8> = seconds.iter().flat_map(|t| t.to_ne_bytes()).collect();
+                d.set_item(name, ndarray(py, &data, "float64", &[seconds.len()])?)?;
+            } else {
+                d.set_item(name, seconds)?;
+            }
+            let summary = TimingStats::of(times);
+            let s = PyDict::new(py);
+            s.set_item("count", summary.count)?;
+            s.set_item("total", summary.total.as_secs_f64())?;
+            s.set_item("mean", summary.mean.as_secs_f64())?;
+            s.set_item("median", summary.median.as_secs_f64())?;
+            s.set_item("p95", summary.p95.as_secs_f64())?;
+            s.set_item("max", summary.max.as_secs_f64())?;
+            stats.set_item(name, s)?;
+        }
+        d.set_item("stats", stats)?;
+        Ok(d)
+    }
+
+    /// Whether a victory has been reached at some point.
+    #[getter]
+    fn won(&self) -> bool {
+        self.game().won()
+    }
+
+    /// Moves played so far, including invalid ones.
+    #[getter]
+    fn moves(&self) -> usize {
+        self.game().moves()
+    }
+
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.game().seed()
+    }
+
+    #[getter]
+    fn rules(&self) -> Rules {
+        Rules {
+            inner: self.game().rules(),
+        }
+    }
+}
+
+/// Record a game to a binary game log.
+///
+/// ``with record("run.akr") as game:`` plays a ``Game`` whose every move is
+/// written to ``path`` as it is made, so a crash keeps the moves so far.
+/// Leaving the block stops logging and rewrites the file from
+/// ``replay()``, complete and flushed, even after a ``restore``;
+/// ``Replay.load(path)`` reads it back.
+///
+/// Args:
+///     path: Log file, replaced if it exists.
+///
+/// The remaining arguments are those of ``Game``.
+///
+/// Raises:
+///     ValueError: For an unknown ``invalid_moves``.
+#[pyfunction]
+#[pyo3(signature = (path, seed=None, rules=None, invalid_moves="noop", keep_playing=false))]
+pub(crate) fn record(
+    py: Python<'_>,
+    path: PathBuf,
+    seed: Option<u64>,
+    rules: Option<&Rules>,
+    invalid_moves: &str,
+    keep_playing: bool,
+) -> PyResult<Recording> {
+    let builder = session::Game::builder()
+        .rules(rules_or_default(rules))
+        .invalid_moves(parse_invalid_moves(invalid_moves)?)
+        .keep_playing(keep_playing);
+    let game = Py::new(py, build(builder, seed, None)?)?;
+    Ok(Recording { path, game })
+}
+
+/// Context manager returned by ``record``; entering it starts the log and
+/// gives the ``Game``.
+#[pyclass(module = "akioi_2048", frozen)]
+pub struct Recording {
+    path: PathBuf,
+    game: Py<Game>,
+}
+
+#[pymethods]
+impl Recording {
+    fn __enter__(&self, py: Python<'_>) -> PyResult<Py<Game>> {
+        self.game
+            .get()
+            .log_moves(Some(self.path.clone()), false, true)?;
+        Ok(self.game.clone_ref(py))
+    }
+
+    #[pyo3(signature = (*_exc))]
+    fn __exit__(&self, py: Python<'_>, _exc: &Bound<'_, pyo3::types::PyTuple>) -> PyResult<bool> {
+        let game = self.game.get();
+        game.log_moves(None, false, false)?;
+        let replay = game.game().replay();
+        py.detach(|| crate::gamelog::save(&replay, &self.path))
+            .map_err(invalid_data)?;
+        Ok(false)
+    }
+
+    /// The game recorded
+    #[getter]
+    fn game(&self, py: Python<'_>) -> Py<Game> {
+        self.game.clone_ref(py)
+    }
+
+    #[getter]
+    fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+}
@@ -0,0 +1,79 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict, PyList};
+
+use super::rules::rules_or_named;
+use super::session::parse_invalid_moves;
+
+/// The active ruleset as data, derived from the engine itself.
+///
+/// Meant for tooling that must stay in sync with the engine, such as
+/// documentation generators and clients in other languages; the engine
+/// server's ``rules_spec`` method returns the same description as JSON.
+///
+/// Args:
+///     rules: ``Rules``, a preset name or a rules tag.
+///     invalid_moves: The ``Game`` policy for moves that change nothing.
+///
+/// Returns:
+///     dict: ``version`` (``RULES_VERSION``), ``tag`` and every ``Rules``
+///     field; ``tiles``, every tile the variant accepts besides
+///     ``blocker`` and ``bomb`` (``None`` without blockers or bombs);
+///     ``merges``, one
+///     dict per merging pair in slide order: ``a`` (the tile nearer the
+///     wall), ``b``, the resulting ``tile`` and ``score``, and whether the
+///     pair ``needs_adjacent`` tiles or ``needs_full_below``; ``spawns``,
+///     the spawn probabilities on an empty board; ``winning_tile``; and
+///     ``invalid_moves``.
+#[pyfunction]
+#[pyo3(signature = (rules=None, invalid_moves="noop"))]
+pub fn rules_spec<'py>(
+    py: Python<'py>,
+    rules: Option<&Bound<'py, PyAny>>,
+    invalid_moves: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    let rules = rules_or_named(rules)?;
+    let invalid_moves = parse_invalid_moves(invalid_moves)?;
+    let spec = crate::spec::spec(&rules, invalid_moves)
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+    let d = PyDict::new(py);
+    d.set_item("version", spec.version)?;
+    d.set_item("tag", rules.tag())?;
+    d.set_item("variant", rules.variant.name())?;
+    d.set_item("base", rules.base)?;
+    d.set_item("max_multiplier", rules.max_multiplier)?;
+    d.set_item("multiplier_merge", rules.multiplier_merge)?;
+    d.set_item("require_full_below", rules.require_full_below)?;
+    d.set_item("difficulty", rules.difficulty.name())?;
+    d.set_item("mercy", rules.mercy)?;
+    d.set_item("bombs", rules.bombs)?;
+    d.set_item("blockers", rules.blockers)?;
+    d.set_item("max_tile", rules.max_tile)?;
+    d.set_item("overflow", rules.overflow.name())?;
+    d.set_item("scoring", rules.scoring.name())?;
+    d.set_item("spawns_per_move", rules.spawns)?;
+    d.set_item("spawn_every", rules.spawn_every)?;
+    d.set_item("placement", rules.placement.name())?;
+    d.set_item("tiles", &spec.tiles)?;
+    d.set_item("blocker", spec.blocker)?;
+    d.set_item("bomb", spec.bomb)?;
+    let merges = PyList::empty(py);
+    for m in &spec.merges {
+        let merge = PyDict::new(py);
+        merge.set_item("a", m.a)?;
+        merge.set_item("b", m.b)?;
+        merge.set_item("tile", m.tile)?;
+        merge.set_item("score", m.score)?;
+        merge.set_item("needs_adjacent", m.needs_adjacent)?;
+        merge.set_item("needs_full_below", m.needs_full_below)?;
+        merges.append(merge)?;
+    }
+    d.set_item("merges", merges)?;
+    let spawns = PyDict::new(py);
+    for &(tile, chance) in &spec.spawns {
+        spawns.set_item(tile, chance)?;
+    }
+    d.set_item("spawns", spawns)?;
+    d.set_item("winning_tile", spec.winning_tile)?;
+    d.set_item("invalid_moves", spec.invalid_moves.name())?;
+    Ok(d)
+}
@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict, PyTuple};
+
+use super::session::Game;
+use super::{direction_to_py, ndarray};
+use crate::ALL_DIRECTIONS;
+use crate::stats::{self, DEFAULT_BIN_WIDTH};
+
+/// Running statistics over finished games, computed natively.
+///
+/// Record games as they finish, then ``merge`` the stats of separate workers;
+/// stats pickle, so they can be sent back from worker processes.
+///
+/// Args:
+///     bin_width: Width of the ``score_histogram`` bins.
+///     heatmaps: Also collect ``heatmaps`` of where play happens on the
+///               board, replaying every recorded game.
+///     branching: Also collect ``branching``, how much choice and chance
+///                every move had, replaying every recorded game.
+///
+/// Raises:
+///     ValueError: Unless ``bin_width`` is positive.
+#[pyclass(module = "akioi_2048")]
+#[derive(Clone)]
+pub struct GameStats {
+    pub(crate) inner: stats::GameStats,
+}
+
+#[pymethods]
+impl GameStats {
+    #[new]
+    #[pyo3(signature = (bin_width=DEFAULT_BIN_WIDTH, heatmaps=false, branching=false))]
+    fn new(bin_width: i64, heatmaps: bool, branching: bool) -> PyResult<Self> {
+        let mut inner = stats::GameStats::new(bin_width).map_err(PyValueError::new_err)?;
+        if heatmaps {
+            inner = inner.with_heatmaps();
+        }
+        if branching {
+            inner = inner.with_branching();
+        }
+        Ok(Self { inner })
+    }
+
+    /// Add a finished ``Game``, including the directions it was played in.
+    fn record(&mut self, game: &Game) {
+        self.inner.record(&game.game());
+    }
+
+    /// Add every game recorded in ``other``.
+    ///
+    /// Raises:
+    ///     ValueError: If the score bins differ in width, or only one of the
+    ///         stats collects heatmaps or branching.
+    fn merge(&mut self, other: &GameStats) -> PyResult<()> {
+        self.inner
+            .merge(&other.inner)
+            .map_err(PyValueError::new_err)
+    }
+
+    #[getter]
+    fn bin_width(&self) -> i64 {
+        self.inner.bin_width
+    }
+
+    #[getter]
+    fn games(&self) -> u64 {
+        self.inner.games
+    }
+
+    /// Games that reached the victory tile.
+    #[getter]
+    fn wins(&self) -> u64 {
+        self.inner.wins
+    }
+
+    #[getter]
+    fn win_rate(&self) -> f64 {
+        self.inner.win_rate()
+    }
+
+    #[getter]
+    fn mean_score(&self) -> f64 {
+        self.inner.mean_score()
+    }
+
+    /// Moves over all games.
+    #[getter]
+    fn moves(&self) -> u64 {
+        self.inner.moves
+    }
+
+    /// Average game length in moves.
+    #[getter]
+    fn mean_moves(&self) -> f64 {
+        self.inner.mean_moves()
+    }
+
+    /// Fewest moves in a game, ``0`` before any game.
+    #[getter]
+    fn shortest(&self) -> u64 {
+        self.inner.shortest
+    }
+
+    /// Most moves in a game.
+    #[getter]
+    fn longest(&self) -> u64 {
+        self.inner.longest
+    }
+
+    /// ``{max_tile: games}``.
+    #[getter]
+    fn max_tiles(&self) -> BTreeMap<i32, u64> {
+        self.inner.max_tiles.clone()
+    }
+
+    /// ``{bin_start: games}`` for bins ``bin_width`` wide.
+    #[getter]
+    fn score_histogram(&self) -> BTreeMap<i64, u64> {
+        self.inner.scores.clone()
+    }
+
+    /// ``{Direction: moves}`` over all recorded games.
+    #[getter]
+    fn direction_counts<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let d = PyDict::new(py);
+        for (&dir, &n) in ALL_DIRECTIONS.iter().zip(&self.inner.directions) {
+            d.set_item(direction_to_py(py, dir)?, n)?;
+        }
+        Ok(d)
+    }
+
+    /// ``{"max_tile", "merges", "spawns"}`` as NumPy ``uint64`` arrays of
+    /// shape ``(4, 4)``, indexed ``[row, col]``, or ``None`` unless
+    /// collected: positions with their largest tile in each cell (ties count
+    /// in every cell), merges by the cell of the merged tile and spawns by
+    /// cell.
+    #[getter]
+    fn heatmaps<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        let Some(h) = &self.inner.heatmaps else {
+            return Ok(None);
+        };
+        let d = PyDict::new(py);
+        for (name, counts) in [
+            ("max_tile", &h.max_tile),
+            ("merges", &h.merges),
+            ("spawns", &h.spawns),
+        ] {
+            let data: Vec<u8> = counts
+                .iter()
+                .flatten()
+                .flat_map(|n| n.to_ne_bytes())
+                .collect();
+            d.set_item(name, ndarray(py, &data, "uint64", &[4, 4])?)?;
+        }
+        Ok(Some(d))
+    }
+
+    /// By move number, the start at ``0``: ``positions`` (``uint64``) counts
+    /// the games that played a move there, and ``legal_moves``,
+    /// ``successors`` (distinct boards a move and a spawn lead to) and
+    /// ``spawn_entropy`` (bits, of the spawn after the move played) are
+    /// ``float64`` averages over them; ``None`` unless collected.
+    #[getter]
+    fn branching<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        let Some(b) = &self.inner.branching else {
+            return Ok(None);
+        };
+        let n = b.positions.len();
+        let d = PyDict::new(py);
+        let positions: Vec<u8> = b.positions.iter().flat_map(|n| n.to_ne_bytes()).collect();
+        d.set_item("positions", ndarray(py, &positions, "uint64", &[n])?)?;
+        for (name, means) in [
+            ("legal_moves", b.mean_legal_moves()),
+            ("successors", b.mean_successors()),
+            ("spawn_entropy", b.mean_spawn_entropy()),
+        ] {
+            let data: Vec<u8> = means.iter().flat_map(|m| m.to_ne_bytes()).collect();
+            d.set_item(name, ndarray(py, &data, "float64", &[n])?)?;
+        }
+        Ok(Some(d))
+    }
+
+    /// Every field as plain Python values; ``directions`` lists the move
+    /// counts by action id (Down=0, Right=1, Up=2, Left=3), ``heatmaps``
+    /// holds nested lists and ``branching`` the totals by move number (the
+    /// entropy in millionths of a bit), or ``None``.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let s = &self.inner;
+        let d = PyDict::new(py);
+        d.set_item("bin_width", s.bin_width)?;
+        d.set_item("games", s.games)?;
+        d.set_item("wins", s.wins)?;
+        d.set_item("moves", s.moves)?;
+        d.set_item("total_score", s.total_score)?;
+        d.set_item("shortest", s.shortest)?;
+        d.set_item("longest", s.longest)?;
+        d.set_item("max_tiles", s.max_tiles.clone())?;
+        d.set_item("scores", s.scores.clone())?;
+        d.set_item("directions", s.directions.to_vec())?;
+        let heatmaps = s
+            .heatmaps
+            .as_ref()
+            .map(|h| {
+                let maps = PyDict::new(py);
+                maps.set_item("max_tile", h.max_tile)?;
+                maps.set_item("merges", h.merges)?;
+                maps.set_item("spawns", h.spawns)?;
+                Ok::<_, PyErr>(maps)
+            })
+            .transpose()?;
+        d.set_item("heatmaps", heatmaps)?;
+        let branching = s
+            .branching
+            .as_ref()
+            .map(|b| {
+                let totals = PyDict::new(py);
+                totals.set_item("positions", b.positions.clone())?;
+                totals.set_item("legal_moves", b.legal_moves.clone())?;
+                totals.set_item("successors", b.successors.clone())?;
+                totals.set_item("spawn_entropy", b.spawn_entropy.clone())?;
+                Ok::<_, PyErr>(totals)
+            })
+            .transpose()?;
+        d.set_item("branching", branching)?;
+        Ok(d)
+    }
+
+    /// Rebuild stats from ``to_dict`` output.
+    ///
+    /// Raises:
+    ///     ValueError: For a missing field or a non-positive ``bin_width``.
+    #[staticmethod]
+    fn from_dict(d: &Bound<'_, PyDict>) -> PyResult<Self> {
+        let field = |name: &str| -> PyResult<Bound<'_, PyAny>> {
+            d.get_item(name)?
+                .ok_or_else(|| PyValueError::new_err(format!("missing field: {name}")))
+        };
+        let mut inner =
+            stats::GameStats::new(field("bin_width")?.extract()?).map_err(PyValueError::new_err)?;
+        inner.games = field("games")?.extract()?;
+        inner.wins = field("wins")?.extract()?;
+        inner.moves = field("moves")?.extract()?;
+        inner.total_score = field("total_score")?.extract()?;
+        inner.shortest = field("shortest")?.extract()?;
+        inner.longest = field("longest")?.extract()?;
+        inner.max_tiles = field("max_tiles")?.extract()?;
+        inner.scores = field("scores")?.extract()?;
+        inner.directions = field("directions")?.extract()?;
+        if let Some(maps) = d.get_item("heatmaps")?.filter(|m| !m.is_none()) {
+            let map = |name: &str| -> PyResult<[[u64; 4]; 4]> {
+                maps.get_item(name)
+                    .map_err(|_| PyValueError::new_err(format!("missing heatmap: {name}")))?
+                    .extract()
+            };
+            inner.heatmaps = Some(stats::Heatmaps {
+                max_tile: map("max_tile")?,
+                merges: map("merges")?,
+                spawns: map("spawns")?,
+            });
+        }
+        if let Some(totals) = d.get_item("branching")?.filter(|t| !t.is_none()) {
+            let column = |name: &str| -> PyResult<Vec<u64>> {
+                totals
+                    .get_item(name)
+                    .map_err(|_| PyValueError::new_err(format!("missing branching: {name}")))?
+                    .extract()
+            };
+            let branching = stats::Branching {
+                positions: column("positions")?,
+                legal_moves: column("legal_moves")?,
+                successors: column("successors")?,
+                spawn_entropy: column("spawn_entropy")?,
+            };
+            let n = branching.positions.len();
+            if [
+                &branching.legal_moves,
+                &branching.successors,
+                &branching.spawn_entropy,
+            ]
+            .iter()
+            .any(|c| c.len() != n)
+            {
+                return Err(PyValueError::new_err("branching totals differ in length"));
+            }
+            inner.branching = Some(branching);
+        }
+        Ok(Self { inner })
+    }
+
+    fn __reduce__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        let from_dict = py.get_type::<GameStats>().getattr("from_dict")?;
+        (from_dict, (self.to_dict(py)?,)).into_pyobject(py)
+    }
+
+    fn __eq__(&self, other: &GameStats) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GameStats(games={}, win_rate={}, mean_score={}, mean_moves={})",
+            self.inner.games,
+            self.inner.win_rate(),
+            self.inner.mean_score(),
+            self.inner.mean_moves()
+        )
+    }
+}
@@ -0,0 +1,97 @@
+use pyo3::prelude::*;
+
+use super::errors::invalid_board;
+use super::rules::{Rules, rules_or_default};
+use super::state_to_py;
+use crate::{Board, EndReason, validate_board_with};
+
+/// A ``State`` with the details behind it.
+///
+/// Attributes:
+///     state: ``State.{Victory, GameOver, Continue}``.
+///     reason: ``"victory_tile"`` (a 65536 tile, whether or not moves
+///             remain), ``"no_legal_moves"``, ``"out_of_time"`` (a ``Game``
+///             forfeited on time) or ``None`` while the game continues.
+///     can_continue: Whether some move still changes the board, e.g. after
+///                   a victory.
+#[pyclass(module = "akioi_2048", frozen, get_all)]
+pub struct Status {
+    state: Py<PyAny>,
+    reason: Option<&'static str>,
+    can_continue: bool,
+}
+
+pub(crate) fn status_to_py(py: Python<'_>, status: crate::Status) -> PyResult<Status> {
+    Ok(Status {
+        state: state_to_py(py, status.state)?,
+        reason: status.reason.map(|reason| match reason {
+            EndReason::VictoryTile => "victory_tile",
+            EndReason::NoLegalMoves => "no_legal_moves",
+            EndReason::OutOfTime => "out_of_time",
+        }),
+        can_continue: status.can_continue,
+    })
+}
+
+#[pymethods]
+impl Status {
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(format!(
+            "Status(state={}, reason={}, can_continue={})",
+            self.state.bind(py).repr()?,
+            self.reason
+                .map_or_else(|| "None".to_string(), |r| format!("{r:?}")),
+            if self.can_continue { "True" } else { "False" }
+        ))
+    }
+}
+
+/// Status of a board: its ``State``, why the game ended and whether any
+/// move remains.
+///
+/// Args:
+///     board: 4x4 board.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     Status: Details of the board's state.
+#[pyfunction]
+#[pyo3(signature = (board, rules=None))]
+pub fn status(py: Python<'_>, board: Board, rules: Option<&Rules>) -> PyResult<Status> {
+    let rules = rules_or_default(rules);
+    validate_board_with(&board, &rules).map_err(invalid_board)?;
+    let engine = crate::Engine::new(rules).map_err(pyo3::exceptions::PyValueError::new_err)?;
+    status_to_py(py, engine.status(&board))
+}
+
+/// Whether no move in any of the four directions changes ``board``.
+///
+/// Evaluated on the board itself, so it also works for boards that did not
+/// come from ``step``. A won board with moves left is not over.
+///
+/// Args:
+///     board: 4x4 board.
+///     rules: Optional ``Rules``.
+#[pyfunction]
+#[pyo3(signature = (board, rules=None))]
+pub fn is_game_over(board: Board, rules: Option<&Rules>) -> PyResult<bool> {
+    let rules = rules_or_default(rules);
+    validate_board_with(&board, &rules).map_err(invalid_board)?;
+    let engine = crate::Engine::new(rules).map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok(engine.is_over(&board))
+}
+
+/// Whether ``board`` holds the winning tile of ``rules``: 65536 under the
+/// standard ones.
+///
+/// Args:
+///     board: 4x4 board.
+///     rules: Optional ``Rules``.
+#[pyfunction]
+#[pyo3(signature = (board, rules=None))]
+pub fn is_won(board: Board, rules: Option<&Rules>) -> PyResult<bool> {
+    let rules = rules_or_default(rules);
+    validate_board_with(&board, &rules).map_err(invalid_board)?;
+    let engine = crate::Engine::new(rules).map_err(pyo3::exceptions::PyValueError::new_err)?;
+    Ok(engine.is_won(&board))
+}
@@ -0,0 +1,66 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::errors::checked_board;
+use super::policy::Policy;
+use super::rules::{Rules, rules_or_default};
+use crate::Engine;
+
+/// Estimate the chance that ``board`` survives ``n_moves`` more moves.
+///
+/// Plays ``samples`` rollouts natively with ``policy``, spawns seeded from
+/// ``seed``, for a "danger meter". Moves that change nothing are not
+/// counted; reaching the winning tile counts as surviving.
+///
+/// Args:
+///     board: 4x4 board.
+///     n_moves: Moves the position has to survive.
+///     samples: Monte Carlo rollouts.
+///     policy: Callable ``policy(board) -> Direction`` or a built-in policy
+///             name (default ``"random"``). Built-in policies run without
+///             the GIL.
+///     seed: Seed of the spawns; rollout ``i`` draws from the stream of
+///           game ``i`` of a ``tournament`` with this seed, so estimates
+///           for different policies are paired.
+///     rules: Optional ``Rules``.
+///
+/// Returns:
+///     dict: ``probability``, its ``std_error``, ``samples``, and
+///         ``mean_moves``, the moves a rollout made on average before it
+///         ended or reached ``n_moves``.
+///
+/// Raises:
+///     InvalidBoardError: For an invalid board.
+///     ValueError: For no samples.
+#[pyfunction]
+#[pyo3(signature = (board, n_moves, samples=1000, policy=None, seed=0, rules=None))]
+pub fn survival_probability<'py>(
+    py: Python<'py>,
+    board: &Bound<'py, PyAny>,
+    n_moves: usize,
+    samples: usize,
+    policy: Option<&Bound<'py, PyAny>>,
+    seed: u64,
+    rules: Option<&Rules>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let rules = rules_or_default(rules);
+    let board = checked_board(board, &rules)?;
+    if samples == 0 {
+        return Err(PyValueError::new_err("samples must be positive"));
+    }
+    let policy = match policy {
+        Some(policy) => Policy::from_py(policy)?,
+        None => Policy::Random,
+    };
+    let engine = Engine::new(rules).map_err(PyValueError::new_err)?;
+    let survival = policy.run(py, engine, |mut choose| {
+        crate::survival::survival_probability(&engine, &board, n_moves, samples, seed, &mut choose)
+    })?;
+    let d = PyDict::new(py);
+    d.set_item("probability", survival.probability)?;
+    d.set_item("std_error", survival.std_error)?;
+    d.set_item("samples", survival.samples)?;
+    d.set_item("mean_moves", survival.mean_moves)?;
+    Ok(d)
+}
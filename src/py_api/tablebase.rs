@@ -0,0 +1,298 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::direction_to_py;
+use super::progress::{CancelToken, Watch};
+use super::rules::{Rules, rules_or_default};
+use crate::Board;
+use crate::tablebase;
+
+/// Exact values of every position reachable from an endgame root within a
+/// move horizon, found by retrograde analysis.
+///
+/// Build one with ``Tablebase.build``; query it with ``lookup`` or
+/// ``tablebase_lookup``, and keep it with ``save`` / ``load``. ``open``
+/// memory-maps a saved table instead of loading it.
+#[pyclass(module = "akioi_2048", frozen)]
+pub struct Tablebase {
+    inner: Inner,
+}
+
+enum Inner {
+    Loaded(tablebase::Tablebase),
+    Mapped(tablebase::MappedTablebase),
+}
+
+impl Inner {
+    fn lookup(&self, board: &Board, moves_left: Option<u32>) -> Option<tablebase::Entry> {
+        match self {
+            Inner::Loaded(table) => table.lookup(board, moves_left),
+            Inner::Mapped(table) => table.lookup(board, moves_left),
+        }
+    }
+
+    fn header(&self) -> (crate::Rules, i32, u32, usize) {
+        match self {
+            Inner::Loaded(t) => (t.rules(), t.goal(), t.horizon(), t.len()),
+            Inner::Mapped(t) => (t.rules(), t.goal(), t.horizon(), t.len()),
+        }
+    }
+}
+
+/// Value of one position in a ``Tablebase``.
+///
+/// Attributes:
+///     win_probability: Chance of reaching the goal tile within the moves
+///                      left under optimal play.
+///     expected_score: Expected score gained over the moves left under the
+///                     same play.
+///     best_move: ``Direction`` that maximizes the win probability, ties
+///                going to the higher expected score; ``None`` once the
+///                game is decided.
+#[pyclass(module = "akioi_2048", frozen, get_all)]
+pub struct TablebaseEntry {
+    win_probability: f64,
+    expected_score: f64,
+    best_move: Option<Py<PyAny>>,
+}
+
+#[pymethods]
+impl TablebaseEntry {
+    fn __repr__(&self) -> String {
+        format!(
+            "TablebaseEntry(win_probability={}, expected_score={})",
+            self.win_probability, self.expected_score
+        )
+    }
+}
+
+fn entry_to_py(py: Python<'_>, entry: tablebase::Entry) -> PyResult<TablebaseEntry> {
+    Ok(TablebaseEntry {
+        win_probability: entry.win_probability,
+        expected_score: entry.expected_score,
+        best_move: entry
+            .best_move
+            .map(|dir| direction_to_py(py, dir))
+            .transpose()?,
+    })
+}
+
+#[pymethods]
+impl Tablebase {
+    /// Solve every position reachable from ``board`` within ``horizon``
+    /// moves, each move followed by every spawn it can lead to.
+    ///
+    /// Args:
+    ///     board: 4x4 root position, best with few empty cells.
+    ///     horizon: Moves to look ahead.
+    ///     goal: A position holding a tile of at least this is won.
+    ///     rules: Optional ``Rules``.
+    ///     max_positions: Give up beyond this many positions.
+    ///     progress: Called as ``progress(done, total)`` with the layers
+    ///               expanded and then solved, ``2 * horizon + 1`` in all.
+    ///     cancel: A ``CancelToken`` that stops the build.
+    ///
+    /// Ctrl-C stops the build too, raising ``KeyboardInterrupt``.
+    ///
+    /// Raises:
+    ///     ValueError: For an invalid board or too many positions.
+    ///     CancelledError: If ``cancel`` was cancelled.
+    #[staticmethod]
+    #[pyo3(signature = (
+        board,
+        horizon,
+        goal=65536,
+        rules=None,
+        max_positions=1_000_000,
+        progress=None,
+        cancel=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn build(
+        py: Python<'_>,
+        board: Board,
+        horizon: u32,
+        goal: i32,
+        rules: Option<&Rules>,
+        max_positions: usize,
+        progress: Option<&Bound<'_, PyAny>>,
+        cancel: Option<&CancelToken>,
+    ) -> PyResult<Self> {
+        let rules = rules_or_default(rules);
+        let inner = Watch::new(progress, cancel)?
+            .run(py, |progress| {
+                tablebase::Tablebase::build_with_progress(
+                    board,
+                    rules,
+                    goal,
+                    horizon,
+                    max_positions,
+                    progress,
+                )
+            })?
+            .map_err(PyValueError::new_err)?;
+        Ok(Self {
+            inner: Inner::Loaded(inner),
+        })
+    }
+
+    /// Load a table written by ``save``.
+    ///
+    /// Raises:
+    ///     OSError: If the file cannot be read.
+    ///     ValueError: If it is not a tablebase.
+    #[staticmethod]
+    fn load(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        let inner = py
+            .detach(|| tablebase::Tablebase::load(&path))
+            .map_err(invalid_data)?;
+        Ok(Self {
+            inner: Inner::Loaded(inner),
+        })
+    }
+
+    /// Use the table saved at ``path`` in place, memory-mapped.
+    ///
+    /// Only the pages lookups touch are read, so tables larger than memory
+    /// work, and processes that open the same file share one copy of it.
+    /// Nothing may write to or truncate the file while the table is open:
+    /// lookups read it in place, and reading past the end of a truncated
+    /// file kills the process. ``load`` reads it into memory instead.
+    ///
+    /// Args:
+    ///     path: A table written by ``save``.
+    ///     verify: Check the whole file against its checksum first, which
+    ///             reads all of it.
+    ///
+    /// Raises:
+    ///     OSError: If the file cannot be read.
+    ///     ValueError: If it is not a tablebase, is in the version 1 format
+    ///                 of earlier releases (``load`` and ``save`` it again)
+    ///                 or fails verification.
+    #[staticmethod]
+    #[pyo3(signature = (path, verify=false))]
+    fn open(py: Python<'_>, path: PathBuf, verify: bool) -> PyResult<Self> {
+        let table = py.detach(|| {
+            // SAFETY: left to the caller, as the documentation above says
+            let table = unsafe { tablebase::MappedTablebase::open(&path) }?;
+            if verify {
+                table.verify()?;
+            }
+            Ok(table)
+        });
+        Ok(Self {
+            inner: Inner::Mapped(table.map_err(invalid_data)?),
+        })
+    }
+
+    /// Whether the table is memory-mapped by ``open``.
+    #[getter]
+    fn mapped(&self) -> bool {
+        matches!(self.inner, Inner::Mapped(_))
+    }
+
+    /// Write the table to ``path`` in its binary format.
+    fn save(&self, py: Python<'_>, path: PathBuf) -> PyResult<()> {
+        py.detach(|| match &self.inner {
+            Inner::Loaded(table) => table.save(&path),
+            Inner::Mapped(table) => table.write(BufWriter::new(File::create(&path)?)),
+        })?;
+        Ok(())
+    }
+
+    /// The value of ``board`` with ``moves_left`` moves to go, or with the
+    /// most moves the table holds for it when omitted; ``None`` for a
+    /// position the table never reached.
+    #[pyo3(signature = (board, moves_left=None))]
+    fn lookup(
+        &self,
+        py: Python<'_>,
+        board: Board,
+        moves_left: Option<u32>,
+    ) -> PyResult<Option<TablebaseEntry>> {
+        self.inner
+            .lookup(&board, moves_left)
+            .map(|entry| entry_to_py(py, entry))
+            .transpose()
+    }
+
+    #[getter]
+    fn goal(&self) -> i32 {
+        self.inner.header().1
+    }
+
+    #[getter]
+    fn horizon(&self) -> u32 {
+        self.inner.header().2
+    }
+
+    #[getter]
+    fn rules(&self) -> Rules {
+        Rules {
+            inner: self.inner.header().0,
+        }
+    }
+
+    /// Number of (position, moves left) entries.
+    fn __len__(&self) -> usize {
+        self.inner.header().3
+    }
+
+    fn __repr__(&self) -> String {
+        let (rules, goal, horizon, len) = self.inner.header();
+        format!(
+            "Tablebase(goal={goal}, horizon={horizon}, entries={len}, rules={:?}{})",
+            rules.tag(),
+            if self.mapped() { ", mapped=True" } else { "" }
+        )
+    }
+}
+
+fn invalid_data(e: io::Error) -> PyErr {
+    match e.kind() {
+        io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof | io::ErrorKind::Unsupported => {
+            PyValueError::new_err(e.to_string())
+        }
+        _ => e.into(),
+    }
+}
+
+/// Look ``board`` up in a tablebase.
+///
+/// Args:
+///     board: 4x4 board.
+///     table: A ``Tablebase``, or the path of a saved one (mapped on every
+///            call, like ``Tablebase.open``, so the file must not change
+///            during it; open it once for many lookups).
+///     moves_left: As in ``Tablebase.lookup``.
+///
+/// Returns:
+///     TablebaseEntry | None: ``None`` for a position the table never
+///     reached.
+#[pyfunction]
+#[pyo3(signature = (board, table, moves_left=None))]
+pub fn tablebase_lookup(
+    py: Python<'_>,
+    board: Board,
+    table: &Bound<'_, PyAny>,
+    moves_left: Option<u32>,
+) -> PyResult<Option<TablebaseEntry>> {
+    if let Ok(table) = table.cast::<Tablebase>() {
+        return table.get().lookup(py, board, moves_left);
+    }
+    let path: PathBuf = table.extract()?;
+    // SAFETY: left to the caller, as the documentation above says
+    let inner = match unsafe { tablebase::MappedTablebase::open(&path) } {
+        Ok(table) => Inner::Mapped(table),
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => {
+            Inner::Loaded(tablebase::Tablebase::load(&path).map_err(invalid_data)?)
+        }
+        Err(e) => return Err(invalid_data(e)),
+    };
+    Tablebase { inner }.lookup(py, board, moves_left)
+}
@@ -0,0 +1,256 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::policy::Policy;
+use super::progress::{CancelToken, Watch};
+use super::rules::{Rules, rules_or_default};
+use crate::Engine;
+use crate::arrow;
+use crate::tournament::{
+    AgentStats, Comparison, Objective, Pareto, Standings, games_table, moves_table, pareto,
+    play_traced, standings,
+};
+
+/// Compare agents over seed-paired games.
+///
+/// Game ``i`` of every agent starts from the same board and draws its
+/// spawns from the same seeded stream, so score differences are paired
+/// game by game. Built-in policies play in parallel with the GIL released;
+/// once a callable is involved the agents play in turn, since every move
+/// calls back into Python.
+///
+/// Args:
+///     agents: Callables ``agent(board) -> Direction`` or built-in policy
+///             names: ``"random"``, ``"greedy-score"`` (the move gaining
+///             the most score), ``"down-left-corner"`` (the first legal of
+///             Down, Left, Right, Up) or ``"snake"`` (the move keeping the
+///             largest tiles along a snake from the bottom-left corner).
+///     games_per_agent: Games each agent plays.
+///     seed: Master seed for every game.
+///     rules: Optional ``Rules``.
+///     games_path: Write one row per game to this Apache Arrow IPC
+///                 (Feather) file, or Parquet file if the name ends in
+///                 ``.parquet``: ``agent``, ``game``, ``seed``,
+///                 ``score``, ``moves``, ``max_tile`` and ``won``.
+///     moves_path: Write one row per move to this Arrow or Parquet file,
+///                 by the same rule: ``agent``, ``game``, ``move``,
+///                 ``direction``, ``delta``, ``score`` (total after the
+///                 move), ``moved`` and the board it was played on as
+///                 ``cell_0`` to ``cell_15``, row-major.
+///     progress: Called as ``progress(done, total)`` with the games
+///               played so far.
+///     cancel: A ``CancelToken`` that stops the tournament between games.
+///     objectives: Names of what to rank the agents on, higher being
+///                 better: ``"score"`` (mean final score), ``"max_tile"``
+///                 (mean largest tile), ``"moves"`` (mean moves survived)
+///                 and ``"win_rate"`` (games reaching the winning tile).
+///                 All four when omitted.
+///
+/// Arrow files load with ``pandas.read_feather`` or ``polars.read_ipc``,
+/// Parquet files with ``pandas.read_parquet`` or ``polars.read_parquet``.
+/// Ctrl-C stops the tournament too, raising ``KeyboardInterrupt``.
+///
+/// Returns:
+///     dict: ``{"seed", "scores", "agents", "comparisons"}``. ``scores``
+///         holds each agent's per-game scores; ``agents[i]`` holds
+///         ``games``, ``mean_score``, ``median_score``, ``std_score``,
+///         ``win_rate``, ``mean_moves`` and ``max_tile``; every pair
+///         ``a < b`` gets a comparison with ``a``, ``b``,
+///         ``mean_difference`` (``a`` minus ``b``), ``wins``, ``losses``,
+///         ``ties``, the paired ``t_statistic`` and its two-sided
+///         ``p_value``, and ``sign_p_value`` of the exact sign test.
+///         ``pareto`` holds the ``objectives`` ranked on, each agent's
+///         ``values`` of them, its ``ranks`` (``0`` when no agent does at
+///         least as well on every objective and better on one, ``1`` when
+///         only rank-0 agents do, ...) and the rank-0 ``front``.
+///
+/// Raises:
+///     ValueError: For an unknown policy name, a non-callable agent or an
+///         unknown objective.
+///     CancelledError: If ``cancel`` was cancelled.
+#[pyfunction]
+#[pyo3(signature = (
+    agents,
+    games_per_agent=100,
+    seed=0,
+    rules=None,
+    games_path=None,
+    moves_path=None,
+    progress=None,
+    cancel=None,
+    objectives=None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn tournament<'py>(
+    py: Python<'py>,
+    agents: Vec<Bound<'py, PyAny>>,
+    games_per_agent: usize,
+    seed: u64,
+    rules: Option<&Rules>,
+    games_path: Option<PathBuf>,
+    moves_path: Option<PathBuf>,
+    progress: Option<&Bound<'py, PyAny>>,
+    cancel: Option<&CancelToken>,
+    objectives: Option<Vec<String>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let rules = rules_or_default(rules);
+    let objectives = match objectives {
+        Some(names) => names
+            .iter()
+            .map(|name| Objective::parse(name))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(PyValueError::new_err)?,
+        None => Objective::ALL.to_vec(),
+    };
+    let policies = agents
+        .iter()
+        .map(Policy::from_py)
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let watch = Watch::new(progress, cancel)?;
+    let traced = moves_path.is_some();
+    let engine = Engine::new(rules).map_err(PyValueError::new_err)?;
+    let native: Option<Vec<_>> = policies.iter().map(|p| p.native(engine)).collect();
+    let (standings, traces) = if let Some(native) = native {
+        let agents = native
+            .into_iter()
+            .map(|mut choose| move |board: &_| choose(board))
+            .collect();
+        watch
+            .run(py, |progress| {
+                crate::tournament::tournament_with_progress(
+                    seed,
+                    games_per_agent,
+                    &rules,
+                    agents,
+                    traced,
+                    progress,
+                )
+            })?
+            .map_err(PyValueError::new_err)?
+    } else {
+        watch.progress.set_total(policies.len() * games_per_agent);
+        let (mut outcomes, mut traces) = (Vec::new(), Vec::new());
+        for policy in &policies {
+            let mut games = Vec::new();
+            let agent = policy.run(py, engine, |mut choose| {
+                (0..games_per_agent)
+                    .map(|game| {
+                        let mut on_move = |m| {
+                            if traced {
+                                games.push((game, m));
+                            }
+                        };
+                        let outcome = play_traced(seed, game, &engine, &mut choose, &mut on_move)?;
+                        watch.tick()?;
+                        Ok(outcome)
+                    })
+                    .collect::<PyResult<Vec<_>>>()
+            })?;
+            let mut trace = vec![Vec::new(); games_per_agent];
+            for (game, m) in games {
+                trace[game].push(m);
+            }
+            outcomes.push(agent);
+            traces.push(trace);
+        }
+        watch.finish(py, None)?;
+        (standings(seed, outcomes), traces)
+    };
+    py.detach(|| {
+        if let Some(path) = games_path {
+            write_table(&path, &games_table(&standings))?;
+        }
+        if let Some(path) = moves_path {
+            write_table(&path, &moves_table(&traces))?;
+        }
+        PyResult::Ok(())
+    })?;
+    let out = standings_to_py(py, &standings)?;
+    out.set_item(
+        "pareto",
+        pareto_to_py(py, &pareto(&standings.outcomes, &objectives))?,
+    )?;
+    Ok(out)
+}
+
+/// Write `table` to `path` as Parquet for a `.parquet` name, else as Arrow
+fn write_table(path: &Path, table: &arrow::Table) -> PyResult<()> {
+    let parquet = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("parquet"));
+    if parquet {
+        crate::parquet::write(BufWriter::new(File::create(path)?), table)?;
+    } else {
+        // the writer lays the file out in memory and writes it at once
+        arrow::write(File::create(path)?, table)?;
+    }
+    Ok(())
+}
+
+fn standings_to_py<'py>(py: Python<'py>, s: &Standings) -> PyResult<Bound<'py, PyDict>> {
+    let scores: Vec<Vec<i64>> = s
+        .outcomes
+        .iter()
+        .map(|games| games.iter().map(|g| g.score).collect())
+        .collect();
+    let out = PyDict::new(py);
+    out.set_item("seed", s.seed)?;
+    out.set_item("scores", scores)?;
+    out.set_item(
+        "agents",
+        s.agents
+            .iter()
+            .map(|a| agent_to_py(py, a))
+            .collect::<PyResult<Vec<_>>>()?,
+    )?;
+    out.set_item(
+        "comparisons",
+        s.comparisons
+            .iter()
+            .map(|c| comparison_to_py(py, c))
+            .collect::<PyResult<Vec<_>>>()?,
+    )?;
+    Ok(out)
+}
+
+fn agent_to_py<'py>(py: Python<'py>, a: &AgentStats) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new(py);
+    d.set_item("games", a.games)?;
+    d.set_item("mean_score", a.mean_score)?;
+    d.set_item("median_score", a.median_score)?;
+    d.set_item("std_score", a.std_score)?;
+    d.set_item("win_rate", a.win_rate)?;
+    d.set_item("mean_moves", a.mean_moves)?;
+    d.set_item("max_tile", a.max_tile)?;
+    Ok(d)
+}
+
+fn pareto_to_py<'py>(py: Python<'py>, p: &Pareto) -> PyResult<Bound<'py, PyDict>> {
+    let names: Vec<&str> = p.objectives.iter().map(|o| o.name()).collect();
+    let d = PyDict::new(py);
+    d.set_item("objectives", names)?;
+    d.set_item("values", p.values.clone())?;
+    d.set_item("ranks", p.ranks.clone())?;
+    d.set_item("front", p.front())?;
+    Ok(d)
+}
+
+fn comparison_to_py<'py>(py: Python<'py>, c: &Comparison) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new(py);
+    d.set_item("a", c.a)?;
+    d.set_item("b", c.b)?;
+    d.set_item("mean_difference", c.mean_difference)?;
+    d.set_item("wins", c.wins)?;
+    d.set_item("losses", c.losses)?;
+    d.set_item("ties", c.ties)?;
+    d.set_item("t_statistic", c.t_statistic)?;
+    d.set_item("p_value", c.p_value)?;
+    d.set_item("sign_p_value", c.sign_p_value)?;
+    Ok(d)
+}
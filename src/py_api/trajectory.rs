@@ -0,0 +1,85 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyTuple};
+
+use super::{board_to_py, direction_to_py, parse_direction, parse_state};
+use crate::trajectory::{self, Transition};
+
+/// Records `(state, action, reward, next_state, done, goal)` transitions.
+///
+/// Args:
+///     goal: Optional target tile. When set, transitions are goal-conditioned:
+///           reward is 1.0 on the move that first reaches the tile, else 0.0,
+///           and the episode ends there. When unset, reward is the score delta.
+///     hindsight: Also emit every finished episode relabelled with the max
+///                tile it actually achieved as the goal.
+#[pyclass(module = "akioi_2048")]
+pub struct TrajectoryRecorder {
+    inner: trajectory::TrajectoryRecorder,
+}
+
+#[pymethods]
+impl TrajectoryRecorder {
+    #[new]
+    #[pyo3(signature = (goal=None, hindsight=false))]
+    fn new(goal: Option<i32>, hindsight: bool) -> Self {
+        Self {
+            inner: trajectory::TrajectoryRecorder::new(goal, hindsight),
+        }
+    }
+
+    /// Record one move as returned by ``step``.
+    ///
+    /// The episode is finished automatically once ``state`` is not
+    /// ``State.Continue``.
+    fn record(
+        &mut self,
+        board: [[i32; 4]; 4],
+        direction: &Bound<'_, PyAny>,
+        delta: i32,
+        next_board: [[i32; 4]; 4],
+        state: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let dir = parse_direction(direction)?;
+        let state = parse_state(state)?;
+        self.inner.record(board, dir, delta, next_board, state);
+        Ok(())
+    }
+
+    /// Finish the current episode early (e.g. when truncated by a move limit).
+    fn finish_episode(&mut self) {
+        self.inner.finish_episode();
+    }
+
+    /// Return all emitted transitions as tuples.
+    fn transitions<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyTuple>>> {
+        self.inner
+            .transitions()
+            .iter()
+            .map(|t| transition_to_py(py, t))
+            .collect()
+    }
+
+    /// Drop all emitted transitions and the current episode.
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.transitions().len()
+    }
+}
+
+pub(crate) fn transition_to_py<'py>(
+    py: Python<'py>,
+    t: &Transition,
+) -> PyResult<Bound<'py, PyTuple>> {
+    (
+        board_to_py(&t.state),
+        direction_to_py(py, t.action)?,
+        t.reward,
+        board_to_py(&t.next_state),
+        t.done,
+        t.goal,
+    )
+        .into_pyobject(py)
+}
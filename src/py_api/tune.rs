@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyAny, PyDict};
+
+use super::progress::{CancelToken, Watch};
+use super::rules::rules_or_named;
+use crate::tune::{Checkpoints, Method, TuneOptions};
+
+/// Tune the weights of the built-in evaluation heuristics by self-play.
+///
+/// Every candidate weight vector plays the same ``n_games`` seeds with a
+/// greedy expectimax agent ``depth`` moves deep, scored by its mean game
+/// score; the whole loop runs natively on all cores, without the GIL.
+///
+/// Args:
+///     n_games: Games each candidate plays.
+///     generations: CMA-ES generations, or annealing steps.
+///     method: ``"cma-es"`` or ``"annealing"``.
+///     depth: Search depth of the self-play agent.
+///     seed: Seed of the games and of the optimizer.
+///     max_moves: Stop each game after this many moves.
+///     population: Candidates per CMA-ES generation (default 9).
+///     sigma: Initial step size, in weight units.
+///     rules: ``Rules``, a preset name or a rules tag.
+///     progress: Called as ``progress(done, total)`` with the self-play
+///               games played so far, from the calling thread.
+///     cancel: A ``CancelToken`` that stops the run.
+///     checkpoint: Path of a checkpoint file. The run saves its whole state
+///                 there as it goes, and resumes from it if it exists, ending
+///                 exactly as an uninterrupted run would.
+///     checkpoint_every: Save after every this many generations, and after
+///                       the last one.
+///
+/// Ctrl-C stops the run too, raising ``KeyboardInterrupt``.
+///
+/// Returns:
+///     dict: ``weights`` (the best weight per feature, as in ``hint``'s
+///     ``factors``), their mean ``score``, ``history`` (the best mean score
+///     after each generation) and ``evaluations`` (candidates evaluated,
+///     starting from the default weights).
+///
+/// Raises:
+///     CancelledError: If ``cancel`` was cancelled.
+///     ValueError: For a checkpoint that cannot be read or written, is
+///                 damaged or was saved by a run with other arguments.
+#[pyfunction]
+#[pyo3(signature = (
+    n_games,
+    generations,
+    method="cma-es",
+    depth=1,
+    seed=0,
+    max_moves=None,
+    population=None,
+    sigma=0.3,
+    rules=None,
+    progress=None,
+    cancel=None,
+    checkpoint=None,
+    checkpoint_every=1,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn tune_heuristics<'py>(
+    py: Python<'py>,
+    n_games: usize,
+    generations: usize,
+    method: &str,
+    depth: u32,
+    seed: u64,
+    max_moves: Option<usize>,
+    population: Option<usize>,
+    sigma: f64,
+    rules: Option<&Bound<'py, PyAny>>,
+    progress: Option<&Bound<'py, PyAny>>,
+    cancel: Option<&CancelToken>,
+    checkpoint: Option<PathBuf>,
+    checkpoint_every: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    let method = Method::from_name(method)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown tuning method: {method}")))?;
+    let options = TuneOptions {
+        method,
+        rules: rules_or_named(rules)?,
+        depth,
+        seed,
+        max_moves,
+        population,
+        sigma,
+    };
+    let checkpoints = checkpoint.as_deref().map(|path| Checkpoints {
+        path,
+        every: checkpoint_every,
+    });
+    let tuned = Watch::new(progress, cancel)?
+        .run(py, |progress| {
+            crate::tune::tune_resumable(
+                n_games,
+                generations,
+                &options,
+                checkpoints.as_ref(),
+                progress,
+            )
+        })?
+        .map_err(PyValueError::new_err)?;
+    let weights = PyDict::new(py);
+    for (name, weight) in tuned.weights.named() {
+        weights.set_item(name, weight)?;
+    }
+    let d = PyDict::new(py);
+    d.set_item("weights", weights)?;
+    d.set_item("score", tuned.score)?;
+    d.set_item("history", tuned.history)?;
+    d.set_item("evaluations", tuned.evaluations)?;
+    Ok(d)
+}
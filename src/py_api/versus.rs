@@ -0,0 +1,150 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyAny;
+
+use super::errors::game_finished;
+use super::rules::{Rules, rules_or_default};
+use super::{board_to_py, parse_direction};
+use crate::versus::{self, Attack, Standing};
+
+/// Two-player versus match: both players move at once, and big merges on one
+/// board drop attack tiles onto the other.
+///
+/// Args:
+///     seed: Match seed; random when omitted. Both players start from the
+///           same board.
+///     rules: Optional ``Rules`` for both boards.
+///     attack_tile: Tile an attack drops on the opponent's board, e.g. ``-1``
+///                  (a x1 multiplier that only gets in the way) or ``2``.
+///     attack_min_merge: Every merge into a tile of at least this value
+///                       sends one attack.
+///     max_attacks: Most attack tiles one move can send.
+///
+/// A player with no move left loses; one reaching 65536 wins.
+///
+/// Raises:
+///     ValueError: For an attack tile that is invalid under ``rules``.
+#[pyclass(module = "akioi_2048")]
+pub struct Arena2P {
+    inner: versus::Arena2P,
+}
+
+/// One player's part of an ``Arena2P.step``.
+///
+/// Attributes:
+///     board: Board after the move, its spawn and the attacks received.
+///     delta: Score gained.
+///     moved: Whether the move changed the board; others count as a pass.
+///     sent: Attack tiles this move sent to the opponent.
+///     received: ``(row, col)`` cells the opponent's attacks landed on;
+///               attacks beyond the empty cells are lost.
+#[pyclass(module = "akioi_2048", frozen, get_all)]
+pub struct VersusStep {
+    board: Vec<Vec<i32>>,
+    delta: i32,
+    moved: bool,
+    sent: usize,
+    received: Vec<(usize, usize)>,
+}
+
+#[pymethods]
+impl VersusStep {
+    fn __repr__(&self) -> String {
+        format!(
+            "VersusStep(delta={}, moved={}, sent={}, received={:?})",
+            self.delta, self.moved, self.sent, self.received
+        )
+    }
+}
+
+#[pymethods]
+impl Arena2P {
+    #[new]
+    #[pyo3(signature = (seed=None, rules=None, attack_tile=-1, attack_min_merge=16, max_attacks=2))]
+    fn new(
+        seed: Option<u64>,
+        rules: Option<&Rules>,
+        attack_tile: i32,
+        attack_min_merge: i32,
+        max_attacks: usize,
+    ) -> PyResult<Self> {
+        let attack = Attack {
+            tile: attack_tile,
+            min_merge: attack_min_merge,
+            max_per_move: max_attacks,
+        };
+        let inner = versus::Arena2P::new(seed, rules_or_default(rules), attack)
+            .map_err(PyValueError::new_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Apply one move per player.
+    ///
+    /// Args:
+    ///     moves: ``(player_0, player_1)``, each a ``Direction`` or action id.
+    ///
+    /// Raises:
+    ///     ValueError: Unless there are exactly two moves.
+    ///     GameFinishedError: Once the match is over.
+    fn step(&mut self, moves: Vec<Bound<'_, PyAny>>) -> PyResult<(VersusStep, VersusStep)> {
+        let [a, b] = moves.as_slice() else {
+            return Err(PyValueError::new_err(format!(
+                "expected 2 moves, got {}",
+                moves.len()
+            )));
+        };
+        let moves = [parse_direction(a)?, parse_direction(b)?];
+        if self.inner.standing() != Standing::Playing {
+            return Err(game_finished("the match is over"));
+        }
+        let steps = self.inner.step(moves).map_err(PyValueError::new_err)?;
+        let [a, b] = steps.map(|s| VersusStep {
+            board: board_to_py(&s.board),
+            delta: s.delta,
+            moved: s.moved,
+            sent: s.sent,
+            received: s.received,
+        });
+        Ok((a, b))
+    }
+
+    /// Both boards, player 0 first.
+    #[getter]
+    fn boards(&self) -> Vec<Vec<Vec<i32>>> {
+        self.inner.boards().iter().map(board_to_py).collect()
+    }
+
+    #[getter]
+    fn scores(&self) -> (i64, i64) {
+        let [a, b] = self.inner.scores();
+        (a, b)
+    }
+
+    /// Whether the match is decided.
+    #[getter]
+    fn is_over(&self) -> bool {
+        self.inner.standing() != Standing::Playing
+    }
+
+    /// ``0`` or ``1`` once a player won, ``None`` while playing or after a
+    /// draw.
+    #[getter]
+    fn winner(&self) -> Option<usize> {
+        match self.inner.standing() {
+            Standing::Won(player) => Some(player),
+            Standing::Playing | Standing::Draw => None,
+        }
+    }
+
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.inner.seed()
+    }
+
+    #[getter]
+    fn rules(&self) -> Rules {
+        Rules {
+            inner: self.inner.rules(),
+        }
+    }
+}
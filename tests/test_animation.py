@@ -0,0 +1,49 @@
+import akioi_2048 as ak
+
+
+def paths(moves: list) -> list[tuple]:
+    return [(m.source, m.target, m.value, m.merged) for m in moves]
+
+
+def test_animation_tracks_every_tile() -> None:
+    board = [
+        [2, 0, 2, 4],
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+        [0, 0, 0, -2],
+    ]
+    result, moves = ak.step_with_animation(board, ak.Direction.Left)
+    assert paths(moves) == [
+        ((0, 0), (0, 0), 2, True),
+        ((0, 2), (0, 0), 2, True),
+        ((0, 3), (0, 1), 4, False),
+        ((3, 3), (3, 0), -2, False),
+    ]
+    assert result.board[0][:2] == [4, 4]
+    assert result.spawn is not None
+
+
+def test_animation_for_number_multiplier_merge() -> None:
+    board = [
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+        [2, 0, 0, 0],
+        [-2, 0, 0, 0],
+    ]
+    _, moves = ak.step_with_animation(board, ak.Direction.Down)
+    assert paths(moves) == [
+        ((2, 0), (3, 0), 2, True),
+        ((3, 0), (3, 0), -2, True),
+    ]
+
+
+def test_animation_of_invalid_move_is_static() -> None:
+    board = [
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+        [8, 0, 0, 0],
+    ]
+    result, moves = ak.step_with_animation(board, ak.Direction.Down)
+    assert result.moved is False
+    assert paths(moves) == [((3, 0), (3, 0), 8, False)]
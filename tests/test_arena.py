@@ -0,0 +1,117 @@
+import os
+import random
+import time
+
+import pytest
+
+import akioi_2048 as ak
+
+np = pytest.importorskip("numpy")
+
+
+def test_arena_observations_are_stacked_boards():
+    arena = ak.Arena(3, seed=1)
+    obs = arena.reset()
+    assert len(arena) == 3
+    assert obs.shape == (3, 4, 4)
+    assert obs.dtype == "int32"
+    for board in obs.tolist():
+        assert sum(1 for row in board for v in row if v != 0) == 2
+
+
+def test_arena_matches_across_equal_seeds():
+    a, b = ak.Arena(4, seed=5), ak.Arena(4, seed=5)
+    actions = [0, 1, ak.Direction.Up, 3]
+    for _ in range(20):
+        obs_a, rewards_a, dones_a = a.step(actions)
+        obs_b, rewards_b, dones_b = b.step(actions)
+        assert obs_a.tolist() == obs_b.tolist()
+        assert rewards_a.tolist() == rewards_b.tolist()
+        assert dones_a.tolist() == dones_b.tolist()
+    assert a.scores.tolist() == b.scores.tolist()
+    assert a.scores.dtype == "int64"
+
+
+def test_arena_auto_resets_finished_games():
+    arena = ak.Arena(2, seed=3)
+    finished = 0
+    for i in range(5000):
+        obs, rewards, dones = arena.step([i % 4, (i + 1) % 4])
+        for env, done in enumerate(dones.tolist()):
+            if done:
+                finished += 1
+                final = arena.final_observations.tolist()[env]
+                assert final != obs.tolist()[env]
+                assert sum(1 for row in obs.tolist()[env] for v in row if v != 0) == 2
+        if finished >= 2:
+            break
+    assert finished >= 2
+
+
+def test_arena_reset_selected_envs():
+    arena = ak.Arena(3, seed=2)
+    for _ in range(10):
+        arena.step([0, 0, 0])
+    before = arena.observations.tolist()
+    after = arena.reset([1]).tolist()
+    assert after[0] == before[0]
+    assert after[2] == before[2]
+    assert arena.scores.tolist()[1] == 0
+
+
+def test_arena_rejects_bad_actions():
+    arena = ak.Arena(2, seed=0)
+    with pytest.raises(ValueError, match="expected 2 actions"):
+        arena.step([0])
+    with pytest.raises(ValueError, match="action id"):
+        arena.step([0, 4])
+    with pytest.raises(ValueError, match="out of range"):
+        arena.reset([2])
+
+
+def test_arena_runs_do_not_depend_on_threads():
+    arenas = [ak.Arena(600, seed=4, threads=n) for n in [1, 4]]
+    assert [a.threads for a in arenas] == [1, 4]
+    rng = random.Random(0)
+    for _ in range(30):
+        actions = [rng.randrange(4) for _ in range(600)]
+        one, four = (a.step(actions) for a in arenas)
+        for x, y in zip(one, four):
+            assert x.tolist() == y.tolist()
+    assert arenas[0].stats.games == arenas[1].stats.games
+
+
+def test_arena_keeps_its_threads_between_steps():
+    if not os.path.isdir("/proc/self/task"):
+        pytest.skip("counting threads needs /proc")
+
+    def threads():
+        return len(os.listdir("/proc/self/task"))
+
+    before = threads()
+    arena = ak.Arena(600, seed=2, threads=4)
+    # up to four workers, fewer on machines with fewer cores
+    workers = threads() - before
+    assert 0 <= workers <= 4
+    for i in range(20):
+        arena.step([i % 4] * 600)
+        assert threads() == before + workers
+    del arena
+    # the workers end on their own once the arena lets go of them
+    for _ in range(100):
+        if threads() == before:
+            break
+        time.sleep(0.01)
+    assert threads() == before
+
+
+def test_env_games_do_not_depend_on_other_resets():
+    a, b = ak.Arena(3, seed=9), ak.Arena(3, seed=9)
+    for _ in range(3):
+        b.reset([2])
+    obs_a = a.reset([0, 1]).tolist()
+    obs_b = b.reset([0, 1]).tolist()
+    assert obs_a[:2] == obs_b[:2]
+    obs_a = a.step([ak.Direction.Up] * 3)[0].tolist()
+    obs_b = b.step([ak.Direction.Up] * 3)[0].tolist()
+    assert obs_a[:2] == obs_b[:2]
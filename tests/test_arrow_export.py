@@ -0,0 +1,144 @@
+import itertools
+import struct
+from pathlib import Path
+
+import pytest
+
+import akioi_2048 as ak
+
+
+class Table:
+    """A flatbuffers table, read field by field through its vtable."""
+
+    def __init__(self, buf: bytes, pos: int) -> None:
+        self.buf, self.pos = buf, pos
+        self.vtable = pos - struct.unpack_from("<i", buf, pos)[0]
+
+    def slot(self, field: int) -> int:
+        size = struct.unpack_from("<H", self.buf, self.vtable)[0]
+        if 4 + 2 * field >= size:
+            return 0
+        return struct.unpack_from("<H", self.buf, self.vtable + 4 + 2 * field)[0]
+
+    def scalar(self, field: int, fmt: str, default: int = 0) -> int:
+        slot = self.slot(field)
+        if not slot:
+            return default
+        return struct.unpack_from(fmt, self.buf, self.pos + slot)[0]
+
+    def ref(self, field: int) -> int:
+        at = self.pos + self.slot(field)
+        return at + struct.unpack_from("<I", self.buf, at)[0]
+
+    def table(self, field: int) -> "Table":
+        return Table(self.buf, self.ref(field))
+
+    def string(self, field: int) -> str:
+        at = self.ref(field)
+        n = struct.unpack_from("<I", self.buf, at)[0]
+        return self.buf[at + 4 : at + 4 + n].decode()
+
+    def tables(self, field: int) -> list["Table"]:
+        at = self.ref(field)
+        n = struct.unpack_from("<I", self.buf, at)[0]
+        slots = [at + 4 + 4 * i for i in range(n)]
+        offsets = [s + struct.unpack_from("<I", self.buf, s)[0] for s in slots]
+        return [Table(self.buf, at) for at in offsets]
+
+    def structs(self, field: int, fmt: str) -> list[tuple[int, ...]]:
+        at = self.ref(field)
+        n = struct.unpack_from("<I", self.buf, at)[0]
+        size = struct.calcsize(fmt)
+        assert (at + 4) % 8 == 0
+        return [struct.unpack_from(fmt, self.buf, at + 4 + size * i) for i in range(n)]
+
+
+def root(buf: bytes) -> Table:
+    return Table(buf, struct.unpack_from("<I", buf, 0)[0])
+
+
+def read_arrow(path: Path) -> dict[str, list]:
+    data = path.read_bytes()
+    assert data[:6] == b"ARROW1" and data[-6:] == b"ARROW1"
+    footer_len = struct.unpack_from("<i", data, len(data) - 10)[0]
+    footer = root(data[len(data) - 10 - footer_len : len(data) - 10])
+    fields = footer.table(1).tables(1)
+    [(offset, meta_len, body_len)] = footer.structs(3, "<qi4xq")
+    assert offset % 8 == 0 and meta_len % 8 == 0 and body_len % 8 == 0
+    assert struct.unpack_from("<Ii", data, offset) == (0xFFFFFFFF, meta_len - 8)
+    message = root(data[offset + 8 : offset + meta_len])
+    assert message.scalar(1, "<B") == 3
+    batch = message.table(2)
+    rows = batch.scalar(0, "<q")
+    nodes = batch.structs(1, "<qq")
+    buffers = iter(batch.structs(2, "<qq"))
+    body = data[offset + meta_len : offset + meta_len + body_len]
+    columns = {}
+    for field, (length, nulls) in zip(fields, nodes):
+        assert (length, nulls) == (rows, 0)
+        kind = field.scalar(2, "<B")
+        next(buffers)  # validity
+        start, size = next(buffers)
+        raw = body[start : start + size]
+        if kind == 2:
+            ty = field.table(3)
+            fmt = {(32, 1): "i", (64, 1): "q", (64, 0): "Q"}[
+                (ty.scalar(0, "<i"), ty.scalar(1, "<B"))
+            ]
+            values = list(struct.unpack(f"<{rows}{fmt}", raw))
+        elif kind == 6:
+            values = [bool(raw[i // 8] >> (i % 8) & 1) for i in range(rows)]
+        else:
+            assert kind == 5
+            offsets = struct.unpack(f"<{rows + 1}i", raw)
+            start, size = next(buffers)
+            text = body[start : start + size]
+            values = [text[a:b].decode() for a, b in itertools.pairwise(offsets)]
+        columns[field.string(0)] = values
+    return columns
+
+
+def test_games_table_matches_the_scores(tmp_path: Path) -> None:
+    path = tmp_path / "games.arrow"
+    result = ak.tournament(
+        ["random", "random"], games_per_agent=4, seed=3, games_path=path
+    )
+    games = read_arrow(path)
+    assert list(games) == ["agent", "game", "seed", "score", "moves", "max_tile", "won"]
+    assert games["agent"] == [0] * 4 + [1] * 4
+    assert games["game"] == [0, 1, 2, 3] * 2
+    assert games["score"] == result["scores"][0] + result["scores"][1]
+    assert games["seed"][:4] == games["seed"][4:]
+    assert max(games["max_tile"]) == max(a["max_tile"] for a in result["agents"])
+
+
+def test_moves_table_adds_up_to_the_games(tmp_path: Path) -> None:
+    order = itertools.cycle([ak.Direction.Down, ak.Direction.Left, ak.Direction.Right])
+    path = tmp_path / "moves.arrow"
+    for agents in [["random"], [lambda board: next(order)]]:
+        result = ak.tournament(agents, games_per_agent=3, seed=1, moves_path=path)
+        moves = read_arrow(path)
+        assert set(moves["direction"]) <= {"Down", "Right", "Up", "Left"}
+        assert [f"cell_{i}" for i in range(16)] == list(moves)[7:]
+        for game, score in enumerate(result["scores"][0]):
+            rows = [i for i, g in enumerate(moves["game"]) if g == game]
+            assert moves["move"][rows[0]] == 0
+            assert moves["score"][rows[-1]] == score
+            assert sum(moves["delta"][i] for i in rows) == score
+
+
+def test_parquet_paths_get_parquet_files(tmp_path: Path) -> None:
+    for name in ["games.parquet", "games.PARQUET"]:
+        path = tmp_path / name
+        ak.tournament(["random"], games_per_agent=2, seed=3, games_path=path)
+        data = path.read_bytes()
+        assert data[:4] == b"PAR1" and data[-4:] == b"PAR1"
+
+
+def test_parquet_tables_match_the_arrow_ones(tmp_path: Path) -> None:
+    pq = pytest.importorskip("pyarrow.parquet")
+    for kind in ["games_path", "moves_path"]:
+        arrow, parquet = tmp_path / "t.arrow", tmp_path / "t.parquet"
+        for path in [arrow, parquet]:
+            ak.tournament(["random"], games_per_agent=2, seed=5, **{kind: path})
+        assert pq.read_table(parquet).to_pydict() == read_arrow(arrow)
@@ -0,0 +1,58 @@
+import pytest
+
+import akioi_2048 as ak
+
+THREES = ak.Rules(variant="classic", base=3)
+HEX = ak.Rules(variant="classic", base=16)
+
+
+def row(*tiles: int) -> list[list[int]]:
+    return [list(tiles)] + [[0] * 4] * 3
+
+
+def test_tag_and_validation() -> None:
+    assert ak.Rules.preset("classic").base == 2
+    assert THREES.tag == "akioi-v2+variant=classic+base=3"
+    assert "base=3" in repr(THREES)
+    for base in [1, 17]:
+        with pytest.raises(ValueError, match="base must be from 2 to 16"):
+            ak.Rules(variant="classic", base=base)
+    with pytest.raises(ValueError, match="classic variant"):
+        ak.Rules(base=3)
+
+
+def test_digits_merge_up_to_the_base() -> None:
+    after, delta, _ = ak.step(row(3, 3, 6, 3), ak.Direction.Left, rules=THREES)
+    assert after[0][:2] == [6, 9]
+    assert delta == 15
+    after, delta, _ = ak.step(row(6, 6, 9, 18), ak.Direction.Left, rules=THREES)
+    assert after[0][:3] == [6, 6, 27]
+    assert delta == 27
+    after, _, _ = ak.step(row(16, 32, 48, 0), ak.Direction.Left, rules=HEX)
+    assert after[0][:2] == [48, 48]
+
+
+def test_tiles_spawns_and_victory() -> None:
+    for tile in [3, 6, 9, 18, 39366, 59049]:
+        ak.validate(row(tile, 0, 0, 0), rules=THREES)
+    for tile in [1, 2, 4, 12, 65536, 2 * 59049]:
+        with pytest.raises(ValueError):
+            ak.validate(row(tile, 0, 0, 0), rules=THREES)
+    assert ak.spawn_probabilities(rules=THREES) == {3: 0.9, 6: 0.1}
+    assert ak.spawn_probabilities(rules=HEX) == {16: 0.9, 32: 0.1}
+    after, _, state = ak.step(row(19683, 39366, 0, 0), ak.Direction.Left, rules=THREES)
+    assert after[0][0] == 59049
+    assert state == ak.State.Victory
+
+
+def test_play_matches_the_reference() -> None:
+    for rules in [THREES, HEX]:
+        board = ak.init(rules=rules)
+        directions = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right]
+        for k in range(200):
+            direction = directions[k % 3]
+            after, _, state = ak.step(board, direction, rules=rules)
+            assert ak.verify_step(board, direction, after, rules=rules)
+            if state == ak.State.GameOver:
+                break
+            board = after
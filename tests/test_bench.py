@@ -0,0 +1,20 @@
+import akioi_2048 as ak
+from akioi_2048 import bench
+
+
+def test_benchmark_reports_every_native_case() -> None:
+    results = ak.benchmark(0.01)
+    names = [r["name"] for r in results]
+    assert names == ["step", "step_detailed", "step_with_animation", "game", "agent:random"]
+    for r in results:
+        assert r["steps"] > 0
+        assert r["steps_per_second"] > 0
+
+
+def test_bench_table_lists_python_cases() -> None:
+    results = bench.python_cases(0.01)
+    table = bench.format_table(results)
+    assert "python:step" in table
+    assert "python:Game.step" in table
+    assert "python:Game.step_in_place" in table
+    assert table.splitlines()[0].split() == ["case", "steps", "steps/s"]
@@ -0,0 +1,103 @@
+from pathlib import Path
+
+import pytest
+
+import akioi_2048 as ak
+
+CYCLE = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right, ak.Direction.Up]
+
+
+def play(game: ak.Game, moves: int) -> None:
+    for i in range(moves):
+        if game.state != ak.State.Continue:
+            return
+        game.step(CYCLE[i % 4])
+
+
+def test_save_and_load_round_trip(tmp_path: Path) -> None:
+    game = ak.Game(seed=11, rules=ak.Rules.preset("mercy"))
+    play(game, 300)
+    replay = game.replay()
+    path = tmp_path / "game.bin"
+    replay.save(path)
+    assert ak.Replay.load(path) == replay
+    # two bytes per move plus the header and its stamp
+    assert path.stat().st_size < 2 * len(replay) + 128
+
+
+def test_constructed_start_is_kept(tmp_path: Path) -> None:
+    board = [[2, 2, 0, 0], [0, 4, 0, 0], [0, 0, 0, 0], [0, 0, 0, 8]]
+    game = ak.Game.from_board(board, score=12, seed=4)
+    play(game, 20)
+    path = tmp_path / "start.bin"
+    game.replay().save(path)
+    loaded = ak.Replay.load(path)
+    assert loaded.start == (board, 12)
+    assert loaded.game().board == game.board
+
+
+def test_corrupt_logs_are_rejected(tmp_path: Path) -> None:
+    game = ak.Game(seed=2)
+    play(game, 40)
+    path = tmp_path / "game.bin"
+    game.replay().save(path)
+    data = path.read_bytes()
+    broken = tmp_path / "broken.bin"
+    for bad in [b"not a log", data[:-3], data[:20] + b"\x7f" + data[21:]]:
+        broken.write_bytes(bad)
+        with pytest.raises(ValueError):
+            ak.Replay.load(broken)
+
+
+def test_log_moves_streams_a_binary_log(tmp_path: Path) -> None:
+    game = ak.Game(seed=9)
+    play(game, 5)
+    path = tmp_path / "stream.bin"
+    game.log_moves(path, binary=True)
+    play(game, 10)
+    # readable while still being written
+    assert ak.Replay.load(path) == game.replay()
+    game.log_moves(None)
+    play(game, 10)
+    game.log_moves(path, append=True, binary=True)
+    play(game, 10)
+    assert ak.Replay.load(path) == game.replay()
+
+
+def test_appending_to_another_games_log_fails(tmp_path: Path) -> None:
+    path = tmp_path / "other.bin"
+    other = ak.Game(seed=1)
+    play(other, 8)
+    other.replay().save(path)
+    game = ak.Game(seed=2)
+    with pytest.raises(ValueError):
+        game.log_moves(path, append=True, binary=True)
+
+
+def test_record_writes_the_game_as_it_is_played(tmp_path: Path) -> None:
+    path = tmp_path / "run.akr"
+    recording = ak.record(path, seed=4, rules=ak.Rules.preset("classic"))
+    assert not path.exists()
+    with recording as game:
+        play(game, 20)
+        # moves are on disk before the block ends
+        assert ak.Replay.load(path).moves == game.replay().moves
+        token = game.snapshot()
+        play(game, 10)
+        game.restore(token)
+        play(game, 3)
+    assert recording.game is game
+    recorded = game.replay()
+    assert ak.Replay.load(path) == recorded
+    # logging stops with the block
+    play(game, 8)
+    assert ak.Replay.load(path) == recorded
+
+
+def test_record_closes_the_log_when_the_block_raises(tmp_path: Path) -> None:
+    path = tmp_path / "crash.akr"
+    with pytest.raises(RuntimeError):
+        with ak.record(path, seed=1) as game:
+            play(game, 15)
+            raise RuntimeError("bot crashed")
+    assert ak.Replay.load(path) == game.replay()
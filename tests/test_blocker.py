@@ -0,0 +1,89 @@
+import pytest
+
+import akioi_2048 as ak
+
+B = ak.BLOCKER
+RULES = ak.Rules(blockers=True)
+
+
+def slide(board, direction):
+    """The board after the move, without its spawn."""
+    result = ak.step_detailed(board, direction, RULES)
+    after = [r[:] for r in result.board]
+    if result.spawn is not None:
+        r, c = result.spawn.cell
+        after[r][c] = 0
+    return after
+
+
+def test_blocker_code() -> None:
+    assert B == -3
+    board = [[B, 0, 0, 0], [0] * 4, [0] * 4, [0] * 4]
+    ak.validate(board, rules=RULES)
+    assert RULES.blockers
+    assert RULES.tag.endswith("+blockers")
+
+
+def test_default_rules_reject_blockers() -> None:
+    board = [[B, 0, 0, 0], [0] * 4, [0] * 4, [2, 0, 0, 0]]
+    with pytest.raises(ValueError, match=r"^invalid tile value: -3$"):
+        ak.validate(board)
+    with pytest.raises(ValueError, match=r"^invalid tile value: -3$"):
+        ak.step(board, ak.Direction.Down)
+    with pytest.raises(ValueError, match="do not allow blockers"):
+        ak.init(blockers=1)
+
+
+def test_tiles_stop_against_blockers() -> None:
+    board = [[0, 2, B, 2], [2, 2, B, 0], [0] * 4, [0] * 4]
+    after = slide(board, ak.Direction.Left)
+    assert after[0][:3] == [2, 0, B]
+    assert after[1][:3] == [4, 0, B]
+    assert after[0][3] == 2
+    board = [[2, B, 2, 0], [0] * 4, [0] * 4, [0] * 4]
+    result = ak.step_detailed(board, ak.Direction.Left, RULES)
+    assert result.board[0][:3] == [2, B, 2]
+    assert not result.moved
+
+
+def test_blockers_never_merge_or_move() -> None:
+    board = [[B, B, 0, 0], [B, -1, 0, 0], [B, 4, 0, 0], [0] * 4]
+    for direction in [ak.Direction.Down, ak.Direction.Right, ak.Direction.Up]:
+        after = slide(board, direction)
+        assert [after[r][0] for r in range(3)] == [B, B, B]
+        assert after[0][1] == B
+    assert ak.step_detailed(board, ak.Direction.Left, RULES).merges == []
+
+
+def test_dead_boards_count_blockers() -> None:
+    board = [[B, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, B]]
+    assert ak.is_game_over(board, RULES)
+    board[0][0] = 2
+    assert not ak.is_game_over(board, RULES)
+
+
+def test_engine_matches_the_reference_with_blockers() -> None:
+    for seed in range(40):
+        board = ak.random_board(64, fill=0.6, seed=seed)
+        board[seed % 4][(seed // 4) % 4] = B
+        for direction in ak.Direction:
+            after, _, _ = ak.step(board, direction, RULES)
+            assert ak.verify_step(board, direction, after, RULES)
+
+
+def test_init_places_blockers() -> None:
+    for blockers in [0, 1, 5, 14]:
+        flat = [t for row in ak.init(blockers=blockers, rules=RULES) for t in row]
+        assert flat.count(B) == blockers
+        assert len([t for t in flat if t not in (0, B)]) == 2
+    with pytest.raises(ValueError, match="14 blockers"):
+        ak.init(blockers=15, rules=RULES)
+
+
+def test_render_and_pack_blockers() -> None:
+    board = ak.board_with({(0, 0): B, (3, 3): 2}, RULES)
+    assert "■" in ak.render(board)
+    assert "#" in ak.render(board, style="ascii")
+    assert ak.unpack(ak.pack(board)) == board
+    with pytest.raises(ValueError, match="8 bytes"):
+        ak.pack(board, size=8)
@@ -0,0 +1,43 @@
+import pytest
+
+import akioi_2048 as ak
+
+BLOCKERS = ak.Rules(blockers=True)
+
+
+def test_set_chains_and_builds_the_board() -> None:
+    builder = ak.BoardBuilder(rules=BLOCKERS)
+    builder.set(3, 0, 2048).set(3, 1, -2).set(0, 3, ak.BLOCKER)
+    assert builder.board == [[0, 0, 0, ak.BLOCKER], [0] * 4, [0] * 4, [2048, -2, 0, 0]]
+    assert builder.validate() == []
+    assert repr(builder) == "BoardBuilder('...#/..../..../2048x2..')"
+    assert builder.clear().board == [[0] * 4 for _ in range(4)]
+
+
+def test_set_rejects_illegal_tiles_and_cells() -> None:
+    builder = ak.BoardBuilder().set(0, 0, 4)
+    for value in (3, -8, 131072):
+        with pytest.raises(ak.InvalidBoardError, match="invalid tile"):
+            builder.set(1, 1, value)
+    with pytest.raises(IndexError):
+        builder.set(4, 0, 2)
+    assert builder.board[1][1] == 0
+
+    classic = ak.BoardBuilder(rules=ak.Rules.preset("classic"))
+    with pytest.raises(ak.InvalidBoardError):
+        classic.set(0, 0, -1)
+    with pytest.raises(ak.InvalidBoardError):
+        ak.BoardBuilder([[-1, 0, 0, 0], [0] * 4, [0] * 4, [0] * 4], classic.rules)
+
+
+def test_validate_warns_of_unreachable_boards() -> None:
+    builder = ak.BoardBuilder().set(0, 0, 65536).set(3, 3, 65536)
+    assert builder.validate() == ["2 tiles of 65536: a game is won at the first"]
+
+    assert builder.clear().validate() == ["no tiles: a spawn follows every move"]
+    [lone] = builder.set(1, 1, 8).validate()
+    assert lone.startswith("only 1 tile")
+
+    walled = ak.BoardBuilder([[ak.BLOCKER] * 4] * 4, BLOCKERS)
+    warnings = walled.validate()
+    assert "16 blockers leave no room for the two starting tiles" in warnings
@@ -0,0 +1,87 @@
+import pytest
+
+import akioi_2048 as ak
+
+BOMBS = ak.Rules(bombs=True)
+WALLED = ak.Rules(bombs=True, blockers=True)
+B, X = ak.BLOCKER, ak.BOMB
+
+
+def row(*tiles) -> list[list[int]]:
+    return [list(tiles), [0] * 4, [0] * 4, [0] * 4]
+
+
+def slide(board, direction, rules=BOMBS):
+    return ak.step_detailed(board, direction, rules=rules)
+
+
+def slid(board, direction, rules=BOMBS) -> list[list[int]]:
+    """The board after the move, without its spawn."""
+    result = slide(board, direction, rules)
+    after = [r[:] for r in result.board]
+    if result.spawn is not None:
+        r, c = result.spawn.cell
+        after[r][c] = 0
+    return after
+
+
+def test_bombs_need_the_rule() -> None:
+    assert X == -5
+    assert not ak.Rules().bombs
+    with pytest.raises(ValueError, match="invalid tile value: -5"):
+        ak.validate(row(X, 0, 0, 0))
+    ak.validate(row(X, 0, 0, 0), rules=BOMBS)
+    assert BOMBS.tag == "akioi-v2+bombs"
+    assert "bombs=True" in repr(BOMBS)
+
+
+def test_a_tile_against_a_bomb_clears_the_line() -> None:
+    board = [[X, 0, 8, 2], [4, 4, 0, 0], [0] * 4, [0] * 4]
+    result = slide(board, ak.Direction.Left)
+    assert result.moved
+    assert result.delta == 8
+    assert slid(board, ak.Direction.Left)[:2] == [[0] * 4, [8, 0, 0, 0]]
+    column = [[0, 0, 0, 0], [2, 0, 0, 0], [0] * 4, [X, 0, 0, 0]]
+    assert slid(column, ak.Direction.Down) == [[0] * 4] * 4
+
+
+def test_merges_pair_up_before_the_bomb() -> None:
+    result = slide(row(2, 2, X, 0), ak.Direction.Left)
+    assert result.delta == 4
+    assert slid(row(2, 2, X, 0), ak.Direction.Left)[0] == [4, X, 0, 0]
+
+
+def test_blockers_survive_and_shield() -> None:
+    assert slid(row(X, 2, B, 4), ak.Direction.Left, WALLED)[0] == [0, 0, B, 0]
+    shielded = slide(row(X, B, 2, 0), ak.Direction.Left, WALLED)
+    assert shielded.board[0][:3] == [X, B, 2]
+    assert not shielded.moved
+
+
+def test_two_bombs_go_off_and_lone_bombs_slide() -> None:
+    assert slid(row(0, X, 0, X), ak.Direction.Left)[0] == [0] * 4
+    assert slid(row(0, 0, X, 0), ak.Direction.Left)[0] == [X, 0, 0, 0]
+    unarmed = ak.Rules(bombs=False)
+    after, _, _ = ak.step(row(X, 2, 0, 0), ak.Direction.Left, unarmed, strict=False)
+    assert after[0][0] == 2 * 5
+
+
+def test_engine_matches_the_reference_with_bombs() -> None:
+    for seed in range(40):
+        board = ak.random_board(64, fill=0.6, seed=seed)
+        board[seed % 4][(seed // 4) % 4] = X
+        board[(seed // 4) % 4][(seed + 1) % 4] = B
+        for direction in ak.Direction:
+            after, _, _ = ak.step(board, direction, rules=WALLED)
+            assert ak.verify_step(board, direction, after, rules=WALLED)
+
+
+def test_games_keep_the_bomb_rule() -> None:
+    board = ak.board_with({(3, 0): X, (3, 3): 2}, rules=BOMBS)
+    game = ak.Game.from_board(board, seed=2, rules=BOMBS)
+    game.step(ak.Direction.Left)
+    assert X not in game.board[3]
+    parsed = ak.Replay.parse(game.replay().to_text())
+    assert parsed.rules == BOMBS
+    assert ak.unpack(ak.pack(board)) == board
+    assert "✱" in ak.render(board)
@@ -0,0 +1,71 @@
+import os
+import pathlib
+import subprocess
+import sys
+
+import akioi_2048 as ak
+
+
+def run_cli(*args, keys=""):
+    env = dict(os.environ, PYTHONPATH=str(pathlib.Path(ak.__file__).parent.parent))
+    return subprocess.run(
+        [sys.executable, "-m", "akioi_2048", *args],
+        input=keys,
+        capture_output=True,
+        text=True,
+        env=env,
+        timeout=30,
+    )
+
+
+def test_cli_save_and_load_round_trip(tmp_path):
+    save = tmp_path / "game.save"
+    played = run_cli("--seed", "5", "--save", str(save), keys="s\nd\nv\nq\n")
+    assert played.returncode == 0, played.stderr
+    text = save.read_text()
+    stamp = ak.engine_stamp()
+    header = f"akioi-2048 replay 2\nrules akioi-v2\nstamp {stamp}\nseed 5\n"
+    assert text.startswith(header)
+
+    game = ak.Replay.parse(text).game()
+
+    loaded = run_cli("--load", str(save), keys="q\n")
+    assert loaded.returncode == 0, loaded.stderr
+    assert f"score {game.score}" in loaded.stdout
+
+
+def test_cli_undo_restores_previous_board():
+    out = run_cli("--seed", "9", keys="s\nu\nq\n").stdout
+    screens = out.split("akioi-2048  ")[1:]
+    assert len(screens) == 3
+    assert screens[0].strip() == screens[2].strip()
+    assert screens[0] != screens[1]
+
+
+def test_cli_rejects_unknown_rules():
+    result = run_cli("--rules", "chess")
+    assert result.returncode != 0
+    assert "unsupported rules tag" in result.stderr
+
+
+def test_cli_analyze_suggests_and_exports_annotated_line(tmp_path):
+    out = tmp_path / "line.save"
+    commands = f"suggest\npv 2\nbest\nmove sideways\nback\nbest\nexport {out}\nq\n"
+    result = run_cli(
+        "analyze", "--rules", "classic", "--depth", "1",
+        "2 2 . ./. . . ./. . . ./. . . 4",
+        keys=commands,
+    )
+    assert result.returncode == 0, result.stderr
+    assert "evaluation" in result.stdout
+    assert "1. " in result.stdout and "2. " in result.stdout
+    assert "error: unknown direction: sideways" in result.stdout
+
+    text = out.read_text()
+    assert "start 0 2 2 0 0 0 0 0 0 0 0 0 0 0 0 0 4\n" in text
+    notes = [line for line in text.splitlines() if line.startswith("#")]
+    assert len(notes) == 1
+    assert notes[0].startswith("# move 1 ")
+    replay = ak.Replay.parse(text)
+    assert len(replay.moves) == 1
+    assert replay.game().score == 4
@@ -0,0 +1,71 @@
+import hashlib
+
+import pytest
+
+import akioi_2048 as ak
+
+
+def played(seed: int = 4) -> ak.Game:
+    game = ak.Game(seed=seed)
+    for direction in [ak.Direction.Down, ak.Direction.Left] * 5:
+        game.step(direction)
+    return game
+
+
+def tampered(stamp: str) -> str:
+    fields = dict(field.split("=") for field in stamp.split())
+    fields["engine"] = "0.0.1"
+    fields["rules"] = "0" * 16
+    return " ".join(f"{name}={value}" for name, value in fields.items())
+
+
+def test_stamp_names_engine_and_hashes() -> None:
+    stamp = ak.engine_stamp()
+    engine, rules, spawns = stamp.split()
+    assert engine.startswith("engine=")
+    assert len(rules) == len("rules=") + 16
+    assert len(spawns) == len("spawns=") + 16
+    assert ak.engine_stamp(ak.Rules.preset("mercy")) != stamp
+    assert ak.check_stamp(stamp) == []
+    with pytest.raises(ValueError, match="merges differently"):
+        ak.check_stamp(stamp, rules=ak.Rules.preset("fib"))
+    with pytest.raises(ValueError, match="invalid compatibility stamp"):
+        ak.check_stamp("engine=1")
+
+
+def test_mismatched_replay_is_refused_unless_forced() -> None:
+    replay = played().replay()
+    text = replay.to_text()
+    stamp = ak.engine_stamp()
+    assert f"\nstamp {stamp}\n" in text
+    old = text.replace(stamp, tampered(stamp))
+    with pytest.raises(ValueError, match="force=True"):
+        ak.Replay.parse(old)
+    with pytest.warns(RuntimeWarning, match="engine 0.0.1"):
+        forced = ak.Replay.parse(old, force=True)
+    assert forced == replay
+    assert forced.digest() == replay.digest()
+
+
+def test_unstamped_version_1_text_still_parses() -> None:
+    replay = played().replay()
+    lines = replay.to_text().splitlines(keepends=True)
+    v1 = "akioi-2048 replay 1\n" + lines[1] + "".join(lines[3:])
+    assert ak.Replay.parse(v1) == replay
+    digest = hashlib.sha256(v1.encode()).hexdigest()
+    assert replay.digest() == digest
+
+
+def test_mismatched_book_is_refused_unless_forced(tmp_path) -> None:
+    path = tmp_path / "opening.book"
+    ak.OpeningBook.build(max_sum=6, depth=1).save(path)
+    data = path.read_bytes()
+    stamp = ak.engine_stamp().encode()
+    assert stamp in data
+    body = data[:-32].replace(stamp, tampered(stamp.decode()).encode())
+    path.write_bytes(body + hashlib.sha256(body).digest())
+    with pytest.raises(ValueError, match="opening book was made by"):
+        ak.OpeningBook.load(path)
+    with pytest.warns(RuntimeWarning, match="merges differently"):
+        book = ak.OpeningBook.load(path, force=True)
+    assert len(book) > 0
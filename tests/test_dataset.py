@@ -0,0 +1,70 @@
+import ast
+import struct
+import zipfile
+from pathlib import Path
+
+import pytest
+
+import akioi_2048 as ak
+
+ARRAYS = {"states", "actions", "rewards", "next_states", "dones", "goals", "stamp"}
+
+
+def npy_header(data: bytes) -> dict:
+    assert data[:6] == b"\x93NUMPY"
+    (length,) = struct.unpack("<H", data[8:10])
+    assert (10 + length) % 64 == 0
+    return ast.literal_eval(data[10 : 10 + length].decode("latin1"))
+
+
+def test_collect_random_dataset(tmp_path: Path) -> None:
+    out = tmp_path / "data.npz"
+    n = ak.collect_dataset("random", 2, out)
+    assert n > 0
+    with zipfile.ZipFile(out) as archive:
+        assert archive.testzip() is None
+        names = {name[: -len(".npy")] for name in archive.namelist()}
+        assert names == ARRAYS
+        states = npy_header(archive.read("states.npy"))
+        actions = npy_header(archive.read("actions.npy"))
+    assert states["shape"] == (n, 4, 4)
+    assert states["descr"] == "<i4"
+    assert actions["shape"] == (n,)
+
+
+def test_dataset_is_stamped_with_the_engine(tmp_path: Path) -> None:
+    out = tmp_path / "data.npz"
+    ak.collect_dataset("random", 1, out)
+    with zipfile.ZipFile(out) as archive:
+        data = archive.read("stamp.npy")
+    header = npy_header(data)
+    assert header["descr"] == "|u1"
+    (length,) = struct.unpack("<H", data[8:10])
+    stamp = data[10 + length :].decode()
+    assert header["shape"] == (len(stamp),)
+    assert stamp == ak.engine_stamp()
+    assert ak.check_stamp(stamp) == []
+
+
+def test_collect_with_callable_policy_and_truncation(tmp_path: Path) -> None:
+    calls = []
+
+    def policy(board: list[list[int]]) -> ak.Direction:
+        calls.append(board)
+        return ak.Direction.Left if len(calls) % 2 else ak.Direction.Down
+
+    n = ak.collect_dataset(policy, 3, tmp_path / "data.npz", max_moves=5)
+    assert n == len(calls) == 15
+
+
+def test_collect_hindsight_adds_relabelled_copy(tmp_path: Path) -> None:
+    n = ak.collect_dataset(
+        "random", 1, tmp_path / "data.npz", goal=65536, hindsight=True, max_moves=10
+    )
+    # the relabelled copy stops at the move that first reached the final max tile
+    assert 10 < n <= 20
+
+
+def test_collect_rejects_unknown_format(tmp_path: Path) -> None:
+    with pytest.raises(ValueError, match="unsupported dataset format: csv"):
+        ak.collect_dataset("random", 1, tmp_path / "data.csv", format="csv")
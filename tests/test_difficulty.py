@@ -0,0 +1,74 @@
+import pytest
+
+import akioi_2048 as ak
+
+NAMES = ["easy", "standard", "cruel", "adaptive"]
+
+
+def at(tile: int) -> list[list[int]]:
+    return ak.board_with({(0, 0): tile})
+
+
+def test_presets_and_tags() -> None:
+    assert ak.Rules().difficulty == "standard"
+    assert ak.Rules.preset("standard") == ak.Rules()
+    for name in NAMES:
+        rules = ak.Rules.preset(name)
+        assert rules == ak.Rules(difficulty=name)
+        assert rules.difficulty == name
+        assert f"difficulty='{name}'" in repr(rules)
+    assert ak.Rules.preset("cruel").tag == "akioi-v2+difficulty=cruel"
+    easy = ak.Rules(difficulty="easy", mercy=True)
+    assert easy.tag == "akioi-v2+mercy+difficulty=easy"
+    with pytest.raises(ValueError, match="unknown difficulty: hard"):
+        ak.Rules(difficulty="hard")
+
+
+def test_difficulties_shift_the_spawn_weights() -> None:
+    odds = {
+        name: ak.spawn_probabilities(rules=ak.Rules.preset(name)) for name in NAMES
+    }
+    assert odds["standard"] == ak.spawn_probabilities()
+    for table in odds.values():
+        assert list(table) == [2, 4, -1, -2]
+        assert sum(table.values()) == pytest.approx(1.0)
+    assert odds["easy"][-1] < odds["standard"][-1] < odds["cruel"][-1]
+    assert odds["easy"][4] > odds["standard"][4] > odds["cruel"][4]
+    numbers = [ak.expected_spawn_value(at(2), ak.Rules.preset(n)) for n in NAMES[:3]]
+    assert numbers[0] > numbers[1] > numbers[2]
+
+
+def test_adaptive_follows_the_largest_tile() -> None:
+    adaptive = ak.Rules.preset("adaptive")
+
+    def odds(tile, rules=adaptive):
+        return ak.spawn_probabilities(at(tile), rules=rules)
+
+    assert odds(2) == odds(128) == odds(2, ak.Rules.preset("easy"))
+    assert odds(1024) == odds(1024, ak.Rules())
+    assert odds(8192) == odds(65536) == odds(2, ak.Rules.preset("cruel"))
+    x1 = [odds(2**k)[-1] for k in range(7, 14)]
+    assert x1 == sorted(x1)
+    assert len(set(x1)) == len(x1)
+
+
+def test_spawns_follow_the_difficulty() -> None:
+    for name in ["easy", "cruel"]:
+        rules = ak.Rules.preset(name)
+        counts = {tile: 0 for tile in ak.spawn_probabilities(rules=rules)}
+        board = at(2)
+        for _ in range(4000):
+            result = ak.step_detailed(board, ak.Direction.Down, rules=rules)
+            counts[result.spawn.value] += 1
+        for tile, chance in ak.spawn_probabilities(board, rules=rules).items():
+            assert counts[tile] / 4000 == pytest.approx(chance, abs=0.03)
+
+
+def test_games_and_replays_keep_the_difficulty() -> None:
+    rules = ak.Rules.preset("cruel")
+    game = ak.Game(seed=9, rules=rules)
+    for move in [ak.Direction.Left, ak.Direction.Down] * 10:
+        game.step(move)
+    parsed = ak.Replay.parse(game.replay().to_text())
+    assert parsed.rules == rules
+    assert parsed.game().board == game.board
@@ -0,0 +1,29 @@
+import akioi_2048 as ak
+
+BOARD = [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [2, 2, 0, 0]]
+
+
+def test_direction_values_are_action_ids():
+    assert [int(d) for d in ak.Direction] == [0, 1, 2, 3]
+    assert [d.name for d in ak.Direction] == ["Down", "Right", "Up", "Left"]
+    assert ak.Direction.LEFT is ak.Direction.Left
+    assert ak.Direction(3) is ak.Direction.Left
+
+
+def test_step_accepts_ids_and_names():
+    for direction in ak.Direction:
+        spellings = [direction, int(direction), direction.name, direction.name.upper()]
+        slides = [ak.step_detailed(BOARD, d).merges for d in spellings]
+        assert all(s == slides[0] for s in slides)
+        _, delta, _ = ak.step(BOARD, direction.name.lower())
+        sideways = direction in (ak.Direction.Left, ak.Direction.Right)
+        assert delta == (4 if sideways else 0)
+
+
+def test_game_step_accepts_ids_and_names():
+    games = [ak.Game(seed=5) for _ in range(3)]
+    for move in range(4):
+        games[0].step(ak.Direction(move))
+        games[1].step(move)
+        games[2].step(ak.Direction(move).name.lower())
+    assert games[0].board == games[1].board == games[2].board
@@ -0,0 +1,50 @@
+import pytest
+
+import akioi_2048 as ak
+
+np = pytest.importorskip("numpy")
+
+BOARD = [[2, 0, 0, 65536], [0, -2, 0, 0], [0, 0, -1, 0], [0, 0, 0, -4]]
+
+
+def test_encode_onehot_planes():
+    planes = ak.encode(BOARD)
+    assert planes.shape == (20, 4, 4)
+    assert planes.dtype == "float32"
+    planes = planes.tolist()
+    assert planes[1][0][0] == 1.0  # 2
+    assert planes[16][0][3] == 1.0  # 65536
+    assert planes[17][2][2] == 1.0  # x1
+    assert planes[18][1][1] == 1.0  # x2
+    assert planes[19][3][3] == 1.0  # x4
+    assert planes[0][0][1] == 1.0  # empty
+    for r in range(4):
+        for c in range(4):
+            assert sum(plane[r][c] for plane in planes) == 1.0
+
+
+def test_encode_log2_and_raw():
+    log2 = ak.encode(BOARD, scheme="log2").tolist()
+    assert log2[0][0] == [1.0, 0.0, 0.0, 16.0]
+    assert log2[1][1][1] == 2.0
+    assert log2[1][2][2] == 1.0
+    assert log2[1][3][3] == 3.0
+    raw = ak.encode(BOARD, scheme="raw")
+    assert raw.shape == (1, 4, 4)
+    assert raw.tolist()[0] == [[float(v) for v in row] for row in BOARD]
+
+
+def test_encode_batches_and_rules():
+    arena = ak.Arena(3, seed=4)
+    batch = ak.encode(arena.observations, scheme="log2")
+    assert batch.shape == (3, 2, 4, 4)
+    assert ak.encode([BOARD, BOARD]).shape == (2, 20, 4, 4)
+    rules = ak.Rules(max_multiplier=16)
+    assert ak.encode(BOARD, rules=rules).shape == (22, 4, 4)
+
+
+def test_encode_rejects_bad_input():
+    with pytest.raises(ValueError, match="unknown encoding scheme"):
+        ak.encode(BOARD, scheme="planes")
+    with pytest.raises(ValueError):
+        ak.encode([[3, 0, 0, 0], [0] * 4, [0] * 4, [0] * 4])
@@ -0,0 +1,122 @@
+import math
+
+import pytest
+
+import akioi_2048 as ak
+
+np = pytest.importorskip("numpy")
+
+ACTIONS = [0, 3, 2, 1]
+
+
+def play(env, n):
+    return [env.step(ACTIONS[i % 4]) for i in range(n)]
+
+
+def test_env_reset_and_step():
+    env = ak.Env(seed=1)
+    obs, info = env.reset()
+    assert obs.shape == (4, 4)
+    assert info["moves"] == 0
+    assert info["state"] is ak.State.Continue
+
+    obs, reward, terminated, truncated, info = env.step(ak.Direction.Down)
+    assert reward == info["delta"]
+    assert not terminated and not truncated
+    assert obs.tolist() == env.observation.tolist()
+    assert info["score"] == env.score
+
+
+def test_env_equal_seeds_give_equal_episodes():
+    a, b = ak.Env(seed=8), ak.Env(seed=8)
+    for (obs_a, *rest_a), (obs_b, *rest_b) in zip(play(a, 30), play(b, 30)):
+        assert obs_a.tolist() == obs_b.tolist()
+        assert rest_a[:3] == rest_b[:3]
+    first, _ = a.reset(seed=2)
+    again, _ = a.reset(seed=2)
+    assert first.tolist() == again.tolist()
+
+
+def test_env_reward_kinds():
+    score, log_score, merges = (
+        ak.Env(seed=4),
+        ak.Env(seed=4, reward="log_score"),
+        ak.Env(seed=4, reward="merges", empty_bonus=0.5),
+    )
+    for s, l, m in zip(play(score, 40), play(log_score, 40), play(merges, 40)):
+        delta = s[4]["delta"]
+        assert l[1] == pytest.approx(math.copysign(math.log2(1 + abs(delta)), delta))
+        empty = sum(1 for row in m[0].tolist() for v in row if v == 0)
+        merge_count = m[1] - 0.5 * empty
+        assert merge_count == int(merge_count) and merge_count >= 0
+
+
+def test_env_callable_reward_and_terminal_penalty():
+    seen = []
+
+    def reward(board, result):
+        seen.append((board, result.board))
+        return 1.0 if result.moved else -1.0
+
+    env = ak.Env(seed=6, reward=reward)
+    _, r, *_ = env.step(0)
+    assert r in (1.0, -1.0)
+    assert len(seen) == 1
+
+    env = ak.Env(seed=6, game_over_penalty=100.0, max_moves=5000)
+    for i in range(5000):
+        _, r, terminated, truncated, info = env.step(ACTIONS[i % 4])
+        if terminated or truncated:
+            break
+    assert info["state"] is ak.State.GameOver
+    assert r == info["delta"] - 100.0
+
+    with pytest.raises(ValueError, match="cannot be combined"):
+        ak.Env(reward=reward, empty_bonus=1.0)
+    with pytest.raises(ValueError, match="unknown reward"):
+        ak.Env(reward="tiles")
+
+
+def test_env_truncates_at_max_moves():
+    env = ak.Env(seed=3, max_moves=3)
+    results = play(env, 3)
+    assert [r[3] for r in results] == [False, False, True]
+
+
+def stuck_opening():
+    """Seed and action id that do not move the first board of that env."""
+    for seed in range(100):
+        for action in range(4):
+            if not ak.Env(seed=seed).step(action)[4]["moved"]:
+                return seed, action
+    raise AssertionError("no stuck opening in seeds 0..100")
+
+
+def test_env_invalid_move_policies():
+    seed, action = stuck_opening()
+    penalized = ak.Env(seed=seed, invalid_moves="penalize", invalid_penalty=2.5)
+    assert penalized.step(action)[1] == -2.5
+    assert ak.Env(seed=seed).step(action)[1] == 0.0
+
+    masked = ak.Env(seed=seed, invalid_moves="mask")
+    with pytest.raises(ValueError, match="invalid move"):
+        masked.step(action)
+
+    resolved = ak.Env(seed=seed, invalid_moves="auto-resolve")
+    *_, info = resolved.step(action)
+    assert info["moved"]
+    assert info["action"] != action
+
+
+def test_env_action_mask_marks_legal_moves():
+    directions = [ak.Direction.Down, ak.Direction.Right, ak.Direction.Up, ak.Direction.Left]
+    env = ak.Env(seed=5)
+    _, info = env.reset()
+    for _ in range(200):
+        mask = info["action_mask"].tolist()
+        assert info["action_mask"].shape == (4,)
+        board = env.observation.tolist()
+        assert mask == [ak.step_detailed(board, d).moved for d in directions]
+        if not any(mask):
+            break
+        *_, info = env.step(mask.index(True))
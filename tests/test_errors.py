@@ -0,0 +1,58 @@
+import time
+
+import pytest
+
+import akioi_2048 as ak
+
+ACTIONS = [0, 1, 2, 3]
+
+
+def test_errors_are_value_errors():
+    for error in (ak.InvalidBoardError, ak.InvalidDirectionError, ak.GameFinishedError):
+        assert issubclass(error, ValueError)
+
+
+def test_bad_boards_raise_invalid_board_error():
+    bad_tile = [[3, 0, 0, 0], [0] * 4, [0] * 4, [0] * 4]
+    with pytest.raises(ak.InvalidBoardError, match="3"):
+        ak.step(bad_tile, ak.Direction.Left)
+    with pytest.raises(ak.InvalidBoardError, match="4x4"):
+        ak.step([[0] * 4] * 3, ak.Direction.Left)
+    with pytest.raises(ak.InvalidBoardError, match="4x4"):
+        ak.validate([[0] * 4] * 3, strict=False)
+    with pytest.raises(ak.InvalidBoardError):
+        ak.is_game_over(bad_tile)
+
+
+def test_bad_directions_raise_invalid_direction_error():
+    board = ak.init()
+    with pytest.raises(ak.InvalidDirectionError, match="unknown direction"):
+        ak.step(board, "sideways")
+    with pytest.raises(ak.InvalidDirectionError, match="Direction"):
+        ak.step(board, 1.5)
+    with pytest.raises(ak.InvalidDirectionError, match="between 0 and 3"):
+        ak.Env(seed=1).step(4)
+
+
+def test_masked_game_refuses_moves_once_over():
+    game = ak.Game(
+        seed=0, invalid_moves="mask", time_limit=0.01, on_timeout="forfeit"
+    )
+    time.sleep(0.05)
+    game.step(ak.Direction.Left)
+    assert game.state == ak.State.GameOver
+    with pytest.raises(ak.GameFinishedError, match="the game is over"):
+        game.step(ak.Direction.Left)
+
+
+def test_env_refuses_steps_after_the_episode_until_reset():
+    env = ak.Env(seed=2)
+    for i in range(100_000):
+        terminated = env.step(ACTIONS[i % 4])[2]
+        if terminated:
+            break
+    assert terminated
+    with pytest.raises(ak.GameFinishedError, match="reset"):
+        env.step(0)
+    env.reset()
+    env.step(0)
@@ -0,0 +1,44 @@
+import pytest
+
+import akioi_2048 as ak
+
+pytest.importorskip("numpy")
+
+OPEN = [[2, 2, 0, 0], [0, 0, 0, 0], [0, 4, 0, 0], [0, 0, 0, -2]]
+EMPTY = [[0] * 4 for _ in range(4)]
+COLUMNS = [
+    "empty_cells",
+    "monotonicity",
+    "smoothness",
+    "corner",
+    "merges",
+    "multipliers",
+]
+
+
+def test_evaluate_batch_gives_one_feature_row_per_board() -> None:
+    features = ak.evaluate_batch([OPEN, EMPTY])
+    assert features.shape == (2, len(COLUMNS))
+    rows = features.tolist()
+    assert rows[1] == [16.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+    named = dict(zip(COLUMNS, rows[0]))
+    assert named["empty_cells"] == 12
+    assert named["multipliers"] == 1
+    assert named["merges"] >= 1
+
+
+def test_evaluate_batch_does_not_depend_on_threads() -> None:
+    boards = [OPEN, EMPTY] * 500
+    runs = [ak.evaluate_batch(boards, threads=n).tolist() for n in [1, 3, 8]]
+    assert runs[0] == runs[1] == runs[2]
+    assert runs[0][::2] == [runs[0][0]] * 500
+
+
+def test_evaluate_batch_rejects_invalid_boards() -> None:
+    assert ak.evaluate_batch([]).shape == (0, 6)
+    with pytest.raises(ak.InvalidBoardError):
+        ak.evaluate_batch([OPEN, [[3, 0, 0, 0]] + EMPTY[1:]])
+    with pytest.raises(ak.InvalidBoardError):
+        ak.evaluate_batch([[[-5, 0, 0, 0]] + EMPTY[1:]])
+    bombs = ak.Rules(bombs=True)
+    assert ak.evaluate_batch([[[-5, 0, 0, 0]] + EMPTY[1:]], rules=bombs).shape == (1, 6)
@@ -0,0 +1,63 @@
+import ctypes
+import pathlib
+
+import pytest
+
+import akioi_2048 as ak
+
+ROOT = pathlib.Path(__file__).resolve().parent.parent
+NAMES = ("libakioi_2048_ffi.so", "libakioi_2048_ffi.dylib", "akioi_2048_ffi.dll")
+
+
+def load_ffi():
+    for profile in ("debug", "release"):
+        for name in NAMES:
+            path = ROOT / "target" / profile / name
+            if path.exists():
+                lib = ctypes.CDLL(str(path))
+                lib.akioi_game_new.restype = ctypes.c_void_p
+                lib.akioi_game_new_with_rules.restype = ctypes.c_void_p
+                lib.akioi_game_new_with_rules.argtypes = [ctypes.c_uint64, ctypes.c_char_p]
+                lib.akioi_game_free.argtypes = [ctypes.c_void_p]
+                lib.akioi_game_step.argtypes = [ctypes.c_void_p, ctypes.c_int, ctypes.c_void_p]
+                lib.akioi_game_board.argtypes = [ctypes.c_void_p, ctypes.c_void_p]
+                lib.akioi_game_score.argtypes = [ctypes.c_void_p]
+                lib.akioi_game_score.restype = ctypes.c_int64
+                return lib
+    pytest.skip("build akioi-2048-ffi first (cargo build -p akioi-2048-ffi)")
+
+
+Cells = ctypes.c_int32 * 16
+IDS = {ak.Direction.Down: 0, ak.Direction.Right: 1, ak.Direction.Up: 2, ak.Direction.Left: 3}
+
+
+def test_ffi_step_matches_python():
+    lib = load_ffi()
+    board = [[2, 2, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+    cells = Cells(*[v for row in board for v in row])
+    out = Cells()
+    delta = ctypes.c_int32()
+    state = lib.akioi_step(cells, 3, out, ctypes.byref(delta))
+    assert state == 0
+    assert delta.value == 4
+    assert out[0] == 4
+    assert sum(1 for v in out if v != 0) == 2
+    assert lib.akioi_step(cells, 4, out, None) == -2
+    assert lib.akioi_step(Cells(3), 0, out, None) == -3
+
+
+def test_ffi_game_matches_seeded_game():
+    lib = load_ffi()
+    handle = lib.akioi_game_new_with_rules(7, b"mercy")
+    assert handle
+    game = ak.Game(seed=7, rules=ak.Rules.preset("mercy"))
+    delta = ctypes.c_int32()
+    for direction in [ak.Direction.Down, ak.Direction.Left, ak.Direction.Up] * 5:
+        state = lib.akioi_game_step(handle, IDS[direction], ctypes.byref(delta))
+        game.step(direction)
+    board = Cells()
+    assert lib.akioi_game_board(handle, board) == state
+    assert list(board) == [v for row in game.board for v in row]
+    assert lib.akioi_game_score(handle) == game.score
+    lib.akioi_game_free(handle)
+    assert not lib.akioi_game_new_with_rules(7, b"no-such-rules")
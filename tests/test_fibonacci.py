@@ -0,0 +1,59 @@
+import pytest
+
+import akioi_2048 as ak
+
+FIB = ak.Rules.preset("fib")
+
+
+def row(*tiles: int) -> list[list[int]]:
+    return [list(tiles)] + [[0] * 4] * 3
+
+
+def test_preset_and_tag() -> None:
+    assert FIB == ak.Rules(variant="fib")
+    assert FIB.variant == "fib"
+    assert FIB.tag == "akioi-v2+variant=fib"
+    assert ak.spawn_probabilities(rules=FIB) == {1: 0.9, 2: 0.1}
+
+
+def test_consecutive_numbers_merge() -> None:
+    after, delta, _ = ak.step(row(1, 1, 2, 3), ak.Direction.Left, rules=FIB)
+    assert after[0][:2] == [2, 5]
+    assert delta == 7
+    after, delta, _ = ak.step(row(2, 2, 5, 8), ak.Direction.Left, rules=FIB)
+    assert after[0][:3] == [2, 2, 13]
+    assert delta == 13
+    after, delta, _ = ak.step(row(8, 21, 0, 0), ak.Direction.Left, rules=FIB)
+    assert after[0][:2] == [8, 21]
+    assert delta == 0
+
+
+def test_validation_follows_the_sequence() -> None:
+    for tile in [1, 2, 3, 5, 8, 13, 46368]:
+        ak.validate(row(tile, 0, 0, 0), rules=FIB)
+    for tile in [4, 16, 65536, -1, 75025]:
+        with pytest.raises(ValueError):
+            ak.validate(row(tile, 0, 0, 0), rules=FIB)
+    with pytest.raises(ValueError):
+        ak.validate(row(1, 0, 0, 0))
+
+
+def test_winning_tile() -> None:
+    after, _, state = ak.step(row(17711, 28657, 0, 0), ak.Direction.Left, rules=FIB)
+    assert after[0][0] == 46368
+    assert state == ak.State.Victory
+    _, delta, _ = ak.step(row(28657, 46368, 0, 0), ak.Direction.Left, rules=FIB)
+    assert delta == 0
+
+
+def test_play_matches_the_reference() -> None:
+    board = ak.init(rules="fib")
+    assert {t for r in board for t in r} <= {0, 1, 2}
+    directions = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right]
+    for k in range(300):
+        direction = directions[k % 3]
+        after, _, state = ak.step(board, direction, rules=FIB)
+        assert ak.verify_step(board, direction, after, rules=FIB)
+        if state == ak.State.GameOver:
+            break
+        board = after
@@ -0,0 +1,110 @@
+import time
+
+import pytest
+
+import akioi_2048 as ak
+
+
+def test_hint_explains_its_choice() -> None:
+    board = [[2, 4, 8, 16], [0, 2, 4, 8], [0, 0, 2, -2], [0, 0, 0, 2]]
+    direction, explanation = ak.hint(board, depth=2)
+    values = explanation["values"]
+    assert list(values)[0] == direction
+    assert list(values.values()) == sorted(values.values(), reverse=True)
+    legal = {d for d in ak.Direction if ak.step_detailed(board, d).moved}
+    assert set(values) == legal
+    assert set(explanation["factors"]) == {
+        "empty_cells",
+        "monotonicity",
+        "smoothness",
+        "corner",
+        "merges",
+        "multipliers",
+    }
+    assert explanation["summary"].startswith(direction.name)
+
+
+def test_hint_reports_the_move_it_recommends() -> None:
+    board = ak.board_with({(3, 0): 2, (3, 1): 2, (3, 2): 4, (3, 3): 8})
+    direction, explanation = ak.hint(board, depth=1)
+    assert direction in (ak.Direction.Left, ak.Direction.Right)
+    assert explanation["score"] == 4
+    assert "scores 4" in explanation["summary"]
+    after = ak.step_detailed(board, direction)
+    empty = sum(tile == 0 for row in after.board for tile in row) + 1
+    assert explanation["factors"]["empty_cells"] == empty
+
+
+def test_no_hint_without_moves() -> None:
+    dead = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+    assert ak.hint(dead) is None
+
+
+def test_hint_results_do_not_depend_on_threads() -> None:
+    board = [[2, 4, 8, 16], [0, 2, 4, 8], [0, 0, 2, -2], [0, 0, 0, 2]]
+    hints = [ak.hint(board, depth=3, threads=n) for n in (1, 2, 4)]
+    assert hints[0] == hints[1] == hints[2]
+    assert hints[0][1]["depth"] == 3
+
+
+def test_hint_keeps_the_deepest_search_within_its_time_budget() -> None:
+    board = [[2, 4, 8, 16], [0, 2, 4, 8], [0, 0, 2, -2], [0, 0, 0, 2]]
+    start = time.perf_counter()
+    direction, explanation = ak.hint(board, depth=50, time_budget=0.05)
+    assert time.perf_counter() - start < 5
+    assert 1 <= explanation["depth"] <= 50
+    shallow = ak.hint(board, depth=explanation["depth"])
+    if explanation["depth"] < 50:
+        assert shallow == (direction, explanation)
+    for bad in ({"time_budget": -1.0}, {"threads": 0}):
+        with pytest.raises(ValueError):
+            ak.hint(board, **bad)
+
+
+def test_evaluate_moves_values_every_legal_move() -> None:
+    board = [[2, 4, 8, 16], [0, 2, 4, 8], [0, 0, 2, -2], [0, 0, 0, 2]]
+    values = ak.evaluate_moves(board, depth=2)
+    legal = [d for d in ak.Direction if ak.step_detailed(board, d).moved]
+    assert list(values) == legal
+    _, explanation = ak.hint(board, depth=1)
+    shallow = ak.evaluate_moves(board, depth=1)
+    for direction, value in explanation["values"].items():
+        assert shallow[direction] == pytest.approx(value)
+    dead = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+    assert ak.evaluate_moves(dead) == {}
+    with pytest.raises(ValueError):
+        ak.evaluate_moves([[3, 0, 0, 0], [0] * 4, [0] * 4, [0] * 4])
+
+
+def test_opening_book_answers_openings(tmp_path) -> None:
+    rules = ak.Rules.preset("classic")
+    book = ak.OpeningBook.build(max_sum=6, depth=1, rules=rules)
+    assert len(book) > 0
+    assert (book.max_sum, book.depth) == (6, 1)
+    start = [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 2, 0], [0, 4, 0, 0]]
+    assert start in book
+    direction, value = book.lookup(start)
+    move, explanation = ak.hint(start, depth=1, rules=rules, book=book)
+    assert move == direction
+    assert explanation["source"] == "book"
+    assert explanation["values"] == {direction: value}
+    _, plain = ak.hint(start, depth=1, rules=rules)
+    assert plain["source"] == "search"
+    assert plain["values"][direction] == pytest.approx(value)
+    late = [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 8, 0], [0, 4, 0, 0]]
+    assert book.lookup(late) is None
+    _, explanation = ak.hint(late, depth=1, rules=rules, book=book)
+    assert explanation["source"] == "search"
+
+    path = tmp_path / "opening.book"
+    book.save(path)
+    loaded = ak.OpeningBook.load(path)
+    assert len(loaded) == len(book)
+    assert loaded.lookup(start) == book.lookup(start)
+    data = bytearray(path.read_bytes())
+    data[-40] ^= 1
+    path.write_bytes(bytes(data))
+    with pytest.raises(ValueError, match="checksum"):
+        ak.OpeningBook.load(path)
+    with pytest.raises(ValueError, match="rules"):
+        ak.hint(start, depth=1, book=book)
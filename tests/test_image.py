@@ -0,0 +1,57 @@
+import struct
+import zlib
+
+import pytest
+
+import akioi_2048 as ak
+
+BOARD = [
+    [2, 0, -1, 0],
+    [0, 65536, 0, -4],
+    [8, 0, 0, 0],
+    [0, 0, -2, 1024],
+]
+
+
+def png_pixels(data: bytes):
+    assert data[:8] == b"\x89PNG\r\n\x1a\n"
+    pos, chunks = 8, {}
+    while pos < len(data):
+        (length,) = struct.unpack(">I", data[pos : pos + 4])
+        kind = data[pos + 4 : pos + 8]
+        body = data[pos + 8 : pos + 8 + length]
+        (crc,) = struct.unpack(">I", data[pos + 8 + length : pos + 12 + length])
+        assert zlib.crc32(kind + body) == crc
+        chunks[kind] = chunks.get(kind, b"") + body
+        pos += 12 + length
+    width, height = struct.unpack(">II", chunks[b"IHDR"][:8])
+    raw = zlib.decompress(chunks[b"IDAT"])
+    stride = 1 + 3 * width
+    assert len(raw) == height * stride
+    return width, height, lambda x, y: raw[y * stride + 1 + 3 * x : y * stride + 4 + 3 * x]
+
+
+def test_png_is_valid_and_colours_tiles(tmp_path) -> None:
+    path = tmp_path / "board.png"
+    ak.render_image(BOARD, path)
+    width, height, pixel = png_pixels(path.read_bytes())
+    assert (width, height) == (460, 460)
+    assert pixel(0, 0) == bytes.fromhex("bbada0")  # frame
+    assert pixel(14 + 112, 14) == bytes.fromhex("cdc1b4")  # empty cell
+    assert pixel(14, 14) == bytes.fromhex("eee4da")  # the 2 tile
+
+
+def test_svg_labels_multipliers(tmp_path) -> None:
+    path = tmp_path / "board.svg"
+    ak.render_image(BOARD, str(path), theme="dark")
+    svg = path.read_text(encoding="utf-8")
+    assert svg.startswith("<svg")
+    assert ">×2</text>" in svg and ">65536</text>" in svg
+    assert svg.count("<rect") == 17
+
+
+def test_unknown_format_or_theme_is_rejected(tmp_path) -> None:
+    with pytest.raises(ValueError, match="unsupported image format"):
+        ak.render_image(BOARD, tmp_path / "board.bmp")
+    with pytest.raises(ValueError, match="unknown theme"):
+        ak.render_image(BOARD, tmp_path / "board.png", theme="neon")
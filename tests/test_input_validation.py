@@ -45,3 +45,27 @@ def test_step_rejects_one() -> None:
     ]
     with pytest.raises(ValueError, match=r"^invalid tile value: 1$"):
         ak.step(board, ak.Direction.Down)
+
+
+def test_validate_rejects_invalid_tiles() -> None:
+    for tile in (3, -8, 131072):
+        board = [[tile, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+        with pytest.raises(ValueError, match=rf"^invalid tile value: {tile}$"):
+            ak.validate(board)
+        ak.validate(board, strict=False)
+
+
+def test_validate_accepts_valid_board() -> None:
+    ak.validate([[2, -2, 0, 0], [0, 65536, 0, 0], [0, 0, -1, 0], [0, 0, 0, 4]])
+
+
+def test_validate_rejects_malformed_board() -> None:
+    with pytest.raises(ValueError, match="4x4"):
+        ak.validate([[0, 0, 0]], strict=False)
+
+
+def test_non_strict_step_merges_custom_tiles() -> None:
+    board = [[3, 0, 0, 0], [3, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+    new_board, delta, _ = ak.step(board, ak.Direction.Down, strict=False)
+    assert new_board[3][0] == 6
+    assert delta == 6
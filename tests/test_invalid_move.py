@@ -1,3 +1,5 @@
+import pytest
+
 import akioi_2048 as ak
 
 
@@ -25,3 +27,66 @@ def test_invalid_move_no_failure() -> None:
     assert new_board == board
     assert delta == 0
     assert msg == ak.State.Continue
+
+
+def stuck_game(**kwargs):
+    """A seeded game whose first board does not move under some direction."""
+    for seed in range(100):
+        for direction in ak.Direction:
+            game = ak.Game(seed=seed, **kwargs)
+            if ak.Game(seed=seed).step(direction)[0] == game.board:
+                return game, direction
+    raise AssertionError("no stuck opening in seeds 0..100")
+
+
+def test_invalid_move_policy_mask_raises() -> None:
+    game, direction = stuck_game(invalid_moves="mask")
+    board = game.board
+    with pytest.raises(ValueError, match="invalid move"):
+        game.step(direction)
+    assert game.board == board
+    assert game.moves == 0
+
+
+def test_invalid_move_policy_auto_resolve() -> None:
+    game, direction = stuck_game(invalid_moves="auto-resolve")
+    board = game.board
+    game.step(direction)
+    assert game.board != board
+    assert game.replay().game().board == game.board
+
+
+def test_invalid_move_policy_noop() -> None:
+    game, direction = stuck_game(invalid_moves="noop")
+    board = game.board
+    new_board, delta, state = game.step(direction)
+    assert new_board == board
+    assert delta == 0
+    assert state == ak.State.Continue
+    assert game.moves == 1
+    assert game.score == 0
+    assert game.replay().game().board == board
+
+
+def test_invalid_move_policy_penalize_lowers_the_env_reward() -> None:
+    pytest.importorskip("numpy")
+    for seed in range(100):
+        for action in range(4):
+            env = ak.Env(seed=seed, invalid_moves="penalize", invalid_penalty=2.5)
+            board = env.observation.tolist()
+            _, reward, terminated, _, info = env.step(action)
+            if info["moved"]:
+                continue
+            assert reward == -2.5
+            assert not terminated
+            assert info["delta"] == 0
+            assert env.observation.tolist() == board
+            noop = ak.Env(seed=seed, invalid_moves="noop", invalid_penalty=2.5)
+            assert noop.step(action)[1] == 0.0
+            return
+    raise AssertionError("no stuck opening in seeds 0..100")
+
+
+def test_invalid_move_policy_unknown() -> None:
+    with pytest.raises(ValueError, match="unknown invalid_moves policy"):
+        ak.Game(invalid_moves="skip")
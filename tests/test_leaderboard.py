@@ -0,0 +1,66 @@
+import pytest
+
+import akioi_2048 as ak
+
+DIRECTIONS = [ak.Direction.Down, ak.Direction.Right, ak.Direction.Left, ak.Direction.Up]
+
+
+def played(seed: int, moves: int, rules=None) -> ak.Replay:
+    game = ak.Game(seed=seed, rules=rules)
+    for k in range(moves):
+        if game.state != ak.State.Continue:
+            break
+        game.step(DIRECTIONS[k % 4])
+    return game.replay()
+
+
+def test_record_and_reopen(tmp_path) -> None:
+    path = tmp_path / "scores.txt"
+    board = ak.Leaderboard(path)
+    assert len(board) == 0
+    replay = played(1, 40)
+    assert board.record(replay)
+    assert not board.record(replay)
+    assert board.record(played(2, 80))
+    again = ak.Leaderboard(str(path))
+    assert len(again) == 2
+    best = again.top()[0]
+    game = played(2, 80).game()
+    assert best.score == game.score
+    assert best.max_tile == max(max(r) for r in game.board)
+    assert best.seed == 2
+    assert best.rules == ak.Rules()
+    assert best.replay_hash == played(2, 80).digest()
+    assert len(best.replay_hash) == 64
+
+
+def test_top_sorts_filters_and_limits(tmp_path) -> None:
+    board = ak.Leaderboard(tmp_path / "scores.txt")
+    for seed in range(6):
+        board.record(played(seed, 20 + 15 * seed))
+    mercy = ak.Rules.preset("mercy")
+    board.record(played(9, 30, rules=mercy))
+    scores = [e.score for e in board.top()]
+    assert scores == sorted(scores, reverse=True)
+    assert len(scores) == 7
+    assert [e.rules for e in board.top(rules=mercy)] == [mercy]
+    assert len(board.top(limit=3)) == 3
+    moves = [e.moves for e in board.top(sort="moves")]
+    assert moves == sorted(moves)
+    tiles = [e.max_tile for e in board.top(sort="max_tile")]
+    assert tiles == sorted(tiles, reverse=True)
+    assert board.rank(10**9) == 1
+    assert board.rank(-1) == 7
+    assert board.rank(-1, rules=mercy) == 2
+    with pytest.raises(ValueError, match="unknown sort key: date"):
+        board.top(sort="date")
+
+
+def test_invalid_files(tmp_path) -> None:
+    path = tmp_path / "other.txt"
+    path.write_text("hello\n")
+    with pytest.raises(ValueError, match="not an akioi-2048 leaderboard"):
+        ak.Leaderboard(path)
+    path.write_text("akioi-2048 leaderboard 1\n12 oops\n")
+    with pytest.raises(ValueError, match="invalid leaderboard entry"):
+        ak.Leaderboard(path)
@@ -0,0 +1,52 @@
+import random
+
+import pytest
+
+import akioi_2048 as ak
+
+TILES = [0, 0, 0, 2, 2, 4, 8, -1, -2, -4]
+
+
+def test_slide_row_matches_a_left_move():
+    rng = random.Random(5)
+    for _ in range(300):
+        row = [rng.choice(TILES) for _ in range(4)]
+        board = [row, [0] * 4, [0] * 4, [0] * 4]
+        result = ak.step_detailed(board, ak.Direction.Left)
+        slid = [list(r) for r in result.board]
+        if result.spawn is not None:
+            r, c = result.spawn.cell
+            slid[r][c] = 0
+        assert ak.slide_row(row) == (slid[0], result.delta), row
+
+
+def test_slide_row_examples():
+    assert ak.slide_row([2, 2, 4, 0]) == ([4, 4, 0, 0], 4)
+    threes = ak.Rules(variant="classic", base=3)
+    assert ak.slide_row([0, 3, 3, 6], rules=threes) == ([6, 6, 0, 0], 6)
+    assert ak.slide_row([1, 1, 2, 0], rules="fib") == ([2, 2, 0, 0], 2)
+
+
+def test_merge_preview_numbers():
+    assert ak.merge_preview(2, 2) == (4, 4)
+    assert ak.merge_preview(2, 4) is None
+    assert ak.merge_preview(65536, 65536) is None
+
+
+def test_merge_preview_multiplier_conditions():
+    assert ak.merge_preview(-2, 8) == (16, 16)
+    assert ak.merge_preview(8, -2, adjacent=False) is None
+    assert ak.merge_preview(8, -2, below=[4]) == (16, 16)
+    assert ak.merge_preview(8, -2, below=[0, 4]) is None
+    relaxed = ak.Rules(require_full_below=False)
+    assert ak.merge_preview(8, -2, below=[0, 4], rules=relaxed) == (16, 16)
+    assert ak.merge_preview(-2, -2) == (-4, -4)
+    assert ak.merge_preview(-2, -2, rules=ak.Rules(multiplier_merge=False)) is None
+
+
+def test_merge_preview_rejects_bad_tiles():
+    blockers = ak.Rules(blockers=True)
+    assert ak.merge_preview(ak.BLOCKER, 2, rules=blockers) is None
+    for a, b, below in [(0, 2, []), (3, 3, []), (2, 2, [2, 2, 2]), (ak.BLOCKER, 2, [])]:
+        with pytest.raises(ak.InvalidBoardError):
+            ak.merge_preview(a, b, below=below)
@@ -0,0 +1,79 @@
+import json
+
+import pytest
+
+import akioi_2048 as ak
+
+MOVES = [ak.Direction.Left, ak.Direction.Down, ak.Direction.Right, ak.Direction.Up]
+
+
+def test_on_move_reports_every_played_move() -> None:
+    game = ak.Game(seed=3)
+    seen = []
+    game.on_move(lambda direction, result: seen.append((direction, result)))
+    deltas = [game.step(move)[1] for move in MOVES]
+    assert [d for d, _ in seen] == MOVES
+    assert [r.delta for _, r in seen] == deltas
+    for _, result in seen:
+        if result.moved:
+            assert result.spawn is not None
+
+
+def test_on_move_skips_rejected_moves() -> None:
+    board = ak.board_with({(3, 0): 2})
+    game = ak.Game.from_board(board, seed=1, invalid_moves="mask")
+    seen = []
+    game.on_move(lambda direction, result: seen.append(direction))
+    with pytest.raises(ValueError, match="invalid move"):
+        game.step(ak.Direction.Left)
+    game.step(ak.Direction.Up)
+    assert seen == [ak.Direction.Up]
+
+
+def test_on_move_errors_propagate() -> None:
+    game = ak.Game(seed=3)
+
+    def fail(direction, result):
+        raise RuntimeError("boom")
+
+    game.on_move(fail)
+    with pytest.raises(RuntimeError, match="boom"):
+        game.step(ak.Direction.Left)
+    assert game.moves == 1
+    with pytest.raises(ValueError, match="callable"):
+        game.on_move(3)
+
+
+def test_log_moves_writes_json_lines(tmp_path) -> None:
+    path = tmp_path / "moves.jsonl"
+    game = ak.Game(seed=5)
+    game.log_moves(path)
+    results = [game.step_detailed(move) for move in MOVES]
+    lines = [json.loads(line) for line in path.read_text().splitlines()]
+    assert [line["move"] for line in lines] == [0, 1, 2, 3]
+    assert [line["direction"] for line in lines] == [m.name for m in MOVES]
+    assert [line["delta"] for line in lines] == [r.delta for r in results]
+    assert lines[-1]["score"] == game.score
+    for line, result in zip(lines, results):
+        assert line["moved"] == result.moved
+        assert line["merges"] == len(result.merges)
+        assert line["state"] == "Continue"
+        if result.spawn is None:
+            assert line["spawn"] is None
+        else:
+            (row, col), value = result.spawn.cell, result.spawn.value
+            assert line["spawn"] == {"row": row, "col": col, "value": value}
+
+
+def test_log_moves_append_and_stop(tmp_path) -> None:
+    path = tmp_path / "moves.jsonl"
+    game = ak.Game(seed=5)
+    game.log_moves(str(path))
+    game.step(ak.Direction.Left)
+    game.log_moves(path, append=True)
+    game.step(ak.Direction.Down)
+    game.log_moves(None)
+    game.step(ak.Direction.Right)
+    assert len(path.read_text().splitlines()) == 2
+    game.log_moves(path)
+    assert path.read_text() == ""
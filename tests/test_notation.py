@@ -0,0 +1,42 @@
+import pytest
+
+import akioi_2048 as ak
+
+BOARDS = [
+    [[0, 2, 4, 8], [-1, -2, -4, 0], [0, 0, 0, 0], [4096, 0, 0, 2]],
+    [[0] * 4 for _ in range(4)],
+    [[2048, 1024, 512, 256], [16, 32, 64, 128], [8, 4, 2, -1], [2, 4, -2, -4]],
+    [[ak.BLOCKER, ak.BOMB, 2, 0], [0] * 4, [0] * 4, [0, 0, 0, 65536]],
+]
+
+
+def test_round_trip() -> None:
+    for board in BOARDS:
+        text = ak.to_notation(board)
+        assert " " not in text
+        assert ak.parse_notation(text) == board
+
+
+def test_notation_layout() -> None:
+    board = [[2, 0, 4, 0], [0] * 4, [0, -2, 0, 0], [8, 8, 0, ak.BLOCKER]]
+    assert ak.to_notation(board) == "2.4./..../.x2../8,8.#"
+    packed = [[-2, 4, 16, -1]] + [[0] * 4] * 3
+    assert ak.to_notation(packed) == "x2,4,16x1/..../..../...."
+
+
+def test_separators_are_optional_between_other_cells() -> None:
+    spaced = ak.parse_notation(" 2 . 4 . / . . . . / . x2 . . / 8 8 . # ")
+    assert spaced == ak.parse_notation("2.4./..../.x2../8,8.#")
+    assert ak.parse_notation("24.../..../..../....")[0] == [24, 0, 0, 0]
+
+
+def test_parse_rejects_malformed_text() -> None:
+    cases = [
+        ("2.4./....", "4 rows"),
+        ("2.4../..../..../....", "row 1 has 5 cells"),
+        ("..../..../..?./....", "row 3: unexpected `\\?`"),
+        ("..../x.../..../....", "invalid tile `x`"),
+    ]
+    for text, message in cases:
+        with pytest.raises(ValueError, match=message):
+            ak.parse_notation(text)
@@ -0,0 +1,179 @@
+import struct
+from pathlib import Path
+
+import pytest
+
+import akioi_2048 as ak
+
+
+def varint(n: int) -> bytes:
+    out = bytearray()
+    while True:
+        byte = n & 0x7F
+        n >>= 7
+        if n:
+            out.append(byte | 0x80)
+        else:
+            out.append(byte)
+            return bytes(out)
+
+
+def field(number: int, payload: bytes | str) -> bytes:
+    if isinstance(payload, str):
+        payload = payload.encode()
+    return varint(number << 3 | 2) + varint(len(payload)) + payload
+
+
+def tensor(name: str, dims: list[int], values: list[float]) -> bytes:
+    raw = struct.pack(f"<{len(values)}f", *values)
+    out = b"".join(varint(1 << 3) + varint(d) for d in dims)
+    return out + varint(2 << 3) + varint(1) + field(8, name) + field(9, raw)
+
+
+def node(op: str, inputs: list[str], output: str, attrs: bytes = b"") -> bytes:
+    out = b"".join(field(1, i) for i in inputs)
+    return out + field(2, output) + field(4, op) + attrs
+
+
+def value_info(name: str, dims: list[int]) -> bytes:
+    shape = b"".join(field(1, varint(1 << 3) + varint(d)) for d in dims)
+    tensor_type = varint(1 << 3) + varint(1) + field(2, shape)
+    return field(1, name) + field(2, field(1, tensor_type))
+
+
+def model(path: Path, nodes, initializers, input_dims) -> Path:
+    graph = b"".join(field(1, n) for n in nodes)
+    graph += b"".join(field(5, t) for t in initializers)
+    graph += field(11, value_info("x", input_dims)) + field(12, value_info("y", []))
+    path.write_bytes(field(7, graph))
+    return path
+
+
+def linear(path: Path, inputs: int, weights: list[float], bias: list[float]):
+    # y = x W + b, W of shape (inputs, 4)
+    return model(
+        path,
+        [node("Gemm", ["x", "w", "b"], "y")],
+        [tensor("w", [inputs, 4], weights), tensor("b", [4], bias)],
+        [1, inputs],
+    )
+
+
+def test_scores_follow_the_weights(tmp_path: Path) -> None:
+    # Down scores the top row, Left the bottom one, Up is a constant 1
+    weights = [0.0] * 64
+    for c in range(4):
+        weights[c * 4 + 0] = 1.0
+        weights[(12 + c) * 4 + 3] = 1.0
+    path = linear(tmp_path / "raw.onnx", 16, weights, [0.0, 0.0, 1.0, 0.0])
+    policy = ak.OnnxPolicy(path, scheme="raw")
+    board = [[2, 4, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 8]]
+    assert policy.scores(board) == [6.0, 0.0, 1.0, 8.0]
+    assert policy.choose(board) == ak.Direction.Left
+    # Left cannot move this board, so the next best is played
+    board[3] = [8, 2, 0, 0]
+    assert policy.scores(board)[3] == 10.0
+    assert policy.choose(board) == ak.Direction.Down
+    assert policy(board) == ak.Direction.Down
+    full = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+    assert policy.choose(full) is None
+
+
+def test_hidden_layer_with_image_input(tmp_path: Path) -> None:
+    channels = len(ak.encode(ak.init(), scheme="log2").tolist())
+    inputs = channels * 16
+    hidden = [1.0 if i % 2 == 0 else -1.0 for i in range(inputs * 4)]
+    softmax_axis = field(5, field(1, "axis") + varint(3 << 3) + varint(1))
+    path = model(
+        tmp_path / "mlp.onnx",
+        [
+            node("Flatten", ["x"], "flat"),
+            node("MatMul", ["flat", "w1"], "h"),
+            node("Add", ["h", "b1"], "z"),
+            node("Relu", ["z"], "a"),
+            node("Gemm", ["a", "w2", "b2"], "logits"),
+            node("Softmax", ["logits"], "y", softmax_axis),
+        ],
+        [
+            tensor("w1", [inputs, 4], hidden),
+            tensor("b1", [4], [0.5, 0.0, -0.5, 1.0]),
+            tensor("w2", [4, 4], [float(i % 5) for i in range(16)]),
+            tensor("b2", [4], [0.0, 0.1, 0.2, 0.3]),
+        ],
+        [1, channels, 4, 4],
+    )
+    policy = ak.OnnxPolicy(path, scheme="log2")
+    for _ in range(5):
+        board = ak.init()
+        scores = policy.scores(board)
+        assert sum(scores) == pytest.approx(1.0)
+        assert policy.choose(board) in list(ak.Direction)
+
+
+def test_native_agent_matches_the_callable(tmp_path: Path) -> None:
+    weights = [((i * 7) % 11 - 5) / 10 for i in range(64)]
+    path = linear(tmp_path / "agent.onnx", 16, weights, [0.0, 0.1, 0.2, 0.3])
+    policy = ak.OnnxPolicy(path, scheme="raw")
+    native = ak.tournament([policy], games_per_agent=3, seed=4)
+    called = ak.tournament([policy.choose], games_per_agent=3, seed=4)
+    assert native["scores"] == called["scores"]
+    card = ak.report_card(policy, n_games=2, seed=1)
+    assert card["batteries"]["seeded"]["games"] == 2
+
+
+def test_unsupported_models_are_rejected(tmp_path: Path) -> None:
+    conv = model(
+        tmp_path / "conv.onnx",
+        [node("Conv", ["x", "w"], "y")],
+        [tensor("w", [4, 1, 3, 3], [0.0] * 36)],
+        [1, 16],
+    )
+    with pytest.raises(ValueError, match="Conv"):
+        ak.OnnxPolicy(conv, scheme="raw")
+    wide = linear(tmp_path / "wide.onnx", 32, [0.0] * 128, [0.0] * 4)
+    with pytest.raises(ValueError, match="takes 32 inputs"):
+        ak.OnnxPolicy(wide, scheme="raw")
+    narrow = linear(tmp_path / "narrow.onnx", 16, [0.0] * 16, [0.0])
+    with pytest.raises(ValueError):
+        ak.OnnxPolicy(narrow, scheme="raw")
+    garbage = tmp_path / "garbage.onnx"
+    garbage.write_bytes(b"\xff\xff\xff")
+    with pytest.raises(ValueError):
+        ak.OnnxPolicy(garbage, scheme="raw")
+    with pytest.raises(ValueError, match="scheme"):
+        ak.OnnxPolicy(wide, scheme="nope")
+
+
+def test_malformed_models_raise_instead_of_panicking(tmp_path: Path) -> None:
+    axis = field(5, field(1, "axis") + varint(3 << 3) + varint(0))
+    for dims, values in [([], [1.0]), ([0, 4], [])]:
+        path = model(
+            tmp_path / "softmax.onnx",
+            [node("Softmax", ["s"], "y", axis)],
+            [tensor("s", dims, values)],
+            [1, 16],
+        )
+        with pytest.raises(ValueError, match="Softmax takes a non-empty tensor"):
+            ak.OnnxPolicy(path, scheme="raw")
+
+
+def test_oversized_shapes_raise_instead_of_overflowing(tmp_path: Path) -> None:
+    huge = 2**32
+    weights = model(
+        tmp_path / "weights.onnx",
+        [node("Add", ["x", "w"], "y")],
+        [tensor("w", [huge, huge], [])],
+        [1, 16],
+    )
+    inputs = model(
+        tmp_path / "inputs.onnx", [node("Identity", ["x"], "y")], [], [huge, huge]
+    )
+    reshape = model(
+        tmp_path / "reshape.onnx",
+        [node("Reshape", ["x", "s"], "y")],
+        [tensor("s", [3], [float(huge), float(huge), -1.0])],
+        [1, 16],
+    )
+    for path in [weights, inputs, reshape]:
+        with pytest.raises(ValueError, match="holds too many values"):
+            ak.OnnxPolicy(path, scheme="raw")
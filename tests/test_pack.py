@@ -0,0 +1,64 @@
+import pytest
+
+import akioi_2048 as ak
+
+BOARDS = [
+    [[0, 2, 4, 8], [-1, -2, -4, 0], [0, 0, 0, 0], [4096, 0, 0, 2]],
+    [[0] * 4 for _ in range(4)],
+    [[2048, 1024, 512, 256], [16, 32, 64, 128], [8, 4, 2, -1], [2, 4, -2, -4]],
+]
+
+
+def test_round_trip_in_both_sizes() -> None:
+    for board in BOARDS:
+        for size in (8, 16):
+            data = ak.pack(board, size=size)
+            assert isinstance(data, bytes)
+            assert len(data) == size
+            assert ak.unpack(data) == board
+
+
+def test_sixteen_bytes_hold_large_tiles() -> None:
+    board = [[65536, -8, 0, 0], [0] * 4, [0] * 4, [0, 0, 0, 1 << 30]]
+    assert ak.unpack(ak.pack(board)) == board
+    with pytest.raises(ValueError, match="into 8 bytes"):
+        ak.pack(board, size=8)
+
+
+def test_packed_layout() -> None:
+    board = [[2, 0, 0, 0], [0] * 4, [0] * 4, [0, 0, 0, -1]]
+    assert ak.pack(board) == bytes([1] + [0] * 14 + [0x80])
+    assert ak.pack(board, size=8) == bytes([0x10, 0, 0, 0, 0, 0, 0, 0x0D])
+
+
+def test_pack_rejects_bad_input() -> None:
+    with pytest.raises(ValueError, match="not a power of two"):
+        ak.pack([[3, 0, 0, 0], [0] * 4, [0] * 4, [0] * 4])
+    with pytest.raises(ValueError, match="8 or 16"):
+        ak.pack(BOARDS[0], size=12)
+    with pytest.raises(ValueError, match="8 or 16"):
+        ak.unpack(b"\x00" * 4)
+    with pytest.raises(ValueError, match="no tile"):
+        ak.unpack(b"\x7f" + b"\x00" * 15)
+
+
+def test_board_key_is_the_eight_byte_packing_as_an_integer() -> None:
+    for board in BOARDS:
+        key = ak.board_key(board)
+        assert isinstance(key, int)
+        assert 0 <= key < 2**64
+        assert key == int.from_bytes(ak.pack(board, size=8), "big")
+        assert ak.board_from_key(key) == board
+    keys = {ak.board_key(board) for board in BOARDS}
+    assert len(keys) == len(BOARDS)
+    assert ak.board_from_key(2**64 - 1) == [[-4] * 4 for _ in range(4)]
+
+
+def test_board_key_refuses_tiles_past_its_range() -> None:
+    board = [[2, 4, 0, 0], [0] * 4, [0] * 4, [0, 0, 8192, 0]]
+    with pytest.raises(ValueError, match="up to 4096 .*, not tile 8192$"):
+        ak.board_key(board)
+    assert ak.unpack(ak.pack(board)) == board
+    for tile in (-8, ak.BOMB):
+        with pytest.raises(ValueError, match=f"not tile {tile}$"):
+            ak.board_key([[tile, 0, 0, 0], [0] * 4, [0] * 4, [0] * 4])
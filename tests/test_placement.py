@@ -0,0 +1,90 @@
+import pytest
+
+import akioi_2048 as ak
+
+
+def empty_cells(board: list[list[int]]) -> list[tuple[int, int]]:
+    return [(r, c) for r in range(4) for c in range(4) if board[r][c] == 0]
+
+
+def on_edge(cell: tuple[int, int]) -> bool:
+    return 0 in cell or 3 in cell
+
+
+def distance_to_top(board: list[list[int]], cell: tuple[int, int]) -> int:
+    top = max(v for row in board for v in row)
+    return min(
+        abs(cell[0] - r) + abs(cell[1] - c)
+        for r in range(4)
+        for c in range(4)
+        if board[r][c] == top
+    )
+
+
+def slid_board(result: ak.MoveResult) -> list[list[int]]:
+    board = [row[:] for row in result.board]
+    row, col = result.spawn.cell
+    board[row][col] = 0
+    return board
+
+
+def test_edge_placement_spawns_on_the_border_while_it_has_room() -> None:
+    game = ak.Game(seed=4, rules=ak.Rules(variant="classic", placement="edge"))
+    start = game.board
+    assert all(on_edge((r, c)) for r in range(4) for c in range(4) if start[r][c])
+    for i in range(200):
+        if game.state != ak.State.Continue:
+            break
+        result = game.step_detailed(list(ak.Direction)[i % 4])
+        if result.spawn is None:
+            continue
+        slid = slid_board(result)
+        if any(on_edge(cell) for cell in empty_cells(slid)):
+            assert on_edge(result.spawn.cell)
+
+
+def test_far_placement_spawns_farthest_from_the_largest_tile() -> None:
+    game = ak.Game(seed=6, rules=ak.Rules(variant="classic", placement="far"))
+    for i in range(100):
+        if game.state != ak.State.Continue:
+            break
+        result = game.step_detailed(list(ak.Direction)[i % 4])
+        if result.spawn is None:
+            continue
+        slid = slid_board(result)
+        farthest = max(distance_to_top(slid, cell) for cell in empty_cells(slid))
+        assert distance_to_top(slid, result.spawn.cell) == farthest
+
+
+def test_successors_follow_the_placement_policy() -> None:
+    board = [[0] * 4, [0, 2, 2, 0], [0] * 4, [0] * 4]
+    counts = {}
+    for placement in ["uniform", "edge", "far"]:
+        rules = ak.Rules(variant="classic", placement=placement)
+        counts[placement] = len(ak.enumerate_successors(board, rules=rules))
+    assert counts["edge"] < counts["uniform"]
+    assert counts["far"] < counts["edge"]
+
+
+def test_reference_model_checks_the_spawn_cell() -> None:
+    board = [[2, 2, 0, 0], [0] * 4, [0] * 4, [0] * 4]
+    interior = [[4, 0, 0, 0], [0, 2, 0, 0], [0] * 4, [0] * 4]
+    border = [[4, 0, 0, 2], [0] * 4, [0] * 4, [0] * 4]
+    rules = ak.Rules(variant="classic", placement="edge")
+    assert ak.verify_step(board, ak.Direction.Left, interior)
+    assert not ak.verify_step(board, ak.Direction.Left, interior, rules=rules)
+    assert ak.verify_step(board, ak.Direction.Left, border, rules=rules)
+    far = ak.Rules(variant="classic", placement="far")
+    corner = [[4, 0, 0, 0], [0] * 4, [0] * 4, [0, 0, 0, 2]]
+    assert ak.verify_step(board, ak.Direction.Left, corner, rules=far)
+    assert not ak.verify_step(board, ak.Direction.Left, border, rules=far)
+
+
+def test_placement_round_trips_through_tags() -> None:
+    rules = ak.Rules(placement="far")
+    assert rules.tag == "akioi-v2+placement=far"
+    assert ak.rules_spec(rules.tag)["placement"] == "far"
+    assert "placement='far'" in repr(rules)
+    assert ak.Rules().placement == "uniform"
+    with pytest.raises(ValueError, match="unknown placement: corner"):
+        ak.Rules(placement="corner")
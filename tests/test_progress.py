@@ -0,0 +1,73 @@
+import _thread
+import threading
+
+import pytest
+
+import akioi_2048 as ak
+
+ENDGAME = [[2, 4, 8, 16], [32, 64, 128, 256], [512, 1024, 2048, 4], [8, 16, 0, 0]]
+
+
+def test_progress_reaches_the_total() -> None:
+    calls = []
+    for agent in ["random", lambda board: ak.Direction.Down]:
+        calls.clear()
+        ak.tournament(
+            [agent], games_per_agent=5, progress=lambda d, t: calls.append((d, t))
+        )
+        assert calls[-1] == (5, 5)
+        assert calls == sorted(calls)
+
+
+def test_tablebase_build_reports_its_layers() -> None:
+    calls = []
+    ak.Tablebase.build(ENDGAME, 2, progress=lambda d, t: calls.append((d, t)))
+    assert calls[-1] == (5, 5)
+
+
+def test_cancelled_token_stops_every_call() -> None:
+    token = ak.CancelToken()
+    token.cancel()
+    assert token.cancelled
+    with pytest.raises(ak.CancelledError):
+        ak.tournament(["random"], games_per_agent=1000, cancel=token)
+    with pytest.raises(ak.CancelledError):
+        ak.tune_heuristics(2, 1000, cancel=token)
+    with pytest.raises(ak.CancelledError):
+        ak.Tablebase.build(ENDGAME, 3, cancel=token)
+
+
+def test_cancel_from_the_progress_callback() -> None:
+    token = ak.CancelToken()
+
+    def progress(done: int, total: int) -> None:
+        if done:
+            token.cancel()
+
+    with pytest.raises(ak.CancelledError):
+        ak.tune_heuristics(2, 10_000, progress=progress, cancel=token)
+    with pytest.raises(ak.CancelledError):
+        ak.tournament(
+            [lambda board: ak.Direction.Left],
+            games_per_agent=1000,
+            progress=progress,
+            cancel=token,
+        )
+
+
+def test_keyboard_interrupt_stops_a_native_call() -> None:
+    timer = threading.Timer(0.2, _thread.interrupt_main)
+    timer.start()
+    with pytest.raises(KeyboardInterrupt):
+        ak.tune_heuristics(4, 100_000)
+    timer.join()
+
+
+def test_progress_errors_propagate() -> None:
+    def progress(done: int, total: int) -> None:
+        raise RuntimeError("stop")
+
+    with pytest.raises(RuntimeError):
+        ak.tournament(["random"], games_per_agent=3, progress=progress)
+    with pytest.raises(ValueError):
+        ak.tournament(["random"], progress=3)
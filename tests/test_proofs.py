@@ -0,0 +1,62 @@
+import akioi_2048 as ak
+
+MOVES = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right, ak.Direction.Up] * 5
+
+
+def play(seed: int) -> ak.Game:
+    game = ak.Game(seed=seed, proofs=True)
+    for direction in MOVES:
+        game.step(direction)
+    return game
+
+
+def test_seeded_games_are_reproducible() -> None:
+    a, b = play(11), play(11)
+    assert a.board == b.board
+    assert a.score == b.score
+    assert a.moves == len(MOVES)
+
+
+def test_records_chain_together() -> None:
+    records = play(5).records()
+    assert records
+    assert records[0].draw_index == 2
+    for prev, cur in zip(records, records[1:]):
+        assert cur.pre_hash == prev.post_hash
+        assert cur.draw_index == prev.draw_index + 1
+    assert ak.verify_chain(records)
+    assert ak.verify_chain(records, seed=5)
+
+
+def test_scripted_spawns_keep_the_chain_in_sequence() -> None:
+    game = ak.Game(
+        seed=5, proofs=True, spawns=[((0, 0), 2), ((0, 3), 4), ((3, 0), 2)]
+    )
+    for direction in MOVES:
+        game.step(direction)
+    records = game.records()
+    assert len(records) > 3
+    for prev, cur in zip(records, records[1:]):
+        assert cur.draw_index == prev.draw_index + 1
+    assert ak.verify_chain(records)
+
+
+def test_tampered_chain_is_rejected() -> None:
+    records = play(5).records()
+    r = records[1]
+    forged = ak.MoveRecord(
+        r.index, r.pre_hash, r.direction, bytes(32), r.draw_index, r.chain
+    )
+    assert not ak.verify_chain([records[0], forged, *records[2:]])
+    assert not ak.verify_chain(records[1:])
+
+
+def test_replay_with_wrong_seed_is_rejected() -> None:
+    records = play(5).records()
+    assert not ak.verify_chain(records, seed=6)
+
+
+def test_games_without_proofs_have_no_records() -> None:
+    game = ak.Game(seed=1)
+    game.step(ak.Direction.Down)
+    assert game.records() == []
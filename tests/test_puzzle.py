@@ -0,0 +1,62 @@
+import pytest
+
+import akioi_2048 as ak
+
+BOARD = ak.board_with({(3, 0): 2, (3, 1): 2, (2, 0): 4})
+
+
+def test_puzzle_solved() -> None:
+    puzzle = ak.Puzzle(BOARD, max_moves=3, tile=8)
+    result = puzzle.check([ak.Direction.Left, ak.Direction.Down])
+    assert result.solved
+    assert result.outcome == "solved"
+    assert result.moves == 2
+    assert max(max(row) for row in result.board) >= 8
+    assert result.score == 12
+
+
+def test_puzzle_out_of_moves() -> None:
+    puzzle = ak.Puzzle(BOARD, max_moves=1, tile=8)
+    result = puzzle.check([ak.Direction.Left, ak.Direction.Down])
+    assert result.outcome == "out_of_moves"
+    assert result.moves == 1
+    assert not result.solved
+
+
+def test_puzzle_in_progress() -> None:
+    puzzle = ak.Puzzle(BOARD, max_moves=5, score=1000)
+    result = puzzle.check([0])
+    assert result.outcome == "in_progress"
+    assert result.moves == 1
+
+
+def test_puzzle_game_over() -> None:
+    board = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+    result = ak.Puzzle(board, max_moves=10, tile=8).check([ak.Direction.Up])
+    assert result.outcome == "game_over"
+    assert result.moves == 0
+
+
+def test_puzzle_clear_multipliers() -> None:
+    board = ak.board_with({(3, 0): -2, (3, 1): 4})
+    puzzle = ak.Puzzle(board, max_moves=1, clear_multipliers=True)
+    assert puzzle.check([ak.Direction.Left]).solved
+
+
+def test_puzzle_is_deterministic() -> None:
+    puzzle = ak.Puzzle(BOARD, max_moves=20, tile=65536, seed=4)
+    moves = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right, ak.Direction.Up] * 5
+    first = puzzle.check(moves)
+    assert first.board == puzzle.check(moves).board
+    game = puzzle.game()
+    assert game.board == BOARD
+    for direction in moves:
+        game.step(direction)
+    assert game.board == first.board
+
+
+def test_puzzle_needs_a_goal() -> None:
+    with pytest.raises(ValueError, match="no goal"):
+        ak.Puzzle(BOARD, max_moves=3)
+    with pytest.raises(ValueError, match="invalid tile value: 3"):
+        ak.Puzzle([[3, 0, 0, 0]] + [[0] * 4] * 3, max_moves=1, tile=4)
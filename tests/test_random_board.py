@@ -0,0 +1,63 @@
+import pytest
+
+import akioi_2048 as ak
+
+CORNERS = [(0, 0), (0, 3), (3, 0), (3, 3)]
+
+
+def tiles(board) -> list[int]:
+    return [t for row in board for t in row if t != 0]
+
+
+def test_scattered_boards_are_live_and_fit_the_spec() -> None:
+    for seed in range(20):
+        board = ak.random_board(512, fill=0.75, seed=seed)
+        ak.validate(board)
+        assert len(tiles(board)) == 12
+        assert max(tiles(board)) == 512
+        assert any(board[r][c] == 512 for r, c in CORNERS)
+        assert not ak.is_game_over(board)
+
+
+def test_seeds_repeat_and_vary() -> None:
+    assert ak.random_board(seed=3) == ak.random_board(seed=3)
+    boards = {str(ak.random_board(seed=seed)) for seed in range(10)}
+    assert len(boards) == 10
+
+
+def test_multipliers_can_be_excluded() -> None:
+    seen = set()
+    for seed in range(20):
+        board = ak.random_board(fill=1.0, seed=seed)
+        seen.update(t for t in tiles(board) if t < 0)
+    assert seen & {-1, -2, -4}
+    for seed in range(20):
+        board = ak.random_board(fill=1.0, seed=seed, include_multipliers=False)
+        assert min(tiles(board)) > 0
+
+
+def test_fill_has_at_least_one_tile() -> None:
+    assert tiles(ak.random_board(8, fill=0.01, seed=1)) == [8]
+    assert tiles(ak.random_board(2, fill=0.25, seed=1)) == [2, 2, 2, 2]
+
+
+def test_reachable_boards_come_from_play() -> None:
+    for seed in range(3):
+        board = ak.random_board(256, fill=0.5, seed=seed, reachable=True)
+        ak.validate(board)
+        assert max(tiles(board)) == 256
+        assert len(tiles(board)) >= 8
+        clean = ak.random_board(
+            128, seed=seed, reachable=True, include_multipliers=False
+        )
+        assert max(tiles(clean)) == 128
+        assert min(tiles(clean)) > 0
+
+
+def test_invalid_specs() -> None:
+    for max_tile in [0, 1, 3, 100, 131072]:
+        with pytest.raises(ValueError, match="max_tile"):
+            ak.random_board(max_tile)
+    for fill in [0.0, -0.5, 1.5]:
+        with pytest.raises(ValueError, match="fill"):
+            ak.random_board(fill=fill)
@@ -0,0 +1,36 @@
+import akioi_2048 as ak
+
+BOARD = ak.board_with({(3, 0): 2})
+
+
+def test_enumerate_successors_merges_duplicates() -> None:
+    assert ak.enumerate_successors(BOARD, depth=0) == [BOARD]
+    boards = ak.enumerate_successors(BOARD)
+    # Right and Up, 15 cells, four spawn tiles; a 2 in both corners twice
+    assert len(boards) == 2 * 15 * 4 - 1
+    assert boards == sorted(boards)
+    assert ak.board_with({(0, 0): 2, (3, 3): 2}) in boards
+    for board in boards:
+        ak.validate(board)
+
+
+def test_counts_match_the_enumeration() -> None:
+    counts = ak.count_reachable_states(BOARD, 3)
+    assert counts[0] == 1
+    for depth in range(1, 4):
+        assert counts[depth] == len(ak.enumerate_successors(BOARD, depth))
+
+
+def test_multipliers_widen_the_tree() -> None:
+    akioi = ak.count_reachable_states(BOARD, 3)
+    classic = ak.count_reachable_states(BOARD, 3, numbers_only=True)
+    assert classic[1] == 2 * 15 * 2 - 1
+    for a, c in zip(akioi[1:], classic[1:]):
+        assert a > c
+    for board in ak.enumerate_successors(BOARD, 2, numbers_only=True):
+        assert all(tile >= 0 for row in board for tile in row)
+
+
+def test_dead_boards_have_no_successors() -> None:
+    dead = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+    assert ak.count_reachable_states(dead, 2) == [1, 0, 0]
@@ -0,0 +1,61 @@
+import random
+
+import akioi_2048 as ak
+
+DIRECTIONS = [ak.Direction.Down, ak.Direction.Right, ak.Direction.Up, ak.Direction.Left]
+TILES = [0, 0, 0, 0, 2, 2, 4, 8, 32768, 65536, -1, -1, -2, -4]
+
+
+def random_board(rng: random.Random) -> list[list[int]]:
+    return [[rng.choice(TILES) for _ in range(4)] for _ in range(4)]
+
+
+def test_engine_matches_reference_on_random_boards() -> None:
+    rng = random.Random(0)
+    for rules in [ak.Rules(), ak.Rules.preset("mercy")]:
+        for _ in range(300):
+            board = random_board(rng)
+            for direction in DIRECTIONS:
+                new_board, _, _ = ak.step(board, direction, rules=rules)
+                assert ak.verify_step(board, direction, new_board, rules=rules)
+
+
+def test_engine_matches_reference_at_other_caps() -> None:
+    rng = random.Random(1)
+    tiles = [0, 0, 0, 0, 2, 4, 1024, 2048, 2048, -1, -2]
+    for overflow in ["block", "win", "wrap"]:
+        rules = ak.Rules(max_tile=2048, overflow=overflow)
+        for _ in range(200):
+            board = [[rng.choice(tiles) for _ in range(4)] for _ in range(4)]
+            for direction in DIRECTIONS:
+                new_board, _, _ = ak.step(board, direction, rules=rules)
+                assert ak.verify_step(board, direction, new_board, rules=rules)
+
+
+def test_verify_step_rejects_wrong_results() -> None:
+    board = ak.board_with({(0, 0): 2, (0, 1): 2})
+
+    def check(tiles: dict[tuple[int, int], int]) -> bool:
+        return ak.verify_step(board, ak.Direction.Left, ak.board_with(tiles))
+
+    assert check({(0, 0): 4, (3, 3): 2})
+    assert not check({(0, 0): 4})
+    assert not check({(0, 0): 2, (0, 1): 2, (3, 3): 2})
+    assert not check({(0, 0): 4, (3, 3): 8})
+    assert not check({(0, 0): 4, (3, 3): 2, (2, 2): 2})
+
+
+def test_verify_step_without_change() -> None:
+    board = ak.board_with({(0, 0): 2})
+    assert ak.verify_step(board, ak.Direction.Left, board)
+    assert not ak.verify_step(board, ak.Direction.Left, ak.board_with({(0, 0): 2, (1, 1): 2}))
+
+
+def test_verify_step_multiplier_edge_cases() -> None:
+    # ×2 only applies when every cell toward the wall is occupied
+    board = [[0, 0, 0, 0], [8, 0, 0, 0], [-2, 0, 0, 0], [0, 0, 0, 0]]
+    after = [[0, 0, 0, 0], [0, 0, 0, 0], [8, 0, 0, 0], [-2, 0, 0, 2]]
+    assert ak.verify_step(board, ak.Direction.Down, after)
+    full = [[0, 0, 0, 0], [8, 0, 0, 0], [-2, 0, 0, 0], [-1, 0, 0, 0]]
+    merged = [[0, 0, 0, 0], [0, 0, 0, 0], [16, 0, 0, 0], [-1, 0, 0, 2]]
+    assert ak.verify_step(full, ak.Direction.Down, merged)
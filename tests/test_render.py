@@ -0,0 +1,36 @@
+import pytest
+
+import akioi_2048 as ak
+
+BOARD = [
+    [2, 0, -1, 0],
+    [0, 65536, 0, -4],
+    [8, 0, 0, 0],
+    [0, 0, -2, 1024],
+]
+
+
+def test_unicode_grid_is_aligned() -> None:
+    lines = ak.render(BOARD).splitlines()
+    assert len(lines) == 9
+    assert lines[0].startswith("┌") and lines[-1].endswith("┘")
+    assert len({len(line) for line in lines}) == 1
+    assert "×1" in lines[1] and "×4" in lines[3] and "65536" in lines[3]
+
+
+def test_ascii_uses_plain_characters() -> None:
+    text = ak.render(BOARD, style="ascii")
+    assert text.isascii()
+    assert "x2" in text
+    assert text.splitlines()[0] == "+" + "-------+" * 4
+
+
+def test_ansi_colours_every_tile() -> None:
+    lines = ak.render(BOARD, style="ansi").splitlines()
+    assert len(lines) == 4
+    assert all(line.count("\x1b[0m") == 4 for line in lines)
+
+
+def test_unknown_style_is_rejected() -> None:
+    with pytest.raises(ValueError, match="unknown render style"):
+        ak.render(BOARD, style="html")
@@ -0,0 +1,113 @@
+import struct
+
+import pytest
+
+import akioi_2048 as ak
+
+MOVES = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right, ak.Direction.Up] * 4
+
+
+def played(seed: int = 3) -> ak.Game:
+    game = ak.Game(seed=seed, rules=ak.Rules.preset("mercy"))
+    for direction in MOVES:
+        game.step(direction)
+    return game
+
+
+def test_replay_reproduces_the_game() -> None:
+    game = played()
+    replay = game.replay()
+    assert replay.seed == 3
+    assert replay.rules.tag == "akioi-v2+mercy"
+    assert len(replay) <= len(MOVES)
+    again = replay.game()
+    assert again.board == game.board
+    assert again.score == game.score
+    frames = replay.frames()
+    assert len(frames) == len(replay) + 1
+    assert frames[-1] == game.board
+
+
+def test_replay_text_round_trip() -> None:
+    replay = played().replay()
+    text = replay.to_text()
+    stamp = ak.engine_stamp(ak.Rules.preset("mercy"))
+    header = f"akioi-2048 replay 2\nrules akioi-v2+mercy\nstamp {stamp}\nseed 3\n"
+    assert text.startswith(header + "moves ")
+    assert ak.Replay.parse(text) == replay
+    with pytest.raises(ValueError, match="not an akioi-2048 replay"):
+        ak.Replay.parse("hello")
+
+
+def test_replay_rejects_moves_that_do_not_play_back() -> None:
+    seed, stuck = next(
+        (seed, d)
+        for seed in range(100)
+        for d in ak.Direction
+        if ak.step(ak.Game(seed=seed).board, d)[0] == ak.Game(seed=seed).board
+    )
+    with pytest.raises(ValueError, match="does not change the board"):
+        ak.Replay(seed, [stuck]).frames()
+
+
+def lzw_decode(data: bytes, min_size: int) -> list:
+    clear, end = 1 << min_size, (1 << min_size) + 1
+    bits = int.from_bytes(data, "little")
+    pos, width, out = 0, min_size + 1, []
+    table, prev = {}, None
+    while True:
+        code = (bits >> pos) & ((1 << width) - 1)
+        pos += width
+        if code == clear:
+            table = {i: [i] for i in range(clear)}
+            width, prev = min_size + 1, None
+            continue
+        if code == end:
+            return out
+        entry = table[code] if code in table else table[prev] + [table[prev][0]]
+        out.extend(entry)
+        if prev is not None and len(table) + 2 < 4096:
+            table[len(table) + 2] = table[prev] + [entry[0]]
+            if len(table) + 2 == 1 << width and width < 12:
+                width += 1
+        prev = code
+
+
+def gif_frames(data: bytes):
+    assert data[:6] == b"GIF89a"
+    width, height, flags = struct.unpack("<HHB", data[6:11])
+    palette = data[13 : 13 + 3 * 256]
+    pos, frames = 13 + 3 * 256, []
+    while data[pos] != 0x3B:
+        if data[pos] == 0x21:  # extension: skip its sub-blocks
+            pos += 2
+            while data[pos]:
+                pos += data[pos] + 1
+            pos += 1
+            continue
+        assert data[pos] == 0x2C
+        min_size, pos = data[pos + 10], pos + 11
+        stream = b""
+        while data[pos]:
+            stream += data[pos + 1 : pos + 1 + data[pos]]
+            pos += data[pos] + 1
+        pos += 1
+        frames.append(lzw_decode(stream, min_size))
+    return width, height, palette, frames
+
+
+def test_export_gif_has_one_decodable_frame_per_board(tmp_path) -> None:
+    replay = played().replay()
+    path = tmp_path / "game.gif"
+    ak.export_gif(replay, path, fps=10)
+    width, height, palette, frames = gif_frames(path.read_bytes())
+    assert (width, height) == (460, 460)
+    assert len(frames) == len(replay) + 1
+    assert all(len(f) == width * height for f in frames)
+    corner = frames[0][0]
+    assert palette[3 * corner : 3 * corner + 3] == bytes.fromhex("bbada0")
+
+
+def test_export_gif_validates_fps(tmp_path) -> None:
+    with pytest.raises(ValueError, match="fps"):
+        ak.export_gif(played().replay(), tmp_path / "game.gif", fps=0)
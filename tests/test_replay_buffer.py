@@ -0,0 +1,126 @@
+import pytest
+
+import akioi_2048 as ak
+
+
+def board(value: int) -> list[list[int]]:
+    return ak.board_with({(0, 0): value})
+
+
+def filled(capacity: int, count: int, **kwargs) -> ak.ReplayBuffer:
+    buf = ak.ReplayBuffer(capacity, seed=1, **kwargs)
+    for k in range(count):
+        buf.push(board(2 ** (k + 1)), k % 4, float(k), board(2 ** (k + 2)), k == 3)
+    return buf
+
+
+def test_ring_evicts_the_oldest() -> None:
+    buf = filled(3, 5)
+    assert len(buf) == 3
+    assert buf.capacity == 3
+    assert not buf.prioritized
+    batch = buf.sample(64, return_numpy=False)
+    assert set(batch["rewards"]) <= {2.0, 3.0, 4.0}
+    assert len(set(batch["rewards"])) > 1
+
+
+def test_sample_lists_match_pushed_transitions() -> None:
+    buf = filled(8, 4)
+    batch = buf.sample(32, return_numpy=False)
+    assert len(batch["states"]) == 32
+    assert batch["weights"] == [1.0] * 32
+    for k, state, action, next_state, done, goal in zip(
+        batch["rewards"],
+        batch["states"],
+        batch["actions"],
+        batch["next_states"],
+        batch["dones"],
+        batch["goals"],
+    ):
+        k = int(k)
+        assert state == board(2 ** (k + 1))
+        assert next_state == board(2 ** (k + 2))
+        assert action == k % 4
+        assert done == (k == 3)
+        assert goal == 0
+
+
+def test_push_accepts_directions_and_goals() -> None:
+    buf = ak.ReplayBuffer(1, seed=0)
+    buf.push(board(2), ak.Direction.Left, 1.0, board(4), False, goal=2048)
+    batch = buf.sample(1, return_numpy=False)
+    assert batch["actions"] == [3]
+    assert batch["goals"] == [2048]
+    assert batch["indices"] == [0]
+
+
+def test_prioritized_sampling_and_updates() -> None:
+    buf = ak.ReplayBuffer(4, prioritized=True, alpha=1.0, seed=3)
+    assert buf.prioritized
+    for k in range(4):
+        priority = 100.0 if k == 2 else 0.01
+        buf.push(board(2), 0, float(k), board(4), False, priority=priority)
+    batch = buf.sample(200, return_numpy=False)
+    assert batch["rewards"].count(2.0) > 180
+    buf.update_priorities([2, 0], [0.01, 100.0])
+    batch = buf.sample(200, return_numpy=False)
+    assert batch["rewards"].count(0.0) > 180
+    buf.update_priorities([0, 1, 2, 3], [1.0, 1.0, 1.0, 5.0])
+    batch = buf.sample(200, return_numpy=False)
+    for index, weight in zip(batch["indices"], batch["weights"]):
+        if index == 3:
+            assert weight < 1.0
+        else:
+            assert weight == pytest.approx(1.0)
+
+
+def test_errors() -> None:
+    with pytest.raises(ValueError, match="capacity"):
+        ak.ReplayBuffer(0)
+    buf = ak.ReplayBuffer(4)
+    with pytest.raises(ValueError, match="empty"):
+        buf.sample(1)
+    with pytest.raises(ValueError, match="cannot pack tile 3"):
+        buf.push([[3, 0, 0, 0]] + [[0] * 4] * 3, 0, 0.0, board(4), False)
+    with pytest.raises(ValueError, match="action id"):
+        buf.push(board(2), 7, 0.0, board(4), False)
+    buf.push(board(2), 0, 0.0, board(4), False)
+    with pytest.raises(ValueError, match="not prioritized"):
+        buf.update_priorities([0], [1.0])
+    buf = ak.ReplayBuffer(4, prioritized=True)
+    buf.push(board(2), 0, 0.0, board(4), False)
+    with pytest.raises(ValueError, match="out of range"):
+        buf.update_priorities([1], [1.0])
+    with pytest.raises(ValueError, match="non-negative"):
+        buf.update_priorities([0], [-1.0])
+
+
+def test_save_and_load(tmp_path) -> None:
+    path = tmp_path / "buffer.bin"
+    buf = filled(8, 4, prioritized=True)
+    buf.update_priorities([1], [50.0])
+    buf.save(path)
+    loaded = ak.ReplayBuffer.load(str(path), seed=1)
+    assert len(loaded) == 4
+    assert loaded.capacity == 8
+    assert loaded.prioritized
+    again = ak.ReplayBuffer.load(path, seed=1)
+    assert loaded.sample(16, return_numpy=False) == again.sample(
+        16, return_numpy=False
+    )
+    bad = tmp_path / "bad.bin"
+    bad.write_bytes(b"not a replay buffer" * 4)
+    with pytest.raises(ValueError, match="replay buffer"):
+        ak.ReplayBuffer.load(bad)
+
+
+def test_sample_numpy() -> None:
+    pytest.importorskip("numpy")
+    buf = filled(8, 4)
+    batch = buf.sample(5)
+    assert batch["states"].shape == (5, 4, 4)
+    assert str(batch["actions"].dtype) == "uint8"
+    lists = dict(zip(batch, (batch[key].tolist() for key in batch)))
+    for state, reward in zip(lists["states"], lists["rewards"]):
+        assert state == board(2 ** (int(reward) + 1))
+    assert lists["weights"] == [1.0] * 5
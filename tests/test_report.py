@@ -0,0 +1,48 @@
+import itertools
+
+import akioi_2048 as ak
+
+FIELDS = {
+    "games",
+    "moves",
+    "median_score",
+    "win_rate",
+    "blunder_rate",
+    "timeouts",
+    "moves_per_second",
+}
+BATTERIES = {"seeded", "positions", "adversarial", "timed"}
+
+
+def cycling_agent():
+    order = itertools.cycle(
+        [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right, ak.Direction.Up]
+    )
+    return lambda board: next(order)
+
+
+def test_report_card_structure() -> None:
+    card = ak.report_card(cycling_agent(), seed=3, n_games=2)
+    assert card["seed"] == 3
+    assert set(card["batteries"]) == BATTERIES
+    assert set(card["overall"]) == FIELDS
+    assert card["batteries"]["seeded"]["games"] == 2
+    assert card["batteries"]["positions"]["games"] == 4
+    assert card["overall"]["games"] == 10
+    assert 0.0 <= card["overall"]["blunder_rate"] <= 1.0
+
+
+def test_report_card_is_seed_deterministic() -> None:
+    first = ak.report_card(cycling_agent(), seed=7, n_games=2)
+    second = ak.report_card(cycling_agent(), seed=7, n_games=2)
+    # the timed battery depends on wall-clock time, so only compare the others
+    for name in BATTERIES - {"timed"}:
+        a, b = first["batteries"][name], second["batteries"][name]
+        assert a["median_score"] == b["median_score"]
+        assert a["moves"] == b["moves"]
+
+
+def test_stuck_agent_blunders_every_move() -> None:
+    card = ak.report_card(lambda board: ak.Direction.Up, seed=1, n_games=1)
+    # after the board settles at the top every further Up is a no-op
+    assert card["overall"]["blunder_rate"] > 0.5
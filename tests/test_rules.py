@@ -0,0 +1,186 @@
+import pytest
+
+import akioi_2048 as ak
+
+
+def column(*values: int) -> list[list[int]]:
+    return [[v, 0, 0, 0] for v in values]
+
+
+def test_default_rules_match_standard() -> None:
+    rules = ak.Rules()
+    assert rules == ak.Rules(4, True, True)
+    assert rules.max_multiplier == 4
+    assert rules.multiplier_merge is True
+    assert rules.require_full_below is True
+
+
+def test_larger_max_multiplier_allows_negative_eight() -> None:
+    rules = ak.Rules(max_multiplier=8)
+    new_board, delta, _ = ak.step(column(0, 0, -4, -4), ak.Direction.Down, rules=rules)
+    assert new_board[3][0] == -8
+    assert delta == -8
+    with pytest.raises(ValueError, match=r"^invalid tile value: -8$"):
+        ak.step(column(0, 0, 0, -8), ak.Direction.Down)
+
+
+def test_smaller_max_multiplier_rejects_negative_four() -> None:
+    rules = ak.Rules(max_multiplier=2)
+    new_board, delta, _ = ak.step(column(0, 0, -2, -2), ak.Direction.Down, rules=rules)
+    assert new_board == column(0, 0, -2, -2)
+    assert delta == 0
+    with pytest.raises(ValueError, match=r"^invalid tile value: -4$"):
+        ak.step(column(0, 0, 0, -4), ak.Direction.Down, rules=rules)
+
+
+def test_multiplier_merge_can_be_disabled() -> None:
+    rules = ak.Rules(multiplier_merge=False)
+    new_board, delta, _ = ak.step(column(0, 0, -1, -1), ak.Direction.Down, rules=rules)
+    assert new_board == column(0, 0, -1, -1)
+    assert delta == 0
+
+
+def test_full_below_constraint_can_be_disabled() -> None:
+    rules = ak.Rules(require_full_below=False)
+    new_board, delta, _ = ak.step(column(2, -2, 0, 0), ak.Direction.Down, rules=rules)
+    assert new_board[3][0] == 4
+    assert delta == 4
+
+
+def test_rules_reject_bad_max_multiplier() -> None:
+    with pytest.raises(ValueError, match="max_multiplier must be a power of two"):
+        ak.Rules(max_multiplier=3)
+
+
+def test_rules_tags_identify_variants() -> None:
+    assert ak.Rules().tag == "akioi-v2"
+    assert ak.Rules.preset("mercy").tag == "akioi-v2+mercy"
+    assert ak.Rules(max_multiplier=8, mercy=True).tag == "akioi-v2+mercy+max_multiplier=8"
+    with pytest.raises(ValueError, match="unknown rules preset: hard"):
+        ak.Rules.preset("hard")
+
+
+def test_rules_tags_of_older_versions_are_rejected() -> None:
+    for tag in ("akioi-v1", "akioi-v1+mercy"):
+        with pytest.raises(ValueError, match="rules version 1, which this engine no"):
+            ak.rules_spec(tag)
+    with pytest.raises(ValueError, match="unsupported rules tag: akioi-v3"):
+        ak.init(rules="akioi-v3")
+    assert ak.rules_spec("akioi-v2+mercy")["mercy"]
+
+
+def test_mercy_never_spawns_x1_into_last_cell() -> None:
+    # one move leaves exactly one empty cell at (0, 0)
+    board = [
+        [2, 2, 4, 8],
+        [4, 2, 4, 2],
+        [2, 4, 2, 4],
+        [4, 2, 4, 2],
+    ]
+    rules = ak.Rules.preset("mercy")
+    spawned = {ak.step(board, ak.Direction.Right, rules=rules)[0][0][0] for _ in range(300)}
+    assert -1 not in spawned
+    assert spawned <= {2, 4, -2}
+
+
+def test_max_tile_caps_merges_and_wins() -> None:
+    rules = ak.Rules(max_tile=2048)
+    assert rules.tag == "akioi-v2+max_tile=2048"
+    twos = column(0, 0, 1024, 1024)
+    board, delta, state = ak.step(twos, ak.Direction.Down, rules=rules)
+    assert board[3][0] == 2048
+    assert delta == 2048
+    assert state == ak.State.Victory
+    with pytest.raises(ValueError, match=r"^invalid tile value: 4096$"):
+        ak.step(column(0, 0, 0, 4096), ak.Direction.Down, rules=rules)
+    # a multiplier merge stops at the cap too
+    board, _, _ = ak.step(column(0, 0, -2, 2048), ak.Direction.Down, rules=rules)
+    assert board[3][0] == 2048
+
+    larger = ak.Rules(max_tile=2**20)
+    board, delta, state = ak.step(
+        column(0, 0, 65536, 65536), ak.Direction.Down, rules=larger
+    )
+    assert board[3][0] == 131072
+    assert delta == 131072
+    assert state == ak.State.Continue
+    assert ak.rules_spec(larger)["winning_tile"] == 2**20
+    assert 2**20 in ak.rules_spec(larger)["tiles"]
+
+
+def test_overflow_behaviors() -> None:
+    top = column(0, 0, 2048, 2048)
+    block = ak.Rules(max_tile=2048, variant="classic")
+    assert ak.step(top, ak.Direction.Down, rules=block)[:2] == (top, 0)
+
+    win = ak.Rules(max_tile=2048, variant="classic", overflow="win")
+    assert win.overflow == "win"
+    # the cap no longer wins, two of them merging does
+    assert ak.step(column(0, 0, 1024, 1024), ak.Direction.Down, rules=win)[2] == (
+        ak.State.Continue
+    )
+    board, delta, state = ak.step(top, ak.Direction.Down, rules=win)
+    assert (board[3][0], delta, state) == (4096, 4096, ak.State.Victory)
+    assert ak.rules_spec(win)["winning_tile"] == 4096
+
+    wrap = ak.Rules(max_tile=2048, variant="classic", overflow="wrap")
+    board, delta, _ = ak.step(top, ak.Direction.Down, rules=wrap)
+    assert (board[3][0], delta) == (2, 4096)
+    fib = ak.Rules(max_tile=128, variant="fib", overflow="wrap")
+    assert ak.rules_spec(fib)["winning_tile"] == 89
+    board, delta, _ = ak.step(column(0, 0, 55, 89), ak.Direction.Down, rules=fib)
+    assert (board[3][0], delta) == (1, 144)
+
+
+def test_max_tile_settings_round_trip_through_tags() -> None:
+    rules = ak.Rules(max_tile=2**20, overflow="wrap")
+    tag = "akioi-v2+max_tile=1048576+overflow=wrap"
+    assert rules.tag == tag
+    spec = ak.rules_spec(tag)
+    assert (spec["max_tile"], spec["overflow"]) == (2**20, "wrap")
+    assert ak.rules_spec()["max_tile"] == 65536
+    assert ak.rules_spec()["overflow"] == "block"
+    with pytest.raises(ValueError, match="max_tile must be a power of two from 16"):
+        ak.Rules(max_tile=1000)
+    with pytest.raises(ValueError, match="unknown overflow: bounce"):
+        ak.Rules(overflow="bounce")
+    with pytest.raises(ValueError, match="at least base"):
+        ak.Rules(variant="classic", base=5, max_tile=16)
+
+
+def test_scoring_rules_change_deltas() -> None:
+    pair = column(0, 0, -1, -1)
+    mixed = [[0, 0, 0, 0], [0, 0, 0, 0], [-1, 2, 0, 0], [-1, 2, 0, 0]]
+    expected = {"standard": (-2, 2), "web": (0, 4), "clamp": (0, 2)}
+    for scoring, deltas in expected.items():
+        rules = ak.Rules(scoring=scoring)
+        assert rules.scoring == scoring
+        got = [ak.step(b, ak.Direction.Down, rules=rules)[1] for b in [pair, mixed]]
+        assert tuple(got) == deltas
+
+    clamp = ak.Rules(scoring="clamp")
+    result = ak.step_detailed(pair, ak.Direction.Down, rules=clamp)
+    assert (result.delta, [m.score for m in result.merges]) == (0, [-2])
+    web = ak.rules_spec(ak.Rules(scoring="web"))
+    assert all(m["score"] >= 0 for m in web["merges"])
+
+
+def test_non_negative_scoring_keeps_rewards_non_negative() -> None:
+    for scoring in ["web", "clamp"]:
+        game = ak.Game(seed=3, rules=ak.Rules(scoring=scoring))
+        for i in range(400):
+            if game.state != ak.State.Continue:
+                break
+            _, delta, _ = game.step(list(ak.Direction)[i % 4])
+            assert delta >= 0
+        assert game.score >= 0
+
+
+def test_scoring_round_trips_through_tags() -> None:
+    rules = ak.Rules(scoring="web")
+    assert rules.tag == "akioi-v2+scoring=web"
+    assert ak.rules_spec(rules.tag)["scoring"] == "web"
+    assert "scoring='web'" in repr(rules)
+    assert ak.rules_spec()["scoring"] == "standard"
+    with pytest.raises(ValueError, match="unknown scoring: signed"):
+        ak.Rules(scoring="signed")
@@ -0,0 +1,47 @@
+import pytest
+
+import akioi_2048 as ak
+
+
+def test_merge_table_agrees_with_merge_preview():
+    for rules in ("akioi", "classic", "fib", ak.Rules(require_full_below=True)):
+        spec = ak.rules_spec(rules)
+        merges = {(m["a"], m["b"]): m for m in spec["merges"]}
+        for a in spec["tiles"]:
+            for b in spec["tiles"]:
+                found = ak.merge_preview(a, b, rules=rules)
+                merge = merges.get((a, b))
+                expected = None if merge is None else (merge["tile"], merge["score"])
+                assert found == expected
+                if merge is None:
+                    continue
+                apart = ak.merge_preview(a, b, adjacent=False, rules=rules)
+                assert (apart is None) == merge["needs_adjacent"]
+                gap = ak.merge_preview(a, b, below=[0], rules=rules)
+                assert (gap is None) == merge["needs_full_below"]
+
+
+def test_default_rules():
+    spec = ak.rules_spec()
+    assert spec["version"] == 2
+    assert spec["tag"] == ak.Rules().tag
+    assert spec["variant"] == "akioi"
+    assert spec["tiles"] == [-4, -2, -1] + [2**k for k in range(1, 17)]
+    assert spec["blocker"] is None and spec["bomb"] is None
+    assert not spec["blockers"]
+    assert spec["winning_tile"] == 65536
+    assert spec["invalid_moves"] == "noop"
+    assert {(m["a"], m["b"]) for m in spec["merges"] if m["needs_adjacent"]}
+    assert spec["spawns"] == ak.spawn_probabilities()
+
+
+def test_variants_and_options():
+    assert ak.rules_spec("fib")["winning_tile"] == 46368
+    classic = ak.rules_spec("classic")
+    assert all(m["a"] == m["b"] for m in classic["merges"])
+    assert classic["spawns"] == ak.spawn_probabilities(rules=ak.Rules.preset("classic"))
+    assert ak.rules_spec(ak.Rules(bombs=True))["bomb"] == -5
+    assert ak.rules_spec(invalid_moves="mask")["invalid_moves"] == "mask"
+    with pytest.raises(ValueError):
+        ak.rules_spec(invalid_moves="ignore")
+
@@ -0,0 +1,95 @@
+import pytest
+
+import akioi_2048 as ak
+
+
+def test_board_with_places_tiles() -> None:
+    board = ak.board_with({(3, 0): 2, (3, 1): 2, (0, 2): -2})
+    assert board == [
+        [0, 0, -2, 0],
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+        [2, 2, 0, 0],
+    ]
+
+
+def test_board_with_rejects_bad_input() -> None:
+    with pytest.raises(ValueError, match="outside the board"):
+        ak.board_with({(4, 0): 2})
+    with pytest.raises(ValueError, match="invalid tile value: 3"):
+        ak.board_with({(0, 0): 3})
+
+
+def test_from_board_starts_at_the_position() -> None:
+    board = ak.board_with({(3, 0): 2, (3, 1): 2})
+    game = ak.Game.from_board(board, score=100, seed=5)
+    assert game.board == board
+    assert game.score == 100
+    assert game.moves == 0
+    new_board, delta, _ = game.step(ak.Direction.Left)
+    assert new_board[3][0] == 4
+    assert delta == 4
+    assert game.score == 104
+    assert sum(tile != 0 for row in new_board for tile in row) == 2
+
+
+def test_from_board_is_seeded() -> None:
+    board = ak.board_with({(0, 0): 8})
+    games = [ak.Game.from_board(board, seed=9) for _ in range(2)]
+    for game in games:
+        for direction in [ak.Direction.Down, ak.Direction.Right, ak.Direction.Up]:
+            game.step(direction)
+    assert games[0].board == games[1].board
+
+
+def test_from_board_rejects_invalid_tiles() -> None:
+    with pytest.raises(ValueError, match="invalid tile value: 131072"):
+        ak.Game.from_board([[131072, 0, 0, 0]] + [[0] * 4] * 3)
+
+
+def test_from_board_replays() -> None:
+    board = ak.board_with({(0, 0): 4, (1, 0): 4, (2, 2): -1})
+    game = ak.Game.from_board(board, score=12, seed=2)
+    for direction in [ak.Direction.Down, ak.Direction.Left, ak.Direction.Up]:
+        game.step(direction)
+    replay = ak.Replay.parse(game.replay().to_text())
+    assert replay.start == (board, 12)
+    again = replay.game()
+    assert again.board == game.board
+    assert again.score == game.score
+    assert replay.frames()[0] == board
+    assert ak.Game(seed=2).replay().start is None
+
+
+def test_scripted_spawns_are_placed_in_order() -> None:
+    board = ak.board_with({(3, 0): 2, (3, 1): 2})
+    game = ak.Game.from_board(board, spawns=[((0, 3), 4), ((0, 0), -1)])
+    assert game.step(ak.Direction.Left)[0] == ak.board_with({(3, 0): 4, (0, 3): 4})
+    assert game.step(ak.Direction.Down)[0] == ak.board_with(
+        {(3, 0): 4, (3, 3): 4, (0, 0): -1}
+    )
+
+
+def test_scripted_spawns_skip_invalid_moves() -> None:
+    board = ak.board_with({(3, 0): 2})
+    game = ak.Game.from_board(board, spawns=[((3, 3), 2)])
+    game.step(ak.Direction.Down)
+    assert game.board == board
+    game.step(ak.Direction.Up)
+    assert game.board == ak.board_with({(0, 0): 2, (3, 3): 2})
+
+
+def test_scripted_spawn_on_occupied_cell_raises() -> None:
+    board = ak.board_with({(3, 0): 2})
+    game = ak.Game.from_board(board, spawns=[((0, 0), 2)])
+    with pytest.raises(ValueError, match="occupied"):
+        game.step(ak.Direction.Up)
+    assert game.board == board
+    assert game.moves == 0
+
+
+def test_scripted_spawns_are_validated() -> None:
+    with pytest.raises(ValueError, match="outside the board"):
+        ak.Game(spawns=[((0, 4), 2)])
+    with pytest.raises(ValueError, match="invalid tile value: 3"):
+        ak.Game(spawns=[((0, 0), 3)])
@@ -0,0 +1,72 @@
+import random
+
+import pytest
+
+import akioi_2048 as ak
+
+CLASSIC = ak.Rules.preset("classic")
+
+
+def test_bounds_of_simple_tile_sets():
+    assert ak.score_upper_bound([[2, 2, 2, 2]] + [[0] * 4] * 3, CLASSIC) == 16
+    assert ak.score_upper_bound([[2] * 4] * 4) == 128
+    assert ak.score_upper_bound(ak.board_with({(0, 0): 2, (3, 3): 4})) == 0
+    doubled = ak.board_with({(0, 0): 2, (1, 1): 2, (2, 2): -2})
+    assert ak.score_upper_bound(doubled) == 12
+    blockers = ak.Rules(blockers=True)
+    walled = ak.board_with({(0, 0): 2, (0, 1): -3, (0, 2): 2}, blockers)
+    assert ak.score_upper_bound(walled, blockers) == 4
+
+
+def test_no_move_beats_the_bound():
+    rng = random.Random(7)
+    tiles = [0, 0, 0, 2, 2, 4, 4, 8, -1, -2]
+    for _ in range(40):
+        board = [[rng.choice(tiles) for _ in range(4)] for _ in range(4)]
+        bound = ak.score_upper_bound(board)
+        for direction in ak.Direction:
+            result = ak.step_detailed(board, direction)
+            if not result.moved:
+                continue
+            slid = [row[:] for row in result.board]
+            r, c = result.spawn.cell
+            slid[r][c] = 0
+            assert result.delta + ak.score_upper_bound(slid) <= bound
+
+
+def test_rejects_invalid_boards():
+    with pytest.raises(ak.InvalidBoardError):
+        ak.score_upper_bound([[3] * 4] * 4)
+
+
+def test_check_consistency_bounds_the_score_by_the_tiles() -> None:
+    classic = ak.Rules.preset("classic")
+    board = [[8, 0, 0, 0], [0] * 4, [0] * 4, [0, 0, 0, 2]]
+    report = ak.check_consistency(board, 12, classic)
+    assert report["consistent"]
+    assert (report["min_score"], report["max_score"], report["step"]) == (8, 16, 4)
+    assert report["problems"] == []
+
+    for score, problem in ((4, "below the 8"), (20, "above the 16"), (10, "multiple")):
+        report = ak.check_consistency(board, score, classic)
+        assert not report["consistent"]
+        [message] = report["problems"]
+        assert problem in message
+
+
+def test_check_consistency_accepts_played_games() -> None:
+    for rules in (None, ak.Rules.preset("classic"), ak.Rules.preset("fib")):
+        game = ak.Game(seed=11, rules=rules)
+        moves = random.Random(5)
+        while game.state == ak.State.Continue and game.moves < 300:
+            game.step(moves.choice(list(ak.Direction)))
+        report = ak.check_consistency(game.board, game.score, rules)
+        assert report["consistent"], report["problems"]
+
+
+def test_multiplier_merges_leave_no_upper_bound() -> None:
+    board = [[65536, 0, 0, 0], [0] * 4, [0] * 4, [0, 0, 0, 2]]
+    report = ak.check_consistency(board, 12)
+    assert report["max_score"] is None
+    assert not report["consistent"]
+    assert "below" in report["problems"][0]
@@ -0,0 +1,127 @@
+import json
+import os
+import pathlib
+import subprocess
+import sys
+
+import akioi_2048 as ak
+
+
+def serve(*requests):
+    env = dict(os.environ, PYTHONPATH=str(pathlib.Path(ak.__file__).parent.parent))
+    lines = [r if isinstance(r, str) else json.dumps(r) for r in requests]
+    done = subprocess.run(
+        [sys.executable, "-m", "akioi_2048", "serve", "--stdio"],
+        input="\n".join(lines) + "\n",
+        capture_output=True,
+        text=True,
+        env=env,
+        timeout=30,
+    )
+    assert done.returncode == 0, done.stderr
+    return [json.loads(line) for line in done.stdout.splitlines()]
+
+
+def call(id, method, **params):
+    return {"jsonrpc": "2.0", "id": id, "method": method, "params": params}
+
+
+def test_server_plays_like_the_library():
+    game = ak.Game(seed=11)
+    moves = [ak.Direction.Down, ak.Direction.Right, ak.Direction.Down]
+    replies = serve(
+        call(1, "new_game", seed=11),
+        *(call(2 + i, "step", game=1, direction=d.name) for i, d in enumerate(moves)),
+        call(9, "state", game=1),
+        call(10, "replay", game=1),
+    )
+    new_game = replies[0]["result"]
+    assert new_game["game"] == 1
+    assert new_game["rules"] == "akioi-v2"
+    assert new_game["board"] == game.board
+    for direction, reply in zip(moves, replies[1:4]):
+        board, delta, _ = game.step(direction)
+        assert reply["result"]["board"] == board
+        assert reply["result"]["delta"] == delta
+        assert reply["result"]["score"] == game.score
+    assert replies[4]["result"]["moves"] == 3
+    assert replies[4]["result"]["state"] == "Continue"
+    assert replies[5]["result"]["replay"] == game.replay().to_text()
+
+
+def test_server_answers_batches_in_order():
+    replies = serve(
+        json.dumps(
+            [
+                call("a", "new_game", seed=1, rules="classic"),
+                call("b", "step", game=1, direction=0),
+                {"jsonrpc": "2.0", "method": "close", "params": {"game": 1}},
+                call("c", "state", game=1),
+            ]
+        )
+    )
+    assert len(replies) == 1
+    batch = replies[0]
+    assert [r["id"] for r in batch] == ["a", "b", "c"]
+    assert batch[0]["result"]["rules"] == ak.Rules.preset("classic").tag
+    assert "result" in batch[1]
+    assert batch[2]["error"] == {"code": -32602, "message": "unknown game: 1"}
+
+
+def test_server_suggests_a_move_for_a_board():
+    board = [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [2, 2, 4, 8]]
+    quick, timed = serve(
+        call(1, "suggest", board=board, depth=1),
+        call(2, "suggest", board=board, depth=40, time_budget=0.05),
+    )
+    result = quick["result"]
+    assert result["direction"] in ("left", "right")
+    assert set(result["values"]) <= {"up", "down", "left", "right"}
+    assert result["summary"]
+    assert result["depth"] == 1
+    assert 1 <= timed["result"]["depth"] <= 40
+
+
+def test_server_reports_errors():
+    replies = serve(
+        "{not json",
+        call(1, "fly"),
+        call(2, "step", game=1, direction="up"),
+        call(3, "new_game", rules="nonsense"),
+        "[]",
+    )
+    codes = [r["error"]["code"] for r in replies]
+    assert codes == [-32700, -32601, -32602, -32602, -32600]
+    assert replies[0]["id"] is None
+    assert "invalid request JSON" in replies[0]["error"]["message"]
+    assert replies[1]["error"]["message"] == "unknown method: fly"
+
+
+def test_server_simulates_a_replayable_game():
+    (reply,) = serve(call(1, "simulate", seed=3, max_moves=40))
+    result = reply["result"]
+    assert result["moves"] == 40
+    game = ak.Replay.parse(result["replay"]).game()
+    assert game.board == result["board"]
+    assert game.score == result["score"]
+    assert result["max_tile"] == max(max(row) for row in game.board)
+
+
+def test_server_rules_spec():
+    (reply,) = serve(call(1, "rules_spec", rules="fib", invalid_moves="penalize"))
+    spec = ak.rules_spec("fib", invalid_moves="penalize")
+    spec["spawns"] = {str(t): p for t, p in spec["spawns"].items()}
+    assert reply["result"] == spec
+
+
+def test_server_survives_hostile_lines():
+    replies = serve(
+        "[" * 100_000,
+        '{"jsonrpc": "2.0", "id": 1, "method": "' + "x" * (1 << 20) + '"}',
+        call(2, "new_game", seed=3),
+    )
+    assert replies[0]["error"]["code"] == -32700
+    assert "nested" in replies[0]["error"]["message"]
+    assert replies[1]["error"]["code"] == -32600
+    assert "at most 1048576 bytes" in replies[1]["error"]["message"]
+    assert replies[2]["result"]["game"] == 1
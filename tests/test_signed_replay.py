@@ -0,0 +1,55 @@
+import hashlib
+import hmac
+
+import pytest
+
+import akioi_2048 as ak
+
+KEY = b"tournament secret"
+
+
+def played(seed: int, moves: int) -> ak.Game:
+    game = ak.Game(seed=seed)
+    directions = [ak.Direction.Down, ak.Direction.Right, ak.Direction.Left]
+    for k in range(moves):
+        game.step(directions[k % 3])
+    return game
+
+
+def test_signature_is_hmac_over_text_and_score() -> None:
+    game = played(5, 60)
+    replay = game.replay()
+    lines = replay.to_text().splitlines(keepends=True)
+    assert lines[0] == "akioi-2048 replay 2\n"
+    assert lines[2].startswith("stamp ")
+    text = "akioi-2048 replay 1\n" + lines[1] + "".join(lines[3:])
+    message = f"{text}score {game.score}\n".encode()
+    expected = hmac.new(KEY, message, hashlib.sha256).digest()
+    assert ak.sign_replay(replay, KEY) == expected
+    long_key = bytes(range(100))
+    expected = hmac.new(long_key, message, hashlib.sha256).digest()
+    assert ak.sign_replay(replay, long_key) == expected
+
+
+def test_verify_accepts_only_the_signed_game() -> None:
+    replay = played(5, 60).replay()
+    signature = ak.sign_replay(replay, KEY)
+    assert ak.verify_replay(replay, signature, KEY)
+    assert not ak.verify_replay(replay, signature, b"guess")
+    assert not ak.verify_replay(replay, signature[:-1], KEY)
+    flipped = bytes([signature[0] ^ 1]) + signature[1:]
+    assert not ak.verify_replay(replay, flipped, KEY)
+    shorter = played(5, 59).replay()
+    assert not ak.verify_replay(shorter, signature, KEY)
+    other_seed = ak.Replay(6, replay.moves, rules=replay.rules)
+    assert not ak.verify_replay(other_seed, signature, KEY)
+
+
+def test_illegal_replays_never_verify() -> None:
+    board = [[2, 0, 0, 0]] + [[0] * 4] * 3
+    replay = ak.Game.from_board(board, seed=1).replay()
+    illegal = ak.Replay(1, [ak.Direction.Up] * 20)
+    with pytest.raises(ValueError, match="does not change the board"):
+        ak.sign_replay(illegal, KEY)
+    assert not ak.verify_replay(illegal, bytes(32), KEY)
+    assert ak.verify_replay(replay, ak.sign_replay(replay, KEY), KEY)
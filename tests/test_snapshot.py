@@ -0,0 +1,62 @@
+import akioi_2048 as ak
+
+MOVES = [ak.Direction.Left, ak.Direction.Down, ak.Direction.Right, ak.Direction.Up]
+
+
+def play(game: ak.Game, moves) -> list:
+    return [game.step(move) for move in moves]
+
+
+def test_restore_replays_the_same_spawns() -> None:
+    game = ak.Game(seed=11)
+    play(game, MOVES)
+    token = game.snapshot()
+    assert token.board == game.board
+    assert token.score == game.score
+    assert token.moves == 4
+    first = play(game, MOVES * 3)
+    game.restore(token)
+    assert game.board == token.board
+    assert game.moves == 4
+    assert play(game, MOVES * 3) == first
+
+
+def test_many_branches_from_one_token() -> None:
+    game = ak.Game(seed=2)
+    token = game.snapshot()
+    ends = {}
+    for move in MOVES:
+        game.restore(token)
+        play(game, [move] * 3)
+        ends[move] = game.board
+    for move in MOVES:
+        game.restore(token)
+        play(game, [move] * 3)
+        assert game.board == ends[move]
+
+
+def test_restore_keeps_history_and_proofs_apart() -> None:
+    game = ak.Game(seed=4, proofs=True)
+    play(game, MOVES[:2])
+    records = len(game.records())
+    token = game.snapshot()
+    play(game, MOVES)
+    long_records = len(game.records())
+    assert long_records > records
+    game.restore(token)
+    assert len(game.records()) == records
+    assert ak.verify_chain(game.records(), seed=4)
+    assert len(game.replay().moves) == records
+    play(game, MOVES)
+    assert len(game.records()) == long_records
+
+
+def test_restore_keeps_callbacks() -> None:
+    game = ak.Game(seed=1)
+    seen = []
+    game.on_move(lambda direction, result: seen.append(direction))
+    token = game.snapshot()
+    game.step(ak.Direction.Left)
+    game.restore(token)
+    game.step(ak.Direction.Down)
+    assert seen == [ak.Direction.Left, ak.Direction.Down]
@@ -0,0 +1,82 @@
+import pytest
+
+import akioi_2048 as ak
+
+
+def tiles(board: list[list[int]]) -> int:
+    return sum(v != 0 for row in board for v in row)
+
+
+def play(game: ak.Game, moves: int) -> list[ak.MoveResult]:
+    results = []
+    for i in range(100):
+        if len(results) == moves or game.state != ak.State.Continue:
+            break
+        result = game.step_detailed(list(ak.Direction)[i % 4])
+        if result.moved:
+            results.append(result)
+    return results
+
+
+def test_two_spawns_add_two_tiles_per_move() -> None:
+    rules = ak.Rules(variant="classic", spawns=2)
+    game = ak.Game(seed=5, rules=rules)
+    before = game.board
+    for result in play(game, 3):
+        assert len(result.spawns) == 2
+        assert result.spawns[0] == result.spawn
+        assert tiles(result.board) == tiles(before) - len(result.merges) + 2
+        before = result.board
+
+
+def test_spawn_every_skips_moves_between_spawns() -> None:
+    rules = ak.Rules(variant="classic", spawn_every=3)
+    game = ak.Game(seed=8, rules=rules)
+    counts = [len(result.spawns) for result in play(game, 7)]
+    assert counts == [1, 0, 0, 1, 0, 0, 1]
+
+
+def test_spawn_settings_round_trip_through_tags() -> None:
+    rules = ak.Rules(spawns=2, spawn_every=3)
+    assert rules.tag == "akioi-v2+spawns=2+spawn_every=3"
+    assert ak.Rules.preset("akioi").spawns == 1
+    spec = ak.rules_spec(rules.tag)
+    assert (spec["spawns_per_move"], spec["spawn_every"]) == (2, 3)
+    assert "spawns=2, spawn_every=3" in repr(rules)
+    for kwargs in [{"spawns": 0}, {"spawns": 5}, {"spawn_every": 17}]:
+        with pytest.raises(ValueError):
+            ak.Rules(**kwargs)
+
+
+def test_replays_reproduce_multi_spawn_games() -> None:
+    rules = ak.Rules(spawns=2, spawn_every=2)
+    game = ak.Game(seed=11, rules=rules)
+    play(game, 12)
+    replayed = ak.Replay.parse(game.replay().to_text()).game()
+    assert replayed.board == game.board
+    assert replayed.score == game.score
+
+
+def test_reference_model_accepts_every_spawn_count() -> None:
+    board = [[2, 2, 0, 0], [0] * 4, [0] * 4, [0, 0, 0, 4]]
+    for spawns in [1, 2, 3]:
+        rules = ak.Rules(variant="classic", spawns=spawns)
+        after, _, _ = ak.step(board, ak.Direction.Left, rules=rules)
+        assert tiles(after) == 2 + spawns
+        assert ak.verify_step(board, ak.Direction.Left, after, rules=rules)
+    rules = ak.Rules(variant="classic", spawns=2)
+    single, _, _ = ak.step(board, ak.Direction.Left, rules=ak.Rules(variant="classic"))
+    assert not ak.verify_step(board, ak.Direction.Left, single, rules=rules)
+
+
+def test_hint_and_books_under_spawn_settings() -> None:
+    board = [[2, 2, 0, 0], [0] * 4, [0] * 4, [0, 0, 0, 4]]
+    rules = ak.Rules(variant="classic", spawns=2, spawn_every=2)
+    for moves_played in [0, 1]:
+        found = ak.hint(board, depth=2, rules=rules, moves_played=moves_played)
+        assert found is not None
+    with pytest.raises(ValueError, match="spawn after every move"):
+        ak.OpeningBook.build(max_sum=8, depth=1, rules=rules)
+    rules = ak.Rules(variant="classic", spawns=2)
+    book = ak.OpeningBook.build(max_sum=4, depth=1, rules=rules)
+    assert len(book) > 0
@@ -0,0 +1,71 @@
+import pytest
+
+import akioi_2048 as ak
+
+FULL_BUT_ONE = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 0]]
+
+
+def test_spawn_probabilities_default() -> None:
+    odds = ak.spawn_probabilities()
+    assert list(odds) == [2, 4, -1, -2]
+    assert odds == {2: 0.783, 4: 0.078, -1: 0.1118, -2: 0.0272}
+    assert sum(odds.values()) == pytest.approx(1.0)
+
+
+def test_spawn_probabilities_mercy_last_cell() -> None:
+    mercy = ak.Rules.preset("mercy")
+    assert ak.spawn_probabilities(FULL_BUT_ONE) == ak.spawn_probabilities()
+    odds = ak.spawn_probabilities(FULL_BUT_ONE, rules=mercy)
+    assert odds[-1] == 0.0
+    assert sum(odds.values()) == pytest.approx(1.0)
+    assert odds[2] == pytest.approx(0.783 / 0.8882)
+    empty = ak.board_with({})
+    assert ak.spawn_probabilities(empty, rules=mercy) == ak.spawn_probabilities()
+
+
+def test_spawn_probabilities_full_board() -> None:
+    full = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+    assert ak.spawn_probabilities(full) == {}
+    assert ak.expected_spawn_value(full) == 0.0
+
+
+def test_expected_spawn_value() -> None:
+    numbers = 2 * 0.783 + 4 * 0.078
+    assert ak.expected_spawn_value(ak.board_with({})) == pytest.approx(numbers)
+    mercy = ak.expected_spawn_value(FULL_BUT_ONE, rules=ak.Rules.preset("mercy"))
+    assert mercy == pytest.approx(numbers / 0.8882)
+
+
+def test_spawned_tiles_follow_the_model() -> None:
+    counts = {tile: 0 for tile in ak.spawn_probabilities()}
+    board = ak.board_with({(0, 0): 2})
+    for _ in range(4000):
+        result = ak.step_detailed(board, ak.Direction.Down)
+        counts[result.spawn.value] += 1
+    for tile, chance in ak.spawn_probabilities().items():
+        assert counts[tile] / 4000 == pytest.approx(chance, abs=0.03)
+
+
+def test_seeded_spawns_are_pinned() -> None:
+    # tiles are drawn as integer indices, so these hold on every platform
+    expected = {
+        "standard": -1,
+        "adaptive": -2,
+    }
+    for difficulty, sixth in expected.items():
+        game = ak.Game(seed=2048, rules=ak.Rules(difficulty=difficulty))
+        assert game.board == [[0] * 4, [0] * 4, [2, 0, 0, 2], [0] * 4]
+        spawns = []
+        for direction in [ak.Direction.Left, ak.Direction.Down] * 4:
+            result = game.step_detailed(direction)
+            if result.moved:
+                spawns.append((result.spawn.cell, result.spawn.value))
+        assert spawns == [
+            ((0, 1), 2),
+            ((3, 2), 2),
+            ((2, 0), 2),
+            ((1, 0), 2),
+            ((0, 3), 2),
+            ((0, 2), sixth),
+            ((3, 3), 2),
+        ]
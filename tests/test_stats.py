@@ -0,0 +1,182 @@
+import math
+import pickle
+
+import pytest
+
+import akioi_2048 as ak
+
+ORDER = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right, ak.Direction.Up]
+
+
+def finished_game(seed: int, rules: ak.Rules | None = None) -> ak.Game:
+    game = ak.Game(seed, rules)
+    i = 0
+    while game.state == ak.State.Continue:
+        game.step(ORDER[i % 4])
+        i += 1
+    return game
+
+
+def test_record_aggregates_games() -> None:
+    games = [finished_game(seed) for seed in range(3)]
+    stats = ak.GameStats(bin_width=500)
+    for game in games:
+        stats.record(game)
+    assert stats.games == 3
+    assert stats.wins == 0
+    assert stats.win_rate == 0.0
+    assert stats.mean_score == sum(g.score for g in games) / 3
+    assert stats.moves == sum(g.moves for g in games)
+    assert stats.mean_moves == stats.moves / 3
+    assert stats.shortest == min(g.moves for g in games)
+    assert stats.longest == max(g.moves for g in games)
+    assert sum(stats.max_tiles.values()) == 3
+    assert sum(stats.score_histogram.values()) == 3
+    for start in stats.score_histogram:
+        assert start % 500 == 0
+    # only accepted moves count towards directions
+    assert set(stats.direction_counts) == set(ORDER)
+    assert 0 < sum(stats.direction_counts.values()) <= stats.moves
+
+
+def test_merge_matches_recording_in_one_place() -> None:
+    games = [finished_game(seed) for seed in range(4)]
+    whole = ak.GameStats()
+    for game in games:
+        whole.record(game)
+    left, right = ak.GameStats(), ak.GameStats()
+    for game in games[:1]:
+        left.record(game)
+    for game in games[1:]:
+        right.record(game)
+    left.merge(right)
+    assert left == whole
+    empty = ak.GameStats()
+    empty.merge(whole)
+    assert empty == whole
+
+
+def test_merge_rejects_different_bins() -> None:
+    with pytest.raises(ValueError, match="bin width"):
+        ak.GameStats(bin_width=10).merge(ak.GameStats(bin_width=20))
+    with pytest.raises(ValueError, match="positive"):
+        ak.GameStats(bin_width=0)
+
+
+def test_stats_round_trip() -> None:
+    stats = ak.GameStats()
+    stats.record(finished_game(7))
+    assert pickle.loads(pickle.dumps(stats)) == stats
+    assert ak.GameStats.from_dict(stats.to_dict()) == stats
+    with pytest.raises(ValueError, match="missing field: wins"):
+        data = stats.to_dict()
+        del data["wins"]
+        ak.GameStats.from_dict(data)
+
+
+def test_heatmaps_count_every_position() -> None:
+    pytest.importorskip("numpy")
+    game = finished_game(5)
+    stats = ak.GameStats(heatmaps=True)
+    assert ak.GameStats().heatmaps is None
+    stats.record(game)
+    maps = stats.heatmaps
+    assert set(maps) == {"max_tile", "merges", "spawns"}
+    spawns = [n for row in maps["spawns"].tolist() for n in row]
+    merges = [n for row in maps["merges"].tolist() for n in row]
+    max_tile = [n for row in maps["max_tile"].tolist() for n in row]
+    # classic rules spawn once after every move that changes the board
+    accepted = sum(stats.direction_counts.values())
+    assert sum(spawns) == accepted
+    assert sum(merges) > 0
+    assert sum(max_tile) >= accepted + 1
+    assert stats.to_dict()["heatmaps"]["spawns"] == maps["spawns"].tolist()
+    assert ak.GameStats.from_dict(stats.to_dict()) == stats
+    assert pickle.loads(pickle.dumps(stats)) == stats
+
+
+def test_heatmaps_merge() -> None:
+    pytest.importorskip("numpy")
+    a, b = ak.GameStats(heatmaps=True), ak.GameStats(heatmaps=True)
+    a.record(finished_game(1))
+    b.record(finished_game(2))
+    both = ak.GameStats(heatmaps=True)
+    both.record(finished_game(1))
+    both.record(finished_game(2))
+    a.merge(b)
+    assert a == both
+    with pytest.raises(ValueError, match="heatmaps"):
+        a.merge(ak.GameStats())
+
+
+def test_branching_by_move() -> None:
+    pytest.importorskip("numpy")
+    stats = ak.GameStats(branching=True)
+    assert ak.GameStats().branching is None
+    stats.record(finished_game(5, ak.Rules.preset("classic")))
+    b = stats.branching
+    accepted = sum(stats.direction_counts.values())
+    assert b["positions"].tolist() == [1] * accepted
+    for legal in b["legal_moves"].tolist():
+        assert 1 <= legal <= 4
+    for successors in b["successors"].tolist():
+        assert successors >= 2
+    # a 2 or a 4 in one of k empty cells, at 90% and 10%
+    odds = -(0.9 * math.log2(0.9) + 0.1 * math.log2(0.1))
+    for entropy in b["spawn_entropy"].tolist():
+        k = round(2 ** (entropy - odds))
+        assert abs(entropy - (math.log2(k) + odds)) < 1e-5
+    assert ak.GameStats.from_dict(stats.to_dict()) == stats
+    assert pickle.loads(pickle.dumps(stats)) == stats
+
+
+def test_branching_is_wider_with_multipliers() -> None:
+    pytest.importorskip("numpy")
+    classic = ak.GameStats(branching=True)
+    akioi = ak.GameStats(branching=True)
+    for seed in range(3):
+        classic.record(finished_game(seed, ak.Rules.preset("classic")))
+        akioi.record(finished_game(seed))
+    entropy = [s.branching["spawn_entropy"].tolist()[0] for s in (classic, akioi)]
+    assert entropy[1] > entropy[0]
+    with pytest.raises(ValueError, match="branching"):
+        classic.merge(ak.GameStats())
+    merged = ak.GameStats(branching=True)
+    merged.merge(classic)
+    merged.merge(akioi)
+    lengths = [len(s.branching["positions"].tolist()) for s in (classic, akioi)]
+    positions = merged.branching["positions"].tolist()
+    assert positions[0] == 6
+    assert len(positions) == max(lengths)
+
+
+def test_empty_stats() -> None:
+    stats = ak.GameStats()
+    assert stats.games == 0
+    assert stats.mean_score == 0.0
+    assert stats.mean_moves == 0.0
+    assert stats.shortest == 0
+    assert stats.max_tiles == {}
+
+
+def test_arena_collects_finished_games() -> None:
+    pytest.importorskip("numpy")
+    arena = ak.Arena(2, seed=3)
+    finished = 0
+    for i in range(2000):
+        _, _, dones = arena.step([i % 4, (i + 1) % 4])
+        finished += sum(dones.tolist())
+    assert finished > 0
+    assert arena.stats.games == finished
+    assert arena.stats.heatmaps is None
+
+
+def test_arena_collects_heatmaps() -> None:
+    pytest.importorskip("numpy")
+    arena = ak.Arena(2, seed=3, heatmaps=True)
+    for i in range(2000):
+        arena.step([i % 4, (i + 1) % 4])
+    maps = arena.stats.heatmaps
+    assert maps is not None
+    spawns = sum(n for row in maps["spawns"].tolist() for n in row)
+    assert spawns == sum(arena.stats.direction_counts.values())
@@ -0,0 +1,73 @@
+import pytest
+
+import akioi_2048 as ak
+
+
+def test_status_of_continuing_board():
+    board = [[2, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+    status = ak.status(board)
+    assert status.state is ak.State.Continue
+    assert status.reason is None
+    assert status.can_continue
+
+
+def test_status_tells_victory_with_moves_from_dead_victory():
+    open_win = [[65536, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+    status = ak.status(open_win)
+    assert status.state is ak.State.Victory
+    assert status.reason == "victory_tile"
+    assert status.can_continue
+
+    full_win = [[65536, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+    status = ak.status(full_win)
+    assert status.state is ak.State.Victory
+    assert not status.can_continue
+
+
+def test_status_game_over_and_game_property():
+    dead = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+    status = ak.status(dead)
+    assert status.state is ak.State.GameOver
+    assert status.reason == "no_legal_moves"
+    assert not status.can_continue
+    assert "no_legal_moves" in repr(status)
+
+    game = ak.Game(seed=1)
+    assert game.status.state is game.state
+    assert game.status.can_continue
+
+
+def test_keep_playing_matches_normal_play_before_a_win():
+    normal, keep = ak.Game(seed=2), ak.Game(seed=2, keep_playing=True)
+    for i in range(300):
+        direction = list(ak.Direction)[i % 4]
+        assert normal.step(direction) == keep.step(direction)
+    assert not keep.won
+    assert keep.status.reason == normal.status.reason
+
+
+def test_is_game_over_and_is_won_check_the_board():
+    dead = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+    assert ak.is_game_over(dead)
+    assert not ak.is_won(dead)
+
+    # only merges along one axis are left
+    column_merge = [[2, 4, 2, 4], [2, 8, 16, 32], [4, 2, 4, 2], [8, 16, 8, 16]]
+    assert not ak.is_game_over(column_merge)
+
+    win = [[65536, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+    assert ak.is_won(win)
+    assert not ak.is_game_over(win)
+
+    with pytest.raises(ValueError):
+        ak.is_won([[3, 0, 0, 0], [0] * 4, [0] * 4, [0] * 4])
+
+
+def test_is_won_follows_the_rules():
+    board = [[2048, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+    assert not ak.is_won(board)
+    assert ak.is_won(board, rules=ak.Rules(max_tile=2048))
+    blocked = [[-3, 0, 0, 0], [0] * 4, [0] * 4, [65536, 0, 0, 0]]
+    with pytest.raises(ValueError):
+        ak.is_won(blocked)
+    assert ak.is_won(blocked, rules=ak.Rules(blockers=True))
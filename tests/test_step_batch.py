@@ -0,0 +1,46 @@
+import pytest
+
+import akioi_2048 as ak
+
+OPEN = [[2, 2, 0, 0], [0, 0, 0, 0], [0, 4, 0, 0], [0, 0, 0, 0]]
+
+
+def test_step_batch_matches_step() -> None:
+    boards = [OPEN, [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [2, 4, 8, 16]]]
+    results = ak.step_batch(boards, [ak.Direction.Left, "up"], seed=1)
+    for board, direction, result in zip(boards, [3, 2], results):
+        assert isinstance(result, ak.StepResult)
+        expected = ak.step(board, direction)
+        assert result.delta == expected.delta
+        assert result.state == expected.state
+
+
+def test_step_batch_is_seeded_whatever_the_threads() -> None:
+    boards = [OPEN] * 1000
+    actions = [i % 4 for i in range(1000)]
+    runs = [ak.step_batch(boards, actions, seed=7, threads=n) for n in [1, 3, 8]]
+    assert runs[0] == runs[1] == runs[2]
+    # every board has its own spawn stream
+    lefts = [r.board for r in runs[0][3::4]]
+    assert len({str(b) for b in lefts}) > 1
+    assert ak.step_batch(boards, actions, seed=8) != runs[0]
+
+
+def test_step_batch_rejects_bad_input() -> None:
+    with pytest.raises(ValueError, match="expected 2 actions"):
+        ak.step_batch([OPEN, OPEN], [0])
+    with pytest.raises(ak.InvalidBoardError):
+        ak.step_batch([OPEN, [[3, 0, 0, 0]] + OPEN[1:]], [0, 0])
+    with pytest.raises(ak.InvalidDirectionError):
+        ak.step_batch([OPEN], [9])
+
+
+def test_step_batch_streams_are_keyed_by_step() -> None:
+    boards = [OPEN] * 600
+    actions = [ak.Direction.Left] * 600
+    first = ak.step_batch(boards, actions, seed=2, step=0)
+    second = ak.step_batch(boards, actions, seed=2, step=1)
+    assert first != second
+    # board i's stream does not depend on the boards around it
+    alone = ak.step_batch(boards[:5], actions[:5], seed=2, step=1, threads=1)
+    assert alone == second[:5]
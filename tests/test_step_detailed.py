@@ -0,0 +1,105 @@
+import akioi_2048 as ak
+
+
+def test_detailed_reports_merges_and_spawn() -> None:
+    board = [
+        [2, 0, 0, 0],
+        [2, 0, 0, 0],
+        [4, 0, 0, 4],
+        [4, 0, 0, 4],
+    ]
+    result = ak.step_detailed(board, ak.Direction.Down)
+    assert result.moved is True
+    assert result.delta == 20
+    assert result.state == ak.State.Continue
+    merges = [(m.target, m.sources, m.value, m.score) for m in result.merges]
+    assert merges == [
+        ((2, 0), [(1, 0), (0, 0)], 4, 4),
+        ((3, 0), [(3, 0), (2, 0)], 8, 8),
+        ((3, 3), [(3, 3), (2, 3)], 8, 8),
+    ]
+    spawn = result.spawn
+    assert spawn is not None
+    row, col = spawn.cell
+    assert (row, col) not in {m.target for m in result.merges}
+    assert result.board[row][col] == spawn.value
+    assert sum(1 for r in result.board for v in r if v) == 4
+
+
+def test_detailed_multiplier_merge_score() -> None:
+    board = [
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+        [0, 0, 512, -2],
+    ]
+    result = ak.step_detailed(board, ak.Direction.Right)
+    assert [(m.target, m.value, m.score) for m in result.merges] == [
+        ((3, 3), 1024, 1024)
+    ]
+
+
+def test_detailed_invalid_move() -> None:
+    board = [
+        [2, 0, 0, 0],
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+    ]
+    result = ak.step_detailed(board, ak.Direction.Up)
+    assert result.moved is False
+    assert result.merges == []
+    assert result.spawn is None
+    assert result.board == board
+
+
+def test_detailed_merge_kinds_and_breakdown() -> None:
+    board = [
+        [2, 0, 0, 0],
+        [2, 0, 0, 0],
+        [8, 0, -1, 0],
+        [-2, 0, -1, 4],
+    ]
+    result = ak.step_detailed(board, ak.Direction.Down)
+    merges = [(m.target, m.tiles, m.kind, m.value) for m in result.merges]
+    assert merges == [
+        ((2, 0), [2, 2], "numbers", 4),
+        ((3, 0), [-2, 8], "multiply", 16),
+        ((3, 2), [-1, -1], "multipliers", -2),
+    ]
+    assert result.score_breakdown == {"numbers": 4, "multipliers": -2, "multiply": 16}
+    assert sum(result.score_breakdown.values()) == result.delta
+
+
+def test_infer_move_finds_direction_and_spawn() -> None:
+    before = [[2, 2, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+    after = [[4, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, -1]]
+    inferred = ak.infer_move(before, after)
+    assert inferred is not None
+    direction, spawn = inferred
+    assert direction == ak.Direction.Left
+    assert (spawn.cell, spawn.value) == ((3, 3), -1)
+
+
+def test_infer_move_matches_played_moves() -> None:
+    # every move of this game is the only one leading to its board
+    game = ak.Game(seed=1)
+    for direction in [ak.Direction.Down, ak.Direction.Right, ak.Direction.Up] * 10:
+        before = game.board
+        result = game.step_detailed(direction)
+        if not result.moved:
+            assert ak.infer_move(before, result.board) is None
+            continue
+        inferred = ak.infer_move(before, result.board)
+        assert inferred is not None
+        assert inferred[1] == result.spawn
+        assert ak.step_detailed(before, inferred[0]).moved
+
+
+def test_infer_move_rejects_impossible_boards() -> None:
+    before = [[2, 2, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+    assert ak.infer_move(before, before) is None
+    no_spawn = [[4, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]
+    assert ak.infer_move(before, no_spawn) is None
+    big_spawn = [[4, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 8]]
+    assert ak.infer_move(before, big_spawn) is None
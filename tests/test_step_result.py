@@ -0,0 +1,47 @@
+import pathlib
+
+import akioi_2048 as ak
+
+BOARD = [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [2, 2, 0, 0]]
+
+
+def test_step_returns_a_named_tuple():
+    result = ak.step(BOARD, ak.Direction.Left)
+    assert isinstance(result, ak.StepResult)
+    assert isinstance(result, tuple)
+    board, delta, state = result
+    assert (result.board, result.delta, result.state) == (board, delta, state)
+    assert result.board[3][0] == 4
+    assert result.delta == 4
+    assert result.state is ak.State.Continue
+    assert result == (board, delta, state)
+
+
+def test_game_step_returns_a_named_tuple():
+    game = ak.Game(seed=3)
+    result = game.step(ak.Direction.Down)
+    assert isinstance(result, ak.StepResult)
+    assert result.board == game.board
+    assert result._fields == ("board", "delta", "state")
+
+
+def test_game_step_in_place_returns_scalars_and_plays_like_step():
+    stepped, in_place = ak.Game(seed=9), ak.Game(seed=9)
+    for i in range(60):
+        direction = list(ak.Direction)[i % 4]
+        board, delta, state = stepped.step(direction)
+        result = in_place.step_in_place(direction)
+        assert result == (delta, state)
+        assert isinstance(result[0], int)
+        assert in_place.board == board
+    assert (in_place.score, in_place.moves) == (stepped.score, stepped.moves)
+    assert in_place.replay().to_text() == stepped.replay().to_text()
+
+
+def test_package_ships_type_information():
+    package = pathlib.Path(ak.__file__).parent
+    assert (package / "py.typed").exists()
+    stubs = (package / "__init__.pyi").read_text()
+    assert "class StepResult(NamedTuple):" in stubs
+    for name in ak.__all__:
+        assert name in stubs, name
@@ -0,0 +1,52 @@
+import pytest
+
+import akioi_2048 as ak
+
+DEAD = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 4, 2]]
+TRAP = [[32, 2, 16, 2], [2, 8, 32, 8], [16, 32, 2, 4], [0, 4, 8, 16]]
+
+
+def test_certain_outcomes():
+    assert ak.survival_probability(DEAD, 1, samples=10)["probability"] == 0.0
+    opening = ak.survival_probability(ak.board_with({(0, 0): 2}), 3, samples=50)
+    assert opening == {
+        "probability": 1.0,
+        "std_error": 0.0,
+        "samples": 50,
+        "mean_moves": 3.0,
+    }
+
+
+def test_danger_grows_with_the_horizon():
+    classic = ak.Rules.preset("classic")
+    odds = [
+        ak.survival_probability(TRAP, n, samples=200, rules=classic)["probability"]
+        for n in (1, 3, 10)
+    ]
+    assert 0.0 < odds[0] < 1.0
+    assert odds == sorted(odds, reverse=True)
+
+
+def test_callable_policies_and_paired_seeds():
+    calls = []
+
+    def down_first(board):
+        calls.append(board)
+        return ak.Direction.Down if len(calls) % 2 else ak.Direction.Left
+
+    board = ak.board_with({(0, 0): 2, (0, 1): 4})
+    first = ak.survival_probability(board, 20, samples=5, policy=down_first, seed=3)
+    assert calls
+    calls.clear()
+    again = ak.survival_probability(board, 20, samples=5, policy=down_first, seed=3)
+    assert first == again
+    assert 0 < first["mean_moves"] <= 20
+
+
+def test_rejects_bad_arguments():
+    with pytest.raises(ValueError):
+        ak.survival_probability(DEAD, 1, samples=0)
+    with pytest.raises(ak.InvalidBoardError):
+        ak.survival_probability([[3] * 4] * 4, 1)
+    with pytest.raises(ValueError):
+        ak.survival_probability(DEAD, 1, policy="psychic")
@@ -0,0 +1,140 @@
+from pathlib import Path
+
+import pytest
+
+import akioi_2048 as ak
+
+# Full but for one cell: few positions stay reachable
+ENDGAME = [
+    [2, 4, 2, 4],
+    [4, 2, 4, 2],
+    [2, 4, 1024, 0],
+    [4, 2, 8, 1024],
+]
+
+
+def children(board: list[list[int]], direction: ak.Direction) -> list:
+    """``(probability, board)`` of every spawn after ``direction``."""
+    detail = ak.step_detailed(board, direction)
+    slid = [row[:] for row in detail.board]
+    row, col = detail.spawn.cell
+    slid[row][col] = 0
+    empty = [(r, c) for r in range(4) for c in range(4) if slid[r][c] == 0]
+    out = []
+    for tile, chance in ak.spawn_probabilities(slid).items():
+        for r, c in empty:
+            child = [row[:] for row in slid]
+            child[r][c] = tile
+            out.append((chance / len(empty), child))
+    return out
+
+
+def test_one_move_from_the_goal() -> None:
+    board = [[2, 4, 2, 4], [4, 2, 4, 2], [2, 4, 2, 4], [4, 2, 1024, 1024]]
+    table = ak.Tablebase.build(board, horizon=1, goal=2048)
+    entry = table.lookup(board)
+    assert entry is not None
+    assert entry.win_probability == 1.0
+    assert entry.best_move == ak.Direction.Right
+    assert entry.expected_score == 2048
+    assert ak.tablebase_lookup(board, table) is not None
+
+
+def test_values_back_up_from_the_next_layer() -> None:
+    table = ak.Tablebase.build(ENDGAME, horizon=3, goal=2048)
+    entry = table.lookup(ENDGAME, moves_left=3)
+    assert entry is not None
+    assert 0.0 < entry.win_probability <= 1.0
+
+    best = None
+    for direction in ak.Direction:
+        if ak.step_detailed(ENDGAME, direction).moved:
+            win = sum(
+                chance * table.lookup(child, moves_left=2).win_probability
+                for chance, child in children(ENDGAME, direction)
+            )
+            best = win if best is None else max(best, win)
+    assert entry.win_probability == pytest.approx(best)
+
+
+def test_save_and_load(tmp_path: Path) -> None:
+    mercy = ak.Rules.preset("mercy")
+    table = ak.Tablebase.build(ENDGAME, horizon=2, goal=2048, rules=mercy)
+    path = tmp_path / "endgame.tb"
+    table.save(path)
+    loaded = ak.Tablebase.load(path)
+    assert len(loaded) == len(table)
+    assert loaded.goal == 2048
+    assert loaded.horizon == 2
+    assert loaded.rules == table.rules
+    from_disk = ak.tablebase_lookup(ENDGAME, str(path))
+    entry = table.lookup(ENDGAME)
+    assert from_disk.win_probability == entry.win_probability
+    assert from_disk.expected_score == entry.expected_score
+    assert from_disk.best_move == entry.best_move
+
+
+def test_unknown_positions_and_limits(tmp_path: Path) -> None:
+    table = ak.Tablebase.build(ENDGAME, horizon=1, goal=2048)
+    assert table.lookup(ak.board_with({(0, 0): 2})) is None
+    assert table.lookup(ENDGAME, moves_left=0) is None
+    with pytest.raises(ValueError, match="more than 10 positions"):
+        ak.Tablebase.build(ak.board_with({(0, 0): 2}), horizon=2, max_positions=10)
+    path = tmp_path / "junk.tb"
+    path.write_bytes(b"not a tablebase")
+    with pytest.raises(ValueError, match="not a tablebase"):
+        ak.Tablebase.load(path)
+
+
+def test_open_maps_a_saved_table(tmp_path: Path) -> None:
+    table = ak.Tablebase.build(ENDGAME, horizon=3, goal=2048)
+    path = tmp_path / "endgame.tb"
+    table.save(path)
+    mapped = ak.Tablebase.open(path, verify=True)
+    assert mapped.mapped and not table.mapped
+    assert len(mapped) == len(table)
+    assert (mapped.goal, mapped.horizon) == (2048, 3)
+    for left in [None, 0, 1, 2, 3]:
+        expected = table.lookup(ENDGAME, moves_left=left)
+        entry = mapped.lookup(ENDGAME, moves_left=left)
+        if expected is None:
+            assert entry is None
+            continue
+        assert entry.win_probability == expected.win_probability
+        assert entry.best_move == expected.best_move
+    assert mapped.lookup(ak.board_with({(0, 0): 2})) is None
+    copy = tmp_path / "copy.tb"
+    mapped.save(copy)
+    assert copy.read_bytes() == path.read_bytes()
+
+
+def test_open_checks_the_file(tmp_path: Path) -> None:
+    table = ak.Tablebase.build(ENDGAME, horizon=2, goal=2048)
+    path = tmp_path / "endgame.tb"
+    table.save(path)
+    data = bytearray(path.read_bytes())
+    data[-40] ^= 1
+    path.write_bytes(bytes(data))
+    ak.Tablebase.open(path)
+    with pytest.raises(ValueError, match="checksum"):
+        ak.Tablebase.open(path, verify=True)
+    with pytest.raises(ValueError, match="checksum"):
+        ak.Tablebase.load(path)
+    path.write_bytes(bytes(data[:-1]))
+    with pytest.raises(ValueError, match="wrong size"):
+        ak.Tablebase.open(path)
+    with pytest.raises(FileNotFoundError):
+        ak.Tablebase.open(tmp_path / "missing.tb")
+
+
+def test_version_1_files_still_load(tmp_path: Path) -> None:
+    table = ak.Tablebase.build(ENDGAME, horizon=2, goal=2048)
+    path = tmp_path / "endgame.tb"
+    table.save(path)
+    # version 1 had the same header and entries, unsorted, and no checksum
+    data = path.read_bytes()
+    path.write_bytes(data[:7] + b"\x01" + data[8:-32])
+    assert len(ak.Tablebase.load(path)) == len(table)
+    assert ak.tablebase_lookup(ENDGAME, path) is not None
+    with pytest.raises(ValueError, match="version 1"):
+        ak.Tablebase.open(path)
@@ -0,0 +1,55 @@
+from concurrent.futures import ThreadPoolExecutor
+
+import akioi_2048 as ak
+
+
+def test_shared_game_applies_every_step_once():
+    game = ak.Game(seed=3)
+    directions = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Up, ak.Direction.Right]
+
+    def play(offset):
+        for i in range(200):
+            game.step(directions[(i + offset) % 4])
+
+    with ThreadPoolExecutor(4) as pool:
+        list(pool.map(play, range(4)))
+
+    assert game.moves == 800
+    replayed = game.replay().game()
+    assert replayed.board == game.board
+    assert replayed.score == game.score
+
+
+def test_batches_from_many_threads_share_the_workers():
+    boards = [ak.board_with({(0, 0): 2, (0, 1): 2, (3, 3): 4})] * 600
+    actions = [ak.Direction.Left] * 600
+
+    def work(seed):
+        return ak.step_batch(boards, actions, seed=seed, threads=4)
+
+    with ThreadPoolExecutor(4) as pool:
+        results = list(pool.map(work, range(8)))
+
+    for seed, result in enumerate(results):
+        alone = ak.step_batch(boards, actions, seed=seed, threads=1)
+        assert result == alone
+
+
+def test_native_calls_run_in_thread_pools(tmp_path):
+    game = ak.Game(seed=9, proofs=True)
+    for _ in range(50):
+        for direction in [ak.Direction.Down, ak.Direction.Left]:
+            game.step(direction)
+    records = game.records()
+
+    def work(i):
+        n = ak.collect_dataset("random", 2, tmp_path / f"{i}.npz")
+        return n, ak.verify_chain(records, seed=9), game.replay().frames()[-1]
+
+    with ThreadPoolExecutor(4) as pool:
+        results = list(pool.map(work, range(4)))
+
+    for n, verified, last in results:
+        assert n > 0
+        assert verified
+        assert last == game.board
@@ -0,0 +1,102 @@
+import sys
+import time
+
+import pytest
+
+import akioi_2048 as ak
+
+MOVES = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right, ak.Direction.Up]
+
+
+def test_games_have_no_clock_by_default() -> None:
+    assert ak.Game(seed=0).time_left is None
+
+
+def test_clock_counts_down_and_adds_increments() -> None:
+    game = ak.Game(seed=0, time_limit=5.0, increment=2.0)
+    assert 4.0 < game.time_left <= 5.0
+    game.step(ak.Direction.Down)
+    assert 6.0 < game.time_left <= 7.0
+
+
+def test_moves_after_the_clock_ran_out_are_rejected() -> None:
+    game = ak.Game(seed=0, time_limit=0.05)
+    game.step(ak.Direction.Down)
+    time.sleep(0.1)
+    board, moves = game.board, game.moves
+    assert game.time_left == 0.0
+    with pytest.raises(ValueError, match="out of time"):
+        game.step(ak.Direction.Left)
+    assert game.board == board
+    assert game.moves == moves
+
+
+def test_forfeit_on_time_ends_the_game() -> None:
+    game = ak.Game(seed=0, time_limit=0.05, on_timeout="forfeit")
+    time.sleep(0.1)
+    board = game.board
+    for direction in MOVES:
+        new_board, delta, state = game.step(direction)
+        assert new_board == board
+        assert delta == 0
+        assert state == ak.State.GameOver
+    assert game.state == ak.State.GameOver
+    assert game.status.reason == "out_of_time"
+    assert game.moves == 0
+    assert game.time_left == 0.0
+
+
+def test_bad_time_controls() -> None:
+    with pytest.raises(ValueError, match="unknown on_timeout policy"):
+        ak.Game(time_limit=1.0, on_timeout="pause")
+    with pytest.raises(ValueError, match="time_limit must be a non-negative"):
+        ak.Game(time_limit=-1.0)
+    board = ak.board_with({(3, 0): 2, (3, 1): 2})
+    assert ak.Game.from_board(board, time_limit=1.0).time_left > 0.5
+
+
+def test_timings_split_decision_from_engine_time() -> None:
+    assert ak.Game(seed=0).timings()["stats"]["engine"]["count"] == 0
+    game = ak.Game(seed=0, timings=True)
+    for i in range(6):
+        if i == 3:
+            time.sleep(0.05)
+        game.step(MOVES[i % 4])
+    timings = game.timings()
+    decision = [float(t) for t in timings["decision"]]
+    engine = [float(t) for t in timings["engine"]]
+    assert len(decision) == len(engine) == game.moves == 6
+    assert decision[3] >= 0.05
+    assert max(engine) < 0.05
+    stats = timings["stats"]
+    assert stats["decision"]["count"] == 6
+    assert stats["decision"]["max"] == max(decision)
+    assert stats["engine"]["total"] == pytest.approx(sum(engine))
+    summary = stats["decision"]
+    assert summary["median"] <= summary["p95"] <= summary["max"]
+
+
+def test_timings_are_lists_without_numpy() -> None:
+    game = ak.Game(seed=0, timings=True)
+    game.step(ak.Direction.Down)
+    numpy = sys.modules.get("numpy")
+    sys.modules["numpy"] = None
+    try:
+        timings = game.timings()
+    finally:
+        if numpy is None:
+            del sys.modules["numpy"]
+        else:
+            sys.modules["numpy"] = numpy
+    assert type(timings["decision"]) is list
+    assert type(timings["engine"]) is list
+    assert len(timings["engine"]) == timings["stats"]["engine"]["count"] == 1
+
+
+def test_timings_follow_restored_snapshots() -> None:
+    game = ak.Game(seed=0, timings=True)
+    game.step(ak.Direction.Down)
+    token = game.snapshot()
+    game.step(ak.Direction.Left)
+    game.restore(token)
+    assert len(game.timings()["engine"].tolist()) == 1
@@ -0,0 +1,125 @@
+import itertools
+
+import pytest
+
+import akioi_2048 as ak
+
+STATS = {
+    "games",
+    "mean_score",
+    "median_score",
+    "std_score",
+    "win_rate",
+    "mean_moves",
+    "max_tile",
+}
+
+
+def cycling_agent():
+    order = itertools.cycle(
+        [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right, ak.Direction.Up]
+    )
+    return lambda board: next(order)
+
+
+def test_tournament_structure() -> None:
+    result = ak.tournament(["random", "random", "random"], games_per_agent=3, seed=2)
+    assert result["seed"] == 2
+    assert [len(scores) for scores in result["scores"]] == [3, 3, 3]
+    assert len(result["agents"]) == 3
+    for stats in result["agents"]:
+        assert set(stats) == STATS
+        assert stats["games"] == 3
+    pairs = [(c["a"], c["b"]) for c in result["comparisons"]]
+    assert pairs == [(0, 1), (0, 2), (1, 2)]
+    for c in result["comparisons"]:
+        assert c["wins"] + c["losses"] + c["ties"] == 3
+        assert 0.0 <= c["p_value"] <= 1.0
+        assert 0.0 <= c["sign_p_value"] <= 1.0
+
+
+def test_same_agent_gets_the_same_games() -> None:
+    result = ak.tournament([cycling_agent(), cycling_agent()], games_per_agent=4)
+    first, second = result["scores"]
+    assert first == second
+    (c,) = result["comparisons"]
+    assert c["ties"] == 4
+    assert c["mean_difference"] == 0.0
+    assert c["p_value"] == 1.0
+    assert c["sign_p_value"] == 1.0
+
+
+def test_tournament_is_seed_deterministic() -> None:
+    first = ak.tournament([cycling_agent()], games_per_agent=3, seed=5)
+    second = ak.tournament([cycling_agent()], games_per_agent=3, seed=5)
+    assert first["scores"] == second["scores"]
+    assert first["comparisons"] == []
+
+
+def test_stronger_agent_wins_the_comparison() -> None:
+    def stuck(board):
+        return ak.Direction.Up
+
+    result = ak.tournament([cycling_agent(), stuck], games_per_agent=12, seed=1)
+    (c,) = result["comparisons"]
+    assert c["mean_difference"] > 0
+    assert c["wins"] > c["losses"]
+    assert c["p_value"] < 0.05
+    assert c["sign_p_value"] < 0.05
+
+
+def test_invalid_agent() -> None:
+    with pytest.raises(ValueError, match="unknown policy"):
+        ak.tournament(["random", "genius"])
+    with pytest.raises(ValueError, match="callable"):
+        ak.tournament([42])
+
+
+def corner_agent(board):
+    # the first of Down, Left, Right, Up that changes the board
+    columns = [list(col) for col in zip(*board)]
+    lines = {
+        ak.Direction.Down: [col[::-1] for col in columns],
+        ak.Direction.Left: [list(row) for row in board],
+        ak.Direction.Right: [row[::-1] for row in board],
+        ak.Direction.Up: columns,
+    }
+    for direction, rows in lines.items():
+        if any(ak.slide_row(row)[0] != row for row in rows):
+            return direction
+    return ak.Direction.Down
+
+
+def test_builtin_baselines() -> None:
+    names = ["greedy-score", "down-left-corner", "snake"]
+    first = ak.tournament(names + ["random"], games_per_agent=8, seed=3)
+    second = ak.tournament(names, games_per_agent=8, seed=3)
+    assert first["scores"][:3] == second["scores"]
+    means = [stats["mean_score"] for stats in first["agents"]]
+    for mean in means[:3]:
+        assert mean > means[3]
+    called = ak.tournament([corner_agent], games_per_agent=8, seed=3)
+    assert called["scores"][0] == first["scores"][1]
+
+
+def test_pareto_ranks_on_objectives() -> None:
+    result = ak.tournament(["snake", "random"], games_per_agent=4, seed=1)
+    pareto = result["pareto"]
+    assert pareto["objectives"] == ["score", "max_tile", "moves", "win_rate"]
+    agents = result["agents"]
+    for values, stats in zip(pareto["values"], agents):
+        assert values[0] == stats["mean_score"]
+        assert values[2] == stats["mean_moves"]
+        assert values[3] == stats["win_rate"]
+    assert pareto["front"] == [i for i, r in enumerate(pareto["ranks"]) if r == 0]
+    assert 0 in pareto["ranks"]
+
+    # one objective is a plain ranking by it, ties sharing a front
+    moves = ak.tournament(
+        ["snake", "random", "snake"], games_per_agent=4, seed=1, objectives=["moves"]
+    )["pareto"]
+    assert moves["ranks"][0] == moves["ranks"][2]
+    best = max(range(3), key=lambda i: moves["values"][i][0])
+    assert moves["ranks"][best] == 0
+    with pytest.raises(ValueError, match="unknown objective: speed"):
+        ak.tournament(["random"], games_per_agent=1, objectives=["speed"])
@@ -0,0 +1,70 @@
+from pathlib import Path
+
+import pytest
+
+import akioi_2048 as ak
+
+
+def test_training_records_learning_curves() -> None:
+    seen = []
+    result = ak.train(
+        12, eval_every=4, eval_games=2, max_moves=200, callback=seen.append
+    )
+    assert len(result["scores"]) == 12
+    assert [e["games"] for e in result["evaluations"]] == [4, 8, 12]
+    assert seen == result["evaluations"]
+    for evaluation in seen:
+        assert evaluation["mean_score"] >= 0
+        assert 0.0 <= evaluation["win_rate"] <= 1.0
+    net = result["network"]
+    assert isinstance(net, ak.NTupleNetwork)
+    board = ak.init()
+    assert net.value(board) != 0.0
+    assert ak.NTupleNetwork().value(board) == 0.0
+    assert net.best_move(board) in list(ak.Direction)
+
+
+def test_training_is_seeded_and_continues() -> None:
+    runs = [ak.train(5, seed=2, eval_games=1, max_moves=100) for _ in range(2)]
+    assert runs[0]["scores"] == runs[1]["scores"]
+    assert runs[0]["evaluations"] == runs[1]["evaluations"]
+    again = ak.train(
+        5, seed=2, eval_games=1, max_moves=100, network=runs[0]["network"]
+    )
+    board = ak.init()
+    before, after = runs[0]["network"].value(board), again["network"].value(board)
+    assert before != after
+
+
+def test_network_save_and_load(tmp_path: Path) -> None:
+    net = ak.train(3, eval_games=0, max_moves=50)["network"]
+    path = tmp_path / "net.ntn"
+    net.save(path)
+    loaded = ak.NTupleNetwork.load(path)
+    assert len(loaded) == len(net) == 4 * 32**4
+    board = [[2, 4, 8, 16], [0, 0, 2, 0], [0, 0, 0, 0], [4, 0, 0, 2]]
+    assert loaded.value(board) == net.value(board)
+    path.write_bytes(path.read_bytes()[:-1] + b"x")
+    with pytest.raises(ValueError, match="checksum"):
+        ak.NTupleNetwork.load(path)
+
+
+def test_train_rejects_bad_settings() -> None:
+    with pytest.raises(ValueError, match="learning rate"):
+        ak.train(1, lr=0.0)
+    with pytest.raises(ValueError, match="lambda"):
+        ak.train(1, lambda_=1.5)
+    with pytest.raises(ValueError, match="callable"):
+        ak.train(1, callback=3)
+
+
+def test_callback_errors_stop_training() -> None:
+    def callback(evaluation: dict) -> None:
+        raise RuntimeError("stop")
+
+    with pytest.raises(RuntimeError, match="stop"):
+        ak.train(10, eval_every=2, eval_games=1, max_moves=20, callback=callback)
+    token = ak.CancelToken()
+    token.cancel()
+    with pytest.raises(ak.CancelledError):
+        ak.train(1000, cancel=token)
@@ -0,0 +1,57 @@
+import akioi_2048 as ak
+
+EMPTY = [[0] * 4 for _ in range(4)]
+
+
+def board_with_max(tile: int) -> list[list[int]]:
+    board = [row[:] for row in EMPTY]
+    board[3][0] = tile
+    return board
+
+
+def test_plain_transitions_use_score_delta() -> None:
+    rec = ak.TrajectoryRecorder()
+    rec.record(EMPTY, ak.Direction.Down, 4, board_with_max(4), ak.State.Continue)
+    assert len(rec) == 0
+    rec.record(
+        board_with_max(4), ak.Direction.Left, 8, board_with_max(8), ak.State.GameOver
+    )
+    transitions = rec.transitions()
+    assert len(transitions) == 2
+    _, action, reward, _, done, goal = transitions[1]
+    assert action == ak.Direction.Left
+    assert reward == 8.0
+    assert done is True
+    assert goal is None
+
+
+def test_goal_episode_stops_at_goal() -> None:
+    rec = ak.TrajectoryRecorder(goal=8)
+    rec.record(EMPTY, ak.Direction.Down, 4, board_with_max(8), ak.State.Continue)
+    rec.record(
+        board_with_max(8), ak.Direction.Down, 16, board_with_max(16), ak.State.GameOver
+    )
+    transitions = rec.transitions()
+    assert [(t[2], t[4], t[5]) for t in transitions] == [(1.0, True, 8)]
+
+
+def test_hindsight_relabels_with_achieved_max_tile() -> None:
+    rec = ak.TrajectoryRecorder(goal=2048, hindsight=True)
+    rec.record(EMPTY, ak.Direction.Down, 0, board_with_max(4), ak.State.Continue)
+    rec.record(
+        board_with_max(4), ak.Direction.Up, 8, board_with_max(8), ak.State.GameOver
+    )
+    transitions = rec.transitions()
+    original = [t for t in transitions if t[5] == 2048]
+    relabeled = [t for t in transitions if t[5] == 8]
+    assert [t[2] for t in original] == [0.0, 0.0]
+    assert [(t[2], t[4]) for t in relabeled] == [(0.0, False), (1.0, True)]
+
+
+def test_finish_episode_flushes_truncated_episode() -> None:
+    rec = ak.TrajectoryRecorder(hindsight=True)
+    rec.record(EMPTY, ak.Direction.Down, 0, board_with_max(2), ak.State.Continue)
+    rec.finish_episode()
+    assert len(rec) == 2
+    rec.clear()
+    assert rec.transitions() == []
@@ -0,0 +1,77 @@
+import pytest
+
+import akioi_2048 as ak
+
+NAMES = ["empty_cells", "monotonicity", "smoothness", "corner", "merges", "multipliers"]
+
+
+def test_cma_es_improves_on_the_default_weights():
+    tuned = ak.tune_heuristics(2, 3, seed=1, max_moves=40, population=4)
+    assert list(tuned["weights"]) == NAMES
+    assert len(tuned["history"]) == 3
+    assert tuned["evaluations"] == 1 + 3 * 4
+    assert tuned["history"] == sorted(tuned["history"])
+    assert tuned["score"] == tuned["history"][-1]
+    first = ak.tune_heuristics(2, 0, seed=1, max_moves=40)
+    assert first["history"] == [] and first["evaluations"] == 1
+    assert tuned["score"] >= first["score"]
+
+
+def test_runs_are_reproducible():
+    for method in ("cma-es", "annealing"):
+        runs = [
+            ak.tune_heuristics(2, 3, method=method, seed=5, max_moves=30, population=3)
+            for _ in range(2)
+        ]
+        assert runs[0] == runs[1]
+
+
+def test_annealing_evaluates_one_candidate_per_step():
+    tuned = ak.tune_heuristics(1, 4, method="annealing", max_moves=20, rules="classic")
+    assert tuned["evaluations"] == 5
+    assert len(tuned["history"]) == 4
+
+
+def test_rejects_bad_settings():
+    for kwargs in (
+        {"n_games": 0},
+        {"method": "grid"},
+        {"population": 1},
+        {"sigma": 0.0},
+    ):
+        args = {"n_games": 1, "generations": 1, "max_moves": 5, **kwargs}
+        with pytest.raises(ValueError):
+            ak.tune_heuristics(**args)
+
+
+def test_checkpoints_resume_exactly(tmp_path):
+    args = {"n_games": 2, "generations": 4, "max_moves": 30, "population": 3}
+    for method in ("cma-es", "annealing"):
+        path = tmp_path / f"{method}.ckpt"
+        straight = ak.tune_heuristics(**args, method=method)
+        token = ak.CancelToken()
+
+        def progress(done, total, token=token):
+            # stop partway through the third generation
+            if done > 2 * (1 + 2 * (3 if method == "cma-es" else 1)):
+                token.cancel()
+
+        with pytest.raises(ak.CancelledError):
+            ak.tune_heuristics(
+                **args, method=method, checkpoint=path, progress=progress, cancel=token
+            )
+        assert path.exists()
+        resumed = ak.tune_heuristics(**args, method=method, checkpoint=path)
+        assert resumed == straight
+
+
+def test_checkpoints_belong_to_one_run(tmp_path):
+    path = tmp_path / "run.ckpt"
+    ak.tune_heuristics(1, 1, max_moves=10, population=2, checkpoint=path)
+    with pytest.raises(ValueError, match="other settings"):
+        ak.tune_heuristics(1, 1, max_moves=10, population=2, seed=3, checkpoint=path)
+    data = bytearray(path.read_bytes())
+    data[20] ^= 1
+    path.write_bytes(bytes(data))
+    with pytest.raises(ValueError, match="checksum"):
+        ak.tune_heuristics(1, 1, max_moves=10, population=2, checkpoint=path)
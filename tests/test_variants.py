@@ -0,0 +1,64 @@
+import pytest
+
+import akioi_2048 as ak
+
+CLASSIC = ak.Rules.preset("classic")
+
+
+def row(*tiles: int) -> list[list[int]]:
+    return [list(tiles)] + [[0] * 4] * 3
+
+
+def test_classic_preset_and_tag() -> None:
+    assert CLASSIC == ak.Rules(variant="classic")
+    assert CLASSIC.variant == "classic"
+    assert ak.Rules().variant == "akioi"
+    assert CLASSIC.tag == "akioi-v2+variant=classic"
+    assert ak.Rules(variant="classic", mercy=True).tag == (
+        "akioi-v2+variant=classic+mercy"
+    )
+    assert "variant='classic'" in repr(CLASSIC)
+    with pytest.raises(ValueError, match="unknown variant: threes"):
+        ak.Rules(variant="threes")
+
+
+def test_classic_merges_numbers_only() -> None:
+    after, delta, _ = ak.step(row(2, 2, 4, 4), ak.Direction.Left, rules=CLASSIC)
+    assert after[0][:2] == [4, 8]
+    assert delta == 12
+    with pytest.raises(ValueError):
+        ak.step(row(2, -1, 0, 0), ak.Direction.Left, rules=CLASSIC)
+    with pytest.raises(ValueError):
+        ak.validate(row(-1, 0, 0, 0), rules=CLASSIC)
+
+
+def test_classic_spawns_two_and_four() -> None:
+    assert ak.spawn_probabilities(rules=CLASSIC) == {2: 0.9, 4: 0.1}
+    seen = set()
+    board = ak.init(rules="classic")
+    for _ in range(200):
+        seen.update(t for r in board for t in r)
+        board = ak.init(rules=CLASSIC)
+    assert seen == {0, 2, 4}
+
+
+def test_init_accepts_tags_and_rejects_unknown_rules() -> None:
+    board = ak.init(blockers=2, rules="akioi-v2+variant=classic+blockers")
+    assert sum(t == ak.BLOCKER for r in board for t in r) == 2
+    with pytest.raises(ValueError, match="unknown rules: nope"):
+        ak.init(rules="nope")
+    with pytest.raises(ValueError, match="rules must be"):
+        ak.init(rules=3)
+
+
+def test_classic_play_matches_the_reference() -> None:
+    board = ak.init(rules=CLASSIC)
+    directions = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right]
+    for k in range(300):
+        direction = directions[k % 3]
+        after, _, state = ak.step(board, direction, rules=CLASSIC)
+        assert ak.verify_step(board, direction, after, rules=CLASSIC)
+        assert all(t >= 0 for r in after for t in r)
+        if state == ak.State.GameOver:
+            break
+        board = after
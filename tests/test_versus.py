@@ -0,0 +1,63 @@
+import pytest
+
+import akioi_2048 as ak
+
+MOVES = [ak.Direction.Down, ak.Direction.Left, ak.Direction.Right, ak.Direction.Up]
+
+
+def play(arena: ak.Arena2P, turns: int) -> list:
+    steps = []
+    for i in range(turns):
+        if arena.is_over:
+            break
+        steps.append(arena.step((MOVES[i % 4], MOVES[(i + 1) % 4])))
+    return steps
+
+
+def test_players_start_from_the_same_board() -> None:
+    arena = ak.Arena2P(seed=4)
+    first, second = arena.boards
+    assert first == second
+    assert arena.scores == (0, 0)
+    assert not arena.is_over
+    assert arena.winner is None
+
+
+def test_attacks_land_on_the_opponent() -> None:
+    arena = ak.Arena2P(seed=1, attack_tile=2, attack_min_merge=4, max_attacks=3)
+    steps = play(arena, 60)
+    assert any(a.sent or b.sent for a, b in steps)
+    for a, b in steps:
+        assert len(a.received) <= b.sent
+        assert len(b.received) <= a.sent
+        assert a.sent <= 3
+        for row, col in a.received:
+            assert a.board[row][col] == 2
+    assert arena.scores[0] == sum(a.delta for a, _ in steps)
+
+
+def test_equal_seeds_give_equal_matches() -> None:
+    boards = []
+    for _ in range(2):
+        arena = ak.Arena2P(seed=9)
+        play(arena, 30)
+        boards.append(arena.boards)
+    assert boards[0] == boards[1]
+
+
+def test_a_match_ends_with_a_winner_or_a_draw() -> None:
+    arena = ak.Arena2P(seed=2, attack_min_merge=2, max_attacks=4)
+    play(arena, 5000)
+    assert arena.is_over
+    if arena.winner is not None:
+        loser = arena.boards[1 - arena.winner]
+        assert ak.is_game_over(loser)
+    with pytest.raises(ValueError, match="the match is over"):
+        arena.step((ak.Direction.Up, ak.Direction.Up))
+
+
+def test_bad_configuration() -> None:
+    with pytest.raises(ValueError, match="invalid tile value: 3"):
+        ak.Arena2P(attack_tile=3)
+    with pytest.raises(ValueError, match="expected 2 moves"):
+        ak.Arena2P(seed=0).step((ak.Direction.Up,))
@@ -0,0 +1,115 @@
+import json
+from pathlib import Path
+
+import pytest
+
+import akioi_2048 as ak
+
+WEB_NUMBERS = {
+    ak.Direction.Up: 0,
+    ak.Direction.Right: 1,
+    ak.Direction.Down: 2,
+    ak.Direction.Left: 3,
+}
+
+
+def record(game: ak.Game, moves: int) -> list[dict]:
+    lines = [{"state": json.loads(game.to_web_save())}]
+    for i in range(moves):
+        if game.state != ak.State.Continue:
+            break
+        direction = list(ak.Direction)[i % 4]
+        result = game.step_detailed(direction)
+        if not result.moved:
+            continue
+        line = {"move": WEB_NUMBERS[direction]}
+        line["state"] = json.loads(game.to_web_save())
+        row, col = result.spawn.cell
+        line["spawn"] = {"x": col, "y": row, "value": result.spawn.value}
+        lines.append(line)
+    return lines
+
+
+def text(lines: list[dict]) -> str:
+    return "\n".join(json.dumps(line) for line in lines)
+
+
+def cells(board: list[list[int]]) -> list[list[dict | None]]:
+    return [
+        [
+            {"position": {"x": x, "y": y}, "value": board[y][x]}
+            if board[y][x]
+            else None
+            for y in range(4)
+        ]
+        for x in range(4)
+    ]
+
+
+def test_engine_games_replay_without_divergence() -> None:
+    for rules in [ak.Rules(), ak.Rules(variant="classic")]:
+        lines = record(ak.Game(seed=3, rules=rules), 60)
+        assert len(lines) > 20
+        assert ak.diff_web_log(text(lines), rules=rules) is None
+
+
+def test_a_divergent_slide_is_found_and_minimized() -> None:
+    rules = ak.Rules(variant="classic")
+    start = [[0] * 4, [0] * 4, [2, 0, 2, 0], [4, 0, 0, 0]]
+    web = [[0] * 4, [0, 0, 2, 0], [0, 0, 0, 8], [0, 0, 0, 4]]
+    lines = [
+        {"state": {"grid": {"size": 4, "cells": cells(start)}, "score": 0}},
+        {"move": "right", "state": {"grid": {"size": 4, "cells": cells(web)}}},
+    ]
+    lines[1]["state"]["score"] = 4
+    found = ak.diff_web_log(text(lines), rules=rules)
+    assert found is not None
+    assert (found["move"], found["kind"]) == (1, "slide")
+    assert found["direction"] == ak.Direction.Right
+    assert found["engine"][2] == [0, 0, 0, 4]
+    assert "row 3 slides Right" in found["message"]
+    assert found["case"] == {
+        "line": [0, 2, 0, 2],
+        "engine": [4, 0, 0, 0],
+        "web": [8, 0, 0, 0],
+    }
+
+
+def test_spawn_score_and_over_divergences() -> None:
+    rules = ak.Rules(variant="classic")
+    lines = record(ak.Game(seed=9, rules=rules), 10)
+    tampered = [dict(line) for line in lines]
+    tampered[3]["state"] = dict(tampered[3]["state"], score=10**6)
+    found = ak.diff_web_log(text(tampered), rules=rules)
+    assert (found["move"], found["kind"], found["case"]) == (3, "score", None)
+    assert found["engine_score"] == lines[3]["state"]["score"]
+
+    tampered = [dict(line) for line in lines]
+    spawn = tampered[2]["spawn"]
+    tampered[2]["spawn"] = dict(spawn, value=spawn["value"] * 2)
+    found = ak.diff_web_log(text(tampered), rules=rules)
+    assert (found["move"], found["kind"]) == (2, "spawn")
+
+    tampered = [dict(line) for line in lines]
+    tampered[1]["state"] = dict(tampered[1]["state"], over=True)
+    found = ak.diff_web_log(text(tampered), rules=rules)
+    assert (found["move"], found["kind"]) == (1, "over")
+
+
+def test_malformed_logs_name_their_line() -> None:
+    lines = record(ak.Game(seed=1, rules=ak.Rules()), 3)
+    with pytest.raises(ValueError, match="line 2: move must be between 0 and 3"):
+        ak.diff_web_log(text([lines[0], dict(lines[1], move=7)]))
+    with pytest.raises(ValueError, match="no starting position"):
+        ak.diff_web_log("# nothing logged\n")
+
+
+def test_webdiff_command_fails_on_a_divergence(tmp_path: Path) -> None:
+    lines = record(ak.Game(seed=2, rules=ak.Rules()), 8)
+    path = tmp_path / "game.jsonl"
+    path.write_text(text(lines))
+    ak.run_cli(["webdiff", str(path)])
+    lines[4]["state"] = dict(lines[4]["state"], score=-1)
+    path.write_text(text(lines))
+    with pytest.raises(ValueError, match="move 4 .* diverges"):
+        ak.run_cli(["webdiff", str(path)])
@@ -0,0 +1,82 @@
+import json
+
+import pytest
+
+import akioi_2048 as ak
+
+# Saved by the original game's LocalStorageManager, cells[x][y] by column
+SAVE = json.dumps(
+    {
+        "grid": {
+            "size": 4,
+            "cells": [
+                [{"position": {"x": 0, "y": 0}, "value": 2}, None, None, None],
+                [None, None, None, None],
+                [None, None, None, {"position": {"x": 2, "y": 3}, "value": -2}],
+                [None, {"position": {"x": 3, "y": 1}, "value": 8}, None, None],
+            ],
+        },
+        "score": 36,
+        "over": False,
+        "won": False,
+        "keepPlaying": False,
+    },
+    indent=2,
+)
+
+
+def test_from_web_save_reads_the_position() -> None:
+    game = ak.Game.from_web_save(SAVE, seed=1)
+    assert game.board == [
+        [2, 0, 0, 0],
+        [0, 0, 0, 8],
+        [0, 0, 0, 0],
+        [0, 0, -2, 0],
+    ]
+    assert game.score == 36
+    assert not game.won
+
+
+def test_to_web_save_round_trips() -> None:
+    game = ak.Game(seed=7)
+    for _ in range(20):
+        game.step(ak.Direction.Left)
+        game.step(ak.Direction.Down)
+    save = json.loads(game.to_web_save())
+    assert save["grid"]["size"] == 4
+    assert save["score"] == game.score
+    assert save["over"] is (game.state == ak.State.GameOver)
+    for x, column in enumerate(save["grid"]["cells"]):
+        for y, cell in enumerate(column):
+            if cell is None:
+                assert game.board[y][x] == 0
+            else:
+                assert cell["position"] == {"x": x, "y": y}
+                assert cell["value"] == game.board[y][x]
+
+    back = ak.Game.from_web_save(game.to_web_save())
+    assert back.board == game.board
+    assert back.score == game.score
+
+
+def test_web_save_keeps_playing_after_a_win() -> None:
+    board = ak.board_with({(0, 0): 65536, (3, 3): 2})
+    save = ak.Game.from_board(board, keep_playing=True).to_web_save()
+    assert json.loads(save)["won"] is True
+    assert json.loads(save)["keepPlaying"] is True
+
+    game = ak.Game.from_web_save(save)
+    assert game.won
+    _, _, state = game.step(ak.Direction.Left)
+    assert state == ak.State.Continue
+
+
+def test_from_web_save_rejects_bad_saves() -> None:
+    with pytest.raises(ValueError, match="invalid save JSON"):
+        ak.Game.from_web_save('{"grid": ')
+    with pytest.raises(ValueError, match="missing `score`"):
+        ak.Game.from_web_save(SAVE.replace('"score"', '"points"'))
+    with pytest.raises(ValueError, match="only 4x4"):
+        ak.Game.from_web_save(SAVE.replace('"size": 4', '"size": 5'))
+    with pytest.raises(ValueError, match="invalid tile value: 3"):
+        ak.Game.from_web_save(SAVE.replace('"value": 8', '"value": 3'))
@@ -0,0 +1,34 @@
+import akioi_2048 as ak
+
+CLASSIC = ak.Rules.preset("classic")
+# One empty cell: whichever way the player moves, some spawn leaves no move
+TRAP = [[32, 2, 16, 2], [2, 8, 32, 8], [16, 32, 2, 4], [0, 4, 8, 16]]
+
+
+def test_adversary_forces_a_dead_board():
+    moves = ak.worst_case_moves(TRAP, depth=2, rules=CLASSIC)
+    assert set(moves) == {ak.Direction.Down, ak.Direction.Left}
+    for outcome in moves.values():
+        assert outcome["guaranteed_moves"] == 1
+        assert outcome["value"] < -5000
+    row, _ = ak.slide_row(TRAP[3], rules=CLASSIC)
+    deaths = [
+        ak.is_game_over(TRAP[:3] + [row[:3] + [tile]], rules=CLASSIC) for tile in (2, 4)
+    ]
+    assert any(deaths)
+
+
+def test_open_board_survives_the_whole_depth():
+    board = ak.board_with({(3, 2): 2, (3, 3): 2})
+    moves = ak.worst_case_moves(board, depth=2)
+    assert moves
+    assert all(m["guaranteed_moves"] == 2 for m in moves.values())
+
+
+def test_worst_case_is_no_better_than_the_average():
+    board = [[2, 4, 8, 16], [0, 2, 4, 8], [0, 0, 2, -2], [0, 0, 0, 2]]
+    worst = ak.worst_case_moves(board, depth=1)
+    _, explanation = ak.hint(board, depth=1)
+    assert set(worst) == set(explanation["values"])
+    for direction, average in explanation["values"].items():
+        assert worst[direction]["value"] <= average + 1e-9
@@ -0,0 +1,187 @@
+//! JavaScript bindings for the akioi 2048 engine, built with `wasm-pack`.
+//!
+//! Boards cross the boundary as flat row-major `Int32Array`s of 16 cells,
+//! directions and states as their names (`"Up"`, `"GameOver"`, ...) and
+//! rules as preset names or tags (see `Rules::tag`).
+
+use akioi_2048_core::{self as engine, Board, Direction, Rules, State};
+use wasm_bindgen::prelude::*;
+
+fn parse_direction(name: &str) -> Result<Direction, JsError> {
+    match name {
+        "Down" => Ok(Direction::Down),
+        "Right" => Ok(Direction::Right),
+        "Up" => Ok(Direction::Up),
+        "Left" => Ok(Direction::Left),
+        _ => Err(JsError::new(&format!(
+            "direction must be Up, Down, Left or Right, got: {name}"
+        ))),
+    }
+}
+
+fn state_name(state: State) -> String {
+    match state {
+        State::Victory => "Victory",
+        State::GameOver => "GameOver",
+        State::Continue => "Continue",
+    }
+    .to_string()
+}
+
+fn parse_rules(rules: Option<String>) -> Result<Rules, JsError> {
+    let Some(name) = rules else {
+        return Ok(Rules::AKIOI);
+    };
+    match Rules::preset(&name) {
+        Some(preset) => Ok(preset),
+        None => Rules::from_tag(&name).map_err(|e| JsError::new(&e)),
+    }
+}
+
+fn parse_board(cells: &[i32]) -> Result<Board, JsError> {
+    if cells.len() != 16 {
+        return Err(JsError::new(&format!(
+            "board must have 16 cells, got: {}",
+            cells.len()
+        )));
+    }
+    let mut board = [[0; 4]; 4];
+    for (i, &v) in cells.iter().enumerate() {
+        board[i / 4][i % 4] = v;
+    }
+    Ok(board)
+}
+
+fn flat(board: &Board) -> Vec<i32> {
+    board.iter().flatten().copied().collect()
+}
+
+/// Outcome of one move
+#[wasm_bindgen]
+pub struct StepResult {
+    board: Vec<i32>,
+    delta: i32,
+    state: State,
+    moved: bool,
+}
+
+#[wasm_bindgen]
+impl StepResult {
+    #[wasm_bindgen(getter)]
+    pub fn board(&self) -> Vec<i32> {
+        self.board.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn delta(&self) -> i32 {
+        self.delta
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> String {
+        state_name(self.state)
+    }
+
+    /// Whether the move changed the board
+    #[wasm_bindgen(getter)]
+    pub fn moved(&self) -> bool {
+        self.moved
+    }
+}
+
+/// A fresh board with two starting tiles
+#[wasm_bindgen]
+pub fn init() -> Vec<i32> {
+    flat(&engine::init())
+}
+
+/// Apply one move; if the board changes a new tile is spawned at random.
+///
+/// # Errors
+/// Throws for a malformed board, unknown direction or rules.
+#[wasm_bindgen]
+pub fn step(board: &[i32], direction: &str, rules: Option<String>) -> Result<StepResult, JsError> {
+    let board = parse_board(board)?;
+    let result = engine::step_detailed(board, parse_direction(direction)?, &parse_rules(rules)?)
+        .map_err(|e| JsError::new(&e))?;
+    Ok(StepResult {
+        board: flat(&result.board),
+        delta: result.delta,
+        state: result.state,
+        moved: result.moved,
+    })
+}
+
+/// A seeded game in progress; equal seeds, rules and moves give equal games.
+#[wasm_bindgen]
+pub struct Game {
+    inner: engine::Game,
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// `seed` is a `BigInt`; a random one is picked when omitted.
+    ///
+    /// # Errors
+    /// Throws for unknown rules.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: Option<u64>, rules: Option<String>) -> Result<Game, JsError> {
+        let mut builder = engine::Game::builder().rules(parse_rules(rules)?);
+        if let Some(seed) = seed {
+            builder = builder.seed(seed);
+        }
+        let inner = builder.build().map_err(|e| JsError::new(&e))?;
+        Ok(Game { inner })
+    }
+
+    /// Apply one move; invalid moves leave the game untouched.
+    ///
+    /// # Errors
+    /// Throws for an unknown direction.
+    pub fn step(&mut self, direction: &str) -> Result<StepResult, JsError> {
+        let result = self.inner.step(parse_direction(direction)?);
+        Ok(StepResult {
+            board: flat(&result.board),
+            delta: result.delta,
+            state: result.state,
+            moved: result.moved,
+        })
+    }
+
+    /// The replay text of this game (see `Replay` in the Python package)
+    #[wasm_bindgen(js_name = replayText)]
+    pub fn replay_text(&self) -> String {
+        self.inner.replay().to_text()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn board(&self) -> Vec<i32> {
+        flat(&self.inner.board())
+    }
+
+    /// Exact up to 2^53, far beyond any reachable score
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> f64 {
+        self.inner.score() as f64
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> String {
+        state_name(self.inner.state())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn moves(&self) -> usize {
+        self.inner.moves()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> u64 {
+        self.inner.seed()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rules(&self) -> String {
+        self.inner.rules().tag()
+    }
+}